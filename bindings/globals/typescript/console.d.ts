@@ -1,4 +1,11 @@
 declare namespace console {
+	interface TableOptions {
+		maxColumns?: number;
+		maxWidth?: number;
+	}
+
+	const stdout: WritableStream;
+
 	function log(...values: any[]): void;
 
 	function info(...values: any[]): void;
@@ -35,5 +42,5 @@ declare namespace console {
 
 	function timeEnd(label?: string): void;
 
-	function table(data: any, columns?: string[]): void;
+	function table(data: any, columns?: string[], options?: TableOptions): void;
 }
@@ -0,0 +1,12 @@
+declare interface ResourceUsage {
+	bytesFetched: number;
+	filesOpened: number;
+	timersCreated: number;
+	futuresPending: number;
+	heapBytes: number;
+	peakHeapBytes: number;
+}
+
+declare namespace spiderfire {
+	function resourceUsage(): ResourceUsage;
+}
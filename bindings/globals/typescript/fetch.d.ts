@@ -15,9 +15,37 @@ declare class Headers implements Iterable<[string, string]> {
 
 	set(name: string, value: string): void;
 
+	forEach(callback: (value: string, name: string, headers: Headers) => void, thisArg?: object): void;
+
+	entries(): Iterator<[string, string]>;
+
+	keys(): Iterator<string>;
+
+	values(): Iterator<string>;
+
 	[Symbol.iterator](): Iterator<[string, string]>;
 }
 
+declare type FormDataEntryValue = File | string;
+
+declare class FormData implements Iterable<[string, FormDataEntryValue]> {
+	constructor();
+
+	append(name: string, value: string | Blob, filename?: string): void;
+
+	delete(name: string): void;
+
+	get(name: string): FormDataEntryValue | null;
+
+	getAll(name: string): FormDataEntryValue[];
+
+	has(name: string): boolean;
+
+	set(name: string, value: string | Blob, filename?: string): void;
+
+	[Symbol.iterator](): Iterator<[string, FormDataEntryValue]>;
+}
+
 declare type BodyInit = BufferSource | Blob | URLSearchParams | string;
 
 declare type RequestInfo = Request | string;
@@ -67,6 +95,23 @@ declare type RequestDuplex = "half";
 
 declare type RequestPriority = "high" | "low" | "auto";
 
+declare interface FetchProgress {
+	lengthComputable: boolean;
+	loaded: number;
+	total: number;
+}
+
+declare interface InformationalResponse {
+	status: number;
+	headers: Headers;
+}
+
+declare interface RetryOptions {
+	attempts?: number;
+	backoff?: number;
+	maxBackoff?: number;
+}
+
 declare interface RequestInit {
 	method?: string;
 	headers?: HeadersInit;
@@ -84,6 +129,11 @@ declare interface RequestInit {
 	keepalive?: boolean;
 	signal?: AbortSignal;
 
+	onUploadProgress?: (progress: FetchProgress) => void;
+	onDownloadProgress?: (progress: FetchProgress) => void;
+	onInformational?: (response: InformationalResponse) => void;
+	retry?: RetryOptions;
+
 	duplex?: RequestDuplex;
 	priority?: RequestPriority;
 	window?: null;
@@ -123,6 +173,8 @@ declare class Request {
 	get signal(): AbortSignal;
 
 	get duplex(): RequestDuplex;
+
+	formData(): Promise<FormData>;
 }
 
 declare interface ResponseInit {
@@ -133,6 +185,14 @@ declare interface ResponseInit {
 
 declare type ResponseType = "basic" | "cors" | "default" | "error" | "opaque" | "opaqueredirect";
 
+declare interface ResponseTiming {
+	dns: number;
+	connect: number;
+	tls: number;
+	firstByte: number;
+	total: number;
+}
+
 declare class Response {
 	constructor(body?: BodyInit, init?: ResponseInit);
 
@@ -142,6 +202,8 @@ declare class Response {
 
 	get redirected(): boolean;
 
+	get urlList(): string[];
+
 	get status(): number;
 
 	get ok(): boolean;
@@ -150,11 +212,25 @@ declare class Response {
 
 	get headers(): Headers;
 
+	get timing(): ResponseTiming | null;
+
 	get bodyUsed(): boolean;
 
 	arrayBuffer(): Promise<ArrayBuffer>;
 
 	text(): Promise<string>;
+
+	formData(): Promise<FormData>;
+}
+
+declare interface FetchDefaults {
+	connectTimeout: number | null;
+	headersTimeout: number | null;
+	timeout: number | null;
 }
 
 declare function fetch(input: RequestInfo, init?: RequestInit): Promise<Response>;
+
+declare namespace fetch {
+	const defaults: FetchDefaults;
+}
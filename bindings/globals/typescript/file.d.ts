@@ -21,6 +21,8 @@ declare class Blob {
 	arrayBuffer(): Promise<ArrayBuffer>;
 
 	bytes(): Promise<Uint8Array>;
+
+	stream(): ReadableStream<Uint8Array>;
 }
 
 declare interface FileOptions extends BlobOptions {
@@ -35,6 +37,16 @@ declare class File extends Blob {
 	get lastModified(): number;
 }
 
+declare interface FileReaderProgressEvent {
+	type: "loadstart" | "progress" | "load" | "abort" | "error" | "loadend",
+	target: FileReader,
+	lengthComputable: boolean,
+	loaded: number,
+	total: number,
+}
+
+declare type FileReaderEventHandler = (event: FileReaderProgressEvent) => void;
+
 declare class FileReader {
 	static EMPTY: number;
 	static LOADING: number;
@@ -55,6 +67,26 @@ declare class FileReader {
 	readAsText(blob: Blob, encoding?: string): void;
 
 	readAsDataURL(blob: Blob): void;
+
+	abort(): void;
+
+	get onloadstart(): FileReaderEventHandler | null;
+	set onloadstart(handler: FileReaderEventHandler | null);
+
+	get onprogress(): FileReaderEventHandler | null;
+	set onprogress(handler: FileReaderEventHandler | null);
+
+	get onload(): FileReaderEventHandler | null;
+	set onload(handler: FileReaderEventHandler | null);
+
+	get onabort(): FileReaderEventHandler | null;
+	set onabort(handler: FileReaderEventHandler | null);
+
+	get onerror(): FileReaderEventHandler | null;
+	set onerror(handler: FileReaderEventHandler | null);
+
+	get onloadend(): FileReaderEventHandler | null;
+	set onloadend(handler: FileReaderEventHandler | null);
 }
 
 
@@ -1 +1,14 @@
 declare function queueMicrotask(callback: () => void): void;
+
+declare interface PromiseHooks {
+	init?(promise: Promise<unknown>): void;
+	resolve?(promise: Promise<unknown>): void;
+	before?(promise: Promise<unknown>): void;
+	after?(promise: Promise<unknown>): void;
+}
+
+declare function setPromiseHooks(hooks?: PromiseHooks): void;
+
+declare function setUncaughtExceptionHandler(handler?: ((error: unknown) => void) | null): void;
+
+declare function reportError(error: unknown): void;
@@ -0,0 +1,13 @@
+declare interface EventLoopUtilization {
+	idle: number;
+	active: number;
+	utilization: number;
+	maxTick: number;
+}
+
+declare namespace performance {
+	function eventLoopUtilization(
+		utilization1?: EventLoopUtilization,
+		utilization2?: EventLoopUtilization,
+	): EventLoopUtilization;
+}
@@ -25,6 +25,12 @@ declare interface ReadableStreamGetReaderOptions {
 	mode?: ReadableStreamReaderMode
 }
 
+declare interface StreamPipeOptions {
+	preventClose?: boolean,
+	preventAbort?: boolean,
+	preventCancel?: boolean,
+}
+
 declare class ReadableStream {
 	constructor(underlyingSource?: UnderlyingSource, strategy?: QueueingStrategy);
 
@@ -35,6 +41,8 @@ declare class ReadableStream {
 	getReader(options?: ReadableStreamGetReaderOptions): ReadableStreamReader;
 
 	tee(): [ReadableStream, ReadableStream];
+
+	pipeTo(destination: WritableStream, options?: StreamPipeOptions): Promise<void>;
 }
 
 declare interface ReadableStreamReadResult {
@@ -0,0 +1,19 @@
+declare interface QueuingStrategyInit {
+	highWaterMark: number,
+}
+
+declare class ByteLengthQueuingStrategy {
+	constructor(init: QueuingStrategyInit);
+
+	get highWaterMark(): number;
+
+	get size(): (chunk: ArrayBufferView) => number;
+}
+
+declare class CountQueuingStrategy {
+	constructor(init: QueuingStrategyInit);
+
+	get highWaterMark(): number;
+
+	get size(): (chunk: any) => 1;
+}
@@ -0,0 +1,47 @@
+declare type UnderlyingSinkStartCallback = (controller: WritableStreamDefaultController) => any;
+declare type UnderlyingSinkWriteCallback = (chunk: any, controller: WritableStreamDefaultController) => Promise<void>;
+declare type UnderlyingSinkCloseCallback = () => Promise<void>;
+declare type UnderlyingSinkAbortCallback = (reason?: any) => Promise<void>;
+
+declare interface UnderlyingSink {
+	start?: UnderlyingSinkStartCallback,
+	write?: UnderlyingSinkWriteCallback,
+	close?: UnderlyingSinkCloseCallback,
+	abort?: UnderlyingSinkAbortCallback,
+}
+
+declare class WritableStream {
+	constructor(underlyingSink?: UnderlyingSink, strategy?: QueueingStrategy);
+
+	get locked(): boolean;
+
+	abort(reason?: any): Promise<void>;
+
+	close(): Promise<void>;
+
+	getWriter(): WritableStreamDefaultWriter;
+}
+
+declare class WritableStreamDefaultWriter {
+	constructor(stream: WritableStream);
+
+	get closed(): Promise<void>;
+
+	get desiredSize(): number | null;
+
+	get ready(): Promise<void>;
+
+	abort(reason?: any): Promise<void>;
+
+	close(): Promise<void>;
+
+	releaseLock(): void;
+
+	write(chunk?: any): Promise<void>;
+}
+
+declare class WritableStreamDefaultController {
+	get signal(): AbortSignal;
+
+	error(e?: any): void;
+}
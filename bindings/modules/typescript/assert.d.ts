@@ -7,12 +7,21 @@ declare module "assert" {
 
 	export function fail(message?: string): void;
 
+	export function match(string: string, regexp: RegExp, message?: string): void;
+
+	export function doesNotMatch(string: string, regexp: RegExp, message?: string): void;
+
+	export function snapshot(value: any, name: string, message?: string): void;
+
 	namespace Assert {
 		export {
 			ok,
 			equals,
 			throws,
 			fail,
+			match,
+			doesNotMatch,
+			snapshot,
 		};
 	}
 
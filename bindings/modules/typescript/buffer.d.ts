@@ -0,0 +1,38 @@
+declare module "buffer" {
+	export function encodeHex(bytes: BufferSource): string;
+
+	export function decodeHex(hex: string): Uint8Array;
+
+	export function encodeBase64(bytes: BufferSource): string;
+
+	export function decodeBase64(base64: string): Uint8Array;
+
+	export function encodeBase64Url(bytes: BufferSource): string;
+
+	export function decodeBase64Url(base64: string): Uint8Array;
+
+	export function concat(buffers: BufferSource[]): Uint8Array;
+
+	export function compare(first: BufferSource, second: BufferSource): number;
+
+	export function indexOf(haystack: BufferSource, needle: BufferSource, fromIndex?: number): number;
+
+	export function byteLength(string: string): number;
+
+	namespace Buffer {
+		export {
+			encodeHex,
+			decodeHex,
+			encodeBase64,
+			decodeBase64,
+			encodeBase64Url,
+			decodeBase64Url,
+			concat,
+			compare,
+			indexOf,
+			byteLength,
+		};
+	}
+
+	export default Buffer;
+}
@@ -1,6 +1,8 @@
 declare module "fs" {
 	export interface Metadata {
-		size: number;
+		size: number | bigint;
+		blocks: number | bigint | null;
+		inode: number | bigint | null;
 
 		isFile: boolean;
 		isDirectory: boolean;
@@ -10,9 +12,37 @@ declare module "fs" {
 		accessed: Date | null;
 		modified: Date | null;
 
+		createdNs?: bigint | null;
+		accessedNs?: bigint | null;
+		modifiedNs?: bigint | null;
+
 		readonly: boolean;
 	}
 
+	export interface MetadataOptions {
+		bigint?: boolean,
+	}
+
+	export interface WalkOptions {
+		maxDepth?: number,
+		includeDirs?: boolean,
+		followSymlinks?: boolean,
+		skip?: string[],
+	}
+
+	export interface WalkEntry {
+		path: string;
+		name: string;
+		depth: number;
+		metadata: Metadata;
+	}
+
+	export interface AbortOptions {
+		signal?: AbortSignal,
+	}
+
+	export type ChecksumAlgorithm = "sha256" | "sha384" | "sha512" | "sha3-256" | "sha3-384" | "sha3-512";
+
 	export interface OpenOptions {
 		read?: boolean,
 		write?: boolean,
@@ -26,20 +56,20 @@ declare module "fs" {
 	export class DirEntry {
 		name(): string;
 		path(): string;
-		metadata(): Metadata;
+		metadata(options?: MetadataOptions): Metadata;
 	}
 
 	export class FileHandle {
-		read(): Promise<Uint8Array>;
+		read(array?: void, options?: AbortOptions): Promise<Uint8Array>;
 		read(array: Uint8Array): Promise<number>;
 
 		readSync(): Uint8Array;
 		readSync(array: Uint8Array): number;
 
-		write(source: BufferSource): Promise<number>;
+		write(source: BufferSource, options?: AbortOptions): Promise<number>;
 		writeSync(source: BufferSource): number;
 
-		writeAll(source: BufferSource): Promise<void>;
+		writeAll(source: BufferSource, options?: AbortOptions): Promise<void>;
 		writeAllSync(source: BufferSource): void;
 
 		truncate(length?: number): Promise<void>;
@@ -54,36 +84,95 @@ declare module "fs" {
 		syncData(): Promise<void>;
 		syncDataSync(): void;
 
-		metadata(): Promise<Metadata>;
+		metadata(options?: MetadataOptions): Promise<Metadata>;
 
-		metadataSync(): Metadata;
+		metadataSync(options?: MetadataOptions): Metadata;
 	}
 
-	export function open(path: string, options?: OpenOptions): Promise<FileHandle>;
+	export interface FileSystemGetHandleOptions {
+		create?: boolean,
+	}
 
-	export function create(path: string): Promise<FileHandle>;
+	export interface FileSystemRemoveOptions {
+		recursive?: boolean,
+	}
+
+	export interface FileSystemCreateWritableOptions {
+		keepExistingData?: boolean,
+	}
 
-	export function metadata(path: string): Promise<Metadata>;
+	export class FileSystemFileHandle {
+		readonly kind: "file";
+		readonly name: string;
 
-	export function linkMetadata(path: string): Promise<Metadata>;
+		isSameEntry(other: FileSystemFileHandle | FileSystemDirectoryHandle): Promise<boolean>;
 
-	export function readDir(path: string): Promise<Iterable<DirEntry>>;
+		getFile(): Promise<File>;
+		createWritable(options?: FileSystemCreateWritableOptions): Promise<WritableStream<BufferSource | Blob | string>>;
+	}
+
+	export class FileSystemDirectoryHandle {
+		readonly kind: "directory";
+		readonly name: string;
 
-	export function createDir(path: string, recursive?: boolean): Promise<void>;
+		isSameEntry(other: FileSystemFileHandle | FileSystemDirectoryHandle): Promise<boolean>;
 
-	export function remove(path: string, recursive?: boolean): Promise<void>;
+		getFileHandle(name: string, options?: FileSystemGetHandleOptions): Promise<FileSystemFileHandle>;
+		getDirectoryHandle(name: string, options?: FileSystemGetHandleOptions): Promise<FileSystemDirectoryHandle>;
+		removeEntry(name: string, options?: FileSystemRemoveOptions): Promise<void>;
+	}
 
-	export function copy(from: string, to: string): Promise<number>;
+	export class Hasher {
+		constructor(algorithm?: ChecksumAlgorithm);
+
+		update(data: BufferSource): Promise<void>;
+		updateSync(data: BufferSource): void;
+
+		digest(): Promise<string>;
+		digestSync(): string;
+	}
+
+	export class Walker {
+		next(): Promise<IteratorResult<WalkEntry, void>>;
+		return(): Promise<IteratorResult<WalkEntry, void>>;
+		[Symbol.asyncIterator](): Walker;
+	}
 
-	export function rename(from: string, to: string): Promise<void>;
+	export function open(path: string, options?: OpenOptions & AbortOptions): Promise<FileHandle>;
 
-	export function symlink(original: string, link: string): Promise<void>;
+	export function create(path: string, options?: AbortOptions): Promise<FileHandle>;
 
-	export function link(original: string, link: string): Promise<void>;
+	export function metadata(path: string, options?: MetadataOptions & AbortOptions): Promise<Metadata>;
 
-	export function readLink(path: string): Promise<string>;
+	export function linkMetadata(path: string, options?: MetadataOptions & AbortOptions): Promise<Metadata>;
 
-	export function canonical(path: string): Promise<string>;
+	export function checksum(path: string, algorithm?: ChecksumAlgorithm, options?: AbortOptions): Promise<string>;
+
+	export function checksumStream(stream: ReadableStream<BufferSource>, algorithm?: ChecksumAlgorithm): Promise<string>;
+
+	export function readDir(path: string, options?: AbortOptions): Promise<Iterable<DirEntry>>;
+
+	export function walk(path: string, options?: WalkOptions & AbortOptions): Walker;
+
+	export function createDir(path: string, recursive?: boolean, options?: AbortOptions): Promise<void>;
+
+	export function remove(path: string, recursive?: boolean, options?: AbortOptions): Promise<void>;
+
+	export function copy(from: string, to: string, options?: AbortOptions): Promise<number>;
+
+	export function rename(from: string, to: string, options?: AbortOptions): Promise<void>;
+
+	export function symlink(original: string, link: string, options?: AbortOptions): Promise<void>;
+
+	export function link(original: string, link: string, options?: AbortOptions): Promise<void>;
+
+	export function readLink(path: string, options?: AbortOptions): Promise<string>;
+
+	export function canonical(path: string, options?: AbortOptions): Promise<string>;
+
+	export function getFileHandle(path: string): Promise<FileSystemFileHandle>;
+
+	export function getDirectoryHandle(path: string): Promise<FileSystemDirectoryHandle>;
 
 	import {
 		open as openSync,
@@ -92,6 +181,8 @@ declare module "fs" {
 		metadata as metadataSync,
 		linkMetadata as linkMetadataSync,
 
+		checksum as checksumSync,
+
 		readDir as readDirSync,
 		createDir as createDirSync,
 		remove as removeSync,
@@ -111,6 +202,8 @@ declare module "fs" {
 		metadataSync,
 		linkMetadataSync,
 
+		checksumSync,
+
 		readDirSync,
 		createDirSync,
 		removeSync,
@@ -130,6 +223,8 @@ declare module "fs" {
 		metadata: typeof metadataSync,
 		linkMetadata: typeof linkMetadataSync,
 
+		checksum: typeof checksumSync,
+
 		readDir: typeof readDirSync,
 		createDir: typeof createDirSync,
 		remove: typeof removeSync,
@@ -145,18 +240,34 @@ declare module "fs" {
 	namespace FileSystem {
 		export {
 			type Metadata,
+			type MetadataOptions,
+			type AbortOptions,
+			type ChecksumAlgorithm,
+			type WalkOptions,
+			type WalkEntry,
 			type OpenOptions,
 			type SeekMode,
 			DirEntry,
 
 			FileHandle,
+			type FileSystemGetHandleOptions,
+			type FileSystemRemoveOptions,
+			type FileSystemCreateWritableOptions,
+			FileSystemFileHandle,
+			FileSystemDirectoryHandle,
+			Hasher,
+			Walker,
 			open,
 			create,
 
 			metadata,
 			linkMetadata,
 
+			checksum,
+			checksumStream,
+
 			readDir,
+			walk,
 			createDir,
 			remove,
 			copy,
@@ -167,6 +278,9 @@ declare module "fs" {
 			readLink,
 			canonical,
 
+			getFileHandle,
+			getDirectoryHandle,
+
 			sync,
 		};
 	}
@@ -175,12 +289,22 @@ declare module "fs" {
 }
 
 declare module "fs/sync" {
-	import {DirEntry, FileHandle, type Metadata, type OpenOptions, type SeekMode} from "fs";
+	import {
+		type ChecksumAlgorithm,
+		DirEntry,
+		FileHandle,
+		type Metadata,
+		type MetadataOptions,
+		type OpenOptions,
+		type SeekMode,
+	} from "fs";
 
 	export {
+		ChecksumAlgorithm,
 		DirEntry,
 		FileHandle,
 		Metadata,
+		MetadataOptions,
 		OpenOptions,
 		SeekMode,
 	};
@@ -189,9 +313,11 @@ declare module "fs/sync" {
 
 	export function create(path: string): FileHandle;
 
-	export function metadata(path: string): Metadata;
+	export function metadata(path: string, options?: MetadataOptions): Metadata;
+
+	export function linkMetadata(path: string, options?: MetadataOptions): Metadata;
 
-	export function linkMetadata(path: string): Metadata;
+	export function checksum(path: string, algorithm?: ChecksumAlgorithm): string;
 
 	export function readDir(path: string): Iterable<DirEntry>;
 
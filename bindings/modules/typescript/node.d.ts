@@ -0,0 +1,233 @@
+declare module "node:assert" {
+	export function ok(value: unknown, message?: string): void;
+
+	export function equal(actual: unknown, expected: unknown, message?: string): void;
+
+	export function notEqual(actual: unknown, expected: unknown, message?: string): void;
+
+	export function strictEqual(actual: unknown, expected: unknown, message?: string): void;
+
+	export function notStrictEqual(actual: unknown, expected: unknown, message?: string): void;
+
+	export function throws(fn: () => void, message?: string): void;
+
+	export function doesNotThrow(fn: () => void, message?: string): void;
+
+	export function fail(message?: string): never;
+
+	export function match(string: string, regexp: RegExp, message?: string): void;
+
+	export function doesNotMatch(string: string, regexp: RegExp, message?: string): void;
+
+	interface NodeAssert {
+		(value: unknown, message?: string): void;
+
+		ok: typeof ok;
+		equal: typeof equal;
+		notEqual: typeof notEqual;
+		strictEqual: typeof strictEqual;
+		notStrictEqual: typeof notStrictEqual;
+		throws: typeof throws;
+		doesNotThrow: typeof doesNotThrow;
+		fail: typeof fail;
+		match: typeof match;
+		doesNotMatch: typeof doesNotMatch;
+		strict: NodeAssert;
+	}
+
+	const assert: NodeAssert;
+
+	export default assert;
+}
+
+declare module "node:fs/promises" {
+	export interface Stats {
+		size: number;
+		birthtime: Date | null;
+		atime: Date | null;
+		mtime: Date | null;
+
+		isFile(): boolean;
+		isDirectory(): boolean;
+		isSymbolicLink(): boolean;
+	}
+
+	export interface EncodingOptions {
+		encoding?: string;
+	}
+
+	export interface RecursiveOptions {
+		recursive?: boolean;
+	}
+
+	export interface AbortOptions {
+		signal?: AbortSignal;
+	}
+
+	export function readFile(path: string, options?: AbortOptions): Promise<Uint8Array>;
+	export function readFile(path: string, options: string | (EncodingOptions & AbortOptions)): Promise<string>;
+
+	export function writeFile(path: string, data: string | BufferSource, options?: AbortOptions): Promise<void>;
+
+	export function appendFile(path: string, data: string | BufferSource, options?: AbortOptions): Promise<void>;
+
+	export function readdir(path: string): Promise<string[]>;
+
+	export function mkdir(path: string, options?: RecursiveOptions): Promise<void>;
+
+	export function rm(path: string, options?: RecursiveOptions): Promise<void>;
+
+	export function rmdir(path: string, options?: RecursiveOptions): Promise<void>;
+
+	export function unlink(path: string): Promise<void>;
+
+	export function copyFile(source: string, destination: string): Promise<void>;
+
+	export function stat(path: string): Promise<Stats>;
+
+	export function lstat(path: string): Promise<Stats>;
+
+	export function rename(from: string, to: string): Promise<void>;
+
+	export function symlink(target: string, path: string): Promise<void>;
+
+	export function link(existing: string, path: string): Promise<void>;
+
+	export function readlink(path: string): Promise<string>;
+
+	export function realpath(path: string): Promise<string>;
+
+	namespace FsPromises {
+		export {
+			readFile,
+			writeFile,
+			appendFile,
+			readdir,
+			mkdir,
+			rm,
+			rmdir,
+			unlink,
+			copyFile,
+			stat,
+			lstat,
+			rename,
+			symlink,
+			link,
+			readlink,
+			realpath,
+		};
+	}
+
+	export default FsPromises;
+}
+
+declare module "node:fs" {
+	import type {EncodingOptions, RecursiveOptions, Stats} from "node:fs/promises";
+	import promises from "node:fs/promises";
+
+	export function readFileSync(path: string): Uint8Array;
+	export function readFileSync(path: string, options: string | EncodingOptions): string;
+
+	export function writeFileSync(path: string, data: string | BufferSource): void;
+
+	export function appendFileSync(path: string, data: string | BufferSource): void;
+
+	export function existsSync(path: string): boolean;
+
+	export function readdirSync(path: string): string[];
+
+	export function mkdirSync(path: string, options?: RecursiveOptions): void;
+
+	export function rmSync(path: string, options?: RecursiveOptions): void;
+
+	export function rmdirSync(path: string, options?: RecursiveOptions): void;
+
+	export function unlinkSync(path: string): void;
+
+	export function copyFileSync(source: string, destination: string): void;
+
+	export function statSync(path: string): Stats;
+
+	export function lstatSync(path: string): Stats;
+
+	export function renameSync(from: string, to: string): void;
+
+	export function symlinkSync(target: string, path: string): void;
+
+	export function linkSync(existing: string, path: string): void;
+
+	export function readlinkSync(path: string): string;
+
+	export function realpathSync(path: string): string;
+
+	export {promises};
+
+	namespace Fs {
+		export {
+			readFileSync,
+			writeFileSync,
+			appendFileSync,
+			existsSync,
+			readdirSync,
+			mkdirSync,
+			rmSync,
+			rmdirSync,
+			unlinkSync,
+			copyFileSync,
+			statSync,
+			lstatSync,
+			renameSync,
+			symlinkSync,
+			linkSync,
+			readlinkSync,
+			realpathSync,
+			promises,
+		};
+	}
+
+	export default Fs;
+}
+
+declare module "node:path" {
+	export const sep: string;
+	export const delimiter: string;
+
+	export function join(...segments: string[]): string;
+
+	export function dirname(path: string): string;
+
+	export function basename(path: string, suffix?: string): string;
+
+	export function extname(path: string): string;
+
+	export function isAbsolute(path: string): boolean;
+
+	namespace Path {
+		export {
+			sep,
+			delimiter,
+			join,
+			dirname,
+			basename,
+			extname,
+			isAbsolute,
+		};
+	}
+
+	export default Path;
+}
+
+declare module "node:buffer" {
+	export * from "buffer";
+	export {default} from "buffer";
+}
+
+declare module "node:url" {
+	export * from "url";
+	export {default} from "url";
+}
+
+declare module "node:util" {
+	export * from "util";
+	export {default} from "util";
+}
@@ -0,0 +1,50 @@
+declare module "test" {
+	export function useFakeTimers(): void;
+
+	export function tick(ms: number): number;
+
+	export function runAllTimers(): number;
+
+	export interface MockRoute {
+		status?: number;
+		statusText?: string;
+		headers?: HeadersInit;
+		body?: BodyInit;
+		delay?: number;
+		error?: any;
+	}
+
+	export type MockResponse = Response | MockRoute;
+
+	export type MockRouteHandler = MockResponse | ((request: Request) => MockResponse | Promise<MockResponse>);
+
+	export interface MockCall {
+		url: string;
+		method: string;
+		headers: Record<string, string>;
+	}
+
+	export interface FetchMock {
+		calls: MockCall[];
+
+		restore(): void;
+	}
+
+	export function mockFetch(
+		routes:
+			| Record<string, MockRouteHandler>
+			| Map<string | RegExp, MockRouteHandler>
+			| [string | RegExp, MockRouteHandler][],
+	): FetchMock;
+
+	namespace Test {
+		export {
+			useFakeTimers,
+			tick,
+			runAllTimers,
+			mockFetch,
+		};
+	}
+
+	export default Test;
+}
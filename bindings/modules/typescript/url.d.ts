@@ -3,10 +3,31 @@ declare module "url" {
 
 	export function domainToUnicode(domain: string): string;
 
+	export function fileURLToPath(url: string | URL): string;
+
+	export function pathToFileURL(path: string): URL;
+
+	export interface HttpOptions {
+		protocol: string;
+		hostname: string;
+		hash: string;
+		search: string;
+		pathname: string;
+		path: string;
+		href: string;
+		port?: number;
+		auth?: string;
+	}
+
+	export function urlToHttpOptions(url: URL): HttpOptions;
+
 	namespace Url {
 		export {
 			domainToASCII,
 			domainToUnicode,
+			fileURLToPath,
+			pathToFileURL,
+			urlToHttpOptions,
 		};
 	}
 
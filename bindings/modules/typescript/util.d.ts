@@ -0,0 +1,25 @@
+declare module "util" {
+	export interface SerializedError {
+		name: string;
+		message: string;
+		stack?: string;
+		fileName?: string;
+		lineNumber?: number;
+		columnNumber?: number;
+		cause?: unknown;
+		errors?: unknown[];
+		value?: unknown;
+
+		[key: string]: unknown;
+	}
+
+	export function serializeError(value: unknown): SerializedError;
+
+	namespace Util {
+		export {
+			serializeError,
+		};
+	}
+
+	export default Util;
+}
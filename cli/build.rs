@@ -0,0 +1,43 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::env;
+use std::fs::{read_dir, read_to_string, write};
+use std::io;
+use std::path::{Path, PathBuf};
+
+const BINDINGS: [&str; 2] = ["../bindings/globals/typescript", "../bindings/modules/typescript"];
+
+fn collect(directory: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+	for entry in read_dir(directory)? {
+		let path = entry?.path();
+		if path.is_dir() {
+			collect(&path, files)?;
+		} else if path.extension().is_some_and(|extension| extension == "ts") {
+			files.push(path);
+		}
+	}
+	Ok(())
+}
+
+fn main() -> io::Result<()> {
+	let mut typings = String::new();
+	for bindings in BINDINGS {
+		println!("cargo:rerun-if-changed={bindings}");
+
+		let mut files = Vec::new();
+		collect(Path::new(bindings), &mut files)?;
+		files.sort();
+
+		for file in files {
+			typings.push_str(&read_to_string(file)?);
+			typings.push('\n');
+		}
+	}
+
+	let out = PathBuf::from(env::var("OUT_DIR").unwrap());
+	write(out.join("spiderfire.d.ts"), typings)
+}
@@ -4,20 +4,44 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::collections::BTreeMap;
 use std::fs::{metadata, read_dir};
 use std::io;
 use std::path::Path;
 
 use humansize::{SizeFormatter, BINARY};
-use runtime::cache::Cache;
+use runtime::cache::{fingerprint, Cache};
+use runtime::module::{Lockfile, LOCKFILE};
+use runtime::typescript::CompilerOptions;
 
-pub(crate) fn cache_statistics() {
+use crate::config::project_root;
+
+pub(crate) fn cache_statistics(options: CompilerOptions) {
 	if let Some(cache) = Cache::new() {
 		println!("Location: {}", cache.dir().display());
 		match cache_size(cache.dir()) {
 			Ok(size) => println!("Size: {}", SizeFormatter::new(size, BINARY)),
 			Err(err) => eprintln!("Error while Calculating Size: {}", err),
 		}
+
+		let options = options.describe();
+		let current = fingerprint(&options);
+		println!("Compiler Options: {options} ({current})");
+		match cache.entries() {
+			Ok(entries) => {
+				let mut counts = BTreeMap::new();
+				for entry in &entries {
+					*counts.entry((&entry.fingerprint, &entry.options)).or_insert(0) += 1;
+				}
+
+				println!("Entries: {}", entries.len());
+				for ((fingerprint, options), count) in counts {
+					let status = if *fingerprint != current { ", stale" } else { "" };
+					println!("  {count} compiled with {options} ({fingerprint}{status})");
+				}
+			}
+			Err(err) => eprintln!("Error while Reading Entries: {}", err),
+		}
 	} else {
 		println!("No Cache Found");
 	}
@@ -35,3 +59,16 @@ fn cache_size(folder: &Path) -> io::Result<u64> {
 	}
 	Ok(size)
 }
+
+pub(crate) fn lock() {
+	let Some(root) = project_root() else {
+		eprintln!("Failed to find the project directory");
+		return;
+	};
+
+	let result = Lockfile::generate(&root).and_then(|lockfile| lockfile.write().map(|_| lockfile));
+	match result {
+		Ok(lockfile) => println!("Locked {} packages in {LOCKFILE}", lockfile.packages().len()),
+		Err(error) => eprintln!("{error}"),
+	}
+}
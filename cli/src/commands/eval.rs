@@ -4,22 +4,28 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use ion::Context;
-use modules::Modules;
-use mozjs::rust::{JSEngine, Runtime};
-use runtime::RuntimeBuilder;
+use std::rc::Rc;
+use std::time::Duration;
 
-use crate::evaluate::eval_inline;
+use ion::{Context, Value};
+use modules::Modules;
+use runtime::config::Config;
+use runtime::{Engine, RuntimeBuilder};
 
-pub(crate) async fn eval_source(source: &str) {
-	let engine = JSEngine::init().unwrap();
-	let rt = Runtime::new(engine.handle());
+use crate::evaluate::{eval_inline, ExitStatus, Output, Reporter};
 
-	let cx = &mut Context::from_runtime(&rt);
+pub(crate) async fn eval_source(source: &str, timeout: Option<Duration>, config: Config, output: Output) -> ExitStatus {
+	let mut engine = Engine::new();
+	let reporter = Reporter::new(output);
+	let rejections = Rc::clone(&reporter);
 	let rt = RuntimeBuilder::<(), _>::new()
+		.config(config)
+		.expect("Configuration should only be set once")
 		.microtask_queue()
 		.macrotask_queue()
 		.standard_modules(Modules)
-		.build(cx);
-	eval_inline(&rt, source).await;
+		.unhandled_rejection_handler(move |cx: &Context, reason: &Value| rejections.rejection(cx, reason))
+		.build(engine.cx());
+	eval_inline(&rt, &reporter, source, timeout).await;
+	reporter.status()
 }
@@ -0,0 +1,137 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::env::current_dir;
+use std::fs::{read_dir, read_to_string, write};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+
+use runtime::typescript::format_source;
+
+use crate::config::ProjectConfig;
+
+const EXTENSIONS: [&str; 8] = ["js", "mjs", "cjs", "jsx", "ts", "mts", "cts", "tsx"];
+
+/// Formats the JavaScript and TypeScript files in `paths`, or the current directory if none are given.
+///
+/// Each path is formatted with the `spiderfire.toml` of the project containing it.
+/// In check mode, files are left untouched and the process exits with status 1 if any would be changed.
+pub(crate) fn format(paths: &[PathBuf], check: bool) {
+	let paths = if paths.is_empty() {
+		&[PathBuf::from(".")][..]
+	} else {
+		paths
+	};
+	let mut files = Vec::new();
+	for path in paths {
+		let project = match discover_project(path) {
+			Ok(project) => project.unwrap_or_default(),
+			Err(error) => {
+				eprintln!("{error}");
+				exit(1);
+			}
+		};
+		if let Err(error) = collect_files(path, &project.fmt.exclude, &mut files) {
+			eprintln!("Failed to read {}: {error}", path.display());
+			exit(1);
+		}
+	}
+
+	let mut changed = Vec::new();
+	let mut failed = false;
+	for file in &files {
+		match format_file(file, check) {
+			Ok(true) => changed.push(file),
+			Ok(false) => {}
+			Err(error) => {
+				eprintln!("Failed to format {}: {error}", file.display());
+				failed = true;
+			}
+		}
+	}
+
+	if check {
+		for file in &changed {
+			println!("{}", file.display());
+		}
+		if !changed.is_empty() {
+			eprintln!("Found {} unformatted file(s) out of {}", changed.len(), files.len());
+		}
+	} else {
+		println!("Formatted {} file(s) out of {}", changed.len(), files.len());
+	}
+
+	if failed || (check && !changed.is_empty()) {
+		exit(1);
+	}
+}
+
+/// Searches for `spiderfire.toml` from the directory of `path`, or `path` itself if it is a directory.
+fn discover_project(path: &Path) -> Result<Option<ProjectConfig>, String> {
+	let path = current_dir().map(|directory| directory.join(path)).unwrap_or_else(|_| path.to_path_buf());
+	let directory = if path.is_dir() {
+		&path
+	} else {
+		path.parent().unwrap_or(&path)
+	};
+	ProjectConfig::discover_from(directory)
+}
+
+/// Formats a file, returning whether its contents differ from the formatted output.
+fn format_file(path: &Path, check: bool) -> Result<bool, String> {
+	let source = read_to_string(path).map_err(|error| error.to_string())?;
+	let formatted = format_source(path, &source).map_err(|error| match error.to_string() {
+		message if message.is_empty() => String::from("Invalid syntax"),
+		message => message,
+	})?;
+
+	if formatted == source {
+		return Ok(false);
+	}
+	if !check {
+		write(path, formatted).map_err(|error| error.to_string())?;
+	}
+	Ok(true)
+}
+
+/// Collects the files to format from `path`, skipping hidden directories, `node_modules` and excluded paths.
+/// Files which are given explicitly are always collected.
+fn collect_files(path: &Path, exclude: &[PathBuf], files: &mut Vec<PathBuf>) -> io::Result<()> {
+	if !path.is_dir() {
+		files.push(path.to_path_buf());
+		return Ok(());
+	}
+
+	let mut entries = read_dir(path)?.collect::<io::Result<Vec<_>>>()?;
+	entries.sort_by_key(|entry| entry.file_name());
+	for entry in entries {
+		let path = entry.path();
+		let name = entry.file_name();
+		let name = name.to_string_lossy();
+		if is_excluded(&path, exclude) {
+			continue;
+		}
+
+		if entry.file_type()?.is_dir() {
+			if !name.starts_with('.') && name != "node_modules" {
+				collect_files(&path, exclude, files)?;
+			}
+		} else if path.extension().is_some_and(|extension| EXTENSIONS.iter().any(|e| extension == *e)) {
+			files.push(path);
+		}
+	}
+	Ok(())
+}
+
+fn is_excluded(path: &Path, exclude: &[PathBuf]) -> bool {
+	let Ok(path) = path.canonicalize() else {
+		return false;
+	};
+	exclude
+		.iter()
+		.any(|exclude| exclude.canonicalize().is_ok_and(|exclude| path.starts_with(exclude)))
+}
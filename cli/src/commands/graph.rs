@@ -0,0 +1,246 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use humansize::{SizeFormatter, BINARY};
+use ion::module::Module;
+use ion::Context;
+use runtime::cache::{locate_in_cache, Cache};
+use runtime::config::Config;
+use runtime::module::{Loader, Resolution};
+use runtime::{Engine, RuntimeBuilder};
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub(crate) enum GraphFormat {
+	#[default]
+	Tree,
+	Json,
+	Dot,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum ModuleKind {
+	JavaScript,
+	TypeScript,
+	Builtin,
+	Url,
+}
+
+#[derive(Debug, Serialize)]
+struct Dependency {
+	specifier: String,
+	module: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct GraphModule {
+	path: String,
+	kind: ModuleKind,
+	size: Option<u64>,
+	cached: bool,
+	error: Option<String>,
+	dependencies: Vec<Dependency>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct ModuleGraph {
+	modules: Vec<GraphModule>,
+	#[serde(skip)]
+	typescript: bool,
+}
+
+impl ModuleGraph {
+	fn build(cx: &Context, loader: &Loader, entry: &Path, typescript: bool) -> ModuleGraph {
+		let mut graph = ModuleGraph { typescript, ..ModuleGraph::default() };
+		let mut indices = HashMap::new();
+		graph.add(cx, loader, &mut indices, entry);
+		graph
+	}
+
+	fn add(&mut self, cx: &Context, loader: &Loader, indices: &mut HashMap<PathBuf, usize>, path: &Path) -> usize {
+		if let Some(index) = indices.get(path) {
+			return *index;
+		}
+
+		let index = self.modules.len();
+		indices.insert(path.to_path_buf(), index);
+
+		let is_typescript = self.typescript && path.extension() == Some(OsStr::new("ts"));
+		let kind = if is_typescript {
+			ModuleKind::TypeScript
+		} else {
+			ModuleKind::JavaScript
+		};
+
+		let source = match read_to_string(path) {
+			Ok(source) => source,
+			Err(error) => {
+				self.modules.push(GraphModule {
+					path: path.to_string_lossy().into_owned(),
+					kind,
+					size: None,
+					cached: false,
+					error: Some(error.to_string()),
+					dependencies: Vec::new(),
+				});
+				return index;
+			}
+		};
+
+		let cached = is_typescript && is_cached(path, &source);
+		let size = source.len() as u64;
+		let script = is_typescript
+			.then(|| locate_in_cache(path, &source))
+			.flatten()
+			.map_or(source, |(script, _)| script);
+
+		let filename = path.file_name().and_then(OsStr::to_str).unwrap_or_default();
+		self.modules.push(GraphModule {
+			path: path.to_string_lossy().into_owned(),
+			kind,
+			size: Some(size),
+			cached,
+			error: None,
+			dependencies: Vec::new(),
+		});
+
+		let specifiers = match Module::compile(cx, filename, Some(path), &script) {
+			Ok(module) => module
+				.requested_modules(cx)
+				.into_iter()
+				.filter_map(|specifier| specifier.to_owned(cx).ok())
+				.collect(),
+			Err(error) => {
+				self.modules[index].error = Some(error.format(cx));
+				Vec::new()
+			}
+		};
+
+		for specifier in specifiers {
+			let module = match loader.resolve_specifier(Some(path), &specifier) {
+				Resolution::Builtin => self.add_external(indices, &specifier, ModuleKind::Builtin),
+				Resolution::Url => self.add_external(indices, &specifier, ModuleKind::Url),
+				Resolution::Path(resolved) => self.add(cx, loader, indices, &resolved),
+			};
+			self.modules[index].dependencies.push(Dependency { specifier, module });
+		}
+
+		index
+	}
+
+	/// Adds a module which is not read from a file, such as a built-in module or a module from a URL.
+	fn add_external(&mut self, indices: &mut HashMap<PathBuf, usize>, specifier: &str, kind: ModuleKind) -> usize {
+		let key = PathBuf::from(specifier);
+		if let Some(index) = indices.get(&key) {
+			return *index;
+		}
+
+		let index = self.modules.len();
+		indices.insert(key, index);
+		self.modules.push(GraphModule {
+			path: String::from(specifier),
+			kind,
+			size: None,
+			cached: false,
+			error: None,
+			dependencies: Vec::new(),
+		});
+		index
+	}
+
+	fn print_tree(&self) {
+		if let Some(entry) = self.modules.first() {
+			println!("{}", entry.describe(&entry.path));
+			let mut visited = vec![false; self.modules.len()];
+			visited[0] = true;
+			self.print_children(0, "", &mut visited);
+		}
+	}
+
+	fn print_children(&self, index: usize, prefix: &str, visited: &mut [bool]) {
+		let dependencies = &self.modules[index].dependencies;
+		for (i, dependency) in dependencies.iter().enumerate() {
+			let last = i == dependencies.len() - 1;
+			let (branch, indent) = if last { ("└── ", "    ") } else { ("├── ", "│   ") };
+
+			let module = &self.modules[dependency.module];
+			if visited[dependency.module] {
+				println!("{prefix}{branch}{} (see above)", dependency.specifier);
+			} else {
+				println!("{prefix}{branch}{}", module.describe(&dependency.specifier));
+				visited[dependency.module] = true;
+				self.print_children(dependency.module, &format!("{prefix}{indent}"), visited);
+			}
+		}
+	}
+
+	fn print_dot(&self) {
+		println!("digraph modules {{");
+		for (index, module) in self.modules.iter().enumerate() {
+			println!("\t{index} [label={:?}];", module.describe(&module.path));
+		}
+		for (index, module) in self.modules.iter().enumerate() {
+			for dependency in &module.dependencies {
+				println!("\t{index} -> {};", dependency.module);
+			}
+		}
+		println!("}}");
+	}
+}
+
+impl GraphModule {
+	fn describe(&self, name: &str) -> String {
+		let mut details = Vec::new();
+		if let Some(size) = self.size {
+			details.push(SizeFormatter::new(size, BINARY).to_string());
+		}
+		details.push(String::from(match self.kind {
+			ModuleKind::JavaScript => "JavaScript",
+			ModuleKind::TypeScript => "TypeScript",
+			ModuleKind::Builtin => "builtin",
+			ModuleKind::Url => "URL",
+		}));
+		if self.cached {
+			details.push(String::from("cached"));
+		}
+		if let Some(error) = &self.error {
+			details.push(format!("error: {error}"));
+		}
+		format!("{name} ({})", details.join(", "))
+	}
+}
+
+fn is_cached(path: &Path, source: &str) -> bool {
+	Cache::new().is_some_and(|cache| {
+		cache.find_folder(path).is_ok_and(|folder| cache.check_cache(path, &folder, source).is_ok())
+	})
+}
+
+pub(crate) fn module_graph(entry: &Path, format: GraphFormat, config: Config, loader: Loader) {
+	let typescript = config.typescript;
+	let mut engine = Engine::new();
+	let rt = RuntimeBuilder::<()>::new()
+		.config(config)
+		.expect("Configuration should only be set once")
+		.build(engine.cx());
+
+	let graph = ModuleGraph::build(rt.cx(), &loader, entry, typescript);
+	match format {
+		GraphFormat::Tree => graph.print_tree(),
+		GraphFormat::Json => match serde_json::to_string_pretty(&graph) {
+			Ok(json) => println!("{json}"),
+			Err(error) => eprintln!("{error}"),
+		},
+		GraphFormat::Dot => graph.print_dot(),
+	}
+}
@@ -0,0 +1,95 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fs::{create_dir_all, write};
+use std::io;
+use std::path::Path;
+
+use clap::ValueEnum;
+
+use crate::config::CONFIG_FILE;
+
+const TYPINGS: &str = include_str!(concat!(env!("OUT_DIR"), "/spiderfire.d.ts"));
+
+const MAIN: &str = "console.log(\"Hello, World!\");\n";
+
+const COMPILER_OPTIONS: &str = r#"{
+	"compilerOptions": {
+		"target": "es2023",
+		"lib": ["es2023"],
+		"module": "es2022",
+		"strict": true,
+		"checkJs": CHECK_JS
+	},
+	"include": ["ENTRY", "types"]
+}
+"#;
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub(crate) enum Template {
+	#[default]
+	#[value(name = "typescript", alias = "ts")]
+	TypeScript,
+	#[value(name = "javascript", alias = "js")]
+	JavaScript,
+}
+
+impl Template {
+	fn entry(&self) -> &'static str {
+		match self {
+			Template::TypeScript => "main.ts",
+			Template::JavaScript => "main.js",
+		}
+	}
+
+	fn files(&self) -> Vec<(&'static str, String)> {
+		let entry = self.entry();
+		let (compiler_config, check_js) = match self {
+			Template::TypeScript => ("tsconfig.json", "false"),
+			Template::JavaScript => ("jsconfig.json", "true"),
+		};
+		let typescript = matches!(self, Template::TypeScript);
+
+		vec![
+			(entry, String::from(MAIN)),
+			(
+				CONFIG_FILE,
+				format!("entry = \"{entry}\"\nlog-level = \"info\"\ntypescript = {typescript}\n"),
+			),
+			(
+				compiler_config,
+				COMPILER_OPTIONS.replace("CHECK_JS", check_js).replace("ENTRY", entry),
+			),
+			("types/spiderfire.d.ts", String::from(TYPINGS)),
+		]
+	}
+}
+
+/// Generates a starter project in `directory`. Existing files are left untouched.
+pub(crate) fn init(directory: &Path, template: Template) {
+	if let Err(error) = create_files(directory, template) {
+		eprintln!("Failed to create project: {error}");
+	}
+}
+
+fn create_files(directory: &Path, template: Template) -> io::Result<()> {
+	for (name, contents) in template.files() {
+		let path = directory.join(name);
+		if path.exists() {
+			println!("Skipped {} (already exists)", path.display());
+			continue;
+		}
+
+		if let Some(parent) = path.parent() {
+			create_dir_all(parent)?;
+		}
+		write(&path, contents)?;
+		println!("Created {}", path.display());
+	}
+
+	println!("Run the project with: spiderfire run");
+	Ok(())
+}
@@ -4,21 +4,49 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::io;
+use std::path::PathBuf;
+use std::process::exit;
+use std::time::Duration;
+
+use clap::CommandFactory;
+use clap_complete::generate;
 use runtime::cache::Cache;
-use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::config::{Config, LogLevel};
+use runtime::module::{Loader, Lockfile};
+use runtime::typescript::CompilerOptions;
+#[cfg(feature = "otlp")]
+use runtime::telemetry::Telemetry;
 
+use crate::config::{project_root, ProjectConfig};
+use crate::evaluate::{ExitStatus, Output};
 use crate::{Cli, Command};
 
 mod cache;
 mod eval;
-mod repl;
+mod fmt;
+pub(crate) mod graph;
+pub(crate) mod init;
+pub(crate) mod repl;
 mod run;
+#[cfg(unix)]
+mod serve;
 
 pub(crate) async fn handle_command(cli: Cli) {
 	match cli.command {
-		Some(Command::Cache { clear }) => {
-			if !clear {
-				cache::cache_statistics();
+		Some(Command::Cache { clear, lock }) => {
+			if lock {
+				cache::lock();
+			} else if !clear {
+				let project = match ProjectConfig::discover() {
+					Ok(project) => project.unwrap_or_default(),
+					Err(error) => {
+						eprintln!("{error}");
+						exit(1);
+					}
+				};
+				let script = project.apply(Config::default()).script;
+				cache::cache_statistics(CompilerOptions { script });
 			} else if let Some(cache) = Cache::new() {
 				if let Err(err) = cache.clear() {
 					eprintln!("{}", err);
@@ -26,32 +54,130 @@ pub(crate) async fn handle_command(cli: Cli) {
 			}
 		}
 
-		Some(Command::Eval { source }) => {
-			CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
-			eval::eval_source(&source).await;
+		Some(Command::Completions { shell }) => {
+			generate(shell, &mut Cli::command(), "spiderfire", &mut io::stdout());
+		}
+
+		Some(Command::Eval { source, timeout, quiet, json_errors }) => {
+			let log_level = if quiet { LogLevel::Error } else { LogLevel::Debug };
+			let config = Config::default().log_level(log_level).script(true);
+			let output = Output { quiet, json_errors };
+			exit_with(eval::eval_source(&source, timeout.map(Duration::from_millis), config, output).await);
 		}
 
-		Some(Command::Run { path, log_level, debug, script }) => {
-			let log_level = if debug {
-				LogLevel::Debug
-			} else {
-				match log_level.to_uppercase().as_str() {
-					"NONE" => LogLevel::None,
-					"INFO" => LogLevel::Info,
-					"WARN" => LogLevel::Warn,
-					"ERROR" => LogLevel::Error,
-					"DEBUG" => LogLevel::Debug,
-					_ => panic!("Invalid Logging Level"),
+		Some(Command::Run {
+			path,
+			log_level,
+			debug,
+			script,
+			preload,
+			timestamps,
+			level_tags,
+			log_ids,
+			update_snapshots,
+			otlp,
+			conditions,
+			repl_on_error,
+			quiet,
+			json_errors,
+		}) => {
+			let project = match ProjectConfig::discover() {
+				Ok(project) => project.unwrap_or_default(),
+				Err(error) => {
+					eprintln!("{error}");
+					exit(1);
 				}
 			};
 
-			CONFIG.set(Config::default().log_level(log_level).script(script)).unwrap();
-			run::run(&path).await;
+			let mut config = project.apply(Config::default());
+			if debug {
+				config = config.log_level(LogLevel::Debug);
+			} else if let Some(log_level) = log_level {
+				config = config.log_level(log_level);
+			} else if quiet {
+				config = config.log_level(LogLevel::Error);
+			}
+
+			let config = config
+				.script(script.unwrap_or(config.script))
+				.log_timestamps(config.log_timestamps || timestamps)
+				.log_level_tags(config.log_level_tags || level_tags)
+				.log_ids(config.log_ids || log_ids)
+				.update_snapshots(update_snapshots)
+				.otlp(config.otlp || otlp);
+
+			#[cfg(feature = "otlp")]
+			let _telemetry = match config.otlp.then(Telemetry::init).transpose() {
+				Ok(telemetry) => telemetry,
+				Err(error) => {
+					eprintln!("Failed to initialise OpenTelemetry: {error}");
+					return;
+				}
+			};
+			#[cfg(not(feature = "otlp"))]
+			if config.otlp {
+				eprintln!("OpenTelemetry export requires spiderfire to be built with the 'otlp' feature");
+			}
+
+			let path = path.map(PathBuf::from).or(project.entry).unwrap_or_else(|| PathBuf::from("main.js"));
+			let preloads: Vec<_> = project.preload.into_iter().chain(preload).collect();
+			let lockfile = match project_root().map(|root| Lockfile::read(&root)).transpose() {
+				Ok(lockfile) => lockfile.flatten(),
+				Err(error) => {
+					eprintln!("{error}");
+					exit(1);
+				}
+			};
+
+			let mut loader = Loader::default().conditions(project.conditions.into_iter().chain(conditions));
+			if let Some(lockfile) = lockfile {
+				loader = loader.lockfile(lockfile);
+			}
+			let output = Output { quiet, json_errors };
+			exit_with(run::run(&path, &preloads, config, loader, output, repl_on_error).await);
 		}
 
-		Some(Command::Repl) | None => {
-			CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
-			repl::start_repl().await;
+		Some(Command::Fmt { paths, check }) => {
+			fmt::format(&paths, check);
 		}
+
+		Some(Command::Graph { path, format, conditions }) => {
+			let project = match ProjectConfig::discover() {
+				Ok(project) => project.unwrap_or_default(),
+				Err(error) => {
+					eprintln!("{error}");
+					exit(1);
+				}
+			};
+			let config = project.apply(Config::default());
+			let loader = Loader::default().conditions(project.conditions.into_iter().chain(conditions));
+			graph::module_graph(&path, format, config, loader);
+		}
+
+		Some(Command::Init { template, directory }) => {
+			init::init(&directory.unwrap_or_default(), template);
+		}
+
+		Some(Command::ServeEval { socket }) => {
+			#[cfg(unix)]
+			serve::serve_eval(&socket, Config::default().log_level(LogLevel::Debug).script(true)).await;
+			#[cfg(not(unix))]
+			eprintln!("Serving evaluations on {} requires Unix domain sockets", socket.display());
+		}
+
+		Some(Command::Repl { preload }) => {
+			repl::start_repl(&preload, Config::default().log_level(LogLevel::Debug).script(true)).await;
+		}
+
+		None => {
+			repl::start_repl(&[], Config::default().log_level(LogLevel::Debug).script(true)).await;
+		}
+	}
+}
+
+/// Exits the process with the code of `status`, unless it is successful.
+fn exit_with(status: ExitStatus) {
+	if status != ExitStatus::Success {
+		exit(status.code());
 	}
 }
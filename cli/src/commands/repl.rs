@@ -4,27 +4,35 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use ion::Context;
+use std::path::PathBuf;
+
 use modules::Modules;
-use mozjs::rust::{JSEngine, Runtime};
-use runtime::RuntimeBuilder;
+use runtime::config::Config;
+use runtime::{Engine, Runtime, RuntimeBuilder};
 use rustyline::error::ReadlineError;
 use rustyline::Editor;
 
-use crate::evaluate::eval_inline;
+use crate::evaluate::{eval_inline, eval_preloads, Output, Reporter};
 use crate::repl::{rustyline_config, ReplHelper};
 
-pub(crate) async fn start_repl() {
-	let engine = JSEngine::init().unwrap();
-	let rt = Runtime::new(engine.handle());
-
-	let cx = &mut Context::from_runtime(&rt);
+pub(crate) async fn start_repl(preloads: &[PathBuf], config: Config) {
+	let mut engine = Engine::new();
 	let rt = RuntimeBuilder::<(), _>::new()
+		.config(config)
+		.expect("Configuration should only be set once")
 		.microtask_queue()
 		.macrotask_queue()
 		.standard_modules(Modules)
-		.build(cx);
+		.build(engine.cx());
+
+	if !eval_preloads(&rt, &Reporter::new(Output::default()), preloads).await {
+		return;
+	}
+	run_repl(&rt).await;
+}
 
+/// Reads and evaluates input in the global scope of the runtime until the user exits.
+pub(crate) async fn run_repl(rt: &Runtime<'_>) {
 	let mut repl = match Editor::with_config(rustyline_config()) {
 		Ok(repl) => repl,
 		Err(err) => {
@@ -33,6 +41,7 @@ pub(crate) async fn start_repl() {
 		}
 	};
 	repl.set_helper(Some(ReplHelper));
+	let reporter = Reporter::new(Output::default());
 	let mut terminate: u8 = 0;
 
 	loop {
@@ -54,7 +63,7 @@ pub(crate) async fn start_repl() {
 
 		if !input.is_empty() && input != "exit" {
 			terminate = 0;
-			eval_inline(&rt, &input).await;
+			eval_inline(rt, &reporter, &input, None).await;
 		}
 
 		if terminate > 1 || input == "exit" {
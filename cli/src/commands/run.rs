@@ -4,16 +4,22 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use runtime::config::Config;
+use runtime::module::Loader;
 
-use crate::evaluate::{eval_module, eval_script};
+use crate::evaluate::{eval_module, eval_script, ExitStatus, Output, Reporter};
 
-pub(crate) async fn run(path: &str) {
-	if Config::global().script {
-		eval_script(Path::new(path)).await;
+/// Runs the entry point, returning the exit status of its most severe error.
+pub(crate) async fn run(
+	path: &Path, preloads: &[PathBuf], config: Config, loader: Loader, output: Output, repl_on_error: bool,
+) -> ExitStatus {
+	let reporter = Reporter::new(output);
+	if config.script {
+		eval_script(path, preloads, config, &reporter, repl_on_error).await;
 	} else {
-		eval_module(Path::new(path)).await;
+		eval_module(path, preloads, config, loader, &reporter, repl_on_error).await;
 	}
+	reporter.status()
 }
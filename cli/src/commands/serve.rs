@@ -0,0 +1,289 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::fs::{remove_file, symlink_metadata};
+use std::future::pending;
+use std::io;
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::UnixStream as StdUnixStream;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
+
+use ion::conversions::FromValue;
+use ion::format::{format_value, Config as FormatConfig};
+use ion::{Context, Promise, PromiseFuture, Value};
+use modules::Modules;
+use mozjs::jsapi::PromiseState;
+use runtime::cache::map::SourceMapStore;
+use runtime::config::{Config, LogLevel};
+use runtime::globals::console::ConsoleSink;
+use runtime::{Engine, Runtime, RuntimeBuilder};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio::sync::oneshot;
+use tokio::time::sleep;
+use tokio::{select, spawn};
+
+use crate::evaluate::{evaluate_script, ExitStatus, JsonError};
+
+/// Evaluation requested by a client, sent as a line of JSON.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Request {
+	/// Identifier which is returned unchanged in the response.
+	#[serde(default)]
+	id: serde_json::Value,
+	/// Classic script evaluated in the global scope of the runtime.
+	source: String,
+	/// Throws a `TimeoutError` in the evaluation after this many milliseconds, and bounds how long the event loop runs
+	/// after it, instead of [EVENT_LOOP_TIMEOUT].
+	timeout: Option<u64>,
+}
+
+/// Maximum time the event loop runs after a request is evaluated, unless the request has a timeout.
+///
+/// Tasks which are still pending when the response is sent, such as intervals, continue when the event loop runs for
+/// later requests.
+const EVENT_LOOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Request received by a connection, which is evaluated by the runtime and answered through `response`.
+struct Job {
+	/// Request, or the reason it could not be parsed.
+	request: Result<Request, String>,
+	response: oneshot::Sender<Response>,
+}
+
+#[derive(Debug, Serialize)]
+struct ConsoleMessage {
+	level: &'static str,
+	message: String,
+}
+
+/// Result of an evaluation, sent as a line of JSON.
+#[derive(Debug, Serialize)]
+struct Response {
+	id: serde_json::Value,
+	/// Completion value of the script, or the value a returned promise was fulfilled with.
+	result: Option<String>,
+	errors: Vec<JsonError>,
+	console: Vec<ConsoleMessage>,
+	/// Reason the request could not be parsed, in which case it was not evaluated.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	invalid: Option<String>,
+}
+
+impl Response {
+	fn invalid(message: String) -> Response {
+		Response {
+			id: serde_json::Value::Null,
+			result: None,
+			errors: Vec::new(),
+			console: Vec::new(),
+			invalid: Some(message),
+		}
+	}
+}
+
+/// Output of the runtime collected while a request is evaluated.
+#[derive(Clone, Default)]
+struct Session {
+	console: Rc<RefCell<Vec<ConsoleMessage>>>,
+	errors: Rc<RefCell<Vec<JsonError>>>,
+}
+
+impl Session {
+	fn response(&self, id: serde_json::Value, result: Option<String>) -> Response {
+		Response {
+			id,
+			result,
+			errors: self.errors.take(),
+			console: self.console.take(),
+			invalid: None,
+		}
+	}
+}
+
+impl ConsoleSink for Session {
+	fn write(&self, level: LogLevel, message: &str) {
+		let level = match level {
+			LogLevel::None | LogLevel::Info => "info",
+			LogLevel::Warn => "warn",
+			LogLevel::Error => "error",
+			LogLevel::Debug => "debug",
+		};
+		self.console.borrow_mut().push(ConsoleMessage { level, message: String::from(message) });
+	}
+}
+
+/// Evaluates scripts sent to a Unix domain socket at `socket` in a single runtime, which is kept alive between
+/// requests so that they do not pay for its startup.
+///
+/// Each line received is a JSON [Request], which is answered with a line containing a JSON [Response].
+/// Connections are read concurrently, while their requests are evaluated one at a time in the global scope of the
+/// runtime, which they share.
+pub(crate) async fn serve_eval(socket: &Path, config: Config) {
+	let listener = match bind(socket) {
+		Ok(listener) => listener,
+		Err(error) => {
+			eprintln!("Failed to listen on {}: {error}", socket.display());
+			return;
+		}
+	};
+
+	let mut engine = Engine::new();
+	let session = Session::default();
+	let rejections = session.clone();
+	let rt = RuntimeBuilder::<(), _>::new()
+		.config(config)
+		.expect("Configuration should only be set once")
+		.microtask_queue()
+		.macrotask_queue()
+		.standard_modules(Modules)
+		.console_sink(session.clone())
+		.unhandled_rejection_handler(move |cx: &Context, reason: &Value| {
+			rejections.errors.borrow_mut().push(JsonError::from_rejection(cx, reason));
+		})
+		.build(engine.cx());
+
+	let (sender, mut jobs) = unbounded_channel();
+	eprintln!("Listening on {}", socket.display());
+	loop {
+		select! {
+			accepted = listener.accept() => match accepted {
+				Ok((stream, _)) => {
+					let sender = sender.clone();
+					spawn(async move {
+						if let Err(error) = serve_connection(stream, sender).await {
+							eprintln!("Connection closed: {error}");
+						}
+					});
+				}
+				Err(error) => eprintln!("Failed to accept connection: {error}"),
+			},
+			Some(job) = jobs.recv() => {
+				let response = match job.request {
+					Ok(request) => evaluate_request(&rt, &session, request).await,
+					Err(message) => Response::invalid(message),
+				};
+				let _ = job.response.send(response);
+			}
+		}
+	}
+}
+
+/// Binds to `socket`, replacing a socket left behind by a previous server, unless a server is still listening on it.
+fn bind(socket: &Path) -> io::Result<UnixListener> {
+	if symlink_metadata(socket).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+		match StdUnixStream::connect(socket) {
+			Ok(_) => {
+				let message = "Another server is listening on the socket";
+				return Err(io::Error::new(io::ErrorKind::AddrInUse, message));
+			}
+			Err(error) if error.kind() == io::ErrorKind::ConnectionRefused => remove_file(socket)?,
+			Err(error) => return Err(error),
+		}
+	}
+	UnixListener::bind(socket)
+}
+
+/// Reads requests from a connection, and writes their responses in the order they were received.
+async fn serve_connection(stream: UnixStream, jobs: UnboundedSender<Job>) -> io::Result<()> {
+	let (reader, mut writer) = stream.into_split();
+	let mut lines = BufReader::new(reader).lines();
+
+	while let Some(line) = lines.next_line().await? {
+		if line.trim().is_empty() {
+			continue;
+		}
+
+		let request = serde_json::from_str(&line).map_err(|error| format!("Invalid request: {error}"));
+		let (sender, response) = oneshot::channel();
+		if jobs.send(Job { request, response: sender }).is_err() {
+			break;
+		}
+		let Ok(response) = response.await else {
+			break;
+		};
+
+		let mut response = serde_json::to_vec(&response)?;
+		response.push(b'\n');
+		writer.write_all(&response).await?;
+	}
+	Ok(())
+}
+
+/// Evaluates a request, and then runs the event loop until it is idle, the promise the script returned settles, or
+/// the timeout of the request elapses.
+async fn evaluate_request(rt: &Runtime<'_>, session: &Session, request: Request) -> Response {
+	let cx = rt.cx();
+	let path = Path::new("serve-eval.js");
+	SourceMapStore::with(|store| store.register_source(path, &request.source));
+	let evaluate = || evaluate_script(cx, path, &request.source);
+	let result = match request.timeout {
+		Some(timeout) => rt.with_timeout(Duration::from_millis(timeout), evaluate),
+		None => evaluate(),
+	};
+
+	let value = match result {
+		Ok(value) => Some(value),
+		Err((status, report)) => {
+			session.errors.borrow_mut().push(JsonError::from_report(cx, status, &report));
+			None
+		}
+	};
+
+	// Reactions added to a promise handle its rejection, so it is reported below instead.
+	let promise = value.as_ref().and_then(|value| Promise::from_value(cx, value, true, ()).ok());
+	let settled = promise
+		.as_ref()
+		.filter(|promise| promise.state() == PromiseState::Pending)
+		.map(|promise| PromiseFuture::new(cx, promise));
+	let handled = settled.is_some();
+
+	let timeout = request.timeout.map_or(EVENT_LOOP_TIMEOUT, Duration::from_millis);
+	let result = select! {
+		result = rt.run_event_loop() => result,
+		_ = sleep(timeout) => Ok(()),
+		_ = async {
+			match settled {
+				Some(settled) => settled.await,
+				None => pending().await,
+			}
+		} => Ok(()),
+	};
+
+	match result {
+		Ok(()) => {}
+		Err(Some(report)) => {
+			let error = JsonError::from_report(cx, ExitStatus::RuntimeError, &report);
+			session.errors.borrow_mut().push(error);
+		}
+		Err(None) => {
+			let message = String::from("Unknown error occurred while executing microtask.");
+			session.errors.borrow_mut().push(JsonError::new(ExitStatus::RuntimeError, message));
+		}
+	}
+
+	let result = value.and_then(|value| match promise {
+		Some(promise) if promise.state() == PromiseState::Rejected => {
+			// Other rejections are reported as errors by the unhandled rejection handler.
+			if handled {
+				let error = JsonError::from_rejection(cx, &promise.result(cx));
+				session.errors.borrow_mut().push(error);
+			}
+			None
+		}
+		Some(promise) if promise.state() == PromiseState::Fulfilled => {
+			Some(format_value(cx, FormatConfig::default().quoted(true), &promise.result(cx)).to_string())
+		}
+		_ => Some(format_value(cx, FormatConfig::default().quoted(true), &value).to_string()),
+	});
+	session.response(request.id, result)
+}
@@ -0,0 +1,149 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::env::current_dir;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use runtime::config::{Config, LogLevel};
+use serde::Deserialize;
+
+pub(crate) const CONFIG_FILE: &str = "spiderfire.toml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct ProjectConfig {
+	pub(crate) entry: Option<PathBuf>,
+	pub(crate) preload: Vec<PathBuf>,
+	pub(crate) conditions: Vec<String>,
+	pub(crate) log_level: Option<String>,
+	pub(crate) script: Option<bool>,
+	pub(crate) typescript: Option<bool>,
+	pub(crate) console: ConsoleConfig,
+	pub(crate) gc: GcConfig,
+	pub(crate) blob: BlobConfig,
+	pub(crate) tracing: TracingConfig,
+	pub(crate) fmt: FmtConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct ConsoleConfig {
+	pub(crate) timestamps: bool,
+	pub(crate) level_tags: bool,
+	pub(crate) ids: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct GcConfig {
+	pub(crate) max_bytes: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct BlobConfig {
+	pub(crate) spill_threshold: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct TracingConfig {
+	pub(crate) otlp: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct FmtConfig {
+	pub(crate) exclude: Vec<PathBuf>,
+}
+
+/// Returns the directory containing `spiderfire.toml`, or the current directory if there is none.
+pub(crate) fn project_root() -> Option<PathBuf> {
+	let directory = current_dir().ok()?;
+	let root = directory.ancestors().find(|directory| directory.join(CONFIG_FILE).is_file());
+	Some(root.unwrap_or(&directory).to_path_buf())
+}
+
+impl ProjectConfig {
+	/// Searches for `spiderfire.toml` in the current directory and its ancestors.
+	///
+	/// Relative paths in the configuration are resolved against the directory containing the file.
+	pub(crate) fn discover() -> Result<Option<ProjectConfig>, String> {
+		match current_dir() {
+			Ok(directory) => ProjectConfig::discover_from(&directory),
+			Err(_) => Ok(None),
+		}
+	}
+
+	/// Searches for `spiderfire.toml` in `directory` and its ancestors.
+	pub(crate) fn discover_from(directory: &Path) -> Result<Option<ProjectConfig>, String> {
+		for directory in directory.ancestors() {
+			let path = directory.join(CONFIG_FILE);
+			if path.is_file() {
+				return ProjectConfig::from_file(&path).map(Some);
+			}
+		}
+
+		Ok(None)
+	}
+
+	pub(crate) fn from_file(path: &Path) -> Result<ProjectConfig, String> {
+		let source = read_to_string(path).map_err(|error| format!("Failed to read {}: {error}", path.display()))?;
+		let mut config: ProjectConfig =
+			toml::from_str(&source).map_err(|error| format!("Failed to parse {}: {error}", path.display()))?;
+
+		if let Some(directory) = path.parent() {
+			config.entry = config.entry.map(|entry| directory.join(entry));
+			config.preload = config.preload.iter().map(|preload| directory.join(preload)).collect();
+			config.fmt.exclude = config.fmt.exclude.iter().map(|exclude| directory.join(exclude)).collect();
+		}
+		if let Some(log_level) = &config.log_level {
+			if parse_log_level(log_level).is_none() {
+				return Err(format!("Invalid Logging Level in {}: {log_level}", path.display()));
+			}
+		}
+
+		Ok(config)
+	}
+
+	/// Applies the configuration to `config`. Values which were not specified are left unchanged.
+	pub(crate) fn apply(&self, mut config: Config) -> Config {
+		if let Some(log_level) = self.log_level.as_deref().and_then(parse_log_level) {
+			config = config.log_level(log_level);
+		}
+		if let Some(script) = self.script {
+			config = config.script(script);
+		}
+		if let Some(typescript) = self.typescript {
+			config = config.typescript(typescript);
+		}
+
+		config
+			.log_timestamps(config.log_timestamps || self.console.timestamps)
+			.log_level_tags(config.log_level_tags || self.console.level_tags)
+			.log_ids(config.log_ids || self.console.ids)
+			.gc_max_bytes(self.gc.max_bytes.or(config.gc_max_bytes))
+			.blob_spill_threshold(self.blob.spill_threshold.or(config.blob_spill_threshold))
+			.otlp(config.otlp || self.tracing.otlp)
+	}
+}
+
+pub(crate) fn parse_log_level(log_level: &str) -> Option<LogLevel> {
+	match log_level.to_uppercase().as_str() {
+		"NONE" => Some(LogLevel::None),
+		"INFO" => Some(LogLevel::Info),
+		"WARN" => Some(LogLevel::Warn),
+		"ERROR" => Some(LogLevel::Error),
+		"DEBUG" => Some(LogLevel::Debug),
+		_ => None,
+	}
+}
+
+/// Parses the `--log-level` argument, so that clap reports invalid levels.
+pub(crate) fn parse_log_level_arg(log_level: &str) -> Result<LogLevel, String> {
+	parse_log_level(log_level).ok_or_else(|| String::from("expected one of NONE, INFO, WARN, ERROR or DEBUG"))
+}
@@ -4,123 +4,490 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::cell::Cell;
 use std::ffi::OsStr;
 use std::fs::read_to_string;
 use std::io::ErrorKind;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Duration;
 
 use ion::format::{format_value, Config as FormatConfig};
-use ion::module::Module;
+use ion::function::CompileOptions;
+use ion::module::{Module, ModuleErrorKind};
 use ion::script::Script;
-use ion::Context;
+use ion::{Context, ErrorReport, Exception, Function, Object, Promise, Value};
 use modules::Modules;
-use mozjs::rust::{JSEngine, Runtime as RustRuntime};
+use mozjs::jsapi::PromiseState;
 use runtime::cache::locate_in_cache;
-use runtime::cache::map::{save_sourcemap, transform_error_report_with_sourcemaps};
+use runtime::cache::map::SourceMapStore;
 use runtime::config::Config;
+use runtime::event_loop::UncaughtErrorAction;
 use runtime::module::Loader;
-use runtime::{Runtime, RuntimeBuilder};
-use sourcemap::SourceMap;
+use runtime::{Engine, Runtime, RuntimeBuilder};
+use serde::Serialize;
 
-pub(crate) async fn eval_inline(rt: &Runtime<'_>, source: &str) {
-	let result = Script::compile_and_evaluate(rt.cx(), Path::new("inline.js"), source);
+use crate::commands::repl::run_repl;
+
+/// Exit status of `spiderfire run` and `spiderfire eval`.
+///
+/// When several errors occur, the status of the most severe is used, in reverse order of declaration.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum ExitStatus {
+	#[default]
+	Success,
+	UnhandledRejection,
+	RuntimeError,
+	CompileError,
+	ReadError,
+}
+
+impl ExitStatus {
+	/// Returns the exit code of the process, as documented in `docs/quick-start.md`.
+	pub(crate) fn code(self) -> i32 {
+		match self {
+			ExitStatus::Success => 0,
+			ExitStatus::RuntimeError => 1,
+			ExitStatus::CompileError => 2,
+			ExitStatus::UnhandledRejection => 3,
+			ExitStatus::ReadError => 4,
+		}
+	}
+}
+
+/// Options for the output of evaluations.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Output {
+	/// Suppresses the completion values of scripts.
+	pub(crate) quiet: bool,
+	/// Prints errors as single-line JSON objects.
+	pub(crate) json_errors: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonLocation {
+	file: String,
+	line: u32,
+	column: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonStackFrame {
+	function: Option<String>,
+	#[serde(flatten)]
+	location: JsonLocation,
+}
+
+/// Error printed by `--json-errors`, and returned by `spiderfire serve-eval`.
+#[derive(Debug, Serialize)]
+pub(crate) struct JsonError {
+	kind: ExitStatus,
+	name: Option<String>,
+	message: String,
+	location: Option<JsonLocation>,
+	stack: Vec<JsonStackFrame>,
+}
+
+impl JsonError {
+	pub(crate) fn new(kind: ExitStatus, message: String) -> JsonError {
+		JsonError {
+			kind,
+			name: None,
+			message,
+			location: None,
+			stack: Vec::new(),
+		}
+	}
+
+	pub(crate) fn from_report(cx: &Context, kind: ExitStatus, report: &ErrorReport) -> JsonError {
+		let mut error = match &report.exception {
+			Exception::Error(error) => JsonError {
+				name: Some(error.kind.to_string()),
+				location: error.location.as_ref().map(|location| JsonLocation {
+					file: location.file.clone(),
+					line: location.lineno,
+					column: location.column,
+				}),
+				..JsonError::new(kind, error.message.to_string())
+			},
+			Exception::Other(value) => {
+				let value = Value::from(cx.root(*value));
+				JsonError::new(kind, format_value(cx, FormatConfig::default(), &value).to_string())
+			}
+		};
+		if let Some(stack) = &report.stack {
+			error.stack = stack
+				.records
+				.iter()
+				.map(|record| JsonStackFrame {
+					function: record.function.clone(),
+					location: JsonLocation {
+						file: record.location.file.clone(),
+						line: record.location.lineno,
+						column: record.location.column,
+					},
+				})
+				.collect();
+		}
+		error
+	}
+
+	pub(crate) fn from_rejection(cx: &Context, reason: &Value) -> JsonError {
+		let kind = ExitStatus::UnhandledRejection;
+		match Exception::from_value(cx, reason) {
+			Ok(exception) => {
+				let report = ErrorReport::from_exception_with_error_stack(cx, exception);
+				JsonError::from_report(cx, kind, &report)
+			}
+			Err(_) => JsonError::new(kind, format_value(cx, FormatConfig::default(), reason).to_string()),
+		}
+	}
+}
+
+/// Prints the results and errors of evaluations, and records the exit status.
+#[derive(Debug)]
+pub(crate) struct Reporter {
+	output: Output,
+	status: Cell<ExitStatus>,
+}
+
+impl Reporter {
+	pub(crate) fn new(output: Output) -> Rc<Reporter> {
+		Rc::new(Reporter { output, status: Cell::default() })
+	}
+
+	/// Returns the status of the most severe error reported so far.
+	pub(crate) fn status(&self) -> ExitStatus {
+		self.status.get()
+	}
+
+	fn record(&self, status: ExitStatus) {
+		self.status.set(self.status.get().max(status));
+	}
+
+	/// Prints the completion value of a script, unless the output is quiet.
+	fn value(&self, cx: &Context, value: &Value) {
+		if !self.output.quiet {
+			println!("{}", format_value(cx, FormatConfig::default().quoted(true), value));
+		}
+	}
+
+	fn error(&self, cx: &Context, status: ExitStatus, report: &ErrorReport) {
+		self.record(status);
+		if self.output.json_errors {
+			print_json(&JsonError::from_report(cx, status, report));
+		} else {
+			eprintln!("{}", report.format(cx));
+		}
+	}
+
+	fn message(&self, status: ExitStatus, message: &str) {
+		self.record(status);
+		if self.output.json_errors {
+			print_json(&JsonError::new(status, String::from(message)));
+		} else {
+			eprintln!("{message}");
+		}
+	}
+
+	pub(crate) fn rejection(&self, cx: &Context, reason: &Value) {
+		self.record(ExitStatus::UnhandledRejection);
+		if self.output.json_errors {
+			print_json(&JsonError::from_rejection(cx, reason));
+		} else {
+			let reason = format_value(cx, FormatConfig::default(), reason);
+			eprintln!("Unhandled Promise Rejection: {reason}");
+		}
+	}
+}
+
+fn print_json(error: &JsonError) {
+	if let Ok(json) = serde_json::to_string(error) {
+		eprintln!("{json}");
+	}
+}
+
+/// Starts a REPL after the entry point has run if an uncaught error occurred, for `--repl-on-error`.
+///
+/// The REPL is shared with the reaction to the evaluation promise of a module entry point.
+struct ErrorRepl {
+	enabled: bool,
+	bound: Cell<bool>,
+}
+
+impl ErrorRepl {
+	fn new(enabled: bool) -> Rc<ErrorRepl> {
+		Rc::new(ErrorRepl { enabled, bound: Cell::new(false) })
+	}
+
+	/// Binds the exception of the first uncaught error to `error` in the global scope.
+	///
+	/// This is done as soon as the error occurs, so that the exception is kept alive by the global.
+	fn bind(&self, cx: &Context, report: &ErrorReport) {
+		if self.enabled && !self.bound.get() {
+			Object::global(cx).set_as(cx, "error", &report.exception);
+			self.bound.set(true);
+		}
+	}
+
+	async fn start(&self, rt: &Runtime<'_>) {
+		if self.bound.get() {
+			eprintln!("Starting REPL, the uncaught error is bound to `error`.");
+			run_repl(rt).await;
+		}
+	}
+}
+
+/// Compiles and evaluates a classic script, returning the status which the error would exit with if it fails.
+///
+/// The locations of the error are transformed with the source map of the script, if it has been registered.
+pub(crate) fn evaluate_script<'cx>(
+	cx: &'cx Context, path: &Path, source: &str,
+) -> Result<Value<'cx>, (ExitStatus, ErrorReport)> {
+	let result = match Script::compile(cx, path, source) {
+		Ok(script) => script.evaluate(cx).map_err(|report| (ExitStatus::RuntimeError, report)),
+		Err(report) => Err((ExitStatus::CompileError, report)),
+	};
+	result.map_err(|(status, mut report)| {
+		SourceMapStore::with(|store| store.transform_error_report(&mut report));
+		(status, report)
+	})
+}
+
+/// Compiles a line which uses `await` outside of a function as the body of an async function, and calls it.
+///
+/// The line is compiled as an expression, so that the promise resolves with its value, or as statements otherwise.
+/// Returns [None] if it compiles as neither.
+fn evaluate_async<'cx>(
+	cx: &'cx Context, path: &Path, source: &str,
+) -> Option<Result<Promise<'cx>, Option<ErrorReport>>> {
+	let filename = path.to_string_lossy();
+	let options = CompileOptions { filename: &filename, line: 1 };
+	let function = [("(", ")"), ("{", "}")].into_iter().find_map(|(open, close)| {
+		let body = format!("return (async () => {open}\n{source}\n{close})();");
+		Function::compile(cx, "", &[], &body, options).ok()
+	})?;
+
+	let result = function.call(cx, &Object::global(cx), &[]);
+	Some(result.map(|value| Promise::from(value.to_object(cx).into_local()).unwrap()))
+}
+
+/// Evaluates a line of JavaScript, throwing a `TimeoutError` in it if it runs for longer than `timeout`.
+///
+/// Lines which use `await` outside of a function are evaluated in an async function, and the value they settle with is
+/// printed once the event loop is idle.
+pub(crate) async fn eval_inline(rt: &Runtime<'_>, reporter: &Reporter, source: &str, timeout: Option<Duration>) {
+	let path = Path::new("inline.js");
+	SourceMapStore::with(|store| store.register_source(path, source));
+	let mut promise = None;
+	let mut evaluate = || match evaluate_script(rt.cx(), path, source) {
+		Err((ExitStatus::CompileError, report)) if source.contains("await") => {
+			match evaluate_async(rt.cx(), path, source) {
+				Some(Ok(settled)) => {
+					promise = Some(settled);
+					Ok(None)
+				}
+				Some(Err(report)) => Err((ExitStatus::RuntimeError, report)),
+				None => Err((ExitStatus::CompileError, Some(report))),
+			}
+		}
+		result => result.map(Some).map_err(|(status, report)| (status, Some(report))),
+	};
+	let result = match timeout {
+		Some(timeout) => rt.with_timeout(timeout, evaluate),
+		None => evaluate(),
+	};
 
 	match result {
-		Ok(v) => println!("{}", format_value(rt.cx(), FormatConfig::default().quoted(true), &v)),
-		Err(report) => eprintln!("{}", report.format(rt.cx())),
+		Ok(Some(v)) => reporter.value(rt.cx(), &v),
+		Ok(None) => {}
+		Err((status, Some(report))) => reporter.error(rt.cx(), status, &report),
+		Err((status, None)) => reporter.message(status, "Unknown error occurred while evaluating."),
+	}
+	// Rejections of the promise are reported below, rather than as unhandled rejections.
+	if let Some(promise) = &promise {
+		promise.catch(rt.cx(), |_, _| Ok(Value::undefined_handle()));
+	}
+	run_event_loop(rt, reporter).await;
+
+	if let Some(promise) = promise {
+		match promise.state() {
+			PromiseState::Fulfilled => reporter.value(rt.cx(), &promise.result(rt.cx())),
+			PromiseState::Rejected => {
+				let reason = promise.result(rt.cx());
+				let exception = Exception::from_value(rt.cx(), &reason).unwrap_or(Exception::Other(reason.get()));
+				let mut report = ErrorReport::from_exception_with_error_stack(rt.cx(), exception);
+				SourceMapStore::with(|store| store.transform_error_report(&mut report));
+				reporter.error(rt.cx(), ExitStatus::RuntimeError, &report);
+			}
+			PromiseState::Pending => {}
+		}
 	}
-	run_event_loop(rt).await;
 }
 
-pub(crate) async fn eval_script(path: &Path) {
-	let engine = JSEngine::init().unwrap();
-	let rt = RustRuntime::new(engine.handle());
+/// Evaluates each preload script as a classic script, running the event loop after each.
+///
+/// Returns false if a preload script could not be read or threw an error.
+pub(crate) async fn eval_preloads(rt: &Runtime<'_>, reporter: &Reporter, preloads: &[PathBuf]) -> bool {
+	for path in preloads {
+		let Some((script, _)) = read_script(path, reporter) else {
+			return false;
+		};
 
-	let cx = &mut Context::from_runtime(&rt);
+		let script = cache(path, script);
+		if let Err((status, report)) = evaluate_script(rt.cx(), path, &script) {
+			reporter.error(rt.cx(), status, &report);
+			return false;
+		}
+		run_event_loop(rt, reporter).await;
+	}
+	true
+}
+
+pub(crate) async fn eval_script(
+	path: &Path, preloads: &[PathBuf], config: Config, reporter: &Rc<Reporter>, repl_on_error: bool,
+) {
+	let mut engine = Engine::new();
+	let rejections = Rc::clone(reporter);
 	let rt = RuntimeBuilder::<(), _>::new()
+		.config(config)
+		.expect("Configuration should only be set once")
 		.microtask_queue()
 		.macrotask_queue()
 		.standard_modules(Modules)
-		.build(cx);
+		.unhandled_rejection_handler(move |cx: &Context, reason: &Value| rejections.rejection(cx, reason))
+		.build(engine.cx());
 
-	if let Some((script, _)) = read_script(path) {
-		let (script, sourcemap) = cache(path, script);
-		if let Some(sourcemap) = sourcemap {
-			save_sourcemap(path, sourcemap);
-		}
-		let result = Script::compile_and_evaluate(rt.cx(), path, &script);
+	if !eval_preloads(&rt, reporter, preloads).await {
+		return;
+	}
+
+	if let Some((script, _)) = read_script(path, reporter) {
+		let script = cache(path, script);
+		let result = evaluate_script(rt.cx(), path, &script);
+		let repl = ErrorRepl::new(repl_on_error);
 
 		match result {
-			Ok(v) => println!("{}", format_value(rt.cx(), FormatConfig::default().quoted(true), &v)),
-			Err(mut report) => {
-				transform_error_report_with_sourcemaps(&mut report);
-				eprintln!("{}", report.format(rt.cx()));
+			Ok(v) => reporter.value(rt.cx(), &v),
+			Err((status, report)) => {
+				if rt.handle_uncaught_error(&report) == UncaughtErrorAction::Exit {
+					reporter.error(rt.cx(), status, &report);
+					repl.bind(rt.cx(), &report);
+				}
 			}
 		}
-		run_event_loop(&rt).await;
+		if let Some(report) = run_event_loop(&rt, reporter).await {
+			repl.bind(rt.cx(), &report);
+		}
+		repl.start(&rt).await;
 	}
 }
 
-pub(crate) async fn eval_module(path: &Path) {
-	let engine = JSEngine::init().unwrap();
-	let rt = RustRuntime::new(engine.handle());
-
-	let cx = &mut Context::from_runtime(&rt);
+pub(crate) async fn eval_module(
+	path: &Path, preloads: &[PathBuf], config: Config, loader: Loader, reporter: &Rc<Reporter>, repl_on_error: bool,
+) {
+	let mut engine = Engine::new();
+	let rejections = Rc::clone(reporter);
 	let rt = RuntimeBuilder::new()
+		.config(config)
+		.expect("Configuration should only be set once")
 		.microtask_queue()
 		.macrotask_queue()
-		.modules(Loader::default())
+		.modules(loader)
 		.standard_modules(Modules)
-		.build(cx);
+		.unhandled_rejection_handler(move |cx: &Context, reason: &Value| rejections.rejection(cx, reason))
+		.build(engine.cx());
 
-	if let Some((script, filename)) = read_script(path) {
-		let (script, sourcemap) = cache(path, script);
-		if let Some(sourcemap) = sourcemap {
-			save_sourcemap(path, sourcemap);
-		}
+	if !eval_preloads(&rt, reporter, preloads).await {
+		return;
+	}
+
+	if let Some((script, filename)) = read_script(path, reporter) {
+		let script = cache(path, script);
 		let result = Module::compile_and_evaluate(rt.cx(), &filename, Some(path), &script);
+		let repl = ErrorRepl::new(repl_on_error);
 
-		if let Err(mut error) = result {
-			transform_error_report_with_sourcemaps(&mut error.report);
-			eprintln!("{}", error.format(rt.cx()));
+		match result {
+			Ok((_, Some(promise))) => {
+				// Errors thrown by the module are runtime errors, rather than rejections of its evaluation promise.
+				let reporter = Rc::clone(reporter);
+				let repl = Rc::clone(&repl);
+				promise.catch(rt.cx(), move |cx, reason| {
+					let exception = Exception::from_value(cx, reason)?;
+					let mut report = ErrorReport::from_exception_with_error_stack(cx, exception);
+					SourceMapStore::with(|store| store.transform_error_report(&mut report));
+					reporter.error(cx, ExitStatus::RuntimeError, &report);
+					repl.bind(cx, &report);
+					Ok(Value::undefined_handle())
+				});
+			}
+			Ok((_, None)) => {}
+			Err(mut error) => {
+				SourceMapStore::with(|store| store.transform_error_report(&mut error.report));
+				let status = match error.kind {
+					ModuleErrorKind::Compilation | ModuleErrorKind::Instantiation => ExitStatus::CompileError,
+					ModuleErrorKind::Evaluation => ExitStatus::RuntimeError,
+				};
+				if rt.handle_uncaught_error(&error.report) == UncaughtErrorAction::Exit {
+					reporter.error(rt.cx(), status, &error.report);
+					repl.bind(rt.cx(), &error.report);
+				}
+			}
+		}
+		if let Some(report) = run_event_loop(&rt, reporter).await {
+			repl.bind(rt.cx(), &report);
 		}
-		run_event_loop(&rt).await;
+		repl.start(&rt).await;
 	}
 }
 
-fn read_script(path: &Path) -> Option<(String, String)> {
+fn read_script(path: &Path, reporter: &Reporter) -> Option<(String, String)> {
 	match read_to_string(path) {
 		Ok(script) => {
 			let filename = String::from(path.file_name().unwrap().to_str().unwrap());
 			Some((script, filename))
 		}
 		Err(error) => {
-			eprintln!("Failed to read file: {}", path.display());
-			match error.kind() {
-				ErrorKind::NotFound => eprintln!("(File was not found)"),
-				ErrorKind::PermissionDenied => eprintln!("Current User lacks permissions to read the file)"),
-				_ => eprintln!("{:?}", error),
-			}
+			let reason = match error.kind() {
+				ErrorKind::NotFound => String::from("(File was not found)"),
+				ErrorKind::PermissionDenied => String::from("Current User lacks permissions to read the file)"),
+				_ => format!("{error:?}"),
+			};
+			let message = format!("Failed to read file: {}\n{reason}", path.display());
+			reporter.message(ExitStatus::ReadError, &message);
 			None
 		}
 	}
 }
 
-async fn run_event_loop(rt: &Runtime<'_>) {
-	if let Err(err) = rt.run_event_loop().await {
-		if let Some(err) = err {
-			eprintln!("{}", err.format(rt.cx()));
-		} else {
-			eprintln!("Unknown error occurred while executing microtask.");
+/// Runs the event loop, reporting and returning the error report if an uncaught error occurs.
+async fn run_event_loop(rt: &Runtime<'_>, reporter: &Reporter) -> Option<ErrorReport> {
+	match rt.run_event_loop().await {
+		Ok(()) => None,
+		Err(Some(mut err)) => {
+			SourceMapStore::with(|store| store.transform_error_report(&mut err));
+			reporter.error(rt.cx(), ExitStatus::RuntimeError, &err);
+			Some(err)
+		}
+		Err(None) => {
+			reporter.message(ExitStatus::RuntimeError, "Unknown error occurred while executing microtask.");
+			None
 		}
 	}
 }
 
-fn cache(path: &Path, script: String) -> (String, Option<SourceMap>) {
+/// Compiles TypeScript through the cache, and registers the source map of the script for its errors and stacks.
+fn cache(path: &Path, script: String) -> String {
 	let is_typescript = Config::global().typescript && path.extension() == Some(OsStr::new("ts"));
-	is_typescript
-		.then(|| locate_in_cache(path, &script))
-		.flatten()
-		.map(|(s, sm)| (s, Some(sm)))
-		.unwrap_or_else(|| (script, None))
+	match is_typescript.then(|| locate_in_cache(path, &script)).flatten() {
+		Some((compiled, sourcemap)) => {
+			SourceMapStore::with(|store| store.insert(path, sourcemap));
+			compiled
+		}
+		None => {
+			SourceMapStore::with(|store| store.register_source(path, &script));
+			script
+		}
+	}
 }
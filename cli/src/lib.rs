@@ -4,11 +4,19 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
+use clap_complete::Shell;
+use commands::graph::GraphFormat;
 use commands::handle_command;
+use commands::init::Template;
+use config::parse_log_level_arg;
+use runtime::config::LogLevel;
 use tokio::task::LocalSet;
 
 mod commands;
+mod config;
 mod evaluate;
 mod repl;
 
@@ -25,40 +33,157 @@ pub(crate) enum Command {
 	Cache {
 		#[arg(help = "Clears the Cache", short, long)]
 		clear: bool,
+
+		#[arg(help = "Records the packages in node_modules in spiderfire.lock", long)]
+		lock: bool,
+	},
+
+	#[command(about = "Prints a shell completion script")]
+	Completions {
+		#[arg(help = "Shell to generate the completion script for", required(true))]
+		shell: Shell,
 	},
 
 	#[command(about = "Evaluates a line of JavaScript")]
 	Eval {
 		#[arg(help = "Line of JavaScript to be evaluated", required(true))]
 		source: String,
+
+		#[arg(help = "Throws a TimeoutError in the evaluation after the given number of milliseconds", long)]
+		timeout: Option<u64>,
+
+		#[arg(help = "Only prints errors, without the result or console output below the error level", short, long)]
+		quiet: bool,
+
+		#[arg(help = "Prints errors as JSON objects, one per line", long)]
+		json_errors: bool,
+	},
+
+	#[command(about = "Formats JavaScript and TypeScript files")]
+	Fmt {
+		#[arg(help = "Files or directories to format, Default: current directory")]
+		paths: Vec<PathBuf>,
+
+		#[arg(help = "Lists unformatted files and fails instead of formatting them", long)]
+		check: bool,
+	},
+
+	#[command(about = "Prints the module graph of a JavaScript file without running it")]
+	Graph {
+		#[arg(help = "The entry point of the module graph", required(true))]
+		path: PathBuf,
+
+		#[arg(help = "Sets the output format", short, long, value_enum, default_value_t)]
+		format: GraphFormat,
+
+		#[arg(
+			help = "Adds a condition to match against the exports of packages, can be repeated",
+			short = 'C',
+			long = "conditions"
+		)]
+		conditions: Vec<String>,
+	},
+
+	#[command(about = "Creates a starter project")]
+	Init {
+		#[arg(help = "Project template, Default: typescript", value_enum, default_value_t)]
+		template: Template,
+
+		#[arg(help = "Directory to create the project in, Default: current directory", short, long)]
+		directory: Option<PathBuf>,
 	},
 
 	#[command(about = "Starts a JavaScript Shell")]
-	Repl,
+	Repl {
+		#[arg(
+			help = "Evaluates a script before the first prompt, can be repeated",
+			long,
+			visible_alias = "require"
+		)]
+		preload: Vec<PathBuf>,
+	},
 
 	#[command(about = "Runs a JavaScript file")]
 	Run {
 		#[arg(
-			help = "The JavaScript file to run, Default: 'main.js'",
-			required(false),
-			default_value = "main.js"
+			help = "The JavaScript file to run, Default: 'entry' in spiderfire.toml or 'main.js'",
+			required(false)
 		)]
-		path: String,
+		path: Option<String>,
 
 		#[arg(
-			help = "Sets logging level, Default: ERROR",
+			help = "Sets logging level, Default: 'log-level' in spiderfire.toml or ERROR",
 			short,
 			long,
 			required(false),
-			default_value = "ERROR"
+			value_parser = parse_log_level_arg
 		)]
-		log_level: String,
+		log_level: Option<LogLevel>,
 
 		#[arg(help = "Sets logging level to DEBUG", short, long)]
 		debug: bool,
 
-		#[arg(help = "Disables ES Modules Features", short, long)]
-		script: bool,
+		#[arg(
+			help = "Disables ES Modules Features, Default: 'script' in spiderfire.toml or false",
+			short,
+			long,
+			num_args = 0..=1,
+			require_equals = true,
+			default_missing_value = "true"
+		)]
+		script: Option<bool>,
+
+		#[arg(
+			help = "Evaluates a script before the entry point, can be repeated",
+			long,
+			visible_alias = "require"
+		)]
+		preload: Vec<PathBuf>,
+
+		#[arg(help = "Prefixes console output with timestamps", long)]
+		timestamps: bool,
+
+		#[arg(help = "Prefixes console output with logging levels", long)]
+		level_tags: bool,
+
+		#[arg(help = "Prefixes console output with process and runtime IDs", long)]
+		log_ids: bool,
+
+		#[arg(help = "Overwrites stored snapshots instead of comparing against them", long)]
+		update_snapshots: bool,
+
+		#[arg(help = "Exports tracing spans to an OpenTelemetry collector", long)]
+		otlp: bool,
+
+		#[arg(
+			help = "Adds a condition to match against the exports of packages, can be repeated",
+			short = 'C',
+			long = "conditions"
+		)]
+		conditions: Vec<String>,
+
+		#[arg(
+			help = "Starts a REPL with the error bound to `error` when an uncaught error occurs",
+			long,
+			visible_alias = "inspect-on-error"
+		)]
+		repl_on_error: bool,
+
+		#[arg(
+			help = "Only prints errors, without the completion value or console output below the error level",
+			short,
+			long
+		)]
+		quiet: bool,
+
+		#[arg(help = "Prints errors as JSON objects, one per line", long)]
+		json_errors: bool,
+	},
+
+	#[command(about = "Evaluates scripts sent as lines of JSON to a Unix domain socket in a persistent runtime")]
+	ServeEval {
+		#[arg(help = "Path of the socket to listen on", long, required(true))]
+		socket: PathBuf,
 	},
 }
 
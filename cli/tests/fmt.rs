@@ -0,0 +1,50 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::env::temp_dir;
+use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+use std::path::Path;
+use std::process;
+use std::process::{Command, Output};
+
+const UNFORMATTED: &str = "let  x=1";
+
+fn spiderfire(args: &[&str]) -> Output {
+	Command::new(env!("CARGO_BIN_EXE_cli"))
+		.args(args)
+		.current_dir(temp_dir())
+		.output()
+		.unwrap()
+}
+
+#[test]
+fn fmt() {
+	let root = temp_dir().join(format!("spiderfire-fmt-{}", process::id()));
+	create_dir_all(root.join("src")).unwrap();
+	create_dir_all(root.join("dist")).unwrap();
+	write(root.join("spiderfire.toml"), "[fmt]\nexclude = [\"dist\"]\n").unwrap();
+	write(root.join("src/main.js"), UNFORMATTED).unwrap();
+	write(root.join("dist/main.js"), UNFORMATTED).unwrap();
+	let path = root.to_str().unwrap();
+
+	let output = spiderfire(&["fmt", "--check", path]);
+	assert_eq!(output.status.code(), Some(1));
+	let stdout = String::from_utf8(output.stdout).unwrap();
+	assert!(stdout.contains(&Path::new("src").join("main.js").display().to_string()));
+	assert!(!stdout.contains("dist"), "Excluded file was checked: {stdout}");
+
+	assert!(spiderfire(&["fmt", path]).status.success());
+	assert_ne!(read_to_string(root.join("src/main.js")).unwrap(), UNFORMATTED);
+	assert_eq!(read_to_string(root.join("dist/main.js")).unwrap(), UNFORMATTED);
+	assert!(spiderfire(&["fmt", "--check", path]).status.success());
+
+	write(root.join("spiderfire.toml"), "[fmt]\nexclude = \"dist\"\n").unwrap();
+	let output = spiderfire(&["fmt", "--check", path]);
+	assert_eq!(output.status.code(), Some(1));
+	assert!(String::from_utf8(output.stderr).unwrap().contains("spiderfire.toml"));
+
+	remove_dir_all(&root).unwrap();
+}
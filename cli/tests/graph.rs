@@ -0,0 +1,89 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::env::temp_dir;
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::process;
+use std::process::Command;
+
+use serde_json::Value;
+
+const MAIN: &str = r#"
+import "./local.js";
+import "package";
+import "node:fs";
+"#;
+
+#[test]
+fn graph() {
+	let root = temp_dir().join(format!("spiderfire-graph-{}", process::id()));
+	create_dir_all(root.join("node_modules/package/dist")).unwrap();
+	write(root.join("main.js"), MAIN).unwrap();
+	write(root.join("local.js"), "").unwrap();
+	write(
+		root.join("node_modules/package/package.json"),
+		r#"{"exports": {"worker": "./dist/worker.js", "default": "./dist/index.js"}}"#,
+	)
+	.unwrap();
+	write(root.join("node_modules/package/dist/index.js"), "").unwrap();
+	write(root.join("node_modules/package/dist/worker.js"), "").unwrap();
+
+	let graph = |args: &[&str]| {
+		let output = Command::new(env!("CARGO_BIN_EXE_cli"))
+			.args(["graph", "main.js", "--format", "json"])
+			.args(args)
+			.current_dir(&root)
+			.output()
+			.unwrap();
+		assert!(output.status.success());
+		serde_json::from_slice::<Value>(&output.stdout).unwrap()
+	};
+
+	let modules = graph(&[])["modules"].as_array().unwrap().clone();
+	let module = |specifier: &str| {
+		let dependency = modules[0]["dependencies"]
+			.as_array()
+			.unwrap()
+			.iter()
+			.find(|dependency| dependency["specifier"] == specifier)
+			.unwrap();
+		modules[dependency["module"].as_u64().unwrap() as usize].clone()
+	};
+
+	assert_eq!(module("./local.js")["kind"], "javascript");
+	let package = module("package");
+	assert_eq!(package["kind"], "javascript");
+	assert!(package["path"].as_str().unwrap().ends_with("index.js"), "{package}");
+	assert_eq!(package["error"], Value::Null);
+	let builtin = module("node:fs");
+	assert_eq!(builtin["kind"], "builtin");
+	assert_eq!(builtin["error"], Value::Null);
+
+	let modules = graph(&["--conditions", "worker"])["modules"].as_array().unwrap().clone();
+	assert!(modules.iter().any(|module| module["path"].as_str().unwrap().ends_with("worker.js")));
+
+	remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn graph_missing_entry() {
+	let root = temp_dir().join(format!("spiderfire-graph-missing-{}", process::id()));
+	create_dir_all(&root).unwrap();
+
+	let output = Command::new(env!("CARGO_BIN_EXE_cli"))
+		.args(["graph", "main.js", "--format", "json"])
+		.current_dir(&root)
+		.output()
+		.unwrap();
+	assert!(output.status.success());
+
+	let graph = serde_json::from_slice::<Value>(&output.stdout).unwrap();
+	let entry = &graph["modules"][0];
+	assert_eq!(entry["kind"], "javascript");
+	assert!(entry["error"].is_string(), "{entry}");
+
+	remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,104 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::env::temp_dir;
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::io::Write;
+use std::path::Path;
+use std::process;
+use std::process::{Command, Output, Stdio};
+
+fn spiderfire(directory: &Path, args: &[&str]) -> Output {
+	Command::new(env!("CARGO_BIN_EXE_cli"))
+		.args(args)
+		.current_dir(directory)
+		.output()
+		.unwrap()
+}
+
+#[test]
+fn arguments() {
+	let root = temp_dir().join(format!("spiderfire-run-{}", process::id()));
+	create_dir_all(&root).unwrap();
+	write(root.join("spiderfire.toml"), "script = true\n").unwrap();
+	write(root.join("main.js"), "export const value = 1;\n").unwrap();
+
+	let output = spiderfire(&root, &["run", "main.js", "--log-level", "verbose"]);
+	assert_eq!(output.status.code(), Some(2));
+	assert!(String::from_utf8(output.stderr).unwrap().contains("invalid value 'verbose'"));
+
+	// The script flag of the configuration file is overridden by the command line.
+	assert!(!spiderfire(&root, &["run", "main.js"]).status.success());
+	assert!(spiderfire(&root, &["run", "main.js", "--script=false"]).status.success());
+
+	remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn exit_codes() {
+	let root = temp_dir().join(format!("spiderfire-exit-{}", process::id()));
+	create_dir_all(&root).unwrap();
+	let cases = [
+		("success.js", "export const value = 1;\n", 0),
+		("runtime.js", "await null;\nthrow new Error(\"Runtime\");\n", 1),
+		("compile.js", "export const = 1;\n", 2),
+		("rejection.js", "Promise.reject(new Error(\"Rejection\"));\n", 3),
+	];
+	for (name, source, code) in cases {
+		write(root.join(name), source).unwrap();
+		let output = spiderfire(&root, &["run", name]);
+		assert_eq!(output.status.code(), Some(code), "{name}");
+	}
+	assert_eq!(spiderfire(&root, &["run", "missing.js"]).status.code(), Some(4));
+
+	remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn repl_on_error() {
+	let root = temp_dir().join(format!("spiderfire-repl-{}", process::id()));
+	create_dir_all(&root).unwrap();
+	// The error is thrown after the module has suspended, so it rejects the evaluation promise.
+	write(root.join("main.js"), "await null;\nthrow new Error(\"Late\");\n").unwrap();
+
+	let mut child = Command::new(env!("CARGO_BIN_EXE_cli"))
+		.args(["run", "main.js", "--repl-on-error"])
+		.current_dir(&root)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.unwrap();
+	child.stdin.take().unwrap().write_all(b"error.message\nexit\n").unwrap();
+	let output = child.wait_with_output().unwrap();
+
+	assert_eq!(output.status.code(), Some(1));
+	assert!(String::from_utf8(output.stderr).unwrap().contains("Starting REPL"));
+	assert!(String::from_utf8(output.stdout).unwrap().contains("Late"));
+
+	remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn repl_await() {
+	let mut child = Command::new(env!("CARGO_BIN_EXE_cli"))
+		.arg("repl")
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.unwrap();
+	let input = [
+		"await new Promise(resolve => setTimeout(() => resolve(42), 10))",
+		"await Promise.reject(new Error(\"Awaited\"))",
+		"exit\n",
+	];
+	child.stdin.take().unwrap().write_all(input.join("\n").as_bytes()).unwrap();
+	let output = child.wait_with_output().unwrap();
+
+	assert!(String::from_utf8(output.stdout).unwrap().contains("42"));
+	assert!(String::from_utf8(output.stderr).unwrap().contains("Awaited"));
+}
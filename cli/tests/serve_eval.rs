@@ -0,0 +1,88 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#![cfg(unix)]
+
+use std::env::temp_dir;
+use std::fs::remove_file;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::process;
+use std::process::{Child, Command, Stdio};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+fn serve(socket: &Path) -> Command {
+	let mut command = Command::new(env!("CARGO_BIN_EXE_cli"));
+	command.arg("serve-eval").arg("--socket").arg(socket).stdout(Stdio::null());
+	command
+}
+
+fn connect(socket: &Path, server: &mut Child) -> UnixStream {
+	let start = Instant::now();
+	loop {
+		if let Ok(stream) = UnixStream::connect(socket) {
+			return stream;
+		}
+		assert!(server.try_wait().unwrap().is_none(), "Server exited");
+		assert!(start.elapsed() < Duration::from_secs(30), "Server did not listen");
+		sleep(Duration::from_millis(50));
+	}
+}
+
+fn request(stream: &mut UnixStream, request: &str) -> Value {
+	stream.write_all(format!("{request}\n").as_bytes()).unwrap();
+	let mut response = String::new();
+	BufReader::new(&*stream).read_line(&mut response).unwrap();
+	serde_json::from_str(&response).unwrap()
+}
+
+#[test]
+fn serve_eval() {
+	let socket = temp_dir().join(format!("spiderfire-serve-{}.sock", process::id()));
+	// A socket left behind by a previous server is replaced.
+	drop(UnixListener::bind(&socket).unwrap());
+
+	let mut server = serve(&socket).stderr(Stdio::null()).spawn().unwrap();
+	// An idle connection does not block others.
+	let _idle = connect(&socket, &mut server);
+	let mut stream = connect(&socket, &mut server);
+
+	let response = request(&mut stream, r#"{"id": 1, "source": "1 + 1"}"#);
+	assert_eq!(response["id"], 1);
+	assert!(response["result"].as_str().unwrap().contains('2'));
+
+	let start = Instant::now();
+	let response = request(
+		&mut stream,
+		r#"{"id": 2, "source": "setInterval(() => {}, 10); 'interval'", "timeout": 100}"#,
+	);
+	assert!(response["result"].as_str().unwrap().contains("interval"));
+	assert!(start.elapsed() < Duration::from_secs(5), "Event loop was not bounded");
+
+	let response = request(
+		&mut stream,
+		r#"{"id": 3, "source": "new Promise(resolve => setTimeout(() => resolve('settled'), 10))"}"#,
+	);
+	assert!(response["result"].as_str().unwrap().contains("settled"));
+
+	let response = request(&mut stream, "not json");
+	assert!(response["invalid"].as_str().unwrap().starts_with("Invalid request"));
+	assert_eq!(response["errors"], Value::Array(Vec::new()));
+
+	// The socket of a server which is still listening is not replaced.
+	let output = serve(&socket).output().unwrap();
+	assert!(String::from_utf8(output.stderr).unwrap().contains("Failed to listen"));
+	let response = request(&mut stream, r#"{"id": 4, "source": "'alive'"}"#);
+	assert!(response["result"].as_str().unwrap().contains("alive"));
+
+	server.kill().unwrap();
+	server.wait().unwrap();
+	remove_file(&socket).unwrap();
+}
@@ -5,10 +5,11 @@
  */
 
 use syn::meta::ParseNestedMeta;
-use syn::{LitStr, Result};
+use syn::{Expr, LitStr, Result};
 
 use crate::attribute::name::Name;
 use crate::attribute::{ArgumentError, ParseArgument, ParseArgumentWith, ParseAttribute};
+use crate::class::field::FieldKind;
 use crate::class::method::MethodKind;
 
 // TODO: Add `inspectable` to provide `toString` and `toJSON`
@@ -48,3 +49,24 @@ impl ParseAttribute for MethodAttribute {
 		Ok(())
 	}
 }
+
+#[derive(Default)]
+pub(crate) struct FieldAttribute {
+	pub(crate) name: Option<Name>,
+	pub(crate) kind: Option<FieldKind>,
+	pub(crate) convert: Option<Box<Expr>>,
+}
+
+impl ParseAttribute for FieldAttribute {
+	fn parse(&mut self, meta: &ParseNestedMeta) -> Result<()> {
+		const FIELD_KIND_ERROR: ArgumentError =
+			ArgumentError::Full("Field cannot have multiple `readonly` or `accessor` attributes.");
+
+		self.name.parse_argument(meta, "name", "Field")?;
+		self.kind.parse_argument_with(meta, FieldKind::Readonly, "readonly", FIELD_KIND_ERROR)?;
+		self.kind.parse_argument_with(meta, FieldKind::Accessor, "accessor", FIELD_KIND_ERROR)?;
+		self.convert.parse_argument(meta, "convert", "Field")?;
+
+		Ok(())
+	}
+}
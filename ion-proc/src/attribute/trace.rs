@@ -6,17 +6,20 @@
 
 use syn::meta::ParseNestedMeta;
 use syn::parse::Result;
+use syn::LitStr;
 
 use crate::attribute::{ParseArgument, ParseAttribute};
 
 #[derive(Default)]
 pub(crate) struct TraceAttribute {
 	pub(crate) no_trace: bool,
+	pub(crate) reason: Option<LitStr>,
 }
 
 impl ParseAttribute for TraceAttribute {
 	fn parse(&mut self, meta: &ParseNestedMeta) -> Result<()> {
 		self.no_trace.parse_argument(meta, "no_trace", "Field")?;
+		self.reason.parse_argument(meta, "reason", "Field")?;
 		Ok(())
 	}
 }
@@ -4,14 +4,14 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, TokenStream};
 use syn::{ItemFn, Result, Type};
 
 use crate::class::method::{Method, MethodReceiver};
 use crate::function::wrapper::impl_wrapper_fn;
 use crate::function::{check_abi, set_signature};
 
-pub(super) fn impl_constructor(ion: &TokenStream, mut constructor: ItemFn, ty: &Type) -> Result<Method> {
+pub(super) fn impl_constructor(ion: &TokenStream, mut constructor: ItemFn, ty: &Type, ident: Ident) -> Result<Method> {
 	let (wrapper, parameters) = impl_wrapper_fn(ion, constructor.clone(), Some(ty), true)?;
 
 	check_abi(&mut constructor)?;
@@ -21,11 +21,6 @@ pub(super) fn impl_constructor(ion: &TokenStream, mut constructor: ItemFn, ty: &
 	let body = parse_quote!({
 		let cx = &#ion::Context::new_unchecked(cx);
 		let args = &mut #ion::Arguments::new(cx, argc, vp);
-		let mut this = #ion::Object::from(
-			cx.root(
-				::mozjs::jsapi::JS_NewObjectForConstructor(cx.as_ptr(), &<#ty as #ion::ClassDefinition>::class().base, args.call_args())
-			)
-		);
 
 		#wrapper
 
@@ -37,6 +32,14 @@ pub(super) fn impl_constructor(ion: &TokenStream, mut constructor: ItemFn, ty: &
 			return false;
 		}
 
+		let mut this = match #ion::class::__new_object_for_constructor::<#ty>(cx, args) {
+			::std::result::Result::Ok(this) => this,
+			::std::result::Result::Err(error) => {
+				#ion::ThrowException::throw(&error, cx);
+				return false;
+			}
+		};
+
 		let result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| {
 			wrapper(cx, args, &mut this)
 		}));
@@ -44,7 +47,7 @@ pub(super) fn impl_constructor(ion: &TokenStream, mut constructor: ItemFn, ty: &
 		#ion::function::__handle_native_constructor_result(cx, result, &this, &mut args.rval())
 	});
 	constructor.block = body;
-	constructor.sig.ident = format_ident!("__ion_bindings_constructor", span = constructor.sig.ident.span());
+	constructor.sig.ident = ident;
 
 	let method = Method {
 		receiver: MethodReceiver::Static,
@@ -0,0 +1,111 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashMap;
+
+use convert_case::{Case, Casing};
+use proc_macro2::TokenStream;
+use syn::spanned::Spanned;
+use syn::{parse2, Error, ItemFn, ItemImpl, ItemStruct, Result, Type};
+
+use crate::attribute::class::FieldAttribute;
+use crate::attribute::name::Name;
+use crate::attribute::ParseAttribute;
+use crate::class::accessor::{flatten_accessors, impl_accessor, insert_accessor};
+use crate::class::method::Method;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum FieldKind {
+	Readonly,
+	Accessor,
+}
+
+/// Generates accessors for the fields of a class with `readonly` or `accessor` attributes.
+///
+/// Getters return a clone of the field, and setters of `accessor` fields replace it with the converted value.
+pub(super) fn impl_field_accessors(ion: &TokenStream, r#struct: &mut ItemStruct, r#type: &Type) -> Result<ItemImpl> {
+	let class = r#struct.ident.clone();
+	let mut accessors = HashMap::new();
+	let mut functions = Vec::new();
+
+	for field in &mut r#struct.fields {
+		let FieldAttribute { name, kind, convert } = FieldAttribute::from_attributes_mut("ion", &mut field.attrs)?;
+		let Some(kind) = kind else {
+			if convert.is_some() || name.is_some() {
+				return Err(Error::new(
+					field.span(),
+					"Field must have a `readonly` or `accessor` attribute.",
+				));
+			}
+			continue;
+		};
+		if kind == FieldKind::Readonly && convert.is_some() {
+			return Err(Error::new(
+				field.span(),
+				"Field with `readonly` attribute cannot have `convert` attributes.",
+			));
+		}
+
+		let Some(ident) = &field.ident else {
+			return Err(Error::new(field.span(), "Accessor Fields must be named."));
+		};
+		let ty = &field.ty;
+		let name = name.unwrap_or_else(|| Name::from_string(ident.to_string().to_case(Case::Camel), ident.span()));
+
+		let getter_ident = format_ident!("__ion_field_get_{}", ident);
+		let getter: ItemFn = parse2(quote_spanned!(field.span() => pub fn #getter_ident(&self) -> #ty {
+			::std::clone::Clone::clone(&self.#ident)
+		}))?;
+		functions.push(getter.clone());
+		let (getter, _) = impl_accessor(ion, getter, r#type, false)?;
+		let getter = Method { names: vec![name.clone()], ..getter };
+
+		let setter = if kind == FieldKind::Accessor {
+			let setter_ident = format_ident!("__ion_field_set_{}", ident);
+			let convert = convert.map(|convert| quote!(#[ion(convert = #convert)]));
+			let setter: ItemFn = parse2(quote_spanned!(field.span() => pub fn #setter_ident(&mut self, value: #ty) {
+				self.#ident = value;
+			}))?;
+			functions.push(setter);
+
+			let setter: ItemFn =
+				parse2(quote_spanned!(field.span() => pub fn #setter_ident(&mut self, #convert value: #ty) {
+					self.#ident = value;
+				}))?;
+			let (setter, _) = impl_accessor(ion, setter, r#type, true)?;
+			Some(Method { names: vec![name.clone()], ..setter })
+		} else {
+			None
+		};
+
+		insert_accessor(&mut accessors, name.as_string(), Some(getter), setter);
+	}
+
+	let specs: Vec<_> = accessors.values().flat_map(|accessor| accessor.to_specs(ion, &class)).collect();
+	let properties = if specs.is_empty() {
+		quote!(::std::option::Option::None)
+	} else {
+		quote!({
+			static SPECS: &[::mozjs::jsapi::JSPropertySpec] = &[
+				#(#specs,)*
+				::mozjs::jsapi::JSPropertySpec::ZERO,
+			];
+			::std::option::Option::Some(SPECS)
+		})
+	};
+	let wrappers = flatten_accessors(accessors).map(|method| method.method);
+
+	let mut field_impl: ItemImpl = parse2(quote_spanned!(r#struct.span() => impl #r#type {
+		#(#functions)*
+		#(#wrappers)*
+
+		pub fn __ion_field_properties() -> ::std::option::Option<&'static [::mozjs::jsapi::JSPropertySpec]> {
+			#properties
+		}
+	}))?;
+	field_impl.attrs.push(parse_quote!(#[doc(hidden)]));
+	Ok(field_impl)
+}
@@ -39,7 +39,7 @@ pub(super) fn impl_js_class_impl(r#impl: &mut ItemImpl) -> Result<[ItemImpl; 2]>
 	}
 
 	let r#type = *r#impl.self_ty.clone();
-	let mut constructor: Option<Method> = None;
+	let mut constructors: Vec<Method> = Vec::new();
 	let mut specs = PrototypeSpecs::default();
 
 	for item in &mut r#impl.items {
@@ -55,17 +55,16 @@ pub(super) fn impl_js_class_impl(r#impl: &mut ItemImpl) -> Result<[ItemImpl; 2]>
 			}
 			ImplItem::Fn(r#fn) => {
 				if let Some(parsed_constructor) = parse_class_method(ion, r#fn, &mut specs, &r#type)? {
-					if let Some(constructor) = constructor.as_ref() {
+					if let Some(overload) = constructors.iter().find(|c| c.nargs == parsed_constructor.nargs) {
 						return Err(Error::new(
-							r#fn.span(),
+							r#fn.sig.ident.span(),
 							format!(
-								"Received multiple constructor implementations: {} and {}.",
-								constructor.method.sig.ident, parsed_constructor.method.sig.ident
+								"Received multiple constructor overloads with {} required arguments: {} and {}.",
+								overload.nargs, overload.method.sig.ident, parsed_constructor.method.sig.ident
 							),
 						));
-					} else {
-						constructor = Some(parsed_constructor);
 					}
+					constructors.push(parsed_constructor);
 				}
 			}
 			_ => (),
@@ -80,7 +79,8 @@ pub(super) fn impl_js_class_impl(r#impl: &mut ItemImpl) -> Result<[ItemImpl; 2]>
 	});
 
 	let ident: Ident = parse2(quote_spanned!(r#type.span() => #r#type))?;
-	class_definition(ion, r#impl.span(), &r#type, &ident, constructor, specs)
+	let (constructor, overloads) = constructor_overloads(r#impl.span(), constructors)?;
+	class_definition(ion, r#impl.span(), &r#type, &ident, constructor, overloads, specs)
 }
 
 fn parse_class_method(
@@ -126,7 +126,8 @@ fn parse_class_method(
 
 	match kind {
 		Some(MethodKind::Constructor) => {
-			let constructor = impl_constructor(ion, method, r#type)?;
+			let ident = format_ident!("__ion_bindings_constructor_{}", r#fn.sig.ident);
+			let constructor = impl_constructor(ion, method, r#type, ident)?;
 			return Ok(Some(Method { names, ..constructor }));
 		}
 		Some(MethodKind::Getter) => {
@@ -164,8 +165,53 @@ fn parse_class_method(
 	Ok(None)
 }
 
+/// Combines the constructors of a class into the constructor of the class, returning it with the bindings of the
+/// overloads it calls.
+///
+/// With multiple overloads, the constructor calls the overload with the most required arguments which were all
+/// passed, or the overload with the fewest required arguments, which throws if too few were passed.
+fn constructor_overloads(span: Span, mut constructors: Vec<Method>) -> Result<(Option<Method>, Vec<ItemFn>)> {
+	constructors.sort_by(|a, b| b.nargs.cmp(&a.nargs));
+	let Some(mut fallback) = constructors.pop() else {
+		return Ok((None, Vec::new()));
+	};
+	if constructors.is_empty() {
+		let span = fallback.method.sig.ident.span();
+		fallback.method.sig.ident = format_ident!("__ion_bindings_constructor", span = span);
+		return Ok((Some(fallback), Vec::new()));
+	}
+
+	let overloads = constructors.iter().map(|constructor| {
+		let ident = &constructor.method.sig.ident;
+		let nargs = u32::from(constructor.nargs);
+		quote!(if argc >= #nargs {
+			return unsafe { Self::#ident(cx, argc, vp) };
+		})
+	});
+	let fallback_ident = &fallback.method.sig.ident;
+	let dispatch = parse2(quote_spanned!(span =>
+		pub unsafe extern "C" fn __ion_bindings_constructor(
+			cx: *mut ::mozjs::jsapi::JSContext, argc: ::core::primitive::u32, vp: *mut ::mozjs::jsval::JSVal,
+		) -> ::core::primitive::bool {
+			#(#overloads)*
+			unsafe { Self::#fallback_ident(cx, argc, vp) }
+		}
+	))?;
+
+	let constructor = Method {
+		receiver: MethodReceiver::Static,
+		method: dispatch,
+		nargs: fallback.nargs,
+		names: Vec::new(),
+	};
+	constructors.push(fallback);
+	let overloads = constructors.into_iter().map(|constructor| constructor.method).collect();
+	Ok((Some(constructor), overloads))
+}
+
 fn class_definition(
-	ion: &TokenStream, span: Span, r#type: &Type, ident: &Ident, constructor: Option<Method>, specs: PrototypeSpecs,
+	ion: &TokenStream, span: Span, r#type: &Type, ident: &Ident, constructor: Option<Method>, overloads: Vec<ItemFn>,
+	specs: PrototypeSpecs,
 ) -> Result<[ItemImpl; 2]> {
 	let (spec_fns, def_fns) = specs.to_impl_fns(ion, span, ident)?;
 	let constructor_function = constructor.as_ref().map(|c| &c.method);
@@ -173,6 +219,7 @@ fn class_definition(
 
 	let mut spec_impls: ItemImpl = parse2(quote_spanned!(span => impl #r#type {
 		#constructor_function
+		#(#overloads)*
 		#(#functions)*
 		#(#spec_fns)*
 	}))?;
@@ -198,6 +245,10 @@ fn class_definition(
 			(#constructor_function, #constructor_nargs)
 		}
 
+		fn field_properties() -> ::std::option::Option<&'static [::mozjs::jsapi::JSPropertySpec]> {
+			#r#type::__ion_field_properties()
+		}
+
 		#(#def_fns)*
 	}))?;
 
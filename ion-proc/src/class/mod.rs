@@ -13,6 +13,7 @@ use crate::class::r#struct::impl_js_class_struct;
 
 mod accessor;
 pub(crate) mod constructor;
+pub(crate) mod field;
 mod r#impl;
 pub(crate) mod method;
 pub(crate) mod property;
@@ -12,9 +12,10 @@ use syn::{parse2, Error, ItemImpl, ItemStruct, Member, Path, Result, Type};
 use crate::attribute::class::ClassAttribute;
 use crate::attribute::krate::crate_from_attributes;
 use crate::attribute::ParseAttribute;
+use crate::class::field::impl_field_accessors;
 use crate::utils::{new_token, path_ends_with};
 
-pub(super) fn impl_js_class_struct(r#struct: &mut ItemStruct) -> Result<[ItemImpl; 7]> {
+pub(super) fn impl_js_class_struct(r#struct: &mut ItemStruct) -> Result<[ItemImpl; 8]> {
 	let ion = &crate_from_attributes(&mut r#struct.attrs);
 
 	let repr_c = r#struct.attrs.iter().fold(Ok(false), |acc, attr| {
@@ -77,12 +78,14 @@ pub(super) fn impl_js_class_struct(r#struct: &mut ItemStruct) -> Result<[ItemImp
 		return Err(Error::new(super_type.span(), "Superclass Type must be a path."));
 	}
 
-	class_impls(ion, r#struct.span(), &name, &r#type, &super_field, &super_type)
+	let field_impl = impl_field_accessors(ion, r#struct, &r#type)?;
+	class_impls(ion, r#struct.span(), &name, &r#type, &super_field, &super_type, field_impl)
 }
 
 fn class_impls(
 	ion: &TokenStream, span: Span, name: &str, r#type: &Type, super_field: &Member, super_type: &Type,
-) -> Result<[ItemImpl; 7]> {
+	field_impl: ItemImpl,
+) -> Result<[ItemImpl; 8]> {
 	let from_value = impl_from_value(ion, span, r#type, false)?;
 	let from_value_mut = impl_from_value(ion, span, r#type, true)?;
 
@@ -143,7 +146,7 @@ fn class_impls(
 		quote_spanned!(super_type.span() => ::std::option::Option::None)
 	} else {
 		quote_spanned!(super_type.span() =>
-			let infos = unsafe { &mut (*cx.get_inner_data().as_ptr()).class_infos };
+			let infos = unsafe { cx.class_infos() };
 			let info = infos.get(&::core::any::TypeId::of::<#super_type>()).expect("Uninitialised Class");
 			::std::option::Option::Some(cx.root(info.prototype.get()))
 		)
@@ -164,6 +167,7 @@ fn class_impls(
 		native_object,
 		class_impl,
 		parent_impl,
+		field_impl,
 	])
 }
 
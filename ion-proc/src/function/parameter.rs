@@ -182,7 +182,10 @@ impl Parameters {
 					Err(e) => return Some(Err(e)),
 				};
 				if let Type::Path(ty) = &*param.pat_ty.ty {
-					if !path_ends_with(&ty.path, "Opt") && !path_ends_with(&ty.path, "Rest") {
+					if !path_ends_with(&ty.path, "Opt")
+						&& !path_ends_with(&ty.path, "Rest")
+						&& !path_ends_with(&ty.path, "NewTarget")
+					{
 						nargs = match nargs.checked_add(1) {
 							Some(nargs) => nargs,
 							None => return Some(Err(Error::new(arg.span(), "Function has too many arguments"))),
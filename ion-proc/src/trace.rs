@@ -4,13 +4,18 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use proc_macro2::{Ident, Span};
+use proc_macro2::{Ident, Span, TokenStream};
 use syn::spanned::Spanned;
-use syn::{parse2, Arm, Block, Data, DeriveInput, Error, Fields, Generics, ItemImpl, Result};
+use syn::visit_mut::VisitMut;
+use syn::{
+	parse2, Arm, Block, Data, DeriveInput, Error, Fields, GenericArgument, Generics, ItemImpl, PathArguments, Result,
+	Type,
+};
 
 use crate::attribute::trace::TraceAttribute;
 use crate::attribute::ParseAttribute;
 use crate::utils::add_trait_bounds;
+use crate::visitors::HeapFinder;
 
 pub(super) fn impl_trace(mut input: DeriveInput) -> Result<ItemImpl> {
 	add_trait_bounds(&mut input.generics, &parse_quote!(::mozjs::gc::Traceable));
@@ -34,14 +39,10 @@ pub(super) fn impl_trace(mut input: DeriveInput) -> Result<ItemImpl> {
 fn impl_body(span: Span, data: &Data) -> Result<Box<Block>> {
 	match data {
 		Data::Struct(r#struct) => {
-			let (idents, skip) = field_idents(&r#struct.fields)?;
-			let traced = idents
-				.iter()
-				.enumerate()
-				.filter_map(|(index, ident)| (!skip.contains(&index)).then_some(ident));
+			let (idents, traced) = fields_to_trace(&r#struct.fields)?;
 			parse2(quote_spanned!(span => {
 				let Self { #(#idents,)* } = self;
-				#(::mozjs::gc::Traceable::trace(#traced, __ion_tracer));*
+				#(#traced)*
 			}))
 		}
 		Data::Enum(r#enum) => {
@@ -50,17 +51,13 @@ fn impl_body(span: Span, data: &Data) -> Result<Box<Block>> {
 				.iter()
 				.map(|variant| {
 					let ident = &variant.ident;
-					let (idents, skip) = field_idents(&variant.fields)?;
-					let traced = idents
-						.iter()
-						.enumerate()
-						.filter_map(|(index, ident)| (!skip.contains(&index)).then_some(ident));
+					let (idents, traced) = fields_to_trace(&variant.fields)?;
 					match &variant.fields {
 						Fields::Named(_) => parse2(quote_spanned!(variant.span() => Self::#ident { #(#idents,)* } => {
-							#(::mozjs::gc::Traceable::trace(#traced, __ion_tracer));*
+							#(#traced)*
 						})),
 						Fields::Unnamed(_) => parse2(quote_spanned!(variant.span() => Self::#ident(#(#idents,)* ) => {
-							#(::mozjs::gc::Traceable::trace(#traced, __ion_tracer));*
+							#(#traced)*
 						})),
 						Fields::Unit => parse2(quote_spanned!(variant.span() => Self::#ident => {})),
 					}
@@ -79,26 +76,128 @@ fn impl_body(span: Span, data: &Data) -> Result<Box<Block>> {
 	}
 }
 
-fn field_idents(fields: &Fields) -> Result<(Vec<Ident>, Vec<usize>)> {
+/// Returns the identifiers which the fields are bound to, and the statements which trace the fields which are not
+/// skipped.
+///
+/// Fields containing a `Heap` can only be skipped with a reason, such as being rooted elsewhere, since the objects
+/// they reference would otherwise be collected while they are still in use.
+fn fields_to_trace(fields: &Fields) -> Result<(Vec<Ident>, Vec<TokenStream>)> {
 	let fields = match fields {
 		Fields::Named(fields) => &fields.named,
 		Fields::Unnamed(fields) => &fields.unnamed,
 		Fields::Unit => return Ok((Vec::new(), Vec::new())),
 	};
-	let mut skip = Vec::new();
-	let idents = fields
-		.iter()
-		.enumerate()
-		.map(|(index, field)| {
-			let attribute = TraceAttribute::from_attributes("trace", &field.attrs)?;
-			if attribute.no_trace {
-				skip.push(index);
+
+	let mut idents = Vec::with_capacity(fields.len());
+	let mut traced = Vec::with_capacity(fields.len());
+	for (index, field) in fields.iter().enumerate() {
+		let ident = match &field.ident {
+			Some(ident) => ident.clone(),
+			None => format_ident!("var{}", index),
+		};
+
+		let attribute = TraceAttribute::from_attributes("trace", &field.attrs)?;
+		if let Some(reason) = &attribute.reason {
+			if !attribute.no_trace {
+				return Err(Error::new(
+					reason.span(),
+					"Field with `reason` attribute must have `no_trace` attribute.",
+				));
 			}
-			match &field.ident {
-				Some(ident) => Ok(ident.clone()),
-				None => Ok(format_ident!("var{}", index)),
+		}
+
+		if attribute.no_trace {
+			let mut finder = HeapFinder::default();
+			finder.visit_type_mut(&mut field.ty.clone());
+			if let (Some(heap), None) = (finder.heap, &attribute.reason) {
+				return Err(Error::new(
+					heap,
+					"Field containing a Heap cannot be skipped without a reason, as the values it references could \
+					 be collected. Use #[trace(no_trace, reason = \"...\")] if they are rooted elsewhere.",
+				));
 			}
-		})
-		.collect::<Result<_>>()?;
-	Ok((idents, skip))
+		} else {
+			traced.push(trace_value(&field.ty, &quote!(#ident), 0));
+		}
+		idents.push(ident);
+	}
+	Ok((idents, traced))
+}
+
+/// Traces a reference to a value of the given type, looking through common containers so that their contents only
+/// need to implement `Traceable`.
+fn trace_value(ty: &Type, value: &TokenStream, depth: usize) -> TokenStream {
+	let inner = format_ident!("__ion_trace_{}", depth);
+	match ty {
+		Type::Paren(ty) => trace_value(&ty.elem, value, depth),
+		Type::Group(ty) => trace_value(&ty.elem, value, depth),
+		Type::Tuple(tuple) => {
+			let idents: Vec<_> = (0..tuple.elems.len()).map(|index| format_ident!("{}_{}", inner, index)).collect();
+			let traced = tuple
+				.elems
+				.iter()
+				.zip(&idents)
+				.map(|(ty, ident)| trace_value(ty, &quote!(#ident), depth + 1));
+			quote!({
+				let (#(#idents,)*) = #value;
+				#(#traced)*
+			})
+		}
+		Type::Array(array) => {
+			let traced = trace_value(&array.elem, &quote!(#inner), depth + 1);
+			quote!(for #inner in #value.iter() { #traced })
+		}
+		Type::Path(path) if path.qself.is_none() => {
+			let Some(segment) = path.path.segments.last() else {
+				return trace_fallback(value);
+			};
+			let arguments: Vec<_> = match &segment.arguments {
+				PathArguments::AngleBracketed(arguments) => arguments
+					.args
+					.iter()
+					.filter_map(|argument| match argument {
+						GenericArgument::Type(ty) => Some(ty),
+						_ => None,
+					})
+					.collect(),
+				_ => Vec::new(),
+			};
+
+			match (segment.ident.to_string().as_str(), arguments.as_slice()) {
+				("Option", [ty]) => {
+					let traced = trace_value(ty, &quote!(#inner), depth + 1);
+					quote!(if let ::std::option::Option::Some(#inner) = #value { #traced })
+				}
+				("Box" | "Rc" | "Arc", [ty]) => {
+					let traced = trace_value(ty, &quote!(#inner), depth + 1);
+					quote!({
+						let #inner = &**#value;
+						#traced
+					})
+				}
+				("RefCell", [ty]) => {
+					let traced = trace_value(ty, &quote!(#inner), depth + 1);
+					// Tracing may happen while the cell is mutably borrowed, in which case borrowing it would panic.
+					quote!({
+						let #inner = &*#value.as_ptr();
+						#traced
+					})
+				}
+				("Vec" | "VecDeque" | "HashSet" | "BTreeSet" | "IndexSet", [ty, ..]) => {
+					let traced = trace_value(ty, &quote!(#inner), depth + 1);
+					quote!(for #inner in #value.iter() { #traced })
+				}
+				("HashMap" | "BTreeMap" | "IndexMap", [_, ty, ..]) => {
+					let traced = trace_value(ty, &quote!(#inner), depth + 1);
+					quote!(for #inner in #value.values() { #traced })
+				}
+				_ => trace_fallback(value),
+			}
+		}
+		_ => trace_fallback(value),
+	}
+}
+
+fn trace_fallback(value: &TokenStream) -> TokenStream {
+	quote!(::mozjs::gc::Traceable::trace(#value, __ion_tracer);)
 }
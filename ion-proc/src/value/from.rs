@@ -36,7 +36,6 @@ pub(crate) fn impl_from_value(mut input: DeriveInput) -> Result<ItemImpl> {
 	}
 
 	let attribute = DataAttribute::from_attributes("ion", &input.attrs)?;
-	let DataAttribute { tag, inherit } = attribute;
 
 	let mut repr = None;
 	for attr in &input.attrs {
@@ -70,7 +69,7 @@ pub(crate) fn impl_from_value(mut input: DeriveInput) -> Result<ItemImpl> {
 
 	let name = &input.ident;
 
-	let (body, requires_object) = impl_body(ion, input.span(), &input.data, name, tag, inherit, repr)?;
+	let (body, requires_object) = impl_body(ion, input.span(), &input.data, name, attribute, repr)?;
 
 	let object = if requires_object {
 		Some(quote_spanned!(input.span() =>
@@ -94,34 +93,54 @@ pub(crate) fn impl_from_value(mut input: DeriveInput) -> Result<ItemImpl> {
 }
 
 fn impl_body(
-	ion: &TokenStream, span: Span, data: &Data, ident: &Ident, tag: Optional<Tag>, inherit: bool, repr: Option<Ident>,
+	ion: &TokenStream, span: Span, data: &Data, ident: &Ident, attribute: DataAttribute, repr: Option<Ident>,
 ) -> Result<(Box<Block>, bool)> {
+	let DataAttribute { tag, inherit, validate } = attribute;
 	match data {
-		Data::Struct(data) => match &data.fields {
-			Fields::Named(fields) => {
-				let mapped = map_fields(ion, &fields.named, None, tag, inherit)?;
-				let (requirement, idents, declarations, requires_object) = mapped;
-				parse2(quote_spanned!(span => {
-					#requirement
-					#(#declarations)*
-					::std::result::Result::Ok(Self { #(#idents, )* })
-				}))
-				.map(|b| (b, requires_object))
-			}
-			Fields::Unnamed(fields) => {
-				let mapped = map_fields(ion, &fields.unnamed, None, tag, inherit)?;
-				let (requirement, idents, declarations, requires_object) = mapped;
-				parse2(quote_spanned!(span => {
-					#requirement
-					#(#declarations)*
-					::std::result::Result::Ok(Self(#(#idents, )*))
-				}))
-				.map(|block| (block, requires_object))
-			}
-			Fields::Unit => {
-				parse2(quote_spanned!(span => { ::std::result::Result::Ok(Self) })).map(|block| (block, false))
-			}
-		},
+		Data::Struct(data) => {
+			let (fields, named) = match &data.fields {
+				Fields::Named(fields) => (&fields.named, true),
+				Fields::Unnamed(fields) => (&fields.unnamed, false),
+				Fields::Unit => {
+					return parse2(quote_spanned!(span => { ::std::result::Result::Ok(Self) }))
+						.map(|block| (block, false));
+				}
+			};
+
+			let mapped = map_fields(ion, fields, None, tag, inherit)?;
+			let (requirement, idents, declarations, requires_object) = mapped;
+			let construct = if named {
+				quote!(Self { #(#idents, )* })
+			} else {
+				quote!(Self(#(#idents, )*))
+			};
+			let validate = validate.map(|validate| quote_spanned!(validate.span() => (#validate)(cx, &__value)?;));
+
+			let value = if fields.is_empty() {
+				construct
+			} else {
+				let name = ident.to_string();
+				quote!(match (#(#idents, )*) {
+					(#(::std::option::Option::Some(#idents), )*) => #construct,
+					_ => return ::std::result::Result::Err(#ion::conversions::__member_errors(#name, __errors)),
+				})
+			};
+			let errors = (!fields.is_empty()).then(|| quote!(let mut __errors = ::std::vec::Vec::new();));
+
+			parse2(quote_spanned!(span => {
+				#requirement
+				#errors
+				#(#declarations)*
+				let __value = #value;
+				#validate
+				::std::result::Result::Ok(__value)
+			}))
+			.map(|block| (block, requires_object))
+		}
+		Data::Enum(_) if validate.is_some() => Err(Error::new(
+			span,
+			"#[ion(validate)] is only implemented for struct types",
+		)),
 		Data::Enum(data) => {
 			let unit = data.variants.iter().all(|variant| matches!(variant.fields, Fields::Unit));
 
@@ -246,6 +265,8 @@ fn map_fields(
 	inherit: bool,
 ) -> Result<(TokenStream, Vec<Ident>, Vec<TokenStream>, bool)> {
 	let mut requires_object = matches!(tag.0, Some(Tag::External | Tag::Internal(_)));
+	// Errors from the fields of structs are collected, so that every invalid field is reported at once.
+	let aggregate = variant.is_none() && !fields.is_empty();
 
 	let requirement = match tag.0 {
 		Some(Tag::External) => {
@@ -253,7 +274,7 @@ fn map_fields(
 				let error = format!("Expected Object at External Tag {variant}");
 				quote!(
 					let __object: #ion::Object = __object.get_as(cx, #variant, true, ())?
-						.ok_or_else(|_| #ion::Error::new(#error, #ion::ErrorKind::Type))?;
+						.ok_or_else(|| #ion::Error::new(#error, #ion::ErrorKind::Type))?;
 				)
 			} else {
 				return Err(Error::new(Span::call_site(), "Cannot have Tag for Struct"));
@@ -330,24 +351,24 @@ fn map_fields(
 					)));
 				}
 				quote_spanned!(field.span() =>
-					let #ident: #ty = <#ty as #ion::conversions::FromValue>::from_value(cx, value, #strict || strict, #convert)
+					<#ty as #ion::conversions::FromValue>::from_value(cx, value, #strict || strict, #convert)
 				)
 			} else if let Some(parser) = &parser {
 				requires_object = true;
 				let error = format!("Expected Value at Key {key}");
-				quote_spanned!(field.span() => let #ident: #ty = __object.get(cx, #key)?.map(#parser).transpose()?
+				quote_spanned!(field.span() => __object.get(cx, #key)?.map(#parser).transpose()?
 					.ok_or_else(|| #ion::Error::new(#error, #ion::ErrorKind::Type)))
 			} else {
 				requires_object = true;
 				let error = format!("Expected Value at key {key} of Type {}", format_type(ty));
-				quote_spanned!(field.span() => let #ident: #ty = __object.get_as(cx, #key, #strict || strict, #convert)?
+				quote_spanned!(field.span() => __object.get_as(cx, #key, #strict || strict, #convert)?
 					.ok_or_else(|| #ion::Error::new(#error, #ion::ErrorKind::Type)))
 			};
 
-			let stmt = if optional {
-				quote_spanned!(field.span() => #base.ok();)
+			let value = if optional {
+				quote_spanned!(field.span() => #base.ok())
 			} else {
-				match default.0 {
+				match &default.0 {
 					Some(DefaultValue::Expr(expr)) => {
 						if inherit {
 							return Some(Err(Error::new(
@@ -355,18 +376,36 @@ fn map_fields(
 								"Cannot have Default Expression with Inherited Field. Use a Closure with One Argument Instead",
 							)));
 						} else {
-							quote_spanned!(field.span() => #base.unwrap_or_else(|_| #expr);)
+							quote_spanned!(field.span() => #base.unwrap_or_else(|_| #expr))
 						}
 					}
 					Some(DefaultValue::Closure(closure)) => {
-						quote_spanned!(field.span() => #base.unwrap_or_else(#closure);)
+						quote_spanned!(field.span() => #base.unwrap_or_else(#closure))
 					}
-					Some(DefaultValue::Literal(lit)) => quote_spanned!(field.span() => #base.unwrap_or(#lit);),
-					Some(DefaultValue::Default) => quote_spanned!(field.span() => #base.unwrap_or_default();),
-					None => quote_spanned!(field.span() => #base?;),
+					Some(DefaultValue::Literal(lit)) => quote_spanned!(field.span() => #base.unwrap_or(#lit)),
+					Some(DefaultValue::Default) => quote_spanned!(field.span() => #base.unwrap_or_default()),
+					None => quote_spanned!(field.span() => #base?),
 				}
 			};
 
+			let stmt = if aggregate {
+				let result = if optional || default.0.is_some() {
+					quote_spanned!(field.span() => ::std::result::Result::Ok(#value))
+				} else {
+					base
+				};
+				quote_spanned!(field.span() =>
+					let #ident: ::std::option::Option<#ty> = #ion::conversions::__collect_member_error(
+						cx,
+						#key,
+						(|| -> #ion::Result<#ty> { #result })(),
+						&mut __errors,
+					)?;
+				)
+			} else {
+				quote_spanned!(field.span() => let #ident: #ty = #value;)
+			};
+
 			Some(Ok((ident, stmt)))
 		})
 		.collect::<Result<_>>()?;
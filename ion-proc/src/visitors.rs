@@ -4,9 +4,10 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use proc_macro2::Span;
 use syn::punctuated::Punctuated;
-use syn::visit_mut::{visit_type_mut, visit_type_path_mut, visit_type_reference_mut, VisitMut};
-use syn::{GenericArgument, PathArguments, Type, TypePath, TypeReference};
+use syn::visit_mut::{visit_path_segment_mut, visit_type_mut, visit_type_path_mut, visit_type_reference_mut, VisitMut};
+use syn::{GenericArgument, PathArguments, PathSegment, Type, TypePath, TypeReference};
 
 use crate::utils::path_ends_with;
 
@@ -48,3 +49,18 @@ impl VisitMut for SelfRenamer<'_> {
 		visit_type_mut(self, ty);
 	}
 }
+
+/// Finds the first `Heap` or `BoxedHeap` in a type, which must be traced to be kept alive.
+#[derive(Default)]
+pub(crate) struct HeapFinder {
+	pub(crate) heap: Option<Span>,
+}
+
+impl VisitMut for HeapFinder {
+	fn visit_path_segment_mut(&mut self, segment: &mut PathSegment) {
+		if self.heap.is_none() && (segment.ident == "Heap" || segment.ident == "BoxedHeap") {
+			self.heap = Some(segment.ident.span());
+		}
+		visit_path_segment_mut(self, segment);
+	}
+}
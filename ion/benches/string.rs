@@ -0,0 +1,72 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ion::conversions::FromValue;
+use ion::string::CompactString;
+use ion::utils::test::TestRuntime;
+use ion::{Context, String, Value};
+
+const INPUTS: [(&str, &str); 4] = [
+	("key", "length"),
+	("ascii", "content-type: application/json; charset=utf-8"),
+	("latin1", "Café crème brûlée à la carte, s'il vous plaît"),
+	("utf16", "Привет, мир! こんにちは世界 🌍"),
+];
+
+/// Runs `f` in a new [Context] for each sample, so that the values it roots are released between samples.
+fn rooted(rt: &TestRuntime, iterations: u64, f: impl Fn(&Context)) -> Duration {
+	let cx = unsafe { Context::new_unchecked(rt.cx.as_ptr()) };
+	let start = Instant::now();
+	for _ in 0..iterations {
+		f(&cx);
+	}
+	start.elapsed()
+}
+
+fn string_conversion(c: &mut Criterion) {
+	let rt = TestRuntime::new();
+	let cx = &rt.cx;
+
+	let mut group = c.benchmark_group("copy_from_str");
+	for (name, input) in INPUTS {
+		group.bench_with_input(BenchmarkId::from_parameter(name), input, |b, input| {
+			b.iter_custom(|iterations| {
+				rooted(&rt, iterations, |cx| {
+					black_box(String::copy_from_str(cx, black_box(input)));
+				})
+			});
+		});
+	}
+	group.finish();
+
+	let mut group = c.benchmark_group("to_owned");
+	for (name, input) in INPUTS {
+		let string = String::copy_from_str(cx, input).unwrap();
+		group.bench_function(BenchmarkId::from_parameter(name), |b| {
+			b.iter(|| black_box(string.to_owned(cx).unwrap()));
+		});
+	}
+	group.finish();
+
+	let mut group = c.benchmark_group("from_value");
+	for (name, input) in INPUTS {
+		let value = Value::string(cx, input);
+		group.bench_function(BenchmarkId::new("String", name), |b| {
+			b.iter(|| black_box(std::string::String::from_value(cx, &value, true, ()).unwrap()));
+		});
+		group.bench_function(BenchmarkId::new("CompactString", name), |b| {
+			b.iter(|| black_box(CompactString::from_value(cx, &value, true, ()).unwrap()));
+		});
+	}
+	group.finish();
+}
+
+criterion_group!(benches, string_conversion);
+criterion_main!(benches);
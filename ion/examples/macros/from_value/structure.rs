@@ -3,7 +3,7 @@ use std::sync::Arc;
 
 use ion::conversions::{ConversionBehavior, FromValue};
 use ion::function::{Enforce, Strict};
-use ion::{Context, FromValue, Object, Result, Value};
+use ion::{Context, Error, ErrorKind, FromValue, Object, Result, Value};
 
 #[derive(FromValue)]
 pub struct Complex<'cx> {
@@ -26,3 +26,18 @@ pub struct Complex<'cx> {
 fn parse_as_atomic_arc(cx: &Context, value: Value) -> Result<Arc<AtomicU64>> {
 	u64::from_value(cx, &value, true, ConversionBehavior::Default).map(|num| Arc::new(AtomicU64::new(num)))
 }
+
+#[derive(FromValue)]
+#[ion(validate = validate_range)]
+pub struct Range {
+	pub start: u32,
+	pub end: u32,
+}
+
+fn validate_range(_: &Context, range: &Range) -> Result<()> {
+	if range.start <= range.end {
+		Ok(())
+	} else {
+		Err(Error::new("start must not be greater than end", ErrorKind::Range))
+	}
+}
@@ -7,7 +7,10 @@ use ion::{js_class, Context, Function, Object, Result, Value};
 pub struct Toggle {
 	reflector: Reflector,
 	toggle: bool,
+	#[ion(readonly)]
 	toggled: i32,
+	#[ion(accessor)]
+	label: String,
 }
 
 #[js_class]
@@ -1,3 +1,4 @@
 pub mod from_value;
 pub mod js_class;
 pub mod js_fn;
+pub mod traceable;
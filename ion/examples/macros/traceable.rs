@@ -0,0 +1,25 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use ion::Traceable;
+use mozjs::jsapi::{Heap, JSObject};
+use mozjs::jsval::JSVal;
+
+#[derive(Traceable)]
+pub struct Containers {
+	callbacks: HashMap<String, Box<Heap<*mut JSObject>>>,
+	pending: Option<Box<Heap<JSVal>>>,
+	pair: (Box<Heap<JSVal>>, u32),
+	queue: RefCell<Vec<Box<Heap<JSVal>>>>,
+	#[trace(no_trace)]
+	count: usize,
+	#[trace(no_trace, reason = "Rooted by the owner of the map.")]
+	rooted: *const Heap<JSVal>,
+}
+
+#[derive(Traceable)]
+pub enum State {
+	Empty,
+	Value(Box<Heap<JSVal>>),
+	Entries { entries: Vec<(String, Box<Heap<JSVal>>)> },
+}
@@ -13,8 +13,9 @@ use mozjs::error::throw_type_error;
 use mozjs::gc::{HandleObject, Traceable};
 use mozjs::glue::JS_GetReservedSlot;
 use mozjs::jsapi::{
-	GCContext, Heap, JSContext, JSFunction, JSFunctionSpec, JSObject, JSPropertySpec, JSTracer, JS_GetConstructor,
-	JS_HasInstance, JS_InitClass, JS_InstanceOf, JS_NewObjectWithGivenProto, JS_SetReservedSlot,
+	GCContext, JSAutoRealm, JSContext, JSFunction, JSFunctionSpec, JSObject, JSPropertySpec, JSTracer,
+	JS_DefineProperties, JS_GetConstructor, JS_HasInstance, JS_InitClass, JS_InstanceOf, JS_NewObjectWithGivenProto,
+	JS_SetReservedSlot,
 };
 use mozjs::jsval::{JSVal, NullValue, PrivateValue, UndefinedValue};
 use mozjs::rust::get_object_class;
@@ -22,8 +23,8 @@ use mozjs::rust::get_object_class;
 pub use crate::class::native::{NativeClass, PrototypeChain, TypeIdWrapper, MAX_PROTO_CHAIN_LENGTH};
 pub use crate::class::reflect::{Castable, DerivedFrom, NativeObject, Reflector};
 use crate::conversions::{IntoValue, ToValue};
-use crate::function::NativeFunction;
-use crate::{class_num_reserved_slots, Context, Error, ErrorKind, Function, Local, Object, Result, Value};
+use crate::function::{Arguments, NativeFunction};
+use crate::{class_num_reserved_slots, BoxedHeap, Context, Error, ErrorKind, Function, Local, Object, Result, Value};
 
 mod native;
 mod reflect;
@@ -33,8 +34,8 @@ mod reflect;
 #[derive(Debug)]
 pub struct ClassInfo {
 	class: &'static NativeClass,
-	constructor: Box<Heap<*mut JSFunction>>,
-	pub prototype: Box<Heap<*mut JSObject>>,
+	constructor: BoxedHeap<*mut JSFunction>,
+	pub prototype: BoxedHeap<*mut JSObject>,
 }
 
 unsafe impl Traceable for ClassInfo {
@@ -73,8 +74,18 @@ pub trait ClassDefinition: NativeObject {
 		None
 	}
 
+	/// Returns the accessors generated for fields with `#[ion(readonly)]` or `#[ion(accessor)]` attributes, which are
+	/// defined on the prototype after the class is initialised.
+	fn field_properties() -> Option<&'static [JSPropertySpec]> {
+		None
+	}
+
+	/// Initialises the class on `object`, in the realm of `object`.
+	/// Returns whether the class was not already initialised in that realm,
+	/// or `false` with a pending exception if its field accessors could not be defined.
 	fn init_class<'cx>(cx: &'cx Context, object: &Object) -> (bool, &'cx ClassInfo) {
-		let infos = unsafe { &mut (*cx.get_inner_data().as_ptr()).class_infos };
+		let _realm = JSAutoRealm::new(cx.as_ptr(), object.handle().get());
+		let infos = unsafe { cx.class_infos() };
 
 		match infos.entry(TypeId::of::<Self>()) {
 			Entry::Occupied(o) => (false, o.into_mut()),
@@ -111,23 +122,32 @@ pub trait ClassDefinition: NativeObject {
 				};
 				let prototype = cx.root(class);
 
+				let field_properties = Self::field_properties();
+				assert!(has_zero_spec(field_properties));
+				let defined = match field_properties {
+					Some(field_properties) => unsafe {
+						JS_DefineProperties(cx.as_ptr(), prototype.handle().into(), field_properties.as_ptr())
+					},
+					None => true,
+				};
+
 				let constructor = unsafe { JS_GetConstructor(cx.as_ptr(), prototype.handle().into()) };
 				let constructor = Object::from(cx.root(constructor));
 				let constructor = Function::from_object(cx, &constructor).unwrap();
 
 				let class_info = ClassInfo {
 					class: Self::class(),
-					constructor: Heap::boxed(constructor.get()),
-					prototype: Heap::boxed(prototype.get()),
+					constructor: BoxedHeap::new(constructor.get()),
+					prototype: BoxedHeap::new(prototype.get()),
 				};
 
-				(true, entry.insert(class_info))
+				(defined, entry.insert(class_info))
 			}
 		}
 	}
 
 	fn new_raw_object(cx: &Context) -> *mut JSObject {
-		let infos = unsafe { &mut (*cx.get_inner_data().as_ptr()).class_infos };
+		let infos = unsafe { cx.class_infos() };
 		let info = infos.get(&TypeId::of::<Self>()).expect("Uninitialised Class");
 		unsafe {
 			JS_NewObjectWithGivenProto(
@@ -191,7 +211,7 @@ pub trait ClassDefinition: NativeObject {
 	}
 
 	fn has_instance(cx: &Context, object: &Object) -> Result<bool> {
-		let infos = unsafe { &mut (*cx.get_inner_data().as_ptr()).class_infos };
+		let infos = unsafe { cx.class_infos() };
 		let constructor =
 			Function::from(cx.root(infos.get(&TypeId::of::<Self>()).expect("Uninitialised Class").constructor.get()))
 				.to_object(cx);
@@ -285,6 +305,35 @@ pub unsafe extern "C" fn trace_native_object_operation<T: Traceable>(trc: *mut J
 	}
 }
 
+/// Creates the object for a native constructor with the prototype of `new.target`, so that JS classes which extend a
+/// native class construct objects with the prototype of the subclass.
+///
+/// Falls back to the prototype of the native class if `new.target.prototype` is not an object.
+#[doc(hidden)]
+pub fn __new_object_for_constructor<'cx, T: ClassDefinition>(
+	cx: &'cx Context, args: &Arguments<'cx>,
+) -> Result<Object<'cx>> {
+	let Some(new_target) = args.new_target() else {
+		return Err(Error::new("Constructor requires 'new'", ErrorKind::Type));
+	};
+
+	let prototype = match new_target.get(cx, "prototype")? {
+		Some(prototype) if prototype.handle().is_object() => prototype.to_object(cx),
+		_ => {
+			let infos = unsafe { cx.class_infos() };
+			let info = infos.get(&TypeId::of::<T>()).expect("Uninitialised Class");
+			cx.root(info.prototype.get()).into()
+		}
+	};
+
+	let object = unsafe { JS_NewObjectWithGivenProto(cx.as_ptr(), &T::class().base, prototype.handle().into()) };
+	if object.is_null() {
+		Err(Error::none())
+	} else {
+		Ok(cx.root(object).into())
+	}
+}
+
 unsafe extern "C" fn illegal_constructor(cx: *mut JSContext, _: u32, _: *mut JSVal) -> bool {
 	throw_type_error(cx, "Illegal constructor.");
 	false
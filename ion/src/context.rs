@@ -8,21 +8,32 @@ use std::any::{Any, TypeId};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::ffi::c_void;
-use std::ptr;
+use std::future::Future;
+use std::mem::ManuallyDrop;
+use std::pin::Pin;
 use std::ptr::NonNull;
+use std::rc::{Rc, Weak};
+use std::task::Poll;
+use std::{ptr, task};
 
 use mozjs::gc::Traceable;
 use mozjs::jsapi::{
-	JSContext, JSTracer, JS_AddExtraGCRootsTracer, JS_GetContextPrivate, JS_RemoveExtraGCRootsTracer,
-	JS_SetContextPrivate, Rooted,
+	GetCurrentRealmOrNull, JSContext, JSString, JSTracer, JS_AddExtraGCRootsTracer, JS_AtomizeAndPinStringN,
+	JS_GetContextPrivate, JS_RemoveExtraGCRootsTracer, JS_SetContextPrivate, Realm, Rooted,
 };
 use mozjs::rust::Runtime;
 use private::RootedArena;
 
 use crate::class::ClassInfo;
+use crate::job_queue::JobQueue;
 use crate::module::ModuleLoader;
 use crate::Local;
 
+/// Maximum number of property names which are interned, as pinned atoms are never collected.
+const MAX_INTERNED_ATOMS: usize = 1024;
+/// Maximum length of property names which are interned.
+const MAX_INTERNED_LENGTH: usize = 64;
+
 /// Represents Types that can be Rooted in SpiderMonkey
 #[derive(Clone, Copy, Debug)]
 pub enum GCType {
@@ -55,9 +66,16 @@ impl<T: Traceable + Any> TraceablePrivate for T {
 
 #[derive(Default)]
 pub struct ContextInner {
-	pub class_infos: HashMap<TypeId, ClassInfo>,
+	/// Information about the native classes initialised in each realm.
+	pub class_infos: HashMap<*mut Realm, HashMap<TypeId, ClassInfo>>,
 	pub module_loader: Option<Box<dyn ModuleLoader>>,
+	/// Pinned atoms of frequently used property names, which do not need to be traced.
+	atoms: HashMap<Box<str>, *mut JSString>,
 	private: Option<Box<dyn TraceablePrivate>>,
+	/// Job queue installed by [JobQueue::install], whose jobs are rooted by themselves.
+	pub(crate) job_queue: Option<Box<JobQueue>>,
+	/// Marker whose weak references are held by [ContextTokens](ContextToken), which expire when this is dropped.
+	alive: Rc<()>,
 }
 
 impl ContextInner {
@@ -76,7 +94,7 @@ impl ContextInner {
 	extern "C" fn trace(trc: *mut JSTracer, data: *mut c_void) {
 		unsafe {
 			let inner = &mut *data.cast::<ContextInner>();
-			for info in inner.class_infos.values() {
+			for info in inner.class_infos.values().flat_map(HashMap::values) {
 				info.trace(trc);
 			}
 			inner.private.trace(trc);
@@ -145,6 +163,64 @@ impl Context {
 		}
 	}
 
+	/// Returns the information about the native classes initialised in the current realm.
+	#[expect(clippy::mut_from_ref)]
+	pub unsafe fn class_infos(&self) -> &mut HashMap<TypeId, ClassInfo> {
+		let realm = unsafe { GetCurrentRealmOrNull(self.as_ptr()) };
+		unsafe { (*self.get_inner_data().as_ptr()).class_infos.entry(realm).or_default() }
+	}
+
+	/// Removes the information about the native classes initialised in a realm.
+	///
+	/// The prototypes of the classes keep the global of the realm alive until they are removed.
+	/// Objects of these classes must not be created in the realm afterwards.
+	pub unsafe fn remove_class_infos(&self, realm: *mut Realm) {
+		unsafe { (*self.get_inner_data().as_ptr()).class_infos.remove(&realm) };
+	}
+
+	/// Returns the interned atom for an ASCII property name, atomising and pinning it on first use.
+	///
+	/// Returns [None] for names which are long or not ASCII, or once the cache is full, so that arbitrary keys do not
+	/// pin an unbounded number of atoms.
+	pub(crate) fn intern(&self, name: &str) -> Option<*mut JSString> {
+		let atoms = unsafe { &mut (*self.get_inner_data().as_ptr()).atoms };
+		if let Some(atom) = atoms.get(name) {
+			return Some(*atom);
+		}
+		if name.len() > MAX_INTERNED_LENGTH || !name.is_ascii() || atoms.len() >= MAX_INTERNED_ATOMS {
+			return None;
+		}
+
+		let atom = unsafe { JS_AtomizeAndPinStringN(self.as_ptr(), name.as_ptr().cast(), name.len()) };
+		if atom.is_null() {
+			return None;
+		}
+		atoms.insert(Box::from(name), atom);
+		Some(atom)
+	}
+
+	/// Returns a [ContextToken] which can be held across await points, and checks that the runtime of this context is
+	/// still alive before it is used.
+	pub fn token(&self) -> ContextToken {
+		let alive = unsafe { &(*self.get_inner_data().as_ptr()).alive };
+		ContextToken {
+			context: self.context,
+			alive: Rc::downgrade(alive),
+		}
+	}
+
+	/// Calls `f` with a [Context] whose rooted values are unrooted once `f` returns, rather than when `self` is dropped.
+	///
+	/// This bounds the number of values rooted by loops, such as those draining iterators or formatting elements.
+	///
+	/// ### Safety
+	/// No values may be rooted with `self`, or any other [Context] created before the scope, within `f`.
+	/// Values are unrooted in the reverse order of rooting, so they would be unrooted while still in use.
+	pub unsafe fn scope<R>(&self, f: impl for<'s> FnOnce(&'s Context) -> R) -> R {
+		let scope = unsafe { Context::new_unchecked(self.as_ptr()) };
+		f(&scope)
+	}
+
 	/// Roots a value and returns a `[Local]` to it.
 	/// The Local is only unrooted when the `[Context]` is dropped
 	pub fn root<T: Rootable>(&self, value: T) -> Local<T> {
@@ -154,6 +230,75 @@ impl Context {
 	}
 }
 
+/// Checked handle to a [Context], for use in futures which may outlive the runtime they were created in.
+///
+/// This replaces [Context::new_unchecked] in async code, which would otherwise read the state of the runtime after it
+/// was freed if the future were polled after the runtime was dropped. Futures which hold the upgraded [Context] across
+/// await points must be wrapped with [guard](ContextToken::guard).
+#[derive(Clone, Debug)]
+pub struct ContextToken {
+	context: NonNull<JSContext>,
+	alive: Weak<()>,
+}
+
+impl ContextToken {
+	/// Checks if the [ContextInner] of the context has not been freed, such as when its runtime is dropped.
+	pub fn is_alive(&self) -> bool {
+		self.alive.strong_count() > 0
+	}
+
+	/// Creates a new [Context], with its own rooted values, if the runtime is still alive.
+	pub fn upgrade(&self) -> Option<Context> {
+		self.is_alive().then(|| unsafe { Context::new_unchecked(self.context.as_ptr()) })
+	}
+
+	/// Creates a new [Context], with its own rooted values.
+	///
+	/// ### Panics
+	/// Panics if the runtime has been dropped.
+	pub fn context(&self) -> Context {
+		self.upgrade().expect("Context was used after its runtime was dropped")
+	}
+
+	/// Wraps a future which holds a [Context] across await points, such that it is only polled while the runtime is
+	/// alive.
+	///
+	/// If the runtime is dropped before the future completes, the future is never polled again, and is leaked rather
+	/// than dropped, as dropping it would unroot its values in the freed runtime.
+	pub fn guard<F: Future>(&self, future: F) -> Guarded<F> {
+		Guarded {
+			token: self.clone(),
+			future: ManuallyDrop::new(Box::pin(future)),
+		}
+	}
+}
+
+/// Future returned by [ContextToken::guard].
+pub struct Guarded<F: Future> {
+	token: ContextToken,
+	future: ManuallyDrop<Pin<Box<F>>>,
+}
+
+impl<F: Future> Future for Guarded<F> {
+	type Output = F::Output;
+
+	fn poll(mut self: Pin<&mut Self>, wcx: &mut task::Context) -> Poll<F::Output> {
+		if self.token.is_alive() {
+			self.future.as_mut().poll(wcx)
+		} else {
+			Poll::Pending
+		}
+	}
+}
+
+impl<F: Future> Drop for Guarded<F> {
+	fn drop(&mut self) {
+		if self.token.is_alive() {
+			unsafe { ManuallyDrop::drop(&mut self.future) };
+		}
+	}
+}
+
 pub trait Rootable: private::Sealed {}
 
 impl<T: private::Sealed> Rootable for T {}
@@ -54,13 +54,17 @@ impl<'cx> ToPropertyKey<'cx> for String<'cx> {
 
 impl<'cx> ToPropertyKey<'cx> for RustString {
 	fn to_key(&self, cx: &'cx Context) -> Option<PropertyKey<'cx>> {
-		String::copy_from_str(cx, self)?.to_key(cx)
+		self.as_str().to_key(cx)
 	}
 }
 
+/// Short ASCII names are looked up in the interned atoms of the [Context], rather than being copied each time.
 impl<'cx> ToPropertyKey<'cx> for &str {
 	fn to_key(&self, cx: &'cx Context) -> Option<PropertyKey<'cx>> {
-		String::copy_from_str(cx, self)?.to_key(cx)
+		match cx.intern(self) {
+			Some(atom) => atom.to_key(cx),
+			None => String::copy_from_str(cx, self)?.to_key(cx),
+		}
 	}
 }
 
@@ -111,7 +115,7 @@ impl<'cx> ToPropertyKey<'cx> for OwnedKey<'cx> {
 	fn to_key(&self, cx: &'cx Context) -> Option<PropertyKey<'cx>> {
 		match self {
 			OwnedKey::Int(i) => i.to_key(cx),
-			OwnedKey::String(str) => str.to_key(cx),
+			OwnedKey::String(str) => str.as_str().to_key(cx),
 			OwnedKey::Symbol(symbol) => symbol.to_key(cx),
 			OwnedKey::Void => Some(cx.root(VoidId()).into()),
 		}
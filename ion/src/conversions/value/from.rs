@@ -17,9 +17,11 @@ use mozjs::typedarray::JSObjectStorage;
 
 use crate::object::RegExp;
 use crate::string::byte::{BytePredicate, ByteString};
+use crate::string::CompactString;
 use crate::typedarray::{ArrayBuffer, TypedArray, TypedArrayElement};
 use crate::{
-	Array, Context, Date, Error, ErrorKind, Exception, Function, Object, Promise, Result, StringRef, Symbol, Value,
+	Array, Context, Date, Error, ErrorKind, Exception, Function, Local, Object, Promise, Result, StringRef, Symbol,
+	Value,
 };
 
 /// Represents types that can be converted to from [JavaScript Values](Value).
@@ -32,6 +34,38 @@ pub trait FromValue<'cx>: Sized {
 	fn from_value(cx: &'cx Context, value: &Value, strict: bool, config: Self::Config) -> Result<Self>;
 }
 
+/// Collects the error from converting the field at `key` of a struct deriving [FromValue], so that the errors of all
+/// its fields can be reported together.
+///
+/// Returns [Err] immediately if an exception is pending, as no further conversions can be performed.
+#[doc(hidden)]
+pub fn __collect_member_error<T>(
+	cx: &Context, key: &'static str, result: Result<T>, errors: &mut Vec<(&'static str, Error)>,
+) -> Result<Option<T>> {
+	match result {
+		Ok(value) => Ok(Some(value)),
+		Err(error) if error.kind == ErrorKind::None || Exception::is_pending(cx) => Err(error),
+		Err(error) => {
+			errors.push((key, error));
+			Ok(None)
+		}
+	}
+}
+
+/// Combines the errors from converting the fields of a struct deriving [FromValue] into one error listing all of them.
+#[doc(hidden)]
+pub fn __member_errors(name: &str, mut errors: Vec<(&'static str, Error)>) -> Error {
+	if errors.len() == 1 {
+		return errors.pop().unwrap().1;
+	}
+
+	let messages: Vec<_> = errors.iter().map(|(key, error)| format!("{key}: {}", error.message)).collect();
+	Error::new(
+		format!("{} invalid members in {name}: {}", errors.len(), messages.join("; ")),
+		ErrorKind::Type,
+	)
+}
+
 impl<'cx> FromValue<'cx> for bool {
 	type Config = ();
 
@@ -164,11 +198,35 @@ impl<'cx, T: BytePredicate> FromValue<'cx> for ByteString<T> {
 	}
 }
 
+/// Converts a value to a [String](crate::String) and passes it to `f`.
+///
+/// Linear strings are not rooted again, as `value` keeps them alive, and no GC can occur while their characters are
+/// read.
+fn with_string<T>(cx: &Context, value: &Value, strict: bool, f: impl FnOnce(&crate::String) -> Result<T>) -> Result<T> {
+	let handle = value.handle();
+	if handle.is_string() {
+		let string = handle.to_string();
+		let string = crate::String::from(unsafe { Local::from_marked(&string) });
+		if string.is_linear() {
+			return f(&string);
+		}
+	}
+	f(&crate::String::from_value(cx, value, strict, ())?)
+}
+
 impl<'cx> FromValue<'cx> for String {
 	type Config = ();
 
-	fn from_value(cx: &'cx Context, value: &Value, strict: bool, config: ()) -> Result<String> {
-		crate::String::from_value(cx, value, strict, config).and_then(|s| s.to_owned(cx))
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<String> {
+		with_string(cx, value, strict, |string| string.to_owned(cx))
+	}
+}
+
+impl<'cx> FromValue<'cx> for CompactString {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<CompactString> {
+		with_string(cx, value, strict, |string| CompactString::from_string(cx, string))
 	}
 }
 
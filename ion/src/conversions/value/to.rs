@@ -13,16 +13,17 @@ use mozjs::jsapi::{
 	PropertyKey as JSPropertyKey, Symbol as JSSymbol,
 };
 use mozjs::jsval::{
-	BooleanValue, DoubleValue, Int32Value, JSVal, NullValue, ObjectOrNullValue, ObjectValue, StringValue, SymbolValue,
-	UInt32Value, UndefinedValue,
+	BigIntValue, BooleanValue, DoubleValue, Int32Value, JSVal, NullValue, ObjectOrNullValue, ObjectValue, StringValue,
+	SymbolValue, UInt32Value, UndefinedValue,
 };
 use mozjs::rust::{maybe_wrap_object_or_null_value, maybe_wrap_object_value, maybe_wrap_value};
 use mozjs::typedarray as jsta;
 
 use crate::object::RegExp;
 use crate::string::byte::{BytePredicate, ByteStr, ByteString};
+use crate::string::CompactString;
 use crate::typedarray::{ArrayBuffer, TypedArray, TypedArrayElement};
-use crate::{Array, Context, Date, Function, Object, Promise, PropertyKey, Symbol, Value};
+use crate::{Array, BigInt, Context, Date, Function, Object, Promise, PropertyKey, Symbol, Value};
 
 /// Represents types that can be converted to JavaScript [Values](Value).
 pub trait ToValue<'cx> {
@@ -121,6 +122,12 @@ impl<'cx> ToValue<'cx> for String {
 	}
 }
 
+impl<'cx> ToValue<'cx> for CompactString {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		self.as_str().to_value(cx, value);
+	}
+}
+
 impl<'cx, T: ToOwned + ToValue<'cx>> ToValue<'cx> for Cow<'_, T> {
 	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
 		self.as_ref().to_value(cx, value)
@@ -217,6 +224,12 @@ impl<'cx> ToValue<'cx> for Function<'cx> {
 	}
 }
 
+impl<'cx> ToValue<'cx> for BigInt<'cx> {
+	fn to_value(&self, _: &'cx Context, value: &mut Value) {
+		value.handle_mut().set(BigIntValue(unsafe { &*self.get() }));
+	}
+}
+
 impl ToValue<'_> for *mut JSSymbol {
 	fn to_value(&self, _: &Context, value: &mut Value) {
 		value.handle_mut().set(SymbolValue(unsafe { &**self }));
@@ -287,13 +300,7 @@ impl<'cx, T: ToValue<'cx>> ToValue<'cx> for Option<T> {
 
 impl<'cx, T: ToValue<'cx>> ToValue<'cx> for [T] {
 	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
-		let array = Array::new_with_length(cx, self.len());
-
-		for (i, t) in self.iter().enumerate() {
-			assert!(array.set_as(cx, i as u32, t));
-		}
-
-		array.to_value(cx, value);
+		Array::from_slice(cx, self).to_value(cx, value);
 	}
 }
 
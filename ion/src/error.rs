@@ -13,6 +13,7 @@ use mozjs::jsapi::{CreateError, JSExnType, JSObject, JSProtoKey, JS_ReportErrorU
 
 use crate::conversions::ToValue;
 use crate::exception::ThrowException;
+use crate::format::NEWLINE;
 use crate::stack::Location;
 use crate::{Context, ErrorReport, Exception, Object, Stack, Value};
 
@@ -105,13 +106,17 @@ impl Display for ErrorKind {
 /// Represents errors in the JS Runtime
 /// Contains information about the type of error, the error message and the error location.
 ///
-/// If created from an error object, it also contains the error object.
+/// If created from an error object, it also contains the error object, and the stack it was thrown with.
+/// The stack is resolved into its records when the error is created, as the stack object is not rooted.
+/// Notes can be added to give context to the error, such as the operation which failed, as it is propagated.
 #[derive(Clone, Debug)]
 pub struct Error {
 	pub kind: ErrorKind,
 	pub message: Cow<'static, str>,
 	pub location: Option<Location>,
 	pub object: Option<*mut JSObject>,
+	pub stack: Option<Stack>,
+	pub notes: Vec<Cow<'static, str>>,
 }
 
 impl Error {
@@ -121,6 +126,8 @@ impl Error {
 			message: message.into(),
 			location: None,
 			object: None,
+			stack: None,
+			notes: Vec::new(),
 		}
 	}
 
@@ -130,9 +137,17 @@ impl Error {
 			message: Cow::Borrowed(""),
 			location: None,
 			object: None,
+			stack: None,
+			notes: Vec::new(),
 		}
 	}
 
+	/// Adds a note to the [Error], which is shown after its message when it is formatted.
+	pub fn note<N: Into<Cow<'static, str>>>(mut self, note: N) -> Error {
+		self.notes.push(note.into());
+		self
+	}
+
 	pub fn to_object<'cx>(&self, cx: &'cx Context) -> Option<Object<'cx>> {
 		if let Some(object) = self.object {
 			return Some(cx.root(object).into());
@@ -141,8 +156,12 @@ impl Error {
 			unsafe {
 				let exception_type = self.kind.to_exception_type();
 
+				// Errors which were thrown from JS keep the location they were thrown from.
 				let stack = Stack::from_capture(cx).unwrap();
-				let (file, lineno, column) = stack
+				let (file, lineno, column) = self
+					.stack
+					.as_ref()
+					.unwrap_or(&stack)
 					.records
 					.first()
 					.map(|record| &record.location)
@@ -183,6 +202,15 @@ impl Error {
 	}
 
 	pub fn format(&self) -> String {
+		let mut string = self.format_message();
+		for note in &self.notes {
+			string.push_str(NEWLINE);
+			string.push_str(&format!("Note: {note}"));
+		}
+		string
+	}
+
+	fn format_message(&self) -> String {
 		let Error { kind, message, location, .. } = self;
 		let message = (!message.is_empty()).then(|| format!(" - {}", message)).unwrap_or(String::new());
 		if let Some(location) = location {
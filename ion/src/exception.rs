@@ -7,8 +7,8 @@
 use mozjs::conversions::ConversionBehavior;
 use mozjs::jsapi::{
 	ESClass, ExceptionStack, ExceptionStackBehavior, ExceptionStackOrNull, GetPendingExceptionStack,
-	IdentifyStandardInstance, JS_ClearPendingException, JS_GetPendingException, JS_IsExceptionPending,
-	JS_SetPendingException, Rooted,
+	IdentifyStandardInstance, JSObject, JS_ClearPendingException, JS_IsExceptionPending, JS_SetPendingException,
+	Rooted,
 };
 use mozjs::jsval::{JSVal, ObjectValue};
 #[cfg(feature = "sourcemap")]
@@ -33,21 +33,19 @@ pub enum Exception {
 
 impl Exception {
 	/// Gets an [Exception] from the runtime and clears the pending exception.
+	/// If the exception is an [Error], the stack it was thrown with is kept as its [stack](Error::stack).
 	/// Returns [None] if there is no pending exception.
 	pub fn new(cx: &Context) -> Result<Option<Exception>> {
-		unsafe {
-			if JS_IsExceptionPending(cx.as_ptr()) {
-				let mut exception = Value::undefined(cx);
-				if JS_GetPendingException(cx.as_ptr(), exception.handle_mut().into()) {
-					let exception = Exception::from_value(cx, &exception)?;
-					Exception::clear(cx);
-					Ok(Some(exception))
-				} else {
-					Ok(None)
-				}
-			} else {
-				Ok(None)
-			}
+		Ok(take_pending_exception(cx)?.map(|(exception, _)| exception))
+	}
+
+	/// Returns the [Stack] which the [Error] was thrown with, or the stack its error object was created with.
+	/// Returns [None] if the exception is not an [Error], or it has no stack.
+	pub fn stack(&self, cx: &Context) -> Option<Stack> {
+		match self {
+			Exception::Error(Error { stack: Some(stack), .. }) => Some(stack.clone()),
+			Exception::Error(Error { object: Some(object), .. }) => error_stack(cx, *object),
+			_ => None,
 		}
 	}
 
@@ -79,6 +77,8 @@ impl Exception {
 					message: message.into(),
 					location: Some(location),
 					object: Some(handle.get()),
+					stack: None,
+					notes: Vec::new(),
 				};
 				Ok(Exception::Error(error))
 			} else {
@@ -194,26 +194,10 @@ impl ErrorReport {
 	/// Creates a new [ErrorReport] with an [Exception] and exception stack from the runtime.
 	/// Returns [None] if there is no pending exception.
 	pub fn new_with_exception_stack(cx: &Context) -> Result<Option<ErrorReport>> {
-		unsafe {
-			if JS_IsExceptionPending(cx.as_ptr()) {
-				let mut exception_stack = ExceptionStack {
-					exception_: Rooted::new_unrooted(),
-					stack_: Rooted::new_unrooted(),
-				};
-
-				if GetPendingExceptionStack(cx.as_ptr(), &mut exception_stack) {
-					let exception = Value::from(cx.root(exception_stack.exception_.ptr.assume_init()));
-					let exception = Exception::from_value(cx, &exception)?;
-					let stack = Stack::from_object(cx, exception_stack.stack_.ptr.assume_init());
-					Exception::clear(cx);
-					Ok(Some(ErrorReport { exception, stack }))
-				} else {
-					Ok(None)
-				}
-			} else {
-				Ok(None)
-			}
-		}
+		Ok(take_pending_exception(cx)?.map(|(exception, stack)| {
+			let stack = (!stack.is_null()).then(|| Stack::from_object(cx, stack)).flatten();
+			ErrorReport { exception, stack }
+		}))
 	}
 
 	/// Creates an [ErrorReport] from an existing [Exception] and optionally a [Stack].
@@ -223,17 +207,24 @@ impl ErrorReport {
 
 	/// Creates an [ErrorReport] from an existing [Exception], with the [Error]'s exception stack.
 	pub fn from_exception_with_error_stack(cx: &Context, exception: Exception) -> ErrorReport {
-		let stack = if let Exception::Error(Error { object: Some(object), .. }) = exception {
-			unsafe {
-				rooted!(in(cx.as_ptr()) let exc = object);
-				Stack::from_object(cx, ExceptionStackOrNull(exc.handle().into()))
-			}
-		} else {
-			None
-		};
+		let stack = exception.stack(cx);
 		ErrorReport { exception, stack }
 	}
 
+	/// Converts the [ErrorReport] into its [Exception], keeping the [Stack] as the stack of an [Error], so that it is
+	/// not lost when the exception is returned as part of a [ResultExc](crate::ResultExc).
+	pub fn into_exception(self) -> Exception {
+		match self.exception {
+			Exception::Error(mut error) => {
+				if error.stack.is_none() {
+					error.stack = self.stack.map(|stack| Stack { object: None, ..stack });
+				}
+				Exception::Error(error)
+			}
+			exception => exception,
+		}
+	}
+
 	/// Transforms the location of the [Exception] and the [Stack] if it exists, according to the given [SourceMap].
 	#[cfg(feature = "sourcemap")]
 	pub fn transform_with_sourcemap(&mut self, sourcemap: &SourceMap) {
@@ -255,3 +246,47 @@ impl ErrorReport {
 		string
 	}
 }
+
+/// Takes the pending exception and the stack it was thrown with, and clears it.
+/// If an [Error] was thrown without a stack, the stack its error object was created with is used instead.
+fn take_pending_exception(cx: &Context) -> Result<Option<(Exception, *mut JSObject)>> {
+	unsafe {
+		if !JS_IsExceptionPending(cx.as_ptr()) {
+			return Ok(None);
+		}
+
+		let mut exception_stack = ExceptionStack {
+			exception_: Rooted::new_unrooted(),
+			stack_: Rooted::new_unrooted(),
+		};
+		if !GetPendingExceptionStack(cx.as_ptr(), &mut exception_stack) {
+			return Ok(None);
+		}
+
+		let exception = Value::from(cx.root(exception_stack.exception_.ptr.assume_init()));
+		let mut exception = Exception::from_value(cx, &exception)?;
+		let mut stack = exception_stack.stack_.ptr.assume_init();
+		if let Exception::Error(error) = &mut exception {
+			if stack.is_null() {
+				if let Some(object) = error.object {
+					rooted!(in(cx.as_ptr()) let object = object);
+					stack = ExceptionStackOrNull(object.handle().into());
+				}
+			}
+			error.stack = (!stack.is_null())
+				.then(|| Stack::from_object(cx, stack))
+				.flatten()
+				.map(|stack| Stack { object: None, ..stack });
+		}
+		Exception::clear(cx);
+		Ok(Some((exception, stack)))
+	}
+}
+
+fn error_stack(cx: &Context, object: *mut JSObject) -> Option<Stack> {
+	unsafe {
+		rooted!(in(cx.as_ptr()) let object = object);
+		let stack = ExceptionStackOrNull(object.handle().into());
+		(!stack.is_null()).then(|| Stack::from_object(cx, stack)).flatten()
+	}
+}
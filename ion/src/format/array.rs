@@ -40,7 +40,7 @@ impl Display for ArrayDisplay<'_> {
 			JSProtoKey::JSProto_Array,
 		)?;
 
-		if self.cfg.depth < 5 {
+		if self.cfg.depth <= self.cfg.max_depth {
 			let length = self.array.len(self.cx);
 
 			if length == 0 {
@@ -56,8 +56,13 @@ impl Display for ArrayDisplay<'_> {
 
 					for index in 0..len {
 						inner.fmt(f)?;
-						let desc = self.array.get_descriptor(self.cx, index)?.unwrap();
-						format_descriptor(self.cx, self.cfg, &desc, Some(self.array.as_object())).fmt(f)?;
+						// SAFETY: Values are only rooted with the scoped Context, and none outlive the closure.
+						unsafe {
+							self.cx.scope(|cx| {
+								let desc = self.array.get_descriptor(cx, index)?.unwrap();
+								format_descriptor(cx, self.cfg, &desc, Some(self.array.as_object())).fmt(f)
+							})
+						}?;
 						",".color(colour).fmt(f)?;
 						f.write_str(NEWLINE)?;
 					}
@@ -68,8 +73,13 @@ impl Display for ArrayDisplay<'_> {
 					let len = length.clamp(0, 3);
 
 					for index in 0..len {
-						let desc = self.array.get_descriptor(self.cx, index)?.unwrap();
-						format_descriptor(self.cx, self.cfg, &desc, Some(self.array.as_object())).fmt(f)?;
+						// SAFETY: Values are only rooted with the scoped Context, and none outlive the closure.
+						unsafe {
+							self.cx.scope(|cx| {
+								let desc = self.array.get_descriptor(cx, index)?.unwrap();
+								format_descriptor(cx, self.cfg, &desc, Some(self.array.as_object())).fmt(f)
+							})
+						}?;
 
 						if index != len - 1 {
 							",".color(colour).fmt(f)?;
@@ -74,6 +74,8 @@ pub struct Config {
 	pub colours: ColourConfig,
 	pub iteration: IteratorFlags,
 	pub depth: u16,
+	/// Depth at which nested objects are summarised, such as `[Object]`, rather than formatted.
+	pub max_depth: u16,
 	pub indentation: u16,
 	pub multiline: bool,
 	pub quoted: bool,
@@ -93,6 +95,10 @@ impl Config {
 		Config { depth, ..self }
 	}
 
+	pub fn max_depth(self, max_depth: u16) -> Config {
+		Config { max_depth, ..self }
+	}
+
 	pub fn indentation(self, indentation: u16) -> Config {
 		Config { indentation, ..self }
 	}
@@ -112,6 +118,7 @@ impl Default for Config {
 			colours: ColourConfig::default(),
 			iteration: IteratorFlags::default(),
 			depth: 0,
+			max_depth: 4,
 			indentation: 0,
 			multiline: true,
 			quoted: false,
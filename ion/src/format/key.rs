@@ -34,7 +34,12 @@ impl Display for KeyDisplay<'_> {
 				let mut buffer = Buffer::new();
 				buffer.format(*i).color(colours.number).fmt(f)
 			}
-			OwnedKey::String(str) => write!(f, "{0}{1}{0}", r#"""#.color(colours.string), str.color(colours.string)),
+			OwnedKey::String(str) => write!(
+				f,
+				"{0}{1}{0}",
+				r#"""#.color(colours.string),
+				str.as_str().color(colours.string)
+			),
 			OwnedKey::Symbol(sym) => {
 				"[".color(colours.symbol).fmt(f)?;
 				format_symbol(self.cx, self.cfg, sym).fmt(f)?;
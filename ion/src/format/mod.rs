@@ -5,14 +5,15 @@
  */
 
 use std::borrow::Cow;
-use std::fmt::{Display, Formatter};
+use std::fmt::{Display, Formatter, Write};
 use std::{fmt, str};
 
-pub use config::Config;
+pub use config::{ColourConfig, Config};
 
 use crate::format::object::format_object;
 use crate::format::primitive::format_primitive;
-use crate::{Context, Value};
+use crate::string::StringBuilder;
+use crate::{Context, Result, Value};
 
 pub mod array;
 pub mod boxed;
@@ -59,6 +60,28 @@ pub struct ValueDisplay<'cx> {
 	cfg: Config,
 }
 
+impl<'cx> ValueDisplay<'cx> {
+	/// Formats the value into a [String](crate::String), without first formatting it into a Rust [String].
+	pub fn to_js_string(&self) -> Result<crate::String<'cx>> {
+		let mut builder = StringBuilder::new();
+		self.write_to(&mut builder)?;
+		builder.finish(self.cx)
+	}
+
+	/// Formats the value into a [StringBuilder].
+	///
+	/// Unquoted strings are appended as they are, without being converted to UTF-8.
+	pub fn write_to(&self, builder: &mut StringBuilder) -> Result<()> {
+		if self.value.handle().is_string() && !self.cfg.quoted {
+			let string = crate::String::from(self.cx.root(self.value.handle().to_string()));
+			builder.push_string(self.cx, &string);
+		} else {
+			write!(builder, "{self}")?;
+		}
+		Ok(())
+	}
+}
+
 impl Display for ValueDisplay<'_> {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
 		if self.value.handle().is_object() {
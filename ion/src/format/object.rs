@@ -123,7 +123,7 @@ impl Display for RawObjectDisplay<'_> {
 
 		write_prefix(f, self.cx, self.cfg, self.object, "Object", JSProtoKey::JSProto_Object)?;
 
-		if self.cfg.depth < 4 {
+		if self.cfg.depth < self.cfg.max_depth {
 			let keys = self.object.keys(self.cx, Some(self.cfg.iteration));
 			let length = keys.len();
 
@@ -138,8 +138,13 @@ impl Display for RawObjectDisplay<'_> {
 
 					for key in keys {
 						inner.fmt(f)?;
-						let desc = self.object.get_descriptor(self.cx, &key)?.unwrap();
-						write_key_descriptor(f, self.cx, self.cfg, &key, &desc, Some(self.object))?;
+						// SAFETY: Values are only rooted with the scoped Context, and none outlive the closure.
+						unsafe {
+							self.cx.scope(|cx| {
+								let desc = self.object.get_descriptor(cx, &key)?.unwrap();
+								write_key_descriptor(f, cx, self.cfg, &key, &desc, Some(self.object))
+							})
+						}?;
 						",".color(colour).fmt(f)?;
 						f.write_str(NEWLINE)?;
 					}
@@ -150,8 +155,13 @@ impl Display for RawObjectDisplay<'_> {
 					let len = length.clamp(0, 3);
 
 					for (i, key) in keys.enumerate() {
-						let desc = self.object.get_descriptor(self.cx, &key)?.unwrap();
-						write_key_descriptor(f, self.cx, self.cfg, &key, &desc, Some(self.object))?;
+						// SAFETY: Values are only rooted with the scoped Context, and none outlive the closure.
+						unsafe {
+							self.cx.scope(|cx| {
+								let desc = self.object.get_descriptor(cx, &key)?.unwrap();
+								write_key_descriptor(f, cx, self.cfg, &key, &desc, Some(self.object))
+							})
+						}?;
 
 						if i != len - 1 {
 							",".color(colour).fmt(f)?;
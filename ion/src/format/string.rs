@@ -4,16 +4,19 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::{fmt, str};
 
-use colored::Colorize;
-use encoding_rs::UTF_8;
+use colored::{Color, Colorize};
+use encoding_rs::mem::{convert_latin1_to_utf8, convert_utf16_to_utf8, utf16_valid_up_to};
 use mozjs::jsval::StringValue;
 
 use crate::format::Config;
 use crate::{Context, Local, Value};
 
+/// Number of characters converted to UTF-8 at a time, so that formatting does not copy the whole string.
+const CHUNK_LENGTH: usize = 1024;
+
 pub fn format_string<'cx>(cx: &'cx Context, cfg: Config, string: &'cx crate::String<'cx>) -> StringDisplay<'cx> {
 	StringDisplay { cx, string, cfg }
 }
@@ -29,28 +32,53 @@ impl Display for StringDisplay<'_> {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
 		let colour = self.cfg.colours.string;
 		if self.cfg.quoted {
-			match self.string.to_owned(self.cx) {
-				Ok(str) => write!(f, "{0}{1}{0}", r#"""#.color(colour), str.color(colour)),
-				Err(_) => {
-					let value = StringValue(unsafe { &*self.string.get() });
-					let value = Value::from(unsafe { Local::from_marked(&value) });
-					let str = value.to_source(self.cx).to_owned(self.cx).unwrap();
-					str.color(colour).fmt(f)
-				}
+			let valid = self
+				.string
+				.as_wtf16(self.cx)
+				.map_or(true, |units| utf16_valid_up_to(units) == units.len());
+			if valid {
+				r#"""#.color(colour).fmt(f)?;
+				write_chunks(self.cx, self.string, f, Some(colour))?;
+				r#"""#.color(colour).fmt(f)
+			} else {
+				let value = StringValue(unsafe { &*self.string.get() });
+				let value = Value::from(unsafe { Local::from_marked(&value) });
+				write_chunks(self.cx, &value.to_source(self.cx), f, Some(colour))
 			}
 		} else {
-			match self.string.to_owned(self.cx) {
-				Ok(str) => str.fmt(f),
-				Err(_) => {
-					let input = self.string.as_wtf16(self.cx).unwrap();
-					let mut encoder = UTF_8.new_encoder();
-					let buf_len = encoder.max_buffer_length_from_utf16_if_no_unmappables(input.len()).unwrap();
-					let mut buf = Vec::with_capacity(buf_len);
-					let (_, _, _, _) = encoder.encode_from_utf16(input, &mut buf, true);
-					let str = unsafe { String::from_utf8_unchecked(buf) };
-					str.fmt(f)
-				}
+			write_chunks(self.cx, self.string, f, None)
+		}
+	}
+}
+
+/// Writes the characters of a string in chunks, replacing unpaired surrogates with the replacement character.
+fn write_chunks(cx: &Context, string: &crate::String, f: &mut Formatter, colour: Option<Color>) -> fmt::Result {
+	let mut buffer = [0; CHUNK_LENGTH * 3];
+	let mut write = |bytes: &[u8]| {
+		let chunk = unsafe { str::from_utf8_unchecked(bytes) };
+		match colour {
+			Some(colour) => chunk.color(colour).fmt(f),
+			None => f.write_str(chunk),
+		}
+	};
+
+	if let Some(chars) = string.as_latin1(cx) {
+		for chars in chars.chunks(CHUNK_LENGTH) {
+			let len = convert_latin1_to_utf8(chars, &mut buffer);
+			write(&buffer[..len])?;
+		}
+	} else {
+		let mut units = string.as_wtf16(cx).unwrap();
+		while !units.is_empty() {
+			let mut len = units.len().min(CHUNK_LENGTH);
+			// Surrogate pairs are not split between chunks.
+			if len < units.len() && (0xD800..0xDC00).contains(&units[len - 1]) {
+				len -= 1;
 			}
+			let written = convert_utf16_to_utf8(&units[..len], &mut buffer);
+			write(&buffer[..written])?;
+			units = &units[len..];
 		}
 	}
+	Ok(())
 }
@@ -8,37 +8,33 @@ use mozjs::jsapi::CallArgs;
 use mozjs::jsval::JSVal;
 
 use crate::conversions::FromValue;
-use crate::function::{Opt, Rest};
+use crate::function::{NewTarget, Opt, Rest};
 use crate::{Context, Error, ErrorKind, Function, Local, Object, Result, Value};
 
 /// Represents Arguments to a [JavaScript Function](crate::Function).
-/// Wrapper around [CallArgs] to provide lifetimes and root all arguments.
+/// Wrapper around [CallArgs] to provide lifetimes to the arguments.
+///
+/// The arguments are rooted by the caller, so they are accessed through handles rather than being rooted again.
+/// The callee is only rooted when it is accessed.
 pub struct Arguments<'cx> {
 	cx: &'cx Context,
 	args: u16,
-	callee: Object<'cx>,
 	call_args: CallArgs,
 }
 
 impl<'cx> Arguments<'cx> {
 	pub unsafe fn new(cx: &'cx Context, argc: u32, vp: *mut JSVal) -> Arguments<'cx> {
-		unsafe {
-			let call_args = CallArgs::from_vp(vp, argc);
-			let callee = cx.root(call_args.callee()).into();
-
-			Arguments {
-				cx,
-				args: u16::try_from(argc).unwrap(),
-				callee,
-				call_args,
-			}
+		Arguments {
+			cx,
+			args: u16::try_from(argc).unwrap(),
+			call_args: unsafe { CallArgs::from_vp(vp, argc) },
 		}
 	}
 
 	/// Checks if the expected minimum number of arguments were passed.
 	pub fn check_args(&self, cx: &Context, min: u16) -> Result<()> {
 		if self.args < min {
-			let callee = Function::from_object(cx, &self.callee).unwrap();
+			let callee = Function::from_object(cx, &self.callee()).unwrap();
 			let name = callee.name(cx)?;
 
 			return Err(Error::new(
@@ -69,7 +65,7 @@ impl<'cx> Arguments<'cx> {
 
 	/// Returns the value of the function being called.
 	pub fn callee(&self) -> Object<'cx> {
-		Object::from(Local::from_handle(self.callee.handle()))
+		Object::from(self.cx.root(self.call_args.callee()))
 	}
 
 	/// Returns the `this` value of the function.
@@ -99,6 +95,15 @@ impl<'cx> Arguments<'cx> {
 		self.call_args.constructing_()
 	}
 
+	/// Returns `new.target` if the function was called with `new`.
+	/// When a class extends a native class, this is the constructor of the subclass.
+	pub fn new_target(&self) -> Option<Object<'cx>> {
+		self.is_constructing().then(|| {
+			let new_target = Value::from(unsafe { Local::from_raw_handle_mut(self.call_args.new_target()) });
+			new_target.to_object(self.cx)
+		})
+	}
+
 	/// Returns `true` if the function ignores the return value.
 	pub fn ignores_return_value(&self) -> bool {
 		self.call_args.ignoresReturnValue_()
@@ -182,6 +187,15 @@ impl<'cx> FromArgument<'_, 'cx> for &'cx Context {
 	}
 }
 
+impl<'cx> FromArgument<'_, 'cx> for NewTarget<'cx> {
+	type Config = ();
+
+	fn from_argument(accessor: &mut Accessor<'_, 'cx>, _: ()) -> Result<NewTarget<'cx>> {
+		let error = || Error::new("new.target is only available when constructing.", ErrorKind::Type);
+		accessor.args.new_target().map(NewTarget).ok_or_else(error)
+	}
+}
+
 impl<'a, 'cx> FromArgument<'a, 'cx> for &'a mut Arguments<'cx> {
 	type Config = ();
 
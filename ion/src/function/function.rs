@@ -4,28 +4,46 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use std::ffi::{CStr, CString};
+use std::ffi::{c_char, CStr, CString};
 use std::ops::Deref;
 
 use mozjs::conversions::jsstr_to_string;
 use mozjs::gc::{RootableVec, RootedVec};
 use mozjs::jsapi::{
-	HandleValueArray, JSContext, JSFunction, JSFunctionSpec, JSObject, JS_CallFunction, JS_DecompileFunction,
-	JS_GetFunctionArity, JS_GetFunctionDisplayId, JS_GetFunctionId, JS_GetFunctionLength, JS_GetFunctionObject,
-	JS_GetObjectFunction, JS_IsBuiltinEvalFunction, JS_IsBuiltinFunctionConstructor, JS_IsConstructor, JS_NewFunction,
-	JS_ObjectIsFunction, NewFunctionFromSpec1, NewFunctionWithReserved, SetFunctionNativeReserved,
+	CompileFunction, HandleValueArray, JSContext, JSFunction, JSFunctionSpec, JSObject, JS_CallFunction,
+	JS_DecompileFunction, JS_GetFunctionArity, JS_GetFunctionDisplayId, JS_GetFunctionId, JS_GetFunctionLength,
+	JS_GetFunctionObject, JS_GetObjectFunction, JS_IsBuiltinEvalFunction, JS_IsBuiltinFunctionConstructor,
+	JS_IsConstructor, JS_NewFunction, JS_ObjectIsFunction, NewFunctionFromSpec1, NewFunctionWithReserved,
+	SetFunctionNativeReserved,
 };
 use mozjs::jsval::{JSVal, ObjectValue};
+use mozjs::rust::wrappers::Call;
+use mozjs::rust::{transform_u16_to_source_text, CompileOptionsWrapper, RootedObjectVectorWrapper};
 
 use crate::flags::PropertyFlags;
 use crate::function::closure::{
 	call_closure, call_closure_once, create_closure_object, create_closure_once_object, Closure, ClosureOnce,
 };
-use crate::{Context, Error, ErrorReport, Local, Object, Value};
+use crate::{Context, Error, ErrorKind, ErrorReport, Exception, Local, Object, Value};
 
 /// Native Function that can be used from JavaScript.
 pub type NativeFunction = unsafe extern "C" fn(*mut JSContext, u32, *mut JSVal) -> bool;
 
+/// Options for compiling a [Function] from source with [Function::compile].
+#[derive(Clone, Copy, Debug)]
+pub struct CompileOptions<'a> {
+	/// Filename of the source, which is shown in stacks and errors.
+	pub filename: &'a str,
+	/// Line of the source which the body starts on.
+	pub line: u32,
+}
+
+impl Default for CompileOptions<'static> {
+	fn default() -> CompileOptions<'static> {
+		CompileOptions { filename: "<anonymous>", line: 1 }
+	}
+}
+
 /// Represents a [Function] within the JavaScript Runtime.
 /// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Functions) for more details.
 #[derive(Debug)]
@@ -52,6 +70,53 @@ impl<'f> Function<'f> {
 		}
 	}
 
+	/// Compiles a function with the given name, parameters and body, in the global scope of the current realm.
+	///
+	/// This is equivalent to `new Function(...params, body)`, without looking up the `Function` constructor, and
+	/// with the location of the source in its stack frames.
+	/// Returns [Err] when function compilation fails, or the name or a parameter contains a nul character.
+	pub fn compile(
+		cx: &'f Context, name: &str, params: &[&str], body: &str, options: CompileOptions,
+	) -> Result<Function<'f>, ErrorReport> {
+		let nul_error = |_| {
+			let error = Error::new(
+				"Function name and parameters cannot contain nul characters",
+				ErrorKind::Syntax,
+			);
+			ErrorReport::from(Exception::Error(error), None)
+		};
+		let name = CString::new(name).map_err(nul_error)?;
+		let params = params
+			.iter()
+			.map(|param| CString::new(*param))
+			.collect::<Result<Vec<_>, _>>()
+			.map_err(nul_error)?;
+		let params: Vec<*const c_char> = params.iter().map(|param| param.as_ptr()).collect();
+
+		let body: Vec<u16> = body.encode_utf16().collect();
+		let mut source = transform_u16_to_source_text(body.as_slice());
+		let options = unsafe { CompileOptionsWrapper::new(cx.as_ptr(), options.filename, options.line) };
+		let scope = RootedObjectVectorWrapper::new(cx.as_ptr());
+
+		let function = unsafe {
+			CompileFunction(
+				cx.as_ptr(),
+				scope.handle(),
+				options.ptr,
+				name.as_ptr(),
+				params.len() as u32,
+				params.as_ptr(),
+				&mut source,
+			)
+		};
+
+		if !function.is_null() {
+			Ok(Function { function: cx.root(function) })
+		} else {
+			Err(ErrorReport::new_with_exception_stack(cx)?.unwrap())
+		}
+	}
+
 	/// Creates a new [Function] with a [ClosureOnce].
 	///
 	/// Throws a JS Exception if called more than once.
@@ -161,6 +226,31 @@ impl<'f> Function<'f> {
 		self.call_with_handle(cx, this, HandleValueArray::from(&args))
 	}
 
+	/// Calls the [Function] with the given `this` [Value], which may be a primitive, and arguments.
+	/// Returns the result of the [Function] as a [Value].
+	/// Returns [Err] if the function call fails or an exception occurs.
+	pub fn call_with_this<'cx>(
+		&self, cx: &'cx Context, this: &Value, args: &[Value],
+	) -> Result<Value<'cx>, Option<ErrorReport>> {
+		let mut root = RootableVec::new_unrooted();
+		let args = RootedVec::from_iter(&mut root, args.iter().map(|a| a.get()));
+		let function = Value::object(cx, &self.to_object(cx));
+		let mut rval = Value::undefined(cx);
+		if unsafe {
+			Call(
+				cx.as_ptr(),
+				this.handle(),
+				function.handle(),
+				&HandleValueArray::from(&args),
+				rval.handle_mut(),
+			)
+		} {
+			Ok(rval)
+		} else {
+			Err(ErrorReport::new_with_exception_stack(cx).unwrap())
+		}
+	}
+
 	/// Calls the [Function] with the given `this` [Object] and arguments as a [HandleValueArray].
 	/// Returns the result of the [Function] as a [Value].
 	/// Returns [Err] if the function call fails or an exception occurs.
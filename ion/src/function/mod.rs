@@ -10,7 +10,7 @@ use std::thread::Result;
 
 pub use arguments::{Accessor, Arguments, FromArgument};
 pub use closure::{Closure, ClosureOnce};
-pub use function::{Function, NativeFunction};
+pub use function::{CompileOptions, Function, NativeFunction};
 use mozjs::conversions::ConversionBehavior;
 
 use crate::conversions::{FromValue, ToValue};
@@ -67,6 +67,11 @@ fn handle_unwind_error(cx: &Context, unwind_error: Box<dyn Any + Send>) -> bool
 /// Helper type for optional arguments.
 pub struct Opt<T>(pub Option<T>);
 
+/// Helper type for `new.target` in constructors, which does not consume an argument.
+///
+/// When a JS class extends a native class, this is the constructor of the subclass.
+pub struct NewTarget<'cx>(pub Object<'cx>);
+
 /// Helper type for rest/spread/variable arguments.
 pub struct Rest<T>(pub Box<[T]>);
 
@@ -0,0 +1,155 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::ffi::c_void;
+use std::ptr;
+
+use mozjs::glue::{CreateJobQueue, JobQueueTraps};
+use mozjs::jsapi::{
+	CurrentGlobalOrNull, Handle, JSContext, JSObject, JS_IsRunning, JobQueueIsEmpty, JobQueueMayNotBeEmpty, SetJobQueue,
+};
+
+use crate::{Context, ErrorReport, Function, Object, ResultExc, TracedHeap};
+
+/// Represents a job which is run by a microtask checkpoint.
+pub enum Job {
+	/// Promise reaction job, queued by SpiderMonkey.
+	Promise(TracedHeap<*mut JSObject>),
+	/// Native microtask, queued by the embedder.
+	Native(Box<dyn FnOnce(&Context) -> ResultExc<()>>),
+}
+
+impl Job {
+	/// Runs the job, returning the report of the exception it threw.
+	pub fn run(self, cx: &Context) -> Result<(), Option<ErrorReport>> {
+		match self {
+			Job::Promise(job) => {
+				let object = cx.root(job.get());
+				let function = Function::from_object(cx, &object).unwrap();
+				function.call(cx, &Object::null(cx), &[]).map(|_| ())
+			}
+			Job::Native(callback) => {
+				callback(cx).map_err(|exception| Some(ErrorReport::from_exception_with_error_stack(cx, exception)))
+			}
+		}
+	}
+}
+
+/// Microtask queue for embedders which drive their own event loop.
+///
+/// Once installed with [JobQueue::install], promise reaction jobs are queued here rather than in SpiderMonkey, and
+/// are only run by [JobQueue::checkpoint].
+/// The event loop of `runtime` installs its own queue, and must not be used with this one.
+#[derive(Default)]
+pub struct JobQueue {
+	jobs: RefCell<VecDeque<Job>>,
+	draining: Cell<bool>,
+}
+
+impl JobQueue {
+	/// Installs a job queue on the context, or returns the existing queue if one has already been installed.
+	pub fn install(cx: &Context) -> &JobQueue {
+		let inner = unsafe { &mut *cx.get_inner_data().as_ptr() };
+		if inner.job_queue.is_none() {
+			let queue = Box::<JobQueue>::default();
+			unsafe {
+				let job_queue = CreateJobQueue(&JOB_QUEUE_TRAPS, ptr::from_ref(&*queue).cast());
+				SetJobQueue(cx.as_ptr(), job_queue);
+			}
+			inner.job_queue = Some(queue);
+		}
+		inner.job_queue.as_deref().unwrap()
+	}
+
+	/// Returns the job queue installed on the context.
+	pub fn get(cx: &Context) -> Option<&JobQueue> {
+		unsafe { (*cx.get_inner_data().as_ptr()).job_queue.as_deref() }
+	}
+
+	pub fn enqueue(&self, cx: &Context, job: Job) {
+		self.jobs.borrow_mut().push_back(job);
+		unsafe { JobQueueMayNotBeEmpty(cx.as_ptr()) };
+	}
+
+	/// Queues a native microtask, which runs after the promise jobs and microtasks already in the queue.
+	pub fn enqueue_native<F>(&self, cx: &Context, callback: F)
+	where
+		F: FnOnce(&Context) -> ResultExc<()> + 'static,
+	{
+		self.enqueue(cx, Job::Native(Box::new(callback)));
+	}
+
+	/// Performs a microtask checkpoint, running jobs until the queue is empty, including those queued by the jobs.
+	///
+	/// Does nothing if script is running or the queue is already being drained, as the outer checkpoint then runs the
+	/// jobs. If a job throws, the checkpoint stops and the remaining jobs are run by the next checkpoint.
+	pub fn checkpoint(&self, cx: &Context) -> Result<(), Option<ErrorReport>> {
+		if self.draining.get() || unsafe { JS_IsRunning(cx.as_ptr()) } {
+			return Ok(());
+		}
+
+		self.draining.set(true);
+		let mut result = Ok(());
+		while let Some(job) = self.pop() {
+			result = job.run(cx);
+			if result.is_err() {
+				break;
+			}
+		}
+		self.draining.set(false);
+
+		if self.is_empty() {
+			unsafe { JobQueueIsEmpty(cx.as_ptr()) };
+		}
+		result
+	}
+
+	/// Checks if a checkpoint is currently running the jobs of the queue.
+	pub fn is_draining(&self) -> bool {
+		self.draining.get()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.jobs.borrow().is_empty()
+	}
+
+	pub fn len(&self) -> usize {
+		self.jobs.borrow().len()
+	}
+
+	fn pop(&self) -> Option<Job> {
+		self.jobs.borrow_mut().pop_front()
+	}
+}
+
+unsafe extern "C" fn get_incumbent_global(_: *const c_void, cx: *mut JSContext) -> *mut JSObject {
+	unsafe { CurrentGlobalOrNull(cx) }
+}
+
+unsafe extern "C" fn enqueue_promise_job(
+	extra: *const c_void, cx: *mut JSContext, _: Handle<*mut JSObject>, job: Handle<*mut JSObject>,
+	_: Handle<*mut JSObject>, _: Handle<*mut JSObject>,
+) -> bool {
+	let cx = unsafe { &Context::new_unchecked(cx) };
+	let queue = unsafe { &*extra.cast::<JobQueue>() };
+	if !job.is_null() {
+		queue.enqueue(cx, Job::Promise(TracedHeap::new(job.get())));
+	}
+	true
+}
+
+unsafe extern "C" fn empty(extra: *const c_void) -> bool {
+	let queue = unsafe { &*extra.cast::<JobQueue>() };
+	queue.is_empty()
+}
+
+static JOB_QUEUE_TRAPS: JobQueueTraps = JobQueueTraps {
+	getIncumbentGlobal: Some(get_incumbent_global),
+	enqueuePromiseJob: Some(enqueue_promise_job),
+	empty: Some(empty),
+};
@@ -13,7 +13,7 @@ use std::result;
 
 pub use bigint::BigInt;
 pub use class::ClassDefinition;
-pub use context::{Context, ContextInner};
+pub use context::{Context, ContextInner, ContextToken, Guarded};
 pub use error::{Error, ErrorKind};
 pub use exception::{ErrorReport, Exception, ThrowException};
 pub use function::{Arguments, Function};
@@ -21,7 +21,7 @@ pub use future::PromiseFuture;
 #[cfg(feature = "macros")]
 pub use ion_proc::*;
 pub use object::*;
-pub use root::{Local, TracedHeap};
+pub use root::{live_boxed_heaps, live_roots, BoxedHeap, LiveRoot, Local, TracedHeap};
 pub use stack::{Stack, StackRecord};
 pub use string::{String, StringRef};
 pub use symbol::Symbol;
@@ -38,6 +38,7 @@ pub mod flags;
 pub mod format;
 pub mod function;
 mod future;
+pub mod job_queue;
 pub mod module;
 pub mod object;
 mod root;
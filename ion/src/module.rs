@@ -8,8 +8,9 @@ use std::path::Path;
 use std::ptr;
 
 use mozjs::jsapi::{
-	CompileModule, CreateModuleRequest, GetModuleRequestSpecifier, Handle, JSContext, JSObject, JS_GetRuntime,
-	ModuleEvaluate, ModuleIsLinked, ModuleLink, SetModuleMetadataHook, SetModulePrivate, SetModuleResolveHook,
+	CompileModule, CreateModuleRequest, GetModuleRequestSpecifier, GetRequestedModuleSpecifier,
+	GetRequestedModulesCount, Handle, JSContext, JSObject, JS_GetRuntime, ModuleEvaluate, ModuleIsLinked, ModuleLink,
+	SetModuleMetadataHook, SetModulePrivate, SetModuleResolveHook,
 };
 use mozjs::jsval::JSVal;
 use mozjs::rust::{transform_u16_to_source_text, CompileOptionsWrapper};
@@ -176,6 +177,18 @@ impl<'cx> Module<'cx> {
 	pub fn is_linked(&self) -> bool {
 		unsafe { ModuleIsLinked(self.0.handle().get()) }
 	}
+
+	/// Returns the specifiers of the modules requested by the module, in source order.
+	/// The module does not need to be linked.
+	pub fn requested_modules(&self, cx: &'cx Context) -> Vec<crate::String<'cx>> {
+		let count = unsafe { GetRequestedModulesCount(cx.as_ptr(), self.0.handle().into()) };
+		(0..count)
+			.map(|index| {
+				let specifier = unsafe { GetRequestedModuleSpecifier(cx.as_ptr(), self.0.handle().into(), index) };
+				crate::String::from(cx.root(specifier))
+			})
+			.collect()
+	}
 }
 
 /// Represents an ES module loader.
@@ -7,14 +7,19 @@
 use std::iter::FusedIterator;
 use std::ops::{Deref, DerefMut};
 
-use mozjs::gc::RootedVec;
-use mozjs::jsapi::{GetArrayLength, HandleValueArray, IsArray, JSObject, NewArrayObject, NewArrayObject1};
-use mozjs::jsval::{JSVal, ObjectValue};
+use mozjs::gc::{RootableVec, RootedVec};
+use mozjs::jsapi::{
+	GetArrayLength, HandleValueArray, IsArray, JSObject, JS_GetElement, NewArrayObject, NewArrayObject1, SetArrayLength,
+};
+use mozjs::jsval::{JSVal, UndefinedValue};
 
 use crate::conversions::{FromValue, ToValue};
 use crate::flags::{IteratorFlags, PropertyFlags};
 use crate::object::object::ObjectKeysIter;
-use crate::{Context, Local, Object, PropertyDescriptor, Result, Value};
+use crate::{Context, Error, ErrorKind, Local, Object, PropertyDescriptor, Result, Value};
+
+/// Maximum number of elements allocated up front when converting an [Array] to a [Vec].
+const MAX_PREALLOCATED_LENGTH: u32 = 4096;
 
 /// Represents an [Array] in the JavaScript Runtime.
 /// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array) for more details.
@@ -41,6 +46,22 @@ impl<'a> Array<'a> {
 		Array::from_handle(cx, HandleValueArray::from(vec))
 	}
 
+	/// Creates an [Array] from a slice of Rust values.
+	///
+	/// All values are converted before the [Array] is created, so that its elements are allocated at once.
+	pub fn from_slice<T: ToValue<'a>>(cx: &'a Context, slice: &[T]) -> Array<'a> {
+		let mut root = RootableVec::new_unrooted();
+		let mut vec = RootedVec::new(&mut root);
+		vec.reserve(slice.len());
+
+		let mut value = Value::undefined(cx);
+		for element in slice {
+			element.to_value(cx, &mut value);
+			vec.push(value.get());
+		}
+		Array::from_rooted_vec(cx, &vec)
+	}
+
 	/// Creates an [Array] from a [HandleValueArray].
 	pub fn from_handle(cx: &'a Context, handle: HandleValueArray) -> Array<'a> {
 		Array {
@@ -67,11 +88,26 @@ impl<'a> Array<'a> {
 		Array { arr: object.into() }
 	}
 
-	/// Converts an [Array] to a [Vec].
-	/// Returns an empty [Vec] if the conversion fails.
-	pub fn to_vec<'cx>(&self, cx: &'cx Context) -> Vec<Value<'cx>> {
-		let value = cx.root(ObjectValue(self.arr.handle().get())).into();
-		Vec::from_value(cx, &value, true, ()).unwrap_or_default()
+	/// Converts the elements of an [Array] to a [Vec] of Rust values.
+	///
+	/// The length is read once, and elements are read by index without checking for holes, which become `undefined`.
+	/// As the length is controlled by scripts, only a bounded number of elements are allocated up front.
+	pub fn to_vec<'cx, T: FromValue<'cx>>(&self, cx: &'cx Context, strict: bool, config: T::Config) -> Result<Vec<T>>
+	where
+		T::Config: Clone,
+	{
+		let length = self.length(cx)?;
+		let mut vec = Vec::with_capacity(length.min(MAX_PREALLOCATED_LENGTH) as usize);
+
+		rooted!(in(cx.as_ptr()) let mut element = UndefinedValue());
+		for index in 0..length {
+			if !unsafe { JS_GetElement(cx.as_ptr(), self.handle().into(), index, element.handle_mut().into()) } {
+				return Err(Error::none());
+			}
+			let element = Value::from(Local::from_handle(element.handle()));
+			vec.push(T::from_value(cx, &element, strict, config.clone())?);
+		}
+		Ok(vec)
 	}
 
 	/// Converts an [Array] to an [Object].
@@ -88,6 +124,12 @@ impl<'a> Array<'a> {
 		length
 	}
 
+	/// Sets the length of the [Array], removing elements after the new length, or adding holes.
+	/// Returns `false` if the length cannot be set.
+	pub fn set_len(&self, cx: &Context, length: u32) -> bool {
+		unsafe { SetArrayLength(cx.as_ptr(), self.handle().into(), length) }
+	}
+
 	/// Returns if the [Array] is empty.
 	pub fn is_empty(&self, cx: &Context) -> bool {
 		self.len(cx) == 0
@@ -150,6 +192,93 @@ impl<'a> Array<'a> {
 		self.arr.delete(cx, index)
 	}
 
+	/// Appends the [Value] to the end of the [Array], and returns the new length.
+	pub fn push(&self, cx: &Context, value: &Value) -> Result<u32> {
+		let length = self.length(cx)?;
+		let new_length = length.checked_add(1).ok_or_else(length_error)?;
+		if self.set(cx, length, value) {
+			Ok(new_length)
+		} else {
+			Err(Error::none())
+		}
+	}
+
+	/// Appends the Rust type to the end of the [Array], and returns the new length.
+	pub fn push_as<'cx, T: ToValue<'cx> + ?Sized>(&self, cx: &'cx Context, value: &T) -> Result<u32> {
+		self.push(cx, &value.as_value(cx))
+	}
+
+	/// Appends the Rust values to the end of the [Array], and returns the new length.
+	///
+	/// The length is read once, rather than for each value.
+	pub fn extend<'cx, T: ToValue<'cx>>(&self, cx: &'cx Context, values: &[T]) -> Result<u32> {
+		let length = self.length(cx)?;
+		let new_length = checked_length(length, values.len())?;
+
+		let mut value = Value::undefined(cx);
+		for (index, element) in (length..).zip(values) {
+			element.to_value(cx, &mut value);
+			if !unsafe { cx.scope(|cx| self.set(cx, index, &value)) } {
+				return Err(Error::none());
+			}
+		}
+		Ok(new_length)
+	}
+
+	/// Removes the last element of the [Array], and returns it.
+	/// Returns [None] if the [Array] is empty.
+	pub fn pop<'cx>(&self, cx: &'cx Context) -> Result<Option<Value<'cx>>> {
+		let length = self.length(cx)?;
+		if length == 0 {
+			return Ok(None);
+		}
+
+		let value = self.get(cx, length - 1)?.unwrap_or_else(|| Value::undefined(cx));
+		if self.set_len(cx, length - 1) {
+			Ok(Some(value))
+		} else {
+			Err(Error::none())
+		}
+	}
+
+	/// Removes `delete_count` elements from `start`, inserts `items` in their place, and returns the removed elements.
+	///
+	/// `start` and `delete_count` are clamped to the length of the [Array], which is read once.
+	pub fn splice<'cx>(&self, cx: &'cx Context, start: u32, delete_count: u32, items: &[Value]) -> Result<Array<'cx>> {
+		let length = self.length(cx)?;
+		let start = start.min(length);
+		let delete_count = delete_count.min(length - start);
+		let new_length = checked_length(length - delete_count, items.len())?;
+		let item_count = new_length - (length - delete_count);
+
+		let removed = Array::new_with_length(cx, delete_count as usize);
+		for index in 0..delete_count {
+			unsafe { cx.scope(|cx| self.copy_element(cx, start + index, &removed, index)) }?;
+		}
+
+		let tail = (start + delete_count)..length;
+		if item_count < delete_count {
+			for from in tail {
+				unsafe { cx.scope(|cx| self.copy_element(cx, from, self, from - delete_count + item_count)) }?;
+			}
+		} else if item_count > delete_count {
+			for from in tail.rev() {
+				unsafe { cx.scope(|cx| self.copy_element(cx, from, self, from + item_count - delete_count)) }?;
+			}
+		}
+
+		for (index, item) in (start..).zip(items) {
+			if !unsafe { cx.scope(|cx| self.set(cx, index, item)) } {
+				return Err(Error::none());
+			}
+		}
+		if self.set_len(cx, new_length) {
+			Ok(removed)
+		} else {
+			Err(Error::none())
+		}
+	}
+
 	pub fn indices<'cx>(&self, cx: &'cx Context, flags: Option<IteratorFlags>) -> ArrayIndicesIter<'cx> {
 		ArrayIndicesIter(self.arr.keys(cx, flags))
 	}
@@ -174,6 +303,28 @@ impl<'a> Array<'a> {
 		unsafe { IsArray(cx.as_ptr(), object.handle().into(), &mut is_array) && is_array }
 	}
 
+	fn length(&self, cx: &Context) -> Result<u32> {
+		let mut length = 0;
+		if unsafe { GetArrayLength(cx.as_ptr(), self.handle().into(), &mut length) } {
+			Ok(length)
+		} else {
+			Err(Error::none())
+		}
+	}
+
+	/// Copies the element at `from` to `to` in `target`, deleting the element in `target` if `from` is a hole.
+	fn copy_element(&self, cx: &Context, from: u32, target: &Array, to: u32) -> Result<()> {
+		let copied = match self.get(cx, from)? {
+			Some(value) => target.set(cx, to, &value),
+			None => target.delete(cx, to),
+		};
+		if copied {
+			Ok(())
+		} else {
+			Err(Error::none())
+		}
+	}
+
 	pub fn as_object(&self) -> &Object<'a> {
 		&self.arr
 	}
@@ -197,6 +348,17 @@ impl DerefMut for Array<'_> {
 	}
 }
 
+fn checked_length(length: u32, additional: usize) -> Result<u32> {
+	u32::try_from(additional)
+		.ok()
+		.and_then(|additional| length.checked_add(additional))
+		.ok_or_else(length_error)
+}
+
+fn length_error() -> Error {
+	Error::new("Invalid Array Length", ErrorKind::Range)
+}
+
 pub struct ArrayIndicesIter<'cx>(ObjectKeysIter<'cx>);
 
 impl Iterator for ArrayIndicesIter<'_> {
@@ -271,6 +433,8 @@ impl FusedIterator for ArrayIter<'_, '_> {}
 
 #[cfg(test)]
 mod tests {
+	use mozjs::conversions::ConversionBehavior;
+
 	use crate::flags::PropertyFlags;
 	use crate::utils::test::TestRuntime;
 	use crate::{Array, Value};
@@ -294,4 +458,38 @@ mod tests {
 		assert!(array.get(cx, 0).unwrap().is_none());
 		assert!(array.get(cx, 2).unwrap().is_some());
 	}
+
+	#[test]
+	fn bulk() {
+		let rt = TestRuntime::new();
+		let cx = &rt.cx;
+
+		let to_vec = |array: &Array| array.to_vec::<i32>(cx, true, ConversionBehavior::Default).unwrap();
+
+		let array = Array::from_slice(cx, &[1, 2, 3]);
+		assert_eq!(array.len(cx), 3);
+		assert_eq!(array.extend(cx, &[4, 5]).unwrap(), 5);
+		assert_eq!(array.push_as(cx, &6).unwrap(), 6);
+		assert_eq!(to_vec(&array), [1, 2, 3, 4, 5, 6]);
+
+		let popped = array.pop(cx).unwrap().unwrap();
+		assert_eq!(popped.handle().to_int32(), 6);
+		assert_eq!(array.len(cx), 5);
+
+		let items = [Value::i32(cx, 7), Value::i32(cx, 8), Value::i32(cx, 9)];
+		let removed = array.splice(cx, 1, 2, &items).unwrap();
+		assert_eq!(to_vec(&removed), [2, 3]);
+		assert_eq!(to_vec(&array), [1, 7, 8, 9, 4, 5]);
+
+		let removed = array.splice(cx, 4, 10, &[]).unwrap();
+		assert_eq!(to_vec(&removed), [4, 5]);
+		assert_eq!(to_vec(&array), [1, 7, 8, 9]);
+
+		let empty = Array::new(cx);
+		assert!(empty.pop(cx).unwrap().is_none());
+
+		// Conversion fails at the first hole, without allocating for the full length.
+		let sparse = Array::new_with_length(cx, u32::MAX as usize);
+		assert!(sparse.to_vec::<i32>(cx, true, ConversionBehavior::Default).is_err());
+	}
 }
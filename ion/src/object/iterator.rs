@@ -8,8 +8,8 @@ use std::{iter, ptr};
 
 use mozjs::gc::Traceable;
 use mozjs::jsapi::{
-	GetRealmIteratorPrototype, Heap, JSClass, JSClassOps, JSContext, JSFunctionSpec, JSNativeWrapper, JSObject,
-	JSTracer, JSCLASS_BACKGROUND_FINALIZE,
+	GetRealmIteratorPrototype, JSClass, JSClassOps, JSContext, JSFunctionSpec, JSNativeWrapper, JSObject, JSTracer,
+	JSCLASS_BACKGROUND_FINALIZE,
 };
 use mozjs::jsval::JSVal;
 
@@ -23,7 +23,7 @@ use crate::function::NativeFunction;
 use crate::object::class_reserved_slots;
 use crate::spec::{create_function_spec, create_function_spec_symbol};
 use crate::symbol::WellKnownSymbolCode;
-use crate::{Arguments, ClassDefinition, Context, Local, Object, ThrowException, Value};
+use crate::{Arguments, BoxedHeap, ClassDefinition, Context, Local, Object, ThrowException, Value};
 
 pub trait JSIterator {
 	fn next_value<'cx>(&mut self, cx: &'cx Context, private: &Value<'cx>) -> Option<Value<'cx>>;
@@ -59,7 +59,7 @@ impl<'cx> ToValue<'cx> for IteratorResult<'cx> {
 pub struct Iterator {
 	reflector: Reflector,
 	iter: Box<dyn JSIterator>,
-	private: Box<Heap<JSVal>>,
+	private: BoxedHeap<JSVal>,
 }
 
 impl Iterator {
@@ -67,7 +67,7 @@ impl Iterator {
 		Iterator {
 			reflector: Reflector::default(),
 			iter: Box::new(iter),
-			private: Heap::boxed(private.get()),
+			private: BoxedHeap::new(private.get()),
 		}
 	}
 
@@ -12,6 +12,7 @@ use mozjs::jsapi::{JSProtoKey, JS_IdToProtoKey, JS_ValueToId, PropertyKey as JSP
 use mozjs::jsid::{IntId, VoidId};
 
 use crate::conversions::ToPropertyKey;
+use crate::string::CompactString;
 use crate::{Context, Local, Result, String, Symbol, Value};
 
 pub struct PropertyKey<'k> {
@@ -26,7 +27,6 @@ impl<'k> PropertyKey<'k> {
 
 	/// Creates a [PropertyKey] from a string.
 	pub fn with_string(cx: &'k Context, string: &str) -> Option<PropertyKey<'k>> {
-		let string = String::copy_from_str(cx, string)?;
 		string.to_key(cx)
 	}
 
@@ -54,9 +54,8 @@ impl<'k> PropertyKey<'k> {
 		if self.handle().is_int() {
 			Ok(OwnedKey::Int(self.handle().to_int()))
 		} else if self.handle().is_string() {
-			Ok(OwnedKey::String(
-				String::from(cx.root(self.handle().to_string())).to_owned(cx)?,
-			))
+			let string = String::from(cx.root(self.handle().to_string()));
+			Ok(OwnedKey::String(CompactString::from_string(cx, &string)?))
 		} else if self.handle().is_symbol() {
 			Ok(OwnedKey::Symbol(cx.root(self.handle().to_symbol()).into()))
 		} else {
@@ -90,10 +89,12 @@ impl DerefMut for PropertyKey<'_> {
 }
 
 /// Represents the key on an object.
+///
+/// String keys are stored as [CompactStrings](CompactString), as most are short enough to be stored inline.
 #[derive(Debug)]
 pub enum OwnedKey<'k> {
 	Int(i32),
-	String(std::string::String),
+	String(CompactString),
 	Symbol(Symbol<'k>),
 	Void,
 }
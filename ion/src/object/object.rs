@@ -477,10 +477,50 @@ impl ExactSizeIterator for ObjectIter<'_, '_> {
 
 impl FusedIterator for ObjectIter<'_, '_> {}
 
+/// Creates an [Object] with the given properties, whose values are converted with [ToValue].
+///
+/// Keys are either literals, or expressions in square brackets, such as symbols.
+/// Properties are set with [Object::set_as], or defined with [Object::define_as] if flags follow the value.
+///
+/// ```ignore
+/// let object = object!(cx, {
+///     "status": status,
+///     "headers": headers => PropertyFlags::CONSTANT_ENUMERATED,
+///     [WellKnownSymbolCode::ToStringTag]: "Response",
+/// });
+/// ```
+#[macro_export]
+macro_rules! object {
+	($cx:expr, { $($key:tt: $value:expr $(=> $flags:expr)?),* $(,)? }) => {{
+		let cx: &$crate::Context = $cx;
+		let object = $crate::Object::new(cx);
+		$($crate::__object_property!(cx, object, $key, &$value $(, $flags)?);)*
+		object
+	}};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __object_property {
+	($cx:ident, $object:ident, [$key:expr], $value:expr $(, $flags:expr)?) => {
+		$crate::__object_property!(@key $cx, $object, $key, $value $(, $flags)?)
+	};
+	($cx:ident, $object:ident, $key:literal, $value:expr $(, $flags:expr)?) => {
+		$crate::__object_property!(@key $cx, $object, $key, $value $(, $flags)?)
+	};
+	(@key $cx:ident, $object:ident, $key:expr, $value:expr) => {
+		$object.set_as($cx, $key, $value)
+	};
+	(@key $cx:ident, $object:ident, $key:expr, $value:expr, $flags:expr) => {
+		$object.define_as($cx, $key, $value, $flags)
+	};
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::conversions::FromValue;
 	use crate::flags::{IteratorFlags, PropertyFlags};
+	use crate::string::CompactString;
 	use crate::symbol::WellKnownSymbolCode;
 	use crate::utils::test::TestRuntime;
 	use crate::{Context, Object, OwnedKey, Symbol, Value};
@@ -559,6 +599,29 @@ mod tests {
 		assert!(object.get(cx, DEFINE.0).unwrap().is_some());
 	}
 
+	#[test]
+	fn object_macro() {
+		let rt = TestRuntime::new();
+		let cx = &rt.cx;
+
+		let object = object!(cx, {
+			"set_key": SET.1,
+			"def_key": DEFINE.1 => PropertyFlags::CONSTANT,
+			[WellKnownSymbolCode::ToStringTag]: "Macro",
+		});
+
+		let descriptor = object.get_descriptor(cx, SET.0).unwrap().unwrap();
+		assert!(descriptor.is_enumerable() && descriptor.is_writable());
+		assert_eq!(SET.1, descriptor.value(cx).unwrap().handle().to_int32());
+
+		let descriptor = object.get_descriptor(cx, DEFINE.0).unwrap().unwrap();
+		assert!(!descriptor.is_enumerable() && !descriptor.is_writable());
+		assert_eq!(DEFINE.1, descriptor.value(cx).unwrap().handle().to_int32());
+
+		let tag = object.get_as::<_, String>(cx, WellKnownSymbolCode::ToStringTag, true, ()).unwrap();
+		assert_eq!(tag.as_deref(), Some("Macro"));
+	}
+
 	#[test]
 	fn iterator() {
 		let rt = TestRuntime::new();
@@ -576,12 +639,12 @@ mod tests {
 
 		for (properties, flags) in properties {
 			for (i, key) in object.keys(cx, flags).into_owned().enumerate() {
-				assert_eq!(OwnedKey::String(String::from(properties[i].0)), key.unwrap());
+				assert_eq!(OwnedKey::String(CompactString::from(properties[i].0)), key.unwrap());
 			}
 
 			for (i, (key, value)) in object.iter(cx, flags).enumerate() {
 				assert_eq!(
-					OwnedKey::String(String::from(properties[i].0)),
+					OwnedKey::String(CompactString::from(properties[i].0)),
 					key.to_owned_key(cx).unwrap()
 				);
 				assert_eq!(properties[i].1, value.unwrap().handle().to_int32());
@@ -599,7 +662,10 @@ mod tests {
 
 		let mut keys = object.keys(cx, flags).into_owned();
 		assert_eq!(OwnedKey::Int(16), keys.next().unwrap().unwrap());
-		assert_eq!(OwnedKey::String(String::from(SET.0)), keys.next().unwrap().unwrap());
+		assert_eq!(
+			OwnedKey::String(CompactString::from(SET.0)),
+			keys.next().unwrap().unwrap()
+		);
 		assert_eq!(
 			OwnedKey::Symbol(Symbol::well_known(cx, WellKnownSymbolCode::ToStringTag)),
 			keys.next().unwrap().unwrap()
@@ -4,6 +4,7 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::cell::Cell;
 use std::future::Future;
 use std::ops::{Deref, DerefMut};
 
@@ -17,7 +18,7 @@ use mozjs::jsapi::{
 
 use crate::conversions::ToValue;
 use crate::flags::PropertyFlags;
-use crate::{Context, Error, Function, Local, Object, ResultExc, Value};
+use crate::{Context, Error, Exception, Function, Local, Object, ResultExc, Value};
 
 /// Represents a [Promise] in the JavaScript Runtime.
 /// Refer to [MDN](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Promise) for more details.
@@ -71,6 +72,50 @@ impl<'p> Promise<'p> {
 		}
 	}
 
+	/// Creates a new [Promise], and calls the executor with functions which resolve or reject it.
+	///
+	/// Unlike [Promise::with_executor], the executor is called with native closures rather than JS functions, and only
+	/// the first call to either closure settles the promise. Resolving the promise with a thenable adopts its state.
+	/// If the executor returns an [Err], the promise is rejected with the exception, unless it was already settled.
+	pub fn new_with_executor<F>(cx: &'p Context, executor: F) -> Promise<'p>
+	where
+		F: FnOnce(&dyn Fn(&Value), &dyn Fn(&Value)) -> ResultExc<()>,
+	{
+		let promise = Promise::new(cx);
+		let settled = Cell::new(false);
+		let resolve = |value: &Value| {
+			if !settled.replace(true) {
+				promise.resolve_or_reject_pending(cx, value);
+			}
+		};
+		let reject = |value: &Value| {
+			if !settled.replace(true) {
+				promise.reject(cx, value);
+			}
+		};
+
+		if let Err(exception) = executor(&resolve, &reject) {
+			reject(&exception.as_value(cx));
+		}
+		promise
+	}
+
+	/// Creates a [Promise] which adopts the state of the given value, similar to `Promise.resolve`.
+	///
+	/// Promises are returned as they are, and other values are resolved with [ResolvePromise], so that thenables,
+	/// including promises from other compartments, are followed by the job queue rather than treated as values.
+	pub fn adopt(cx: &'p Context, value: &Value) -> Promise<'p> {
+		if value.handle().is_object() {
+			if let Some(promise) = Promise::from(value.to_object(cx).into_local()) {
+				return promise;
+			}
+		}
+
+		let promise = Promise::new(cx);
+		promise.resolve_or_reject_pending(cx, value);
+		promise
+	}
+
 	/// Creates a new [Promise] with a [Future].
 	/// The future is run to completion on the current thread and cannot interact with an asynchronous runtime.
 	///
@@ -241,6 +286,15 @@ impl<'p> Promise<'p> {
 		unsafe { RejectPromise(cx.as_ptr(), self.handle().into(), value.handle().into()) }
 	}
 
+	/// Resolves the [Promise] with the given [Value], or rejects it with the pending exception if resolution fails.
+	fn resolve_or_reject_pending(&self, cx: &Context, value: &Value) {
+		if !self.resolve(cx, value) {
+			if let Ok(Some(exception)) = Exception::new(cx) {
+				self.reject(cx, &exception.as_value(cx));
+			}
+		}
+	}
+
 	/// Rejects the [Promise] with the given [Error].
 	pub fn reject_with_error(&self, cx: &Context, error: &Error) -> bool {
 		self.reject(cx, &error.as_value(cx))
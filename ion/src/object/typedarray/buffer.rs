@@ -41,8 +41,30 @@ impl<'ab> ArrayBuffer<'ab> {
 	}
 
 	/// Creates a new [ArrayBuffer] by transferring ownership of the bytes to the JS runtime.
+	///
+	/// The allocation of the [Vec] is used as is, without shrinking it to its length.
 	pub fn from_vec(cx: &Context, bytes: Vec<u8>) -> Option<ArrayBuffer> {
-		ArrayBuffer::from_boxed_slice(cx, bytes.into_boxed_slice())
+		unsafe extern "C" fn free_external_vec(_: *mut c_void, data: *mut c_void) {
+			let _ = unsafe { Box::from_raw(data.cast::<Vec<u8>>()) };
+		}
+
+		let mut bytes = Box::new(bytes);
+		let (ptr, len) = (bytes.as_mut_ptr(), bytes.len());
+		let buffer = unsafe {
+			NewExternalArrayBuffer(
+				cx.as_ptr(),
+				len,
+				ptr.cast(),
+				Some(free_external_vec),
+				Box::into_raw(bytes).cast(),
+			)
+		};
+
+		if buffer.is_null() {
+			None
+		} else {
+			Some(ArrayBuffer { buffer: cx.root(buffer) })
+		}
 	}
 
 	/// Creates a new [ArrayBuffer] by transferring ownership of the bytes to the JS runtime.
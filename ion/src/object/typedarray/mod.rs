@@ -23,24 +23,28 @@ use crate::{Context, Value};
 mod buffer;
 mod view;
 
+/// Bytes which are converted to an [ArrayBuffer] without copying them.
+///
+/// Any type which converts into a [Vec] without copying, such as a uniquely owned `Bytes`, is handed to the JS runtime
+/// directly.
 pub struct ArrayBufferWrapper {
-	buf: Box<[<ArrayBufferU8 as jsta::TypedArrayElement>::Element]>,
+	buf: Vec<<ArrayBufferU8 as jsta::TypedArrayElement>::Element>,
 }
 
 impl ArrayBufferWrapper {
 	pub fn into_array_buffer(self, cx: &Context) -> Option<ArrayBuffer> {
-		ArrayBuffer::from_boxed_slice(cx, self.buf)
+		ArrayBuffer::from_vec(cx, self.buf)
 	}
 }
 
-impl<B: Into<Box<[<ArrayBufferU8 as jsta::TypedArrayElement>::Element]>>> From<B> for ArrayBufferWrapper {
+impl<B: Into<Vec<<ArrayBufferU8 as jsta::TypedArrayElement>::Element>>> From<B> for ArrayBufferWrapper {
 	fn from(buffer: B) -> ArrayBufferWrapper {
 		ArrayBufferWrapper { buf: buffer.into() }
 	}
 }
 
 impl Deref for ArrayBufferWrapper {
-	type Target = Box<[<ArrayBufferU8 as jsta::TypedArrayElement>::Element]>;
+	type Target = Vec<<ArrayBufferU8 as jsta::TypedArrayElement>::Element>;
 
 	fn deref(&self) -> &Self::Target {
 		&self.buf
@@ -4,11 +4,14 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::fmt::{Debug, Formatter};
 use std::marker::PhantomPinned;
+use std::ops::Deref;
 
 use mozjs::gc::{GCMethods, RootKind, RootedTraceableSet, Traceable};
-use mozjs::jsapi::Heap;
+use mozjs::jsapi::{Heap, JSTracer};
 
+use crate::root::tracker::{track, untrack, Kind};
 use crate::Local;
 
 #[derive(Debug)]
@@ -27,6 +30,7 @@ where
 	pub fn new(value: T) -> TracedHeap<T> {
 		let heap = Heap::boxed(value);
 		unsafe { RootedTraceableSet::add(&*heap) };
+		track(&*heap, Kind::Persistent);
 		TracedHeap { heap, _pin: PhantomPinned }
 	}
 }
@@ -59,5 +63,57 @@ where
 {
 	fn drop(&mut self) {
 		unsafe { RootedTraceableSet::remove(&*self.heap) }
+		untrack(&*self.heap);
+	}
+}
+
+/// Boxed [Heap], which is not rooted, and must be traced by its owner.
+///
+/// Boxed heaps are tracked in debug builds, so that those which outlive their runtime can be found with
+/// [live_boxed_heaps](crate::live_boxed_heaps).
+pub struct BoxedHeap<T: GCMethods + Copy + 'static> {
+	heap: Box<Heap<T>>,
+}
+
+impl<T: GCMethods + Copy + 'static> BoxedHeap<T>
+where
+	Heap<T>: Default,
+{
+	pub fn new(value: T) -> BoxedHeap<T> {
+		let heap = Heap::boxed(value);
+		track(&*heap, Kind::Boxed);
+		BoxedHeap { heap }
+	}
+}
+
+impl<T: GCMethods + Copy + 'static> Deref for BoxedHeap<T> {
+	type Target = Heap<T>;
+
+	fn deref(&self) -> &Heap<T> {
+		&self.heap
+	}
+}
+
+impl<T: GCMethods + Copy + 'static> Debug for BoxedHeap<T>
+where
+	Heap<T>: Debug,
+{
+	fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+		self.heap.fmt(f)
+	}
+}
+
+unsafe impl<T: GCMethods + Copy + 'static> Traceable for BoxedHeap<T>
+where
+	Heap<T>: Traceable,
+{
+	unsafe fn trace(&self, trc: *mut JSTracer) {
+		self.heap.trace(trc);
+	}
+}
+
+impl<T: GCMethods + Copy + 'static> Drop for BoxedHeap<T> {
+	fn drop(&mut self) {
+		untrack(&*self.heap);
 	}
 }
@@ -4,8 +4,10 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-pub use heap::TracedHeap;
+pub use heap::{BoxedHeap, TracedHeap};
 pub use local::Local;
+pub use tracker::{live_boxed_heaps, live_roots, LiveRoot};
 
 mod heap;
 mod local;
+pub(crate) mod tracker;
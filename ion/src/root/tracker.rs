@@ -0,0 +1,105 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#[cfg(debug_assertions)]
+use std::backtrace::Backtrace;
+#[cfg(debug_assertions)]
+use std::cell::RefCell;
+#[cfg(debug_assertions)]
+use std::collections::HashMap;
+#[cfg(debug_assertions)]
+use std::ptr;
+
+/// Persistent root which is alive, with the backtrace of its creation.
+#[derive(Debug)]
+pub struct LiveRoot {
+	pub type_name: &'static str,
+	/// Backtrace of the creation of the root, which is only captured if `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` is
+	/// set.
+	pub backtrace: String,
+}
+
+/// Kind of a tracked root.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Kind {
+	/// Root which is traced by the runtime until it is dropped, such as a [TracedHeap](crate::TracedHeap).
+	Persistent,
+	/// [BoxedHeap](crate::BoxedHeap), which is traced by its owner.
+	Boxed,
+}
+
+#[cfg(debug_assertions)]
+struct Record {
+	kind: Kind,
+	type_name: &'static str,
+	backtrace: Backtrace,
+}
+
+#[cfg(debug_assertions)]
+thread_local! {
+	static ROOTS: RefCell<HashMap<usize, Record>> = RefCell::new(HashMap::new());
+}
+
+/// Records the creation of a root, in debug builds.
+pub(crate) fn track<T>(root: &T, kind: Kind) {
+	#[cfg(debug_assertions)]
+	{
+		let record = Record {
+			kind,
+			type_name: std::any::type_name::<T>(),
+			backtrace: Backtrace::capture(),
+		};
+		ROOTS.with_borrow_mut(|roots| roots.insert(ptr::from_ref(root) as usize, record));
+	}
+	#[cfg(not(debug_assertions))]
+	let _ = (root, kind);
+}
+
+/// Records the removal of a root, in debug builds.
+pub(crate) fn untrack<T>(root: &T) {
+	#[cfg(debug_assertions)]
+	ROOTS.with_borrow_mut(|roots| roots.remove(&(ptr::from_ref(root) as usize)));
+	#[cfg(not(debug_assertions))]
+	let _ = root;
+}
+
+/// Returns the persistent roots, such as [TracedHeaps](crate::TracedHeap), which are alive on the current thread.
+///
+/// Roots which are alive once their runtime has been torn down have leaked, and would be traced by a dead runtime.
+/// Roots are only tracked in debug builds, so this is always empty in release builds.
+pub fn live_roots() -> Vec<LiveRoot> {
+	live(Kind::Persistent)
+}
+
+/// Returns the [BoxedHeaps](crate::BoxedHeap) which are alive on the current thread.
+///
+/// Boxed heaps are dropped by their owners, such as when the object owning them is finalised. Those which are alive
+/// once their runtime has been destroyed have leaked, and writing to them when they are dropped would crash.
+/// Boxed heaps are only tracked in debug builds, so this is always empty in release builds.
+pub fn live_boxed_heaps() -> Vec<LiveRoot> {
+	live(Kind::Boxed)
+}
+
+fn live(kind: Kind) -> Vec<LiveRoot> {
+	#[cfg(debug_assertions)]
+	{
+		ROOTS.with_borrow(|roots| {
+			roots
+				.values()
+				.filter(|record| record.kind == kind)
+				.map(|record| LiveRoot {
+					type_name: record.type_name,
+					backtrace: record.backtrace.to_string(),
+				})
+				.collect()
+		})
+	}
+	#[cfg(not(debug_assertions))]
+	{
+		let _ = kind;
+		Vec::new()
+	}
+}
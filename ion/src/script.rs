@@ -4,12 +4,19 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use std::path::Path;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::thread;
+use std::thread::JoinHandle;
 
-use mozjs::jsapi::{Compile, JSScript, JS_ExecuteScript};
-use mozjs::rust::{transform_u16_to_source_text, CompileOptionsWrapper};
+use mozjs::jsapi::{Compile, InstantiateGlobalStencil, InstantiateOptions, JSAutoRealm, JSScript, JS_ExecuteScript};
+use mozjs::rust::{
+	transform_u16_to_source_text, CompileGlobalScriptToStencil, CompileOptionsWrapper, Runtime, Stencil as RawStencil,
+};
 
-use crate::{Context, ErrorReport, Local, Value};
+use crate::stack::Location;
+use crate::{default_new_global, Context, ContextInner, Error, ErrorKind, ErrorReport, Exception, Local, Value};
 
 #[derive(Debug)]
 pub struct Script<'cx> {
@@ -60,3 +67,121 @@ impl<'s> From<Local<'s, *mut JSScript>> for Script<'s> {
 		Script { script }
 	}
 }
+
+/// Represents a script compiled into a stencil, which is independent of any global.
+///
+/// A stencil can be instantiated in multiple realms, and can be compiled on another thread.
+pub struct Stencil {
+	stencil: RawStencil,
+}
+
+// Stencils are immutable and atomically reference-counted.
+unsafe impl Send for Stencil {}
+unsafe impl Sync for Stencil {}
+
+impl Stencil {
+	/// Compiles a script with a given filename into a stencil, without instantiating it.
+	/// Returns [Err] when script compilation fails.
+	pub fn compile(cx: &Context, path: &Path, script: &str) -> Result<Stencil, ErrorReport> {
+		let script: Vec<u16> = script.encode_utf16().collect();
+		let mut source = transform_u16_to_source_text(script.as_slice());
+		let options = unsafe { CompileOptionsWrapper::new(cx.as_ptr(), path.to_str().unwrap(), 1) };
+
+		let stencil = unsafe { CompileGlobalScriptToStencil(cx.as_ptr(), options.ptr, &mut source) };
+
+		if !stencil.is_null() {
+			Ok(Stencil { stencil })
+		} else {
+			Err(ErrorReport::new_with_exception_stack(cx)?.unwrap())
+		}
+	}
+
+	/// Compiles a script into a stencil on a new thread, with a child runtime of the given runtime.
+	pub fn compile_off_thread(rt: &Runtime, path: PathBuf, script: String) -> OffThreadCompilation {
+		let parent = rt.prepare_for_new_child();
+		let handle = thread::spawn(move || {
+			let rt = Runtime::create_with_parent(parent);
+			let cx = Context::from_runtime(&rt);
+
+			let result = {
+				let global = default_new_global(&cx);
+				let _realm = JSAutoRealm::new(cx.as_ptr(), global.handle().get());
+				Stencil::compile(&cx, &path, &script).map_err(|report| CompileError::new(report.exception))
+			};
+
+			unsafe {
+				let inner = cx.get_inner_data().as_ptr();
+				ContextInner::remove_tracer(cx.as_ptr(), inner);
+				drop(Box::from_raw(inner));
+			}
+			result
+		});
+		OffThreadCompilation { handle }
+	}
+
+	/// Instantiates the stencil as a [Script] in the current realm.
+	/// Returns [Err] when instantiation fails.
+	pub fn instantiate<'cx>(&self, cx: &'cx Context) -> Result<Script<'cx>, ErrorReport> {
+		let options = InstantiateOptions {
+			skipFilenameValidation: false,
+			hideScriptFromDebugger: false,
+			deferDebugMetadata: false,
+		};
+		let script = unsafe { InstantiateGlobalStencil(cx.as_ptr(), &options, *self.stencil, ptr::null_mut()) };
+
+		if !script.is_null() {
+			Ok(Script { script: cx.root(script) })
+		} else {
+			Err(ErrorReport::new_with_exception_stack(cx)?.unwrap())
+		}
+	}
+}
+
+/// Compilation error which can be sent between threads.
+struct CompileError {
+	kind: ErrorKind,
+	message: Cow<'static, str>,
+	location: Option<Location>,
+}
+
+impl CompileError {
+	fn new(exception: Exception) -> CompileError {
+		match exception {
+			Exception::Error(error) => CompileError {
+				kind: error.kind,
+				message: error.message,
+				location: error.location,
+			},
+			Exception::Other(_) => CompileError {
+				kind: ErrorKind::Normal,
+				message: Cow::Borrowed("Script compilation failed"),
+				location: None,
+			},
+		}
+	}
+}
+
+/// Represents a script being compiled into a [Stencil] on another thread.
+pub struct OffThreadCompilation {
+	handle: JoinHandle<Result<Stencil, CompileError>>,
+}
+
+impl OffThreadCompilation {
+	/// Checks if the compilation has finished.
+	pub fn is_finished(&self) -> bool {
+		self.handle.is_finished()
+	}
+
+	/// Waits for the compilation to finish, and returns the compiled stencil.
+	/// Returns [Err] when script compilation fails.
+	pub fn join(self) -> Result<Stencil, Error> {
+		match self.handle.join() {
+			Ok(Ok(stencil)) => Ok(stencil),
+			Ok(Err(error)) => Err(Error {
+				location: error.location,
+				..Error::new(error.message, error.kind)
+			}),
+			Err(_) => Err(Error::new("Off-thread compilation panicked", ErrorKind::Internal)),
+		}
+	}
+}
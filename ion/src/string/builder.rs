@@ -0,0 +1,170 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt;
+use std::fmt::Write;
+
+use mozjs::jsapi::{JSString, JS_ConcatStrings, JS_NewExternalStringLatin1, JS_NewExternalUCString};
+
+use crate::string::external::create_callbacks;
+use crate::utils::BoxExt;
+use crate::{Context, Error, Result, String};
+
+/// Maximum length of a segment in bytes.
+/// Once a segment is full, a new one is started, so that appending never copies more than this.
+const SEGMENT_CAPACITY: usize = 64 * 1024;
+
+enum Segment {
+	Latin1(Vec<u8>),
+	/// UTF-16 code units in native endianness, which may include unpaired surrogates.
+	Utf16(Vec<u8>),
+}
+
+/// Builds a [String] from Latin-1 and UTF-16 segments, without copying the characters whenever it grows.
+///
+/// When the builder is finished, each segment is moved into the JS Runtime as an external string, and the segments are
+/// concatenated into a rope.
+#[derive(Default)]
+pub struct StringBuilder {
+	segments: Vec<Segment>,
+	len: usize,
+}
+
+impl StringBuilder {
+	/// Creates an empty [StringBuilder].
+	pub fn new() -> StringBuilder {
+		StringBuilder::default()
+	}
+
+	/// Returns the length of the built string in UTF-16 code units.
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// Appends Latin-1 characters, which are widened to UTF-16 while the last segment has space for them.
+	pub fn push_latin1(&mut self, mut chars: &[u8]) {
+		self.len += chars.len();
+		if let Some(Segment::Utf16(units)) = self.segments.last_mut() {
+			let len = (SEGMENT_CAPACITY.saturating_sub(units.len()) / 2).min(chars.len());
+			units.extend(chars[..len].iter().flat_map(|char| u16::from(*char).to_ne_bytes()));
+			chars = &chars[len..];
+		}
+
+		while !chars.is_empty() {
+			let segment = self.segment(false);
+			let len = (SEGMENT_CAPACITY - segment.len()).min(chars.len());
+			segment.extend_from_slice(&chars[..len]);
+			chars = &chars[len..];
+		}
+	}
+
+	/// Appends UTF-16 code units, which may include unpaired surrogates.
+	pub fn push_utf16(&mut self, mut units: &[u16]) {
+		self.len += units.len();
+		while !units.is_empty() {
+			let segment = self.segment(true);
+			let len = ((SEGMENT_CAPACITY - segment.len()) / 2).min(units.len());
+			segment.extend(units[..len].iter().flat_map(|unit| unit.to_ne_bytes()));
+			units = &units[len..];
+		}
+	}
+
+	/// Appends a string, as Latin-1 if it only contains Latin-1 characters, and as UTF-16 otherwise.
+	pub fn push_str(&mut self, string: &str) {
+		if string.is_ascii() {
+			self.push_latin1(string.as_bytes());
+		} else if let Some(latin1) = string.chars().map(|char| u8::try_from(char).ok()).collect::<Option<Vec<_>>>() {
+			self.push_latin1(&latin1);
+		} else {
+			let utf16: Vec<u16> = string.encode_utf16().collect();
+			self.push_utf16(&utf16);
+		}
+	}
+
+	/// Appends the characters of a [String].
+	pub fn push_string(&mut self, cx: &Context, string: &String) {
+		match string.as_latin1(cx) {
+			Some(chars) => self.push_latin1(chars),
+			None => self.push_utf16(string.as_wtf16(cx).unwrap()),
+		}
+	}
+
+	/// Moves the segments into the JS Runtime, and concatenates them into a [String].
+	pub fn finish(self, cx: &Context) -> Result<String> {
+		let mut string: Option<String> = None;
+		for segment in self.segments {
+			let segment = new_external_string(cx, segment)?;
+			string = Some(match string {
+				Some(string) => {
+					let concatenated =
+						unsafe { JS_ConcatStrings(cx.as_ptr(), string.handle().into(), segment.handle().into()) };
+					root_string(cx, concatenated)?
+				}
+				None => segment,
+			});
+		}
+		Ok(string.unwrap_or_else(|| String::new(cx)))
+	}
+
+	/// Returns the last segment if it has the same encoding and is not full, or starts a new segment.
+	fn segment(&mut self, utf16: bool) -> &mut Vec<u8> {
+		let reusable = match self.segments.last() {
+			Some(Segment::Latin1(chars)) => !utf16 && chars.len() < SEGMENT_CAPACITY,
+			Some(Segment::Utf16(units)) => utf16 && units.len() < SEGMENT_CAPACITY,
+			None => false,
+		};
+		if !reusable {
+			self.segments.push(if utf16 { Segment::Utf16(Vec::new()) } else { Segment::Latin1(Vec::new()) });
+		}
+
+		match self.segments.last_mut().unwrap() {
+			Segment::Latin1(bytes) | Segment::Utf16(bytes) => bytes,
+		}
+	}
+}
+
+impl Write for StringBuilder {
+	fn write_str(&mut self, string: &str) -> fmt::Result {
+		self.push_str(string);
+		Ok(())
+	}
+}
+
+fn new_external_string(cx: &Context, segment: Segment) -> Result<String> {
+	unsafe {
+		match segment {
+			Segment::Latin1(chars) => {
+				let (chars, len) = Box::into_raw_parts(chars.into_boxed_slice());
+				let string = JS_NewExternalStringLatin1(cx.as_ptr(), chars, len, create_callbacks(len));
+				if string.is_null() {
+					let _ = Box::from_raw_parts(chars, len);
+				}
+				root_string(cx, string)
+			}
+			Segment::Utf16(units) => {
+				let (bytes, len) = Box::into_raw_parts(units.into_boxed_slice());
+				let chars = bytes.cast::<u16>();
+				let string = JS_NewExternalUCString(cx.as_ptr(), chars, len / 2, create_callbacks(len / 2));
+				if string.is_null() {
+					let _ = Box::from_raw_parts(bytes, len);
+				}
+				root_string(cx, string)
+			}
+		}
+	}
+}
+
+fn root_string(cx: &Context, string: *mut JSString) -> Result<String> {
+	if string.is_null() {
+		Err(Error::none())
+	} else {
+		Ok(String::from(cx.root(string)))
+	}
+}
@@ -0,0 +1,168 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::borrow::Borrow;
+use std::fmt;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::string::String as RustString;
+
+use arrayvec::ArrayString;
+
+use crate::{Context, Error, ErrorKind, Result, String};
+
+/// Maximum length in bytes of a [CompactString] which is stored inline.
+pub const INLINE_CAPACITY: usize = 24;
+
+#[derive(Clone)]
+enum Repr {
+	Inline(ArrayString<INLINE_CAPACITY>),
+	Heap(Box<str>),
+}
+
+/// Immutable string which stores up to [INLINE_CAPACITY] bytes inline, rather than allocating.
+///
+/// Converting short strings from JS, such as property keys, into a [CompactString] avoids an allocation per string.
+#[derive(Clone)]
+pub struct CompactString(Repr);
+
+impl CompactString {
+	/// Creates an empty [CompactString].
+	pub fn new() -> CompactString {
+		CompactString(Repr::Inline(ArrayString::new()))
+	}
+
+	/// Converts a [String] into a [CompactString], storing it inline if it is short enough.
+	pub fn from_string(cx: &Context, string: &String) -> Result<CompactString> {
+		let mut inline = ArrayString::new();
+		if let Some(chars) = string.as_latin1(cx) {
+			if chars.len() <= INLINE_CAPACITY {
+				if chars.iter().all(|char| inline.try_push(char::from(*char)).is_ok()) {
+					return Ok(CompactString(Repr::Inline(inline)));
+				}
+				inline.clear();
+			}
+		} else {
+			let units = string.as_wtf16(cx).unwrap();
+			// Each code unit is encoded as at least one byte in UTF-8.
+			if units.len() <= INLINE_CAPACITY {
+				for char in char::decode_utf16(units.iter().copied()) {
+					let char =
+						char.map_err(|_| Error::new("String contains invalid UTF-16 codepoints", ErrorKind::Type))?;
+					if inline.try_push(char).is_err() {
+						return string.to_owned(cx).map(CompactString::from);
+					}
+				}
+				return Ok(CompactString(Repr::Inline(inline)));
+			}
+		}
+		string.to_owned(cx).map(CompactString::from)
+	}
+
+	/// Checks if the string is stored inline.
+	pub fn is_inline(&self) -> bool {
+		matches!(self.0, Repr::Inline(_))
+	}
+
+	pub fn as_str(&self) -> &str {
+		match &self.0 {
+			Repr::Inline(string) => string.as_str(),
+			Repr::Heap(string) => string,
+		}
+	}
+}
+
+impl Default for CompactString {
+	fn default() -> CompactString {
+		CompactString::new()
+	}
+}
+
+impl From<&str> for CompactString {
+	fn from(string: &str) -> CompactString {
+		match ArrayString::from(string) {
+			Ok(string) => CompactString(Repr::Inline(string)),
+			Err(_) => CompactString(Repr::Heap(Box::from(string))),
+		}
+	}
+}
+
+impl From<RustString> for CompactString {
+	fn from(string: RustString) -> CompactString {
+		match ArrayString::from(&string) {
+			Ok(string) => CompactString(Repr::Inline(string)),
+			Err(_) => CompactString(Repr::Heap(string.into_boxed_str())),
+		}
+	}
+}
+
+impl From<CompactString> for RustString {
+	fn from(string: CompactString) -> RustString {
+		match string.0 {
+			Repr::Inline(string) => RustString::from(string.as_str()),
+			Repr::Heap(string) => RustString::from(string),
+		}
+	}
+}
+
+impl Deref for CompactString {
+	type Target = str;
+
+	fn deref(&self) -> &str {
+		self.as_str()
+	}
+}
+
+impl AsRef<str> for CompactString {
+	fn as_ref(&self) -> &str {
+		self.as_str()
+	}
+}
+
+impl Borrow<str> for CompactString {
+	fn borrow(&self) -> &str {
+		self.as_str()
+	}
+}
+
+impl PartialEq for CompactString {
+	fn eq(&self, other: &CompactString) -> bool {
+		self.as_str() == other.as_str()
+	}
+}
+
+impl Eq for CompactString {}
+
+impl PartialEq<str> for CompactString {
+	fn eq(&self, other: &str) -> bool {
+		self.as_str() == other
+	}
+}
+
+impl PartialEq<&str> for CompactString {
+	fn eq(&self, other: &&str) -> bool {
+		self.as_str() == *other
+	}
+}
+
+impl Hash for CompactString {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.as_str().hash(state);
+	}
+}
+
+impl Debug for CompactString {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		Debug::fmt(self.as_str(), f)
+	}
+}
+
+impl Display for CompactString {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
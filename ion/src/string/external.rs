@@ -41,6 +41,30 @@ mod utf16 {
 	}
 }
 
+pub(super) struct FinalisedData {
+	len: usize,
+	finaliser: Box<dyn FnOnce() + Send>,
+}
+
+mod finalised_latin1 {
+	use std::ffi::c_void;
+
+	use mozjs::jsapi::MallocSizeOf;
+
+	use crate::string::external::FinalisedData;
+	use crate::utils::BoxExt;
+
+	pub(crate) unsafe extern "C" fn finalise(data: *const c_void, chars: *mut u8) {
+		let data = unsafe { Box::from_raw(data.cast::<FinalisedData>().cast_mut()) };
+		let _ = unsafe { Box::from_raw_parts(chars, data.len) };
+		(data.finaliser)();
+	}
+
+	pub(crate) extern "C" fn size_of(data: *const c_void, _: *const u8, _: MallocSizeOf) -> usize {
+		unsafe { (*data.cast::<FinalisedData>()).len }
+	}
+}
+
 static EXTERNAL_STRING_CALLBACKS_TRAPS: JSExternalStringCallbacksTraps = JSExternalStringCallbacksTraps {
 	latin1Finalize: Some(latin1::finalise),
 	latin1SizeOfBuffer: Some(latin1::size_of),
@@ -51,3 +75,24 @@ static EXTERNAL_STRING_CALLBACKS_TRAPS: JSExternalStringCallbacksTraps = JSExter
 pub(super) fn create_callbacks(len: usize) -> *mut JSExternalStringCallbacks {
 	unsafe { CreateJSExternalStringCallbacks(&EXTERNAL_STRING_CALLBACKS_TRAPS, len as *mut c_void) }
 }
+
+static FINALISED_STRING_CALLBACKS_TRAPS: JSExternalStringCallbacksTraps = JSExternalStringCallbacksTraps {
+	latin1Finalize: Some(finalised_latin1::finalise),
+	latin1SizeOfBuffer: Some(finalised_latin1::size_of),
+	utf16Finalize: None,
+	utf16SizeOfBuffer: None,
+};
+
+/// Creates callbacks for a Latin-1 string which call `finaliser` when the string is finalised.
+/// Returns the data passed to the callbacks, which must be freed with [free_finalised_data] if string creation fails.
+pub(super) fn create_finalised_callbacks(
+	len: usize, finaliser: Box<dyn FnOnce() + Send>,
+) -> (*mut JSExternalStringCallbacks, *mut FinalisedData) {
+	let data = Box::into_raw(Box::new(FinalisedData { len, finaliser }));
+	let callbacks = unsafe { CreateJSExternalStringCallbacks(&FINALISED_STRING_CALLBACKS_TRAPS, data.cast()) };
+	(callbacks, data)
+}
+
+pub(super) unsafe fn free_finalised_data(data: *mut FinalisedData) {
+	let _ = unsafe { Box::from_raw(data) };
+}
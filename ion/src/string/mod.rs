@@ -8,21 +8,27 @@ use std::ops::{Deref, DerefMut, Range};
 use std::string::String as RustString;
 use std::{ptr, slice};
 
+pub use builder::StringBuilder;
 use bytemuck::cast_slice;
 use byteorder::NativeEndian;
+pub use compact::CompactString;
+use encoding_rs::mem::{convert_latin1_to_utf8, convert_utf16_to_utf8, utf16_valid_up_to};
 use mozjs::jsapi::{
 	JSString, JS_CompareStrings, JS_ConcatStrings, JS_DeprecatedStringHasLatin1Chars, JS_GetEmptyString,
 	JS_GetLatin1StringCharsAndLength, JS_GetStringCharAt, JS_GetTwoByteStringCharsAndLength, JS_NewDependentString,
-	JS_NewExternalStringLatin1, JS_NewExternalUCString, JS_NewUCStringCopyN, JS_StringIsLinear,
+	JS_NewExternalStringLatin1, JS_NewExternalUCString, JS_NewStringCopyN, JS_NewUCStringCopyN,
+	JS_StringIsLinear,
 };
 use utf16string::{WStr, WString};
 
 use crate::string::byte::{ByteStr, ByteString, Latin1};
-use crate::string::external::create_callbacks;
+use crate::string::external::{create_callbacks, create_finalised_callbacks, free_finalised_data};
 use crate::utils::BoxExt;
 use crate::{Context, Error, ErrorKind, Local};
 
+mod builder;
 pub mod byte;
+pub mod compact;
 mod external;
 
 #[derive(Copy, Clone, Debug)]
@@ -70,9 +76,17 @@ impl<'s> String<'s> {
 	}
 
 	/// Creates a new [String] with a given string, by copying it to the JS Runtime.
+	///
+	/// Strings which only contain Latin-1 characters are copied as Latin-1, rather than being widened to UTF-16.
 	pub fn copy_from_str<'cx>(cx: &'cx Context, string: &str) -> Option<String<'cx>> {
-		let utf16: Vec<u16> = string.encode_utf16().collect();
-		let jsstr = unsafe { JS_NewUCStringCopyN(cx.as_ptr(), utf16.as_ptr(), utf16.len()) };
+		let jsstr = if string.is_ascii() {
+			unsafe { JS_NewStringCopyN(cx.as_ptr(), string.as_ptr().cast(), string.len()) }
+		} else if let Ok(latin1) = string.chars().map(u8::try_from).collect::<Result<Vec<_>, _>>() {
+			unsafe { JS_NewStringCopyN(cx.as_ptr(), latin1.as_ptr().cast(), latin1.len()) }
+		} else {
+			let utf16: Vec<u16> = string.encode_utf16().collect();
+			unsafe { JS_NewUCStringCopyN(cx.as_ptr(), utf16.as_ptr(), utf16.len()) }
+		};
 		if jsstr.is_null() {
 			None
 		} else {
@@ -99,6 +113,29 @@ impl<'s> String<'s> {
 		}
 	}
 
+	/// Creates a new string like [String::from_latin1], which calls `finaliser` once it has been garbage collected.
+	///
+	/// Strings derived from the string, such as by concatenation, may outlive it.
+	pub fn from_latin1_with_finaliser<F: FnOnce() + Send + 'static>(
+		cx: &Context, string: ByteString<Latin1>, finaliser: F,
+	) -> Result<String, ByteString<Latin1>> {
+		let bytes = string.into_vec().into_boxed_slice();
+		let (chars, len) = unsafe { Box::into_raw_parts(bytes) };
+
+		unsafe {
+			let (callbacks, data) = create_finalised_callbacks(len, Box::new(finaliser));
+			let jsstr = JS_NewExternalStringLatin1(cx.as_ptr(), chars, len, callbacks);
+
+			if jsstr.is_null() {
+				free_finalised_data(data);
+				let bytes = Box::from_raw_parts(chars, len).into_vec();
+				Err(ByteString::from_unchecked(bytes))
+			} else {
+				Ok(String::from(cx.root(jsstr)))
+			}
+		}
+	}
+
 	/// Creates a new string by moving ownership of the UTF-16 string to the JS Runtime temporarily.
 	/// Returns the string if the creation of the string in the runtime fails.
 	pub fn from_wstring(cx: &Context, string: WString<NativeEndian>) -> Result<String, WString<NativeEndian>> {
@@ -207,16 +244,35 @@ impl<'s> String<'s> {
 	/// Converts a [String] to an owned [String](RustString).
 	pub fn to_owned(&self, cx: &Context) -> crate::Result<RustString> {
 		if let Some(chars) = self.as_latin1(cx) {
-			let mut string = RustString::with_capacity(chars.len());
-			string.extend(chars.iter().map(|c| *c as char));
-			Ok(string)
+			if chars.is_ascii() {
+				// ASCII is valid UTF-8, so the characters can be copied as is.
+				return Ok(unsafe { RustString::from_utf8_unchecked(chars.to_vec()) });
+			}
+			// Latin-1 characters are encoded as at most two bytes in UTF-8.
+			let mut bytes = vec![0; chars.len() * 2];
+			let len = convert_latin1_to_utf8(chars, &mut bytes);
+			Ok(unsafe { RustString::from_utf8_unchecked(shrink(bytes, len)) })
 		} else {
-			let string = self.as_wstr(cx)?.unwrap();
-			Ok(string.to_utf8())
+			let chars = self.as_wtf16(cx).unwrap();
+			if utf16_valid_up_to(chars) != chars.len() {
+				return Err(Error::new("String contains invalid UTF-16 codepoints", ErrorKind::Type));
+			}
+			// UTF-16 code units are encoded as at most three bytes in UTF-8.
+			let mut bytes = vec![0; chars.len() * 3];
+			let len = convert_utf16_to_utf8(chars, &mut bytes);
+			Ok(unsafe { RustString::from_utf8_unchecked(shrink(bytes, len)) })
 		}
 	}
 }
 
+/// Truncates a buffer sized for the worst case of a conversion to the length of its output, and releases the unused
+/// capacity, as owned strings are often kept, such as in [OwnedKeys](crate::OwnedKey).
+fn shrink(mut bytes: Vec<u8>, len: usize) -> Vec<u8> {
+	bytes.truncate(len);
+	bytes.shrink_to_fit();
+	bytes
+}
+
 impl<'s> From<Local<'s, *mut JSString>> for String<'s> {
 	fn from(str: Local<'s, *mut JSString>) -> String<'s> {
 		String { str }
@@ -0,0 +1,76 @@
+use std::path::Path;
+
+use ion::class::Reflector;
+use ion::conversions::{ConversionBehavior, FromValue};
+use ion::function::NewTarget;
+use ion::script::Script;
+use ion::utils::test::TestRuntime;
+use ion::{js_class, ClassDefinition, Context, Function, Object, Result};
+
+const SCRIPT: &str = r#"
+class Square extends Shape {
+	constructor() {
+		super(4);
+	}
+
+	get area() {
+		return 1;
+	}
+}
+
+const square = new Square();
+[
+	square instanceof Square,
+	Object.getPrototypeOf(square) === Square.prototype,
+	square.area,
+	square.sides,
+	square.constructorName,
+	new Shape().sides,
+].join(",")
+"#;
+
+#[js_class]
+pub struct Shape {
+	reflector: Reflector,
+	#[ion(readonly)]
+	sides: u32,
+	#[ion(readonly)]
+	constructor_name: String,
+}
+
+#[js_class]
+impl Shape {
+	#[ion(constructor)]
+	pub fn empty() -> Shape {
+		Shape {
+			reflector: Reflector::default(),
+			sides: 0,
+			constructor_name: String::from("Shape"),
+		}
+	}
+
+	#[ion(constructor)]
+	pub fn with_sides(
+		cx: &Context, NewTarget(new_target): NewTarget, #[ion(convert = ConversionBehavior::EnforceRange)] sides: u32,
+	) -> Result<Shape> {
+		let constructor = Function::from_object(cx, &new_target).unwrap();
+		Ok(Shape {
+			reflector: Reflector::default(),
+			sides,
+			constructor_name: constructor.name(cx)?,
+		})
+	}
+}
+
+#[test]
+fn subclass() {
+	let rt = TestRuntime::new();
+	let cx = &rt.cx;
+
+	let global = Object::from(cx.root(rt.global));
+	assert!(Shape::init_class(cx, &global).0);
+
+	let result = Script::compile_and_evaluate(cx, Path::new("class.js"), SCRIPT).unwrap();
+	let result = String::from_value(cx, &result, true, ()).unwrap();
+	assert_eq!(result, "true,true,1,4,Square,0");
+}
@@ -0,0 +1,29 @@
+use ion::conversions::{FromValue, ToValue};
+use ion::string::{CompactString, StringBuilder};
+use ion::utils::test::TestRuntime;
+use ion::{String, Value};
+
+#[test]
+fn compact_string() {
+	let rt = TestRuntime::new();
+	let cx = &rt.cx;
+
+	let long = "0123456789".repeat(4);
+	let cases = [("length", true), ("clé", true), ("🦀 crab", true), (long.as_str(), false)];
+	for (input, inline) in cases {
+		let value = Value::string(cx, input);
+		let string = CompactString::from_value(cx, &value, true, ()).unwrap();
+		assert_eq!(string, input);
+		assert_eq!(string.is_inline(), inline);
+		assert_eq!(<std::string::String>::from_value(cx, &value, true, ()).unwrap(), input);
+
+		let value = string.as_value(cx);
+		assert_eq!(String::from_value(cx, &value, true, ()).unwrap().to_owned(cx).unwrap(), input);
+	}
+
+	let mut builder = StringBuilder::new();
+	builder.push_utf16(&[0x61, 0xD83E]);
+	let value = builder.finish(cx).unwrap().as_value(cx);
+	assert!(CompactString::from_value(cx, &value, true, ()).is_err());
+	assert!(<std::string::String>::from_value(cx, &value, true, ()).is_err());
+}
@@ -0,0 +1,59 @@
+use std::path::Path;
+
+use ion::script::Script;
+use ion::utils::test::TestRuntime;
+use ion::{Error, Exception, ThrowException};
+
+const FILE_NAME: &str = "exception.js";
+
+#[test]
+fn pending_stack() {
+	let rt = TestRuntime::new();
+	let cx = &rt.cx;
+
+	let error = Script::compile_and_evaluate(cx, Path::new(FILE_NAME), "\nnew Error(\"created\")").unwrap();
+	Exception::from_value(cx, &error).unwrap().throw(cx);
+
+	let exception = Exception::new(cx).unwrap().unwrap();
+	assert!(!Exception::is_pending(cx));
+	let stack = exception.stack(cx).unwrap();
+	assert_eq!(stack.records[0].location.file, FILE_NAME);
+	assert_eq!(stack.records[0].location.lineno, 2);
+}
+
+#[test]
+fn report_into_exception() {
+	let rt = TestRuntime::new();
+	let cx = &rt.cx;
+
+	let source = "function thrower() {\n\tthrow new Error(\"thrown\");\n}\nthrower();";
+	let report = Script::compile_and_evaluate(cx, Path::new(FILE_NAME), source).unwrap_err();
+	let records = report.stack.as_ref().unwrap().records.len();
+
+	let Exception::Error(error) = report.into_exception() else {
+		panic!("Expected Error");
+	};
+	assert!(error.stack.is_some());
+	let stack = Exception::Error(error.clone()).stack(cx).unwrap();
+	assert_eq!(stack.records.len(), records);
+
+	let mut native = Error::new("native", None);
+	native.stack = error.stack;
+	let object = native.to_object(cx).unwrap();
+	let Exception::Error(native) = Exception::from_object(cx, &object).unwrap() else {
+		panic!("Expected Error");
+	};
+	assert_eq!(native.location.unwrap().lineno, 2);
+}
+
+#[test]
+fn notes() {
+	let error = Error::new("Failed", None).note("while reading").note("while piping");
+	let expected = "Error - Failed\nNote: while reading\nNote: while piping";
+	assert_eq!(error.format(), expected);
+
+	let Exception::Error(error) = Exception::from(error) else {
+		panic!("Expected Error");
+	};
+	assert_eq!(error.notes, ["while reading", "while piping"]);
+}
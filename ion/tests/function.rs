@@ -0,0 +1,43 @@
+use ion::conversions::{ConversionBehavior, FromValue};
+use ion::function::CompileOptions;
+use ion::utils::test::TestRuntime;
+use ion::{ErrorKind, Exception, Function, Object, Value};
+
+#[test]
+fn compile() {
+	let rt = TestRuntime::new();
+	let cx = &rt.cx;
+
+	let add = Function::compile(cx, "add", &["a", "b"], "return a + b;", CompileOptions::default()).unwrap();
+	assert_eq!(add.name(cx).unwrap(), "add");
+	assert_eq!(add.nargs(), 2);
+
+	let args = [Value::i32(cx, 1), Value::i32(cx, 2)];
+	let result = add.call(cx, &Object::global(cx), &args).unwrap();
+	let result = i32::from_value(cx, &result, true, ConversionBehavior::EnforceRange).unwrap();
+	assert_eq!(result, 3);
+
+	let options = CompileOptions { filename: "thrower.js", line: 10 };
+	let thrower = Function::compile(cx, "thrower", &[], "throw new Error(\"thrown\");", options).unwrap();
+	let report = thrower.call(cx, &Object::global(cx), &[]).unwrap_err().unwrap();
+	let stack = report.stack.unwrap();
+	assert_eq!(stack.records[0].function.as_deref(), Some("thrower"));
+	assert_eq!(stack.records[0].location.file, "thrower.js");
+	assert!(stack.records[0].location.lineno >= 10);
+
+	let report = Function::compile(cx, "invalid", &[], "return (;", options).unwrap_err();
+	let Exception::Error(error) = report.exception else {
+		panic!("Expected Error");
+	};
+	assert_eq!(error.kind, ErrorKind::Syntax);
+	assert_eq!(error.location.unwrap().file, "thrower.js");
+
+	let name = Function::compile(cx, "nul\0", &[], "", CompileOptions::default()).unwrap_err();
+	let param = Function::compile(cx, "nul", &["a\0"], "", CompileOptions::default()).unwrap_err();
+	for report in [name, param] {
+		let Exception::Error(error) = report.exception else {
+			panic!("Expected Error");
+		};
+		assert_eq!(error.kind, ErrorKind::Syntax);
+	}
+}
@@ -0,0 +1,28 @@
+use ion::conversions::ConversionBehavior;
+use ion::utils::test::TestRuntime;
+use ion::{Object, OwnedKey, PropertyKey};
+
+#[test]
+fn interned_keys() {
+	let rt = TestRuntime::new();
+	let cx = &rt.cx;
+	let object = Object::new(cx);
+
+	for i in 0..4 {
+		object.set_as(cx, "value", &i);
+		let value: i32 = object.get_as(cx, "value", true, ConversionBehavior::EnforceRange).unwrap().unwrap();
+		assert_eq!(i, value);
+	}
+
+	let long = "k".repeat(128);
+	for key in ["done", "content-type", "clé", long.as_str()] {
+		object.set_as(cx, key, &true);
+		assert!(object.has_own(cx, key));
+
+		let key = PropertyKey::with_string(cx, key).unwrap();
+		assert!(matches!(key.to_owned_key(cx).unwrap(), OwnedKey::String(_)));
+	}
+
+	let key = PropertyKey::with_string(cx, "0").unwrap();
+	assert!(matches!(key.to_owned_key(cx).unwrap(), OwnedKey::Int(0)));
+}
@@ -0,0 +1,60 @@
+use std::path::Path;
+
+use ion::conversions::FromValue;
+use ion::job_queue::JobQueue;
+use ion::script::Script;
+use ion::utils::test::TestRuntime;
+use ion::{Context, Error, Value};
+
+const SCRIPT: &str = r#"
+globalThis.order = [];
+Promise.resolve().then(() => order.push("promise"));
+"#;
+
+#[test]
+fn job_queue() {
+	let rt = TestRuntime::new();
+	let cx = &rt.cx;
+
+	let queue = JobQueue::install(cx);
+	assert!(queue.is_empty());
+
+	evaluate(cx, SCRIPT);
+	queue.enqueue_native(cx, |cx| {
+		let queue = JobQueue::get(cx).unwrap();
+		assert!(queue.is_draining());
+
+		evaluate(cx, r#"order.push("native"); Promise.resolve().then(() => order.push("nested"));"#);
+		queue.checkpoint(cx).unwrap();
+		assert_eq!(order(cx), "promise,native");
+		Ok(())
+	});
+	assert_eq!(queue.len(), 2);
+
+	queue.checkpoint(cx).unwrap();
+	assert!(queue.is_empty());
+	assert!(!queue.is_draining());
+	assert_eq!(order(cx), "promise,native,nested");
+
+	queue.enqueue_native(cx, |_| Err(Error::new("Native Job Failed", None).into()));
+	queue.enqueue_native(cx, |cx| {
+		evaluate(cx, r#"order.push("after")"#);
+		Ok(())
+	});
+
+	let report = queue.checkpoint(cx).unwrap_err().unwrap();
+	assert!(report.format(cx).contains("Native Job Failed"));
+	assert_eq!(queue.len(), 1);
+
+	queue.checkpoint(cx).unwrap();
+	assert_eq!(order(cx), "promise,native,nested,after");
+}
+
+fn order(cx: &Context) -> String {
+	let order = evaluate(cx, r#"order.join(",")"#);
+	String::from_value(cx, &order, true, ()).unwrap()
+}
+
+fn evaluate<'cx>(cx: &'cx Context, source: &str) -> Value<'cx> {
+	Script::compile_and_evaluate(cx, Path::new("job_queue.js"), source).unwrap()
+}
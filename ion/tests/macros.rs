@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use ion::class::Reflector;
+use ion::conversions::{ConversionBehavior, FromValue};
+use ion::script::Script;
+use ion::utils::test::TestRuntime;
+use ion::{js_class, ClassDefinition, Context, Error, ErrorKind, FromValue, Object, Value};
+use mozjs::jsapi::{GCReason, Heap, JS_GC};
+use mozjs::jsval::JSVal;
+
+const SCRIPT: &str = r#"
+const store = new Store();
+store.set("objects", { number: 1 });
+store.set("objects", { number: 2 });
+store.set("arrays", [3]);
+"#;
+
+const CHECK: &str = r#"
+[store.size, store.get("objects", 0).number, store.get("objects", 1).number, store.get("arrays", 0)[0]].join(",")
+"#;
+
+#[derive(Debug, FromValue, PartialEq)]
+#[ion(tag = "kind")]
+enum Message {
+	Text { text: String },
+	Flag { flag: bool },
+}
+
+#[derive(Debug, FromValue, PartialEq)]
+#[repr(u8)]
+enum Level {
+	Low = 1,
+	High = 2,
+}
+
+#[derive(Debug, FromValue, PartialEq)]
+#[ion(validate = validate_range)]
+struct Range {
+	start: u32,
+	end: u32,
+	#[ion(default = 1)]
+	step: u32,
+}
+
+fn validate_range(_: &Context, range: &Range) -> ion::Result<()> {
+	if range.start <= range.end {
+		Ok(())
+	} else {
+		Err(Error::new("start must not be greater than end", ErrorKind::Range))
+	}
+}
+
+#[js_class]
+pub struct Store {
+	reflector: Reflector,
+	values: HashMap<String, Vec<Box<Heap<JSVal>>>>,
+}
+
+#[js_class]
+impl Store {
+	#[ion(constructor)]
+	pub fn constructor() -> Store {
+		Store {
+			reflector: Reflector::default(),
+			values: HashMap::new(),
+		}
+	}
+
+	pub fn set(&mut self, key: String, value: Value) {
+		self.values.entry(key).or_default().push(Heap::boxed(value.get()));
+	}
+
+	pub fn get(&self, key: String, #[ion(convert = ConversionBehavior::EnforceRange)] index: u32) -> Option<JSVal> {
+		let values = self.values.get(&key)?;
+		values.get(index as usize).map(|value| value.get())
+	}
+
+	#[ion(get)]
+	pub fn get_size(&self) -> u32 {
+		self.values.values().map(Vec::len).sum::<usize>() as u32
+	}
+}
+
+#[test]
+fn ui() {
+	let cases = trybuild::TestCases::new();
+	cases.compile_fail("tests/ui/**/*.rs");
+}
+
+#[test]
+fn round_trip() {
+	let rt = TestRuntime::new();
+	let cx = &rt.cx;
+
+	let message = evaluate(cx, r#"({ kind: "Text", text: "Hello" })"#);
+	let message = Message::from_value(cx, &message, true, ()).unwrap();
+	assert_eq!(message, Message::Text { text: String::from("Hello") });
+
+	let message = evaluate(cx, r#"({ kind: "Flag", flag: true })"#);
+	let message = Message::from_value(cx, &message, true, ()).unwrap();
+	assert_eq!(message, Message::Flag { flag: true });
+
+	let message = evaluate(cx, r#"({ kind: "Other" })"#);
+	assert!(Message::from_value(cx, &message, true, ()).is_err());
+
+	let level = evaluate(cx, "2");
+	assert_eq!(Level::from_value(cx, &level, true, ()).unwrap(), Level::High);
+	let level = evaluate(cx, "3");
+	assert!(Level::from_value(cx, &level, true, ()).is_err());
+
+	let global = Object::from(cx.root(rt.global));
+	assert!(Store::init_class(cx, &global).0);
+
+	evaluate(cx, SCRIPT);
+	unsafe { JS_GC(cx.as_ptr(), GCReason::API) };
+
+	let result = evaluate(cx, CHECK);
+	let result = String::from_value(cx, &result, true, ()).unwrap();
+	assert_eq!(result, "3,1,2,3");
+}
+
+#[test]
+fn dictionary() {
+	let rt = TestRuntime::new();
+	let cx = &rt.cx;
+
+	let range = evaluate(cx, "({ start: 1, end: 4 })");
+	let range = Range::from_value(cx, &range, true, ()).unwrap();
+	assert_eq!(range, Range { start: 1, end: 4, step: 1 });
+
+	let range = evaluate(cx, "({ start: 4, end: 1 })");
+	let error = Range::from_value(cx, &range, true, ()).unwrap_err();
+	assert_eq!(error.kind, ErrorKind::Range);
+
+	let range = evaluate(cx, r#"({ start: "1", step: 2 })"#);
+	let error = Range::from_value(cx, &range, true, ()).unwrap_err();
+	assert_eq!(error.kind, ErrorKind::Type);
+	assert!(error.message.starts_with("2 invalid members in Range: start: "));
+	assert!(error.message.contains("; end: "));
+}
+
+fn evaluate<'cx>(cx: &'cx Context, source: &str) -> Value<'cx> {
+	Script::compile_and_evaluate(cx, Path::new("macros.js"), source).unwrap()
+}
@@ -0,0 +1,54 @@
+use std::path::Path;
+
+use ion::conversions::{FromValue, ToValue};
+use ion::job_queue::JobQueue;
+use ion::script::Script;
+use ion::utils::test::TestRuntime;
+use ion::{Context, Error, Promise, Value};
+use mozjs::jsapi::PromiseState;
+
+#[test]
+fn new_with_executor() {
+	let rt = TestRuntime::new();
+	let cx = &rt.cx;
+
+	let promise = Promise::new_with_executor(cx, |resolve, reject| {
+		resolve(&Value::i32(cx, 1));
+		reject(&Value::i32(cx, 2));
+		resolve(&Value::i32(cx, 3));
+		Ok(())
+	});
+	assert_eq!(promise.state(), PromiseState::Fulfilled);
+	assert_eq!(promise.result(cx).handle().to_int32(), 1);
+
+	let promise = Promise::new_with_executor(cx, |_, _| Err(Error::new("Executor Failed", None).into()));
+	assert_eq!(promise.state(), PromiseState::Rejected);
+}
+
+#[test]
+fn adopt() {
+	let rt = TestRuntime::new();
+	let cx = &rt.cx;
+	let queue = JobQueue::install(cx);
+
+	let value = Value::i32(cx, 1);
+	let promise = Promise::adopt(cx, &value);
+	assert_eq!(promise.state(), PromiseState::Fulfilled);
+	assert_eq!(Promise::adopt(cx, &promise.as_value(cx)).get(), promise.get());
+
+	let thenable = evaluate(cx, "({ then(resolve) { resolve(2); } })");
+	let promise = Promise::adopt(cx, &thenable);
+	assert_eq!(promise.state(), PromiseState::Pending);
+	queue.checkpoint(cx).unwrap();
+	assert_eq!(promise.state(), PromiseState::Fulfilled);
+	let result = i32::from_value(cx, &promise.result(cx), true, Default::default()).unwrap();
+	assert_eq!(result, 2);
+
+	let thenable = evaluate(cx, r#"({ get then() { throw new Error("Getter Failed"); } })"#);
+	let promise = Promise::adopt(cx, &thenable);
+	assert_eq!(promise.state(), PromiseState::Rejected);
+}
+
+fn evaluate<'cx>(cx: &'cx Context, source: &str) -> Value<'cx> {
+	Script::compile_and_evaluate(cx, Path::new("promise.js"), source).unwrap()
+}
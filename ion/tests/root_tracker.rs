@@ -0,0 +1,29 @@
+use ion::utils::test::TestRuntime;
+use ion::{live_boxed_heaps, live_roots, BoxedHeap, Object, TracedHeap};
+
+#[test]
+fn root_tracker() {
+	let rt = TestRuntime::new();
+	let cx = &rt.cx;
+	let before = live_roots().len();
+	let boxed_before = live_boxed_heaps().len();
+
+	let object = Object::new(cx);
+	let heap = TracedHeap::new(object.handle().get());
+	let boxed = BoxedHeap::new(object.handle().get());
+	assert_eq!(boxed.get(), object.handle().get());
+	if cfg!(debug_assertions) {
+		let roots = live_roots();
+		assert_eq!(roots.len(), before + 1);
+		assert!(roots.iter().any(|root| root.type_name.contains("Heap")));
+		assert_eq!(live_boxed_heaps().len(), boxed_before + 1);
+	} else {
+		assert!(live_roots().is_empty());
+		assert!(live_boxed_heaps().is_empty());
+	}
+
+	drop(heap);
+	drop(boxed);
+	assert_eq!(live_roots().len(), before);
+	assert_eq!(live_boxed_heaps().len(), boxed_before);
+}
@@ -0,0 +1,29 @@
+use ion::conversions::{ConversionBehavior, FromValue};
+use ion::utils::test::TestRuntime;
+use ion::{Array, Value};
+
+#[test]
+fn scope() {
+	let rt = TestRuntime::new();
+	let cx = &rt.cx;
+
+	let array = Array::new(cx);
+	for i in 0..1000 {
+		unsafe {
+			cx.scope(|cx| {
+				let value = Value::i32(cx, i);
+				assert!(array.set(cx, i as u32, &value));
+			})
+		};
+	}
+
+	let sum = unsafe {
+		cx.scope(|cx| {
+			(0..array.len(cx)).fold(0, |sum, i| {
+				let value = array.get(cx, i).unwrap().unwrap();
+				sum + i32::from_value(cx, &value, true, ConversionBehavior::EnforceRange).unwrap()
+			})
+		})
+	};
+	assert_eq!(sum, (0..1000).sum::<i32>());
+}
@@ -0,0 +1,35 @@
+use std::path::{Path, PathBuf};
+
+use ion::conversions::{ConversionBehavior, FromValue};
+use ion::script::Stencil;
+use ion::utils::test::TestRuntime;
+use ion::{default_new_global, ErrorKind};
+use mozjs::jsapi::JSAutoRealm;
+
+const SCRIPT: &str = "[1, 2, 3].reduce((a, b) => a + b, 0)";
+
+#[test]
+fn stencil() {
+	let rt = TestRuntime::new();
+	let cx = &rt.cx;
+
+	let stencil = Stencil::compile(cx, Path::new("stencil.js"), SCRIPT).unwrap();
+
+	let result = stencil.instantiate(cx).unwrap().evaluate(cx).unwrap();
+	assert_eq!(6, i32::from_value(cx, &result, true, ConversionBehavior::EnforceRange).unwrap());
+
+	let global = default_new_global(cx);
+	let _realm = JSAutoRealm::new(cx.as_ptr(), global.handle().get());
+	let result = stencil.instantiate(cx).unwrap().evaluate(cx).unwrap();
+	assert_eq!(6, i32::from_value(cx, &result, true, ConversionBehavior::EnforceRange).unwrap());
+
+	let compilation = Stencil::compile_off_thread(&rt.runtime, PathBuf::from("stencil.js"), String::from(SCRIPT));
+	let stencil = compilation.join().unwrap();
+	let result = stencil.instantiate(cx).unwrap().evaluate(cx).unwrap();
+	assert_eq!(6, i32::from_value(cx, &result, true, ConversionBehavior::EnforceRange).unwrap());
+
+	let compilation = Stencil::compile_off_thread(&rt.runtime, PathBuf::from("error.js"), String::from("let = ;"));
+	let error = compilation.join().err().unwrap();
+	assert_eq!(ErrorKind::Syntax, error.kind);
+	assert_eq!("error.js", error.location.unwrap().file);
+}
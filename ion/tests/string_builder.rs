@@ -0,0 +1,59 @@
+use std::fmt::Write;
+
+use ion::format::{format_value, Config};
+use ion::string::StringBuilder;
+use ion::utils::test::TestRuntime;
+use ion::{Object, Value};
+
+#[test]
+fn string_builder() {
+	let rt = TestRuntime::new();
+	let cx = &rt.cx;
+
+	assert_eq!(StringBuilder::new().finish(cx).unwrap().to_owned(cx).unwrap(), "");
+
+	let mut builder = StringBuilder::new();
+	builder.push_str("ascii, ");
+	builder.push_str("clé, ");
+	builder.push_str("🦀, ");
+	builder.push_latin1(b"latin1");
+	write!(builder, " {}", 42).unwrap();
+	assert_eq!(builder.len(), "ascii, clé, 🦀, latin1 42".encode_utf16().count());
+
+	let string = builder.finish(cx).unwrap();
+	assert!(string.is_utf16());
+	assert_eq!(string.to_owned(cx).unwrap(), "ascii, clé, 🦀, latin1 42");
+
+	let long = "0123456789".repeat(20_000);
+	let mut builder = StringBuilder::new();
+	builder.push_str(&long);
+	builder.push_utf16(&[0xD83E, 0xDD80]);
+	builder.push_str(&long);
+	let string = builder.finish(cx).unwrap();
+	assert_eq!(string.to_owned(cx).unwrap(), format!("{long}🦀{long}"));
+}
+
+#[test]
+fn format_to_js_string() {
+	let rt = TestRuntime::new();
+	let cx = &rt.cx;
+
+	let object = Object::new(cx);
+	object.set_as(cx, "key", "value");
+	let value = Value::object(cx, &object);
+
+	let config = Config::default().multiline(false);
+	let expected = format_value(cx, config, &value).to_string();
+	let string = format_value(cx, config, &value).to_js_string().unwrap();
+	assert_eq!(string.to_owned(cx).unwrap(), expected);
+
+	// Strings are formatted in chunks, without splitting surrogate pairs.
+	let long = format!("{}🦀{}", "a".repeat(1023), "b".repeat(2000));
+	let value = Value::string(cx, &long);
+	assert_eq!(format_value(cx, config, &value).to_string(), long);
+	let quoted = format_value(cx, config.quoted(true), &value).to_string();
+	assert!(quoted.contains('🦀') && !quoted.contains(char::REPLACEMENT_CHARACTER));
+
+	let string = format_value(cx, config, &value).to_js_string().unwrap();
+	assert_eq!(string.to_owned(cx).unwrap(), long);
+}
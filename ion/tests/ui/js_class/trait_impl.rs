@@ -0,0 +1,12 @@
+use ion::js_class;
+
+pub struct Unit;
+
+#[js_class]
+impl Default for Unit {
+	fn default() -> Unit {
+		Unit
+	}
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use ion::Traceable;
+
+#[derive(Traceable)]
+pub struct Duplicate {
+	#[trace(no_trace, no_trace)]
+	count: usize,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use ion::Traceable;
+use mozjs::jsapi::Heap;
+use mozjs::jsval::JSVal;
+
+#[derive(Traceable)]
+pub struct Untraced {
+	#[trace(no_trace)]
+	value: Box<Heap<JSVal>>,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use ion::Traceable;
+
+#[derive(Traceable)]
+pub struct Reasoned {
+	#[trace(reason = "Not a Heap.")]
+	count: usize,
+}
+
+fn main() {}
@@ -8,5 +8,8 @@ export const ok = ______assertInternal______.ok;
 export const equals = ______assertInternal______.equals;
 export const throws = ______assertInternal______.throws;
 export const fail = ______assertInternal______.fail;
+export const match = ______assertInternal______.match;
+export const doesNotMatch = ______assertInternal______.doesNotMatch;
+export const snapshot = ______assertInternal______.snapshot;
 
 export default Object.freeze(______assertInternal______);
@@ -4,9 +4,15 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::{Path, PathBuf};
+
+use ion::format::{format_value, ColourConfig, Config as FormatConfig};
 use ion::function::Opt;
-use ion::{Context, Error, Function, Object, Result, Value};
+use ion::stack::Stack;
+use ion::{Context, Error, Function, Object, RegExp, Result, Value};
 use mozjs::jsapi::JSFunctionSpec;
+use runtime::config::CONFIG;
 use runtime::module::NativeModule;
 
 fn assert_internal(message: Option<String>) -> Result<()> {
@@ -49,11 +55,90 @@ fn fail(Opt(message): Opt<String>) -> Result<()> {
 	assert_internal(message)
 }
 
+#[js_fn]
+fn matches(cx: &Context, string: String, regexp: RegExp, Opt(message): Opt<String>) -> Result<()> {
+	if regexp.execute_test_no_static(cx, &string, &mut 0) {
+		Ok(())
+	} else {
+		assert_internal(message)
+	}
+}
+
+#[js_fn]
+fn does_not_match(cx: &Context, string: String, regexp: RegExp, Opt(message): Opt<String>) -> Result<()> {
+	if regexp.execute_test_no_static(cx, &string, &mut 0) {
+		assert_internal(message)
+	} else {
+		Ok(())
+	}
+}
+
+/// Returns the path of the snapshot, in the `__snapshots__` directory next to the calling script.
+fn snapshot_path(cx: &Context, name: &str) -> PathBuf {
+	let script = Stack::from_capture(cx)
+		.and_then(|stack| stack.records.into_iter().next())
+		.map(|record| {
+			let file = record.location.file;
+			PathBuf::from(file.strip_prefix("file://").unwrap_or(&file))
+		})
+		.unwrap_or_default();
+
+	let directory = script.parent().unwrap_or(Path::new(""));
+	let file_name = script.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+	let name: String = name
+		.chars()
+		.map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+		.collect();
+	directory.join("__snapshots__").join(file_name).join(format!("{name}.snap"))
+}
+
+/// Removes the escape sequences used to colour formatted values.
+fn strip_colours(string: &str) -> String {
+	let mut stripped = String::with_capacity(string.len());
+	let mut chars = string.chars();
+	while let Some(c) = chars.next() {
+		if c == '\x1b' {
+			chars.by_ref().find(|c| *c == 'm');
+		} else {
+			stripped.push(c);
+		}
+	}
+	stripped
+}
+
+#[js_fn]
+fn snapshot(cx: &Context, value: Value, name: String, Opt(message): Opt<String>) -> Result<()> {
+	let config = FormatConfig::default().colours(ColourConfig::white()).quoted(true);
+	let actual = strip_colours(&format_value(cx, config, &value).to_string());
+	let path = snapshot_path(cx, &name);
+
+	let update = CONFIG.get().is_some_and(|config| config.update_snapshots);
+	match read_to_string(&path) {
+		Ok(expected) if !update => {
+			if expected == actual {
+				Ok(())
+			} else {
+				assert_internal(Some(message.unwrap_or_else(|| format!("Snapshot {name} does not match"))))
+			}
+		}
+		_ => {
+			if let Some(parent) = path.parent() {
+				create_dir_all(parent)?;
+			}
+			write(&path, actual)?;
+			Ok(())
+		}
+	}
+}
+
 const FUNCTIONS: &[JSFunctionSpec] = &[
 	function_spec!(ok, 0),
 	function_spec!(equals, 2),
 	function_spec!(throws, 1),
 	function_spec!(fail, 0),
+	function_spec!(matches, c"match", 2),
+	function_spec!(does_not_match, c"doesNotMatch", 2),
+	function_spec!(snapshot, 2),
 	JSFunctionSpec::ZERO,
 ];
 
@@ -0,0 +1,18 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+export const encodeHex = ______bufferInternal______.encodeHex;
+export const decodeHex = ______bufferInternal______.decodeHex;
+export const encodeBase64 = ______bufferInternal______.encodeBase64;
+export const decodeBase64 = ______bufferInternal______.decodeBase64;
+export const encodeBase64Url = ______bufferInternal______.encodeBase64Url;
+export const decodeBase64Url = ______bufferInternal______.decodeBase64Url;
+export const concat = ______bufferInternal______.concat;
+export const compare = ______bufferInternal______.compare;
+export const indexOf = ______bufferInternal______.indexOf;
+export const byteLength = ______bufferInternal______.byteLength;
+
+export default Object.freeze(______bufferInternal______);
@@ -0,0 +1,162 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cmp::Ordering;
+
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
+use base64::{alphabet, Engine};
+use ion::function::Opt;
+use ion::typedarray::Uint8ArrayWrapper;
+use ion::{Context, Error, ErrorKind, Object, Result};
+use mozjs::conversions::ConversionBehavior;
+use mozjs::jsapi::JSFunctionSpec;
+use runtime::globals::file::BufferSource;
+use runtime::module::NativeModule;
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+	&alphabet::STANDARD,
+	GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+const BASE64_URL: GeneralPurpose = GeneralPurpose::new(
+	&alphabet::URL_SAFE,
+	GeneralPurposeConfig::new()
+		.with_encode_padding(false)
+		.with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+fn hex_value(digit: u8) -> Option<u8> {
+	match digit {
+		b'0'..=b'9' => Some(digit - b'0'),
+		b'a'..=b'f' => Some(digit - b'a' + 10),
+		b'A'..=b'F' => Some(digit - b'A' + 10),
+		_ => None,
+	}
+}
+
+#[js_fn]
+fn encode_hex(#[ion(convert = true)] bytes: BufferSource) -> String {
+	let bytes = unsafe { bytes.as_slice() };
+	let mut hex = String::with_capacity(bytes.len() * 2);
+	for byte in bytes {
+		hex.push(char::from(HEX_DIGITS[usize::from(byte >> 4)]));
+		hex.push(char::from(HEX_DIGITS[usize::from(byte & 0xF)]));
+	}
+	hex
+}
+
+#[js_fn]
+fn decode_hex(hex: String) -> Result<Uint8ArrayWrapper> {
+	if hex.len() % 2 != 0 {
+		return Err(Error::new("Hex string must have an even length.", ErrorKind::Range));
+	}
+
+	let bytes: Option<Vec<_>> = hex
+		.as_bytes()
+		.chunks_exact(2)
+		.map(|pair| Some((hex_value(pair[0])? << 4) | hex_value(pair[1])?))
+		.collect();
+	match bytes {
+		Some(bytes) => Ok(Uint8ArrayWrapper::from(bytes)),
+		None => Err(Error::new("Hex string contains an invalid character.", ErrorKind::Range)),
+	}
+}
+
+#[js_fn]
+fn encode_base64(#[ion(convert = true)] bytes: BufferSource) -> String {
+	BASE64.encode(unsafe { bytes.as_slice() })
+}
+
+#[js_fn]
+fn decode_base64(base64: String) -> Result<Uint8ArrayWrapper> {
+	let bytes = BASE64.decode(base64.trim()).map_err(|error| Error::new(error.to_string(), ErrorKind::Range))?;
+	Ok(Uint8ArrayWrapper::from(bytes))
+}
+
+#[js_fn]
+fn encode_base64_url(#[ion(convert = true)] bytes: BufferSource) -> String {
+	BASE64_URL.encode(unsafe { bytes.as_slice() })
+}
+
+#[js_fn]
+fn decode_base64_url(base64: String) -> Result<Uint8ArrayWrapper> {
+	let bytes = BASE64_URL.decode(base64.trim()).map_err(|error| Error::new(error.to_string(), ErrorKind::Range))?;
+	Ok(Uint8ArrayWrapper::from(bytes))
+}
+
+#[js_fn]
+fn concat(#[ion(convert = true)] buffers: Vec<BufferSource>) -> Uint8ArrayWrapper {
+	let mut bytes = Vec::with_capacity(buffers.iter().map(BufferSource::len).sum());
+	for buffer in &buffers {
+		bytes.extend_from_slice(unsafe { buffer.as_slice() });
+	}
+	Uint8ArrayWrapper::from(bytes)
+}
+
+#[js_fn]
+fn compare(#[ion(convert = true)] first: BufferSource, #[ion(convert = true)] second: BufferSource) -> i32 {
+	match unsafe { first.as_slice().cmp(second.as_slice()) } {
+		Ordering::Less => -1,
+		Ordering::Equal => 0,
+		Ordering::Greater => 1,
+	}
+}
+
+#[js_fn]
+fn index_of(
+	#[ion(convert = true)] haystack: BufferSource, #[ion(convert = true)] needle: BufferSource,
+	#[ion(convert = ConversionBehavior::Clamp)] Opt(from_index): Opt<i64>,
+) -> i64 {
+	let (haystack, needle) = unsafe { (haystack.as_slice(), needle.as_slice()) };
+	// Negative indices are offsets from the end, as in Node.
+	let length = haystack.len() as i64;
+	let from_index = from_index.unwrap_or(0);
+	let start = if from_index < 0 {
+		length.saturating_add(from_index).max(0)
+	} else {
+		from_index.min(length)
+	};
+
+	if needle.is_empty() {
+		return start;
+	}
+	let position = haystack[start as usize..].windows(needle.len()).position(|window| window == needle);
+	position.map_or(-1, |position| start + position as i64)
+}
+
+#[js_fn]
+fn byte_length(string: String) -> u64 {
+	string.len() as u64
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[
+	function_spec!(encode_hex, c"encodeHex", 1),
+	function_spec!(decode_hex, c"decodeHex", 1),
+	function_spec!(encode_base64, c"encodeBase64", 1),
+	function_spec!(decode_base64, c"decodeBase64", 1),
+	function_spec!(encode_base64_url, c"encodeBase64Url", 1),
+	function_spec!(decode_base64_url, c"decodeBase64Url", 1),
+	function_spec!(concat, 1),
+	function_spec!(compare, 2),
+	function_spec!(index_of, c"indexOf", 2),
+	function_spec!(byte_length, c"byteLength", 1),
+	JSFunctionSpec::ZERO,
+];
+
+pub struct BufferM;
+
+impl<'cx> NativeModule<'cx> for BufferM {
+	const NAME: &'static str = "buffer";
+	const VARIABLE_NAME: &'static str = "buffer";
+	const SOURCE: &'static str = include_str!("buffer.js");
+
+	fn module(&self, cx: &'cx Context) -> Option<Object<'cx>> {
+		let buffer = Object::new(cx);
+		unsafe { buffer.define_methods(cx, FUNCTIONS).then_some(buffer) }
+	}
+}
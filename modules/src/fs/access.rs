@@ -0,0 +1,356 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ion::class::{ClassObjectWrapper, Reflector};
+use ion::conversions::{FromValue, IntoValue, ToValue};
+use ion::function::Opt;
+use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Promise, Result, Value};
+use runtime::globals::file::{Blob, BlobData, BufferSource, File, FileSnapshot};
+use runtime::globals::streams::writable::{NativeSink, SinkFuture, WritableStream};
+use runtime::promise::{future_to_promise, run_blocking};
+
+use crate::fs::{base_error, dir_error, file_error};
+
+#[derive(Copy, Clone, Debug, Default, FromValue)]
+struct GetHandleOptions {
+	#[ion(default)]
+	create: bool,
+}
+
+#[derive(Copy, Clone, Debug, Default, FromValue)]
+struct RemoveOptions {
+	#[ion(default)]
+	recursive: bool,
+}
+
+#[derive(Copy, Clone, Debug, Default, FromValue)]
+struct WritableOptions {
+	#[ion(name = "keepExistingData", default)]
+	keep_existing_data: bool,
+}
+
+fn entry_name(path: &Path) -> String {
+	path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+fn validate_name(name: &str) -> Result<()> {
+	if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+		Err(Error::new(format!("Invalid entry name: {name}"), ErrorKind::Type))
+	} else {
+		Ok(())
+	}
+}
+
+fn type_mismatch(path: &Path, expected: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidInput, format!("{} is not a {expected}", path.display()))
+}
+
+fn open_file_handle(path: PathBuf, create: bool) -> io::Result<PathBuf> {
+	match fs::metadata(&path) {
+		Ok(metadata) if metadata.is_file() => Ok(path),
+		Ok(_) => Err(type_mismatch(&path, "file")),
+		Err(err) if err.kind() == io::ErrorKind::NotFound && create => {
+			fs::OpenOptions::new().write(true).create(true).truncate(false).open(&path)?;
+			Ok(path)
+		}
+		Err(err) => Err(err),
+	}
+}
+
+fn open_directory_handle(path: PathBuf, create: bool) -> io::Result<PathBuf> {
+	match fs::metadata(&path) {
+		Ok(metadata) if metadata.is_dir() => Ok(path),
+		Ok(_) => Err(type_mismatch(&path, "directory")),
+		Err(err) if err.kind() == io::ErrorKind::NotFound && create => {
+			fs::create_dir(&path)?;
+			Ok(path)
+		}
+		Err(err) => Err(err),
+	}
+}
+
+fn same_entry(cx: &Context, path: &Path, other: &Object) -> bool {
+	let other = if let Ok(handle) = FileSystemFileHandle::get_private(cx, other) {
+		&handle.path
+	} else if let Ok(handle) = FileSystemDirectoryHandle::get_private(cx, other) {
+		&handle.path
+	} else {
+		return false;
+	};
+
+	path == other || fs::canonicalize(path).ok().is_some_and(|path| fs::canonicalize(other).ok() == Some(path))
+}
+
+#[js_class]
+pub struct FileSystemFileHandle {
+	reflector: Reflector,
+	#[trace(no_trace)]
+	path: PathBuf,
+}
+
+impl FileSystemFileHandle {
+	pub(crate) fn new(path: PathBuf) -> FileSystemFileHandle {
+		FileSystemFileHandle { reflector: Reflector::new(), path }
+	}
+}
+
+#[js_class]
+impl FileSystemFileHandle {
+	#[ion(get)]
+	pub fn get_kind(&self) -> &'static str {
+		"file"
+	}
+
+	#[ion(get)]
+	pub fn get_name(&self) -> String {
+		entry_name(&self.path)
+	}
+
+	#[ion(name = "isSameEntry")]
+	pub fn is_same_entry<'cx>(&self, cx: &'cx Context, other: Object) -> Option<Promise<'cx>> {
+		let same = same_entry(cx, &self.path, &other);
+		future_to_promise(cx, async move { Ok::<_, ()>(same) })
+	}
+
+	#[ion(name = "getFile")]
+	pub fn get_file<'cx>(&self, cx: &'cx Context) -> Option<Promise<'cx>> {
+		let path = self.path.clone();
+		future_to_promise(cx, async move {
+			let path_str = path.to_string_lossy().into_owned();
+			match run_blocking(move |_| FileSnapshot::new(&path)).await {
+				Ok(snapshot) => Ok(ClassObjectWrapper(Box::new(File::from_snapshot(snapshot)))),
+				Err(err) => Err(file_error("read", &path_str, err, ())),
+			}
+		})
+	}
+
+	#[ion(name = "createWritable")]
+	pub fn create_writable<'cx>(&self, cx: &'cx Context, Opt(options): Opt<WritableOptions>) -> Option<Promise<'cx>> {
+		let options = options.unwrap_or_default();
+		let path = self.path.clone();
+
+		future_to_promise(cx, async move {
+			let path_str = path.to_string_lossy().into_owned();
+			match run_blocking(move |_| FileSink::new(path, options.keep_existing_data)).await {
+				Ok(sink) => Ok(WritableFile(sink)),
+				Err(err) => Err(file_error("open", &path_str, err, ())),
+			}
+		})
+	}
+}
+
+#[js_class]
+pub struct FileSystemDirectoryHandle {
+	reflector: Reflector,
+	#[trace(no_trace)]
+	path: PathBuf,
+}
+
+impl FileSystemDirectoryHandle {
+	pub(crate) fn new(path: PathBuf) -> FileSystemDirectoryHandle {
+		FileSystemDirectoryHandle { reflector: Reflector::new(), path }
+	}
+}
+
+#[js_class]
+impl FileSystemDirectoryHandle {
+	#[ion(get)]
+	pub fn get_kind(&self) -> &'static str {
+		"directory"
+	}
+
+	#[ion(get)]
+	pub fn get_name(&self) -> String {
+		entry_name(&self.path)
+	}
+
+	#[ion(name = "isSameEntry")]
+	pub fn is_same_entry<'cx>(&self, cx: &'cx Context, other: Object) -> Option<Promise<'cx>> {
+		let same = same_entry(cx, &self.path, &other);
+		future_to_promise(cx, async move { Ok::<_, ()>(same) })
+	}
+
+	#[ion(name = "getFileHandle")]
+	pub fn get_file_handle<'cx>(
+		&self, cx: &'cx Context, name: String, Opt(options): Opt<GetHandleOptions>,
+	) -> Option<Promise<'cx>> {
+		let create = options.unwrap_or_default().create;
+		let path = self.path.join(&name);
+
+		future_to_promise(cx, async move {
+			validate_name(&name)?;
+			let path_str = path.to_string_lossy().into_owned();
+			match run_blocking(move |_| open_file_handle(path, create)).await {
+				Ok(path) => Ok(ClassObjectWrapper(Box::new(FileSystemFileHandle::new(path)))),
+				Err(err) => Err(file_error("get", &path_str, err, ())),
+			}
+		})
+	}
+
+	#[ion(name = "getDirectoryHandle")]
+	pub fn get_directory_handle<'cx>(
+		&self, cx: &'cx Context, name: String, Opt(options): Opt<GetHandleOptions>,
+	) -> Option<Promise<'cx>> {
+		let create = options.unwrap_or_default().create;
+		let path = self.path.join(&name);
+
+		future_to_promise(cx, async move {
+			validate_name(&name)?;
+			let path_str = path.to_string_lossy().into_owned();
+			match run_blocking(move |_| open_directory_handle(path, create)).await {
+				Ok(path) => Ok(ClassObjectWrapper(Box::new(FileSystemDirectoryHandle::new(path)))),
+				Err(err) => Err(dir_error("get", &path_str, err)),
+			}
+		})
+	}
+
+	#[ion(name = "removeEntry")]
+	pub fn remove_entry<'cx>(
+		&self, cx: &'cx Context, name: String, Opt(options): Opt<RemoveOptions>,
+	) -> Option<Promise<'cx>> {
+		let recursive = options.unwrap_or_default().recursive;
+		let path = self.path.join(&name);
+
+		future_to_promise(cx, async move {
+			validate_name(&name)?;
+			let path_str = path.to_string_lossy().into_owned();
+			let result = run_blocking(move |_| {
+				if fs::metadata(&path)?.is_dir() {
+					if recursive {
+						fs::remove_dir_all(&path)
+					} else {
+						fs::remove_dir(&path)
+					}
+				} else {
+					fs::remove_file(&path)
+				}
+			})
+			.await;
+			result.map_err(|err| base_error("remove", &path_str, err))
+		})
+	}
+}
+
+/// Writes to a swap file next to the target, which replaces the target when the stream is closed.
+struct FileSink {
+	file: Arc<fs::File>,
+	swap: Arc<Path>,
+	path: Arc<Path>,
+}
+
+impl FileSink {
+	fn new(path: PathBuf, keep_existing_data: bool) -> io::Result<FileSink> {
+		let mut swap = OsString::from(path.as_os_str());
+		swap.push(".crswap");
+		let swap = PathBuf::from(swap);
+
+		if keep_existing_data {
+			fs::copy(&path, &swap)?;
+		}
+		let file = fs::OpenOptions::new().write(true).create(true).truncate(!keep_existing_data).open(&swap)?;
+
+		Ok(FileSink {
+			file: Arc::new(file),
+			swap: Arc::from(swap),
+			path: Arc::from(path),
+		})
+	}
+}
+
+enum Chunk {
+	Bytes(Vec<u8>),
+	Blob(BlobData),
+}
+
+impl NativeSink for FileSink {
+	fn write(&mut self, cx: &Context, chunk: &Value) -> Result<SinkFuture> {
+		let chunk = if chunk.handle().is_string() {
+			Chunk::Bytes(String::from_value(cx, chunk, true, ())?.into_bytes())
+		} else if let Ok(source) = BufferSource::from_value(cx, chunk, true, false) {
+			Chunk::Bytes(source.to_vec())
+		} else if let Ok(blob) = <&Blob>::from_value(cx, chunk, true, ()) {
+			Chunk::Blob(blob.data().clone())
+		} else {
+			return Err(Error::new(
+				"Expected String, BufferSource or Blob in FileSystemWritableFileStream",
+				ErrorKind::Type,
+			));
+		};
+
+		let file = Arc::clone(&self.file);
+		let swap = Arc::clone(&self.swap);
+		Ok(Box::pin(async move {
+			let bytes = match chunk {
+				Chunk::Bytes(bytes) => bytes,
+				Chunk::Blob(data) => data.read().await?.to_vec(),
+			};
+			run_blocking(move |_| (&*file).write_all(&bytes))
+				.await
+				.map_err(|err| file_error("write", &swap.to_string_lossy(), err, ()))
+		}))
+	}
+
+	fn close(&mut self, _: &Context) -> SinkFuture {
+		let file = Arc::clone(&self.file);
+		let swap = Arc::clone(&self.swap);
+		let path = Arc::clone(&self.path);
+		Box::pin(async move {
+			let path_str = path.to_string_lossy().into_owned();
+			run_blocking(move |_| {
+				file.sync_all()?;
+				fs::rename(&swap, &path)
+			})
+			.await
+			.map_err(|err| file_error("write", &path_str, err, ()))
+		})
+	}
+
+	fn abort(&mut self, _: &Context, _: &Value) -> SinkFuture {
+		let swap = Arc::clone(&self.swap);
+		Box::pin(async move {
+			let swap_str = swap.to_string_lossy().into_owned();
+			run_blocking(move |_| fs::remove_file(&swap))
+				.await
+				.map_err(|err| file_error("remove", &swap_str, err, ()))
+		})
+	}
+}
+
+struct WritableFile(FileSink);
+
+impl IntoValue<'_> for WritableFile {
+	fn into_value(self: Box<Self>, cx: &Context, value: &mut Value) {
+		WritableStream::from_native_sink(cx, self.0).to_value(cx, value);
+	}
+}
+
+#[js_fn]
+pub(crate) fn get_file_handle(cx: &Context, path_str: String) -> Option<Promise> {
+	future_to_promise(cx, async move {
+		let path = PathBuf::from(&path_str);
+		match run_blocking(move |_| open_file_handle(path, false)).await {
+			Ok(path) => Ok(ClassObjectWrapper(Box::new(FileSystemFileHandle::new(path)))),
+			Err(err) => Err(file_error("get", &path_str, err, ())),
+		}
+	})
+}
+
+#[js_fn]
+pub(crate) fn get_directory_handle(cx: &Context, path_str: String) -> Option<Promise> {
+	future_to_promise(cx, async move {
+		let path = PathBuf::from(&path_str);
+		match run_blocking(move |_| open_directory_handle(path, false)).await {
+			Ok(path) => Ok(ClassObjectWrapper(Box::new(FileSystemDirectoryHandle::new(path)))),
+			Err(err) => Err(dir_error("get", &path_str, err)),
+		}
+	})
+}
@@ -0,0 +1,221 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::fs::File;
+use std::future::Future;
+use std::io;
+use std::io::Read;
+use std::path::Path;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use futures::channel::oneshot;
+use ion::class::Reflector;
+use ion::function::Opt;
+use ion::{Context, Error, ErrorKind, Promise, Result};
+use runtime::globals::file::BufferSource;
+use runtime::promise::{future_to_promise, run_blocking, Cancellation};
+use sha2::digest::DynDigest;
+use sha2::{Sha256, Sha384, Sha512};
+use sha3::{Sha3_256, Sha3_384, Sha3_512};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+type Digest = Box<dyn DynDigest + Send>;
+
+#[derive(Copy, Clone, Debug, Default)]
+pub enum Algorithm {
+	#[default]
+	Sha256,
+	Sha384,
+	Sha512,
+	Sha3_256,
+	Sha3_384,
+	Sha3_512,
+}
+
+impl Algorithm {
+	pub(crate) fn parse(algorithm: Option<String>) -> Result<Algorithm> {
+		algorithm.as_deref().map_or(Ok(Algorithm::default()), Algorithm::from_str)
+	}
+
+	fn digest(self) -> Digest {
+		match self {
+			Algorithm::Sha256 => Box::new(Sha256::default()),
+			Algorithm::Sha384 => Box::new(Sha384::default()),
+			Algorithm::Sha512 => Box::new(Sha512::default()),
+			Algorithm::Sha3_256 => Box::new(Sha3_256::default()),
+			Algorithm::Sha3_384 => Box::new(Sha3_384::default()),
+			Algorithm::Sha3_512 => Box::new(Sha3_512::default()),
+		}
+	}
+}
+
+impl FromStr for Algorithm {
+	type Err = Error;
+
+	fn from_str(algorithm: &str) -> Result<Algorithm> {
+		match algorithm.to_ascii_lowercase().as_str() {
+			"sha256" | "sha-256" => Ok(Algorithm::Sha256),
+			"sha384" | "sha-384" => Ok(Algorithm::Sha384),
+			"sha512" | "sha-512" => Ok(Algorithm::Sha512),
+			"sha3-256" => Ok(Algorithm::Sha3_256),
+			"sha3-384" => Ok(Algorithm::Sha3_384),
+			"sha3-512" => Ok(Algorithm::Sha3_512),
+			_ => Err(Error::new(
+				format!("Unsupported checksum algorithm: {algorithm}"),
+				ErrorKind::Type,
+			)),
+		}
+	}
+}
+
+/// Computes the checksum of a file as a hexadecimal string, reading it in chunks so that it is never fully in memory.
+pub(crate) fn checksum_file(
+	path: &Path, algorithm: Algorithm, cancellation: Option<&Cancellation>,
+) -> io::Result<String> {
+	let mut file = File::open(path)?;
+	let mut digest = algorithm.digest();
+	let mut buffer = vec![0; CHUNK_SIZE];
+
+	loop {
+		if cancellation.is_some_and(Cancellation::is_cancelled) {
+			return Err(io::Error::from(io::ErrorKind::Interrupted));
+		}
+		match file.read(&mut buffer) {
+			Ok(0) => break,
+			Ok(read) => digest.update(&buffer[..read]),
+			Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+			Err(err) => return Err(err),
+		}
+	}
+	Ok(to_hex(&digest.finalize()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+	let mut hex = String::with_capacity(bytes.len() * 2);
+	for byte in bytes {
+		let _ = write!(hex, "{byte:02x}");
+	}
+	hex
+}
+
+struct HasherState {
+	/// Digest of the hasher, which is [None] while asynchronous updates are queued.
+	digest: Option<Digest>,
+	/// Receives the digest once the last queued update has completed.
+	last: Option<oneshot::Receiver<Digest>>,
+	queued: u64,
+	completed: u64,
+}
+
+/// Incrementally computes a checksum, such as over the chunks of a `ReadableStream`.
+///
+/// Asynchronous updates are hashed on the blocking thread pool, in the order they were made.
+#[js_class]
+pub struct Hasher {
+	reflector: Reflector,
+	#[trace(no_trace)]
+	state: Rc<RefCell<HasherState>>,
+}
+
+impl Hasher {
+	/// Runs a task with the digest on the blocking thread pool, once all previously queued tasks have completed.
+	///
+	/// Each task receives the digest from the task queued before it, and passes it on to the task queued after it.
+	fn queue<F, T>(&self, task: F) -> impl Future<Output = Result<T>>
+	where
+		F: FnOnce(&mut Digest) -> T + Send + 'static,
+		T: Send + 'static,
+	{
+		let state = Rc::clone(&self.state);
+		let (sender, receiver) = oneshot::channel();
+		let previous = {
+			let mut state = state.borrow_mut();
+			state.queued += 1;
+			state.last.replace(receiver)
+		};
+
+		async move {
+			let digest = match previous {
+				Some(previous) => previous.await.ok(),
+				None => state.borrow_mut().digest.take(),
+			};
+			let mut digest = digest.ok_or_else(|| Error::new("Hasher was aborted by a previous operation.", None))?;
+
+			let (digest, output) = run_blocking(move |_| {
+				let output = task(&mut digest);
+				(digest, output)
+			})
+			.await;
+
+			let mut state = state.borrow_mut();
+			state.completed += 1;
+			if state.completed == state.queued {
+				state.last = None;
+				state.digest = Some(digest);
+			} else {
+				let _ = sender.send(digest);
+			}
+			Ok(output)
+		}
+	}
+
+	fn with_sync<F, T>(&self, callback: F) -> Result<T>
+	where
+		F: FnOnce(&mut Digest) -> T,
+	{
+		let mut state = self.state.borrow_mut();
+		let idle = state.completed == state.queued;
+		match &mut state.digest {
+			Some(digest) if idle => Ok(callback(digest)),
+			_ => Err(Error::new("Hasher is busy due to async operation.", None)),
+		}
+	}
+}
+
+#[js_class]
+impl Hasher {
+	#[ion(constructor)]
+	pub fn constructor(Opt(algorithm): Opt<String>) -> Result<Hasher> {
+		let algorithm = Algorithm::parse(algorithm)?;
+		Ok(Hasher {
+			reflector: Reflector::default(),
+			state: Rc::new(RefCell::new(HasherState {
+				digest: Some(algorithm.digest()),
+				last: None,
+				queued: 0,
+				completed: 0,
+			})),
+		})
+	}
+
+	pub fn update<'cx>(
+		&self, cx: &'cx Context, #[ion(convert = false)] data: BufferSource<'cx>,
+	) -> Option<Promise<'cx>> {
+		let data = data.to_vec();
+		let task = self.queue(move |digest| digest.update(&data));
+		future_to_promise(cx, task)
+	}
+
+	#[ion(name = "updateSync")]
+	pub fn update_sync(&self, #[ion(convert = false)] data: BufferSource) -> Result<()> {
+		self.with_sync(|digest| digest.update(unsafe { data.as_slice() }))
+	}
+
+	/// Returns the checksum of the data so far as a hexadecimal string, and resets the hasher.
+	pub fn digest<'cx>(&self, cx: &'cx Context) -> Option<Promise<'cx>> {
+		let task = self.queue(|digest| to_hex(&digest.finalize_reset()));
+		future_to_promise(cx, task)
+	}
+
+	#[ion(name = "digestSync")]
+	pub fn digest_sync(&self) -> Result<String> {
+		self.with_sync(|digest| to_hex(&digest.finalize_reset()))
+	}
+}
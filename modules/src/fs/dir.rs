@@ -10,9 +10,10 @@ use std::iter::Iterator as Iter;
 
 use ion::class::Reflector;
 use ion::conversions::ToValue;
+use ion::function::Opt;
 use ion::{ClassDefinition, Context, Iterator, JSIterator, Result, Value};
 
-use crate::fs::{metadata_error, Metadata};
+use crate::fs::{metadata_error, Metadata, MetadataOptions};
 
 #[js_class]
 pub struct DirEntry {
@@ -31,8 +32,11 @@ impl DirEntry {
 		self.entry.path().to_string_lossy().into_owned()
 	}
 
-	pub fn metadata(&self) -> Result<Metadata> {
-		self.entry.metadata().map(Metadata).map_err(|err| metadata_error(&self.path(), err))
+	pub fn metadata(&self, Opt(options): Opt<MetadataOptions>) -> Result<Metadata> {
+		self.entry
+			.metadata()
+			.map(|metadata| Metadata::new(metadata, options))
+			.map_err(|err| metadata_error(&self.path(), err))
 	}
 }
 
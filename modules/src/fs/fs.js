@@ -5,6 +5,10 @@
  */
 
 export const FileHandle = ______fsInternal______.FileHandle;
+export const FileSystemFileHandle = ______fsInternal______.FileSystemFileHandle;
+export const FileSystemDirectoryHandle = ______fsInternal______.FileSystemDirectoryHandle;
+export const Hasher = ______fsInternal______.Hasher;
+export const Walker = ______fsInternal______.Walker;
 
 export const open = ______fsInternal______.open;
 export const create = ______fsInternal______.create;
@@ -12,7 +16,10 @@ export const create = ______fsInternal______.create;
 export const metadata = ______fsInternal______.metadata;
 export const linkMetadata = ______fsInternal______.linkMetadata;
 
+export const checksum = ______fsInternal______.checksum;
+
 export const readDir = ______fsInternal______.readDir;
+export const walk = ______fsInternal______.walk;
 export const createDir = ______fsInternal______.createDir;
 export const remove = ______fsInternal______.remove;
 export const copy = ______fsInternal______.copy;
@@ -23,12 +30,17 @@ export const link = ______fsInternal______.link;
 export const readLink = ______fsInternal______.readLink;
 export const canonical = ______fsInternal______.canonical;
 
+export const getFileHandle = ______fsInternal______.getFileHandle;
+export const getDirectoryHandle = ______fsInternal______.getDirectoryHandle;
+
 export const openSync = ______fsInternal______.openSync;
 export const createSync = ______fsInternal______.createSync;
 
 export const metadataSync = ______fsInternal______.metadataSync;
 export const linkMetadataSync = ______fsInternal______.linkMetadataSync;
 
+export const checksumSync = ______fsInternal______.checksumSync;
+
 export const readDirSync = ______fsInternal______.readDirSync;
 export const createDirSync = ______fsInternal______.createDirSync;
 export const removeSync = ______fsInternal______.removeSync;
@@ -42,4 +54,18 @@ export const canonicalSync = ______fsInternal______.canonicalSync;
 
 export const sync = ______fsInternal______.sync;
 
+export async function checksumStream(stream, algorithm) {
+	const hasher = new Hasher(algorithm);
+	const reader = stream.getReader();
+	while (true) {
+		const {done, value} = await reader.read();
+		if (done) {
+			return hasher.digest();
+		}
+		await hasher.update(value);
+	}
+}
+
+______fsInternal______.checksumStream = checksumStream;
+
 export default Object.freeze(______fsInternal______);
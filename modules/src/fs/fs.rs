@@ -4,24 +4,36 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::fs::File;
+use std::io::{Read, Write};
 #[cfg(windows)]
 use std::os::windows::fs::MetadataExt;
 use std::path::{Path, PathBuf};
-use std::{fs, os};
+use std::{fs, io, os};
 
 use ion::class::ClassObjectWrapper;
 use ion::flags::PropertyFlags;
 use ion::function::Opt;
 use ion::{ClassDefinition, Context, Iterator, Object, Promise, Result};
 use mozjs::jsapi::{JSFunction, JSFunctionSpec, JSObject};
+use runtime::globals::abort::Signal;
 use runtime::module::NativeModule;
-use runtime::promise::future_to_promise;
-use tokio::task::spawn_blocking;
+use runtime::promise::{future_to_promise_with_signal, run_blocking, Cancellation};
+use runtime::resources::record_file_opened;
 #[cfg(windows)]
 use windows::Win32::Storage::FileSystem::{FILE_ATTRIBUTE_DIRECTORY, FILE_FLAGS_AND_ATTRIBUTES};
 
+use crate::fs::access::{get_directory_handle, get_file_handle};
+use crate::fs::checksum::{checksum_file, Algorithm};
 use crate::fs::dir::DirIterator;
-use crate::fs::{base_error, dir_error, file_error, metadata_error, translate_error, FileHandle, Metadata};
+use crate::fs::walk::WalkOptions;
+use crate::fs::{
+	base_error, dir_error, file_error, metadata_error, translate_error, AbortOptions, Abortable, FileHandle,
+	FileSystemDirectoryHandle, FileSystemFileHandle, Hasher, Metadata, MetadataOptions, Walker,
+};
+
+/// Size of the chunks in which files are copied when the copy can be aborted.
+const COPY_CHUNK_SIZE: usize = 64 * 1024;
 
 #[derive(Copy, Clone, Debug, FromValue)]
 struct OpenOptions {
@@ -74,10 +86,12 @@ impl Default for OpenOptions {
 }
 
 #[js_fn]
-fn open(cx: &Context, path_str: String, Opt(options): Opt<OpenOptions>) -> Option<Promise> {
-	future_to_promise(cx, async move {
+fn open(cx: &Context, path_str: String, Opt(options): Opt<Abortable<OpenOptions>>) -> Option<Promise> {
+	record_file_opened(cx);
+	let (options, signal) = Abortable::split(options);
+	future_to_promise_with_signal(cx, &signal, async move {
 		let path = Path::new(&path_str);
-		let options = options.unwrap_or_default().into_tokio();
+		let options = options.into_tokio();
 
 		match options.open(path).await {
 			Ok(file) => Ok(ClassObjectWrapper(Box::new(FileHandle::new(
@@ -91,6 +105,7 @@ fn open(cx: &Context, path_str: String, Opt(options): Opt<OpenOptions>) -> Optio
 
 #[js_fn]
 fn open_sync(cx: &Context, path_str: String, Opt(options): Opt<OpenOptions>) -> Result<*mut JSObject> {
+	record_file_opened(cx);
 	let path = Path::new(&path_str);
 	let options = options.unwrap_or_default().into_std();
 
@@ -101,8 +116,9 @@ fn open_sync(cx: &Context, path_str: String, Opt(options): Opt<OpenOptions>) ->
 }
 
 #[js_fn]
-fn create(cx: &Context, path_str: String) -> Option<Promise> {
-	future_to_promise(cx, async move {
+fn create(cx: &Context, path_str: String, Opt(options): Opt<AbortOptions>) -> Option<Promise> {
+	record_file_opened(cx);
+	future_to_promise_with_signal(cx, &AbortOptions::signal(options), async move {
 		let path = Path::new(&path_str);
 		let mut options = tokio::fs::OpenOptions::new();
 		options.read(true).write(true).truncate(true).create(true);
@@ -119,6 +135,7 @@ fn create(cx: &Context, path_str: String) -> Option<Promise> {
 
 #[js_fn]
 fn create_sync(cx: &Context, path_str: String) -> Result<*mut JSObject> {
+	record_file_opened(cx);
 	let path = Path::new(&path_str);
 	let mut options = fs::OpenOptions::new();
 	options.read(true).write(true).truncate(true).create(true);
@@ -130,53 +147,79 @@ fn create_sync(cx: &Context, path_str: String) -> Result<*mut JSObject> {
 }
 
 #[js_fn]
-fn metadata(cx: &Context, path_str: String) -> Option<Promise> {
-	future_to_promise(cx, async move {
+fn metadata(cx: &Context, path_str: String, Opt(options): Opt<Abortable<MetadataOptions>>) -> Option<Promise> {
+	let (options, signal) = Abortable::split(options);
+	future_to_promise_with_signal(cx, &signal, async move {
 		let path = Path::new(&path_str);
 		match tokio::fs::metadata(path).await {
-			Ok(meta) => Ok(Metadata(meta)),
+			Ok(meta) => Ok(Metadata::new(meta, Some(options))),
 			Err(err) => Err(metadata_error(&path_str, err)),
 		}
 	})
 }
 
 #[js_fn]
-fn metadata_sync(path_str: String) -> Result<Metadata> {
+fn metadata_sync(path_str: String, Opt(options): Opt<MetadataOptions>) -> Result<Metadata> {
 	let path = Path::new(&path_str);
 	match fs::metadata(path) {
-		Ok(meta) => Ok(Metadata(meta)),
+		Ok(meta) => Ok(Metadata::new(meta, options)),
 		Err(err) => Err(metadata_error(&path_str, err)),
 	}
 }
 
 #[js_fn]
-fn link_metadata(cx: &Context, path_str: String) -> Option<Promise> {
-	future_to_promise(cx, async move {
+fn link_metadata(cx: &Context, path_str: String, Opt(options): Opt<Abortable<MetadataOptions>>) -> Option<Promise> {
+	let (options, signal) = Abortable::split(options);
+	future_to_promise_with_signal(cx, &signal, async move {
 		let path = Path::new(&path_str);
 		match tokio::fs::symlink_metadata(path).await {
-			Ok(meta) => Ok(Metadata(meta)),
+			Ok(meta) => Ok(Metadata::new(meta, Some(options))),
 			Err(err) => Err(metadata_error(&path_str, err)),
 		}
 	})
 }
 
 #[js_fn]
-fn link_metadata_sync(path_str: String) -> Result<Metadata> {
+fn link_metadata_sync(path_str: String, Opt(options): Opt<MetadataOptions>) -> Result<Metadata> {
 	let path = Path::new(&path_str);
 	match fs::symlink_metadata(path) {
-		Ok(meta) => Ok(Metadata(meta)),
+		Ok(meta) => Ok(Metadata::new(meta, options)),
 		Err(err) => Err(metadata_error(&path_str, err)),
 	}
 }
 
 #[js_fn]
-fn read_dir(cx: &Context, path_str: String) -> Option<Promise> {
-	future_to_promise(cx, async move {
+fn checksum(
+	cx: &Context, path_str: String, Opt(algorithm): Opt<Option<String>>, Opt(options): Opt<AbortOptions>,
+) -> Option<Promise> {
+	record_file_opened(cx);
+	let algorithm = Algorithm::parse(algorithm.flatten());
+	future_to_promise_with_signal(cx, &AbortOptions::signal(options), async move {
+		let algorithm = algorithm?;
 		let path = PathBuf::from(&path_str);
 
-		spawn_blocking(move || fs::read_dir(path))
+		run_blocking(move |cancellation| checksum_file(&path, algorithm, Some(cancellation)))
+			.await
+			.map_err(|err| file_error("checksum", &path_str, err, ()))
+	})
+}
+
+#[js_fn]
+fn checksum_sync(cx: &Context, path_str: String, Opt(algorithm): Opt<String>) -> Result<String> {
+	record_file_opened(cx);
+	let algorithm = Algorithm::parse(algorithm)?;
+	let path = Path::new(&path_str);
+
+	checksum_file(path, algorithm, None).map_err(|err| file_error("checksum", &path_str, err, ()))
+}
+
+#[js_fn]
+fn read_dir(cx: &Context, path_str: String, Opt(options): Opt<AbortOptions>) -> Option<Promise> {
+	future_to_promise_with_signal(cx, &AbortOptions::signal(options), async move {
+		let path = PathBuf::from(&path_str);
+
+		run_blocking(move |_| fs::read_dir(path))
 			.await
-			.unwrap()
 			.map(DirIterator::new_iterator)
 			.map_err(|err| dir_error("read", &path_str, err))
 	})
@@ -193,8 +236,17 @@ fn read_dir_sync(path_str: String) -> Result<Iterator> {
 }
 
 #[js_fn]
-fn create_dir(cx: &Context, path_str: String, Opt(recursive): Opt<bool>) -> Option<Promise> {
-	future_to_promise(cx, async move {
+fn walk(cx: &Context, path_str: String, Opt(options): Opt<Abortable<WalkOptions>>) -> *mut JSObject {
+	let (options, signal) = Abortable::split(options);
+	let walker = Walker::new(PathBuf::from(path_str), options, signal);
+	Walker::new_object(cx, Box::new(walker))
+}
+
+#[js_fn]
+fn create_dir(
+	cx: &Context, path_str: String, Opt(recursive): Opt<bool>, Opt(options): Opt<AbortOptions>,
+) -> Option<Promise> {
+	future_to_promise_with_signal(cx, &AbortOptions::signal(options), async move {
 		let path = Path::new(&path_str);
 		let recursive = recursive.unwrap_or_default();
 
@@ -227,8 +279,10 @@ fn create_dir_sync(path_str: String, Opt(recursive): Opt<bool>) -> Result<()> {
 }
 
 #[js_fn]
-fn remove(cx: &Context, path_str: String, Opt(recursive): Opt<bool>) -> Option<Promise> {
-	future_to_promise(cx, async move {
+fn remove(
+	cx: &Context, path_str: String, Opt(recursive): Opt<bool>, Opt(options): Opt<AbortOptions>,
+) -> Option<Promise> {
+	future_to_promise_with_signal(cx, &AbortOptions::signal(options), async move {
 		let path = Path::new(&path_str);
 		let recursive = recursive.unwrap_or_default();
 
@@ -295,17 +349,55 @@ fn remove_sync(path_str: String, Opt(recursive): Opt<bool>) -> Result<()> {
 }
 
 #[js_fn]
-fn copy(cx: &Context, from_str: String, to_str: String) -> Option<Promise> {
-	future_to_promise(cx, async move {
-		let from = Path::new(&from_str);
-		let to = Path::new(&to_str);
-
-		tokio::fs::copy(from, to)
-			.await
-			.map_err(|err| translate_error("copy from", &from_str, &to_str, err))
+fn copy(cx: &Context, from_str: String, to_str: String, Opt(options): Opt<AbortOptions>) -> Option<Promise> {
+	let signal = AbortOptions::signal(options);
+	let cancellable = !matches!(signal, Signal::None);
+	future_to_promise_with_signal(cx, &signal, async move {
+		let from = PathBuf::from(&from_str);
+		let to = PathBuf::from(&to_str);
+
+		run_blocking(move |cancellation| {
+			if cancellable {
+				copy_file(&from, &to, cancellation)
+			} else {
+				fs::copy(from, to)
+			}
+		})
+		.await
+		.map_err(|err| translate_error("copy from", &from_str, &to_str, err))
 	})
 }
 
+/// Copies the contents and permissions of a file in chunks, so that the copy stops once it is cancelled.
+///
+/// The partially copied destination is removed when the copy is cancelled.
+fn copy_file(from: &Path, to: &Path, cancellation: &Cancellation) -> io::Result<u64> {
+	let mut source = File::open(from)?;
+	let permissions = source.metadata()?.permissions();
+	let mut destination = File::create(to)?;
+	let mut buffer = vec![0; COPY_CHUNK_SIZE];
+	let mut copied = 0;
+
+	loop {
+		if cancellation.is_cancelled() {
+			drop(destination);
+			let _ = fs::remove_file(to);
+			return Err(io::Error::from(io::ErrorKind::Interrupted));
+		}
+		match source.read(&mut buffer) {
+			Ok(0) => break,
+			Ok(read) => {
+				destination.write_all(&buffer[..read])?;
+				copied += read as u64;
+			}
+			Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+			Err(err) => return Err(err),
+		}
+	}
+	destination.set_permissions(permissions)?;
+	Ok(copied)
+}
+
 #[js_fn]
 fn copy_sync(from_str: String, to_str: String) -> Result<u64> {
 	let from = Path::new(&from_str);
@@ -315,8 +407,8 @@ fn copy_sync(from_str: String, to_str: String) -> Result<u64> {
 }
 
 #[js_fn]
-fn rename(cx: &Context, from_str: String, to_str: String) -> Option<Promise> {
-	future_to_promise(cx, async move {
+fn rename(cx: &Context, from_str: String, to_str: String, Opt(options): Opt<AbortOptions>) -> Option<Promise> {
+	future_to_promise_with_signal(cx, &AbortOptions::signal(options), async move {
 		let from = Path::new(&from_str);
 		let to = Path::new(&to_str);
 
@@ -335,8 +427,8 @@ fn rename_sync(from_str: String, to_str: String) -> Result<()> {
 }
 
 #[js_fn]
-fn symlink(cx: &Context, original_str: String, link_str: String) -> Option<Promise> {
-	future_to_promise(cx, async move {
+fn symlink(cx: &Context, original_str: String, link_str: String, Opt(options): Opt<AbortOptions>) -> Option<Promise> {
+	future_to_promise_with_signal(cx, &AbortOptions::signal(options), async move {
 		let original = Path::new(&original_str);
 		let link = Path::new(&link_str);
 
@@ -379,8 +471,8 @@ fn symlink_sync(original_str: String, link_str: String) -> Result<()> {
 }
 
 #[js_fn]
-fn link(cx: &Context, original_str: String, link_str: String) -> Option<Promise> {
-	future_to_promise(cx, async move {
+fn link(cx: &Context, original_str: String, link_str: String, Opt(options): Opt<AbortOptions>) -> Option<Promise> {
+	future_to_promise_with_signal(cx, &AbortOptions::signal(options), async move {
 		let original = Path::new(&original_str);
 		let link = Path::new(&link_str);
 
@@ -399,8 +491,8 @@ fn link_sync(original_str: String, link_str: String) -> Result<()> {
 }
 
 #[js_fn]
-fn read_link(cx: &Context, path_str: String) -> Option<Promise> {
-	future_to_promise(cx, async move {
+fn read_link(cx: &Context, path_str: String, Opt(options): Opt<AbortOptions>) -> Option<Promise> {
+	future_to_promise_with_signal(cx, &AbortOptions::signal(options), async move {
 		let path = Path::new(&path_str);
 
 		match tokio::fs::read_link(&path).await {
@@ -421,8 +513,8 @@ fn read_link_sync(path_str: String) -> Result<String> {
 }
 
 #[js_fn]
-fn canonical(cx: &Context, path_str: String) -> Option<Promise> {
-	future_to_promise(cx, async move {
+fn canonical(cx: &Context, path_str: String, Opt(options): Opt<AbortOptions>) -> Option<Promise> {
+	future_to_promise_with_signal(cx, &AbortOptions::signal(options), async move {
 		let path = Path::new(&path_str);
 
 		match tokio::fs::canonicalize(&path).await {
@@ -445,8 +537,9 @@ fn canonical_sync(path_str: String) -> Result<String> {
 const SYNC_FUNCTIONS: &[JSFunctionSpec] = &[
 	function_spec!(open_sync, c"open", 1),
 	function_spec!(create_sync, c"create", 1),
-	function_spec!(metadata, c"metadata", 1),
-	function_spec!(link_metadata, c"linkMetadata", 1),
+	function_spec!(metadata_sync, c"metadata", 1),
+	function_spec!(link_metadata_sync, c"linkMetadata", 1),
+	function_spec!(checksum_sync, c"checksum", 1),
 	function_spec!(read_dir_sync, c"readDir", 1),
 	function_spec!(create_dir_sync, c"createDir", 1),
 	function_spec!(remove_sync, c"remove", 1),
@@ -464,7 +557,9 @@ const ASYNC_FUNCTIONS: &[JSFunctionSpec] = &[
 	function_spec!(create, 1),
 	function_spec!(metadata, 1),
 	function_spec!(link_metadata, c"linkMetadata", 1),
+	function_spec!(checksum, 1),
 	function_spec!(read_dir, c"readDir", 1),
+	function_spec!(walk, 1),
 	function_spec!(create_dir, c"createDir", 1),
 	function_spec!(remove, c"remove", 1),
 	function_spec!(copy, 2),
@@ -473,6 +568,8 @@ const ASYNC_FUNCTIONS: &[JSFunctionSpec] = &[
 	function_spec!(link, c"link", 2),
 	function_spec!(read_link, c"readLink", 1),
 	function_spec!(canonical, c"canonical", 1),
+	function_spec!(get_file_handle, c"getFileHandle", 1),
+	function_spec!(get_directory_handle, c"getDirectoryHandle", 1),
 	JSFunctionSpec::ZERO,
 ];
 
@@ -508,16 +605,23 @@ impl<'cx> NativeModule<'cx> for FileSystem<'cx> {
 
 		let mut result = unsafe { fs.define_methods(cx, ASYNC_FUNCTIONS) }
 			&& fs.define_as(cx, "sync", &self.sync, PropertyFlags::CONSTANT_ENUMERATED)
-			&& FileHandle::init_class(cx, &fs).0;
+			&& FileHandle::init_class(cx, &fs).0
+			&& FileSystemFileHandle::init_class(cx, &fs).0
+			&& FileSystemDirectoryHandle::init_class(cx, &fs).0
+			&& Hasher::init_class(cx, &fs).0
+			&& Walker::init_class(cx, &fs).0;
 
 		macro_rules! key {
 			($key:literal) => {
 				($key, concat!($key, "Sync"))
 			};
 		}
-		const SYNC_KEYS: [(&str, &str); 11] = [
+		const SYNC_KEYS: [(&str, &str); 14] = [
 			key!("open"),
 			key!("create"),
+			key!("metadata"),
+			key!("linkMetadata"),
+			key!("checksum"),
 			key!("readDir"),
 			key!("createDir"),
 			key!("remove"),
@@ -10,6 +10,8 @@ export const create = ______fsSyncInternal______.create;
 export const metadata = ______fsSyncInternal______.metadata;
 export const linkMetadata = ______fsSyncInternal______.linkMetadata;
 
+export const checksum = ______fsSyncInternal______.checksum;
+
 export const readDir = ______fsSyncInternal______.readDir;
 export const createDir = ______fsSyncInternal______.createDir;
 export const remove = ______fsSyncInternal______.remove;
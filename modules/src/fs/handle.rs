@@ -21,11 +21,11 @@ use ion::function::Opt;
 use ion::typedarray::{Uint8Array, Uint8ArrayWrapper};
 use ion::{Context, Error, ErrorKind, Promise, Result, TracedHeap, Value};
 use mozjs::jsval::DoubleValue;
+use runtime::globals::abort::Signal;
 use runtime::globals::file::BufferSource;
-use runtime::promise::future_to_promise;
-use tokio::task::spawn_blocking;
+use runtime::promise::{future_to_promise_with_signal, run_blocking};
 
-use crate::fs::{file_error, seek_error, Metadata};
+use crate::fs::{file_error, seek_error, AbortOptions, Metadata, MetadataOptions};
 
 #[derive(Copy, Clone, Debug, Default)]
 pub enum SeekMode {
@@ -136,12 +136,11 @@ impl FileHandle {
 			})
 			.await;
 
-			let (handle, result) = spawn_blocking(move || {
+			let (handle, result) = run_blocking(move |_| {
 				let result = callback(&mut handle);
 				(handle, result)
 			})
-			.await
-			.unwrap();
+			.await;
 
 			if taken {
 				handle_cell.borrow_mut().replace(handle);
@@ -151,9 +150,11 @@ impl FileHandle {
 		}
 	}
 
+	/// Runs a blocking operation on the file, and returns a promise which settles with its result, or is rejected once
+	/// the signal is aborted.
 	#[allow(clippy::too_many_arguments)]
 	pub(crate) fn with_blocking_promise<'cx, F, T, A, E, D>(
-		&self, cx: &'cx Context, action: &'static str, path: Arc<str>, callback: F, callback_after: A,
+		&self, cx: &'cx Context, signal: &Signal, action: &'static str, path: Arc<str>, callback: F, callback_after: A,
 		error_callback: E, error_data: D,
 	) -> Option<Promise<'cx>>
 	where
@@ -164,7 +165,7 @@ impl FileHandle {
 		D: 'static,
 	{
 		let task = self.with_blocking_task(callback);
-		future_to_promise(cx, async move {
+		future_to_promise_with_signal(cx, signal, async move {
 			let result = task.await.map_err(|err| error_callback(action, &path, err, error_data));
 			callback_after();
 			result
@@ -174,7 +175,18 @@ impl FileHandle {
 
 #[js_class]
 impl FileHandle {
-	pub fn read<'cx>(&self, cx: &'cx Context, Opt(array): Opt<Uint8Array>) -> Option<Promise<'cx>> {
+	/// Reads into the array, or reads the rest of the file if there is none.
+	///
+	/// The signal only applies when reading the rest of the file, as the array must outlive the read.
+	pub fn read<'cx>(
+		&self, cx: &'cx Context, Opt(array): Opt<Option<Uint8Array>>, Opt(options): Opt<AbortOptions>,
+	) -> Option<Promise<'cx>> {
+		let array = array.flatten();
+		let signal = if array.is_none() {
+			AbortOptions::signal(options)
+		} else {
+			Signal::None
+		};
 		let path = Arc::clone(&self.path);
 		let bytes = array.as_ref().map(|array| unsafe {
 			let (ptr, len) = array.data();
@@ -184,6 +196,7 @@ impl FileHandle {
 
 		self.with_blocking_promise(
 			cx,
+			&signal,
 			"read",
 			path,
 			move |file| read_inner(file, bytes),
@@ -202,12 +215,13 @@ impl FileHandle {
 	}
 
 	pub fn write<'cx>(
-		&self, cx: &'cx Context, #[ion(convert = false)] contents: BufferSource<'cx>,
+		&self, cx: &'cx Context, #[ion(convert = false)] contents: BufferSource<'cx>, Opt(options): Opt<AbortOptions>,
 	) -> Option<Promise<'cx>> {
 		let path = Arc::clone(&self.path);
 		let contents = contents.to_vec();
 		self.with_blocking_promise(
 			cx,
+			&AbortOptions::signal(options),
 			"write",
 			path,
 			move |file| file.write(&contents).map(|bytes| bytes as u64),
@@ -230,12 +244,13 @@ impl FileHandle {
 
 	#[ion(name = "writeAll")]
 	pub fn write_all<'cx>(
-		&self, cx: &'cx Context, #[ion(convert = false)] contents: BufferSource<'cx>,
+		&self, cx: &'cx Context, #[ion(convert = false)] contents: BufferSource<'cx>, Opt(options): Opt<AbortOptions>,
 	) -> Option<Promise<'cx>> {
 		let path = Arc::clone(&self.path);
 		let contents = contents.to_vec();
 		self.with_blocking_promise(
 			cx,
+			&AbortOptions::signal(options),
 			"write",
 			path,
 			move |file| file.write_all(&contents).map(|_| ()),
@@ -262,6 +277,7 @@ impl FileHandle {
 		let path = Arc::clone(&self.path);
 		self.with_blocking_promise(
 			cx,
+			&Signal::None,
 			"truncate",
 			path,
 			move |file| file.set_len(length.unwrap_or(0)),
@@ -289,6 +305,7 @@ impl FileHandle {
 		let mode = mode.unwrap_or_default();
 		self.with_blocking_promise(
 			cx,
+			&Signal::None,
 			"seek",
 			path,
 			move |file| {
@@ -314,7 +331,16 @@ impl FileHandle {
 
 	pub fn sync<'cx>(&self, cx: &'cx Context) -> Option<Promise<'cx>> {
 		let path = Arc::clone(&self.path);
-		self.with_blocking_promise(cx, "sync", path, move |file| file.sync_all(), || {}, file_error, ())
+		self.with_blocking_promise(
+			cx,
+			&Signal::None,
+			"sync",
+			path,
+			move |file| file.sync_all(),
+			|| {},
+			file_error,
+			(),
+		)
 	}
 
 	#[ion(name = "syncSync")]
@@ -327,6 +353,7 @@ impl FileHandle {
 		let path = Arc::clone(&self.path);
 		self.with_blocking_promise(
 			cx,
+			&Signal::None,
 			"sync data for",
 			path,
 			move |file| file.sync_data(),
@@ -341,13 +368,14 @@ impl FileHandle {
 		self.with_sync(|file| file.sync_data().map_err(|err| file_error("sync data for", &self.path, err, ())))
 	}
 
-	pub fn metadata<'cx>(&self, cx: &'cx Context) -> Option<Promise<'cx>> {
+	pub fn metadata<'cx>(&self, cx: &'cx Context, Opt(options): Opt<MetadataOptions>) -> Option<Promise<'cx>> {
 		let path = Arc::clone(&self.path);
 		self.with_blocking_promise(
 			cx,
+			&Signal::None,
 			"get metadata for",
 			path,
-			move |file| file.metadata().map(Metadata),
+			move |file| file.metadata().map(|metadata| Metadata::new(metadata, options)),
 			|| {},
 			file_error,
 			(),
@@ -355,10 +383,10 @@ impl FileHandle {
 	}
 
 	#[ion(name = "metadataSync")]
-	pub fn metadata_sync(&self) -> Result<Metadata> {
+	pub fn metadata_sync(&self, Opt(options): Opt<MetadataOptions>) -> Result<Metadata> {
 		self.with_sync(|file| {
 			file.metadata()
-				.map(Metadata)
+				.map(|metadata| Metadata::new(metadata, options))
 				.map_err(|err| file_error("get metadata for", &self.path, err, ()))
 		})
 	}
@@ -5,17 +5,24 @@
  */
 
 use std::io;
-use std::time::SystemTime;
+use std::time::{SystemTime, UNIX_EPOCH};
 
+pub use access::{FileSystemDirectoryHandle, FileSystemFileHandle};
+pub use checksum::Hasher;
 use chrono::DateTime;
 pub use fs::*;
 pub use handle::*;
-use ion::conversions::ToValue;
-use ion::{Context, Date, Error, Object, Value};
+use ion::conversions::{FromValue, ToValue};
+use ion::{BigInt, Context, Date, Error, Result, Value};
+use runtime::globals::abort::{AbortSignal, Signal};
+pub use walk::Walker;
 
+mod access;
+mod checksum;
 mod dir;
 mod fs;
 mod handle;
+mod walk;
 
 pub(crate) fn base_error(base: &str, path: &str, err: io::Error) -> Error {
 	Error::new(format!("Could not {} {}: {}", base, path, err), None)
@@ -47,8 +54,91 @@ pub(crate) fn translate_error(action: &str, from: &str, to: &str, err: io::Error
 	Error::new(format!("Could not {} {} to {}: {}", action, from, to, err), None)
 }
 
+/// Options of asynchronous operations which can be cancelled with an [AbortSignal].
+#[derive(Default, FromValue)]
+pub struct AbortOptions {
+	signal: Option<AbortSignal>,
+}
+
+impl AbortOptions {
+	pub(crate) fn signal(options: Option<AbortOptions>) -> Signal {
+		options
+			.and_then(|options| options.signal)
+			.map(|signal| signal.signal().clone())
+			.unwrap_or_default()
+	}
+}
+
+/// Options of an asynchronous operation, along with the [AbortSignal] from the same object which cancels it.
+pub struct Abortable<T> {
+	options: T,
+	signal: Signal,
+}
+
+impl<T: Default> Abortable<T> {
+	pub(crate) fn split(abortable: Option<Abortable<T>>) -> (T, Signal) {
+		abortable.map(|abortable| (abortable.options, abortable.signal)).unwrap_or_default()
+	}
+}
+
+impl<'cx, T: FromValue<'cx, Config = ()>> FromValue<'cx> for Abortable<T> {
+	type Config = ();
+
+	fn from_value(cx: &'cx Context, value: &Value, strict: bool, _: ()) -> Result<Abortable<T>> {
+		let options = T::from_value(cx, value, strict, ())?;
+		let abort = AbortOptions::from_value(cx, value, strict, ())?;
+		Ok(Abortable {
+			options,
+			signal: AbortOptions::signal(Some(abort)),
+		})
+	}
+}
+
+/// Largest integer which can be represented exactly by a double, above which integers are exposed as [BigInt].
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+#[derive(Copy, Clone, Debug, Default, FromValue)]
+pub struct MetadataOptions {
+	#[ion(default)]
+	bigint: bool,
+}
+
+/// Metadata of a file, directory or symlink.
+///
+/// Integers are exposed as [BigInt] if `bigint` is set or they exceed [MAX_SAFE_INTEGER]. When `bigint` is set,
+/// timestamps are also exposed in nanoseconds since the Unix epoch.
 #[derive(Debug)]
-pub struct Metadata(pub(crate) std::fs::Metadata);
+pub struct Metadata {
+	metadata: std::fs::Metadata,
+	bigint: bool,
+}
+
+impl Metadata {
+	pub(crate) fn new(metadata: std::fs::Metadata, options: Option<MetadataOptions>) -> Metadata {
+		Metadata {
+			metadata,
+			bigint: options.unwrap_or_default().bigint,
+		}
+	}
+
+	fn integer<'cx>(&self, cx: &'cx Context, integer: Option<u64>) -> Value<'cx> {
+		match integer {
+			Some(integer) if self.bigint || integer > MAX_SAFE_INTEGER => BigInt::from_u64(cx, integer).as_value(cx),
+			integer => integer.as_value(cx),
+		}
+	}
+}
+
+#[cfg(unix)]
+fn blocks_and_inode(metadata: &std::fs::Metadata) -> (Option<u64>, Option<u64>) {
+	use std::os::unix::fs::MetadataExt;
+	(Some(metadata.blocks()), Some(metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn blocks_and_inode(_: &std::fs::Metadata) -> (Option<u64>, Option<u64>) {
+	(None, None)
+}
 
 impl ToValue<'_> for Metadata {
 	fn to_value(&self, cx: &Context, value: &mut Value) {
@@ -56,18 +146,36 @@ impl ToValue<'_> for Metadata {
 			time.ok().map(|time| Date::from_date(cx, DateTime::from(time)))
 		}
 
-		let obj = Object::new(cx);
-		obj.set_as(cx, "size", &self.0.len());
-
-		obj.set_as(cx, "isFile", &self.0.is_file());
-		obj.set_as(cx, "isDirectory", &self.0.is_dir());
-		obj.set_as(cx, "isSymlink", &self.0.is_symlink());
+		fn system_time_into_nanoseconds(cx: &Context, time: io::Result<SystemTime>) -> Option<BigInt> {
+			let nanoseconds = match time.ok()?.duration_since(UNIX_EPOCH) {
+				Ok(duration) => i64::try_from(duration.as_nanos()).ok()?,
+				Err(err) => -i64::try_from(err.duration().as_nanos()).ok()?,
+			};
+			Some(BigInt::from_i64(cx, nanoseconds))
+		}
 
-		obj.set_as(cx, "created", &system_time_into_date(cx, self.0.created()));
-		obj.set_as(cx, "accessed", &system_time_into_date(cx, self.0.accessed()));
-		obj.set_as(cx, "modified", &system_time_into_date(cx, self.0.modified()));
+		let (blocks, inode) = blocks_and_inode(&self.metadata);
+		let obj = object!(cx, {
+			"size": self.integer(cx, Some(self.metadata.len())),
+			"blocks": self.integer(cx, blocks),
+			"inode": self.integer(cx, inode),
+
+			"isFile": self.metadata.is_file(),
+			"isDirectory": self.metadata.is_dir(),
+			"isSymlink": self.metadata.is_symlink(),
+
+			"created": system_time_into_date(cx, self.metadata.created()),
+			"accessed": system_time_into_date(cx, self.metadata.accessed()),
+			"modified": system_time_into_date(cx, self.metadata.modified()),
+		});
+
+		if self.bigint {
+			obj.set_as(cx, "createdNs", &system_time_into_nanoseconds(cx, self.metadata.created()));
+			obj.set_as(cx, "accessedNs", &system_time_into_nanoseconds(cx, self.metadata.accessed()));
+			obj.set_as(cx, "modifiedNs", &system_time_into_nanoseconds(cx, self.metadata.modified()));
+		}
 
-		obj.set_as(cx, "readonly", &self.0.permissions().readonly());
+		obj.set_as(cx, "readonly", &self.metadata.permissions().readonly());
 
 		obj.to_value(cx, value);
 	}
@@ -0,0 +1,260 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::{HashSet, VecDeque};
+use std::fs::{canonicalize, ReadDir};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::{fs, io};
+
+use ion::class::Reflector;
+use ion::conversions::ToValue;
+use ion::symbol::WellKnownSymbolCode;
+use ion::{Context, Object, Promise, Result, Value};
+use mozjs::conversions::ConversionBehavior;
+use runtime::globals::abort::Signal;
+use runtime::promise::{future_to_promise, future_to_promise_with_signal, run_blocking};
+use tokio::sync::Mutex;
+
+use crate::fs::{base_error, Metadata};
+
+/// Number of entries read on the blocking thread pool at a time.
+const BATCH_SIZE: usize = 256;
+
+#[derive(Clone, Debug, FromValue)]
+pub struct WalkOptions {
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	max_depth: Option<u32>,
+	#[ion(default = true)]
+	include_dirs: bool,
+	#[ion(default)]
+	follow_symlinks: bool,
+	/// Names of entries which are neither yielded nor descended into.
+	#[ion(default)]
+	skip: Vec<String>,
+}
+
+impl Default for WalkOptions {
+	fn default() -> WalkOptions {
+		WalkOptions {
+			max_depth: None,
+			include_dirs: true,
+			follow_symlinks: false,
+			skip: Vec::new(),
+		}
+	}
+}
+
+struct WalkEntry {
+	path: PathBuf,
+	depth: u32,
+	metadata: Metadata,
+}
+
+impl ToValue<'_> for WalkEntry {
+	fn to_value(&self, cx: &Context, value: &mut Value) {
+		let name = self.path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+
+		let obj = object!(cx, {
+			"path": self.path.to_string_lossy().into_owned(),
+			"name": name,
+			"depth": self.depth,
+			"metadata": self.metadata,
+		});
+		obj.to_value(cx, value);
+	}
+}
+
+type WalkItem = std::result::Result<WalkEntry, (PathBuf, io::Error)>;
+
+/// Depth-first traversal of a directory tree, yielding each directory before its contents.
+struct Walk {
+	root: Option<PathBuf>,
+	stack: Vec<(PathBuf, ReadDir, u32)>,
+	visited: HashSet<PathBuf>,
+	options: WalkOptions,
+}
+
+impl Walk {
+	fn new(root: PathBuf, options: WalkOptions) -> Walk {
+		Walk {
+			root: Some(root),
+			stack: Vec::new(),
+			visited: HashSet::new(),
+			options,
+		}
+	}
+
+	/// Queues the entries of a directory, whose entries are at `depth`.
+	///
+	/// When following symlinks, directories which have already been visited are not queued again.
+	fn descend(&mut self, path: &Path, depth: u32) -> io::Result<()> {
+		if self.options.max_depth.is_some_and(|max_depth| depth > max_depth) {
+			return Ok(());
+		}
+		if self.options.follow_symlinks && !self.visited.insert(canonicalize(path)?) {
+			return Ok(());
+		}
+
+		let dir = fs::read_dir(path)?;
+		self.stack.push((path.to_path_buf(), dir, depth));
+		Ok(())
+	}
+}
+
+impl Iterator for Walk {
+	type Item = WalkItem;
+
+	fn next(&mut self) -> Option<WalkItem> {
+		if let Some(root) = self.root.take() {
+			if let Err(err) = self.descend(&root, 1) {
+				return Some(Err((root, err)));
+			}
+		}
+
+		loop {
+			let (dir_path, dir, depth) = self.stack.last_mut()?;
+			let depth = *depth;
+			let entry = match dir.next() {
+				Some(Ok(entry)) => entry,
+				Some(Err(err)) => return Some(Err((dir_path.clone(), err))),
+				None => {
+					self.stack.pop();
+					continue;
+				}
+			};
+
+			if self.options.skip.iter().any(|name| entry.file_name() == name.as_str()) {
+				continue;
+			}
+
+			let path = entry.path();
+			let metadata = if self.options.follow_symlinks {
+				fs::metadata(&path).or_else(|_| entry.metadata())
+			} else {
+				entry.metadata()
+			};
+			let metadata = match metadata {
+				Ok(metadata) => metadata,
+				Err(err) => return Some(Err((path, err))),
+			};
+
+			if metadata.is_dir() {
+				if let Err(err) = self.descend(&path, depth + 1) {
+					return Some(Err((path, err)));
+				}
+				if !self.options.include_dirs {
+					continue;
+				}
+			}
+
+			let metadata = Metadata::new(metadata, None);
+			return Some(Ok(WalkEntry { path, depth, metadata }));
+		}
+	}
+}
+
+struct WalkStep(Option<WalkEntry>);
+
+impl ToValue<'_> for WalkStep {
+	fn to_value(&self, cx: &Context, value: &mut Value) {
+		let obj = Object::new(cx);
+		if let Some(entry) = &self.0 {
+			obj.set_as(cx, "value", entry);
+		}
+		obj.set_as(cx, "done", &self.0.is_none());
+		obj.to_value(cx, value);
+	}
+}
+
+struct WalkerState {
+	walk: Option<Walk>,
+	buffer: VecDeque<WalkItem>,
+}
+
+impl WalkerState {
+	async fn next(state: Rc<Mutex<WalkerState>>) -> Result<WalkStep> {
+		let mut state = state.lock().await;
+		while state.buffer.is_empty() {
+			let Some(mut walk) = state.walk.take() else {
+				break;
+			};
+			let (walk, batch, exhausted) = run_blocking(move |cancellation| {
+				let mut batch = Vec::with_capacity(BATCH_SIZE);
+				while batch.len() < BATCH_SIZE && !cancellation.is_cancelled() {
+					match walk.next() {
+						Some(item) => batch.push(item),
+						None => return (walk, batch, true),
+					}
+				}
+				(walk, batch, false)
+			})
+			.await;
+
+			// A batch may be cut short, so the walk only ends once the iterator is exhausted.
+			if !exhausted {
+				state.walk = Some(walk);
+			}
+			state.buffer.extend(batch);
+		}
+
+		match state.buffer.pop_front() {
+			Some(Ok(entry)) => Ok(WalkStep(Some(entry))),
+			Some(Err((path, err))) => Err(base_error("walk", &path.to_string_lossy(), err)),
+			None => Ok(WalkStep(None)),
+		}
+	}
+
+	async fn finish(state: Rc<Mutex<WalkerState>>) -> Result<WalkStep> {
+		let mut state = state.lock().await;
+		state.walk = None;
+		state.buffer.clear();
+		Ok(WalkStep(None))
+	}
+}
+
+/// Asynchronous iterator over the entries of a directory tree.
+///
+/// Entries and their metadata are read on the blocking thread pool in batches, rather than with a promise for each
+/// directory. Once the signal passed to `walk` is aborted, each step is rejected with its reason.
+#[js_class]
+pub struct Walker {
+	reflector: Reflector,
+	#[trace(no_trace)]
+	state: Rc<Mutex<WalkerState>>,
+	#[trace(no_trace)]
+	signal: Signal,
+}
+
+impl Walker {
+	pub(crate) fn new(root: PathBuf, options: WalkOptions, signal: Signal) -> Walker {
+		Walker {
+			reflector: Reflector::new(),
+			state: Rc::new(Mutex::new(WalkerState {
+				walk: Some(Walk::new(root, options)),
+				buffer: VecDeque::new(),
+			})),
+			signal,
+		}
+	}
+}
+
+#[js_class]
+impl Walker {
+	pub fn next<'cx>(&self, cx: &'cx Context) -> Option<Promise<'cx>> {
+		future_to_promise_with_signal(cx, &self.signal, WalkerState::next(Rc::clone(&self.state)))
+	}
+
+	#[ion(name = "return")]
+	pub fn finish<'cx>(&self, cx: &'cx Context) -> Option<Promise<'cx>> {
+		future_to_promise(cx, WalkerState::finish(Rc::clone(&self.state)))
+	}
+
+	#[ion(name = WellKnownSymbolCode::AsyncIterator)]
+	pub fn async_iterator<'cx>(&self, cx: &'cx Context) -> Object<'cx> {
+		cx.root(self.reflector.get()).into()
+	}
+}
@@ -10,26 +10,42 @@
 extern crate ion;
 
 use ion::{Context, Object};
+use runtime::globals::enabled_globals;
 use runtime::module::{init_global_module, init_module, StandardModules};
 
 pub use crate::assert::Assert;
+pub use crate::buffer::BufferM;
+#[cfg(feature = "fs")]
 pub use crate::fs::{FileSystem, FileSystemSync};
 pub use crate::path::PathM;
+pub use crate::test::Test;
 pub use crate::url::UrlM;
+pub use crate::util::UtilM;
 
 mod assert;
+mod buffer;
+#[cfg(feature = "fs")]
 mod fs;
+mod node;
 mod path;
+mod test;
 mod url;
+mod util;
 
 macro_rules! inner_init {
 	($cx:ident, $global:ident, $init:ident) => {{
 		fn inner(cx: &Context, global: &Object) -> Option<()> {
 			$init(cx, global, &Assert)?;
-			let fs_sync = $init(cx, global, &FileSystemSync)?;
-			$init(cx, global, &FileSystem { sync: &fs_sync })?;
+			$init(cx, global, &BufferM)?;
+			#[cfg(feature = "fs")]
+			if enabled_globals(cx).fs {
+				let fs_sync = $init(cx, global, &FileSystemSync)?;
+				$init(cx, global, &FileSystem { sync: &fs_sync })?;
+			}
 			$init(cx, global, &PathM)?;
+			$init(cx, global, &Test)?;
 			$init(cx, global, &UrlM)?;
+			$init(cx, global, &UtilM)?;
 			Some(())
 		}
 		inner($cx, $global).is_some()
@@ -40,7 +56,8 @@ pub struct Modules;
 
 impl StandardModules for Modules {
 	fn init(self, cx: &Context, global: &Object) -> bool {
-		inner_init!(cx, global, init_module)
+		let fs = cfg!(feature = "fs") && enabled_globals(cx).fs;
+		inner_init!(cx, global, init_module) && node::init_compat(cx, fs)
 	}
 
 	fn init_globals(self, cx: &Context, global: &Object) -> bool {
@@ -0,0 +1,68 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import * as assert from "assert";
+
+export function ok(value, message) {
+	assert.ok(Boolean(value), message);
+}
+
+export function equal(actual, expected, message) {
+	assert.ok(actual == expected, message);
+}
+
+export function notEqual(actual, expected, message) {
+	assert.ok(actual != expected, message);
+}
+
+export function strictEqual(actual, expected, message) {
+	assert.equals(actual, expected, message);
+}
+
+export function notStrictEqual(actual, expected, message) {
+	assert.ok(!Object.is(actual, expected), message);
+}
+
+export function throws(fn, message) {
+	try {
+		fn();
+	} catch {
+		return;
+	}
+	assert.fail(message ?? "Missing expected exception");
+}
+
+export function doesNotThrow(fn, message) {
+	try {
+		fn();
+	} catch {
+		assert.fail(message ?? "Got unwanted exception");
+	}
+}
+
+export const fail = assert.fail;
+export const match = assert.match;
+export const doesNotMatch = assert.doesNotMatch;
+
+function nodeAssert(value, message) {
+	ok(value, message);
+}
+
+Object.assign(nodeAssert, {
+	ok,
+	equal,
+	notEqual,
+	strictEqual,
+	notStrictEqual,
+	throws,
+	doesNotThrow,
+	fail,
+	match,
+	doesNotMatch,
+});
+nodeAssert.strict = nodeAssert;
+
+export default Object.freeze(nodeAssert);
@@ -0,0 +1,92 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import * as fs from "fs";
+import promises from "node:fs/promises";
+
+// toStats, encode and decode are prepended from fs_common.js.
+
+export function readFileSync(path, options) {
+	return decode(fs.openSync(path).readSync(), options);
+}
+
+export function writeFileSync(path, data) {
+	fs.createSync(path).writeAllSync(encode(data));
+}
+
+export function appendFileSync(path, data) {
+	fs.openSync(path, {append: true, create: true}).writeAllSync(encode(data));
+}
+
+export function existsSync(path) {
+	try {
+		fs.metadataSync(path);
+		return true;
+	} catch {
+		return false;
+	}
+}
+
+export function readdirSync(path) {
+	return Array.from(fs.readDirSync(path), entry => entry.name());
+}
+
+export function mkdirSync(path, options) {
+	fs.createDirSync(path, options?.recursive);
+}
+
+export function rmSync(path, options) {
+	fs.removeSync(path, options?.recursive);
+}
+
+export function rmdirSync(path, options) {
+	fs.removeSync(path, options?.recursive);
+}
+
+export function unlinkSync(path) {
+	fs.removeSync(path);
+}
+
+export function copyFileSync(source, destination) {
+	fs.copySync(source, destination);
+}
+
+export function statSync(path, options) {
+	return toStats(fs.metadataSync(path, {bigint: options?.bigint}));
+}
+
+export function lstatSync(path, options) {
+	return toStats(fs.linkMetadataSync(path, {bigint: options?.bigint}));
+}
+
+export const renameSync = fs.renameSync;
+export const symlinkSync = fs.symlinkSync;
+export const linkSync = fs.linkSync;
+export const readlinkSync = fs.readLinkSync;
+export const realpathSync = fs.canonicalSync;
+
+export {promises};
+
+export default Object.freeze({
+	readFileSync,
+	writeFileSync,
+	appendFileSync,
+	existsSync,
+	readdirSync,
+	mkdirSync,
+	rmSync,
+	rmdirSync,
+	unlinkSync,
+	copyFileSync,
+	statSync,
+	lstatSync,
+	renameSync,
+	symlinkSync,
+	linkSync,
+	readlinkSync,
+	realpathSync,
+	promises,
+});
@@ -0,0 +1,33 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+// Helpers shared by `node:fs` and `node:fs/promises`, which are prepended to both modules.
+
+function toStats(metadata) {
+	return {
+		size: metadata.size,
+		blocks: metadata.blocks,
+		ino: metadata.inode,
+		birthtime: metadata.created,
+		atime: metadata.accessed,
+		mtime: metadata.modified,
+		birthtimeNs: metadata.createdNs,
+		atimeNs: metadata.accessedNs,
+		mtimeNs: metadata.modifiedNs,
+		isFile: () => metadata.isFile,
+		isDirectory: () => metadata.isDirectory,
+		isSymbolicLink: () => metadata.isSymlink,
+	};
+}
+
+function encode(data) {
+	return typeof data === "string" ? new TextEncoder().encode(data) : data;
+}
+
+function decode(bytes, options) {
+	const encoding = typeof options === "string" ? options : options?.encoding;
+	return encoding ? new TextDecoder(encoding).decode(bytes) : bytes;
+}
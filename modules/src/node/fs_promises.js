@@ -0,0 +1,84 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import * as fs from "fs";
+
+// toStats, encode and decode are prepended from fs_common.js.
+
+export async function readFile(path, options) {
+	const signal = options?.signal;
+	const handle = await fs.open(path, {signal});
+	return decode(await handle.read(undefined, {signal}), options);
+}
+
+export async function writeFile(path, data, options) {
+	const signal = options?.signal;
+	const handle = await fs.create(path, {signal});
+	await handle.writeAll(encode(data), {signal});
+}
+
+export async function appendFile(path, data, options) {
+	const signal = options?.signal;
+	const handle = await fs.open(path, {append: true, create: true, signal});
+	await handle.writeAll(encode(data), {signal});
+}
+
+export async function readdir(path) {
+	return Array.from(await fs.readDir(path), entry => entry.name());
+}
+
+export async function mkdir(path, options) {
+	await fs.createDir(path, options?.recursive);
+}
+
+export async function rm(path, options) {
+	await fs.remove(path, options?.recursive);
+}
+
+export async function rmdir(path, options) {
+	await fs.remove(path, options?.recursive);
+}
+
+export async function unlink(path) {
+	await fs.remove(path);
+}
+
+export async function copyFile(source, destination) {
+	await fs.copy(source, destination);
+}
+
+export async function stat(path, options) {
+	return toStats(await fs.metadata(path, {bigint: options?.bigint}));
+}
+
+export async function lstat(path, options) {
+	return toStats(await fs.linkMetadata(path, {bigint: options?.bigint}));
+}
+
+export const rename = fs.rename;
+export const symlink = fs.symlink;
+export const link = fs.link;
+export const readlink = fs.readLink;
+export const realpath = fs.canonical;
+
+export default Object.freeze({
+	readFile,
+	writeFile,
+	appendFile,
+	readdir,
+	mkdir,
+	rm,
+	rmdir,
+	unlink,
+	copyFile,
+	stat,
+	lstat,
+	rename,
+	symlink,
+	link,
+	readlink,
+	realpath,
+});
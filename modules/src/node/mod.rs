@@ -0,0 +1,25 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use ion::Context;
+use runtime::module::register_module;
+
+/// Modules which adapt the standard modules to the APIs of their Node.js equivalents.
+/// Other `node:` specifiers are resolved by the loader to the standard module of the same name.
+const COMPAT_MODULES: &[(&str, &str)] = &[
+	("node:assert", include_str!("assert.js")),
+	("node:fs", concat!(include_str!("fs_common.js"), include_str!("fs.js"))),
+	("node:fs/promises", concat!(include_str!("fs_common.js"), include_str!("fs_promises.js"))),
+	("node:path", include_str!("path.js")),
+];
+
+/// Registers the compatibility modules, excluding those for `fs` if it is disabled.
+pub fn init_compat(cx: &Context, fs: bool) -> bool {
+	COMPAT_MODULES
+		.iter()
+		.filter(|(name, _)| fs || !name.starts_with("node:fs"))
+		.all(|(name, source)| register_module(cx, name, source))
+}
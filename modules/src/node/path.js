@@ -0,0 +1,39 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import * as path from "path";
+
+export const sep = path.separator;
+export const delimiter = path.delimiter;
+
+export function join(...segments) {
+	return path.join(...segments);
+}
+
+export function dirname(p) {
+	const parent = path.parent(p);
+	if (parent === null) {
+		return p;
+	}
+	return parent === "" ? "." : parent;
+}
+
+export function basename(p, suffix) {
+	const name = path.fileName(p) ?? "";
+	if (suffix !== undefined && name !== suffix && name.endsWith(suffix)) {
+		return name.slice(0, -suffix.length);
+	}
+	return name;
+}
+
+export function extname(p) {
+	const extension = path.extension(p);
+	return extension ? `.${extension}` : "";
+}
+
+export const isAbsolute = path.isAbsolute;
+
+export default Object.freeze({sep, delimiter, join, dirname, basename, extname, isAbsolute});
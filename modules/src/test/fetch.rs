@@ -0,0 +1,36 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::rc::Rc;
+
+use ion::flags::PropertyFlags;
+use ion::{Context, Function, Object, Value};
+use runtime::globals::fetch::{add_fetch_interceptor, remove_fetch_interceptor, FetchInterceptor, FunctionInterceptor};
+
+/// Adds a fetch interceptor which calls the handler with each request, which `mockFetch` is built on.
+/// Returns a function which removes the interceptor.
+#[js_fn]
+fn intercept_fetch<'cx>(cx: &'cx Context, handler: Function) -> Function<'cx> {
+	let interceptor: Rc<dyn FetchInterceptor> = Rc::new(FunctionInterceptor::new(&handler));
+	add_fetch_interceptor(cx, Rc::clone(&interceptor));
+	Function::from_closure(
+		cx,
+		c"restore",
+		Box::new(move |args| {
+			remove_fetch_interceptor(args.cx(), &interceptor);
+			Ok(Value::undefined(args.cx()))
+		}),
+		0,
+		PropertyFlags::empty(),
+	)
+}
+
+pub(crate) fn define(cx: &Context, test: &Object) -> bool {
+	!test
+		.define_method(cx, "interceptFetch", intercept_fetch, 1, PropertyFlags::empty())
+		.handle()
+		.is_null()
+}
@@ -0,0 +1,88 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+const internal = ______testInternal______;
+
+export const useFakeTimers = internal.useFakeTimers;
+export const tick = internal.tick;
+export const runAllTimers = internal.runAllTimers;
+
+function toMatcher(pattern) {
+	if (pattern instanceof RegExp) {
+		return url => url.search(pattern) !== -1;
+	}
+	const parts = String(pattern).split("*").map(part => part.replace(/[.+?^${}()|[\]\\]/g, "\\$&"));
+	const regexp = new RegExp(`^${parts.join(".*")}$`);
+	return url => regexp.test(url);
+}
+
+// Responses can only be read once, so canned responses are read on first use, and copied for each request.
+function toRoute(route) {
+	if (!(route instanceof Response)) {
+		return route;
+	}
+	let snapshot = null;
+	return async () => {
+		snapshot ??= route.arrayBuffer().then(body => ({
+			status: route.status,
+			statusText: route.statusText,
+			headers: [...route.headers],
+			body,
+		}));
+		const {body, ...init} = await snapshot;
+		return new Response(body.slice(0), init);
+	};
+}
+
+async function respond(route, request) {
+	if (typeof route === "function") {
+		route = await route(request);
+		if (route instanceof Response) {
+			return route;
+		}
+	}
+
+	const {delay = 0, error = null, body, ...init} = route ?? {};
+	if (delay > 0) {
+		await new Promise(resolve => setTimeout(resolve, delay));
+	}
+	if (error !== null && error !== false) {
+		throw error;
+	}
+	return new Response(body, init);
+}
+
+export function mockFetch(routes) {
+	if (internal.interceptFetch === undefined) {
+		throw new Error("fetch is not available");
+	}
+
+	const entries = Array.isArray(routes) || routes instanceof Map ? [...routes] : Object.entries(routes);
+	const matchers = entries.map(([pattern, route]) => [toMatcher(pattern), toRoute(route)]);
+	const calls = [];
+
+	const restore = internal.interceptFetch(request => {
+		const match = matchers.find(([matches]) => matches(request.url));
+		if (match === undefined) {
+			return undefined;
+		}
+
+		calls.push({
+			url: request.url,
+			method: request.method,
+			headers: Object.fromEntries(request.headers),
+		});
+		return respond(match[1], request);
+	});
+	return {calls, restore};
+}
+
+export default Object.freeze({
+	useFakeTimers,
+	tick,
+	runAllTimers,
+	mockFetch,
+});
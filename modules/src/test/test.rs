@@ -0,0 +1,64 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::time::Duration;
+
+use ion::function::Enforce;
+use ion::{Context, Error, ErrorReport, Exception, Object, Result, ResultExc};
+use mozjs::jsapi::JSFunctionSpec;
+use runtime::event_loop;
+use runtime::module::NativeModule;
+
+fn timer_exception(report: Option<ErrorReport>) -> Exception {
+	match report {
+		Some(report) => report.into_exception(),
+		None => Error::new("Timers were interrupted", None).into(),
+	}
+}
+
+#[js_fn]
+fn use_fake_timers(cx: &Context) -> Result<()> {
+	if event_loop::use_fake_timers(cx) {
+		Ok(())
+	} else {
+		Err(Error::new("Fake timers require deterministic mode", None))
+	}
+}
+
+/// Advances fake timers by the given number of milliseconds, running timers as they become due.
+#[js_fn]
+fn tick(cx: &Context, Enforce(ms): Enforce<u32>) -> ResultExc<u32> {
+	event_loop::advance_timers(cx, Duration::from_millis(u64::from(ms))).map_err(timer_exception)
+}
+
+#[js_fn]
+fn run_all_timers(cx: &Context) -> ResultExc<u32> {
+	event_loop::run_all_timers(cx).map_err(timer_exception)
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[
+	function_spec!(use_fake_timers, c"useFakeTimers", 0),
+	function_spec!(tick, 1),
+	function_spec!(run_all_timers, c"runAllTimers", 0),
+	JSFunctionSpec::ZERO,
+];
+
+pub struct Test;
+
+impl<'cx> NativeModule<'cx> for Test {
+	const NAME: &'static str = "test";
+	const VARIABLE_NAME: &'static str = "test";
+	const SOURCE: &'static str = include_str!("test.js");
+
+	fn module(&self, cx: &'cx Context) -> Option<Object<'cx>> {
+		let test = Object::new(cx);
+		#[cfg(feature = "fetch")]
+		if !super::fetch::define(cx, &test) {
+			return None;
+		}
+		unsafe { test.define_methods(cx, FUNCTIONS).then_some(test) }
+	}
+}
@@ -6,6 +6,9 @@
 
 export const domainToASCII = ______urlInternal______.domainToASCII;
 export const domainToUnicode = ______urlInternal______.domainToUnicode;
+export const fileURLToPath = ______urlInternal______.fileURLToPath;
+export const pathToFileURL = ______urlInternal______.pathToFileURL;
+export const urlToHttpOptions = ______urlInternal______.urlToHttpOptions;
 export const URL = ______urlInternal______.URL;
 export const URLSearchParams = ______urlInternal______.URLSearchParams;
 
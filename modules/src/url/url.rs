@@ -4,12 +4,50 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::io;
+use std::path::{absolute, Component, PathBuf, MAIN_SEPARATOR};
+
 use idna::domain_to_ascii_strict;
 use ion::function::Opt;
-use ion::{ClassDefinition, Context, Object, Result};
-use mozjs::jsapi::JSFunctionSpec;
+use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Result};
+use mozjs::jsapi::{JSFunctionSpec, JSObject};
+use percent_encoding::percent_decode_str;
 use runtime::globals::url::{URLSearchParams, URL};
 use runtime::module::NativeModule;
+use url::Url;
+
+#[derive(FromValue)]
+pub enum UrlOrString<'cx> {
+	#[ion(inherit)]
+	Url(&'cx URL),
+	#[ion(inherit)]
+	String(String),
+}
+
+impl UrlOrString<'_> {
+	fn to_url(&self) -> Result<Url> {
+		let url = match self {
+			UrlOrString::Url(url) => Url::parse(&url.get_href()),
+			UrlOrString::String(url) => Url::parse(url),
+		};
+		url.map_err(|error| Error::new(error.to_string(), ErrorKind::Type))
+	}
+}
+
+/// Resolves the path against the current working directory, and normalises `.` and `..` components.
+fn resolve(path: &str) -> io::Result<PathBuf> {
+	let mut resolved = PathBuf::new();
+	for component in absolute(path)?.components() {
+		match component {
+			Component::CurDir => {}
+			Component::ParentDir => {
+				resolved.pop();
+			}
+			component => resolved.push(component),
+		}
+	}
+	Ok(resolved)
+}
 
 #[js_fn]
 fn domain_to_ascii(domain: String, Opt(strict): Opt<bool>) -> Result<String> {
@@ -27,9 +65,82 @@ fn domain_to_unicode(domain: String) -> String {
 	idna::domain_to_unicode(&domain).0
 }
 
+#[js_fn]
+fn file_url_to_path(url: UrlOrString) -> Result<String> {
+	let url = url.to_url()?;
+	if url.scheme() != "file" {
+		return Err(Error::new("The URL must be of scheme file", ErrorKind::Type));
+	}
+
+	let path = url.path().to_ascii_lowercase();
+	if path.contains("%2f") || (cfg!(windows) && path.contains("%5c")) {
+		return Err(Error::new(
+			"File URL path must not include encoded path separators",
+			ErrorKind::Type,
+		));
+	}
+
+	match url.to_file_path() {
+		Ok(path) => Ok(path.to_string_lossy().into_owned()),
+		Err(_) => Err(Error::new(
+			"File URL must have an empty or local host, and an absolute path",
+			ErrorKind::Type,
+		)),
+	}
+}
+
+#[js_fn]
+fn path_to_file_url(cx: &Context, path: String) -> Result<*mut JSObject> {
+	let resolved = resolve(&path)?;
+	let url = if path.ends_with('/') || path.ends_with(MAIN_SEPARATOR) {
+		Url::from_directory_path(resolved)
+	} else {
+		Url::from_file_path(resolved)
+	};
+
+	let url = url.map_err(|_| Error::new("Path could not be converted to a file URL", ErrorKind::Type))?;
+	URL::parse(cx, url.into(), Opt(None)).ok_or_else(|| Error::new("Invalid URL", ErrorKind::Type))
+}
+
+#[js_fn]
+fn url_to_http_options<'cx>(cx: &'cx Context, url: &URL) -> Result<Object<'cx>> {
+	let href = url.get_href();
+	let url = Url::parse(&href).map_err(|error| Error::new(error.to_string(), ErrorKind::Type))?;
+
+	let hostname = url.host_str().unwrap_or_default();
+	let hostname = hostname.strip_prefix('[').and_then(|host| host.strip_suffix(']')).unwrap_or(hostname);
+	let search = url.query().filter(|query| !query.is_empty()).map(|query| format!("?{query}"));
+	let search = search.unwrap_or_default();
+	let hash = url.fragment().filter(|fragment| !fragment.is_empty()).map(|fragment| format!("#{fragment}"));
+
+	let options = Object::new(cx);
+	options.set_as(cx, "protocol", &format!("{}:", url.scheme()));
+	options.set_as(cx, "hostname", &String::from(hostname));
+	options.set_as(cx, "hash", &hash.unwrap_or_default());
+	options.set_as(cx, "search", &search);
+	options.set_as(cx, "pathname", &String::from(url.path()));
+	options.set_as(cx, "path", &format!("{}{search}", url.path()));
+	options.set_as(cx, "href", &href);
+
+	if let Some(port) = url.port() {
+		options.set_as(cx, "port", &port);
+	}
+
+	let password = url.password().unwrap_or_default();
+	if !url.username().is_empty() || !password.is_empty() {
+		let decode = |component| percent_decode_str(component).decode_utf8_lossy().into_owned();
+		options.set_as(cx, "auth", &format!("{}:{}", decode(url.username()), decode(password)));
+	}
+
+	Ok(options)
+}
+
 const FUNCTIONS: &[JSFunctionSpec] = &[
 	function_spec!(domain_to_ascii, c"domainToASCII", 0),
 	function_spec!(domain_to_unicode, c"domainToUnicode", 0),
+	function_spec!(file_url_to_path, c"fileURLToPath", 1),
+	function_spec!(path_to_file_url, c"pathToFileURL", 1),
+	function_spec!(url_to_http_options, c"urlToHttpOptions", 1),
 	JSFunctionSpec::ZERO,
 ];
 
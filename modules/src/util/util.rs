@@ -0,0 +1,148 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use ion::conversions::{FromValue, ToValue};
+use ion::format::{format_value, ColourConfig, Config as FormatConfig};
+use ion::{Array, Context, Function, Object, OwnedKey, Result, Value};
+use mozjs::jsapi::{ESClass, JSFunctionSpec, JSObject};
+use runtime::module::NativeModule;
+
+/// Maximum depth of nested objects which are serialised. Deeper objects are replaced with `"[Object]"`.
+const MAX_DEPTH: usize = 16;
+
+/// Properties of errors which are serialised before their own enumerable properties.
+const ERROR_PROPERTIES: [&str; 6] = ["name", "message", "stack", "fileName", "lineNumber", "columnNumber"];
+
+/// Converts a value into one which can be represented in JSON, or [None] if it should be omitted.
+///
+/// `ancestors` holds the objects currently being serialised, which are replaced with `"[Circular]"`.
+fn serialise<'cx>(cx: &'cx Context, value: &Value<'cx>, ancestors: &mut Vec<*mut JSObject>) -> Option<Value<'cx>> {
+	let handle = value.handle();
+	if handle.is_string() || handle.is_boolean() || handle.is_null() {
+		return Some(Value::from(cx.root(handle.get())));
+	} else if handle.is_number() {
+		let number = handle.to_number();
+		return Some(if number.is_finite() { Value::f64(cx, number) } else { Value::null(cx) });
+	} else if handle.is_bigint() {
+		return Some(String::from_value(cx, value, false, ()).ok()?.as_value(cx));
+	} else if !handle.is_object() {
+		return None;
+	}
+
+	let object = value.to_object(cx);
+	if is_callable(cx, &object) {
+		return None;
+	}
+	if ancestors.contains(&object.handle().get()) {
+		return Some(Value::string(cx, "[Circular]"));
+	}
+	if ancestors.len() >= MAX_DEPTH {
+		return Some(Value::string(cx, "[Object]"));
+	}
+
+	ancestors.push(object.handle().get());
+	let serialised = if Array::is_array(cx, &object) {
+		let array = Array::from(cx, object.into_local()).unwrap();
+		let elements: Vec<_> = array
+			.to_vec::<Value>(cx, false, ())
+			.unwrap_or_default()
+			.iter()
+			.map(|element| serialise(cx, element, ancestors).unwrap_or_else(|| Value::null(cx)))
+			.collect();
+		elements.as_value(cx)
+	} else if object.get_builtin_class(cx) == ESClass::Error {
+		serialise_error(cx, &object, ancestors).as_value(cx)
+	} else {
+		let serialised = Object::new(cx);
+		serialise_properties(cx, &object, &serialised, ancestors);
+		serialised.as_value(cx)
+	};
+	ancestors.pop();
+	Some(serialised)
+}
+
+fn is_callable(cx: &Context, object: &Object) -> bool {
+	Function::from_object(cx, object).is_some()
+}
+
+fn serialise_error<'cx>(cx: &'cx Context, error: &Object<'cx>, ancestors: &mut Vec<*mut JSObject>) -> Object<'cx> {
+	let serialised = Object::new(cx);
+	for key in ERROR_PROPERTIES {
+		if let Ok(Some(value)) = error.get(cx, key) {
+			if let Some(value) = serialise(cx, &value, ancestors) {
+				serialised.set(cx, key, &value);
+			}
+		}
+	}
+
+	for key in ["cause", "errors"] {
+		if error.has_own(cx, key) {
+			let value = error.get(cx, key).ok().flatten();
+			if let Some(value) = value.and_then(|value| serialise(cx, &value, ancestors)) {
+				serialised.set(cx, key, &value);
+			}
+		}
+	}
+
+	serialise_properties(cx, error, &serialised, ancestors);
+	serialised
+}
+
+/// Serialises the own enumerable string-keyed properties of `object` which are not already set on `serialised`.
+fn serialise_properties(cx: &Context, object: &Object, serialised: &Object, ancestors: &mut Vec<*mut JSObject>) {
+	for key in object.keys(cx, None) {
+		let Ok(OwnedKey::String(name)) = key.to_owned_key(cx) else {
+			continue;
+		};
+		if serialised.has_own(cx, name.as_str()) {
+			continue;
+		}
+		if let Ok(Some(value)) = object.get(cx, name.as_str()) {
+			if let Some(value) = serialise(cx, &value, ancestors) {
+				serialised.set(cx, name.as_str(), &value);
+			}
+		}
+	}
+}
+
+#[js_fn]
+fn serialize_error<'cx>(cx: &'cx Context, value: Value<'cx>) -> Result<Object<'cx>> {
+	let mut ancestors = Vec::new();
+	if value.handle().is_object() {
+		let object = value.to_object(cx);
+		if object.get_builtin_class(cx) == ESClass::Error {
+			ancestors.push(object.handle().get());
+			return Ok(serialise_error(cx, &object, &mut ancestors));
+		}
+	}
+
+	let config = FormatConfig::default().colours(ColourConfig::white());
+	let serialised = Object::new(cx);
+	serialised.set_as(cx, "name", "NonError");
+	serialised.set_as(cx, "message", &format_value(cx, config, &value).to_js_string()?);
+	if let Some(value) = serialise(cx, &value, &mut ancestors) {
+		serialised.set(cx, "value", &value);
+	}
+	Ok(serialised)
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[
+	function_spec!(serialize_error, c"serializeError", 1),
+	JSFunctionSpec::ZERO,
+];
+
+pub struct UtilM;
+
+impl<'cx> NativeModule<'cx> for UtilM {
+	const NAME: &'static str = "util";
+	const VARIABLE_NAME: &'static str = "util";
+	const SOURCE: &'static str = include_str!("util.js");
+
+	fn module(&self, cx: &'cx Context) -> Option<Object<'cx>> {
+		let util = Object::new(cx);
+		unsafe { util.define_methods(cx, FUNCTIONS).then_some(util) }
+	}
+}
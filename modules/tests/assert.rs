@@ -19,6 +19,9 @@ const OK: (&str, &str) = ("ok", include_str!("scripts/assert/ok.js"));
 const EQUALS: (&str, &str) = ("equals", include_str!("scripts/assert/equals.js"));
 const THROWS: (&str, &str) = ("throws", include_str!("scripts/assert/throws.js"));
 const FAIL: (&str, &str) = ("fail", include_str!("scripts/assert/fail.js"));
+const MATCH: (&str, &str) = ("match", include_str!("scripts/assert/match.js"));
+const DOES_NOT_MATCH: (&str, &str) = ("doesNotMatch", include_str!("scripts/assert/doesNotMatch.js"));
+const SNAPSHOT: (&str, &str) = ("snapshot", include_str!("scripts/assert/snapshot.js"));
 
 const EXCEPTION_STRING: &str = "_spidermonkey_exception_";
 
@@ -40,6 +43,9 @@ async fn assert() {
 	eval_module(&rt, rt.cx(), EQUALS).await;
 	eval_module(&rt, rt.cx(), THROWS).await;
 	eval_module(&rt, rt.cx(), FAIL).await;
+	eval_module(&rt, rt.cx(), MATCH).await;
+	eval_module(&rt, rt.cx(), DOES_NOT_MATCH).await;
+	eval_module(&rt, rt.cx(), SNAPSHOT).await;
 }
 
 pub async fn eval_module(rt: &Runtime<'_>, cx: &Context, test: (&str, &str)) {
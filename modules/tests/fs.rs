@@ -0,0 +1,57 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use ion::module::Module;
+use ion::Context;
+use modules::Modules;
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::{JSEngine, Runtime as RustRuntime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::module::Loader;
+use runtime::{Runtime, RuntimeBuilder};
+
+const CHECKSUM: (&str, &str) = ("checksum", include_str!("scripts/fs/checksum.js"));
+const ABORT: (&str, &str) = ("abort", include_str!("scripts/fs/abort.js"));
+const WALK: (&str, &str) = ("walk", include_str!("scripts/fs/walk.js"));
+
+#[tokio::test]
+async fn fs() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = RustRuntime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::new()
+		.modules(Loader::default())
+		.standard_modules(Modules)
+		.microtask_queue()
+		.macrotask_queue()
+		.build(cx);
+
+	eval_module(&rt, rt.cx(), CHECKSUM).await;
+	eval_module(&rt, rt.cx(), ABORT).await;
+	eval_module(&rt, rt.cx(), WALK).await;
+}
+
+pub async fn eval_module(rt: &Runtime<'_>, cx: &Context, test: (&str, &str)) {
+	let (test, script) = test;
+	let filename = format!("{}.js", test);
+	let path = format!("./tests/scripts/fs/{}.js", test);
+
+	let result = Module::compile_and_evaluate(cx, &filename, Some(Path::new(&path)), script);
+	assert!(result.is_ok(), "Exception was thrown in: {}", filename);
+
+	let (_, promise) = result.unwrap();
+	assert!(rt.run_event_loop().await.is_ok());
+	assert!(
+		promise.is_some_and(|promise| promise.state() == PromiseState::Fulfilled),
+		"Module evaluation failed: {}",
+		filename
+	);
+}
@@ -0,0 +1,45 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use ion::module::Module;
+use ion::Context;
+use modules::Modules;
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::{JSEngine, Runtime as RustRuntime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::module::Loader;
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "node.js";
+const SCRIPT: &str = include_str!("scripts/node.js");
+
+#[tokio::test]
+async fn node_compat() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = RustRuntime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::new()
+		.modules(Loader::default())
+		.standard_modules(Modules)
+		.microtask_queue()
+		.build(cx);
+
+	let path = Path::new("./tests/scripts/node.js");
+	let result = Module::compile_and_evaluate(rt.cx(), FILE_NAME, Some(path), SCRIPT);
+	assert!(result.is_ok(), "Exception was thrown in: {FILE_NAME}");
+
+	let (_, promise) = result.unwrap();
+	assert!(rt.run_event_loop().await.is_ok());
+	assert!(
+		promise.is_some_and(|promise| promise.state() == PromiseState::Fulfilled),
+		"Module evaluation failed"
+	);
+}
@@ -0,0 +1,44 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals} from "assert";
+import {
+	byteLength,
+	compare,
+	concat,
+	decodeBase64,
+	decodeBase64Url,
+	decodeHex,
+	encodeBase64,
+	encodeBase64Url,
+	encodeHex,
+	indexOf,
+} from "buffer";
+
+const bytes = new Uint8Array([0xfb, 0xff, 0x00, 0x7f]);
+
+equals(encodeHex(bytes), "fbff007f", "buffer.encodeHex");
+equals(compare(decodeHex("FBFF007F"), bytes), 0, "buffer.decodeHex");
+equals(encodeBase64(bytes), "+/8Afw==", "buffer.encodeBase64");
+equals(compare(decodeBase64("+/8Afw"), bytes), 0, "buffer.decodeBase64");
+equals(encodeBase64Url(bytes.buffer), "-_8Afw", "buffer.encodeBase64Url");
+equals(compare(decodeBase64Url("-_8Afw=="), bytes), 0, "buffer.decodeBase64Url");
+
+const joined = concat([bytes.subarray(0, 2), new Uint8Array([1, 2]), bytes.subarray(2)]);
+equals(encodeHex(joined), "fbff0102007f", "buffer.concat");
+equals(compare(bytes, joined), 1, "buffer.compare");
+equals(compare(joined, bytes), -1, "buffer.compare");
+
+equals(indexOf(joined, new Uint8Array([1, 2])), 2, "buffer.indexOf");
+equals(indexOf(joined, new Uint8Array([1, 2]), 3), -1, "buffer.indexOf");
+equals(indexOf(joined, new Uint8Array([1, 2]), -4), 2, "buffer.indexOf");
+equals(indexOf(joined, new Uint8Array([])), 0, "buffer.indexOf");
+equals(indexOf(joined, new Uint8Array([]), 3), 3, "buffer.indexOf");
+equals(indexOf(joined, new Uint8Array([]), 10), 6, "buffer.indexOf");
+equals(indexOf(joined, new Uint8Array([]), -2), 4, "buffer.indexOf");
+
+equals(byteLength("spiderfire"), 10, "buffer.byteLength");
+equals(byteLength("🕷️🔥"), 11, "buffer.byteLength");
@@ -0,0 +1,46 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals} from "assert";
+import {copy, create, removeSync, walk} from "fs";
+import {existsSync} from "node:fs";
+import {readFile, writeFile} from "node:fs/promises";
+
+const SIZE = 32 * 1024 * 1024;
+const SOURCE = "./tests/scripts/fs/abort-source.tmp";
+const DESTINATION = "./tests/scripts/fs/abort-destination.tmp";
+
+function rejection(promise) {
+	return promise.then(() => null, error => error);
+}
+
+function sleep(delay) {
+	return new Promise(resolve => setTimeout(resolve, delay));
+}
+
+const aborted = AbortSignal.abort("aborted");
+
+equals(await rejection(readFile("./tests/scripts/fs/abort.js", {signal: aborted})), "aborted");
+equals(await rejection(writeFile(DESTINATION, "data", {signal: aborted})), "aborted");
+equals(await rejection(create(DESTINATION, {signal: aborted})), "aborted");
+equals(existsSync(DESTINATION), false);
+equals(await rejection(walk("./tests/scripts", {signal: aborted}).next()), "aborted");
+
+const handle = await create(SOURCE);
+await handle.writeAll(new Uint8Array(SIZE), {signal: new AbortController().signal});
+equals((await readFile(SOURCE, {signal: new AbortController().signal})).length, SIZE);
+
+// The partially copied destination is removed once the copy is aborted.
+const controller = new AbortController();
+const copied = copy(SOURCE, DESTINATION, {signal: controller.signal});
+setTimeout(() => controller.abort(), 0);
+equals((await rejection(copied))?.name, "AbortError");
+for (let i = 0; i < 100 && existsSync(DESTINATION); i++) {
+	await sleep(10);
+}
+equals(existsSync(DESTINATION), false);
+
+removeSync(SOURCE);
@@ -0,0 +1,54 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, throws} from "assert";
+import {checksum, checksumStream, checksumSync, Hasher, readDir} from "fs";
+
+const ABC = new Uint8Array([0x61, 0x62, 0x63]);
+const ABC_SHA256 = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+const ABC_SHA3_256 = "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532";
+
+const hasher = new Hasher();
+hasher.updateSync(ABC.subarray(0, 1));
+hasher.updateSync(ABC.subarray(1));
+equals(hasher.digestSync(), ABC_SHA256);
+
+const asyncHasher = new Hasher("sha3-256");
+const update = asyncHasher.update(ABC);
+throws(() => asyncHasher.updateSync(ABC));
+await update;
+equals(await asyncHasher.digest(), ABC_SHA3_256);
+
+const queuedHasher = new Hasher();
+queuedHasher.update(ABC.subarray(0, 1));
+queuedHasher.update(ABC.subarray(1, 2));
+queuedHasher.update(ABC.subarray(2));
+equals(await queuedHasher.digest(), ABC_SHA256);
+queuedHasher.updateSync(ABC);
+equals(queuedHasher.digestSync(), ABC_SHA256);
+
+throws(() => new Hasher("md5"));
+
+const stream = new ReadableStream({
+	start(controller) {
+		controller.enqueue(ABC.subarray(0, 2));
+		controller.enqueue(ABC.subarray(2));
+		controller.close();
+	},
+});
+equals(await checksumStream(stream), ABC_SHA256);
+
+const path = "./tests/scripts/fs/checksum.js";
+equals(await checksum(path, "sha512"), checksumSync(path, "sha512"));
+
+const controller = new AbortController();
+const aborted = checksum(path, undefined, {signal: controller.signal});
+controller.abort();
+equals((await aborted.then(() => null, error => error))?.name, "AbortError");
+
+const reason = await readDir("./tests/scripts", {signal: AbortSignal.abort("aborted")}).catch(error => error);
+equals(reason, "aborted");
+equals(await checksum(path, "sha512", {signal: new AbortController().signal}), checksumSync(path, "sha512"));
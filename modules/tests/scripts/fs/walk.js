@@ -0,0 +1,56 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok} from "assert";
+import {createDirSync, createSync, removeSync, walk} from "fs";
+
+async function collect(path, options) {
+	const entries = new Map();
+	for await (const entry of walk(path, options)) {
+		entries.set(entry.name, entry);
+	}
+	return entries;
+}
+
+const all = await collect("./tests/scripts");
+equals(all.get("buffer.js").depth, 1);
+ok(all.get("buffer.js").metadata.isFile);
+equals(all.get("walk.js").depth, 2);
+equals(all.get("walk.js").path, "./tests/scripts/fs/walk.js");
+ok(all.get("assert").metadata.isDirectory);
+equals(all.get("equals.js").depth, 2);
+equals(all.get("equals.js").path, "./tests/scripts/assert/equals.js");
+
+const shallow = await collect("./tests/scripts", {maxDepth: 1});
+ok(shallow.has("assert"));
+ok(!shallow.has("equals.js"));
+
+const files = await collect("./tests/scripts", {includeDirs: false});
+ok(!files.has("assert"));
+ok(files.has("equals.js"));
+
+const skipped = await collect("./tests/scripts", {skip: ["assert"]});
+ok(!skipped.has("assert"));
+ok(!skipped.has("equals.js"));
+ok(skipped.has("buffer.js"));
+
+let failed = false;
+try {
+	await walk("./tests/scripts/missing").next();
+} catch {
+	failed = true;
+}
+ok(failed);
+
+// Directories with more entries than a batch are walked to the end.
+const LARGE = "./tests/scripts/fs/walk.tmp";
+createDirSync(LARGE);
+for (let i = 0; i < 300; i++) {
+	createSync(`${LARGE}/${i}.txt`);
+}
+const large = await collect(LARGE);
+removeSync(LARGE, true);
+equals(large.size, 300);
@@ -0,0 +1,55 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok} from "assert";
+import {mockFetch} from "test";
+
+const mock = mockFetch({
+	"https://api.example.invalid/users/*": {
+		body: JSON.stringify({name: "Ferris"}),
+		headers: {"Content-Type": "application/json"},
+	},
+	"https://api.example.invalid/slow": {body: "slow", delay: 20},
+	"https://api.example.invalid/down": {error: true},
+	"https://api.example.invalid/canned": new Response("canned", {status: 201}),
+});
+
+const user = await fetch("https://api.example.invalid/users/1", {method: "POST", headers: {"X-Test": "1"}});
+equals(user.status, 200, "test.mockFetch");
+equals(user.headers.get("Content-Type"), "application/json", "test.mockFetch");
+equals((await user.json()).name, "Ferris", "test.mockFetch");
+
+const start = Date.now();
+const slow = await fetch("https://api.example.invalid/slow");
+equals(await slow.text(), "slow", "test.mockFetch");
+ok(Date.now() - start >= 20, "test.mockFetch");
+
+let failed = false;
+try {
+	await fetch("https://api.example.invalid/down");
+} catch (error) {
+	failed = error instanceof TypeError;
+}
+ok(failed, "test.mockFetch");
+
+for (let i = 0; i < 2; i++) {
+	const canned = await fetch("https://api.example.invalid/canned");
+	equals(canned.status, 201, "test.mockFetch");
+	equals(await canned.text(), "canned", "test.mockFetch");
+}
+
+equals(mock.calls.length, 5, "test.mockFetch");
+equals(mock.calls[0].url, "https://api.example.invalid/users/1", "test.mockFetch");
+equals(mock.calls[0].method, "POST", "test.mockFetch");
+equals(mock.calls[0].headers["x-test"], "1", "test.mockFetch");
+
+mock.restore();
+const fallback = mockFetch({"*": {body: "fallback"}});
+const restored = await fetch("https://api.example.invalid/users/1");
+equals(await restored.text(), "fallback", "test.mockFetch");
+equals(mock.calls.length, 5, "test.mockFetch");
+equals(fallback.calls.length, 1, "test.mockFetch");
+fallback.restore();
@@ -0,0 +1,48 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok} from "assert";
+import nodeAssert, {strictEqual, throws} from "node:assert";
+import {existsSync, promises, statSync} from "node:fs";
+import {readdir} from "node:fs/promises";
+import path, {basename, dirname, extname} from "node:path";
+import {URL} from "node:url";
+import * as url from "url";
+
+equals(dirname("/spiderfire/main.js"), "/spiderfire");
+equals(dirname("main.js"), ".");
+equals(basename("/spiderfire/main.js"), "main.js");
+equals(basename("/spiderfire/main.js", ".js"), "main");
+equals(extname("/spiderfire/main.js"), ".js");
+equals(extname("/spiderfire/Makefile"), "");
+equals(path.basename("main.js"), "main.js");
+
+nodeAssert(true);
+strictEqual(1, 1);
+throws(() => {
+	throw new Error();
+});
+
+let failed = false;
+try {
+	throws(() => {});
+} catch {
+	failed = true;
+}
+ok(failed, "node:assert throws did not fail for a function that does not throw");
+
+equals(URL, url.URL);
+ok(existsSync("./tests/scripts/node.js"));
+ok(!existsSync("./tests/scripts/missing.js"));
+equals(promises.readdir, readdir);
+ok((await readdir("./tests/scripts")).includes("node.js"));
+
+const stats = statSync("./tests/scripts/node.js");
+equals(typeof stats.size, "number");
+const bigintStats = statSync("./tests/scripts/node.js", {bigint: true});
+equals(typeof bigintStats.size, "bigint");
+equals(typeof bigintStats.mtimeNs, "bigint");
+equals(bigintStats.size, BigInt(stats.size));
@@ -0,0 +1,42 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok, throws} from "assert";
+import {runAllTimers, tick, useFakeTimers} from "test";
+
+useFakeTimers();
+
+const calls = [];
+setTimeout(() => calls.push(`timeout:${Date.now()}`), 100);
+const interval = setInterval(() => calls.push(`interval:${Date.now()}`), 40);
+
+equals(tick(50), 1, "test.tick");
+equals(calls.join(","), "interval:40", "test.tick");
+equals(Date.now(), 50, "test.tick");
+
+equals(tick(50), 2, "test.tick");
+equals(calls.join(","), "interval:40,interval:80,timeout:100", "test.tick");
+clearInterval(interval);
+
+setTimeout(() => {
+	calls.push(`outer:${Date.now()}`);
+	setTimeout(() => calls.push(`inner:${Date.now()}`), 1000);
+}, 500);
+equals(runAllTimers(), 2, "test.runAllTimers");
+equals(calls.slice(3).join(","), "outer:600,inner:1600", "test.runAllTimers");
+
+let reentered = false;
+setTimeout(() => {
+	throws(() => tick(10), "test.tick");
+	throws(() => runAllTimers(), "test.runAllTimers");
+	reentered = true;
+}, 10);
+equals(tick(10), 1, "test.tick");
+ok(reentered, "test.tick");
+
+setTimeout(() => {
+	throw new Error("Pending fake timers should not run");
+}, 10);
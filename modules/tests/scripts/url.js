@@ -0,0 +1,31 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, fail} from "assert";
+import {fileURLToPath, pathToFileURL, urlToHttpOptions} from "url";
+
+const file = pathToFileURL("/tmp/a b/../c#d.js");
+equals(file.href, "file:///tmp/c%23d.js", "url.pathToFileURL");
+equals(fileURLToPath(file), "/tmp/c#d.js", "url.fileURLToPath");
+equals(fileURLToPath("file:///tmp/dir/"), "/tmp/dir/", "url.fileURLToPath");
+equals(pathToFileURL("/tmp/dir/").href, "file:///tmp/dir/", "url.pathToFileURL");
+
+for (const url of ["https://example.com/", "file:///tmp/a%2Fb"]) {
+	try {
+		fileURLToPath(url);
+		fail("url.fileURLToPath");
+	} catch (error) {
+		equals(error instanceof TypeError, true, "url.fileURLToPath");
+	}
+}
+
+const options = urlToHttpOptions(new URL("https://us%20er:pa%3Ass@[::1]:8080/path?query=1#hash"));
+equals(options.protocol, "https:", "url.urlToHttpOptions");
+equals(options.hostname, "::1", "url.urlToHttpOptions");
+equals(options.port, 8080, "url.urlToHttpOptions");
+equals(options.path, "/path?query=1", "url.urlToHttpOptions");
+equals(options.hash, "#hash", "url.urlToHttpOptions");
+equals(options.auth, "us er:pa:ss", "url.urlToHttpOptions");
@@ -0,0 +1,34 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok} from "assert";
+import {serializeError} from "util";
+
+const cause = new RangeError("Out of Range");
+const error = new TypeError("Invalid Type", {cause});
+error.code = "ERR_INVALID_TYPE";
+error.self = error;
+
+const serialized = serializeError(error);
+equals(serialized.name, "TypeError", "util.serializeError");
+equals(serialized.message, "Invalid Type", "util.serializeError");
+equals(typeof serialized.stack, "string", "util.serializeError");
+equals(serialized.code, "ERR_INVALID_TYPE", "util.serializeError");
+equals(serialized.self, "[Circular]", "util.serializeError");
+equals(serialized.cause.name, "RangeError", "util.serializeError");
+equals(serialized.cause.message, "Out of Range", "util.serializeError");
+
+const aggregate = serializeError(new AggregateError([new Error("First"), 2], "Multiple Errors"));
+equals(aggregate.errors.length, 2, "util.serializeError");
+equals(aggregate.errors[0].message, "First", "util.serializeError");
+equals(aggregate.errors[1], 2, "util.serializeError");
+
+const thrown = serializeError({reason: "Unknown", retry: () => {}});
+equals(thrown.name, "NonError", "util.serializeError");
+equals(thrown.value.reason, "Unknown", "util.serializeError");
+ok(!("retry" in thrown.value), "util.serializeError");
+
+ok(JSON.stringify(serialized).length > 0, "util.serializeError");
@@ -15,12 +15,23 @@ use base64::prelude::BASE64_URL_SAFE;
 use base64::Engine;
 use dirs::home_dir;
 use dunce::canonicalize;
+use serde::{Deserialize, Serialize};
 use sha3::{Digest, Sha3_512};
 use sourcemap::SourceMap;
 
 use crate::config::Config;
 use crate::typescript;
-use crate::typescript::compile_typescript;
+use crate::typescript::{compile_typescript, CompilerOptions};
+
+const METADATA_EXTENSION: &str = "meta.json";
+
+/// Metadata of a cache entry, which records the options its source was compiled with.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct EntryMetadata {
+	pub source: String,
+	pub fingerprint: String,
+	pub options: String,
+}
 
 pub struct Cache {
 	dir: PathBuf,
@@ -47,6 +58,27 @@ impl Cache {
 		Ok(())
 	}
 
+	/// Reads the metadata of the entries in the cache.
+	/// Entries which were cached without metadata are skipped.
+	pub fn entries(&self) -> io::Result<Vec<EntryMetadata>> {
+		let mut entries = Vec::new();
+		for folder in read_dir(&self.dir)? {
+			let folder = folder?.path();
+			if !folder.is_dir() {
+				continue;
+			}
+			for file in read_dir(folder)? {
+				let file = file?.path();
+				if file.to_str().is_some_and(|file| file.ends_with(METADATA_EXTENSION)) {
+					if let Ok(metadata) = serde_json::from_str(&read_to_string(file)?) {
+						entries.push(metadata);
+					}
+				}
+			}
+		}
+		Ok(entries)
+	}
+
 	pub fn find_folder<P: AsRef<Path>>(&self, path: P) -> Result<PathBuf, Error> {
 		let canonical = canonicalize(path)?;
 		let folder = canonical.parent().ok_or(Error::Other)?;
@@ -64,7 +96,7 @@ impl Cache {
 		let source_file = path.file_stem().and_then(OsStr::to_str).ok_or(Error::Other)?;
 		let extension = path.extension().and_then(OsStr::to_str).ok_or(Error::Other)?;
 
-		let source_hash = hash(source, None);
+		let source_hash = source_key(source, &fingerprint(&CompilerOptions::global().describe()));
 		let destination_file = folder.join(source_file).with_extension("js");
 		let map_file = folder.join(source_file).with_extension("js.map");
 
@@ -107,15 +139,19 @@ impl Cache {
 			let source_file = path.file_stem().and_then(OsStr::to_str).ok_or(Error::Other)?;
 			let extension = path.extension().and_then(OsStr::to_str).ok_or(Error::Other)?;
 
-			let source_hash = source_hash.map(String::from).unwrap_or_else(|| hash(source, None));
+			let compiler_options = CompilerOptions::global();
+			let options = compiler_options.describe();
+			let fingerprint = fingerprint(&options);
+			let source_hash = source_hash.map(String::from).unwrap_or_else(|| source_key(source, &fingerprint));
 			let destination_file = folder.join(source_file).with_extension("js");
 			let map_file = folder.join(source_file).with_extension("js.map");
 
 			let source_hash_file = folder.join(format!("{source_file}.{extension}.sha512"));
+			let metadata_file = folder.join(format!("{source_file}.{extension}.{METADATA_EXTENSION}"));
 			let destination_hash_file = destination_file.with_extension("js.sha512");
 			let map_hash_file = map_file.with_extension("map.sha512");
 
-			let (destination, sourcemap) = compile_typescript(source_name, source)?;
+			let (destination, sourcemap) = compile_typescript(source_name, source, compiler_options)?;
 			let mut sourcemap_str: Vec<u8> = Vec::new();
 			sourcemap.to_writer(&mut sourcemap_str).unwrap();
 			let sourcemap_str = from_utf8(&sourcemap_str)?;
@@ -130,6 +166,13 @@ impl Cache {
 			write(destination_hash_file, hash(&destination, None))?;
 			write(map_hash_file, hash(sourcemap_str, None))?;
 
+			let metadata = EntryMetadata {
+				source: path.display().to_string(),
+				fingerprint,
+				options,
+			};
+			write(metadata_file, serde_json::to_string_pretty(&metadata).unwrap())?;
+
 			Ok((destination, sourcemap))
 		} else {
 			Err(Error::Other)
@@ -175,7 +218,19 @@ impl Display for Error {
 	}
 }
 
-fn hash<T: AsRef<[u8]>>(bytes: T, len: Option<usize>) -> String {
+/// Fingerprint of the compiler options, which is part of the key of cache entries, so that changing the options or
+/// version of spiderfire does not reuse output compiled with different options.
+pub fn fingerprint(options: &str) -> String {
+	hash(options, Some(16))
+}
+
+/// Key of a cache entry, from its source and the fingerprint of the options it is compiled with.
+fn source_key(source: &str, fingerprint: &str) -> String {
+	let hash = Sha3_512::new().chain_update(fingerprint).chain_update(source).finalize();
+	BASE64_URL_SAFE.encode(hash)
+}
+
+pub(crate) fn hash<T: AsRef<[u8]>>(bytes: T, len: Option<usize>) -> String {
 	let hash = BASE64_URL_SAFE.encode(Sha3_512::new().chain_update(bytes).finalize());
 	len.map_or(hash.clone(), |len| String::from(&hash[0..len]))
 }
@@ -5,50 +5,188 @@
  */
 
 use std::cell::RefCell;
-use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+#[cfg(feature = "fs")]
+use std::fs::read;
 use std::path::{Path, PathBuf};
 
+use data_url::DataUrl;
 use ion::utils::normalise_path;
-use ion::{Error, ErrorReport, Exception};
+use ion::{Context, Error, ErrorReport, Exception, Stack};
+use mozjs::conversions::jsstr_to_string;
+use mozjs::jsapi::{Handle, JSContext, JSSecurityCallbacks, JSString, RuntimeCode};
 use sourcemap::SourceMap;
+#[cfg(feature = "fs")]
+use url::Url;
 
-thread_local!(static SOURCEMAP_CACHE: RefCell<HashMap<PathBuf, SourceMap>> = RefCell::new(HashMap::new()));
+thread_local!(static SOURCEMAP_STORE: RefCell<SourceMapStore> = RefCell::default());
 
-pub fn find_sourcemap<P: AsRef<Path>>(path: P) -> Option<SourceMap> {
-	SOURCEMAP_CACHE.with_borrow_mut(|cache| {
-		let path = path.as_ref().to_path_buf();
-		match cache.entry(path) {
-			Entry::Occupied(o) => Some(o.get().clone()),
-			Entry::Vacant(_) => None,
-		}
-	})
+/// Prefixes of the comments which reference the source map of a script.
+const SOURCEMAP_COMMENTS: [&str; 2] = ["//# sourceMappingURL=", "//@ sourceMappingURL="];
+
+/// Prefixes of the source which SpiderMonkey generates for functions created with the `Function` constructors.
+const FUNCTION_PREFIXES: [&str; 4] = [
+	"function anonymous(",
+	"function* anonymous(",
+	"async function anonymous(",
+	"async function* anonymous(",
+];
+
+pub(crate) static SECURITY_CALLBACKS: JSSecurityCallbacks = JSSecurityCallbacks {
+	contentSecurityPolicyAllows: Some(register_runtime_code),
+	codeForEvalGets: None,
+	subsumes: None,
+};
+
+/// Source maps of the scripts and modules evaluated on the current thread, by their normalised path.
+///
+/// Maps are saved when TypeScript is compiled through the cache, or registered from the `sourceMappingURL` comment of
+/// JavaScript, so that locations in errors and stacks can be transformed back to the original sources.
+#[derive(Debug, Default)]
+pub struct SourceMapStore {
+	maps: HashMap<PathBuf, SourceMap>,
 }
 
-pub fn save_sourcemap<P: AsRef<Path>>(path: P, sourcemap: SourceMap) -> bool {
-	SOURCEMAP_CACHE.with_borrow_mut(|cache| {
-		let path = normalise_path(path);
-		match cache.entry(path) {
-			Entry::Vacant(v) => {
-				v.insert(sourcemap);
+impl SourceMapStore {
+	/// Calls the closure with the store of the current thread.
+	pub fn with<F, T>(f: F) -> T
+	where
+		F: FnOnce(&mut SourceMapStore) -> T,
+	{
+		SOURCEMAP_STORE.with_borrow_mut(f)
+	}
+
+	pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<&SourceMap> {
+		self.maps.get(&normalise_path(path))
+	}
+
+	/// Saves the source map of a script, replacing any previous map of its path.
+	pub fn insert<P: AsRef<Path>>(&mut self, path: P, sourcemap: SourceMap) {
+		self.maps.insert(normalise_path(path), sourcemap);
+	}
+
+	/// Registers the source map referenced by the `sourceMappingURL` comment at the end of a script, which is either
+	/// read from `path`, or evaluated from a string with `path` as its filename.
+	///
+	/// Inline `data:` URLs are decoded, while other URLs are resolved against the directory of the script, if it exists
+	/// on the file system and the `fs` feature is enabled. As the URL is controlled by the script, source maps are only
+	/// read from within that directory. If the script has no readable source map, any previous map
+	/// of its path is removed, so that code evaluated repeatedly under the same filename is not transformed with a
+	/// stale map.
+	///
+	/// Returns whether a source map was registered.
+	pub fn register_source<P: AsRef<Path>>(&mut self, path: P, source: &str) -> bool {
+		let path = path.as_ref();
+		match find_reference(source).and_then(|url| load(path, url)) {
+			Some(sourcemap) => {
+				self.insert(path, sourcemap);
 				true
 			}
-			Entry::Occupied(_) => false,
+			None => {
+				self.maps.remove(&normalise_path(path));
+				false
+			}
 		}
-	})
-}
+	}
 
-pub fn transform_error_report_with_sourcemaps(report: &mut ErrorReport) {
-	if let Exception::Error(Error { location: Some(location), .. }) = &mut report.exception {
-		if let Some(sourcemap) = find_sourcemap(&location.file) {
-			report.exception.transform_with_sourcemap(&sourcemap);
+	/// Transforms the locations of the records of the [Stack] whose scripts have a source map.
+	pub fn transform_stack(&self, stack: &mut Stack) {
+		for record in &mut stack.records {
+			if let Some(sourcemap) = self.get(&record.location.file) {
+				record.transform_with_sourcemap(sourcemap);
+			}
 		}
 	}
-	if let Some(stack) = &mut report.stack {
-		for record in &mut stack.records {
-			if let Some(sourcemap) = find_sourcemap(&record.location.file) {
-				record.transform_with_sourcemap(&sourcemap);
+
+	/// Transforms the location of the exception and the stack of the [ErrorReport], where their scripts have a source
+	/// map.
+	pub fn transform_error_report(&self, report: &mut ErrorReport) {
+		if let Exception::Error(Error { location: Some(location), .. }) = &report.exception {
+			if let Some(sourcemap) = self.get(&location.file) {
+				report.exception.transform_with_sourcemap(sourcemap);
 			}
 		}
+		if let Some(stack) = &mut report.stack {
+			self.transform_stack(stack);
+		}
+	}
+}
+
+/// Registers the source map of code evaluated with `eval` or created with the `Function` constructors, under the
+/// filename which SpiderMonkey gives to it, such as `main.js line 3 > eval`.
+///
+/// This is called by SpiderMonkey before the code is compiled, and always allows it to be compiled.
+unsafe extern "C" fn register_runtime_code(cx: *mut JSContext, kind: RuntimeCode, code: Handle<*mut JSString>) -> bool {
+	let cx = unsafe { &Context::new_unchecked(cx) };
+	if kind != RuntimeCode::JS {
+		return true;
+	}
+	let Some(caller) = Stack::from_capture(cx).and_then(|stack| stack.records.into_iter().next()) else {
+		return true;
+	};
+
+	let code = unsafe { jsstr_to_string(cx.as_ptr(), code.get()) };
+	let function = FUNCTION_PREFIXES.iter().any(|prefix| code.starts_with(prefix));
+	let (introduction, code) = match code.strip_suffix("\n}") {
+		Some(body) if function => ("Function", body),
+		_ => ("eval", code.as_str()),
+	};
+
+	let location = caller.location;
+	let path = format!("{} line {} > {introduction}", location.file, location.lineno);
+	SourceMapStore::with(|store| store.register_source(path, code));
+	true
+}
+
+/// Finds the URL in the `sourceMappingURL` comment among the comments at the end of the source.
+fn find_reference(source: &str) -> Option<&str> {
+	for line in source.lines().rev() {
+		let line = line.trim();
+		if line.is_empty() {
+			continue;
+		}
+		if !line.starts_with("//") {
+			return None;
+		}
+		if let Some(url) = SOURCEMAP_COMMENTS.iter().find_map(|prefix| line.strip_prefix(prefix)) {
+			return Some(url.trim_start());
+		}
 	}
+	None
+}
+
+fn load(path: &Path, url: &str) -> Option<SourceMap> {
+	let bytes = if url.starts_with("data:") {
+		let (bytes, _) = DataUrl::process(url).ok()?.decode_to_vec().ok()?;
+		bytes
+	} else {
+		read_file(path, url)?
+	};
+	SourceMap::from_slice(&bytes).ok()
+}
+
+#[cfg(feature = "fs")]
+fn read_file(path: &Path, url: &str) -> Option<Vec<u8>> {
+	if !path.is_file() {
+		return None;
+	}
+	let directory = path.canonicalize().ok()?.parent()?.to_path_buf();
+	let file = match Url::parse(url) {
+		Ok(url) if url.scheme() == "file" => url.to_file_path().ok()?,
+		Ok(_) => return None,
+		Err(_) => directory.join(url),
+	};
+
+	let file = file.canonicalize().ok()?;
+	if file.starts_with(&directory) {
+		read(file).ok()
+	} else {
+		None
+	}
+}
+
+/// Source maps are only read from the file system with the `fs` feature.
+#[cfg(not(feature = "fs"))]
+fn read_file(_: &Path, _: &str) -> Option<Vec<u8>> {
+	None
 }
@@ -4,14 +4,19 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+#[cfg(feature = "fs")]
 use std::path::Path;
 
+#[cfg(feature = "fs")]
 pub use cache::*;
+#[cfg(feature = "fs")]
 use sourcemap::SourceMap;
 
+#[cfg(feature = "fs")]
 mod cache;
 pub mod map;
 
+#[cfg(feature = "fs")]
 pub fn locate_in_cache<P: AsRef<Path>>(path: P, script: &str) -> Option<(String, SourceMap)> {
 	let result = Cache::new().map(|cache| {
 		let path = path.as_ref();
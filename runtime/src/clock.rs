@@ -0,0 +1,92 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::Cell;
+use std::rc::Rc;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Source of the time of a runtime, which timers, `Date` and `performance.now` are based on.
+///
+/// The clock follows the system clock, unless the runtime is in
+/// [deterministic mode](crate::config::Config::deterministic), where it is virtual. Virtual time starts at the Unix
+/// epoch and only passes when it is advanced, so time-dependent scripts behave the same on every run.
+#[derive(Clone, Debug)]
+pub struct Clock {
+	origin: Instant,
+	elapsed: Option<Rc<Cell<Duration>>>,
+}
+
+impl Clock {
+	/// Creates a clock which follows the system clock.
+	pub fn system() -> Clock {
+		Clock { origin: Instant::now(), elapsed: None }
+	}
+
+	/// Creates a virtual clock, which starts at the Unix epoch.
+	pub fn virtual_time() -> Clock {
+		Clock {
+			origin: epoch(),
+			elapsed: Some(Rc::default()),
+		}
+	}
+
+	pub fn is_virtual(&self) -> bool {
+		self.elapsed.is_some()
+	}
+
+	/// Returns the current instant, which timer deadlines are compared against.
+	pub fn now(&self) -> Instant {
+		self.origin + self.elapsed()
+	}
+
+	/// Returns the time since the clock was created, as returned by `performance.now`.
+	pub fn elapsed(&self) -> Duration {
+		match &self.elapsed {
+			Some(elapsed) => elapsed.get(),
+			None => self.origin.elapsed(),
+		}
+	}
+
+	/// Returns the time since the Unix epoch, as returned by `Date.now`.
+	pub fn since_epoch(&self) -> Duration {
+		match &self.elapsed {
+			Some(elapsed) => elapsed.get(),
+			None => SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default(),
+		}
+	}
+
+	/// Advances virtual time by `duration`.
+	/// Returns false if the clock follows the system clock, which cannot be advanced.
+	pub fn advance(&self, duration: Duration) -> bool {
+		match &self.elapsed {
+			Some(elapsed) => {
+				elapsed.set(elapsed.get().saturating_add(duration));
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Advances virtual time to `instant`, if it is later than the current time.
+	pub fn advance_to(&self, instant: Instant) -> bool {
+		self.advance(instant.saturating_duration_since(self.now()))
+	}
+}
+
+impl Default for Clock {
+	fn default() -> Clock {
+		Clock::system()
+	}
+}
+
+/// Returns the first instant observed by the runtimes of the process, which timer deadlines are rounded from.
+///
+/// Virtual clocks start at this instant, so that their deadlines are rounded the same way on every run.
+pub(crate) fn epoch() -> Instant {
+	static EPOCH: OnceLock<Instant> = OnceLock::new();
+	*EPOCH.get_or_init(Instant::now)
+}
@@ -28,13 +28,106 @@ impl LogLevel {
 	pub fn is_stderr(&self) -> bool {
 		!self.is_stdout()
 	}
+
+	pub fn tag(&self) -> &'static str {
+		match self {
+			LogLevel::None => "NONE",
+			LogLevel::Info => "INFO",
+			LogLevel::Warn => "WARN",
+			LogLevel::Error => "ERROR",
+			LogLevel::Debug => "DEBUG",
+		}
+	}
+}
+
+/// Selects the groups of globals and standard modules which runtimes define, so that runtimes for untrusted code can
+/// be created without access to the network, file system or timers.
+///
+/// Globals without side effects, such as `URL` and `TextEncoder`, are always defined.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Globals {
+	pub console: bool,
+	pub fetch: bool,
+	pub fs: bool,
+	pub performance: bool,
+	pub timers: bool,
+}
+
+impl Globals {
+	/// Returns the set of globals with every group disabled.
+	pub fn none() -> Globals {
+		Globals {
+			console: false,
+			fetch: false,
+			fs: false,
+			performance: false,
+			timers: false,
+		}
+	}
+
+	/// Returns the set of globals for runtimes without access to the network or file system, which only defines
+	/// `console`, `performance` and the timers.
+	pub fn minimal() -> Globals {
+		Globals {
+			fetch: false,
+			fs: false,
+			..Globals::default()
+		}
+	}
+
+	/// Defines `console`.
+	pub fn console(self, console: bool) -> Globals {
+		Globals { console, ..self }
+	}
+
+	/// Defines `fetch`, `Headers`, `Request` and `Response`, when built with the `fetch` feature.
+	pub fn fetch(self, fetch: bool) -> Globals {
+		Globals { fetch, ..self }
+	}
+
+	/// Registers the `fs` and `fs/sync` standard modules, and their `node:` equivalents.
+	pub fn fs(self, fs: bool) -> Globals {
+		Globals { fs, ..self }
+	}
+
+	/// Defines `performance`.
+	pub fn performance(self, performance: bool) -> Globals {
+		Globals { performance, ..self }
+	}
+
+	/// Defines `setTimeout` and `setInterval`, when the runtime has a macrotask queue.
+	pub fn timers(self, timers: bool) -> Globals {
+		Globals { timers, ..self }
+	}
 }
 
-#[derive(Clone, Copy, Debug)]
+impl Default for Globals {
+	fn default() -> Globals {
+		Globals {
+			console: true,
+			fetch: true,
+			fs: true,
+			performance: true,
+			timers: true,
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct Config {
 	pub log_level: LogLevel,
 	pub script: bool,
 	pub typescript: bool,
+	pub log_timestamps: bool,
+	pub log_level_tags: bool,
+	pub log_ids: bool,
+	pub gc_max_bytes: Option<u32>,
+	pub blob_spill_threshold: Option<u64>,
+	pub update_snapshots: bool,
+	pub otlp: bool,
+	pub globals: Globals,
+	pub deterministic: bool,
+	pub random_seed: u64,
 }
 
 impl Config {
@@ -50,6 +143,63 @@ impl Config {
 		Config { typescript, ..self }
 	}
 
+	/// Prefixes console output with an ISO 8601 timestamp.
+	pub fn log_timestamps(self, log_timestamps: bool) -> Config {
+		Config { log_timestamps, ..self }
+	}
+
+	/// Prefixes console output with the level of the message, such as `[WARN]`.
+	pub fn log_level_tags(self, log_level_tags: bool) -> Config {
+		Config { log_level_tags, ..self }
+	}
+
+	/// Prefixes console output with the process ID and the ID of the runtime it originates from.
+	pub fn log_ids(self, log_ids: bool) -> Config {
+		Config { log_ids, ..self }
+	}
+
+	/// Sets the maximum size of the garbage-collected heap, in bytes.
+	pub fn gc_max_bytes(self, gc_max_bytes: Option<u32>) -> Config {
+		Config { gc_max_bytes, ..self }
+	}
+
+	/// Stores blobs larger than the threshold, in bytes, in temporary files instead of in memory.
+	pub fn blob_spill_threshold(self, blob_spill_threshold: Option<u64>) -> Config {
+		Config { blob_spill_threshold, ..self }
+	}
+
+	/// Overwrites stored snapshots with the values passed to `assert.snapshot`, instead of comparing them.
+	pub fn update_snapshots(self, update_snapshots: bool) -> Config {
+		Config { update_snapshots, ..self }
+	}
+
+	/// Exports tracing spans to an OpenTelemetry collector over OTLP, when built with the `otlp` feature.
+	/// The collector endpoint and service name are read from the standard `OTEL_*` environment variables.
+	pub fn otlp(self, otlp: bool) -> Config {
+		Config { otlp, ..self }
+	}
+
+	/// Sets the globals defined by runtimes, unless overridden with
+	/// [RuntimeBuilder::globals](crate::RuntimeBuilder::globals).
+	pub fn globals(self, globals: Globals) -> Config {
+		Config { globals, ..self }
+	}
+
+	/// Runs runtimes on a virtual [Clock](crate::clock::Clock), and makes `Math.random` return a sequence seeded by
+	/// [random_seed](Config::random_seed), so that scripts behave the same on every run.
+	///
+	/// `Date`, `performance.now` and timers then start at the Unix epoch, and time only passes when it is advanced with
+	/// [Runtime::advance_time](crate::Runtime::advance_time), or once the event loop has nothing to run but timers.
+	/// The local time zone is UTC, for every runtime of the process.
+	pub fn deterministic(self, deterministic: bool) -> Config {
+		Config { deterministic, ..self }
+	}
+
+	/// Sets the seed of `Math.random` in deterministic mode.
+	pub fn random_seed(self, random_seed: u64) -> Config {
+		Config { random_seed, ..self }
+	}
+
 	pub fn global() -> &'static Config {
 		CONFIG.get().expect("Configuration not initialised")
 	}
@@ -61,6 +211,16 @@ impl Default for Config {
 			log_level: LogLevel::Error,
 			script: false,
 			typescript: true,
+			log_timestamps: false,
+			log_level_tags: false,
+			log_ids: false,
+			gc_max_bytes: None,
+			blob_spill_threshold: Some(64 * 1024 * 1024),
+			update_snapshots: false,
+			otlp: false,
+			globals: Globals::default(),
+			deterministic: false,
+			random_seed: 0,
 		}
 	}
 }
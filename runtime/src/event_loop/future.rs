@@ -4,40 +4,76 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use std::task;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::task::Poll;
+use std::{mem, task};
 
+use futures::future::LocalBoxFuture;
 use futures::stream::FuturesUnordered;
-use futures::StreamExt;
+use futures::{FutureExt, StreamExt};
 use ion::conversions::BoxedIntoValue;
-use ion::{Context, Error, ErrorKind, ErrorReport, Promise, ThrowException, Value};
+use ion::{Context, Error, ErrorKind, ErrorReport, Exception, Promise, TracedHeap, Value};
 use mozjs::jsapi::JSObject;
-use tokio::task::JoinHandle;
+use tracing::debug_span;
 
-type FutureOutput = (Result<BoxedIntoValue, BoxedIntoValue>, *mut JSObject);
+use crate::event_loop::hooks::PromiseHooks;
+use crate::event_loop::microtasks::MicrotaskQueue;
+use crate::event_loop::spawner::SpawnedFuture;
+use crate::globals::abort::abort_error;
+use crate::ContextExt;
+
+pub(crate) type FutureOutput = Result<BoxedIntoValue, BoxedIntoValue>;
+type QueuedOutput = (u64, Result<FutureOutput, String>);
+type QueuedFuture = Pin<Box<dyn Future<Output = QueuedOutput>>>;
+
+struct PendingFuture {
+	promise: TracedHeap<*mut JSObject>,
+	abort: Option<Box<dyn FnOnce()>>,
+}
 
 #[derive(Default)]
 pub struct FutureQueue {
-	queue: FuturesUnordered<JoinHandle<FutureOutput>>,
+	queue: FuturesUnordered<QueuedFuture>,
+	pending: HashMap<u64, PendingFuture>,
+	completed: Vec<QueuedOutput>,
+	next: u64,
 }
 
 impl FutureQueue {
-	pub fn run_futures(&mut self, cx: &Context, wcx: &mut task::Context) -> Result<(), Option<ErrorReport>> {
+	/// Settles the promises of all completed futures.
+	/// Returns whether any future was completed.
+	///
+	/// The microtask queue, if given, is drained after each promise is settled.
+	/// Polling the queue registers the waker of the event loop with any pending futures.
+	pub fn run_futures(
+		&mut self, cx: &Context, wcx: &mut task::Context, mut microtasks: Option<&mut MicrotaskQueue>,
+	) -> Result<bool, Option<ErrorReport>> {
 		let mut results = Vec::new();
 
-		while let Poll::Ready(Some(item)) = self.queue.poll_next_unpin(wcx) {
-			match item {
-				Ok(item) => results.push(item),
-				Err(error) => {
-					Error::new(error.to_string(), ErrorKind::Normal).throw(cx);
-					return Err(None);
-				}
-			}
+		let mut completed = mem::take(&mut self.completed);
+		while let Poll::Ready(Some(output)) = self.queue.poll_next_unpin(wcx) {
+			completed.push(output);
+		}
+
+		for (key, item) in completed {
+			let Some(pending) = self.pending.remove(&key) else {
+				continue;
+			};
+			// A future which panicked or was cancelled by the spawner rejects its promise.
+			let item = item.unwrap_or_else(|error| {
+				let error = Error::new(error, ErrorKind::Normal);
+				Err(Box::new(error) as BoxedIntoValue)
+			});
+			results.push((item, pending.promise));
 		}
 
+		let completed = !results.is_empty();
 		for (result, promise) in results {
+			let _span = debug_span!("future", fulfilled = result.is_ok()).entered();
 			let mut value = Value::undefined(cx);
-			let promise = Promise::from(cx.root(promise)).unwrap();
+			let promise = Promise::from(promise.to_local()).unwrap();
 
 			let result = match result {
 				Ok(o) => {
@@ -53,16 +89,81 @@ impl FutureQueue {
 			if !result {
 				return Err(ErrorReport::new_with_exception_stack(cx).unwrap());
 			}
+
+			let hooks = unsafe { cx.get_private().event_loop.promise_hooks.clone() };
+			if let Some(hooks) = hooks {
+				PromiseHooks::call(&hooks.resolve, cx, &promise);
+			}
+
+			if let Some(microtasks) = microtasks.as_deref_mut() {
+				microtasks.run_jobs(cx)?;
+			}
 		}
 
-		Ok(())
+		Ok(completed)
+	}
+
+	/// Polls the queue without settling the promises of completed futures, which is done by the next call to
+	/// [run_futures](FutureQueue::run_futures). This does not run any JS.
+	pub(crate) fn poll_native(&mut self, wcx: &mut task::Context) {
+		while let Poll::Ready(Some(output)) = self.queue.poll_next_unpin(wcx) {
+			self.completed.push(output);
+		}
 	}
 
-	pub fn enqueue(&self, handle: JoinHandle<FutureOutput>) {
-		self.queue.push(handle);
+	/// Enqueues a future returned by a [Spawner](crate::event_loop::Spawner), which settles the given promise when
+	/// completed.
+	pub fn enqueue(&mut self, future: SpawnedFuture, promise: &Promise) {
+		let key = self.insert(promise, future.abort);
+		self.queue.push(future.output.map(move |result| (key, result)).boxed_local());
+	}
+
+	/// Enqueues a future which is polled in place by the queue instead of being spawned, which settles the given
+	/// promise when completed.
+	pub fn enqueue_local(&mut self, future: LocalBoxFuture<'static, FutureOutput>, promise: &Promise) {
+		self.enqueue(SpawnedFuture::in_place(future), promise);
+	}
+
+	fn insert(&mut self, promise: &Promise, abort: Option<Box<dyn FnOnce()>>) -> u64 {
+		let key = self.next;
+		self.next += 1;
+
+		self.pending.insert(
+			key,
+			PendingFuture {
+				promise: TracedHeap::new(promise.get()),
+				abort,
+			},
+		);
+		key
+	}
+
+	/// Cancels all pending futures, and rejects their promises with an `AbortError`.
+	///
+	/// The futures are aborted at their next suspension point, and are never polled again.
+	/// This releases the persistent roots of the promises.
+	pub fn shutdown(&mut self, cx: &Context) {
+		self.queue.clear();
+		self.completed.clear();
+
+		for (_, pending) in self.pending.drain() {
+			if let Some(abort) = pending.abort {
+				abort();
+			}
+
+			let promise = Promise::from(pending.promise.to_local()).unwrap();
+			if !promise.reject(cx, &abort_error(cx)) {
+				Exception::clear(cx);
+			}
+		}
 	}
 
 	pub fn is_empty(&self) -> bool {
-		self.queue.is_empty()
+		self.queue.is_empty() && self.pending.is_empty()
+	}
+
+	/// Returns the number of futures which have not completed.
+	pub fn len(&self) -> usize {
+		self.pending.len()
 	}
 }
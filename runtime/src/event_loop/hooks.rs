@@ -0,0 +1,33 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use ion::{Context, Promise};
+
+pub type PromiseHook = Box<dyn for<'cx> Fn(&'cx Context, &Promise<'cx>)>;
+
+/// Represents callbacks which are called during the lifecycle of promises.
+///
+/// SpiderMonkey does not notify the runtime of promises created by scripts,
+/// so `init` and `resolve` are only called for promises created and settled by the runtime for native futures.
+#[derive(Default)]
+pub struct PromiseHooks {
+	/// Called when a promise is created for a native future.
+	pub init: Option<PromiseHook>,
+	/// Called when a promise is settled with the result of a native future.
+	pub resolve: Option<PromiseHook>,
+	/// Called before a reaction job of a promise is run.
+	pub before: Option<PromiseHook>,
+	/// Called after a reaction job of a promise is run.
+	pub after: Option<PromiseHook>,
+}
+
+impl PromiseHooks {
+	pub(crate) fn call(hook: &Option<PromiseHook>, cx: &Context, promise: &Promise) {
+		if let Some(hook) = hook {
+			hook(cx, promise);
+		}
+	}
+}
@@ -9,24 +9,30 @@ use std::fmt;
 use std::fmt::{Debug, Formatter};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use chrono::{DateTime, Duration, Utc};
-use ion::{Context, ErrorReport, Function, Object, Value};
+use ion::{Context, Error, ErrorReport, Function, Object, Value};
 use mozjs::jsapi::JSFunction;
 use mozjs::jsval::JSVal;
+use tracing::debug_span;
+
+use crate::clock::{epoch, Clock};
+use crate::ContextExt;
 
 pub struct SignalMacrotask {
-	callback: Option<Box<dyn FnOnce()>>,
+	callback: Option<Box<dyn FnOnce(&Context)>>,
 	terminate: Arc<AtomicBool>,
-	scheduled: DateTime<Utc>,
+	deadline: Instant,
 }
 
 impl SignalMacrotask {
-	pub fn new(callback: Box<dyn FnOnce()>, terminate: Arc<AtomicBool>, duration: Duration) -> SignalMacrotask {
+	pub fn new(
+		callback: Box<dyn FnOnce(&Context)>, terminate: Arc<AtomicBool>, duration: Duration, now: Instant,
+	) -> SignalMacrotask {
 		SignalMacrotask {
 			callback: Some(callback),
 			terminate,
-			scheduled: Utc::now() + duration,
+			deadline: now + duration,
 		}
 	}
 }
@@ -35,53 +41,87 @@ impl Debug for SignalMacrotask {
 	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
 		f.debug_struct("SignalMacrotask")
 			.field("terminate", &self.terminate.as_ref())
-			.field("scheduled", &self.scheduled)
+			.field("deadline", &self.deadline)
 			.finish()
 	}
 }
 
+/// Represents how a repeating timer is rescheduled when its deadline has been missed by more than one period.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum MissedTickBehavior {
+	/// Runs the missed ticks as soon as possible, until the timer has caught up.
+	Burst,
+	/// Schedules the next tick one period after the current time, discarding the original schedule.
+	Delay,
+	/// Skips the missed ticks, and schedules the next tick on the original schedule.
+	#[default]
+	Skip,
+}
+
 #[derive(Debug)]
 pub struct TimerMacrotask {
 	callback: *mut JSFunction,
 	arguments: Box<[JSVal]>,
 	repeat: bool,
-	scheduled: DateTime<Utc>,
+	deadline: Instant,
 	duration: Duration,
 	nesting: u8,
 }
 
 impl TimerMacrotask {
-	pub fn new(callback: Function, arguments: Box<[JSVal]>, repeat: bool, duration: Duration) -> TimerMacrotask {
+	pub fn new(
+		callback: Function, arguments: Box<[JSVal]>, repeat: bool, duration: Duration, now: Instant,
+	) -> TimerMacrotask {
 		TimerMacrotask {
 			callback: callback.get(),
 			arguments,
 			repeat,
 			duration,
-			scheduled: Utc::now(),
+			deadline: coalesce(now + duration),
 			nesting: 0,
 		}
 	}
 
-	pub fn reset(&mut self) -> bool {
-		if self.repeat {
-			self.scheduled = Utc::now();
+	/// Reschedules a repeating timer relative to its previous deadline, so that intervals do not drift.
+	/// Returns whether the timer repeats.
+	pub fn reset(&mut self, behavior: MissedTickBehavior, now: Instant) -> bool {
+		if !self.repeat {
+			return false;
+		}
+
+		self.nesting = self.nesting.saturating_add(1);
+		if self.nesting > MAXIMUM_NESTING_LEVEL {
+			self.duration = self.duration.max(MINIMUM_DELAY_NESTED);
 		}
-		self.repeat
+
+		let next = self.deadline + self.duration;
+		let deadline = if next > now {
+			next
+		} else {
+			match behavior {
+				MissedTickBehavior::Burst => next,
+				MissedTickBehavior::Delay => now + self.duration,
+				MissedTickBehavior::Skip => {
+					let period = self.duration.as_nanos().max(1);
+					let missed = (now - next).as_nanos() / period + 1;
+					next + Duration::from_nanos(u64::try_from(missed * period).unwrap_or(u64::MAX))
+				}
+			}
+		};
+		self.deadline = coalesce(deadline);
+		true
 	}
 }
 
 #[derive(Debug)]
 pub struct UserMacrotask {
 	callback: *mut JSFunction,
-	scheduled: DateTime<Utc>,
+	deadline: Instant,
 }
 
 impl UserMacrotask {
-	pub fn new(callback: Function) -> UserMacrotask {
-		UserMacrotask {
-			callback: callback.get(),
-			scheduled: Utc::now(),
-		}
+	pub fn new(callback: Function, now: Instant) -> UserMacrotask {
+		UserMacrotask { callback: callback.get(), deadline: now }
 	}
 }
 
@@ -92,19 +132,50 @@ pub enum Macrotask {
 	User(UserMacrotask),
 }
 
+/// Resolution of timer deadlines.
+pub const TIMER_RESOLUTION: Duration = Duration::from_millis(1);
+
+/// Rounds a deadline up to the next multiple of [TIMER_RESOLUTION] since the first timer was created.
+///
+/// Timers which become due within the same millisecond share a deadline, so they are run in the same tick of the
+/// event loop in the order they were created, rather than each waking the event loop.
+fn coalesce(deadline: Instant) -> Instant {
+	let epoch = epoch();
+	let Some(elapsed) = deadline.checked_duration_since(epoch) else {
+		return deadline;
+	};
+
+	let resolution = TIMER_RESOLUTION.as_nanos();
+	let ticks = elapsed.as_nanos().div_ceil(resolution);
+	epoch + Duration::from_nanos(u64::try_from(ticks * resolution).unwrap_or(u64::MAX))
+}
+
+/// Maximum timer nesting level before timeouts are clamped, as specified by the HTML Standard.
+pub const MAXIMUM_NESTING_LEVEL: u8 = 5;
+/// Minimum timeout of nested timers, as specified by the HTML Standard.
+pub const MINIMUM_DELAY_NESTED: Duration = Duration::from_millis(4);
+
 #[derive(Debug, Default)]
 pub struct MacrotaskQueue {
 	pub(crate) map: HashMap<u32, Macrotask>,
+	/// Nesting level of the currently running timer, or 0 if no timer is running.
 	pub(crate) nesting: u8,
+	pub(crate) missed_tick_behavior: MissedTickBehavior,
+	pub(crate) clock: Clock,
+	/// Whether timers only run when time is advanced by [advance_timers] or [run_all_timers].
+	pub(crate) fake: bool,
+	/// Whether a macrotask is currently running.
+	running: bool,
 	next: Option<u32>,
 	latest: Option<u32>,
 }
 
 impl Macrotask {
 	pub fn run(&mut self, cx: &Context) -> Result<(), Option<ErrorReport>> {
+		let _span = debug_span!("macrotask", kind = self.kind()).entered();
 		if let Macrotask::Signal(signal) = self {
 			if let Some(callback) = signal.callback.take() {
-				callback();
+				callback(cx);
 			}
 			return Ok(());
 		}
@@ -122,13 +193,21 @@ impl Macrotask {
 		Ok(())
 	}
 
-	pub fn remove(&mut self) -> bool {
+	pub fn remove(&mut self, behavior: MissedTickBehavior, now: Instant) -> bool {
 		match self {
-			Macrotask::Timer(timer) => !timer.reset(),
+			Macrotask::Timer(timer) => !timer.reset(behavior, now),
 			_ => true,
 		}
 	}
 
+	fn kind(&self) -> &'static str {
+		match self {
+			Macrotask::Signal(_) => "signal",
+			Macrotask::Timer(_) => "timer",
+			Macrotask::User(_) => "user",
+		}
+	}
+
 	fn terminate(&self) -> bool {
 		match self {
 			Macrotask::Signal(signal) => signal.terminate.load(Ordering::SeqCst),
@@ -136,36 +215,86 @@ impl Macrotask {
 		}
 	}
 
-	fn remaining(&self) -> Duration {
+	fn deadline(&self) -> Instant {
 		match self {
-			Macrotask::Signal(signal) => signal.scheduled - Utc::now(),
-			Macrotask::Timer(timer) => timer.scheduled + timer.duration - Utc::now(),
-			Macrotask::User(user) => user.scheduled - Utc::now(),
+			Macrotask::Signal(signal) => signal.deadline,
+			Macrotask::Timer(timer) => timer.deadline,
+			Macrotask::User(user) => user.deadline,
+		}
+	}
+
+	fn nesting(&self) -> u8 {
+		match self {
+			Macrotask::Timer(timer) => timer.nesting,
+			_ => 0,
 		}
 	}
 }
 
 impl MacrotaskQueue {
-	pub fn run_job(&mut self, cx: &Context) -> Result<(), Option<ErrorReport>> {
+	pub fn new(missed_tick_behavior: MissedTickBehavior, clock: Clock) -> MacrotaskQueue {
+		MacrotaskQueue {
+			missed_tick_behavior,
+			clock,
+			..MacrotaskQueue::default()
+		}
+	}
+
+	/// Returns the current time of the [Clock] of the runtime, which deadlines are scheduled from.
+	pub fn now(&self) -> Instant {
+		self.clock.now()
+	}
+
+	/// Runs the next macrotask which is due.
+	/// Returns whether a macrotask was run.
+	pub fn run_job(&mut self, cx: &Context) -> Result<bool, Option<ErrorReport>> {
 		self.find_next();
-		if let Some(next) = self.next {
-			{
-				let macrotask = self.map.get_mut(&next);
-				if let Some(macrotask) = macrotask {
-					macrotask.run(cx)?;
-				}
-			}
+		let Some(next) = self.next else {
+			return Ok(false);
+		};
 
-			// The previous reference may be invalidated by running the macrotask.
+		let mut result = Ok(());
+		{
 			let macrotask = self.map.get_mut(&next);
 			if let Some(macrotask) = macrotask {
-				if macrotask.remove() {
-					self.map.remove(&next);
-				}
+				self.nesting = macrotask.nesting();
+				self.running = true;
+				result = macrotask.run(cx);
+				self.running = false;
+				self.nesting = 0;
 			}
 		}
 
-		Ok(())
+		// The previous reference may be invalidated by running the macrotask.
+		// Macrotasks which threw are still removed or rescheduled, so that they do not run again immediately.
+		let macrotask = self.map.get_mut(&next);
+		if let Some(macrotask) = macrotask {
+			if macrotask.remove(self.missed_tick_behavior, self.clock.now()) {
+				self.map.remove(&next);
+			}
+		}
+
+		result.map(|_| true)
+	}
+
+	/// Advances the virtual clock to `deadline`, running each macrotask which becomes due at its own deadline, in
+	/// order.
+	/// Returns the number of macrotasks run.
+	fn run_until(&mut self, cx: &Context, deadline: Instant, limit: u32) -> Result<u32, Option<ErrorReport>> {
+		let mut count = 0;
+		while let Some(next) = self.next_deadline().filter(|next| *next <= deadline) {
+			if count == limit {
+				let message = format!("Aborted after running {limit} timers, which may repeat indefinitely");
+				let error = Error::new(message, None);
+				return Err(Some(ErrorReport::from(error.into(), None)));
+			}
+			self.clock.advance_to(next);
+			if self.run_job(cx)? {
+				count += 1;
+			}
+		}
+		self.clock.advance_to(deadline);
+		Ok(count)
 	}
 
 	pub fn enqueue(&mut self, mut macrotask: Macrotask, id: Option<u32>) -> u32 {
@@ -173,7 +302,7 @@ impl MacrotaskQueue {
 
 		let next = self.next.and_then(|next| self.map.get(&next));
 		if let Some(next) = next {
-			if macrotask.remaining() < next.remaining() {
+			if macrotask.deadline() < next.deadline() {
 				self.set_next(index, &macrotask);
 			}
 		} else {
@@ -181,8 +310,7 @@ impl MacrotaskQueue {
 		}
 
 		if let Macrotask::Timer(timer) = &mut macrotask {
-			self.nesting += 1;
-			timer.nesting = self.nesting;
+			timer.nesting = self.nesting.saturating_add(1);
 		}
 
 		self.latest = Some(index);
@@ -202,6 +330,7 @@ impl MacrotaskQueue {
 	}
 
 	pub fn find_next(&mut self) {
+		let now = self.clock.now();
 		let mut next: Option<(u32, &Macrotask)> = None;
 		let mut to_remove = Vec::new();
 		for (id, macrotask) in &self.map {
@@ -209,11 +338,12 @@ impl MacrotaskQueue {
 				to_remove.push(*id);
 				continue;
 			}
-			if let Some((_, next_macrotask)) = next {
-				if macrotask.remaining() < next_macrotask.remaining() {
+			if let Some((next_id, next_macrotask)) = next {
+				let deadline = (macrotask.deadline(), *id);
+				if deadline < (next_macrotask.deadline(), next_id) {
 					next = Some((*id, macrotask));
 				}
-			} else if macrotask.remaining() <= Duration::zero() {
+			} else if macrotask.deadline() <= now {
 				next = Some((*id, macrotask));
 			}
 		}
@@ -224,13 +354,87 @@ impl MacrotaskQueue {
 		self.next = next;
 	}
 
+	/// Returns the deadline of the earliest macrotask.
+	pub fn next_deadline(&self) -> Option<Instant> {
+		self.map
+			.values()
+			.filter(|macrotask| !macrotask.terminate())
+			.map(Macrotask::deadline)
+			.min()
+	}
+
 	pub fn set_next(&mut self, index: u32, macrotask: &Macrotask) {
-		if macrotask.remaining() < Duration::zero() {
+		if macrotask.deadline() < self.clock.now() {
 			self.next = Some(index);
 		}
 	}
 
+	/// Returns whether any macrotask is due at the current time.
+	pub fn is_due(&self) -> bool {
+		self.next_deadline().is_some_and(|deadline| deadline <= self.clock.now())
+	}
+
 	pub fn is_empty(&self) -> bool {
 		self.map.is_empty()
 	}
 }
+
+/// Maximum number of timers run by [run_all_timers], as repeating timers would otherwise run forever.
+pub const MAXIMUM_FAKE_TIMER_RUNS: u32 = 100_000;
+
+/// Switches the runtime to fake timers, which only run when time is advanced by [advance_timers] or
+/// [run_all_timers], instead of once the event loop is idle. Pending fake timers do not keep the event loop alive.
+///
+/// Returns false if the runtime has no macrotask queue, or is not in
+/// [deterministic mode](crate::config::Config::deterministic).
+pub fn use_fake_timers(cx: &Context) -> bool {
+	let event_loop = unsafe { &mut cx.get_private().event_loop };
+	match &mut event_loop.macrotasks {
+		Some(queue) if queue.clock.is_virtual() => {
+			queue.fake = true;
+			true
+		}
+		_ => false,
+	}
+}
+
+fn fake_timers(cx: &Context) -> Result<&mut MacrotaskQueue, Option<ErrorReport>> {
+	let event_loop = unsafe { &mut cx.get_private().event_loop };
+	match &mut event_loop.macrotasks {
+		Some(queue) if queue.fake && queue.running => {
+			let error = Error::new("Timers cannot be advanced from within a timer", None);
+			Err(Some(ErrorReport::from(error.into(), None)))
+		}
+		Some(queue) if queue.fake => Ok(queue),
+		_ => {
+			let error = Error::new("Fake timers are not in use", None);
+			Err(Some(ErrorReport::from(error.into(), None)))
+		}
+	}
+}
+
+/// Advances fake timers by `duration`, running each timer which becomes due in order of its deadline.
+/// Returns the number of timers run.
+///
+/// Fails if called from within a timer, as the timer would otherwise run again before it is rescheduled.
+///
+/// Microtasks queued by the timers are run at the next microtask checkpoint, rather than between timers.
+pub fn advance_timers(cx: &Context, duration: Duration) -> Result<u32, Option<ErrorReport>> {
+	let queue = fake_timers(cx)?;
+	let deadline = queue.now() + duration;
+	queue.run_until(cx, deadline, u32::MAX)
+}
+
+/// Runs fake timers until none remain, including those created while they run, advancing time to each deadline.
+/// Returns the number of timers run.
+///
+/// Fails after [MAXIMUM_FAKE_TIMER_RUNS] timers, such as when an interval is never cleared, or if called from within a
+/// timer.
+pub fn run_all_timers(cx: &Context) -> Result<u32, Option<ErrorReport>> {
+	let queue = fake_timers(cx)?;
+	let mut count = 0;
+	while let Some(deadline) = queue.next_deadline() {
+		count += queue.run_until(cx, deadline, MAXIMUM_FAKE_TIMER_RUNS - count)?;
+	}
+	Ok(count)
+}
@@ -0,0 +1,144 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::task;
+use std::task::Poll;
+
+use futures::channel::mpsc::{unbounded, TrySendError, UnboundedReceiver, UnboundedSender};
+use futures::stream::SelectAll;
+use futures::StreamExt;
+use ion::clone::StructuredCloneBuffer;
+use ion::conversions::ToValue;
+use ion::{Context, ErrorReport, Function, Object, ResultExc, ThrowException, TracedHeap, Value};
+use mozjs::jsapi::{CloneDataPolicy, JSObject, StructuredCloneScope};
+
+use crate::event_loop::microtasks::MicrotaskQueue;
+use crate::globals::clone::{StructuredCloneDataHolder, STRUCTURED_CLONE_CALLBACKS};
+
+/// Value sent to the event loop of a runtime, which may be running on another thread.
+#[derive(Clone, Debug)]
+pub struct Message(MessageData);
+
+#[derive(Clone, Debug)]
+enum MessageData {
+	Clone(Vec<u8>),
+	String(String),
+}
+
+impl Message {
+	/// Serialises a value with the structured clone algorithm, so it can be received by another runtime.
+	pub fn serialize(cx: &Context, value: &Value) -> ResultExc<Message> {
+		let mut buffer = clone_buffer();
+		buffer.write(cx, value, None, &policy())?;
+		Ok(Message(MessageData::Clone(unsafe { buffer.to_vec() })))
+	}
+
+	/// Creates a message which is received as a string, for senders without a runtime.
+	pub fn string(string: String) -> Message {
+		Message(MessageData::String(string))
+	}
+
+	fn to_value<'cx>(&self, cx: &'cx Context) -> ResultExc<Value<'cx>> {
+		match &self.0 {
+			MessageData::Clone(bytes) => {
+				let buffer = clone_buffer();
+				unsafe { buffer.write_from_bytes(bytes) };
+				buffer.read(cx, &policy())
+			}
+			MessageData::String(string) => Ok(string.as_value(cx)),
+		}
+	}
+}
+
+fn clone_buffer() -> StructuredCloneBuffer {
+	StructuredCloneBuffer::new(
+		StructuredCloneScope::DifferentProcess,
+		&STRUCTURED_CLONE_CALLBACKS,
+		Some(Box::new(StructuredCloneDataHolder::default())),
+	)
+}
+
+fn policy() -> CloneDataPolicy {
+	CloneDataPolicy {
+		allowIntraClusterClonableSharedObjects_: false,
+		allowSharedMemoryObjects_: false,
+	}
+}
+
+/// Handle which sends messages to the event loop of a runtime from any thread.
+///
+/// The event loop keeps running while any sender is alive.
+#[derive(Clone, Debug)]
+pub struct EventLoopSender {
+	sender: UnboundedSender<Message>,
+}
+
+impl EventLoopSender {
+	/// Queues a message for the callback of the runtime.
+	/// Returns the message if the runtime has been dropped.
+	pub fn send(&self, message: Message) -> Result<(), Message> {
+		self.sender.unbounded_send(message).map_err(TrySendError::into_inner)
+	}
+
+	/// Returns whether the runtime has been dropped.
+	pub fn is_closed(&self) -> bool {
+		self.sender.is_closed()
+	}
+}
+
+#[derive(Default)]
+pub struct MessageQueue {
+	receivers: SelectAll<UnboundedReceiver<Message>>,
+	callback: Option<TracedHeap<*mut JSObject>>,
+}
+
+impl MessageQueue {
+	/// Creates a sender whose messages are passed to `callback`, replacing the callback of existing senders.
+	pub fn sender(&mut self, cx: &Context, callback: &Function) -> EventLoopSender {
+		let (sender, receiver) = unbounded();
+		self.receivers.push(receiver);
+		self.callback = Some(TracedHeap::new(callback.to_object(cx).handle().get()));
+		EventLoopSender { sender }
+	}
+
+	/// Calls the callback with each received message.
+	/// Returns whether any message was received.
+	///
+	/// The microtask queue, if given, is drained after each message.
+	pub fn run_messages(
+		&mut self, cx: &Context, wcx: &mut task::Context, mut microtasks: Option<&mut MicrotaskQueue>,
+	) -> Result<bool, Option<ErrorReport>> {
+		let mut received = false;
+
+		while let Poll::Ready(Some(message)) = self.receivers.poll_next_unpin(wcx) {
+			received = true;
+			let Some(callback) = &self.callback else {
+				continue;
+			};
+
+			let value = match message.to_value(cx) {
+				Ok(value) => value,
+				Err(exception) => {
+					exception.throw(cx);
+					return Err(ErrorReport::new_with_exception_stack(cx).unwrap());
+				}
+			};
+
+			let callback = Function::from_object(cx, &callback.to_local()).unwrap();
+			callback.call(cx, &Object::global(cx), &[value])?;
+
+			if let Some(microtasks) = microtasks.as_deref_mut() {
+				microtasks.run_jobs(cx)?;
+			}
+		}
+
+		Ok(received)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.receivers.is_empty()
+	}
+}
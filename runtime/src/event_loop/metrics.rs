@@ -0,0 +1,61 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::time::{Duration, Instant};
+
+/// Snapshot of the time spent by the event loop since it was first polled.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EventLoopMetrics {
+	/// Time spent running tasks.
+	pub active: Duration,
+	/// Time spent waiting for tasks.
+	pub idle: Duration,
+	/// Duration of the longest tick.
+	pub max_tick: Duration,
+	/// Number of times the event loop was polled.
+	pub ticks: u64,
+}
+
+impl EventLoopMetrics {
+	/// Returns the fraction of time spent running tasks, between 0 and 1.
+	pub fn utilization(&self) -> f64 {
+		let total = self.active + self.idle;
+		if total.is_zero() {
+			0.0
+		} else {
+			self.active.as_secs_f64() / total.as_secs_f64()
+		}
+	}
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Utilization {
+	started: Option<Instant>,
+	active: Duration,
+	max_tick: Duration,
+	ticks: u64,
+}
+
+impl Utilization {
+	/// Records a tick of the event loop which started at `start` and has just finished.
+	pub(crate) fn record(&mut self, start: Instant) {
+		let tick = start.elapsed();
+		self.started.get_or_insert(start);
+		self.active += tick;
+		self.max_tick = self.max_tick.max(tick);
+		self.ticks += 1;
+	}
+
+	pub(crate) fn snapshot(&self) -> EventLoopMetrics {
+		let elapsed = self.started.map(|started| started.elapsed()).unwrap_or_default();
+		EventLoopMetrics {
+			active: self.active,
+			idle: elapsed.saturating_sub(self.active),
+			max_tick: self.max_tick,
+			ticks: self.ticks,
+		}
+	}
+}
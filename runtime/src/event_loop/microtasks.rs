@@ -6,18 +6,25 @@
 
 use std::collections::vec_deque::VecDeque;
 use std::ffi::c_void;
+use std::mem;
 
-use ion::{Context, ErrorReport, Function, Object};
+use ion::{Context, ErrorReport, Function, Object, Promise};
 use mozjs::glue::JobQueueTraps;
 use mozjs::jsapi::{
-	CurrentGlobalOrNull, Handle, JSContext, JSFunction, JSObject, JobQueueIsEmpty, JobQueueMayNotBeEmpty,
+	CurrentGlobalOrNull, Handle, JSContext, JSFunction, JSObject, JS_IsRunning, JobQueueIsEmpty,
+	JobQueueMayNotBeEmpty,
 };
+use tracing::debug_span;
 
+use crate::event_loop::hooks::PromiseHooks;
 use crate::ContextExt;
 
 #[derive(Clone, Debug)]
 pub enum Microtask {
-	Promise(*mut JSObject),
+	Promise {
+		job: *mut JSObject,
+		promise: *mut JSObject,
+	},
 	User(*mut JSFunction),
 	None,
 }
@@ -31,11 +38,22 @@ pub struct MicrotaskQueue {
 impl Microtask {
 	pub fn run(&self, cx: &Context) -> Result<(), Option<ErrorReport>> {
 		match self {
-			Microtask::Promise(job) => {
+			Microtask::Promise { job, promise } => {
 				let object = cx.root(*job);
 				let function = Function::from_object(cx, &object).unwrap();
 
-				function.call(cx, &Object::null(cx), &[]).map(|_| ())
+				let hooks = unsafe { cx.get_private().event_loop.promise_hooks.clone() };
+				let promise = hooks.as_ref().and_then(|_| (!promise.is_null()).then(|| cx.root(*promise)));
+				let promise = promise.and_then(Promise::from);
+
+				if let (Some(hooks), Some(promise)) = (&hooks, &promise) {
+					PromiseHooks::call(&hooks.before, cx, promise);
+				}
+				let result = function.call(cx, &Object::null(cx), &[]).map(|_| ());
+				if let (Some(hooks), Some(promise)) = (&hooks, &promise) {
+					PromiseHooks::call(&hooks.after, cx, promise);
+				}
+				result
 			}
 			Microtask::User(callback) => {
 				let callback = Function::from(cx.root(*callback));
@@ -58,9 +76,22 @@ impl MicrotaskQueue {
 		}
 
 		self.draining = true;
+		let _span = debug_span!("microtasks", count = self.queue.len()).entered();
 
 		while let Some(microtask) = self.queue.pop_front() {
-			microtask.run(cx)?;
+			match microtask.run(cx) {
+				Ok(()) => {}
+				// Exceptions thrown by callbacks are reported, and the checkpoint continues with the next microtask.
+				Err(Some(report)) if matches!(microtask, Microtask::User(_)) => {
+					let event_loop = unsafe { &cx.get_private().event_loop };
+					event_loop.report_error(cx, report);
+				}
+				Err(error) => {
+					// Remaining microtasks must still be run by the next checkpoint.
+					self.draining = false;
+					return Err(error);
+				}
+			}
 		}
 
 		self.draining = false;
@@ -74,19 +105,65 @@ impl MicrotaskQueue {
 	}
 }
 
+/// Performs a microtask checkpoint, running all queued microtasks.
+///
+/// Does nothing if script is currently running, as the checkpoint is then performed once the outermost script returns.
+pub fn microtask_checkpoint(cx: &Context) -> Result<(), Option<ErrorReport>> {
+	if unsafe { JS_IsRunning(cx.as_ptr()) } {
+		return Ok(());
+	}
+
+	let event_loop = unsafe { &mut cx.get_private().event_loop };
+	match &mut event_loop.microtasks {
+		Some(microtasks) => microtasks.run_jobs(cx),
+		None => Ok(()),
+	}
+}
+
+/// Performs a microtask checkpoint when dropped.
+///
+/// Native code which calls into script outside of the event loop, such as from a future, should hold this guard
+/// around the call, so that microtasks queued by the callback run before control returns to native code.
+/// Errors thrown by microtasks are reported as uncaught errors.
+#[must_use = "the checkpoint is performed when the guard is dropped"]
+pub struct MicrotaskCheckpoint<'cx> {
+	cx: &'cx Context,
+}
+
+impl<'cx> MicrotaskCheckpoint<'cx> {
+	pub fn new(cx: &'cx Context) -> MicrotaskCheckpoint<'cx> {
+		MicrotaskCheckpoint { cx }
+	}
+
+	/// Leaves the queued microtasks to the next checkpoint performed by the event loop, rather than running them now.
+	///
+	/// This is used where running arbitrary microtasks is unsafe, such as while a structured clone is being read.
+	pub fn defer(self) {
+		mem::forget(self);
+	}
+}
+
+impl Drop for MicrotaskCheckpoint<'_> {
+	fn drop(&mut self) {
+		if let Err(Some(report)) = microtask_checkpoint(self.cx) {
+			unsafe { self.cx.get_private().event_loop.report_error(self.cx, report) };
+		}
+	}
+}
+
 unsafe extern "C" fn get_incumbent_global(_: *const c_void, cx: *mut JSContext) -> *mut JSObject {
 	unsafe { CurrentGlobalOrNull(cx) }
 }
 
 unsafe extern "C" fn enqueue_promise_job(
-	_: *const c_void, cx: *mut JSContext, _: Handle<*mut JSObject>, job: Handle<*mut JSObject>,
+	_: *const c_void, cx: *mut JSContext, promise: Handle<*mut JSObject>, job: Handle<*mut JSObject>,
 	_: Handle<*mut JSObject>, _: Handle<*mut JSObject>,
 ) -> bool {
 	let cx = unsafe { &Context::new_unchecked(cx) };
 	let event_loop = unsafe { &mut cx.get_private().event_loop };
 	let microtasks = event_loop.microtasks.as_mut().unwrap();
 	if !job.is_null() {
-		microtasks.enqueue(cx, Microtask::Promise(job.get()))
+		microtasks.enqueue(cx, Microtask::Promise { job: job.get(), promise: promise.get() })
 	} else {
 		microtasks.enqueue(cx, Microtask::None)
 	};
@@ -4,83 +4,294 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::ffi::c_void;
+use std::future::Future;
+use std::pin::pin;
+use std::rc::Rc;
 use std::task;
-use std::task::Poll;
+use std::task::{Poll, Waker};
+use std::time::Instant;
 
-use futures::future::poll_fn;
+use futures::future::{poll_fn, LocalBoxFuture};
 use ion::format::{format_value, Config};
-use ion::{Context, ErrorReport, Local, Promise};
-use mozjs::jsapi::{Handle, Heap, JSContext, JSObject, PromiseRejectionHandlingState};
+use ion::{BoxedHeap, Context, Error, ErrorReport, Exception, Local, Promise, TracedHeap};
+use mozjs::jsapi::{Handle, JSContext, JSObject, PromiseRejectionHandlingState};
+use mozjs::jsval::{JSVal, ObjectValue, UndefinedValue};
+use tracing::error;
 
 use crate::event_loop::future::FutureQueue;
+use crate::event_loop::hooks::PromiseHooks;
 use crate::event_loop::macrotasks::MacrotaskQueue;
+use crate::event_loop::messages::MessageQueue;
+use crate::event_loop::metrics::Utilization;
 use crate::event_loop::microtasks::MicrotaskQueue;
+use crate::event_loop::spawner::{Spawner, ThreadSleep};
+use crate::inspector::{notify, InspectorEvent};
 use crate::ContextExt;
 
 pub(crate) mod future;
+pub mod hooks;
 pub(crate) mod macrotasks;
+pub(crate) mod messages;
+mod metrics;
 pub(crate) mod microtasks;
+mod spawner;
+mod uncaught;
+
+pub use macrotasks::{advance_timers, run_all_timers, use_fake_timers, MissedTickBehavior, MAXIMUM_FAKE_TIMER_RUNS};
+pub use messages::{EventLoopSender, Message};
+pub use metrics::EventLoopMetrics;
+pub use microtasks::{microtask_checkpoint, MicrotaskCheckpoint};
+#[cfg(feature = "tokio-promise")]
+pub use spawner::TokioSpawner;
+pub use spawner::{SpawnedFuture, Spawner};
+pub use uncaught::{UncaughtErrorAction, UncaughtErrorHandler, UnhandledRejectionHandler};
 
 #[derive(Default)]
 pub struct EventLoop {
 	pub(crate) futures: Option<FutureQueue>,
 	pub(crate) microtasks: Option<MicrotaskQueue>,
 	pub(crate) macrotasks: Option<MacrotaskQueue>,
-	pub(crate) unhandled_rejections: VecDeque<Box<Heap<*mut JSObject>>>,
+	pub(crate) messages: Option<MessageQueue>,
+	pub(crate) unhandled_rejections: VecDeque<BoxedHeap<*mut JSObject>>,
+	pub(crate) promise_hooks: Option<Rc<PromiseHooks>>,
+	pub(crate) uncaught_error_handler: Option<Rc<dyn UncaughtErrorHandler>>,
+	pub(crate) uncaught_exception_handler: Option<Rc<dyn UncaughtErrorHandler>>,
+	pub(crate) unhandled_rejection_handler: Option<Rc<dyn UnhandledRejectionHandler>>,
+	pub(crate) spawner: Option<Rc<dyn Spawner>>,
+	exit_error: RefCell<Option<ExitError>>,
+	timer: Option<(Instant, LocalBoxFuture<'static, ()>)>,
+	waker: Option<Waker>,
+	utilization: Utilization,
 }
 
 impl EventLoop {
 	pub async fn run_event_loop(&mut self, cx: &Context) -> Result<(), Option<ErrorReport>> {
-		let mut complete = false;
-		poll_fn(|wcx| self.poll_event_loop(cx, wcx, &mut complete)).await
+		poll_fn(|wcx| {
+			if !self.waker.as_ref().is_some_and(|waker| waker.will_wake(wcx.waker())) {
+				self.waker = Some(wcx.waker().clone());
+			}
+			if let Some(error) = self.exit_error.take() {
+				return Poll::Ready(Err(Some(error.into_report())));
+			}
+
+			let start = Instant::now();
+			let poll = self.poll_event_loop(cx, wcx);
+			self.utilization.record(start);
+			unsafe { cx.get_private().resources.sample_heap(cx) };
+
+			if let Some(error) = self.exit_error.take() {
+				return Poll::Ready(Err(Some(error.into_report())));
+			}
+
+			if let Poll::Ready(Err(Some(report))) = &poll {
+				notify(cx, || InspectorEvent::UncaughtError { message: report.format(cx) });
+				if self.handle_uncaught_error(cx, report) == UncaughtErrorAction::Continue {
+					wcx.waker().wake_by_ref();
+					return Poll::Pending;
+				}
+			}
+			poll
+		})
+		.await
+	}
+
+	/// Passes an uncaught error to the handler of the host, and then to the handler set by
+	/// `setUncaughtExceptionHandler`, until one of them decides to continue.
+	pub fn handle_uncaught_error(&self, cx: &Context, report: &ErrorReport) -> UncaughtErrorAction {
+		let handlers = [&self.uncaught_error_handler, &self.uncaught_exception_handler];
+		for handler in handlers.into_iter().flatten() {
+			// The handler may replace itself while it runs.
+			let handler = Rc::clone(handler);
+			if handler.handle(cx, report) == UncaughtErrorAction::Continue {
+				return UncaughtErrorAction::Continue;
+			}
+		}
+		UncaughtErrorAction::Exit
+	}
+
+	/// Reports an error which was not caught by scripts without interrupting the current task, such as an exception
+	/// thrown by a `queueMicrotask` callback or passed to `reportError`.
+	///
+	/// If none of the handlers continue, the event loop stops with the error when it is next polled.
+	pub fn report_error(&self, cx: &Context, report: ErrorReport) {
+		notify(cx, || InspectorEvent::UncaughtError { message: report.format(cx) });
+		if self.handle_uncaught_error(cx, &report) == UncaughtErrorAction::Exit {
+			self.exit_error.borrow_mut().get_or_insert_with(|| ExitError::new(report));
+			if let Some(waker) = &self.waker {
+				waker.wake_by_ref();
+			}
+		}
 	}
 
-	fn poll_event_loop(
-		&mut self, cx: &Context, wcx: &mut task::Context, complete: &mut bool,
-	) -> Poll<Result<(), Option<ErrorReport>>> {
+	/// Returns the time spent running tasks and waiting for them since the event loop was first run.
+	pub fn metrics(&self) -> EventLoopMetrics {
+		self.utilization.snapshot()
+	}
+
+	/// Sets the [PromiseHooks] called during the lifecycle of promises, replacing any existing hooks.
+	pub fn set_promise_hooks(&mut self, hooks: Option<PromiseHooks>) {
+		self.promise_hooks = hooks.map(Rc::new);
+	}
+
+	/// Runs `future` to completion while polling native futures which are polled in place, as they are otherwise only
+	/// polled by the event loop. No JS is run.
+	#[cfg_attr(not(feature = "fetch"), expect(dead_code))]
+	pub(crate) async fn poll_native_until<F: Future>(&mut self, future: F) -> F::Output {
+		let mut future = pin!(future);
+		poll_fn(|wcx| {
+			if let Some(futures) = &mut self.futures {
+				futures.poll_native(wcx);
+			}
+			future.as_mut().poll(wcx)
+		})
+		.await
+	}
+
+	/// Wakes the event loop, such as after the virtual [Clock](crate::clock::Clock) has been advanced.
+	pub(crate) fn wake(&self) {
+		if let Some(waker) = &self.waker {
+			waker.wake_by_ref();
+		}
+	}
+
+	/// Cancels all in-flight native futures, and rejects their promises with an `AbortError`.
+	/// Senders of messages to the event loop are closed.
+	///
+	/// This must be called before the runtime is dropped, as the futures may otherwise outlive the context.
+	pub fn shutdown(&mut self, cx: &Context) {
+		if let Some(futures) = &mut self.futures {
+			futures.shutdown(cx);
+			debug_assert!(futures.is_empty(), "Native futures were not cancelled during shutdown");
+		}
+		self.messages = None;
+		self.timer = None;
+		self.waker = None;
+	}
+
+	/// Polls the event loop once.
+	///
+	/// Tasks are run in the following order:
+	/// - Completed native futures, each followed by a microtask checkpoint
+	/// - Messages from [EventLoopSender]s, each followed by a microtask checkpoint
+	/// - Remaining microtasks
+	/// - A single due macrotask, followed by a microtask checkpoint
+	///
+	/// This guarantees that the microtask queue is empty before any future or macrotask is run.
+	///
+	/// If any task was run, the event loop is immediately woken again, as the task may have queued more work.
+	/// Otherwise, the event loop only wakes when a native future is completed, a message is sent,
+	/// or the next macrotask is due.
+	fn poll_event_loop(&mut self, cx: &Context, wcx: &mut task::Context) -> Poll<Result<(), Option<ErrorReport>>> {
+		let mut progressed = false;
+
 		if let Some(futures) = &mut self.futures {
 			if !futures.is_empty() {
-				futures.run_futures(cx, wcx)?;
+				progressed |= futures.run_futures(cx, wcx, self.microtasks.as_mut())?;
 			}
 		}
 
-		if let Some(microtasks) = &mut self.microtasks {
-			if !microtasks.is_empty() {
-				microtasks.run_jobs(cx)?;
+		if let Some(messages) = &mut self.messages {
+			if !messages.is_empty() {
+				progressed |= messages.run_messages(cx, wcx, self.microtasks.as_mut())?;
 			}
 		}
 
+		progressed |= self.run_microtasks(cx)?;
+
 		if let Some(macrotasks) = &mut self.macrotasks {
-			if !macrotasks.is_empty() {
-				macrotasks.run_job(cx)?;
+			if !macrotasks.is_empty() && macrotasks.run_job(cx)? {
+				self.run_microtasks(cx)?;
+				progressed = true;
 			}
 		}
 
 		while let Some(promise) = self.unhandled_rejections.pop_front() {
 			let promise = Promise::from(unsafe { Local::from_heap(&promise) }).unwrap();
 			let result = promise.result(cx);
-			eprintln!(
-				"Unhandled Promise Rejection: {}",
-				format_value(cx, Config::default(), &result)
-			);
+			let reason = format_value(cx, Config::default(), &result).to_string();
+			match &self.unhandled_rejection_handler {
+				Some(handler) => Rc::clone(handler).handle(cx, &result),
+				None => error!("Unhandled Promise Rejection: {reason}"),
+			}
+			notify(cx, || InspectorEvent::UnhandledRejection { reason });
 		}
 
-		let empty = self.is_empty();
-		if empty && *complete {
-			Poll::Ready(Ok(()))
+		if self.is_empty() {
+			self.timer = None;
+			return Poll::Ready(Ok(()));
+		}
+
+		if progressed {
+			wcx.waker().wake_by_ref();
 		} else {
+			self.register_timer(wcx);
+		}
+		Poll::Pending
+	}
+
+	/// Performs a microtask checkpoint, draining the microtask queue.
+	/// Returns whether any microtask was run.
+	fn run_microtasks(&mut self, cx: &Context) -> Result<bool, Option<ErrorReport>> {
+		if let Some(microtasks) = &mut self.microtasks {
+			if !microtasks.is_empty() {
+				microtasks.run_jobs(cx)?;
+				return Ok(true);
+			}
+		}
+		Ok(false)
+	}
+
+	/// Registers a timer with the [Spawner] to wake the event loop when the next macrotask is due.
+	///
+	/// Without a spawner, the timer is waited for on a separate thread. With a virtual
+	/// [Clock](crate::clock::Clock), time is advanced to the deadline once the event loop is otherwise idle, unless
+	/// fake timers are in use.
+	fn register_timer(&mut self, wcx: &mut task::Context) {
+		let deadline = self.macrotasks.as_ref().and_then(MacrotaskQueue::next_deadline);
+		let Some(deadline) = deadline else {
+			self.timer = None;
+			return;
+		};
+
+		let macrotasks = self.macrotasks.as_ref().unwrap();
+		let clock = &macrotasks.clock;
+		if macrotasks.fake {
+			// Fake timers only run when time is advanced by scripts.
+			self.timer = None;
+			return;
+		} else if clock.is_virtual() {
+			// Virtual time passes once no other task can run, rather than waiting for the system clock.
+			self.timer = None;
+			let idle = self.futures.as_ref().map(FutureQueue::is_empty).unwrap_or(true)
+				&& self.messages.as_ref().map(MessageQueue::is_empty).unwrap_or(true);
+			if idle && clock.advance_to(deadline) {
+				wcx.waker().wake_by_ref();
+			}
+			return;
+		}
+
+		if !matches!(&self.timer, Some((current, _)) if *current == deadline) {
+			let timer = match &self.spawner {
+				Some(spawner) => spawner.sleep_until(deadline),
+				None => Box::pin(ThreadSleep::new(deadline)),
+			};
+			self.timer = Some((deadline, timer));
+		}
+
+		let (_, timer) = self.timer.as_mut().unwrap();
+		if timer.as_mut().poll(wcx).is_ready() {
 			wcx.waker().wake_by_ref();
-			*complete = empty;
-			Poll::Pending
 		}
 	}
 
 	fn is_empty(&self) -> bool {
 		self.microtasks.as_ref().map(|m| m.is_empty()).unwrap_or(true)
 			&& self.futures.as_ref().map(|f| f.is_empty()).unwrap_or(true)
-			&& self.macrotasks.as_ref().map(|m| m.is_empty()).unwrap_or(true)
+			&& self.macrotasks.as_ref().map(|m| m.is_empty() || (m.fake && !m.is_due())).unwrap_or(true)
+			&& self.messages.as_ref().map(|m| m.is_empty()).unwrap_or(true)
 	}
 }
 
@@ -91,7 +302,7 @@ pub(crate) unsafe extern "C" fn promise_rejection_tracker_callback(
 	let promise = Promise::from(unsafe { Local::from_raw_handle(promise) }).unwrap();
 	let unhandled = unsafe { &mut cx.get_private().event_loop.unhandled_rejections };
 	match state {
-		PromiseRejectionHandlingState::Unhandled => unhandled.push_back(Heap::boxed(promise.get())),
+		PromiseRejectionHandlingState::Unhandled => unhandled.push_back(BoxedHeap::new(promise.get())),
 		PromiseRejectionHandlingState::Handled => {
 			let idx = unhandled.iter().position(|unhandled| unhandled.get() == promise.get());
 			if let Some(idx) = idx {
@@ -100,3 +311,36 @@ pub(crate) unsafe extern "C" fn promise_rejection_tracker_callback(
 		}
 	}
 }
+
+/// Error which stops the event loop when it is next polled, whose exception is rooted until then.
+struct ExitError {
+	report: ErrorReport,
+	exception: TracedHeap<JSVal>,
+}
+
+impl ExitError {
+	fn new(mut report: ErrorReport) -> ExitError {
+		let exception = match &report.exception {
+			Exception::Error(Error { object: Some(object), .. }) => ObjectValue(*object),
+			Exception::Error(_) => UndefinedValue(),
+			Exception::Other(value) => *value,
+		};
+		// The records of the stack are kept, but its object is not rooted.
+		if let Some(stack) = &mut report.stack {
+			stack.object = None;
+		}
+		let exception = TracedHeap::new(exception);
+		ExitError { report, exception }
+	}
+
+	/// Returns the [ErrorReport], updating its exception in case it was moved by the garbage collector.
+	fn into_report(self) -> ErrorReport {
+		let ExitError { mut report, exception } = self;
+		match &mut report.exception {
+			Exception::Error(Error { object: object @ Some(_), .. }) => *object = Some(exception.get().to_object()),
+			Exception::Error(_) => {}
+			Exception::Other(value) => *value = exception.get(),
+		}
+		report
+	}
+}
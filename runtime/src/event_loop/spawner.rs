@@ -0,0 +1,117 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::Instant;
+
+use futures::future::LocalBoxFuture;
+use futures::FutureExt;
+
+use crate::event_loop::future::FutureOutput;
+
+/// Native future which was handed to a [Spawner], and completes with the output of the original future, or an error
+/// if it panicked or was cancelled.
+pub struct SpawnedFuture {
+	pub output: LocalBoxFuture<'static, Result<FutureOutput, String>>,
+	/// Cancels the future when its runtime is shut down, if it is not dropped along with [output](Self::output).
+	pub abort: Option<Box<dyn FnOnce()>>,
+}
+
+impl SpawnedFuture {
+	/// Wraps a future which is polled in place by the event loop.
+	pub fn in_place(future: LocalBoxFuture<'static, FutureOutput>) -> SpawnedFuture {
+		let output = AssertUnwindSafe(future)
+			.catch_unwind()
+			.map(|result| result.map_err(|_| String::from("Native future panicked")));
+		SpawnedFuture {
+			output: output.boxed_local(),
+			abort: None,
+		}
+	}
+}
+
+/// Executor which the event loop of a runtime runs its native futures and timers on.
+///
+/// The [TokioSpawner] is used by default, which requires a Tokio [LocalSet](tokio::task::LocalSet). Other executors,
+/// such as those of embedders or targets without Tokio, can be used with
+/// [RuntimeBuilder::spawner](crate::RuntimeBuilder::spawner).
+pub trait Spawner {
+	/// Spawns a native future, such as those of `fetch` or file system operations.
+	///
+	/// By default, the future is polled in place by the event loop.
+	fn spawn(&self, future: LocalBoxFuture<'static, FutureOutput>) -> SpawnedFuture {
+		SpawnedFuture::in_place(future)
+	}
+
+	/// Returns a future which completes at `deadline`, which wakes the event loop when the next timer is due.
+	fn sleep_until(&self, deadline: Instant) -> LocalBoxFuture<'static, ()>;
+}
+
+/// Spawns native futures onto the current [LocalSet](tokio::task::LocalSet), and waits for timers with the timer
+/// driver of the current Tokio runtime.
+#[cfg(feature = "tokio-promise")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TokioSpawner;
+
+#[cfg(feature = "tokio-promise")]
+impl Spawner for TokioSpawner {
+	fn spawn(&self, future: LocalBoxFuture<'static, FutureOutput>) -> SpawnedFuture {
+		let handle = tokio::task::spawn_local(future);
+		let abort = handle.abort_handle();
+		SpawnedFuture {
+			output: handle.map(|result| result.map_err(|error| error.to_string())).boxed_local(),
+			abort: Some(Box::new(move || abort.abort())),
+		}
+	}
+
+	fn sleep_until(&self, deadline: Instant) -> LocalBoxFuture<'static, ()> {
+		tokio::time::sleep_until(deadline.into()).boxed_local()
+	}
+}
+
+/// Future which completes at a deadline, and is woken by a separate thread.
+///
+/// Timers use this when the runtime has no [Spawner] to wait for them.
+pub(crate) struct ThreadSleep {
+	deadline: Instant,
+	waker: Option<Arc<Mutex<Waker>>>,
+}
+
+impl ThreadSleep {
+	pub(crate) fn new(deadline: Instant) -> ThreadSleep {
+		ThreadSleep { deadline, waker: None }
+	}
+}
+
+impl Future for ThreadSleep {
+	type Output = ();
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+		if Instant::now() >= self.deadline {
+			return Poll::Ready(());
+		}
+
+		match &self.waker {
+			Some(waker) => waker.lock().unwrap().clone_from(cx.waker()),
+			None => {
+				let waker = Arc::new(Mutex::new(cx.waker().clone()));
+				let deadline = self.deadline;
+				let thread_waker = Arc::clone(&waker);
+				thread::spawn(move || {
+					thread::sleep(deadline.saturating_duration_since(Instant::now()));
+					thread_waker.lock().unwrap().wake_by_ref();
+				});
+				self.waker = Some(waker);
+			}
+		}
+		Poll::Pending
+	}
+}
@@ -0,0 +1,43 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use ion::{Context, ErrorReport, Value};
+
+/// Decides whether the event loop stops after an uncaught error.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UncaughtErrorAction {
+	/// Stops the event loop and returns the error, so that the host can print it and exit.
+	#[default]
+	Exit,
+	/// Discards the error and keeps running the event loop.
+	Continue,
+}
+
+/// Receives errors which were not caught by scripts, before the default behaviour of stopping the event loop.
+///
+/// This is implemented for closures, such as `|cx, report| UncaughtErrorAction::Continue`.
+pub trait UncaughtErrorHandler {
+	fn handle(&self, cx: &Context, report: &ErrorReport) -> UncaughtErrorAction;
+}
+
+impl<F: Fn(&Context, &ErrorReport) -> UncaughtErrorAction> UncaughtErrorHandler for F {
+	fn handle(&self, cx: &Context, report: &ErrorReport) -> UncaughtErrorAction {
+		self(cx, report)
+	}
+}
+
+/// Receives the reasons of promises which were rejected without a handler, instead of printing them.
+///
+/// This is implemented for closures, such as `|cx, reason| eprintln!("{}", format_value(cx, config, reason))`.
+pub trait UnhandledRejectionHandler {
+	fn handle(&self, cx: &Context, reason: &Value);
+}
+
+impl<F: Fn(&Context, &Value)> UnhandledRejectionHandler for F {
+	fn handle(&self, cx: &Context, reason: &Value) {
+		self(cx, reason)
+	}
+}
@@ -6,36 +6,88 @@
 
 use std::future::Future;
 use std::pin::{pin, Pin};
+use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::task::Poll;
+use std::time::Duration;
 use std::{ptr, task};
 
-use chrono::Duration;
 use ion::class::Reflector;
 use ion::conversions::{FromValue, ToValue};
 use ion::function::{Enforce, Opt};
-use ion::{ClassDefinition, Context, Error, ErrorKind, Exception, Object, Result, ResultExc, Value};
-use mozjs::jsapi::{Heap, JSObject};
+use ion::{
+	BoxedHeap, ClassDefinition, Context, Error, ErrorKind, Exception, Object, Result, ResultExc, TracedHeap, Value,
+};
+use mozjs::jsapi::JSObject;
 use mozjs::jsval::JSVal;
 use tokio::sync::watch::{channel, Receiver, Sender};
 
 use crate::event_loop::macrotasks::{Macrotask, SignalMacrotask};
 use crate::ContextExt;
 
+/// Reason of an aborted signal, which stays rooted while any [Signal] refers to it.
+pub type AbortReason = Rc<TracedHeap<JSVal>>;
+
+/// Creates an error with the name of a `DOMException`, such as `AbortError`.
+pub fn dom_error<'cx>(cx: &'cx Context, name: &str, message: &str) -> Value<'cx> {
+	let error = Error::new(String::from(message), None).as_value(cx);
+	error.to_object(cx).set_as(cx, "name", name);
+	error
+}
+
+/// Creates the `AbortError` which is the default reason of aborted signals and cancelled operations.
+pub fn abort_error<'cx>(cx: &'cx Context) -> Value<'cx> {
+	dom_error(cx, "AbortError", "The operation was aborted.")
+}
+
 #[derive(Clone, Debug, Default)]
 pub enum Signal {
 	#[default]
 	None,
-	Abort(JSVal),
-	Receiver(Receiver<Option<JSVal>>),
-	Timeout(Receiver<Option<JSVal>>, Arc<AtomicBool>),
+	Abort(AbortReason),
+	Receiver(Receiver<Option<AbortReason>>),
+	Timeout(Receiver<Option<AbortReason>>, Arc<AtomicBool>),
 }
 
 impl Signal {
 	pub fn poll(&self) -> SignalFuture {
 		SignalFuture { inner: self.clone() }
 	}
+
+	pub fn reason(&self) -> Option<JSVal> {
+		match self {
+			Signal::None => None,
+			Signal::Abort(reason) => Some(reason.get()),
+			Signal::Receiver(receiver) | Signal::Timeout(receiver, _) => {
+				receiver.borrow().as_ref().map(|reason| reason.get())
+			}
+		}
+	}
+}
+
+/// Aborts the [Signal]s created with it, at most once.
+#[derive(Debug)]
+pub struct AbortSender(Sender<Option<AbortReason>>);
+
+impl AbortSender {
+	pub fn new() -> (AbortSender, Signal) {
+		let (sender, receiver) = channel(None);
+		(AbortSender(sender), Signal::Receiver(receiver))
+	}
+
+	/// Aborts the signals with the reason, or an `AbortError` if it is undefined.
+	/// Does nothing if they have already been aborted, so that the first reason is kept.
+	pub fn abort(&self, cx: &Context, reason: Option<&Value>) {
+		if self.0.borrow().is_some() {
+			return;
+		}
+		let reason = match reason {
+			Some(reason) if !reason.handle().is_undefined() => reason.get(),
+			_ => abort_error(cx).get(),
+		};
+		self.0.send_replace(Some(Rc::new(TracedHeap::new(reason))));
+	}
 }
 
 pub struct SignalFuture {
@@ -48,15 +100,15 @@ impl Future for SignalFuture {
 	fn poll(mut self: Pin<&mut SignalFuture>, cx: &mut task::Context) -> Poll<JSVal> {
 		match &mut self.inner {
 			Signal::None => Poll::Pending,
-			Signal::Abort(abort) => Poll::Ready(*abort),
+			Signal::Abort(abort) => Poll::Ready(abort.get()),
 			Signal::Receiver(receiver) | Signal::Timeout(receiver, _) => {
-				if let Some(abort) = *receiver.borrow() {
-					return Poll::Ready(abort);
+				if let Some(abort) = &*receiver.borrow() {
+					return Poll::Ready(abort.get());
 				}
 				let changed = { pin!(receiver.changed()).poll(cx) };
 				match changed {
-					Poll::Ready(_) => match *receiver.borrow() {
-						Some(abort) => Poll::Ready(abort),
+					Poll::Ready(_) => match &*receiver.borrow() {
+						Some(abort) => Poll::Ready(abort.get()),
 						None => Poll::Pending,
 					},
 					Poll::Pending => Poll::Pending,
@@ -79,21 +131,21 @@ impl Drop for SignalFuture {
 #[js_class]
 pub struct AbortController {
 	reflector: Reflector,
-	signal: Box<Heap<*mut JSObject>>,
+	signal: BoxedHeap<*mut JSObject>,
 	#[trace(no_trace)]
-	sender: Sender<Option<JSVal>>,
+	sender: AbortSender,
 }
 
 #[js_class]
 impl AbortController {
 	#[ion(constructor)]
 	pub fn constructor(cx: &Context) -> AbortController {
-		let (sender, receiver) = channel(None);
-		let signal = Heap::boxed(AbortSignal::new_object(
+		let (sender, signal) = AbortSender::new();
+		let signal = BoxedHeap::new(AbortSignal::new_object(
 			cx,
 			Box::new(AbortSignal {
 				reflector: Reflector::default(),
-				signal: Signal::Receiver(receiver),
+				signal,
 			}),
 		));
 		AbortController {
@@ -109,8 +161,7 @@ impl AbortController {
 	}
 
 	pub fn abort<'cx>(&self, cx: &'cx Context, Opt(reason): Opt<Value<'cx>>) {
-		let reason = reason.unwrap_or_else(|| Error::new("AbortError", None).as_value(cx));
-		self.sender.send_replace(Some(reason.get()));
+		self.sender.abort(cx, reason.as_ref());
 	}
 }
 
@@ -122,6 +173,13 @@ pub struct AbortSignal {
 	pub(crate) signal: Signal,
 }
 
+impl AbortSignal {
+	/// Returns the [Signal] which native operations wait on to be aborted.
+	pub fn signal(&self) -> &Signal {
+		&self.signal
+	}
+}
+
 #[js_class]
 impl AbortSignal {
 	#[ion(get)]
@@ -131,11 +189,7 @@ impl AbortSignal {
 
 	#[ion(get)]
 	pub fn get_reason(&self) -> Option<JSVal> {
-		match &self.signal {
-			Signal::None => None,
-			Signal::Abort(abort) => Some(*abort),
-			Signal::Receiver(receiver) | Signal::Timeout(receiver, _) => *receiver.borrow(),
-		}
+		self.signal.reason()
 	}
 
 	#[ion(name = "throwIfAborted")]
@@ -148,12 +202,15 @@ impl AbortSignal {
 	}
 
 	pub fn abort<'cx>(cx: &'cx Context, Opt(reason): Opt<Value<'cx>>) -> *mut JSObject {
-		let reason = reason.unwrap_or_else(|| Error::new("AbortError", None).as_value(cx));
+		let reason = match reason {
+			Some(reason) if !reason.handle().is_undefined() => reason,
+			_ => abort_error(cx),
+		};
 		AbortSignal::new_object(
 			cx,
 			Box::new(AbortSignal {
 				reflector: Reflector::default(),
-				signal: Signal::Abort(reason.get()),
+				signal: Signal::Abort(Rc::new(TracedHeap::new(reason.get()))),
 			}),
 		)
 	}
@@ -163,18 +220,16 @@ impl AbortSignal {
 		let terminate = Arc::new(AtomicBool::new(false));
 		let terminate2 = Arc::clone(&terminate);
 
-		let error = Error::new(format!("Timeout Error: {time}ms"), None).as_value(cx).get();
-		let callback = Box::new(move || {
-			sender.send_replace(Some(error));
+		let callback = Box::new(move |cx: &Context| {
+			let error = dom_error(cx, "TimeoutError", &format!("Signal timed out after {time}ms"));
+			sender.send_replace(Some(Rc::new(TracedHeap::new(error.get()))));
 		});
 
-		let duration = Duration::milliseconds(time as i64);
+		let duration = Duration::from_millis(time);
 		let event_loop = unsafe { &mut cx.get_private().event_loop };
 		if let Some(queue) = &mut event_loop.macrotasks {
-			queue.enqueue(
-				Macrotask::Signal(SignalMacrotask::new(callback, terminate, duration)),
-				None,
-			);
+			let macrotask = SignalMacrotask::new(callback, terminate, duration, queue.now());
+			queue.enqueue(Macrotask::Signal(macrotask), None);
 			AbortSignal::new_object(
 				cx,
 				Box::new(AbortSignal {
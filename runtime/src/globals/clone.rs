@@ -4,22 +4,24 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::any::Any;
 use std::ffi::c_void;
 use std::ptr;
 
-use bytes::{Bytes, BytesMut};
-use ion::class::Reflector;
+use bytes::BytesMut;
 use ion::clone::{read_uint64, write_uint64, StructuredCloneBuffer};
 use ion::flags::PropertyFlags;
 use ion::function::Opt;
-use ion::{ClassDefinition, Context, Local, Object, ResultExc, Value};
+use ion::{ClassDefinition, Context, Local, Object, ResultExc, ThrowException, TracedHeap, Value};
 use mozjs::jsapi::{
 	CloneDataPolicy, Handle, JSContext, JSObject, JSStructuredCloneCallbacks, JSStructuredCloneReader,
 	JSStructuredCloneWriter, JS_ReadBytes, JS_ReadString, JS_WriteBytes, JS_WriteString, JS_WriteUint32Pair,
-	StructuredCloneScope,
+	MutableHandleObject, StructuredCloneScope, TransferableOwnership,
 };
 
-use crate::globals::file::Blob;
+use crate::globals::file::{Blob, BlobData};
+use crate::globals::streams::readable::ReadableStream;
+use crate::globals::streams::writable::WritableStream;
 
 #[derive(Clone, Copy, Debug)]
 #[repr(u32)]
@@ -27,12 +29,20 @@ enum StructuredCloneTags {
 	Min = 0xFFFF8000,
 	BlobSameProcess = 0xFFFF8001,
 	BlobDifferentProcess = 0xFFFF8002,
+	ReadableStream = 0xFFFF8003,
+	WritableStream = 0xFFFF8004,
 	Max = 0xFFFFFFFF,
 }
 
 #[derive(Debug, Default)]
 pub struct StructuredCloneDataHolder {
-	blob_data: Vec<Bytes>,
+	blob_data: Vec<BlobData>,
+	transferred: Vec<TracedHeap<*mut JSObject>>,
+}
+
+unsafe fn data_holder<'a>(private: *mut c_void) -> &'a mut StructuredCloneDataHolder {
+	let data = unsafe { &mut *private.cast::<Option<Box<dyn Any>>>() };
+	data.as_mut().and_then(|data| data.downcast_mut()).unwrap()
 }
 
 unsafe extern "C" fn read_callback(
@@ -49,7 +59,7 @@ unsafe extern "C" fn read_callback(
 	);
 
 	let cx = unsafe { &Context::new_unchecked(cx) };
-	let data = unsafe { &mut *private.cast::<StructuredCloneDataHolder>() };
+	let data = unsafe { data_holder(private) };
 
 	if tag == StructuredCloneTags::BlobSameProcess as u32 {
 		let index;
@@ -62,11 +72,7 @@ unsafe extern "C" fn read_callback(
 
 		Blob::new_object(
 			cx,
-			Box::new(Blob {
-				reflector: Reflector::default(),
-				bytes: data.blob_data[index].clone(),
-				kind: Some(kind.to_owned(cx).unwrap()),
-			}),
+			Box::new(Blob::from_data(data.blob_data[index].clone(), Some(kind.to_owned(cx).unwrap()))),
 		)
 	} else if tag == StructuredCloneTags::BlobDifferentProcess as u32 {
 		let mut bytes;
@@ -82,11 +88,7 @@ unsafe extern "C" fn read_callback(
 
 		Blob::new_object(
 			cx,
-			Box::new(Blob {
-				reflector: Reflector::default(),
-				bytes: bytes.freeze(),
-				kind: Some(kind.to_owned(cx).unwrap()),
-			}),
+			Box::new(Blob::from_data(BlobData::from_bytes(bytes.freeze()), Some(kind.to_owned(cx).unwrap()))),
 		)
 	} else {
 		ptr::null_mut()
@@ -99,7 +101,7 @@ unsafe extern "C" fn write_callback(
 ) -> bool {
 	let cx = unsafe { &Context::new_unchecked(cx) };
 	let object = Object::from(unsafe { Local::from_raw_handle(obj) });
-	let data = unsafe { &mut *private.cast::<StructuredCloneDataHolder>() };
+	let data = unsafe { data_holder(private) };
 
 	if let Ok(blob) = Blob::get_private(cx, &object) {
 		let kind = ion::String::copy_from_str(cx, blob.kind.as_deref().unwrap_or("")).unwrap();
@@ -108,11 +110,18 @@ unsafe extern "C" fn write_callback(
 			if *same_process_scope {
 				JS_WriteUint32Pair(w, StructuredCloneTags::BlobSameProcess as u32, 0);
 				write_uint64(w, data.blob_data.len() as u64);
-				data.blob_data.push(blob.bytes.clone());
+				data.blob_data.push(blob.data.clone());
 			} else {
+				let bytes = match blob.data.read_sync() {
+					Ok(bytes) => bytes,
+					Err(error) => {
+						error.throw(cx);
+						return false;
+					}
+				};
 				JS_WriteUint32Pair(w, StructuredCloneTags::BlobDifferentProcess as u32, 0);
-				write_uint64(w, blob.bytes.len() as u64);
-				JS_WriteBytes(w, blob.bytes.as_ptr().cast(), blob.bytes.len());
+				write_uint64(w, bytes.len() as u64);
+				JS_WriteBytes(w, bytes.as_ptr().cast(), bytes.len());
 			}
 			JS_WriteString(w, kind.handle().into());
 		}
@@ -121,14 +130,95 @@ unsafe extern "C" fn write_callback(
 	true
 }
 
+unsafe extern "C" fn read_transfer_callback(
+	cx: *mut JSContext, _: *mut JSStructuredCloneReader, _: *const CloneDataPolicy, tag: u32, _: *mut c_void,
+	extra_data: u64, private: *mut c_void, return_object: MutableHandleObject,
+) -> bool {
+	let cx = unsafe { &Context::new_unchecked(cx) };
+	let data = unsafe { data_holder(private) };
+
+	let Some(handle) = data.transferred.get(extra_data as usize) else {
+		return false;
+	};
+	let handle = Object::from(handle.to_local());
+
+	let object = if tag == StructuredCloneTags::ReadableStream as u32 {
+		ReadableStream::from_transferred(cx, &handle)
+	} else if tag == StructuredCloneTags::WritableStream as u32 {
+		WritableStream::from_transferred(cx, &handle)
+	} else {
+		return false;
+	};
+
+	let mut return_object = Object::from(unsafe { Local::from_raw_handle_mut(return_object) });
+	return_object.handle_mut().set(object.handle().get());
+	true
+}
+
+unsafe extern "C" fn write_transfer_callback(
+	cx: *mut JSContext, obj: Handle<*mut JSObject>, private: *mut c_void, tag: *mut u32,
+	ownership: *mut TransferableOwnership, content: *mut *mut c_void, extra_data: *mut u64,
+) -> bool {
+	let cx = unsafe { &Context::new_unchecked(cx) };
+	let object = Object::from(unsafe { Local::from_raw_handle(obj) });
+	let data = unsafe { data_holder(private) };
+
+	let result = if let Ok(stream) = ReadableStream::get_mut_private(cx, &object) {
+		stream.get_reader(cx, Opt(None)).map(|reader| (StructuredCloneTags::ReadableStream, reader))
+	} else if let Ok(stream) = WritableStream::get_mut_private(cx, &object) {
+		stream.get_writer(cx).map(|writer| (StructuredCloneTags::WritableStream, writer))
+	} else {
+		return false;
+	};
+
+	match result {
+		Ok((kind, handle)) => {
+			unsafe {
+				*tag = kind as u32;
+				*ownership = TransferableOwnership::SCTAG_TMO_CUSTOM;
+				*content = ptr::null_mut();
+				*extra_data = data.transferred.len() as u64;
+			}
+			data.transferred.push(TracedHeap::new(handle.handle().get()));
+			true
+		}
+		Err(error) => {
+			error.throw(cx);
+			false
+		}
+	}
+}
+
+unsafe extern "C" fn can_transfer_callback(
+	cx: *mut JSContext, obj: Handle<*mut JSObject>, same_process_scope_required: *mut bool, _: *mut c_void,
+) -> bool {
+	let cx = unsafe { &Context::new_unchecked(cx) };
+	let object = Object::from(unsafe { Local::from_raw_handle(obj) });
+
+	let transferable = if let Ok(stream) = ReadableStream::get_private(cx, &object) {
+		!stream.get_locked()
+	} else if let Ok(stream) = WritableStream::get_private(cx, &object) {
+		!stream.get_locked()
+	} else {
+		false
+	};
+
+	if transferable {
+		unsafe {
+			*same_process_scope_required = true;
+		}
+	}
+	transferable
+}
+
 pub static STRUCTURED_CLONE_CALLBACKS: JSStructuredCloneCallbacks = JSStructuredCloneCallbacks {
 	read: Some(read_callback),
 	write: Some(write_callback),
 	reportError: None,
-	readTransfer: None,
-	writeTransfer: None,
+	readTransfer: Some(read_transfer_callback),
+	writeTransfer: Some(write_transfer_callback),
 	freeTransfer: None,
-	canTransfer: None,
+	canTransfer: Some(can_transfer_callback),
 	sabCloned: None,
 };
 
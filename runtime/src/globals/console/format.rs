@@ -13,7 +13,7 @@ use ion::{BigInt, Context, Local, Result, Value};
 use mozjs::conversions::ConversionBehavior;
 
 use crate::config::{Config, LogLevel};
-use crate::globals::console::INDENTS;
+use crate::globals::console::{print, INDENTS};
 
 pub(crate) enum FormatArg<'cx> {
 	String(String),
@@ -116,7 +116,7 @@ pub(crate) fn format_args<'cx>(cx: &'cx Context, args: &'cx [Value<'cx>]) -> Vec
 
 	inner(cx, args).unwrap_or_else(|error| {
 		if Config::global().log_level >= LogLevel::Warn {
-			eprintln!("{}", error.format());
+			print(cx, LogLevel::Warn, &error.format());
 		}
 		Vec::new()
 	})
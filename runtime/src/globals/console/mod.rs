@@ -5,12 +5,15 @@
  */
 
 mod format;
+mod sink;
 
 use std::cell::{Cell, RefCell};
 use std::collections::hash_map::{Entry, HashMap};
+use std::fmt::{Display, Write};
+use std::{fmt, process};
 
 use chrono::offset::Utc;
-use chrono::DateTime;
+use chrono::{DateTime, SecondsFormat};
 use indent::indent_all_by;
 use indexmap::IndexSet;
 use ion::conversions::FromValue;
@@ -19,21 +22,32 @@ use ion::format::key::format_key;
 use ion::format::primitive::format_primitive;
 use ion::format::{format_value, indent_str, Config as FormatConfig};
 use ion::function::{Opt, Rest};
+use ion::string::CompactString;
 use ion::{Context, Object, OwnedKey, Result, Stack, Value};
-use mozjs::jsapi::JSFunctionSpec;
+use mozjs::conversions::ConversionBehavior;
+use mozjs::jsapi::{JSFunctionSpec, JSPropertySpec};
 use term_table::row::Row;
 use term_table::table_cell::{Alignment, TableCell};
 use term_table::{Table, TableStyle};
+use terminal_size::{terminal_size, Width};
 
-use crate::cache::map::find_sourcemap;
+use crate::cache::map::SourceMapStore;
 use crate::config::{Config, LogLevel};
 use crate::globals::console::format::{format_args, format_value_args, FormatArg};
+use crate::globals::streams::writable::{StdoutSink, WritableStream};
+use crate::inspector::{notify, InspectorEvent};
+use crate::ContextExt;
+
+pub use sink::{ConsoleSink, StdioSink};
 
 const ANSI_CLEAR: &str = "\x1b[1;1H";
 const ANSI_CLEAR_SCREEN_DOWN: &str = "\x1b[0J";
 
 const DEFAULT_LABEL: &str = "default";
 
+/// Minimum width of the contents of a cell in `console.table`, below which cells are not truncated further.
+const MIN_CELL_WIDTH: usize = 8;
+
 thread_local! {
 	static COUNT_MAP: RefCell<HashMap<String, u32>> = RefCell::new(HashMap::new());
 	static TIMER_MAP: RefCell<HashMap<String, DateTime<Utc>>> = RefCell::new(HashMap::new());
@@ -41,56 +55,77 @@ thread_local! {
 	static INDENTS: Cell<u16> = const { Cell::new(0) };
 }
 
-fn log_args(cx: &Context, args: &[Value], log_level: LogLevel) {
-	if log_level == LogLevel::None || args.is_empty() {
-		return;
+pub(crate) fn print(cx: &Context, level: LogLevel, message: &str) {
+	notify(cx, || InspectorEvent::Console { level, message: String::from(message) });
+
+	let private = unsafe { cx.get_private() };
+	let prefixed;
+	let message: &str = match prefix(private.id, level) {
+		Some(prefix) => {
+			prefixed = format!("{prefix} {message}");
+			&prefixed
+		}
+		None => message,
+	};
+
+	match &private.console_sink {
+		Some(sink) => sink.write(level, message),
+		None => StdioSink.write(level, message),
 	}
+}
 
-	if args.len() == 1 {
-		print_args(format_value_args(cx, args.iter()), log_level);
-	} else {
-		print_args(format_args(cx, args).into_iter(), log_level);
+fn prefix(id: u32, level: LogLevel) -> Option<String> {
+	let config = Config::global();
+	let mut prefix = Vec::new();
+
+	if config.log_timestamps {
+		prefix.push(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true));
+	}
+	if config.log_level_tags {
+		prefix.push(format!("[{}]", level.tag()));
+	}
+	if config.log_ids {
+		prefix.push(format!("[{}:{id}]", process::id()));
 	}
+
+	(!prefix.is_empty()).then(|| prefix.join(" "))
 }
 
-fn print_args<'cx>(args: impl Iterator<Item = FormatArg<'cx>>, log_level: LogLevel) {
-	if log_level == LogLevel::None {
+fn log_args(cx: &Context, output: &mut String, args: &[Value]) {
+	if args.is_empty() {
 		return;
 	}
 
+	if args.len() == 1 {
+		write_args(output, format_value_args(cx, args.iter()));
+	} else {
+		write_args(output, format_args(cx, args).into_iter());
+	}
+}
+
+fn write_args<'cx>(output: &mut String, args: impl Iterator<Item = FormatArg<'cx>>) {
 	let mut first = true;
 
 	let mut prev_spaced = false;
 	for arg in args {
 		let spaced = arg.spaced();
-		let to_space = !first && (prev_spaced || spaced);
-		match log_level {
-			LogLevel::Info | LogLevel::Debug => {
-				if to_space {
-					print!(" ");
-				}
-				print!("{arg}");
-			}
-			LogLevel::Warn | LogLevel::Error => {
-				if to_space {
-					eprint!(" ");
-				}
-				eprint!("{arg}");
-			}
-			LogLevel::None => unreachable!(),
-		};
+		if !first && (prev_spaced || spaced) {
+			output.push(' ');
+		}
+		let _ = write!(output, "{arg}");
 		first = false;
 		prev_spaced = spaced;
 	}
 }
 
-fn print_indent(log_level: LogLevel) {
-	let indentation = usize::from(INDENTS.get());
-	match log_level {
-		LogLevel::Info | LogLevel::Debug => print!("{}", indent_str(indentation)),
-		LogLevel::Warn | LogLevel::Error => eprint!("{}", indent_str(indentation)),
-		LogLevel::None => {}
-	}
+fn indent() -> String {
+	indent_str(usize::from(INDENTS.get())).into_owned()
+}
+
+fn log_with_level(cx: &Context, level: LogLevel, values: &[Value]) {
+	let mut output = indent();
+	log_args(cx, &mut output, values);
+	print(cx, level, &output);
 }
 
 // TODO: Convert to Undefinable<String> as null is a valid label
@@ -105,36 +140,28 @@ fn get_label(label: Option<String>) -> String {
 #[js_fn]
 fn log(cx: &Context, Rest(values): Rest<Value>) {
 	if Config::global().log_level >= LogLevel::Info {
-		print_indent(LogLevel::Info);
-		log_args(cx, &values, LogLevel::Info);
-		println!();
+		log_with_level(cx, LogLevel::Info, &values);
 	}
 }
 
 #[js_fn]
 fn warn(cx: &Context, Rest(values): Rest<Value>) {
 	if Config::global().log_level >= LogLevel::Warn {
-		print_indent(LogLevel::Warn);
-		log_args(cx, &values, LogLevel::Warn);
-		println!();
+		log_with_level(cx, LogLevel::Warn, &values);
 	}
 }
 
 #[js_fn]
 fn error(cx: &Context, Rest(values): Rest<Value>) {
 	if Config::global().log_level >= LogLevel::Error {
-		print_indent(LogLevel::Error);
-		log_args(cx, &values, LogLevel::Error);
-		println!();
+		log_with_level(cx, LogLevel::Error, &values);
 	}
 }
 
 #[js_fn]
 fn debug(cx: &Context, Rest(values): Rest<Value>) {
 	if Config::global().log_level == LogLevel::Debug {
-		print_indent(LogLevel::Debug);
-		log_args(cx, &values, LogLevel::Debug);
-		println!();
+		log_with_level(cx, LogLevel::Debug, &values);
 	}
 }
 
@@ -146,62 +173,51 @@ fn assert(cx: &Context, Opt(assertion): Opt<bool>, Rest(values): Rest<Value>) {
 				return;
 			}
 
+			let mut output = indent();
 			if values.is_empty() {
-				print_indent(LogLevel::Error);
-				eprintln!("Assertion Failed");
-				return;
-			}
-
-			if values[0].handle().is_string() {
-				print_indent(LogLevel::Error);
-				eprint!(
+				output.push_str("Assertion Failed");
+			} else if values[0].handle().is_string() {
+				let _ = write!(
+					output,
 					"Assertion Failed: {} ",
 					format_primitive(cx, FormatConfig::default(), &values[0])
 				);
-				log_args(cx, &values[2..], LogLevel::Error);
-				eprintln!();
-				return;
+				log_args(cx, &mut output, &values[1..]);
+			} else {
+				output.push_str("Assertion Failed: ");
+				log_args(cx, &mut output, &values);
 			}
-
-			print_indent(LogLevel::Error);
-			eprint!("Assertion Failed: ");
-			log_args(cx, &values, LogLevel::Error);
-			println!();
+			print(cx, LogLevel::Error, &output);
 		} else {
-			eprintln!("Assertion Failed:");
+			print(cx, LogLevel::Error, "Assertion Failed:");
 		}
 	}
 }
 
 #[js_fn]
-fn clear() {
+fn clear(cx: &Context) {
 	INDENTS.set(0);
 
-	println!("{ANSI_CLEAR}");
-	println!("{ANSI_CLEAR_SCREEN_DOWN}");
+	print(cx, LogLevel::Info, ANSI_CLEAR);
+	print(cx, LogLevel::Info, ANSI_CLEAR_SCREEN_DOWN);
 }
 
 #[js_fn]
 fn trace(cx: &Context, Rest(values): Rest<Value>) {
 	if Config::global().log_level == LogLevel::Debug {
-		print_indent(LogLevel::Debug);
-		print!("Trace: ");
-		log_args(cx, &values, LogLevel::Debug);
-		println!();
+		let mut output = indent();
+		output.push_str("Trace: ");
+		log_args(cx, &mut output, &values);
+		print(cx, LogLevel::Debug, &output);
 
 		let mut stack = Stack::from_capture(cx);
 		let indents = ((INDENTS.get() + 1) * 2) as usize;
 
 		if let Some(stack) = &mut stack {
-			for record in &mut stack.records {
-				if let Some(sourcemap) = find_sourcemap(&record.location.file) {
-					record.transform_with_sourcemap(&sourcemap);
-				}
-			}
-
-			println!("{}", &indent_all_by(indents, stack.format()));
+			SourceMapStore::with(|store| store.transform_stack(stack));
+			print(cx, LogLevel::Debug, &indent_all_by(indents, stack.format()));
 		} else {
-			eprintln!("Current Stack could not be captured.");
+			print(cx, LogLevel::Error, "Current Stack could not be captured.");
 		}
 	}
 }
@@ -211,8 +227,9 @@ fn group(cx: &Context, Rest(values): Rest<Value>) {
 	INDENTS.set(INDENTS.get().min(u16::MAX - 1) + 1);
 
 	if Config::global().log_level >= LogLevel::Info {
-		log_args(cx, &values, LogLevel::Info);
-		println!();
+		let mut output = String::new();
+		log_args(cx, &mut output, &values);
+		print(cx, LogLevel::Info, &output);
 	}
 }
 
@@ -222,7 +239,7 @@ fn group_end() {
 }
 
 #[js_fn]
-fn count(Opt(label): Opt<String>) {
+fn count(cx: &Context, Opt(label): Opt<String>) {
 	let label = get_label(label);
 	COUNT_MAP.with_borrow_mut(|counts| {
 		let count = match counts.entry(label.clone()) {
@@ -230,14 +247,13 @@ fn count(Opt(label): Opt<String>) {
 			Entry::Occupied(mut o) => o.insert(o.get() + 1),
 		};
 		if Config::global().log_level >= LogLevel::Info {
-			print_indent(LogLevel::Info);
-			println!("{label}: {count}");
+			print(cx, LogLevel::Info, &format!("{}{label}: {count}", indent()));
 		}
 	});
 }
 
 #[js_fn]
-fn count_reset(Opt(label): Opt<String>) {
+fn count_reset(cx: &Context, Opt(label): Opt<String>) {
 	let label = get_label(label);
 	COUNT_MAP.with_borrow_mut(|counts| match counts.get_mut(&label) {
 		Some(count) => {
@@ -245,15 +261,18 @@ fn count_reset(Opt(label): Opt<String>) {
 		}
 		None => {
 			if Config::global().log_level >= LogLevel::Warn {
-				print_indent(LogLevel::Warn);
-				eprintln!("Count for {label} does not exist");
+				print(
+					cx,
+					LogLevel::Warn,
+					&format!("{}Count for {label} does not exist", indent()),
+				);
 			}
 		}
 	});
 }
 
 #[js_fn]
-fn time(Opt(label): Opt<String>) {
+fn time(cx: &Context, Opt(label): Opt<String>) {
 	let label = get_label(label);
 	TIMER_MAP.with_borrow_mut(|timers| match timers.entry(label.clone()) {
 		Entry::Vacant(v) => {
@@ -261,8 +280,7 @@ fn time(Opt(label): Opt<String>) {
 		}
 		Entry::Occupied(_) => {
 			if Config::global().log_level >= LogLevel::Warn {
-				print_indent(LogLevel::Warn);
-				eprintln!("Timer {label} already exists");
+				print(cx, LogLevel::Warn, &format!("{}Timer {label} already exists", indent()));
 			}
 		}
 	});
@@ -275,49 +293,113 @@ fn time_log(cx: &Context, Opt(label): Opt<String>, Rest(values): Rest<Value>) {
 		Some(start) => {
 			if Config::global().log_level >= LogLevel::Info {
 				let duration = Utc::now().timestamp_millis() - start.timestamp_millis();
-				print_indent(LogLevel::Info);
-				print!("{label}: {duration}ms ");
-				log_args(cx, &values, LogLevel::Info);
-				println!();
+				let mut output = format!("{}{label}: {duration}ms ", indent());
+				log_args(cx, &mut output, &values);
+				print(cx, LogLevel::Info, &output);
 			}
 		}
 		None => {
 			if Config::global().log_level >= LogLevel::Warn {
-				print_indent(LogLevel::Warn);
-				eprintln!("Timer {label} does not exist");
+				print(cx, LogLevel::Warn, &format!("{}Timer {label} does not exist", indent()));
 			}
 		}
 	});
 }
 
 #[js_fn]
-fn time_end(Opt(label): Opt<String>) {
+fn time_end(cx: &Context, Opt(label): Opt<String>) {
 	let label = get_label(label);
 	TIMER_MAP.with_borrow_mut(|timers| match timers.remove(&label) {
 		Some(start_time) => {
 			if Config::global().log_level >= LogLevel::Info {
 				let duration = Utc::now().timestamp_millis() - start_time.timestamp_millis();
-				print_indent(LogLevel::Info);
-				print!("{label}: {duration}ms - Timer Ended");
-				println!();
+				print(
+					cx,
+					LogLevel::Info,
+					&format!("{}{label}: {duration}ms - Timer Ended", indent()),
+				);
 			}
 		}
 		None => {
 			if Config::global().log_level >= LogLevel::Warn {
-				print_indent(LogLevel::Warn);
-				eprintln!("Timer {label} does not exist");
+				print(cx, LogLevel::Warn, &format!("{}Timer {label} does not exist", indent()));
 			}
 		}
 	});
 }
 
+#[derive(Clone, Copy, Debug, Default, FromValue)]
+struct TableOptions {
+	/// Maximum number of columns of properties, after which the remaining columns are elided.
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	max_columns: Option<u32>,
+	/// Maximum width of the table, which defaults to the width of the terminal.
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	max_width: Option<u32>,
+}
+
+/// Formats a cell, truncated to `width` visible characters and ending with an ellipsis.
+/// ANSI escape sequences are kept, so that colours are still reset.
+///
+/// Characters past the width are discarded as they are formatted, so that large values are not formatted into a string
+/// only to be truncated.
+fn truncate(content: &dyn Display, width: Option<usize>) -> String {
+	struct Truncated {
+		string: String,
+		width: usize,
+		visible: usize,
+		last: usize,
+		escape: bool,
+		truncated: bool,
+	}
+
+	impl Write for Truncated {
+		fn write_str(&mut self, string: &str) -> fmt::Result {
+			for char in string.chars() {
+				if self.escape || char == '\x1b' {
+					self.escape = !char.is_ascii_alphabetic();
+					self.string.push(char);
+				} else if self.visible < self.width {
+					self.last = self.string.len();
+					self.string.push(char);
+					self.visible += 1;
+				} else {
+					self.truncated = true;
+				}
+			}
+			Ok(())
+		}
+	}
+
+	let Some(width) = width else {
+		return content.to_string();
+	};
+
+	let mut truncated = Truncated {
+		string: String::new(),
+		width,
+		visible: 0,
+		last: 0,
+		escape: false,
+		truncated: false,
+	};
+	let _ = write!(truncated, "{content}");
+
+	let mut string = truncated.string;
+	if truncated.truncated {
+		string.remove(truncated.last);
+		string.push('…');
+	}
+	string
+}
+
 #[js_fn]
-fn table(cx: &Context, data: Value, Opt(columns): Opt<Vec<String>>) -> Result<()> {
+fn table(cx: &Context, data: Value, Opt(columns): Opt<Vec<String>>, Opt(options): Opt<TableOptions>) -> Result<()> {
 	fn sort_keys<'cx, I: IntoIterator<Item = Result<OwnedKey<'cx>>>>(
 		cx: &'cx Context, unsorted: I,
 	) -> Result<IndexSet<OwnedKey<'cx>>> {
 		let mut indexes = IndexSet::<i32>::new();
-		let mut headers = IndexSet::<String>::new();
+		let mut headers = IndexSet::<CompactString>::new();
 
 		for key in unsorted {
 			match key {
@@ -331,7 +413,7 @@ fn table(cx: &Context, data: Value, Opt(columns): Opt<Vec<String>>) -> Result<()
 		Ok(combine_keys(cx, indexes, headers))
 	}
 
-	fn combine_keys(_: &Context, indexes: IndexSet<i32>, headers: IndexSet<String>) -> IndexSet<OwnedKey> {
+	fn combine_keys(_: &Context, indexes: IndexSet<i32>, headers: IndexSet<CompactString>) -> IndexSet<OwnedKey> {
 		let mut indexes: Vec<i32> = indexes.into_iter().collect();
 		indexes.sort_unstable();
 
@@ -345,12 +427,12 @@ fn table(cx: &Context, data: Value, Opt(columns): Opt<Vec<String>>) -> Result<()
 		let rows = object.keys(cx, None).map(|key| key.to_owned_key(cx));
 		let mut has_values = false;
 
-		let (rows, columns) = if let Some(columns) = columns {
+		let (rows, mut columns) = if let Some(columns) = columns {
 			let mut keys = IndexSet::new();
 			for column in columns.into_iter() {
 				let key = match column.parse::<i32>() {
 					Ok(int) => OwnedKey::Int(int),
-					Err(_) => OwnedKey::String(column),
+					Err(_) => OwnedKey::String(CompactString::from(column)),
 				};
 				keys.insert(key);
 			}
@@ -377,14 +459,39 @@ fn table(cx: &Context, data: Value, Opt(columns): Opt<Vec<String>>) -> Result<()
 			(sort_keys(cx, rows)?, sort_keys(cx, keys.into_iter().map(Ok))?)
 		};
 
-		let mut headers = Vec::with_capacity(1 + columns.len() + 1);
-		headers.push(TableCell::builder("Indices").alignment(Alignment::Center).build());
+		let options = options.unwrap_or_default();
+		let elided = match options.max_columns {
+			Some(max_columns) if columns.len() > max_columns as usize => {
+				let elided = columns.len() - max_columns as usize;
+				columns.truncate(max_columns as usize);
+				elided
+			}
+			_ => 0,
+		};
+
+		let count = 1 + columns.len() + usize::from(has_values) + usize::from(elided > 0);
+		let width = options
+			.max_width
+			.map(|width| width as usize)
+			.or_else(|| terminal_size().map(|(Width(width), _)| usize::from(width)))
+			.map(|width| {
+				let width = width.saturating_sub(usize::from(indents) * 2);
+				(width.saturating_sub(3 * count + 1) / count).max(MIN_CELL_WIDTH)
+			});
+		let cell =
+			|content: &dyn Display| TableCell::builder(truncate(content, width)).alignment(Alignment::Center).build();
+		let value_config = FormatConfig::default().multiline(false).quoted(true).max_depth(1);
+
+		let mut headers = Vec::with_capacity(count);
+		headers.push(cell(&"Indices"));
 		for column in &columns {
-			let key = format_key(cx, FormatConfig::default(), column);
-			headers.push(TableCell::builder(key).alignment(Alignment::Center).build());
+			headers.push(cell(&format_key(cx, FormatConfig::default(), column)));
 		}
 		if has_values {
-			headers.push(TableCell::builder("Values").alignment(Alignment::Center).build());
+			headers.push(cell(&"Values"));
+		}
+		if elided > 0 {
+			headers.push(cell(&format_args!("… {elided} more")));
 		}
 
 		let mut table = Table::builder().style(TableStyle::thin()).rows(vec![Row::new(headers)]).build();
@@ -393,14 +500,13 @@ fn table(cx: &Context, data: Value, Opt(columns): Opt<Vec<String>>) -> Result<()
 			let value = object.get(cx, row)?.unwrap();
 			let key = format_key(cx, FormatConfig::default(), row);
 
-			let mut cells = Vec::with_capacity(1 + columns.len() + 1);
-			cells.push(TableCell::builder(key).alignment(Alignment::Center).build());
+			let mut cells = Vec::with_capacity(count);
+			cells.push(cell(&key));
 
 			if let Ok(object) = Object::from_value(cx, &value, true, ()) {
 				for column in &columns {
 					if let Some(value) = object.get(cx, column)? {
-						let value = format_value(cx, FormatConfig::default().multiline(false).quoted(true), &value);
-						cells.push(TableCell::builder(value).alignment(Alignment::Center).build());
+						cells.push(cell(&format_value(cx, value_config, &value)));
 					} else {
 						cells.push(TableCell::new(""));
 					}
@@ -411,26 +517,33 @@ fn table(cx: &Context, data: Value, Opt(columns): Opt<Vec<String>>) -> Result<()
 			} else {
 				cells.extend((0..columns.len()).map(|_| TableCell::new("")));
 				if has_values {
-					let value = format_value(cx, FormatConfig::default().multiline(false).quoted(true), &value);
-					cells.push(TableCell::builder(value).alignment(Alignment::Center).build());
+					cells.push(cell(&format_value(cx, value_config, &value)));
 				}
 			}
+			if elided > 0 {
+				cells.push(TableCell::new(""));
+			}
 
 			table.add_row(Row::new(cells));
 		}
 
-		println!("{}", indent_all_by((indents * 2) as usize, table.render()))
+		print(cx, LogLevel::Info, &indent_all_by((indents * 2) as usize, table.render()));
 	} else if Config::global().log_level >= LogLevel::Info {
-		print_indent(LogLevel::Info);
-		println!(
-			"{}",
-			format_value(cx, FormatConfig::default().indentation(indents), &data)
-		);
+		let value = format_value(cx, FormatConfig::default().indentation(indents), &data);
+		print(cx, LogLevel::Info, &format!("{}{value}", indent()));
 	}
 
 	Ok(())
 }
 
+/// Creates the writable stream for stdout when it is first accessed, and replaces the getter with it.
+#[js_fn]
+fn stdout<'cx>(cx: &'cx Context, #[ion(this)] this: &Object) -> Object<'cx> {
+	let stdout = WritableStream::from_native_sink(cx, StdoutSink);
+	this.define_as(cx, "stdout", &stdout, PropertyFlags::CONSTANT_ENUMERATED);
+	stdout
+}
+
 const METHODS: &[JSFunctionSpec] = &[
 	function_spec!(log, 0),
 	function_spec!(log, c"info", 0),
@@ -454,8 +567,10 @@ const METHODS: &[JSFunctionSpec] = &[
 	JSFunctionSpec::ZERO,
 ];
 
+const PROPERTIES: &[JSPropertySpec] = &[property_spec_getter!(stdout), JSPropertySpec::ZERO];
+
 pub fn define(cx: &Context, global: &Object) -> bool {
 	let console = Object::new(cx);
-	(unsafe { console.define_methods(cx, METHODS) })
+	(unsafe { console.define_methods(cx, METHODS) && console.define_properties(cx, PROPERTIES) })
 		&& global.define_as(cx, "console", &console, PropertyFlags::CONSTANT_ENUMERATED)
 }
@@ -0,0 +1,32 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use crate::config::LogLevel;
+
+/// Receives the output of the `console` global.
+///
+/// Each call receives one complete message, which may span multiple lines, without a trailing newline.
+pub trait ConsoleSink {
+	fn write(&self, level: LogLevel, message: &str);
+}
+
+/// Writes informational and debug messages to stdout, and warnings and errors to stderr.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StdioSink;
+
+impl ConsoleSink for StdioSink {
+	fn write(&self, level: LogLevel, message: &str) {
+		if level == LogLevel::None {
+			return;
+		}
+
+		if level.is_stdout() {
+			println!("{message}");
+		} else {
+			eprintln!("{message}");
+		}
+	}
+}
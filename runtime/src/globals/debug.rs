@@ -0,0 +1,37 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use ion::flags::PropertyFlags;
+use ion::{Context, Object};
+use mozjs::jsapi::JSFunctionSpec;
+
+use crate::resources::resource_usage;
+
+/// Returns the resources used by the runtime, with the heap sizes in bytes.
+#[js_fn]
+fn resource_usage_object<'cx>(cx: &'cx Context) -> Object<'cx> {
+	let usage = resource_usage(cx);
+
+	let object = Object::new(cx);
+	object.set_as(cx, "bytesFetched", &usage.bytes_fetched);
+	object.set_as(cx, "filesOpened", &usage.files_opened);
+	object.set_as(cx, "timersCreated", &usage.timers_created);
+	object.set_as(cx, "futuresPending", &(usage.futures_pending as u64));
+	object.set_as(cx, "heapBytes", &usage.heap_bytes);
+	object.set_as(cx, "peakHeapBytes", &usage.peak_heap_bytes);
+	object
+}
+
+const METHODS: &[JSFunctionSpec] = &[
+	function_spec!(resource_usage_object, c"resourceUsage", 0),
+	JSFunctionSpec::ZERO,
+];
+
+pub fn define(cx: &Context, global: &Object) -> bool {
+	let spiderfire = Object::new(cx);
+	(unsafe { spiderfire.define_methods(cx, METHODS) })
+		&& global.define_as(cx, "spiderfire", &spiderfire, PropertyFlags::CONSTANT)
+}
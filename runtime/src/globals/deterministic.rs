@@ -0,0 +1,91 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::env;
+use std::path::Path;
+use std::sync::Once;
+
+use ion::conversions::{FromValue, ToValue};
+use ion::flags::PropertyFlags;
+use ion::script::Script;
+use ion::{Context, Function, Object};
+use mozjs::jsapi::ResetTimeZone;
+
+use crate::ContextExt;
+
+/// Replaces `Date` with a proxy which constructs dates at the current time of the [Clock](crate::clock::Clock) of the
+/// runtime, when called without arguments. Other dates are constructed by the original `Date`.
+const DATE: &str = r#"(now => {
+	const NativeDate = Date;
+	const VirtualDate = new Proxy(NativeDate, {
+		apply() {
+			return new NativeDate(now()).toString();
+		},
+		construct(target, args, newTarget) {
+			return Reflect.construct(target, args.length === 0 ? [now()] : args, newTarget);
+		},
+	});
+	const define = (object, key, value) => {
+		Object.defineProperty(object, key, { value, writable: true, configurable: true });
+	};
+	define(NativeDate, "now", now);
+	define(NativeDate.prototype, "constructor", VirtualDate);
+	return VirtualDate;
+})"#;
+
+/// Returns the next number of the sequence seeded by [random_seed](crate::config::Config::random_seed), using
+/// SplitMix64.
+#[js_fn]
+fn random(cx: &Context) -> f64 {
+	let state = unsafe { &mut cx.get_private().random };
+	*state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+	let mut z = *state;
+	z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+	z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+	z ^= z >> 31;
+	(z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Pins the local time zone of `Date` and `Intl` to UTC, as it otherwise differs between machines.
+///
+/// The time zone is read from the environment of the process, so this applies to every runtime.
+fn pin_time_zone() {
+	static PINNED: Once = Once::new();
+	PINNED.call_once(|| {
+		env::set_var("TZ", "UTC");
+		unsafe { ResetTimeZone() };
+	});
+}
+
+#[js_fn]
+fn now(cx: &Context) -> f64 {
+	unsafe { cx.get_private().clock.since_epoch().as_millis() as f64 }
+}
+
+/// Replaces `Math.random` and `Date` with versions based on the state of the runtime, and pins the time zone to UTC,
+/// for deterministic mode.
+pub fn define(cx: &Context, global: &Object) -> bool {
+	pin_time_zone();
+
+	let Ok(Some(math)) = global.get_as::<Object>(cx, "Math", true, ()) else {
+		return false;
+	};
+	let flags = PropertyFlags::CONFIGURABLE | PropertyFlags::WRITABLE;
+	math.define_method(cx, "random", random, 0, flags);
+
+	let Ok(shim) = Script::compile_and_evaluate(cx, Path::new("deterministic.js"), DATE) else {
+		return false;
+	};
+	let Ok(shim) = Function::from_value(cx, &shim, true, ()) else {
+		return false;
+	};
+	let date_now = Function::new(cx, "now", Some(now), 0, PropertyFlags::empty());
+	match shim.call(cx, global, &[date_now.as_value(cx)]) {
+		Ok(date) => global.define(cx, "Date", &date, flags),
+		Err(_) => false,
+	}
+}
@@ -54,7 +54,7 @@ impl TextEncoder {
 	pub fn encode_into(&mut self, input: String, destination: Uint8Array) -> EncodeResult {
 		let (_, read, written, _) = self.encoder.encode_from_utf8(&input, unsafe { destination.as_mut_slice() }, true);
 		EncodeResult {
-			read: read as u64,
+			read: input[..read].encode_utf16().count() as u64,
 			written: written as u64,
 		}
 	}
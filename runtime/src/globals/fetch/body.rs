@@ -4,6 +4,7 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::error::Error as StdError;
 use std::fmt::{Display, Formatter};
 use std::pin::Pin;
 use std::task::Poll;
@@ -11,19 +12,24 @@ use std::{fmt, task};
 
 use bytes::Bytes;
 use form_urlencoded::Serializer;
+use futures::channel::mpsc::UnboundedSender;
 use http::header::CONTENT_TYPE;
 use http::{HeaderMap, HeaderValue};
+use http_body_util::combinators::BoxBody;
 use http_body_util::Full;
 use hyper::body::{Frame, Incoming, SizeHint};
 use ion::conversions::FromValue;
-use ion::{Context, Error, ErrorKind, Value};
-use mozjs::jsapi::Heap;
+use ion::{BoxedHeap, Context, Error, ErrorKind, Value};
 use mozjs::jsval::JSVal;
 use pin_project::pin_project;
 
 use crate::globals::file::{Blob, BufferSource};
 use crate::globals::url::URLSearchParams;
 
+const PROGRESS_CHUNK_SIZE: usize = 64 * 1024;
+
+pub type BoxError = Box<dyn StdError + Send + Sync>;
+
 #[derive(Debug, Clone, Traceable)]
 #[non_exhaustive]
 enum FetchBodyInner {
@@ -52,7 +58,7 @@ impl Display for FetchBodyKind {
 #[derive(Debug, Traceable)]
 pub struct FetchBody {
 	body: FetchBodyInner,
-	source: Option<Box<Heap<JSVal>>>,
+	source: Option<BoxedHeap<JSVal>>,
 	pub(crate) kind: Option<FetchBodyKind>,
 }
 
@@ -86,6 +92,18 @@ impl FetchBody {
 		}
 	}
 
+	/// Converts the body into a [Body] which sends the number of bytes sent so far to `progress`.
+	pub(crate) fn to_progress_body(&self, progress: UnboundedSender<u64>) -> Body {
+		match &self.body {
+			FetchBodyInner::None => Body::Empty,
+			FetchBodyInner::Bytes(bytes) => Body::Progress(ProgressBody {
+				bytes: bytes.clone(),
+				sent: 0,
+				progress,
+			}),
+		}
+	}
+
 	pub(crate) fn add_content_type_header(&self, headers: &mut HeaderMap) {
 		if let Some(kind) = &self.kind {
 			if !headers.contains_key(CONTENT_TYPE) {
@@ -99,7 +117,7 @@ impl Clone for FetchBody {
 	fn clone(&self) -> FetchBody {
 		FetchBody {
 			body: self.body.clone(),
-			source: self.source.as_ref().map(|s| Heap::boxed(s.get())),
+			source: self.source.as_ref().map(|s| BoxedHeap::new(s.get())),
 			kind: self.kind.clone(),
 		}
 	}
@@ -121,20 +139,20 @@ impl<'cx> FromValue<'cx> for FetchBody {
 		if value.handle().is_string() {
 			return Ok(FetchBody {
 				body: FetchBodyInner::Bytes(Bytes::from(String::from_value(cx, value, strict, ()).unwrap())),
-				source: Some(Heap::boxed(value.get())),
+				source: Some(BoxedHeap::new(value.get())),
 				kind: Some(FetchBodyKind::String),
 			});
 		} else if value.handle().is_object() {
 			if let Ok(source) = BufferSource::from_value(cx, value, strict, false) {
 				return Ok(FetchBody {
 					body: FetchBodyInner::Bytes(source.to_bytes()),
-					source: Some(Heap::boxed(value.get())),
+					source: Some(BoxedHeap::new(value.get())),
 					kind: None,
 				});
 			} else if let Ok(blob) = <&Blob>::from_value(cx, value, strict, ()) {
 				return Ok(FetchBody {
-					body: FetchBodyInner::Bytes(blob.bytes.clone()),
-					source: Some(Heap::boxed(value.get())),
+					body: FetchBodyInner::Bytes(blob.data.read_sync()?),
+					source: Some(BoxedHeap::new(value.get())),
 					kind: blob.kind.clone().map(FetchBodyKind::Blob),
 				});
 			} else if let Ok(search_params) = <&URLSearchParams>::from_value(cx, value, strict, ()) {
@@ -142,7 +160,7 @@ impl<'cx> FromValue<'cx> for FetchBody {
 					body: FetchBodyInner::Bytes(Bytes::from(
 						Serializer::new(String::new()).extend_pairs(search_params.pairs()).finish(),
 					)),
-					source: Some(Heap::boxed(value.get())),
+					source: Some(BoxedHeap::new(value.get())),
 					kind: Some(FetchBodyKind::URLSearchParams),
 				});
 			}
@@ -158,11 +176,21 @@ pub enum Body {
 	Empty,
 	Once(#[pin] Full<Bytes>),
 	Incoming(#[pin] Incoming),
+	Progress(ProgressBody),
+	/// Body streamed by a [FetchClient](crate::globals::fetch::FetchClient) other than hyper.
+	Stream(#[pin] BoxBody<Bytes, BoxError>),
+}
+
+impl Body {
+	/// Checks if the body is being received from a [FetchClient](crate::globals::fetch::FetchClient).
+	pub fn is_received(&self) -> bool {
+		matches!(self, Body::Incoming(_) | Body::Stream(_))
+	}
 }
 
 impl hyper::body::Body for Body {
 	type Data = Bytes;
-	type Error = hyper::Error;
+	type Error = BoxError;
 
 	fn poll_frame(
 		self: Pin<&mut Self>, cx: &mut task::Context<'_>,
@@ -170,7 +198,9 @@ impl hyper::body::Body for Body {
 		match self.project() {
 			BodyProject::Empty => Poll::Ready(None),
 			BodyProject::Once(full) => full.poll_frame(cx).map_err(|e| match e {}),
-			BodyProject::Incoming(incoming) => incoming.poll_frame(cx),
+			BodyProject::Incoming(incoming) => incoming.poll_frame(cx).map_err(BoxError::from),
+			BodyProject::Progress(progress) => Poll::Ready(progress.next_frame().map(Ok)),
+			BodyProject::Stream(stream) => stream.poll_frame(cx),
 		}
 	}
 
@@ -179,6 +209,8 @@ impl hyper::body::Body for Body {
 			Body::Empty => true,
 			Body::Once(full) => full.is_end_stream(),
 			Body::Incoming(incoming) => incoming.is_end_stream(),
+			Body::Progress(progress) => progress.sent == progress.bytes.len(),
+			Body::Stream(stream) => stream.is_end_stream(),
 		}
 	}
 
@@ -187,7 +219,30 @@ impl hyper::body::Body for Body {
 			Body::Empty => SizeHint::with_exact(0),
 			Body::Once(full) => full.size_hint(),
 			Body::Incoming(incoming) => incoming.size_hint(),
+			Body::Progress(progress) => SizeHint::with_exact((progress.bytes.len() - progress.sent) as u64),
+			Body::Stream(stream) => stream.size_hint(),
+		}
+	}
+}
+
+/// Body which is sent in chunks, reporting the number of bytes sent after each chunk.
+pub struct ProgressBody {
+	bytes: Bytes,
+	sent: usize,
+	progress: UnboundedSender<u64>,
+}
+
+impl ProgressBody {
+	fn next_frame(&mut self) -> Option<Frame<Bytes>> {
+		if self.sent == self.bytes.len() {
+			return None;
 		}
+
+		let end = self.bytes.len().min(self.sent + PROGRESS_CHUNK_SIZE);
+		let chunk = self.bytes.slice(self.sent..end);
+		self.sent = end;
+		let _ = self.progress.unbounded_send(self.sent as u64);
+		Some(Frame::data(chunk))
 	}
 }
 
@@ -0,0 +1,473 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::{create_dir_all, read, read_dir, remove_dir, remove_dir_all, remove_file, write};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::str;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+use headers::{Age, CacheControl, Date, Expires, HeaderMapExt, LastModified};
+use http::header::{
+	AGE, CONTENT_LENGTH, ETAG, EXPIRES, IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_RANGE, IF_UNMODIFIED_SINCE,
+	LAST_MODIFIED, VARY,
+};
+use http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
+use ion::{ClassDefinition, Context};
+use url::Url;
+
+use crate::cache::{hash, Cache};
+use crate::globals::fetch::header::HeadersKind;
+use crate::globals::fetch::request::RequestCache;
+use crate::globals::fetch::response::{Response, ResponseKind};
+use crate::globals::fetch::Headers;
+use crate::promise::run_blocking;
+
+/// Maximum size of a response body which is stored in the cache.
+pub(crate) const MAX_ENTRY_SIZE: u64 = 16 * 1024 * 1024;
+/// Maximum total size of the response bodies held in memory, after which the oldest responses are evicted.
+const MAX_MEMORY_SIZE: usize = 64 * 1024 * 1024;
+/// Maximum total size of the responses persisted to disk, after which the least recently written are evicted.
+const MAX_DISK_SIZE: u64 = 256 * 1024 * 1024;
+/// Fraction of the time since the response was last modified which is used as its heuristic freshness lifetime.
+const HEURISTIC_FRACTION: u32 = 10;
+
+/// Returns the cache mode after accounting for conditional headers set by the caller.
+pub(crate) fn cache_mode(mode: RequestCache, headers: &HeaderMap) -> RequestCache {
+	let conditional = [IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_UNMODIFIED_SINCE, IF_MATCH, IF_RANGE];
+	if mode == RequestCache::Default && conditional.iter().any(|name| headers.contains_key(name)) {
+		RequestCache::NoStore
+	} else {
+		mode
+	}
+}
+
+/// Returns whether a response can be stored.
+///
+/// See [RFC 9111 Section 3](https://httpwg.org/specs/rfc9111.html#response.cacheability).
+pub(crate) fn is_storable(method: &Method, request: &HeaderMap, status: StatusCode, response: &HeaderMap) -> bool {
+	if *method != Method::GET || status.is_informational() || matches!(status.as_u16(), 206 | 304) {
+		return false;
+	}
+
+	let no_store = |headers: &HeaderMap| headers.typed_get::<CacheControl>().is_some_and(|cc| cc.no_store());
+	if no_store(request) || no_store(response) || vary_names(response).is_none() {
+		return false;
+	}
+
+	let explicit = response.contains_key(EXPIRES)
+		|| response.typed_get::<CacheControl>().is_some_and(|cc| cc.max_age().is_some());
+	explicit || is_heuristically_cacheable(status)
+}
+
+fn is_heuristically_cacheable(status: StatusCode) -> bool {
+	matches!(status.as_u16(), 200 | 203 | 204 | 300 | 301 | 308 | 404 | 405 | 410 | 414 | 501)
+}
+
+/// Returns the names of the request headers listed in the `Vary` header, or [None] if it contains `*`.
+fn vary_names(headers: &HeaderMap) -> Option<Vec<HeaderName>> {
+	let mut names = Vec::new();
+	for value in headers.get_all(VARY) {
+		for name in value.to_str().ok()?.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+			if name == "*" {
+				return None;
+			}
+			names.push(HeaderName::from_bytes(name.as_bytes()).ok()?);
+		}
+	}
+	Some(names)
+}
+
+fn cache_key(url: &Url) -> String {
+	let mut url = url.clone();
+	url.set_fragment(None);
+	url.into()
+}
+
+/// Response stored in the [HttpCache].
+#[derive(Clone, Debug)]
+pub(crate) struct CachedResponse {
+	status: StatusCode,
+	headers: HeaderMap,
+	body: Bytes,
+	/// Values of the request headers nominated by the `Vary` header.
+	vary: Vec<(HeaderName, Option<HeaderValue>)>,
+	request_time: SystemTime,
+	response_time: SystemTime,
+}
+
+impl CachedResponse {
+	fn matches(&self, request: &HeaderMap) -> bool {
+		self.vary.iter().all(|(name, value)| request.get(name) == value.as_ref())
+	}
+
+	fn date(&self) -> SystemTime {
+		self.headers.typed_get::<Date>().map_or(self.response_time, SystemTime::from)
+	}
+
+	/// Calculates the freshness lifetime from the explicit expiration time, or heuristically from `Last-Modified`.
+	///
+	/// See [RFC 9111 Section 4.2.1](https://httpwg.org/specs/rfc9111.html#calculating.freshness.lifetime).
+	fn freshness_lifetime(&self) -> Duration {
+		if let Some(max_age) = self.headers.typed_get::<CacheControl>().and_then(|cc| cc.max_age()) {
+			return max_age;
+		}
+
+		let date = self.date();
+		if self.headers.contains_key(EXPIRES) {
+			let expires = self.headers.typed_get::<Expires>().map(SystemTime::from);
+			return expires.and_then(|expires| expires.duration_since(date).ok()).unwrap_or_default();
+		}
+
+		match self.headers.typed_get::<LastModified>() {
+			Some(modified) if is_heuristically_cacheable(self.status) => {
+				date.duration_since(SystemTime::from(modified)).unwrap_or_default() / HEURISTIC_FRACTION
+			}
+			_ => Duration::ZERO,
+		}
+	}
+
+	/// Calculates the current age of the response.
+	///
+	/// See [RFC 9111 Section 4.2.3](https://httpwg.org/specs/rfc9111.html#age.calculations).
+	fn age(&self, now: SystemTime) -> Duration {
+		let apparent_age = self.response_time.duration_since(self.date()).unwrap_or_default();
+		let response_delay = self.response_time.duration_since(self.request_time).unwrap_or_default();
+		let age = self.headers.typed_get::<Age>().map_or(Duration::ZERO, |age| Duration::from_secs(age.as_secs()));
+
+		let corrected_initial_age = apparent_age.max(age + response_delay);
+		corrected_initial_age + now.duration_since(self.response_time).unwrap_or_default()
+	}
+
+	/// Returns whether the response can be used without revalidation, taking into account the request directives.
+	pub(crate) fn is_fresh(&self, request: &HeaderMap) -> bool {
+		let response_directives = self.headers.typed_get::<CacheControl>().unwrap_or_else(CacheControl::new);
+		let request_directives = request.typed_get::<CacheControl>().unwrap_or_else(CacheControl::new);
+		if response_directives.no_cache() || request_directives.no_cache() {
+			return false;
+		}
+
+		let age = self.age(SystemTime::now());
+		if request_directives.max_age().is_some_and(|max_age| age > max_age) {
+			return false;
+		}
+
+		let lifetime = self.freshness_lifetime();
+		let age = age + request_directives.min_fresh().unwrap_or_default();
+		if lifetime > age {
+			return true;
+		}
+		!response_directives.must_revalidate()
+			&& request_directives.max_stale().is_some_and(|max_stale| age - lifetime <= max_stale)
+	}
+
+	/// Returns the conditional headers used to revalidate the response, which are empty if it has no validators.
+	pub(crate) fn validators(&self) -> HeaderMap {
+		let mut validators = HeaderMap::new();
+		if let Some(etag) = self.headers.get(ETAG) {
+			validators.insert(IF_NONE_MATCH, etag.clone());
+		} else if let Some(modified) = self.headers.get(LAST_MODIFIED) {
+			validators.insert(IF_MODIFIED_SINCE, modified.clone());
+		}
+		validators
+	}
+
+	/// Creates a [Response] for the stored response, with its current age.
+	pub(crate) fn to_response(&self, cx: &Context, url: Url) -> Response {
+		let mut response = Response::new_from_bytes(self.body.clone(), url);
+		response.kind = ResponseKind::default();
+		response.status = Some(self.status);
+		response.status_text = self.status.canonical_reason().map(String::from);
+
+		let mut headers = self.headers.clone();
+		let age = self.age(SystemTime::now()).as_secs();
+		headers.insert(AGE, HeaderValue::from(age));
+		let headers = Headers::from_map(headers, HeadersKind::Immutable);
+		response.headers.set(Headers::new_object(cx, Box::new(headers)));
+		response
+	}
+
+	fn encode(&self, key: &str) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		let millis = |time: SystemTime| time.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis().to_string();
+		for line in [
+			String::from(key),
+			self.status.as_u16().to_string(),
+			millis(self.request_time),
+			millis(self.response_time),
+		] {
+			bytes.extend_from_slice(line.as_bytes());
+			bytes.push(b'\n');
+		}
+
+		let vary = self.vary.iter().map(|(name, value)| ("vary", name, value.as_ref()));
+		let headers = self.headers.iter().map(|(name, value)| ("header", name, Some(value)));
+		for (tag, name, value) in vary.chain(headers) {
+			bytes.extend_from_slice(tag.as_bytes());
+			bytes.push(b' ');
+			bytes.extend_from_slice(name.as_str().as_bytes());
+			if let Some(value) = value {
+				bytes.push(b':');
+				bytes.extend_from_slice(value.as_bytes());
+			}
+			bytes.push(b'\n');
+		}
+
+		bytes.push(b'\n');
+		bytes.extend_from_slice(&self.body);
+		bytes
+	}
+
+	fn decode(bytes: &[u8]) -> Option<(String, CachedResponse)> {
+		let end = bytes.windows(2).position(|window| window == b"\n\n")?;
+		let mut lines = bytes[..end].split(|byte| *byte == b'\n');
+
+		let key = String::from(str::from_utf8(lines.next()?).ok()?);
+		let status = StatusCode::from_bytes(lines.next()?).ok()?;
+		let mut time = || {
+			let millis = str::from_utf8(lines.next()?).ok()?.parse().ok()?;
+			Some(UNIX_EPOCH + Duration::from_millis(millis))
+		};
+		let request_time = time()?;
+		let response_time = time()?;
+
+		let mut response = CachedResponse {
+			status,
+			headers: HeaderMap::new(),
+			body: Bytes::copy_from_slice(&bytes[end + 2..]),
+			vary: Vec::new(),
+			request_time,
+			response_time,
+		};
+		for line in lines {
+			let space = line.iter().position(|byte| *byte == b' ')?;
+			let (tag, field) = (&line[..space], &line[space + 1..]);
+			let (name, value) = match field.iter().position(|byte| *byte == b':') {
+				Some(colon) => (&field[..colon], Some(HeaderValue::from_bytes(&field[colon + 1..]).ok()?)),
+				None => (field, None),
+			};
+			let name = HeaderName::from_bytes(name).ok()?;
+			match (tag, value) {
+				(b"vary", value) => response.vary.push((name, value)),
+				(b"header", Some(value)) => {
+					response.headers.append(name, value);
+				}
+				_ => return None,
+			}
+		}
+		Some((key, response))
+	}
+}
+
+/// Private HTTP cache used by `fetch`, as defined in [RFC 9111](https://httpwg.org/specs/rfc9111.html).
+///
+/// Responses are held in memory, and optionally persisted to disk so they can be reused by later runs.
+/// Both are bounded in size, after which the oldest responses are evicted.
+#[derive(Clone, Default)]
+pub struct HttpCache {
+	entries: Rc<RefCell<HashMap<String, Vec<CachedResponse>>>>,
+	dir: Option<PathBuf>,
+}
+
+impl HttpCache {
+	pub fn new() -> HttpCache {
+		HttpCache::default()
+	}
+
+	/// Creates a cache which persists responses to the `fetch` folder of the runtime cache directory.
+	pub fn persistent() -> HttpCache {
+		let dir = Cache::new().map(|cache| cache.dir().join("fetch"));
+		HttpCache { dir, ..HttpCache::default() }
+	}
+
+	/// Persists responses to the given directory.
+	pub fn dir(mut self, dir: PathBuf) -> HttpCache {
+		self.dir = Some(dir);
+		self
+	}
+
+	/// Removes all stored responses, including those persisted to disk.
+	pub async fn clear(&self) {
+		self.entries.borrow_mut().clear();
+		if let Some(dir) = self.dir.clone() {
+			run_blocking(move |_| {
+				let _ = remove_dir_all(dir);
+			})
+			.await;
+		}
+	}
+
+	/// Returns the stored response for the URL which matches the request headers nominated by its `Vary` header.
+	pub(crate) async fn lookup(&self, url: &Url, request: &HeaderMap) -> Option<CachedResponse> {
+		let key = cache_key(url);
+		self.load(&key).await;
+		let entries = self.entries.borrow();
+		entries.get(&key)?.iter().find(|entry| entry.matches(request)).cloned()
+	}
+
+	/// Stores the response, replacing any stored response for the same request headers.
+	pub(crate) async fn store(
+		&self, url: &Url, request: &HeaderMap, status: StatusCode, headers: HeaderMap, body: Bytes,
+		request_time: SystemTime,
+	) {
+		let Some(names) = vary_names(&headers) else {
+			return;
+		};
+		let vary = names
+			.into_iter()
+			.map(|name| {
+				let value = request.get(&name).cloned();
+				(name, value)
+			})
+			.collect();
+		let response = CachedResponse {
+			status,
+			headers,
+			body,
+			vary,
+			request_time,
+			response_time: SystemTime::now(),
+		};
+		self.insert(cache_key(url), response).await;
+	}
+
+	/// Updates the stored response with the headers of a `304 Not Modified` response to its revalidation.
+	pub(crate) async fn update(
+		&self, url: &Url, request: &HeaderMap, headers: &HeaderMap, request_time: SystemTime,
+	) -> Option<CachedResponse> {
+		let mut response = self.lookup(url, request).await?;
+		for name in headers.keys().filter(|name| **name != CONTENT_LENGTH) {
+			response.headers.remove(name);
+		}
+		for (name, value) in headers.iter().filter(|(name, _)| **name != CONTENT_LENGTH) {
+			response.headers.append(name, value.clone());
+		}
+		response.request_time = request_time;
+		response.response_time = SystemTime::now();
+
+		self.insert(cache_key(url), response.clone()).await;
+		Some(response)
+	}
+
+	/// Removes all stored responses for the URL, after it has been modified by an unsafe request.
+	pub(crate) async fn invalidate(&self, url: &Url) {
+		let key = cache_key(url);
+		self.entries.borrow_mut().remove(&key);
+		if let Some(dir) = &self.dir {
+			let folder = dir.join(hash(&key, Some(32)));
+			run_blocking(move |_| {
+				let _ = remove_dir_all(folder);
+			})
+			.await;
+		}
+	}
+
+	async fn insert(&self, key: String, response: CachedResponse) {
+		self.load(&key).await;
+		if let Some(dir) = self.dir.clone() {
+			let folder = dir.join(hash(&key, Some(32)));
+			let variant = response.vary.iter().map(|(name, value)| {
+				let value = value.as_ref().map_or(&b""[..], HeaderValue::as_bytes);
+				[name.as_str().as_bytes(), b":", value, b"\n"].concat()
+			});
+			let variant = hash(variant.collect::<Vec<_>>().concat(), Some(32));
+			let bytes = response.encode(&key);
+			run_blocking(move |_| {
+				if create_dir_all(&folder).and_then(|_| write(folder.join(variant), bytes)).is_ok() {
+					evict_files(&dir, MAX_DISK_SIZE);
+				}
+			})
+			.await;
+		}
+
+		let mut entries = self.entries.borrow_mut();
+		let variants = entries.entry(key).or_default();
+		variants.retain(|entry| entry.vary != response.vary);
+		variants.push(response);
+
+		let mut size: usize = entries.values().flatten().map(|entry| entry.body.len()).sum();
+		while size > MAX_MEMORY_SIZE {
+			let oldest = entries
+				.iter()
+				.flat_map(|(key, variants)| variants.iter().enumerate().map(move |(index, entry)| (key, index, entry)))
+				.min_by_key(|(_, _, entry)| entry.response_time)
+				.map(|(key, index, _)| (key.clone(), index));
+			let Some((key, index)) = oldest else {
+				break;
+			};
+			let variants = entries.get_mut(&key).unwrap();
+			size -= variants.remove(index).body.len();
+			if variants.is_empty() {
+				entries.remove(&key);
+			}
+		}
+	}
+
+	/// Loads the responses for the key persisted to disk, if they are not already in memory.
+	async fn load(&self, key: &str) {
+		let Some(dir) = &self.dir else {
+			return;
+		};
+		if self.entries.borrow().contains_key(key) {
+			return;
+		}
+
+		let folder = dir.join(hash(key, Some(32)));
+		let stored = String::from(key);
+		let variants: Vec<_> = run_blocking(move |_| {
+			let Ok(files) = read_dir(folder) else {
+				return Vec::new();
+			};
+			files
+				.filter_map(|file| read(file.ok()?.path()).ok())
+				.filter_map(|bytes| CachedResponse::decode(&bytes))
+				.filter(|(key, _)| *key == stored)
+				.map(|(_, response)| response)
+				.collect()
+		})
+		.await;
+		if !variants.is_empty() {
+			self.entries.borrow_mut().entry(String::from(key)).or_insert(variants);
+		}
+	}
+}
+
+/// Removes the oldest responses persisted to the directory until their total size is within the bound.
+fn evict_files(dir: &Path, max_size: u64) {
+	let Ok(folders) = read_dir(dir) else {
+		return;
+	};
+	let mut files: Vec<_> = folders
+		.filter_map(|folder| read_dir(folder.ok()?.path()).ok())
+		.flatten()
+		.filter_map(|file| {
+			let file = file.ok()?;
+			let metadata = file.metadata().ok()?;
+			Some((metadata.modified().unwrap_or(UNIX_EPOCH), metadata.len(), file.path()))
+		})
+		.collect();
+
+	let mut size: u64 = files.iter().map(|(_, len, _)| len).sum();
+	if size <= max_size {
+		return;
+	}
+
+	files.sort_unstable_by_key(|(modified, _, _)| *modified);
+	for (_, len, path) in files {
+		if size <= max_size {
+			break;
+		}
+		if remove_file(&path).is_ok() {
+			size -= len;
+			if let Some(folder) = path.parent() {
+				let _ = remove_dir(folder);
+			}
+		}
+	}
+}
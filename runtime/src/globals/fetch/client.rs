@@ -4,22 +4,90 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use std::sync::OnceLock;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
 use std::time::Duration;
 
-use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use hyper_rustls::HttpsConnectorBuilder;
 use hyper_util::client::legacy;
 use hyper_util::client::legacy::connect::HttpConnector;
 use hyper_util::rt::TokioExecutor;
+use ion::{Context, Error};
 
 use crate::globals::fetch::body::Body;
+use crate::globals::fetch::dns::Resolver;
+use crate::globals::fetch::timeout::SharedTimeouts;
+use crate::globals::fetch::timing::{TcpConnector, TimedConnector};
+use crate::ContextExt;
 
-pub type Client = legacy::Client<HttpsConnector<HttpConnector>, Body>;
+/// Delay before connecting with the other address family, when a host has both IPv4 and IPv6 addresses.
+const HAPPY_EYEBALLS_TIMEOUT: Duration = Duration::from_millis(250);
 
-pub static GLOBAL_CLIENT: OnceLock<Client> = OnceLock::new();
+/// Future which resolves to the response to a request, or the error which prevented it from being received.
+pub type ClientFuture = Pin<Box<dyn Future<Output = Result<hyper::Response<Body>, Error>>>>;
 
-pub fn default_client() -> Client {
-	let https = HttpsConnectorBuilder::new().with_webpki_roots().https_or_http().enable_http1().build();
+/// Transport which sends the requests made by `fetch` over the network.
+///
+/// The default client is a [HyperClient], but embedders can replace it with another HTTP stack or a mock transport
+/// with [RuntimeBuilder::fetch_client](crate::RuntimeBuilder::fetch_client). Response bodies which are not received
+/// from hyper can be returned as [Body::Once] or [Body::Stream].
+pub trait FetchClient {
+	/// Sends a request, resolving once the response head has been received.
+	fn send(&self, request: hyper::Request<Body>) -> ClientFuture;
+}
+
+pub type HyperClient = legacy::Client<TimedConnector, Body>;
+
+impl FetchClient for HyperClient {
+	fn send(&self, request: hyper::Request<Body>) -> ClientFuture {
+		let response = self.request(request);
+		Box::pin(async move {
+			match response.await {
+				Ok(response) => Ok(response.map(Body::Incoming)),
+				Err(error) => Err(Error::new(transport_error_message(&error), None)),
+			}
+		})
+	}
+}
+
+/// Returns the client of the runtime, creating the default client if none was configured.
+///
+/// The default client connects within the [connect timeout](crate::globals::fetch::FetchTimeouts::connect) of the
+/// runtime.
+pub(crate) fn runtime_client(cx: &Context) -> Rc<dyn FetchClient> {
+	let private = unsafe { cx.get_private() };
+	let timeouts = Arc::clone(&private.fetch_timeouts);
+	let client = private.fetch_client.get_or_insert_with(|| {
+		let client = build_client(Resolver::default(), timeouts);
+		Rc::new(client)
+	});
+	Rc::clone(client)
+}
+
+pub fn default_client() -> HyperClient {
+	client_with_resolver(Resolver::default())
+}
+
+/// Creates a client which resolves hosts with `resolver`.
+///
+/// Connections race the IPv6 and IPv4 addresses of a host, as described by
+/// [Happy Eyeballs](https://datatracker.ietf.org/doc/html/rfc8305).
+pub fn client_with_resolver(resolver: Resolver) -> HyperClient {
+	build_client(resolver, SharedTimeouts::default())
+}
+
+fn build_client(resolver: Resolver, timeouts: SharedTimeouts) -> HyperClient {
+	let mut http = HttpConnector::new_with_resolver(resolver);
+	http.enforce_http(false);
+	http.set_happy_eyeballs_timeout(Some(HAPPY_EYEBALLS_TIMEOUT));
+
+	let https = HttpsConnectorBuilder::new()
+		.with_webpki_roots()
+		.https_or_http()
+		.enable_http1()
+		.wrap_connector(TcpConnector::new(http, timeouts));
 
 	let mut client = legacy::Client::builder(TokioExecutor::default());
 
@@ -28,5 +96,17 @@ pub fn default_client() -> Client {
 	client.retry_canceled_requests(true);
 	client.set_host(false);
 
-	client.build(https)
+	client.build(TimedConnector::new(https))
+}
+
+/// Describes an error of the hyper client with its sources, which hold the details of connection errors.
+fn transport_error_message(error: &(dyn std::error::Error + 'static)) -> String {
+	let mut message = error.to_string();
+	let mut source = error.source();
+	while let Some(error) = source {
+		message.push_str(": ");
+		message.push_str(&error.to_string());
+		source = error.source();
+	}
+	message
 }
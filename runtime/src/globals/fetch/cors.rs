@@ -0,0 +1,273 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashMap;
+use std::str;
+use std::time::{Duration, Instant};
+
+use http::header::{
+	ACCEPT, ACCEPT_LANGUAGE, ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS,
+	ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE,
+	ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD, AUTHORIZATION, CACHE_CONTROL, CONTENT_LANGUAGE,
+	CONTENT_LENGTH, CONTENT_TYPE, EXPIRES, LAST_MODIFIED, ORIGIN, PRAGMA, USER_AGENT,
+};
+use http::{HeaderMap, HeaderName, HeaderValue, Method};
+use ion::Context;
+use uri_url::url_to_uri;
+use url::{Origin, Url};
+
+use crate::globals::fetch::header::{header_list, remove_all_header_entries};
+use crate::globals::fetch::request::RequestCredentials;
+use crate::globals::fetch::{Body, FetchClient, Request, DEFAULT_USER_AGENT};
+use crate::ContextExt;
+
+/// Duration for which the result of a preflight is cached when the response has no `Access-Control-Max-Age` header.
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(5);
+/// Maximum duration for which the result of a preflight is cached.
+const MAX_MAX_AGE: Duration = Duration::from_secs(7200);
+/// Maximum number of preflight results which are cached, after which the result expiring soonest is evicted.
+const MAX_ENTRIES: usize = 256;
+
+static SAFELISTED_RESPONSE_HEADERS: [HeaderName; 7] =
+	[CACHE_CONTROL, CONTENT_LANGUAGE, CONTENT_LENGTH, CONTENT_TYPE, EXPIRES, LAST_MODIFIED, PRAGMA];
+
+/// Checks if a request is made to a different origin than the origin of the runtime.
+pub(crate) fn is_cross_origin(origin: &Origin, url: &Url) -> bool {
+	url.origin() != *origin
+}
+
+/// Checks if a request was redirected from a cross-origin URL to a URL of another origin.
+///
+/// See [Fetch Section 3.1.5](https://fetch.spec.whatwg.org/#concept-request-tainted-origin).
+fn is_redirect_tainted(origin: &Origin, locations: &[Url]) -> bool {
+	locations.windows(2).any(|urls| {
+		let (last, current) = (urls[0].origin(), urls[1].origin());
+		current != last && *origin != last
+	})
+}
+
+/// Serializes the origin of a request, which is `null` if the request is redirect-tainted.
+pub(crate) fn serialize_origin(origin: &Origin, locations: &[Url]) -> String {
+	if is_redirect_tainted(origin, locations) {
+		String::from("null")
+	} else {
+		origin.ascii_serialization()
+	}
+}
+
+fn is_safelisted_method(method: &Method) -> bool {
+	matches!(*method, Method::GET | Method::HEAD | Method::POST)
+}
+
+/// Checks if a byte is a CORS-unsafe request-header byte.
+fn is_unsafe_byte(byte: u8) -> bool {
+	(byte < 0x20 && byte != b'\t') || b"\"():<>?@[\\]{}\x7F".contains(&byte)
+}
+
+/// Checks if a request header can be sent to another origin without a preflight.
+fn is_safelisted_request_header(name: &HeaderName, value: &HeaderValue) -> bool {
+	let bytes = value.as_bytes();
+	if bytes.len() > 128 {
+		return false;
+	}
+
+	if name == ACCEPT {
+		!bytes.iter().copied().any(is_unsafe_byte)
+	} else if name == ACCEPT_LANGUAGE || name == CONTENT_LANGUAGE {
+		bytes.iter().all(|byte| byte.is_ascii_alphanumeric() || b" *,-.;=".contains(byte))
+	} else if name == CONTENT_TYPE {
+		if bytes.iter().copied().any(is_unsafe_byte) {
+			return false;
+		}
+		let essence = bytes.split(|byte| *byte == b';').next().unwrap_or_default();
+		let essence = str::from_utf8(essence).unwrap_or_default().trim().to_ascii_lowercase();
+		matches!(
+			essence.as_str(),
+			"application/x-www-form-urlencoded" | "multipart/form-data" | "text/plain"
+		)
+	} else {
+		false
+	}
+}
+
+/// Returns the sorted names of the headers of a request which are not CORS-safelisted.
+fn unsafe_request_header_names(headers: &HeaderMap) -> Vec<HeaderName> {
+	let mut names: Vec<_> = headers
+		.iter()
+		.filter(|(name, value)| !is_safelisted_request_header(name, value))
+		.map(|(name, _)| name.clone())
+		.collect();
+	names.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+	names.dedup();
+	names
+}
+
+/// Checks if a cross-origin request must be preceded by a preflight, as it uses a method or headers which could not
+/// be sent by a form.
+pub(crate) fn requires_preflight(method: &Method, headers: &HeaderMap) -> bool {
+	!is_safelisted_method(method) || !unsafe_request_header_names(headers).is_empty()
+}
+
+/// Splits the values of a header into a list of trimmed, non-empty items.
+/// Returns [None] if any value is not valid UTF-8.
+fn list_values(headers: &HeaderMap, name: &HeaderName) -> Option<Vec<String>> {
+	let mut items = Vec::new();
+	for value in headers.get_all(name) {
+		let value = value.to_str().ok()?;
+		items.extend(value.split(',').map(str::trim).filter(|item| !item.is_empty()).map(String::from));
+	}
+	Some(items)
+}
+
+/// Performs a CORS check, which succeeds if the response allows the [serialized origin](serialize_origin) to read it.
+pub(crate) fn cors_check(origin: &str, credentials: RequestCredentials, headers: &HeaderMap) -> bool {
+	let mut allowed = headers.get_all(ACCESS_CONTROL_ALLOW_ORIGIN).iter();
+	let allowed = match (allowed.next(), allowed.next()) {
+		(Some(allowed), None) => allowed,
+		_ => return false,
+	};
+
+	let include = credentials == RequestCredentials::Include;
+	if allowed == "*" && !include {
+		return true;
+	}
+	if allowed.as_bytes() != origin.as_bytes() {
+		return false;
+	}
+	!include || headers.get(ACCESS_CONTROL_ALLOW_CREDENTIALS).is_some_and(|value| value == "true")
+}
+
+/// Removes the headers of a cross-origin response which are neither CORS-safelisted nor exposed by
+/// `Access-Control-Expose-Headers`.
+pub(crate) fn filter_response_headers(headers: &mut HeaderMap, credentials: RequestCredentials) {
+	let exposed = list_values(headers, &ACCESS_CONTROL_EXPOSE_HEADERS).unwrap_or_default();
+	if credentials != RequestCredentials::Include && exposed.iter().any(|name| name == "*") {
+		return;
+	}
+
+	let exposed: Vec<_> = exposed.iter().filter_map(|name| HeaderName::from_bytes(name.as_bytes()).ok()).collect();
+	let removed: Vec<_> = headers
+		.keys()
+		.filter(|name| !SAFELISTED_RESPONSE_HEADERS.contains(name) && !exposed.contains(name))
+		.cloned()
+		.collect();
+	for name in removed {
+		remove_all_header_entries(headers, &name);
+	}
+}
+
+#[derive(Debug)]
+struct PreflightEntry {
+	methods: Vec<Method>,
+	headers: Vec<HeaderName>,
+	any_method: bool,
+	any_header: bool,
+	expires: Instant,
+}
+
+impl PreflightEntry {
+	fn allows(&self, method: &Method, headers: &[HeaderName]) -> bool {
+		let method = is_safelisted_method(method) || self.any_method || self.methods.contains(method);
+		method
+			&& headers
+				.iter()
+				.all(|name| (self.any_header && name != AUTHORIZATION) || self.headers.contains(name))
+	}
+}
+
+/// Results of successful CORS preflights, keyed by the URL of the request and whether it included credentials.
+#[derive(Debug, Default)]
+pub(crate) struct PreflightCache {
+	entries: HashMap<(String, bool), PreflightEntry>,
+}
+
+impl PreflightCache {
+	fn allows(&mut self, url: &Url, include: bool, method: &Method, headers: &[HeaderName]) -> bool {
+		let key = (String::from(url.as_str()), include);
+		match self.entries.get(&key) {
+			Some(entry) if entry.expires > Instant::now() => entry.allows(method, headers),
+			Some(_) => {
+				self.entries.remove(&key);
+				false
+			}
+			None => false,
+		}
+	}
+
+	fn insert(&mut self, url: &Url, include: bool, entry: PreflightEntry) {
+		let now = Instant::now();
+		self.entries.retain(|_, entry| entry.expires > now);
+		if self.entries.len() >= MAX_ENTRIES {
+			let soonest = self.entries.iter().min_by_key(|(_, entry)| entry.expires).map(|(key, _)| key.clone());
+			if let Some(key) = soonest {
+				self.entries.remove(&key);
+			}
+		}
+		self.entries.insert((String::from(url.as_str()), include), entry);
+	}
+}
+
+/// Sends a CORS preflight for a request, unless the result of a previous preflight allows it.
+/// Returns whether the request is allowed to be sent.
+pub(crate) async fn preflight(cx: &Context, request: &Request, client: &dyn FetchClient, origin: &Origin) -> bool {
+	let include = request.credentials == RequestCredentials::Include;
+	let origin = serialize_origin(origin, &request.locations);
+	let names = unsafe_request_header_names(&header_list(cx, &request.headers).borrow());
+	let cache = unsafe { &mut cx.get_private().fetch_preflight };
+	if cache.allows(&request.url, include, &request.method, &names) {
+		return true;
+	}
+
+	let Ok(uri) = url_to_uri(&request.url) else {
+		return false;
+	};
+	let mut headers = HeaderMap::new();
+	headers.append(ACCEPT, HeaderValue::from_static("*/*"));
+	headers.append(ORIGIN, HeaderValue::from_str(&origin).unwrap());
+	headers.append(ACCESS_CONTROL_REQUEST_METHOD, HeaderValue::from_str(request.method.as_str()).unwrap());
+	if !names.is_empty() {
+		let names: Vec<_> = names.iter().map(HeaderName::as_str).collect();
+		headers.append(ACCESS_CONTROL_REQUEST_HEADERS, HeaderValue::from_str(&names.join(",")).unwrap());
+	}
+	headers.append(USER_AGENT, HeaderValue::from_static(DEFAULT_USER_AGENT));
+
+	let mut preflight = hyper::Request::builder().method(Method::OPTIONS).uri(uri).body(Body::Empty).unwrap();
+	*preflight.headers_mut() = headers;
+
+	let Ok(response) = client.send(preflight).await else {
+		return false;
+	};
+	let headers = response.headers();
+	if !response.status().is_success() || !cors_check(&origin, request.credentials, headers) {
+		return false;
+	}
+
+	let (Some(methods), Some(header_names)) = (
+		list_values(headers, &ACCESS_CONTROL_ALLOW_METHODS),
+		list_values(headers, &ACCESS_CONTROL_ALLOW_HEADERS),
+	) else {
+		return false;
+	};
+	let max_age = headers
+		.get(ACCESS_CONTROL_MAX_AGE)
+		.and_then(|max_age| max_age.to_str().ok()?.parse().ok())
+		.map_or(DEFAULT_MAX_AGE, |max_age| Duration::from_secs(max_age).min(MAX_MAX_AGE));
+
+	let entry = PreflightEntry {
+		methods: methods.iter().filter_map(|method| Method::from_bytes(method.as_bytes()).ok()).collect(),
+		headers: header_names.iter().filter_map(|name| HeaderName::from_bytes(name.as_bytes()).ok()).collect(),
+		any_method: !include && methods.iter().any(|method| method == "*"),
+		any_header: !include && header_names.iter().any(|name| name == "*"),
+		expires: Instant::now() + max_age,
+	};
+	if !entry.allows(&request.method, &names) {
+		return false;
+	}
+
+	let cache = unsafe { &mut cx.get_private().fetch_preflight };
+	cache.insert(&request.url, include, entry);
+	true
+}
@@ -0,0 +1,283 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::{Duration, Instant};
+use std::{fs, io, task, vec};
+
+use futures::future::join;
+use futures::TryFutureExt;
+use hyper_util::client::legacy::connect::dns::{GaiResolver, Name};
+use tokio::net::UdpSocket;
+use tokio::sync::OnceCell;
+use tokio::task::spawn_blocking;
+use tokio::time::timeout;
+use tower_service::Service;
+use uuid::Uuid;
+
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+/// Maximum time to wait for the nameserver to return the records of a host, to find their TTL.
+const QUERY_TIMEOUT: Duration = Duration::from_millis(250);
+
+const DNS_PORT: u16 = 53;
+const TYPE_A: u16 = 1;
+const TYPE_AAAA: u16 = 28;
+const CLASS_IN: u16 = 1;
+
+tokio::task_local! {
+	/// Time taken to resolve the host of the connection being established by the current task.
+	static LOOKUP: Cell<Option<Duration>>;
+}
+
+/// Runs a future which establishes a connection, and returns its output with the time taken to resolve the host, if
+/// it was resolved by a [Resolver].
+///
+/// The time is recorded for the task establishing the connection, so concurrent connections to the same host do not
+/// observe the lookups of each other.
+pub(crate) async fn with_lookup_timing<F: Future>(future: F) -> (F::Output, Option<Duration>) {
+	LOOKUP
+		.scope(Cell::new(None), async {
+			let output = future.await;
+			(output, LOOKUP.with(Cell::get))
+		})
+		.await
+}
+
+#[derive(Debug)]
+struct CachedAddrs {
+	addrs: Vec<SocketAddr>,
+	expires: Instant,
+}
+
+/// DNS resolver used by the fetch client.
+///
+/// Hosts can be overridden to resolve to fixed addresses, which is useful for pointing a domain at a local server.
+/// Other hosts are resolved with the system resolver, and the addresses are cached for the TTL of their DNS records,
+/// which are queried from the nameserver at the same time.
+#[derive(Clone, Debug)]
+pub struct Resolver {
+	overrides: Arc<HashMap<String, Vec<SocketAddr>>>,
+	cache: Arc<Mutex<HashMap<String, CachedAddrs>>>,
+	nameserver: Option<SocketAddr>,
+	ttl: Duration,
+}
+
+impl Resolver {
+	pub fn new() -> Resolver {
+		Resolver::default()
+	}
+
+	/// Resolves `host` to the given addresses instead of querying the system resolver.
+	pub fn override_host(mut self, host: &str, addrs: &[IpAddr]) -> Resolver {
+		let addrs = addrs.iter().map(|addr| SocketAddr::new(*addr, 0)).collect();
+		Arc::make_mut(&mut self.overrides).insert(host.to_ascii_lowercase(), addrs);
+		self
+	}
+
+	/// Queries `nameserver` for the TTL of records, instead of the first nameserver in `/etc/resolv.conf`.
+	pub fn nameserver(mut self, nameserver: SocketAddr) -> Resolver {
+		self.nameserver = Some(nameserver);
+		self
+	}
+
+	/// Sets the maximum time resolved addresses are cached for, which is also used when the TTL of their records
+	/// cannot be queried. A TTL of zero disables caching.
+	pub fn ttl(mut self, ttl: Duration) -> Resolver {
+		self.ttl = ttl;
+		self
+	}
+
+	/// Removes all cached addresses.
+	pub fn clear_cache(&self) {
+		self.cache.lock().unwrap().clear();
+	}
+
+	async fn resolve(self, name: Name) -> io::Result<Vec<SocketAddr>> {
+		let host = name.as_str().to_ascii_lowercase();
+		let start = Instant::now();
+		let addrs = self.lookup(&host, name).await?;
+		let _ = LOOKUP.try_with(|lookup| lookup.set(Some(start.elapsed())));
+		Ok(addrs)
+	}
+
+	async fn lookup(&self, host: &str, name: Name) -> io::Result<Vec<SocketAddr>> {
+		if let Some(addrs) = self.overrides.get(host) {
+			return Ok(addrs.clone());
+		}
+
+		{
+			let mut cache = self.cache.lock().unwrap();
+			let now = Instant::now();
+			cache.retain(|_, cached| cached.expires > now);
+			if let Some(cached) = cache.get(host) {
+				return Ok(cached.addrs.clone());
+			}
+		}
+
+		if self.ttl.is_zero() {
+			return Ok(GaiResolver::new().call(name).await?.collect());
+		}
+
+		let (addrs, record_ttl) = join(GaiResolver::new().call(name), self.record_ttl(host)).await;
+		let addrs: Vec<_> = addrs?.collect();
+		let ttl = record_ttl.map_or(self.ttl, |ttl| ttl.min(self.ttl));
+		if !ttl.is_zero() {
+			let cached = CachedAddrs {
+				addrs: addrs.clone(),
+				expires: Instant::now() + ttl,
+			};
+			self.cache.lock().unwrap().insert(String::from(host), cached);
+		}
+		Ok(addrs)
+	}
+
+	/// Returns the lowest TTL of the records of `host`, or `None` if the nameserver could not be queried in time.
+	async fn record_ttl(&self, host: &str) -> Option<Duration> {
+		let nameserver = match self.nameserver {
+			Some(nameserver) => nameserver,
+			None => system_nameserver().await?,
+		};
+		timeout(QUERY_TIMEOUT, query_ttl(nameserver, host)).await.ok()?.ok()?
+	}
+}
+
+impl Default for Resolver {
+	fn default() -> Resolver {
+		Resolver {
+			overrides: Arc::default(),
+			cache: Arc::default(),
+			nameserver: None,
+			ttl: DEFAULT_TTL,
+		}
+	}
+}
+
+impl Service<Name> for Resolver {
+	type Response = vec::IntoIter<SocketAddr>;
+	type Error = io::Error;
+	type Future = Pin<Box<dyn Future<Output = io::Result<vec::IntoIter<SocketAddr>>> + Send>>;
+
+	fn poll_ready(&mut self, _: &mut task::Context<'_>) -> Poll<io::Result<()>> {
+		Poll::Ready(Ok(()))
+	}
+
+	fn call(&mut self, name: Name) -> Self::Future {
+		Box::pin(self.clone().resolve(name).map_ok(Vec::into_iter))
+	}
+}
+
+/// Returns the first nameserver in `/etc/resolv.conf`, which is only read once.
+async fn system_nameserver() -> Option<SocketAddr> {
+	static NAMESERVER: OnceCell<Option<SocketAddr>> = OnceCell::const_new();
+	let read = || async {
+		let nameserver = spawn_blocking(|| {
+			let conf = fs::read_to_string("/etc/resolv.conf").ok()?;
+			conf.lines().find_map(|line| {
+				let mut words = line.split_whitespace();
+				let address = words.next().filter(|word| *word == "nameserver").and(words.next())?;
+				address.parse().ok().map(|ip: IpAddr| SocketAddr::new(ip, DNS_PORT))
+			})
+		});
+		nameserver.await.ok().flatten()
+	};
+	*NAMESERVER.get_or_init(read).await
+}
+
+/// Queries the A and AAAA records of `host`, and returns the lowest TTL of their answers.
+async fn query_ttl(nameserver: SocketAddr, host: &str) -> io::Result<Option<Duration>> {
+	let local = match nameserver {
+		SocketAddr::V4(_) => SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+		SocketAddr::V6(_) => SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0),
+	};
+	let socket = UdpSocket::bind(local).await?;
+	socket.connect(nameserver).await?;
+
+	let random = Uuid::new_v4();
+	let id = u16::from_be_bytes([random.as_bytes()[0], random.as_bytes()[1]]);
+	let mut pending = vec![id, id.wrapping_add(1)];
+	for (id, record_type) in pending.iter().zip([TYPE_A, TYPE_AAAA]) {
+		let query = encode_query(*id, host, record_type).ok_or(io::ErrorKind::InvalidInput)?;
+		socket.send(&query).await?;
+	}
+
+	let mut ttl = None;
+	let mut buffer = [0; 512];
+	while !pending.is_empty() {
+		let read = socket.recv(&mut buffer).await?;
+		if let Some((id, answer_ttl)) = decode_response(&buffer[..read]) {
+			if let Some(index) = pending.iter().position(|pending| *pending == id) {
+				pending.swap_remove(index);
+				ttl = match (ttl, answer_ttl) {
+					(Some(ttl), Some(answer_ttl)) => Some(ttl.min(answer_ttl)),
+					(ttl, answer_ttl) => ttl.or(answer_ttl),
+				};
+			}
+		}
+	}
+	Ok(ttl)
+}
+
+fn encode_query(id: u16, host: &str, record_type: u16) -> Option<Vec<u8>> {
+	let mut query = Vec::with_capacity(host.len() + 18);
+	query.extend_from_slice(&id.to_be_bytes());
+	// Recursion is desired for the single question.
+	query.extend_from_slice(&[0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+	for label in host.trim_end_matches('.').split('.') {
+		if label.is_empty() || label.len() > 63 {
+			return None;
+		}
+		query.push(label.len() as u8);
+		query.extend_from_slice(label.as_bytes());
+	}
+	query.push(0);
+	query.extend_from_slice(&record_type.to_be_bytes());
+	query.extend_from_slice(&CLASS_IN.to_be_bytes());
+	Some(query)
+}
+
+/// Returns the ID of a response and the lowest TTL of its answers, or `None` if the response is malformed.
+fn decode_response(response: &[u8]) -> Option<(u16, Option<Duration>)> {
+	let header = response.get(..12)?;
+	if header[2] & 0x80 == 0 {
+		return None;
+	}
+	let id = u16::from_be_bytes([header[0], header[1]]);
+	let questions = u16::from_be_bytes([header[4], header[5]]);
+	let answers = u16::from_be_bytes([header[6], header[7]]);
+
+	let mut offset = 12;
+	for _ in 0..questions {
+		offset = skip_name(response, offset)? + 4;
+	}
+
+	let mut ttl: Option<u32> = None;
+	for _ in 0..answers {
+		offset = skip_name(response, offset)?;
+		let record = response.get(offset..offset + 10)?;
+		let record_ttl = u32::from_be_bytes([record[4], record[5], record[6], record[7]]);
+		offset += 10 + usize::from(u16::from_be_bytes([record[8], record[9]]));
+		ttl = Some(ttl.map_or(record_ttl, |ttl| ttl.min(record_ttl)));
+	}
+	Some((id, ttl.map(|ttl| Duration::from_secs(u64::from(ttl)))))
+}
+
+/// Returns the offset after the name at `offset`, which ends with a pointer if it is compressed.
+fn skip_name(message: &[u8], mut offset: usize) -> Option<usize> {
+	loop {
+		match *message.get(offset)? {
+			0 => return Some(offset + 1),
+			length if length & 0xC0 == 0xC0 => return Some(offset + 2),
+			length => offset += usize::from(length) + 1,
+		}
+	}
+}
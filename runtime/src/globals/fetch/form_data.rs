@@ -0,0 +1,159 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use ion::class::Reflector;
+use ion::conversions::{FromValue, ToValue};
+use ion::function::Opt;
+use ion::symbol::WellKnownSymbolCode;
+use ion::{BoxedHeap, ClassDefinition, Context, JSIterator, Object, Result, Value};
+use mozjs::jsapi::JSObject;
+
+use crate::globals::fetch::multipart::FormValue;
+use crate::globals::file::{Blob, File};
+
+#[derive(Traceable)]
+pub enum FormDataEntryValue {
+	String(String),
+	File(BoxedHeap<*mut JSObject>),
+}
+
+impl FormDataEntryValue {
+	/// Converts a value to an entry, wrapping blobs in a [File] named `filename`, or `"blob"` if none is given.
+	fn from_value(cx: &Context, value: &Value, filename: Option<String>) -> Result<FormDataEntryValue> {
+		if value.handle().is_object() {
+			let object = value.to_object(cx);
+			if let Ok(blob) = Blob::get_private(cx, &object) {
+				if filename.is_none() && File::instance_of(cx, &object) {
+					return Ok(FormDataEntryValue::File(BoxedHeap::new(object.handle().get())));
+				}
+
+				let name = filename.unwrap_or_else(|| String::from("blob"));
+				let file = File::from_data(blob.data.clone(), blob.kind.clone(), name);
+				return Ok(FormDataEntryValue::File(BoxedHeap::new(File::new_object(
+					cx,
+					Box::new(file),
+				))));
+			}
+		}
+		Ok(FormDataEntryValue::String(String::from_value(cx, value, false, ())?))
+	}
+
+	pub(crate) fn from_form_value(cx: &Context, value: FormValue) -> FormDataEntryValue {
+		match value {
+			FormValue::Text(text) => FormDataEntryValue::String(text),
+			FormValue::File { data, kind, name } => {
+				let file = File::from_data(data, kind, name);
+				FormDataEntryValue::File(BoxedHeap::new(File::new_object(cx, Box::new(file))))
+			}
+		}
+	}
+}
+
+impl<'cx> ToValue<'cx> for FormDataEntryValue {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		match self {
+			FormDataEntryValue::String(string) => string.to_value(cx, value),
+			FormDataEntryValue::File(file) => file.get().to_value(cx, value),
+		}
+	}
+}
+
+#[derive(Traceable)]
+pub struct FormDataEntry {
+	pub(crate) name: String,
+	pub(crate) value: FormDataEntryValue,
+}
+
+#[js_class]
+pub struct FormData {
+	reflector: Reflector,
+	pub(crate) entries: Vec<FormDataEntry>,
+}
+
+#[js_class]
+impl FormData {
+	#[ion(constructor)]
+	pub fn constructor() -> FormData {
+		FormData {
+			reflector: Reflector::default(),
+			entries: Vec::new(),
+		}
+	}
+
+	pub fn append(&mut self, cx: &Context, name: String, value: Value, Opt(filename): Opt<String>) -> Result<()> {
+		let value = FormDataEntryValue::from_value(cx, &value, filename)?;
+		self.entries.push(FormDataEntry { name, value });
+		Ok(())
+	}
+
+	pub fn delete(&mut self, name: String) {
+		self.entries.retain(|entry| entry.name != name);
+	}
+
+	pub fn get<'cx>(&self, cx: &'cx Context, name: String) -> Option<Value<'cx>> {
+		self.entries.iter().find(|entry| entry.name == name).map(|entry| entry.value.as_value(cx))
+	}
+
+	#[ion(name = "getAll")]
+	pub fn get_all<'cx>(&self, cx: &'cx Context, name: String) -> Vec<Value<'cx>> {
+		self.entries.iter().filter(|entry| entry.name == name).map(|entry| entry.value.as_value(cx)).collect()
+	}
+
+	pub fn has(&self, name: String) -> bool {
+		self.entries.iter().any(|entry| entry.name == name)
+	}
+
+	pub fn set(&mut self, cx: &Context, name: String, value: Value, Opt(filename): Opt<String>) -> Result<()> {
+		let value = FormDataEntryValue::from_value(cx, &value, filename)?;
+		match self.entries.iter().position(|entry| entry.name == name) {
+			Some(index) => {
+				self.entries[index].value = value;
+				let mut i = 0;
+				self.entries.retain(|entry| {
+					i += 1;
+					i <= index + 1 || entry.name != name
+				});
+			}
+			None => self.entries.push(FormDataEntry { name, value }),
+		}
+		Ok(())
+	}
+
+	#[ion(name = WellKnownSymbolCode::Iterator)]
+	pub fn iterator(cx: &Context, #[ion(this)] this: &Object) -> ion::Iterator {
+		let thisv = this.as_value(cx);
+		ion::Iterator::new(FormDataIterator::default(), &thisv)
+	}
+}
+
+impl FormData {
+	/// Creates a `FormData` object with the entries of a parsed body.
+	pub(crate) fn from_form_values<'cx>(cx: &'cx Context, entries: Vec<(String, FormValue)>) -> Result<Object<'cx>> {
+		// The form data is rooted before the files are created, so that they are traced through its entries.
+		let object = Object::from(cx.root(FormData::new_object(cx, Box::new(FormData::constructor()))));
+		let form_data = FormData::get_mut_private(cx, &object)?;
+		for (name, value) in entries {
+			let value = FormDataEntryValue::from_form_value(cx, value);
+			form_data.entries.push(FormDataEntry { name, value });
+		}
+		Ok(object)
+	}
+}
+
+#[derive(Default)]
+pub struct FormDataIterator(usize);
+
+impl JSIterator for FormDataIterator {
+	fn next_value<'cx>(&mut self, cx: &'cx Context, private: &Value<'cx>) -> Option<Value<'cx>> {
+		let object = private.to_object(cx);
+		let form_data = FormData::get_private(cx, &object).unwrap();
+		let entry = form_data.entries.get(self.0);
+		entry.map(move |entry| {
+			self.0 += 1;
+			[entry.name.as_value(cx), entry.value.as_value(cx)].as_value(cx)
+		})
+	}
+}
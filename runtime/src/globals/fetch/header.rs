@@ -6,10 +6,13 @@
 
 #![allow(clippy::declare_interior_mutable_const)]
 
+use std::cell::RefCell;
 use std::cmp::Ordering;
-use std::fmt::{Display, Formatter};
+use std::fmt::{Debug, Display, Formatter};
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
 use std::str::FromStr;
-use std::{fmt, str, vec};
+use std::{fmt, str};
 
 use http::header::{
 	Entry, HeaderMap, HeaderName, HeaderValue, ACCEPT, ACCEPT_CHARSET, ACCEPT_ENCODING, ACCEPT_LANGUAGE,
@@ -22,8 +25,9 @@ use ion::conversions::{FromValue, ToValue};
 use ion::function::Opt;
 use ion::string::byte::{ByteString, VisibleAscii};
 use ion::symbol::WellKnownSymbolCode;
-use ion::{Array, ClassDefinition, Context, Error, ErrorKind, JSIterator, Object, OwnedKey, Result, Value};
+use ion::{Array, Context, Error, ErrorKind, Function, JSIterator, Local, Object, OwnedKey, Result, ResultExc, Value};
 use mime::{Mime, APPLICATION, FORM_DATA, MULTIPART, PLAIN, TEXT, WWW_FORM_URLENCODED};
+use mozjs::jsapi::{Heap, JSObject};
 
 #[derive(FromValue)]
 pub enum Header {
@@ -74,13 +78,13 @@ impl ToValue<'_> for HeaderEntry {
 	}
 }
 
-pub struct HeadersObject(HeaderMap);
+pub struct HeadersObject(HeaderList);
 
 impl<'cx> FromValue<'cx> for HeadersObject {
 	type Config = ();
 	fn from_value(cx: &'cx Context, value: &Value, _: bool, _: ()) -> Result<HeadersObject> {
 		let object = Object::from_value(cx, value, true, ())?;
-		let mut headers = HeaderMap::new();
+		let mut headers = HeaderList::default();
 		append_to_headers(cx, &mut headers, object)?;
 		Ok(HeadersObject(headers))
 	}
@@ -100,37 +104,111 @@ pub enum HeadersInit<'cx> {
 }
 
 impl HeadersInit<'_> {
-	pub(crate) fn into_headers(self, mut headers: HeaderMap, kind: HeadersKind) -> Result<Headers> {
+	pub(crate) fn into_headers(self, headers: HeaderMap, kind: HeadersKind) -> Result<Headers> {
+		let mut headers = HeaderList::from(headers);
 		match self {
 			HeadersInit::Existing(existing) => {
-				headers.extend(existing.headers.iter().map(|(name, value)| (name.clone(), value.clone())));
-				Ok(Headers {
-					reflector: Reflector::default(),
-					headers,
-					kind,
-				})
+				let existing = existing.headers.borrow();
+				for (name, original) in existing.ordered() {
+					headers.record(original.as_bytes(), name);
+					for value in existing.get_all(name) {
+						headers.map.append(name.clone(), value.clone());
+					}
+				}
+				Ok(Headers::from_list(headers, kind))
 			}
 			HeadersInit::Array(vec) => Headers::from_array(vec, headers, kind),
 			HeadersInit::Object(object) => {
-				let mut name = None;
-				for (nm, value) in object.0 {
-					if let nm @ Some(_) = nm {
-						name = nm;
+				for (name, original) in object.0.ordered() {
+					for value in object.0.get_all(name) {
+						append_header(&mut headers, original.as_bytes(), name.clone(), value.clone(), kind)?;
 					}
-					append_header(&mut headers, name.clone().unwrap(), value, kind)?;
 				}
-				Ok(Headers {
-					reflector: Reflector::default(),
-					headers,
-					kind,
-				})
+				Ok(Headers::from_list(headers, kind))
+			}
+			HeadersInit::Empty => Ok(Headers::from_list(headers, kind)),
+		}
+	}
+}
+
+/// List of headers which preserves the casing and order in which each header name was first added, for display.
+///
+/// Headers are compared case-insensitively through the underlying [HeaderMap], which is also used for iteration in
+/// JavaScript, as required by the Fetch Standard.
+#[derive(Clone, Default)]
+pub struct HeaderList {
+	map: HeaderMap,
+	names: Vec<Box<str>>,
+	version: u64,
+}
+
+impl HeaderList {
+	/// Records the original casing of a header name, unless the header is already present.
+	/// If the header was previously removed, it is moved to the end of the order.
+	fn record(&mut self, original: &[u8], name: &HeaderName) {
+		if self.map.contains_key(name) {
+			return;
+		}
+		self.names.retain(|recorded| !recorded.as_bytes().eq_ignore_ascii_case(original));
+		self.names.push(Box::from(str::from_utf8(original).unwrap_or(name.as_str())));
+	}
+
+	/// Returns each header name present in the list, along with its original casing, in the order they were added.
+	/// Headers added directly to the [HeaderMap] are ordered after the others, and are displayed in lowercase.
+	pub fn ordered(&self) -> Vec<(&HeaderName, &str)> {
+		let mut ordered = Vec::with_capacity(self.map.keys_len());
+		for original in &self.names {
+			if let Some(name) = self.map.keys().find(|name| original.eq_ignore_ascii_case(name.as_str())) {
+				ordered.push((name, &**original));
+			}
+		}
+		for name in self.map.keys() {
+			if !self.names.iter().any(|original| original.eq_ignore_ascii_case(name.as_str())) {
+				ordered.push((name, name.as_str()));
 			}
-			HeadersInit::Empty => Ok(Headers {
-				reflector: Reflector::default(),
-				headers,
-				kind,
-			}),
 		}
+		ordered
+	}
+}
+
+impl From<HeaderMap> for HeaderList {
+	fn from(map: HeaderMap) -> HeaderList {
+		HeaderList { map, ..HeaderList::default() }
+	}
+}
+
+impl Deref for HeaderList {
+	type Target = HeaderMap;
+
+	fn deref(&self) -> &HeaderMap {
+		&self.map
+	}
+}
+
+impl DerefMut for HeaderList {
+	fn deref_mut(&mut self) -> &mut HeaderMap {
+		self.version += 1;
+		&mut self.map
+	}
+}
+
+impl Debug for HeaderList {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let entries = self.ordered().into_iter().flat_map(|(name, original)| {
+			self.map.get_all(name).iter().map(move |value| (original, value))
+		});
+		f.debug_map().entries(entries).finish()
+	}
+}
+
+impl Display for HeaderList {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		for (name, original) in self.ordered() {
+			for value in self.map.get_all(name) {
+				writeln!(f, "{original}: {}", value_to_string(value))?;
+			}
+		}
+		Ok(())
 	}
 }
 
@@ -149,7 +227,7 @@ pub enum HeadersKind {
 pub struct Headers {
 	pub(crate) reflector: Reflector,
 	#[trace(no_trace)]
-	pub(crate) headers: HeaderMap,
+	pub(crate) headers: Rc<RefCell<HeaderList>>,
 	#[trace(no_trace)]
 	pub(crate) kind: HeadersKind,
 }
@@ -159,17 +237,25 @@ impl Headers {
 		Headers { kind, ..Headers::default() }
 	}
 
-	pub fn from_array(vec: Vec<HeaderEntry>, mut headers: HeaderMap, kind: HeadersKind) -> Result<Headers> {
+	pub fn from_map(headers: HeaderMap, kind: HeadersKind) -> Headers {
+		Headers::from_list(HeaderList::from(headers), kind)
+	}
+
+	pub fn from_list(headers: HeaderList, kind: HeadersKind) -> Headers {
+		Headers {
+			reflector: Reflector::default(),
+			headers: Rc::new(RefCell::new(headers)),
+			kind,
+		}
+	}
+
+	pub fn from_array(vec: Vec<HeaderEntry>, mut headers: HeaderList, kind: HeadersKind) -> Result<Headers> {
 		for entry in vec {
 			let name = HeaderName::from_bytes(&entry.name)?;
 			let value = HeaderValue::from_bytes(&entry.value)?;
-			append_header(&mut headers, name, value, kind)?;
+			append_header(&mut headers, &entry.name, name, value, kind)?;
 		}
-		Ok(Headers {
-			reflector: Reflector::default(),
-			headers,
-			kind,
-		})
+		Ok(Headers::from_list(headers, kind))
 	}
 }
 
@@ -182,9 +268,11 @@ impl Headers {
 
 	pub fn append(&mut self, name: ByteString<VisibleAscii>, value: ByteString<VisibleAscii>) -> Result<()> {
 		if self.kind != HeadersKind::Immutable {
-			let name = HeaderName::from_bytes(&name)?;
+			let header = HeaderName::from_bytes(&name)?;
 			let value = HeaderValue::from_bytes(&value)?;
-			self.headers.append(name, value);
+			let mut headers = self.headers.borrow_mut();
+			headers.record(&name, &header);
+			headers.append(header, value);
 			Ok(())
 		} else {
 			Err(Error::new("Cannot Modify Readonly Headers", None))
@@ -204,88 +292,183 @@ impl Headers {
 			return Ok(());
 		}
 
-		remove_all_header_entries(&mut self.headers, &name);
-		remove_privileged_no_cors_headers(&mut self.headers, self.kind);
+		let mut headers = self.headers.borrow_mut();
+		remove_all_header_entries(&mut headers, &name);
+		remove_privileged_no_cors_headers(&mut headers, self.kind);
 		Ok(())
 	}
 
 	pub fn get(&self, name: ByteString<VisibleAscii>) -> Result<Option<Header>> {
 		let name = HeaderName::from_bytes(&name)?;
-		Ok(get_header(&self.headers, &name))
+		Ok(get_header(&self.headers.borrow(), &name))
 	}
 
+	#[ion(name = "getSetCookie")]
 	pub fn get_set_cookie(&self) -> Vec<String> {
-		let header = get_header(&self.headers, &SET_COOKIE);
-		header.map_or_else(Vec::new, |header| match header {
-			Header::Multiple(vec) => vec,
-			Header::Single(str) => vec![str],
-		})
+		self.headers.borrow().get_all(&SET_COOKIE).iter().map(value_to_string).collect()
 	}
 
 	pub fn has(&self, name: ByteString<VisibleAscii>) -> Result<bool> {
 		let name = HeaderName::from_bytes(&name)?;
-		Ok(self.headers.contains_key(name))
+		Ok(self.headers.borrow().contains_key(name))
 	}
 
 	pub fn set(&mut self, name: ByteString<VisibleAscii>, value: ByteString<VisibleAscii>) -> Result<()> {
-		let name = HeaderName::from_bytes(&name)?;
+		let header = HeaderName::from_bytes(&name)?;
 		let value = HeaderValue::from_bytes(&value)?;
-		if !validate_header(&name, &HeaderValue::from_static(""), self.kind)? {
+		if !validate_header(&header, &HeaderValue::from_static(""), self.kind)? {
 			return Ok(());
 		}
+		let mut headers = self.headers.borrow_mut();
 		if self.kind == HeadersKind::RequestNoCors
-			&& !validate_no_cors_safelisted_request_header(&mut self.headers, &name, &value)
+			&& !validate_no_cors_safelisted_request_header(&headers, &header, &value)
 		{
 			return Ok(());
 		}
-		self.headers.insert(name, value);
-		remove_privileged_no_cors_headers(&mut self.headers, self.kind);
+		headers.record(&name, &header);
+		headers.insert(header, value);
+		remove_privileged_no_cors_headers(&mut headers, self.kind);
+		Ok(())
+	}
+
+	#[ion(name = "forEach")]
+	pub fn for_each(&self, cx: &Context, callback: Function, Opt(this_arg): Opt<Value>) -> ResultExc<()> {
+		let this_arg = this_arg.unwrap_or_else(|| Value::undefined(cx));
+		for (name, value) in HeaderPairs::new(Rc::clone(&self.headers)) {
+			let args = [value.as_value(cx), name.as_value(cx), self.reflector.get().as_value(cx)];
+			match callback.call_with_this(cx, &this_arg, &args) {
+				Ok(_) => {}
+				Err(Some(report)) => return Err(report.into_exception()),
+				Err(None) => return Err(Error::none().into()),
+			}
+		}
 		Ok(())
 	}
 
+	pub fn entries(&self, cx: &Context) -> ion::Iterator {
+		self.iterate(cx, HeadersIteratorKind::Entries)
+	}
+
+	pub fn keys(&self, cx: &Context) -> ion::Iterator {
+		self.iterate(cx, HeadersIteratorKind::Keys)
+	}
+
+	pub fn values(&self, cx: &Context) -> ion::Iterator {
+		self.iterate(cx, HeadersIteratorKind::Values)
+	}
+
 	#[ion(name = WellKnownSymbolCode::Iterator)]
 	pub fn iterator(&self, cx: &Context) -> ion::Iterator {
-		let cookies: Vec<_> = self.headers.get_all(&SET_COOKIE).iter().map(HeaderValue::clone).collect();
-
-		let mut keys: Vec<_> = self.headers.keys().map(|name| name.as_str().to_ascii_lowercase()).collect();
-		keys.reserve(cookies.len());
-		for _ in 0..cookies.len() {
-			keys.push(String::from(SET_COOKIE.as_str()));
-		}
-		keys.sort();
+		self.iterate(cx, HeadersIteratorKind::Entries)
+	}
+}
 
+impl Headers {
+	fn iterate(&self, cx: &Context, kind: HeadersIteratorKind) -> ion::Iterator {
 		let this = self.reflector.get().as_value(cx);
 		ion::Iterator::new(
 			HeadersIterator {
-				keys: keys.into_iter(),
-				cookies: cookies.into_iter(),
+				pairs: HeaderPairs::new(Rc::clone(&self.headers)),
+				kind,
 			},
 			&this,
 		)
 	}
 }
 
+/// Returns the header list of the [Headers] object owned by a [Request](crate::globals::fetch::Request) or
+/// [Response](crate::globals::fetch::Response), sharing it rather than copying it.
+pub(crate) fn header_list(cx: &Context, headers: &Heap<*mut JSObject>) -> Rc<RefCell<HeaderList>> {
+	let headers = Object::from(unsafe { Local::from_heap(headers) });
+	Rc::clone(&Headers::get_private(cx, &headers).unwrap().headers)
+}
+
+#[derive(Clone, Copy, Debug)]
+enum HeadersIteratorKind {
+	Entries,
+	Keys,
+	Values,
+}
+
 pub struct HeadersIterator {
-	keys: vec::IntoIter<String>,
-	cookies: vec::IntoIter<HeaderValue>,
+	pairs: HeaderPairs,
+	kind: HeadersIteratorKind,
 }
 
 impl JSIterator for HeadersIterator {
-	fn next_value<'cx>(&mut self, cx: &'cx Context, private: &Value<'cx>) -> Option<Value<'cx>> {
-		let object = private.to_object(cx);
-		let headers = Headers::get_private(cx, &object).unwrap();
-		let key = self.keys.next();
-		key.and_then(|key| {
-			if key == SET_COOKIE.as_str() {
-				self.cookies.next().map(|value| [key.as_str(), value.to_str().unwrap()].as_value(cx))
-			} else {
-				get_header(&headers.headers, &HeaderName::from_bytes(key.as_bytes()).unwrap())
-					.map(|value| [key.as_str(), &value.to_string()].as_value(cx))
-			}
+	fn next_value<'cx>(&mut self, cx: &'cx Context, _: &Value<'cx>) -> Option<Value<'cx>> {
+		let (name, value) = self.pairs.next()?;
+		Some(match self.kind {
+			HeadersIteratorKind::Entries => [name.as_str(), value.as_str()].as_value(cx),
+			HeadersIteratorKind::Keys => name.as_value(cx),
+			HeadersIteratorKind::Values => value.as_value(cx),
 		})
 	}
 }
 
+/// Iterates over the sorted and combined pairs of a header list, converting each pair only when it is reached.
+///
+/// The order of the pairs is recomputed if the list is modified during iteration.
+struct HeaderPairs {
+	headers: Rc<RefCell<HeaderList>>,
+	version: Option<u64>,
+	order: Vec<(HeaderName, Option<usize>)>,
+	index: usize,
+}
+
+impl HeaderPairs {
+	fn new(headers: Rc<RefCell<HeaderList>>) -> HeaderPairs {
+		HeaderPairs {
+			headers,
+			version: None,
+			order: Vec::new(),
+			index: 0,
+		}
+	}
+}
+
+impl Iterator for HeaderPairs {
+	type Item = (String, String);
+
+	fn next(&mut self) -> Option<(String, String)> {
+		let headers = self.headers.borrow();
+		if self.version != Some(headers.version) {
+			self.order = sort_and_combine(&headers);
+			self.version = Some(headers.version);
+		}
+
+		let (name, index) = self.order.get(self.index)?;
+		self.index += 1;
+		let value = match index {
+			Some(index) => headers.get_all(name).iter().nth(*index).map(value_to_string)?,
+			None => headers.get_all(name).iter().map(value_to_string).collect::<Vec<_>>().join(", "),
+		};
+		Some((String::from(name.as_str()), value))
+	}
+}
+
+/// Returns the header names sorted in order, with an index for each value of `Set-Cookie`, whose values are not
+/// combined.
+///
+/// Refer to the [Fetch Standard](https://fetch.spec.whatwg.org/#concept-header-list-sort-and-combine) for more details.
+fn sort_and_combine(headers: &HeaderMap) -> Vec<(HeaderName, Option<usize>)> {
+	let mut order = Vec::with_capacity(headers.keys_len());
+	for name in headers.keys() {
+		if name == SET_COOKIE {
+			let count = headers.get_all(name).iter().count();
+			order.extend((0..count).map(|index| (name.clone(), Some(index))));
+		} else {
+			order.push((name.clone(), None));
+		}
+	}
+	order.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+	order
+}
+
+fn value_to_string(value: &HeaderValue) -> String {
+	value.as_bytes().iter().copied().map(char::from).collect()
+}
+
 const COOKIE2: HeaderName = HeaderName::from_static("cookie2");
 pub(crate) const SET_COOKIE2: HeaderName = HeaderName::from_static("set-cookie2");
 const KEEP_ALIVE: HeaderName = HeaderName::from_static("keep-alive");
@@ -348,7 +531,7 @@ fn validate_header(name: &HeaderName, value: &HeaderValue, kind: HeadersKind) ->
 	Ok(true)
 }
 
-fn validate_no_cors_safelisted_request_header(headers: &mut HeaderMap, name: &HeaderName, value: &HeaderValue) -> bool {
+fn validate_no_cors_safelisted_request_header(headers: &HeaderMap, name: &HeaderName, value: &HeaderValue) -> bool {
 	if !NO_CORS_SAFELISTED_REQUEST_HEADERS.contains(name) {
 		return false;
 	}
@@ -428,7 +611,9 @@ fn validate_no_cors_safelisted_request_header(headers: &mut HeaderMap, name: &He
 	true
 }
 
-fn append_header(headers: &mut HeaderMap, name: HeaderName, value: HeaderValue, kind: HeadersKind) -> Result<()> {
+fn append_header(
+	headers: &mut HeaderList, original: &[u8], name: HeaderName, value: HeaderValue, kind: HeadersKind,
+) -> Result<()> {
 	if !validate_header(&name, &value, kind)? {
 		return Ok(());
 	}
@@ -437,6 +622,7 @@ fn append_header(headers: &mut HeaderMap, name: HeaderName, value: HeaderValue,
 		return Ok(());
 	}
 
+	headers.record(original, &name);
 	headers.append(name, value);
 	remove_privileged_no_cors_headers(headers, kind);
 	Ok(())
@@ -448,23 +634,19 @@ fn remove_privileged_no_cors_headers(headers: &mut HeaderMap, kind: HeadersKind)
 	}
 }
 
-fn append_to_headers(cx: &Context, headers: &mut HeaderMap, obj: Object) -> Result<()> {
+fn append_to_headers(cx: &Context, headers: &mut HeaderList, obj: Object) -> Result<()> {
 	for key in obj.keys(cx, None).map(|key| key.to_owned_key(cx)) {
 		let key = match key {
 			Ok(OwnedKey::Int(i)) => i.to_string(),
-			Ok(OwnedKey::String(s)) => s,
+			Ok(OwnedKey::String(s)) => s.into(),
 			_ => continue,
 		};
 
 		let name = HeaderName::from_str(&key.to_lowercase())?;
 		let value = obj.get(cx, &key)?.unwrap();
+		headers.record(key.as_bytes(), &name);
 		if let Ok(array) = Array::from_value(cx, &value, false, ()) {
-			let vec: Vec<_> = array
-				.to_vec(cx)
-				.into_iter()
-				.map(|v| String::from_value(cx, &v, false, ()))
-				.collect::<Result<_>>()?;
-			let str = vec.join(", ");
+			let str = array.to_vec::<String>(cx, false, ())?.join(", ");
 			let value = HeaderValue::from_str(&str)?;
 			headers.insert(name, value);
 		} else if let Ok(str) = String::from_value(cx, &value, false, ()) {
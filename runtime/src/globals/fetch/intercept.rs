@@ -0,0 +1,104 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::rc::Rc;
+
+use futures::future::LocalBoxFuture;
+use ion::class::NativeObject;
+use ion::{ClassDefinition, Context, Function, Object, Promise, PromiseFuture, TracedHeap, Value};
+use mozjs::jsapi::JSFunction;
+
+use crate::event_loop::MicrotaskCheckpoint;
+use crate::globals::fetch::response::network_error;
+use crate::globals::fetch::{Request, Response};
+use crate::ContextExt;
+
+/// Result of intercepting a request before it is sent over the network.
+pub enum Interception {
+	/// Sends the request, including any modifications made by the interceptor.
+	Continue,
+	/// Skips the network and uses the given response instead.
+	Respond(Response),
+}
+
+/// Observes, modifies or short-circuits HTTP(S) requests made by `fetch`.
+///
+/// Interceptors are registered per runtime and run in order of registration, before a request is sent over the
+/// network. This is also done for each redirect that is followed.
+pub trait FetchInterceptor {
+	fn intercept<'a>(&'a self, cx: &'a Context, request: &'a mut Request) -> LocalBoxFuture<'a, Interception>;
+}
+
+/// Adds an interceptor to the runtime of the context, which runs after those already registered.
+pub fn add_fetch_interceptor(cx: &Context, interceptor: Rc<dyn FetchInterceptor>) {
+	unsafe { cx.get_private().fetch_interceptors.push(interceptor) }
+}
+
+/// Removes an interceptor which was added with [add_fetch_interceptor]. Requests which are in flight are unaffected.
+/// Returns false if the interceptor was not registered.
+pub fn remove_fetch_interceptor(cx: &Context, interceptor: &Rc<dyn FetchInterceptor>) -> bool {
+	let interceptors = unsafe { &mut cx.get_private().fetch_interceptors };
+	let length = interceptors.len();
+	interceptors.retain(|registered| !Rc::ptr_eq(registered, interceptor));
+	interceptors.len() != length
+}
+
+/// [FetchInterceptor] which calls a JavaScript function with the [Request].
+///
+/// The function may modify the request, and may return a [Response] (or a promise resolving to one) to respond to
+/// the request. Any other return value sends the request. If the function throws, the request fails with a network
+/// error.
+pub struct FunctionInterceptor {
+	function: TracedHeap<*mut JSFunction>,
+}
+
+impl FunctionInterceptor {
+	pub fn new(function: &Function) -> FunctionInterceptor {
+		FunctionInterceptor {
+			function: TracedHeap::new(function.get()),
+		}
+	}
+
+	fn to_interception(cx: &Context, value: &Value) -> Interception {
+		if !value.handle().is_object() {
+			return Interception::Continue;
+		}
+
+		let object = value.to_object(cx);
+		match Response::get_mut_private(cx, &object) {
+			Ok(response) if response.body.is_some() => Interception::Respond(response.take()),
+			Ok(_) => Interception::Respond(network_error()),
+			Err(_) => Interception::Continue,
+		}
+	}
+}
+
+impl FetchInterceptor for FunctionInterceptor {
+	fn intercept<'a>(&'a self, cx: &'a Context, request: &'a mut Request) -> LocalBoxFuture<'a, Interception> {
+		Box::pin(async move {
+			let function = Function::from(self.function.to_local());
+			let request = Object::from(cx.root(request.reflector().get()));
+			let result = {
+				let _checkpoint = MicrotaskCheckpoint::new(cx);
+				function.call(cx, &Object::null(cx), &[Value::object(cx, &request)])
+			};
+			let value = match result {
+				Ok(value) => value,
+				Err(_) => return Interception::Respond(network_error()),
+			};
+
+			if value.handle().is_object() {
+				if let Some(promise) = Promise::from(cx.root(value.handle().to_object())) {
+					return match PromiseFuture::new(cx, &promise).await {
+						Ok(value) => FunctionInterceptor::to_interception(cx, &Value::from(cx.root(value))),
+						Err(_) => Interception::Respond(network_error()),
+					};
+				}
+			}
+			FunctionInterceptor::to_interception(cx, &value)
+		})
+	}
+}
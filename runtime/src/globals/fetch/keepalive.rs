@@ -0,0 +1,72 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use tokio::sync::Notify;
+
+/// Maximum total size of the bodies of keepalive requests in flight, as specified by the Fetch Standard.
+pub const KEEPALIVE_BODY_LIMIT: u64 = 64 * 1024;
+
+/// Keepalive requests which are in flight, so that the runtime can wait for them before shutting down.
+#[derive(Debug, Default)]
+pub(crate) struct KeepaliveRequests {
+	requests: Cell<usize>,
+	bytes: Cell<u64>,
+	idle: Notify,
+}
+
+impl KeepaliveRequests {
+	/// Reserves the size of the body of a keepalive request until the returned guard is dropped.
+	/// Returns [None] if the total size of the bodies in flight would exceed [KEEPALIVE_BODY_LIMIT].
+	pub(crate) fn reserve(self: &Rc<KeepaliveRequests>, bytes: u64) -> Option<KeepaliveGuard> {
+		let total = self.bytes.get() + bytes;
+		if total > KEEPALIVE_BODY_LIMIT {
+			return None;
+		}
+
+		self.requests.set(self.requests.get() + 1);
+		self.bytes.set(total);
+		Some(KeepaliveGuard { requests: Rc::clone(self), bytes })
+	}
+
+	pub(crate) fn requests(&self) -> usize {
+		self.requests.get()
+	}
+
+	pub(crate) fn bytes(&self) -> u64 {
+		self.bytes.get()
+	}
+
+	/// Waits until no keepalive requests are in flight.
+	pub(crate) async fn idle(&self) {
+		loop {
+			let notified = self.idle.notified();
+			if self.requests.get() == 0 {
+				return;
+			}
+			notified.await;
+		}
+	}
+}
+
+/// Releases the reservation of a keepalive request once it has completed or been cancelled.
+pub(crate) struct KeepaliveGuard {
+	requests: Rc<KeepaliveRequests>,
+	bytes: u64,
+}
+
+impl Drop for KeepaliveGuard {
+	fn drop(&mut self) {
+		let requests = &self.requests;
+		requests.requests.set(requests.requests.get() - 1);
+		requests.bytes.set(requests.bytes.get() - self.bytes);
+		if requests.requests.get() == 0 {
+			requests.idle.notify_waiters();
+		}
+	}
+}
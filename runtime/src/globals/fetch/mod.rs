@@ -8,51 +8,89 @@ use std::collections::Bound;
 use std::iter::once;
 use std::str;
 use std::str::FromStr;
+use std::time::{Duration, Instant, SystemTime};
 
 use arrayvec::ArrayVec;
 use async_recursion::async_recursion;
 use body::FetchBody;
+pub use body::{Body, BoxError};
 use bytes::Bytes;
-use client::Client;
-pub use client::{default_client, GLOBAL_CLIENT};
+use cache::{cache_mode, is_storable, MAX_ENTRY_SIZE};
+pub use cache::HttpCache;
+use client::runtime_client;
+pub use client::{client_with_resolver, default_client, ClientFuture, FetchClient, HyperClient};
 use const_format::concatcp;
+pub(crate) use cors::PreflightCache;
 use data_url::DataUrl;
+pub use dns::Resolver;
+pub use form_data::FormData;
+use futures::channel::mpsc;
 use futures::future::{select, Either};
-pub use header::Headers;
-use header::{remove_all_header_entries, HeadersKind, FORBIDDEN_RESPONSE_HEADERS};
-use headers::{HeaderMapExt, Range};
+pub use header::{HeaderList, Headers};
+use header::{header_list, remove_all_header_entries, HeadersKind, FORBIDDEN_RESPONSE_HEADERS};
+use headers::{ContentLength, HeaderMapExt, Range};
 use http::header::{
-	ACCEPT, ACCEPT_ENCODING, ACCEPT_LANGUAGE, ACCESS_CONTROL_ALLOW_HEADERS, CACHE_CONTROL, CONTENT_ENCODING,
-	CONTENT_LANGUAGE, CONTENT_LENGTH, CONTENT_LOCATION, CONTENT_RANGE, CONTENT_TYPE, HOST, IF_MATCH, IF_MODIFIED_SINCE,
-	IF_NONE_MATCH, IF_RANGE, IF_UNMODIFIED_SINCE, LOCATION, PRAGMA, RANGE, REFERER, REFERRER_POLICY, USER_AGENT,
+	ACCEPT, ACCEPT_ENCODING, ACCEPT_LANGUAGE, CACHE_CONTROL, CONTENT_ENCODING, CONTENT_LANGUAGE, CONTENT_LENGTH,
+	CONTENT_LOCATION, CONTENT_RANGE, CONTENT_TYPE, HOST, LOCATION, ORIGIN, PRAGMA, RANGE, REFERER, REFERRER_POLICY,
+	USER_AGENT,
 };
 use http::{HeaderMap, HeaderValue, Method, StatusCode};
-use ion::class::{ClassObjectWrapper, Reflector};
+use hyper::ext::on_informational;
+pub use intercept::{
+	add_fetch_interceptor, remove_fetch_interceptor, FetchInterceptor, FunctionInterceptor, Interception,
+};
+use ion::class::ClassObjectWrapper;
 use ion::conversions::ToValue;
 use ion::flags::PropertyFlags;
 use ion::function::Opt;
-use ion::{ClassDefinition, Context, Error, ErrorKind, Exception, Local, Object, Promise, ResultExc, TracedHeap};
-use request::{Referrer, ReferrerPolicy, RequestCache, RequestCredentials, RequestMode, RequestRedirect};
+use ion::{
+	ClassDefinition, Context, Error, ErrorKind, Exception, Function, Local, Object, Promise, ResultExc, TracedHeap,
+};
+pub(crate) use keepalive::KeepaliveRequests;
+pub use keepalive::KEEPALIVE_BODY_LIMIT;
+use mozjs::jsapi::JSObject;
+use request::{Referrer, ReferrerPolicy, RequestCache, RequestMode, RequestRedirect};
 pub use request::{Request, RequestInfo, RequestInit};
 pub use response::Response;
-use response::{network_error, ResponseKind, ResponseTaint};
+use response::{network_error, network_error_with, NetworkError, ResponseKind, ResponseTaint};
+pub use retry::RetryOptions;
 use sys_locale::get_locales;
+pub use timeout::FetchTimeouts;
+pub(crate) use timeout::SharedTimeouts;
+use timeout::{fetch_timeouts, timeout_error};
+pub use timing::{ConnectionTiming, ResponseTiming};
 use tokio::fs::read;
+use tokio::time::{sleep, timeout};
+use tracing::{field, info_span, Instrument, Span};
+use unix::UNIX_SCHEME;
 use uri_url::url_to_uri;
 use url::Url;
 
+use crate::event_loop::MicrotaskCheckpoint;
 use crate::globals::abort::AbortSignal;
-use crate::globals::fetch::body::Body;
-use crate::globals::file::Blob;
-use crate::globals::url::parse_uuid_from_url_path;
+use crate::globals::fetch::progress::{with_progress, with_reports};
 use crate::promise::future_to_promise;
+#[cfg(feature = "otlp")]
+use crate::telemetry::inject_context;
 use crate::{ContextExt, VERSION};
 
 mod body;
+mod cache;
 mod client;
+mod cors;
+mod dns;
+mod form_data;
 mod header;
+mod intercept;
+mod keepalive;
+mod multipart;
+mod progress;
 mod request;
 mod response;
+mod retry;
+mod timeout;
+mod timing;
+mod unix;
 
 const DEFAULT_USER_AGENT: &str = concatcp!("Spiderfire/", VERSION);
 
@@ -75,10 +113,25 @@ fn fetch<'cx>(cx: &'cx Context, resource: RequestInfo, init: Opt<RequestInit>) -
 		return Some(promise);
 	}
 
-	let headers = Object::from(unsafe { Local::from_heap(&request.headers) });
-	let headers = Headers::get_mut_private(cx, &headers).unwrap();
-	if !headers.headers.contains_key(ACCEPT) {
-		headers.headers.append(ACCEPT, HeaderValue::from_static("*/*"));
+	let keepalive = if request.keepalive {
+		let requests = unsafe { &cx.get_private().fetch_keepalive };
+		// Stream bodies are rejected by the constructor, so bodies without a length are empty.
+		match requests.reserve(request.body.len().unwrap_or(0) as u64) {
+			Some(guard) => Some(guard),
+			None => {
+				let error = Error::new("Keepalive request bodies exceed the limit of 64 KiB", ErrorKind::Type);
+				promise.reject_with_error(cx, &error);
+				return Some(promise);
+			}
+		}
+	} else {
+		None
+	};
+
+	let headers = header_list(cx, &request.headers);
+	let mut headers = headers.borrow_mut();
+	if !headers.contains_key(ACCEPT) {
+		headers.append(ACCEPT, HeaderValue::from_static("*/*"));
 	}
 
 	let mut locales = get_locales().enumerate();
@@ -89,36 +142,75 @@ fn fetch<'cx>(cx: &'cx Context, resource: RequestInfo, init: Opt<RequestInit>) -
 		locale_string.push_str(";q=0.");
 		locale_string.push_str(&(1000 - index).to_string());
 	}
-	if !headers.headers.contains_key(ACCEPT_LANGUAGE) {
-		headers.headers.append(ACCEPT_LANGUAGE, HeaderValue::from_str(&locale_string).unwrap());
+	if !headers.contains_key(ACCEPT_LANGUAGE) {
+		headers.append(ACCEPT_LANGUAGE, HeaderValue::from_str(&locale_string).unwrap());
 	}
+	drop(headers);
+
+	let span = info_span!(
+		"fetch",
+		http.request.method = %request.method,
+		url.full = %request.url,
+		http.response.status_code = field::Empty,
+	);
 
 	let request = TracedHeap::new(Request::new_object(cx, Box::new(request)));
-	let cx2 = unsafe { Context::new_unchecked(cx.as_ptr()) };
-	future_to_promise(cx, async move {
+	let client = runtime_client(cx);
+	let token = cx.token();
+	let future = async move {
+		let _keepalive = keepalive;
+		let cx = token.context();
 		let request = Object::from(request.to_local());
-		fetch_internal(&cx2, &request, GLOBAL_CLIENT.get().unwrap().clone()).await
-	})
+		fetch_internal(&cx, &request, &*client).await
+	};
+	future_to_promise(cx, cx.token().guard(future).instrument(span))
 }
 
-async fn fetch_internal(cx: &Context, request: &Object<'_>, client: Client) -> ResultExc<ClassObjectWrapper<Response>> {
+async fn fetch_internal(
+	cx: &Context, request: &Object<'_>, client: &dyn FetchClient,
+) -> ResultExc<ClassObjectWrapper<Response>> {
 	let request = Request::get_mut_private(cx, request)?;
 	let signal = Object::from(unsafe { Local::from_heap(&request.signal_object) });
 	let signal = AbortSignal::get_private(cx, &signal)?.signal.clone().poll();
-	let send = Box::pin(main_fetch(cx, request, client, 0));
+	let start = Instant::now();
+	let total = fetch_timeouts(cx).total;
+	let send = Box::pin(async {
+		match total {
+			Some(total) => timeout(total, main_fetch(cx, request, client, ResponseTaint::default(), 0)).await.ok(),
+			None => Some(main_fetch(cx, request, client, ResponseTaint::default(), 0).await),
+		}
+	});
 	let response = match select(send, signal).await {
-		Either::Left((response, _)) => Ok(response),
+		Either::Left((None, _)) => Err(timeout_error(cx, total.unwrap_or_default())),
+		Either::Left((Some(mut response), _)) => {
+			if let Some(status) = response.status {
+				Span::current().record("http.response.status_code", status.as_u16());
+			}
+			response.url_list.clone_from(&request.locations);
+			response.download_progress.set(request.download_progress.get());
+			if let Some(timing) = &mut response.timing {
+				timing.total = start.elapsed();
+			}
+			Ok(response)
+		}
 		Either::Right((exception, _)) => Err(Exception::Other(exception)),
 	};
-	response.and_then(|response| {
-		if response.kind == ResponseKind::Error {
-			Err(Exception::Error(Error::new(
+	response.and_then(|response| match response.kind {
+		ResponseKind::Error => Err(match response.error {
+			Some(NetworkError::Timeout(timeout)) => timeout_error(cx, timeout),
+			Some(NetworkError::Transport(error)) => Exception::Error(Error::new(
+				format!(
+					"Network Error: Failed to fetch from {}: {}",
+					&request.url, error.message
+				),
+				ErrorKind::Type,
+			)),
+			None => Exception::Error(Error::new(
 				format!("Network Error: Failed to fetch from {}", &request.url),
 				ErrorKind::Type,
-			)))
-		} else {
-			Ok(ClassObjectWrapper(Box::new(response)))
-		}
+			)),
+		}),
+		_ => Ok(ClassObjectWrapper(Box::new(response))),
 	})
 }
 
@@ -208,30 +300,38 @@ static BAD_PORTS: &[u16] = &[
 static SCHEMES: [&str; 4] = ["about", "blob", "data", "file"];
 
 #[async_recursion(?Send)]
-async fn main_fetch(cx: &Context, request: &mut Request, client: Client, redirections: u8) -> Response {
+async fn main_fetch(
+	cx: &Context, request: &mut Request, client: &dyn FetchClient, mut taint: ResponseTaint, redirections: u8,
+) -> Response {
 	let scheme = request.url.scheme();
 
 	// TODO: Upgrade HTTP Schemes if the host is a domain and matches the Known HSTS Domain List
 
-	let mut taint = ResponseTaint::default();
+	let origin = unsafe { cx.get_private().fetch_origin.clone() };
+	let cross_origin = origin.as_ref().is_some_and(|origin| cors::is_cross_origin(origin, &request.url));
+
 	let mut opaque_redirect = false;
 	let mut response = {
-		if request.mode == RequestMode::SameOrigin {
+		if request.mode == RequestMode::SameOrigin && (origin.is_none() || cross_origin) {
 			network_error()
 		} else if SCHEMES.contains(&scheme) {
 			scheme_fetch(cx, scheme, request, request.url.clone()).await
-		} else if scheme == "https" || scheme == "http" {
+		} else if scheme == "https" || scheme == "http" || scheme == UNIX_SCHEME {
 			if let Some(port) = request.url.port() {
 				if BAD_PORTS.contains(&port) {
 					return network_error();
 				}
 			}
-			if request.mode == RequestMode::NoCors {
-				if request.redirect != RequestRedirect::Follow {
-					return network_error();
+			// Requests which were redirected from another origin keep their tainting, even if they return to the origin.
+			if (cross_origin || taint != ResponseTaint::Basic) && scheme != UNIX_SCHEME {
+				if request.mode == RequestMode::NoCors {
+					if request.redirect != RequestRedirect::Follow {
+						return network_error();
+					}
+					taint = ResponseTaint::Opaque;
+				} else {
+					taint = ResponseTaint::Cors;
 				}
-			} else {
-				taint = ResponseTaint::Cors;
 			}
 			let (response, opaque) = http_fetch(cx, request, client, taint, redirections).await;
 			opaque_redirect = opaque;
@@ -249,14 +349,14 @@ async fn main_fetch(cx: &Context, request: &mut Request, client: Client, redirec
 
 	response.url.get_or_insert(request.url.clone());
 
-	let headers = Object::from(unsafe { Local::from_heap(&response.headers) });
-	let headers = Headers::get_mut_private(cx, &headers).unwrap();
+	let headers = header_list(cx, &response.headers);
+	let mut headers = headers.borrow_mut();
 
 	if !opaque_redirect
 		&& taint == ResponseTaint::Opaque
 		&& response.status == Some(StatusCode::PARTIAL_CONTENT)
 		&& response.range_requested
-		&& !headers.headers.contains_key(RANGE)
+		&& !headers.contains_key(RANGE)
 	{
 		let url = response.url.take().unwrap();
 		response = network_error();
@@ -284,51 +384,22 @@ async fn main_fetch(cx: &Context, request: &mut Request, client: Client, redirec
 		response.status_text = None;
 		response.body = None;
 
-		headers.headers.clear();
+		headers.clear();
 	} else {
 		match taint {
 			ResponseTaint::Basic => {
 				response.kind = ResponseKind::Basic;
 
 				for name in &FORBIDDEN_RESPONSE_HEADERS {
-					remove_all_header_entries(&mut headers.headers, name);
+					remove_all_header_entries(&mut headers, name);
 				}
 			}
 			ResponseTaint::Cors => {
 				response.kind = ResponseKind::Cors;
 
-				let mut allows_all = false;
-				let allowed: Vec<_> = headers
-					.headers
-					.get_all(ACCESS_CONTROL_ALLOW_HEADERS)
-					.into_iter()
-					.map(|v| {
-						if v == "*" {
-							allows_all = true
-						}
-						v.clone()
-					})
-					.collect();
-				let mut to_remove = Vec::new();
-				if request.credentials != RequestCredentials::Include && allows_all {
-					for name in headers.headers.keys() {
-						if headers.headers.get_all(name).into_iter().size_hint().1.is_none() {
-							to_remove.push(name.clone());
-						}
-					}
-				} else {
-					for name in headers.headers.keys() {
-						let allowed = allowed.iter().any(|allowed| allowed.as_bytes() == name.as_str().as_bytes());
-						if allowed {
-							to_remove.push(name.clone());
-						}
-					}
-				}
-				for name in to_remove {
-					remove_all_header_entries(&mut headers.headers, &name);
-				}
+				cors::filter_response_headers(&mut headers, request.credentials);
 				for name in &FORBIDDEN_RESPONSE_HEADERS {
-					remove_all_header_entries(&mut headers.headers, name);
+					remove_all_header_entries(&mut headers, name);
 				}
 			}
 			ResponseTaint::Opaque => {
@@ -338,7 +409,7 @@ async fn main_fetch(cx: &Context, request: &mut Request, client: Client, redirec
 				response.status_text = None;
 				response.body = None;
 
-				headers.headers.clear();
+				headers.clear();
 			}
 		}
 	}
@@ -347,20 +418,14 @@ async fn main_fetch(cx: &Context, request: &mut Request, client: Client, redirec
 }
 
 async fn scheme_fetch(cx: &Context, scheme: &str, request: &Request, url: Url) -> Response {
-	let headers = Object::from(unsafe { Local::from_heap(&request.headers) });
-	let headers = &Headers::get_mut_private(cx, &headers).unwrap().headers;
+	let headers = header_list(cx, &request.headers);
 
 	match scheme {
 		"about" if url.path() == "blank" => {
 			let response = Response::new_from_bytes(Bytes::default(), url);
-			let headers = Headers {
-				reflector: Reflector::default(),
-				headers: HeaderMap::from_iter(once((
-					CONTENT_TYPE,
-					HeaderValue::from_static("text/html;charset=UTF-8"),
-				))),
-				kind: HeadersKind::Immutable,
-			};
+			let content_type = HeaderValue::from_static("text/html;charset=UTF-8");
+			let headers = HeaderMap::from_iter(once((CONTENT_TYPE, content_type)));
+			let headers = Headers::from_map(headers, HeadersKind::Immutable);
 			response.headers.set(Headers::new_object(cx, Box::new(headers)));
 			response
 		}
@@ -369,20 +434,13 @@ async fn scheme_fetch(cx: &Context, scheme: &str, request: &Request, url: Url) -
 				return network_error();
 			}
 
-			let uuid = match parse_uuid_from_url_path(&url) {
-				Some(uuid) => uuid,
-				_ => return network_error(),
-			};
 			let blob = unsafe {
-				match cx.get_private().blob_store.get(&uuid) {
+				match cx.get_private().blob_store.get(&url) {
 					Some(blob) => blob,
 					_ => return network_error(),
 				}
 			};
 
-			let blob = Object::from(unsafe { Local::from_heap(blob) });
-			let blob = Blob::get_private(cx, &blob).unwrap();
-
 			let kind = match HeaderValue::from_str(blob.kind.as_deref().unwrap_or("")) {
 				Ok(kind) => kind,
 				Err(_) => return network_error(),
@@ -391,19 +449,19 @@ async fn scheme_fetch(cx: &Context, scheme: &str, request: &Request, url: Url) -
 			let mut response_headers = ArrayVec::<_, 3>::new();
 			response_headers.push((CONTENT_TYPE, kind));
 
-			let mut bytes = blob.bytes.clone();
-			let (status, range_requested) = match headers.typed_try_get::<Range>() {
+			let mut data = blob.data.clone();
+			let range = headers.borrow().typed_try_get::<Range>();
+			let (status, range_requested) = match range {
 				Ok(Some(range)) => {
-					let len = bytes.len();
-					if let Some((start, end)) = range.satisfiable_ranges(len as u64).next() {
-						let (start, end) = (start.map(|s| s as usize), end.map(|e| e as usize));
-						bytes = bytes.slice((start, end));
-
+					let len = data.len();
+					if let Some((start, end)) = range.satisfiable_ranges(len).next() {
 						let (start, end) = match (start, end) {
 							(Bound::Included(s), Bound::Included(e)) => (s, e),
 							(Bound::Included(s), Bound::Unbounded) => (s, len - 1),
 							_ => unreachable!(),
 						};
+						data = data.slice(start, end + 1);
+
 						let range = match HeaderValue::from_str(&format!("{start}-{end}/{len}")) {
 							Ok(range) => range,
 							Err(_) => return network_error(),
@@ -420,17 +478,17 @@ async fn scheme_fetch(cx: &Context, scheme: &str, request: &Request, url: Url) -
 				Err(_) => return network_error(),
 			};
 
+			let bytes = match data.read().await {
+				Ok(bytes) => bytes,
+				Err(_) => return network_error(),
+			};
 			response_headers.push((CONTENT_LENGTH, HeaderValue::from(bytes.len())));
 
 			let mut response = Response::new_from_bytes(bytes, url);
 			response.status = Some(status);
 			response.range_requested = range_requested;
 
-			let headers = Headers {
-				reflector: Reflector::default(),
-				headers: HeaderMap::from_iter(response_headers),
-				kind: HeadersKind::Immutable,
-			};
+			let headers = Headers::from_map(HeaderMap::from_iter(response_headers), HeadersKind::Immutable);
 			response.headers.set(Headers::new_object(cx, Box::new(headers)));
 			response
 		}
@@ -444,15 +502,15 @@ async fn scheme_fetch(cx: &Context, scheme: &str, request: &Request, url: Url) -
 				Ok(decoded) => decoded,
 				Err(_) => return network_error(),
 			};
-			let mime = data_url.mime_type();
-			let mime = format!("{}/{}", mime.type_, mime.subtype);
+			// The parameters of the MIME type, such as its charset, are kept in the Content-Type header.
+			let mime = match HeaderValue::from_str(&data_url.mime_type().to_string()) {
+				Ok(mime) => mime,
+				Err(_) => return network_error(),
+			};
 
 			let response = Response::new_from_bytes(Bytes::from(body), url);
-			let headers = Headers {
-				reflector: Reflector::default(),
-				headers: HeaderMap::from_iter(once((CONTENT_TYPE, HeaderValue::from_str(&mime).unwrap()))),
-				kind: HeadersKind::Immutable,
-			};
+			let headers = HeaderMap::from_iter(once((CONTENT_TYPE, mime)));
+			let headers = Headers::from_map(headers, HeadersKind::Immutable);
 			response.headers.set(Headers::new_object(cx, Box::new(headers)));
 			response
 		}
@@ -479,9 +537,107 @@ async fn scheme_fetch(cx: &Context, scheme: &str, request: &Request, url: Url) -
 }
 
 async fn http_fetch(
-	cx: &Context, request: &mut Request, client: Client, taint: ResponseTaint, redirections: u8,
+	cx: &Context, request: &mut Request, client: &dyn FetchClient, taint: ResponseTaint, redirections: u8,
 ) -> (Response, bool) {
-	let response = http_network_fetch(cx, request, client.clone(), false).await;
+	let interceptors = unsafe { cx.get_private().fetch_interceptors.clone() };
+	for interceptor in interceptors {
+		if let Interception::Respond(mut response) = interceptor.intercept(cx, request).await {
+			if response.headers.get().is_null() {
+				let headers = Headers::new(HeadersKind::Immutable);
+				response.headers.set(Headers::new_object(cx, Box::new(headers)));
+			}
+			return (response, false);
+		}
+	}
+
+	let request_headers = header_list(cx, &request.headers);
+	if taint == ResponseTaint::Cors && cors::requires_preflight(&request.method, &request_headers.borrow()) {
+		let origin = unsafe { cx.get_private().fetch_origin.clone() }.unwrap();
+		if !cors::preflight(cx, request, client, &origin).await {
+			return (network_error(), false);
+		}
+	}
+
+	let cache = cache_mode(request.cache, &request_headers.borrow());
+	let http_cache = unsafe { cx.get_private().fetch_cache.clone() };
+	let request_headers = HeaderMap::clone(&request_headers.borrow());
+
+	let mut validators = HeaderMap::new();
+	if request.method == Method::GET
+		&& !matches!(cache, RequestCache::NoStore | RequestCache::Reload)
+		&& !request_headers.contains_key(RANGE)
+	{
+		let cached = match &http_cache {
+			Some(http_cache) => http_cache.lookup(&request.url, &request_headers).await,
+			None => None,
+		};
+		if let Some(response) = cached {
+			let fresh = cache == RequestCache::Default && response.is_fresh(&request_headers);
+			if fresh || matches!(cache, RequestCache::ForceCache | RequestCache::OnlyIfCached) {
+				let response = response.to_response(cx, request.url.clone());
+				return redirect_or_response(cx, request, response, client, taint, redirections).await;
+			}
+
+			validators = response.validators();
+		}
+	}
+
+	if cache == RequestCache::OnlyIfCached {
+		return (network_error(), false);
+	}
+
+	let request_time = SystemTime::now();
+	let mut attempt = 1;
+	let mut response = loop {
+		let response = http_network_fetch(cx, request, client, &validators, false).await;
+		match request.retry.and_then(|retry| retry.delay(cx, &request.method, &response, attempt)) {
+			Some(delay) => {
+				sleep(delay).await;
+				attempt += 1;
+			}
+			None => break response,
+		}
+	};
+
+	if let (Some(http_cache), Some(status)) = (&http_cache, response.status) {
+		let headers = HeaderMap::clone(&header_list(cx, &response.headers).borrow());
+
+		if status == StatusCode::NOT_MODIFIED && !validators.is_empty() {
+			let stored = http_cache.update(&request.url, &request_headers, &headers, request_time).await;
+			if let Some(stored) = stored {
+				response = stored.to_response(cx, request.url.clone());
+			}
+		} else if cache != RequestCache::NoStore
+			&& is_storable(&request.method, &request_headers, status, &headers)
+			&& headers.typed_get::<ContentLength>().is_some_and(|length| length.0 <= MAX_ENTRY_SIZE)
+		{
+			match response.buffer(cx).await {
+				Ok(body) => {
+					let url = &request.url;
+					http_cache.store(url, &request_headers, status, headers, body, request_time).await;
+				}
+				Err(_) => return (network_error(), false),
+			}
+		} else if !is_safe(&request.method) && (status.is_success() || status.is_redirection()) {
+			http_cache.invalidate(&request.url).await;
+		}
+	}
+
+	redirect_or_response(cx, request, response, client, taint, redirections).await
+}
+
+async fn redirect_or_response(
+	cx: &Context, request: &mut Request, response: Response, client: &dyn FetchClient, taint: ResponseTaint,
+	redirections: u8,
+) -> (Response, bool) {
+	if taint == ResponseTaint::Cors && response.status.is_some() {
+		let origin = unsafe { cx.get_private().fetch_origin.clone() }.unwrap();
+		let origin = cors::serialize_origin(&origin, &request.locations);
+		if !cors::cors_check(&origin, request.credentials, &header_list(cx, &response.headers).borrow()) {
+			return (network_error(), false);
+		}
+	}
+
 	match response.status {
 		Some(status) if status.is_redirection() => match request.redirect {
 			RequestRedirect::Follow => (
@@ -495,10 +651,15 @@ async fn http_fetch(
 	}
 }
 
+fn is_safe(method: &Method) -> bool {
+	matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE)
+}
+
 #[async_recursion(?Send)]
-async fn http_network_fetch(cx: &Context, request: &Request, client: Client, is_new: bool) -> Response {
-	let headers = Object::from(unsafe { Local::from_heap(&request.headers) });
-	let mut headers = Headers::get_mut_private(cx, &headers).unwrap().headers.clone();
+async fn http_network_fetch(
+	cx: &Context, request: &Request, client: &dyn FetchClient, validators: &HeaderMap, is_new: bool,
+) -> Response {
+	let mut headers = HeaderMap::clone(&header_list(cx, &request.headers).borrow());
 
 	let length = request
 		.body
@@ -513,20 +674,19 @@ async fn http_network_fetch(cx: &Context, request: &Request, client: Client, is_
 		headers.append(REFERER, HeaderValue::from_str(url.as_str()).unwrap());
 	}
 
+	if let Some(origin) = unsafe { &cx.get_private().fetch_origin } {
+		let cors = request.mode == RequestMode::Cors && cors::is_cross_origin(origin, &request.url);
+		if cors || !matches!(request.method, Method::GET | Method::HEAD) {
+			let origin = cors::serialize_origin(origin, &request.locations);
+			headers.append(ORIGIN, HeaderValue::from_str(&origin).unwrap());
+		}
+	}
+
 	if !headers.contains_key(USER_AGENT) {
 		headers.append(USER_AGENT, HeaderValue::from_static(DEFAULT_USER_AGENT));
 	}
 
-	let mut cache = request.cache;
-	if cache == RequestCache::Default
-		&& (headers.contains_key(IF_MODIFIED_SINCE)
-			|| headers.contains_key(IF_NONE_MATCH)
-			|| headers.contains_key(IF_UNMODIFIED_SINCE)
-			|| headers.contains_key(IF_MATCH)
-			|| headers.contains_key(IF_RANGE))
-	{
-		cache = RequestCache::NoStore;
-	}
+	let cache = cache_mode(request.cache, &headers);
 
 	if cache == RequestCache::NoCache && !headers.contains_key(CACHE_CONTROL) {
 		headers.append(CACHE_CONTROL, HeaderValue::from_static("max-age=0"));
@@ -546,45 +706,110 @@ async fn http_network_fetch(cx: &Context, request: &Request, client: Client, is_
 	}
 
 	if !headers.contains_key(HOST) {
-		let host = request
-			.url
-			.host_str()
-			.map(|host| {
-				if let Some(port) = request.url.port() {
-					format!("{host}:{port}")
-				} else {
-					String::from(host)
-				}
-			})
-			.unwrap();
+		let host = if request.url.scheme() == UNIX_SCHEME {
+			String::from("localhost")
+		} else {
+			request
+				.url
+				.host_str()
+				.map(|host| {
+					if let Some(port) = request.url.port() {
+						format!("{host}:{port}")
+					} else {
+						String::from(host)
+					}
+				})
+				.unwrap()
+		};
 		headers.append(HOST, HeaderValue::from_str(&host).unwrap());
 	}
 
-	if request.cache == RequestCache::OnlyIfCached {
-		return network_error();
+	for (name, value) in validators {
+		headers.insert(name, value.clone());
 	}
 
+	#[cfg(feature = "otlp")]
+	inject_context(&mut headers);
+
 	let range_requested = headers.contains_key(RANGE);
 
-	let uri = url_to_uri(&request.url).unwrap();
+	let unix = request.url.scheme() == UNIX_SCHEME;
+	let uri = if unix {
+		match unix::request_target(&request.url) {
+			Some(uri) => uri,
+			None => return network_error(),
+		}
+	} else {
+		url_to_uri(&request.url).unwrap()
+	};
 	let mut builder = hyper::Request::builder().method(request.method.clone()).uri(uri);
 	*builder.headers_mut().unwrap() = headers;
-	let req = builder.body(request.body.to_http_body()).unwrap();
 
-	let mut response = match client.request(req).await {
-		Ok(response) => {
-			let response = response.map(Body::Incoming);
-			let (headers, response) = Response::from_hyper(response, request.url.clone());
+	let upload_progress = request.upload_progress.get();
+	let (sender, receiver) = mpsc::unbounded();
+	let body = if upload_progress.is_null() {
+		request.body.to_http_body()
+	} else {
+		request.body.to_progress_body(sender)
+	};
+	let mut req = builder.body(body).unwrap();
+
+	let informational = request.informational.get();
+	let (informational_sender, informational_receiver) = mpsc::unbounded();
+	if !informational.is_null() {
+		on_informational(&mut req, move |response| {
+			let _ = informational_sender.unbounded_send((response.status(), response.headers().clone()));
+		});
+	}
 
-			let headers = Headers {
-				reflector: Reflector::default(),
-				headers,
-				kind: HeadersKind::Immutable,
+	let start = Instant::now();
+	let headers_timeout = fetch_timeouts(cx).response_headers;
+	let sending = async {
+		let sending = async {
+			let response = if unix {
+				unix::send(&request.url, req).await.map(|response| response.map(Body::Incoming))
+			} else {
+				client.send(req).await
 			};
+			response.map_err(NetworkError::Transport)
+		};
+		match headers_timeout {
+			Some(headers_timeout) => timeout(headers_timeout, sending)
+				.await
+				.unwrap_or(Err(NetworkError::Timeout(headers_timeout))),
+			None => sending.await,
+		}
+	};
+	let sending = with_reports(informational_receiver, sending, |(status, headers)| {
+		report_informational(cx, informational, status, headers)
+	});
+	let total = request.body.len().map(|len| len as u64);
+	let response = match with_progress(cx, upload_progress, total, receiver, sending).await {
+		Ok(Ok(response)) => response,
+		_ => return network_error(),
+	};
+
+	let mut response = match response {
+		Ok(response) => {
+			let connection = response
+				.extensions()
+				.get::<SharedConnectionTiming>()
+				.and_then(SharedConnectionTiming::take)
+				.unwrap_or_default();
+			let timing = ResponseTiming {
+				connection,
+				first_byte: start.elapsed(),
+				total: Duration::ZERO,
+			};
+
+			let (headers, mut response) = Response::from_hyper(response, request.url.clone());
+			response.timing = Some(timing);
+
+			let headers = Headers::from_map(headers, HeadersKind::Immutable);
 			response.headers.set(Headers::new_object(cx, Box::new(headers)));
 			response
 		}
-		Err(_) => return network_error(),
+		Err(error) => return network_error_with(error),
 	};
 
 	response.range_requested = range_requested;
@@ -594,18 +819,39 @@ async fn http_network_fetch(cx: &Context, request: &Request, client: Client, is_
 	}
 
 	if response.status == Some(StatusCode::MISDIRECTED_REQUEST) && !is_new && !request.body.is_stream() {
-		return http_network_fetch(cx, request, client, true).await;
+		return http_network_fetch(cx, request, client, validators, true).await;
 	}
 
 	response
 }
 
+/// Calls the informational response callback with the status and headers of a 1xx response, such as 103 Early Hints.
+fn report_informational(cx: &Context, callback: *mut JSObject, status: StatusCode, headers: HeaderMap) -> ResultExc<()> {
+	let Some(callback) = Function::from_object(cx, &cx.root(callback)) else {
+		return Ok(());
+	};
+
+	let headers = Headers::from_map(headers, HeadersKind::Immutable);
+	let response = object!(cx, {
+		"status": status.as_u16(),
+		"headers": Headers::new_object(cx, Box::new(headers)),
+	});
+
+	let _checkpoint = MicrotaskCheckpoint::new(cx);
+	match callback.call(cx, &Object::null(cx), &[response.as_value(cx)]) {
+		Ok(_) => Ok(()),
+		Err(Some(report)) => Err(report.into_exception()),
+		Err(None) => Err(Error::none().into()),
+	}
+}
+
 async fn http_redirect_fetch(
-	cx: &Context, request: &mut Request, response: Response, client: Client, taint: ResponseTaint, redirections: u8,
+	cx: &Context, request: &mut Request, response: Response, client: &dyn FetchClient, taint: ResponseTaint,
+	redirections: u8,
 ) -> Response {
-	let headers = Object::from(unsafe { Local::from_heap(&response.headers) });
-	let headers = Headers::get_private(cx, &headers).unwrap();
-	let mut location = headers.headers.get_all(LOCATION).into_iter();
+	let response_headers = header_list(cx, &response.headers);
+	let response_headers = response_headers.borrow();
+	let mut location = response_headers.get_all(LOCATION).into_iter();
 	let location = match location.size_hint().1 {
 		Some(0) => return response,
 		None => return network_error(),
@@ -648,30 +894,37 @@ async fn http_redirect_fetch(
 	{
 		request.method = Method::GET;
 		request.body = FetchBody::default();
-		let headers = Object::from(unsafe { Local::from_heap(&request.headers) });
-		let headers = Headers::get_mut_private(cx, &headers).unwrap();
-		remove_all_header_entries(&mut headers.headers, &CONTENT_ENCODING);
-		remove_all_header_entries(&mut headers.headers, &CONTENT_LANGUAGE);
-		remove_all_header_entries(&mut headers.headers, &CONTENT_LOCATION);
-		remove_all_header_entries(&mut headers.headers, &CONTENT_TYPE);
+		let headers = header_list(cx, &request.headers);
+		let mut headers = headers.borrow_mut();
+		remove_all_header_entries(&mut headers, &CONTENT_ENCODING);
+		remove_all_header_entries(&mut headers, &CONTENT_LANGUAGE);
+		remove_all_header_entries(&mut headers, &CONTENT_LOCATION);
+		remove_all_header_entries(&mut headers, &CONTENT_TYPE);
 	}
 
 	request.locations.push(location.clone());
 	request.url = location;
 
-	let policy = headers.headers.get_all(REFERRER_POLICY).into_iter().rev();
+	let policy = response_headers.get_all(REFERRER_POLICY).into_iter().rev();
 	let policy = policy
 		.filter(|v| !v.is_empty())
 		.find_map(|v| ReferrerPolicy::from_str(str::from_utf8(v.as_bytes()).unwrap()).ok());
 	if let Some(policy) = policy {
 		request.referrer_policy = policy;
 	}
+	drop(response_headers);
 
-	main_fetch(cx, request, client, redirections + 1).await
+	main_fetch(cx, request, client, taint, redirections + 1).await
 }
 
 pub fn define(cx: &Context, global: &Object) -> bool {
-	let _ = GLOBAL_CLIENT.set(default_client());
-	global.define_method(cx, "fetch", fetch, 1, PropertyFlags::CONSTANT_ENUMERATED);
-	Headers::init_class(cx, global).0 && Request::init_class(cx, global).0 && Response::init_class(cx, global).0
+	let fetch = global.define_method(cx, "fetch", fetch, 1, PropertyFlags::CONSTANT_ENUMERATED);
+	let defaults = Object::new(cx);
+	let flags = PropertyFlags::CONSTANT_ENUMERATED;
+	(unsafe { defaults.define_properties(cx, timeout::DEFAULTS) })
+		&& fetch.to_object(cx).define_as(cx, "defaults", &defaults, flags)
+		&& Headers::init_class(cx, global).0
+		&& Request::init_class(cx, global).0
+		&& Response::init_class(cx, global).0
+		&& FormData::init_class(cx, global).0
 }
@@ -0,0 +1,321 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::io;
+use std::mem::take;
+
+use bytes::{Buf, BytesMut};
+use http_body_util::BodyExt;
+use ion::{Error, ErrorKind};
+
+use crate::config::CONFIG;
+use crate::globals::fetch::body::Body;
+use crate::globals::file::{BlobData, TemporaryFileWriter};
+use crate::promise::run_blocking;
+
+/// Maximum size of the headers of a part, in bytes.
+const MAX_HEADERS_SIZE: usize = 16 * 1024;
+
+/// Value of a part of a `multipart/form-data` body.
+pub(crate) enum FormValue {
+	Text(String),
+	File {
+		data: BlobData,
+		kind: Option<String>,
+		name: String,
+	},
+}
+
+/// Error which occurs while parsing a `multipart/form-data` body, which can be sent from the blocking thread pool.
+enum MultipartError {
+	Invalid(&'static str),
+	Io(io::Error),
+}
+
+impl MultipartError {
+	fn into_error(self) -> Error {
+		match self {
+			MultipartError::Invalid(message) => {
+				Error::new(format!("Invalid multipart/form-data body: {message}"), ErrorKind::Type)
+			}
+			MultipartError::Io(error) => Error::from(error),
+		}
+	}
+}
+
+impl From<io::Error> for MultipartError {
+	fn from(error: io::Error) -> MultipartError {
+		MultipartError::Io(error)
+	}
+}
+
+#[derive(Default)]
+enum State {
+	Delimiter,
+	Headers,
+	Body(Part),
+	#[default]
+	Done,
+}
+
+struct Part {
+	name: String,
+	filename: Option<String>,
+	kind: Option<String>,
+	sink: PartSink,
+}
+
+enum PartSink {
+	Memory(BytesMut),
+	File(TemporaryFileWriter, u64),
+}
+
+impl Part {
+	/// Appends to the contents of the part, moving them to a temporary file once a file part exceeds
+	/// [Config::blob_spill_threshold](crate::config::Config::blob_spill_threshold).
+	fn write(&mut self, bytes: &[u8]) -> Result<(), MultipartError> {
+		match &mut self.sink {
+			PartSink::Memory(buffer) => {
+				buffer.extend_from_slice(bytes);
+				let threshold = CONFIG.get().and_then(|config| config.blob_spill_threshold);
+				let len = buffer.len() as u64;
+				if self.filename.is_some() && threshold.is_some_and(|threshold| len > threshold) {
+					let mut writer = TemporaryFileWriter::new()?;
+					writer.write(buffer)?;
+					self.sink = PartSink::File(writer, len);
+				}
+			}
+			PartSink::File(writer, len) => {
+				writer.write(bytes)?;
+				*len += bytes.len() as u64;
+			}
+		}
+		Ok(())
+	}
+
+	fn finish(self) -> Result<(String, FormValue), MultipartError> {
+		let value = match self.filename {
+			Some(name) => {
+				let data = match self.sink {
+					PartSink::Memory(buffer) => BlobData::Memory(buffer.freeze()),
+					PartSink::File(writer, _) => BlobData::File(writer.finish()?),
+				};
+				FormValue::File { data, kind: self.kind, name }
+			}
+			None => match self.sink {
+				PartSink::Memory(buffer) => FormValue::Text(String::from_utf8_lossy(&buffer).into_owned()),
+				PartSink::File(..) => unreachable!(),
+			},
+		};
+		Ok((self.name, value))
+	}
+}
+
+/// Incremental parser of `multipart/form-data` bodies.
+///
+/// Only the current part, up to a delimiter in length, is held in memory at once, apart from the contents of text
+/// parts and file parts which are too small to be spilled to temporary files.
+struct MultipartParser {
+	delimiter: Vec<u8>,
+	buffer: BytesMut,
+	state: State,
+	entries: Vec<(String, FormValue)>,
+}
+
+impl MultipartParser {
+	fn new(boundary: &str) -> MultipartParser {
+		let mut buffer = BytesMut::new();
+		// The first delimiter is not preceded by a line break, so one is added to match it like the others.
+		buffer.extend_from_slice(b"\r\n");
+		MultipartParser {
+			delimiter: [b"\r\n--", boundary.as_bytes()].concat(),
+			buffer,
+			state: State::Delimiter,
+			entries: Vec::new(),
+		}
+	}
+
+	/// Parses a chunk of the body, completing the parts it ends.
+	///
+	/// This performs blocking I/O when file parts are spilled to temporary files.
+	fn feed(&mut self, chunk: &[u8]) -> Result<(), MultipartError> {
+		if !matches!(self.state, State::Done) {
+			self.buffer.extend_from_slice(chunk);
+		}
+
+		loop {
+			match take(&mut self.state) {
+				State::Delimiter => {
+					let Some(index) = find(&self.buffer, &self.delimiter) else {
+						let keep = self.delimiter.len() - 1;
+						self.buffer.advance(self.buffer.len().saturating_sub(keep));
+						return Ok(());
+					};
+					let end = index + self.delimiter.len();
+					if self.buffer.len() < end + 2 {
+						self.buffer.advance(index);
+						return Ok(());
+					}
+
+					match &self.buffer[end..end + 2] {
+						b"--" => {
+							self.buffer.clear();
+							self.state = State::Done;
+							return Ok(());
+						}
+						b"\r\n" => {
+							self.buffer.advance(end + 2);
+							self.state = State::Headers;
+						}
+						_ => {
+							// The boundary is a prefix of other text in the preamble.
+							self.buffer.advance(index + 1);
+						}
+					}
+				}
+				State::Headers => {
+					let Some(index) = find(&self.buffer, b"\r\n\r\n") else {
+						if self.buffer.len() > MAX_HEADERS_SIZE {
+							return Err(multipart_error("Part headers are too large"));
+						}
+						self.state = State::Headers;
+						return Ok(());
+					};
+					let part = parse_headers(&self.buffer[..index])?;
+					self.buffer.advance(index + 4);
+					self.state = State::Body(part);
+				}
+				State::Body(mut part) => {
+					if let Some(index) = find(&self.buffer, &self.delimiter) {
+						part.write(&self.buffer[..index])?;
+						self.entries.push(part.finish()?);
+						self.buffer.advance(index);
+						self.state = State::Delimiter;
+					} else {
+						let written = self.buffer.len().saturating_sub(self.delimiter.len() - 1);
+						part.write(&self.buffer[..written])?;
+						self.buffer.advance(written);
+						self.state = State::Body(part);
+						return Ok(());
+					}
+				}
+				State::Done => {
+					self.state = State::Done;
+					return Ok(());
+				}
+			}
+		}
+	}
+
+	/// Returns the entries of the body, once all of it has been parsed.
+	fn finish(self) -> Result<Vec<(String, FormValue)>, MultipartError> {
+		match self.state {
+			State::Done => Ok(self.entries),
+			_ => Err(multipart_error("Unexpected end of multipart body")),
+		}
+	}
+}
+
+/// Parses a `multipart/form-data` body as it is received, returning the entries and the number of bytes read.
+///
+/// Chunks are parsed on the blocking thread pool, as file parts may be spilled to temporary files.
+pub(crate) async fn parse_multipart(mut body: Body, boundary: &str) -> Result<(Vec<(String, FormValue)>, u64), Error> {
+	let mut parser = MultipartParser::new(boundary);
+	let mut read = 0;
+	while let Some(frame) = body.frame().await {
+		let frame = frame.map_err(|error| Error::new(error.to_string(), None))?;
+		if let Ok(data) = frame.into_data() {
+			read += data.len() as u64;
+			let result;
+			(parser, result) = run_blocking(move |_| {
+				let result = parser.feed(&data);
+				(parser, result)
+			})
+			.await;
+			result.map_err(MultipartError::into_error)?;
+		}
+	}
+
+	let entries = run_blocking(move |_| parser.finish()).await.map_err(MultipartError::into_error)?;
+	Ok((entries, read))
+}
+
+/// Returns the value of the `boundary` parameter of a `multipart/form-data` content type.
+pub(crate) fn boundary(content_type: &str) -> Option<String> {
+	let mut parameters = content_type.split(';');
+	let essence = parameters.next()?.trim();
+	if !essence.eq_ignore_ascii_case("multipart/form-data") {
+		return None;
+	}
+	parameters.find_map(|parameter| {
+		let (name, value) = parameter.split_once('=')?;
+		name.trim().eq_ignore_ascii_case("boundary").then(|| String::from(value.trim().trim_matches('"')))
+	})
+}
+
+fn parse_headers(headers: &[u8]) -> Result<Part, MultipartError> {
+	let headers = String::from_utf8_lossy(headers);
+	let mut disposition = None;
+	let mut kind = None;
+	for line in headers.split("\r\n") {
+		let Some((name, value)) = line.split_once(':') else {
+			continue;
+		};
+		if name.trim().eq_ignore_ascii_case("content-disposition") {
+			disposition = Some(value.trim());
+		} else if name.trim().eq_ignore_ascii_case("content-type") {
+			kind = Some(String::from(value.trim()));
+		}
+	}
+
+	let disposition = disposition.ok_or_else(|| multipart_error("Part is missing a Content-Disposition header"))?;
+	let mut name = None;
+	let mut filename = None;
+	for (parameter, value) in disposition_parameters(disposition) {
+		if parameter.eq_ignore_ascii_case("name") {
+			name = Some(value);
+		} else if parameter.eq_ignore_ascii_case("filename") {
+			filename = Some(value);
+		}
+	}
+
+	Ok(Part {
+		name: name.ok_or_else(|| multipart_error("Part is missing a name"))?,
+		kind: kind.filter(|_| filename.is_some()),
+		filename,
+		sink: PartSink::Memory(BytesMut::new()),
+	})
+}
+
+/// Parses the parameters of a `Content-Disposition: form-data` header, decoding the escapes used by the HTML
+/// Standard in quoted values.
+fn disposition_parameters(disposition: &str) -> Vec<(String, String)> {
+	let mut parameters = Vec::new();
+	let mut rest = disposition.split_once(';').map_or("", |(_, rest)| rest);
+	while let Some((name, value)) = rest.split_once('=') {
+		let name = name.trim_start_matches([' ', '\t', ';']).trim();
+		let value = value.trim_start();
+		let (value, remaining) = match value.strip_prefix('"') {
+			Some(quoted) => {
+				let end = quoted.find('"').unwrap_or(quoted.len());
+				(&quoted[..end], quoted.get(end + 1..).unwrap_or_default())
+			}
+			None => value.split_once(';').unwrap_or((value, "")),
+		};
+		let value = value.replace("%0A", "\n").replace("%0D", "\r").replace("%22", "\"");
+		parameters.push((String::from(name), value));
+		rest = remaining;
+	}
+	parameters
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+	haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+fn multipart_error(message: &'static str) -> MultipartError {
+	MultipartError::Invalid(message)
+}
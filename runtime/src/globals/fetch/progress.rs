@@ -0,0 +1,66 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::future::Future;
+use std::pin::pin;
+
+use futures::channel::mpsc::UnboundedReceiver;
+use futures::future::{select, Either};
+use futures::StreamExt;
+use ion::conversions::ToValue;
+use ion::{Context, Error, Function, Object, ResultExc};
+use mozjs::jsapi::JSObject;
+
+use crate::event_loop::MicrotaskCheckpoint;
+
+/// Calls a progress callback with the number of bytes transferred so far, and the total if it is known.
+pub(crate) fn report_progress(cx: &Context, callback: *mut JSObject, loaded: u64, total: Option<u64>) -> ResultExc<()> {
+	if callback.is_null() {
+		return Ok(());
+	}
+	let Some(callback) = Function::from_object(cx, &cx.root(callback)) else {
+		return Ok(());
+	};
+
+	let progress = object!(cx, {
+		"lengthComputable": total.is_some(),
+		"loaded": loaded as f64,
+		"total": total.unwrap_or(0) as f64,
+	});
+
+	let _checkpoint = MicrotaskCheckpoint::new(cx);
+	match callback.call(cx, &Object::null(cx), &[progress.as_value(cx)]) {
+		Ok(_) => Ok(()),
+		Err(Some(report)) => Err(report.into_exception()),
+		Err(None) => Err(Error::none().into()),
+	}
+}
+
+/// Runs `future` to completion, reporting the progress received from `progress` to the callback as it arrives.
+pub(crate) async fn with_progress<F: Future>(
+	cx: &Context, callback: *mut JSObject, total: Option<u64>, progress: UnboundedReceiver<u64>, future: F,
+) -> ResultExc<F::Output> {
+	with_reports(progress, future, |loaded| report_progress(cx, callback, loaded, total)).await
+}
+
+/// Runs `future` to completion, passing each value received from `receiver` to `report` as it arrives.
+pub(crate) async fn with_reports<F: Future, T>(
+	mut receiver: UnboundedReceiver<T>, future: F, mut report: impl FnMut(T) -> ResultExc<()>,
+) -> ResultExc<F::Output> {
+	let mut future = pin!(future);
+	let output = loop {
+		match select(future.as_mut(), receiver.next()).await {
+			Either::Left((output, _)) => break output,
+			Either::Right((Some(value), _)) => report(value)?,
+			Either::Right((None, _)) => break future.await,
+		}
+	};
+
+	while let Ok(Some(value)) = receiver.try_next() {
+		report(value)?;
+	}
+	Ok(output)
+}
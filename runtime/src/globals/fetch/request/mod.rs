@@ -4,20 +4,29 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::mem::take;
+use std::ptr;
 use std::str::FromStr;
 
+use http::header::CONTENT_TYPE;
 use http::{HeaderMap, Method};
-use ion::class::Reflector;
+use http_body_util::BodyExt;
+use ion::class::{NativeObject, Reflector};
 use ion::function::Opt;
-use ion::{ClassDefinition, Context, Error, ErrorKind, Result};
-use mozjs::jsapi::{Heap, JSObject};
+use ion::{
+	BoxedHeap, ClassDefinition, Context, Error, ErrorKind, Exception, Local, Object, Promise, Result, TracedHeap,
+};
+use mozjs::jsapi::JSObject;
 pub use options::*;
 use url::Url;
 
 use crate::globals::abort::AbortSignal;
 use crate::globals::fetch::body::FetchBody;
+use crate::globals::fetch::form_data::FormData;
 use crate::globals::fetch::header::HeadersKind;
+use crate::globals::fetch::multipart::{boundary, parse_multipart, FormValue};
 use crate::globals::fetch::Headers;
+use crate::promise::future_to_promise;
 
 mod options;
 
@@ -33,7 +42,7 @@ pub enum RequestInfo<'cx> {
 pub struct Request {
 	reflector: Reflector,
 
-	pub(crate) headers: Box<Heap<*mut JSObject>>,
+	pub(crate) headers: BoxedHeap<*mut JSObject>,
 	pub(crate) body: FetchBody,
 	pub(crate) body_used: bool,
 
@@ -52,13 +61,21 @@ pub struct Request {
 	pub(crate) cache: RequestCache,
 	pub(crate) redirect: RequestRedirect,
 
+	#[ion(readonly)]
 	pub(crate) integrity: String,
 
 	pub(crate) unsafe_request: bool,
+	#[ion(readonly)]
 	pub(crate) keepalive: bool,
 
 	pub(crate) client_window: bool,
-	pub(crate) signal_object: Box<Heap<*mut JSObject>>,
+	pub(crate) signal_object: BoxedHeap<*mut JSObject>,
+
+	pub(crate) upload_progress: BoxedHeap<*mut JSObject>,
+	pub(crate) download_progress: BoxedHeap<*mut JSObject>,
+	pub(crate) informational: BoxedHeap<*mut JSObject>,
+	#[trace(no_trace)]
+	pub(crate) retry: Option<RetryOptions>,
 }
 
 #[js_class]
@@ -68,7 +85,7 @@ impl Request {
 		let mut fallback_cors = false;
 
 		let mut request = match info {
-			RequestInfo::Request(request) => request.clone(),
+			RequestInfo::Request(request) => request.clone(cx)?,
 			RequestInfo::String(url) => {
 				let url = Url::from_str(&url)?;
 				if url.username() != "" || url.password().is_some() {
@@ -80,7 +97,7 @@ impl Request {
 				Request {
 					reflector: Reflector::default(),
 
-					headers: Box::default(),
+					headers: BoxedHeap::new(ptr::null_mut()),
 					body: FetchBody::default(),
 					body_used: false,
 
@@ -102,7 +119,12 @@ impl Request {
 					keepalive: false,
 
 					client_window: true,
-					signal_object: Heap::boxed(AbortSignal::new_object(cx, Box::default())),
+					signal_object: BoxedHeap::new(AbortSignal::new_object(cx, Box::default())),
+
+					upload_progress: BoxedHeap::new(ptr::null_mut()),
+					download_progress: BoxedHeap::new(ptr::null_mut()),
+					informational: BoxedHeap::new(ptr::null_mut()),
+					retry: None,
 				}
 			}
 		};
@@ -158,6 +180,19 @@ impl Request {
 				request.signal_object.set(signal_object);
 			}
 
+			if let Some(callback) = init.on_upload_progress {
+				request.upload_progress.set(callback.to_object(cx).handle().get());
+			}
+			if let Some(callback) = init.on_download_progress {
+				request.download_progress.set(callback.to_object(cx).handle().get());
+			}
+			if let Some(callback) = init.on_informational {
+				request.informational.set(callback.to_object(cx).handle().get());
+			}
+			if let Some(retry) = init.retry {
+				request.retry = Some(retry);
+			}
+
 			if let Some(mut method) = init.method {
 				method.make_ascii_uppercase();
 				let method = Method::from_str(&method)?;
@@ -188,20 +223,25 @@ impl Request {
 			HeadersKind::Request
 		};
 
-		let mut headers = if let Some(headers) = headers {
+		let headers = if let Some(headers) = headers {
 			headers.into_headers(HeaderMap::new(), kind)?
+		} else if request.headers.get().is_null() {
+			Headers::new(kind)
 		} else {
-			Headers {
-				reflector: Reflector::default(),
-				headers: HeaderMap::new(),
-				kind,
-			}
+			let existing = Object::from(unsafe { Local::from_heap(&request.headers) });
+			Headers::from_list(Headers::get_private(cx, &existing)?.headers.borrow().clone(), kind)
 		};
 
 		if let Some(body) = body {
-			body.add_content_type_header(&mut headers.headers);
+			body.add_content_type_header(&mut headers.headers.borrow_mut());
 			request.body = body;
 		}
+		if request.keepalive && request.body.is_stream() {
+			return Err(Error::new(
+				"Keepalive requests cannot have a stream body",
+				ErrorKind::Type,
+			));
+		}
 		request.headers.set(Headers::new_object(cx, Box::new(headers)));
 
 		Ok(request)
@@ -257,16 +297,6 @@ impl Request {
 		self.redirect.to_string()
 	}
 
-	#[ion(get)]
-	pub fn get_integrity(&self) -> String {
-		self.integrity.clone()
-	}
-
-	#[ion(get)]
-	pub fn get_keepalive(&self) -> bool {
-		self.keepalive
-	}
-
 	#[ion(get)]
 	pub fn get_is_reload_navigation(&self) -> bool {
 		false
@@ -286,16 +316,69 @@ impl Request {
 	pub fn get_duplex(&self) -> String {
 		String::from("half")
 	}
+
+	#[ion(name = "formData")]
+	pub fn form_data<'cx>(&mut self, cx: &'cx Context) -> Option<Promise<'cx>> {
+		let this = TracedHeap::new(self.reflector().get());
+		let token = cx.token();
+		future_to_promise::<_, _, Exception>(
+			cx,
+			token.clone().guard(async move {
+				let cx2 = token.context();
+				let request = Object::from(this.to_local());
+				let request = Request::get_mut_private(&cx2, &request)?;
+				let entries = request.read_form_data(&cx2).await?;
+				Ok(FormData::from_form_values(&cx2, entries)?.handle().get())
+			}),
+		)
+	}
+}
+
+impl Request {
+	/// Reads the body as form data, parsing `multipart/form-data` bodies without holding large files in memory.
+	async fn read_form_data(&mut self, cx: &Context) -> Result<Vec<(String, FormValue)>> {
+		if self.body_used {
+			return Err(Error::new("Request body has already been used.", None));
+		}
+
+		let headers = Object::from(unsafe { Local::from_heap(&self.headers) });
+		let content_type = Headers::get_private(cx, &headers)?
+			.headers
+			.borrow()
+			.get(CONTENT_TYPE)
+			.and_then(|kind| kind.to_str().ok().map(String::from))
+			.unwrap_or_default();
+		let essence = content_type.split(';').next().unwrap_or_default().trim();
+
+		let body = take(&mut self.body).to_http_body();
+		self.body_used = true;
+		if let Some(boundary) = boundary(&content_type) {
+			Ok(parse_multipart(body, &boundary).await?.0)
+		} else if essence.eq_ignore_ascii_case("application/x-www-form-urlencoded") {
+			let bytes = body.collect().await.map_err(|error| Error::new(error.to_string(), None))?.to_bytes();
+			let entries = form_urlencoded::parse(&bytes).into_owned();
+			Ok(entries.map(|(name, value)| (name, FormValue::Text(value))).collect())
+		} else {
+			Err(Error::new(
+				"Request does not have a form data content type",
+				ErrorKind::Type,
+			))
+		}
+	}
 }
 
-impl Clone for Request {
-	fn clone(&self) -> Request {
+impl Request {
+	/// Clones the request, with a copy of its headers.
+	fn clone(&self, cx: &Context) -> Result<Request> {
 		let url = self.locations.last().unwrap().clone();
+		let headers = Object::from(unsafe { Local::from_heap(&self.headers) });
+		let headers = Headers::get_private(cx, &headers)?;
+		let headers = Headers::from_list(headers.headers.borrow().clone(), headers.kind);
 
-		Request {
+		Ok(Request {
 			reflector: Reflector::default(),
 
-			headers: Box::default(),
+			headers: BoxedHeap::new(Headers::new_object(cx, Box::new(headers))),
 			body: self.body.clone(),
 			body_used: self.body_used,
 
@@ -317,7 +400,12 @@ impl Clone for Request {
 			keepalive: self.keepalive,
 
 			client_window: self.client_window,
-			signal_object: Heap::boxed(self.signal_object.get()),
-		}
+			signal_object: BoxedHeap::new(self.signal_object.get()),
+
+			upload_progress: BoxedHeap::new(self.upload_progress.get()),
+			download_progress: BoxedHeap::new(self.download_progress.get()),
+			informational: BoxedHeap::new(self.informational.get()),
+			retry: self.retry,
+		})
 	}
 }
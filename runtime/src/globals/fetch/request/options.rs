@@ -9,13 +9,14 @@ use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
 use ion::conversions::FromValue;
-use ion::{Context, Error, ErrorKind, Result, Value};
+use ion::{Context, Error, ErrorKind, Function, Result, Value};
 use mozjs::jsapi::JSObject;
 use mozjs::jsval::JSVal;
 use url::Url;
 
 use crate::globals::fetch::body::FetchBody;
 use crate::globals::fetch::header::HeadersInit;
+use crate::globals::fetch::retry::RetryOptions;
 
 #[derive(Clone, Default, Debug, Traceable)]
 pub enum Referrer {
@@ -399,6 +400,11 @@ pub struct RequestInit<'cx> {
 	pub(crate) keepalive: Option<bool>,
 	pub(crate) signal: Option<*mut JSObject>,
 
+	pub(crate) on_upload_progress: Option<Function<'cx>>,
+	pub(crate) on_download_progress: Option<Function<'cx>>,
+	pub(crate) on_informational: Option<Function<'cx>>,
+	pub(crate) retry: Option<RetryOptions>,
+
 	#[expect(dead_code)]
 	pub(crate) duplex: Option<RequestDuplex>,
 	#[expect(dead_code)]
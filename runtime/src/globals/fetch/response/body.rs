@@ -4,10 +4,13 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use bytes::Bytes;
+use futures::channel::mpsc::UnboundedSender;
 use http_body_util::BodyExt;
-use ion::Result;
+use ion::{Error, Result};
 
 use crate::globals::fetch::body::{Body, FetchBody};
+use crate::globals::fetch::multipart::{parse_multipart, FormValue};
 
 #[derive(Traceable)]
 pub enum ResponseBody {
@@ -16,12 +19,42 @@ pub enum ResponseBody {
 }
 
 impl ResponseBody {
-	pub async fn read_to_bytes(self) -> Result<Vec<u8>> {
-		let body = match self {
+	/// Checks if the body is being received over the network.
+	pub fn is_incoming(&self) -> bool {
+		matches!(self, ResponseBody::Hyper(body) if body.is_received())
+	}
+
+	fn into_http_body(self) -> Body {
+		match self {
 			ResponseBody::Fetch(body) => body.to_http_body(),
 			ResponseBody::Hyper(body) => body,
-		};
+		}
+	}
+
+	pub async fn read_to_bytes(self) -> Result<Bytes> {
+		match self.into_http_body().collect().await {
+			Ok(collected) => Ok(collected.to_bytes()),
+			Err(error) => Err(Error::new(error.to_string(), None)),
+		}
+	}
+
+	/// Reads the body, sending the number of bytes read so far to `progress` after each chunk.
+	pub async fn read_to_bytes_with_progress(self, progress: UnboundedSender<u64>) -> Result<Bytes> {
+		let mut body = self.into_http_body();
+
+		let mut bytes = Vec::new();
+		while let Some(frame) = body.frame().await {
+			let frame = frame.map_err(|error| Error::new(error.to_string(), None))?;
+			if let Ok(data) = frame.into_data() {
+				bytes.extend_from_slice(&data);
+				let _ = progress.unbounded_send(bytes.len() as u64);
+			}
+		}
+		Ok(Bytes::from(bytes))
+	}
 
-		Ok(body.collect().await?.to_bytes().to_vec())
+	/// Parses the body as `multipart/form-data` as it is received, returning the entries and the number of bytes read.
+	pub(crate) async fn read_multipart(self, boundary: &str) -> Result<(Vec<(String, FormValue)>, u64)> {
+		parse_multipart(self.into_http_body(), boundary).await
 	}
 }
@@ -4,22 +4,35 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::ptr;
+use std::time::Duration;
+
 use bytes::Bytes;
+use futures::channel::mpsc;
+use http::header::{CONTENT_LENGTH, CONTENT_TYPE};
 use http::{HeaderMap, StatusCode};
 use hyper::ext::ReasonPhrase;
 use ion::class::{NativeObject, Reflector};
 use ion::function::Opt;
 use ion::typedarray::ArrayBufferWrapper;
-use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Promise, Result, TracedHeap};
-use mozjs::jsapi::{Heap, JSObject};
+use ion::{
+	BoxedHeap, ClassDefinition, Context, Error, ErrorKind, Exception, Local, Object, Promise, Result, ResultExc,
+	TracedHeap,
+};
+use mozjs::jsapi::JSObject;
 pub use options::*;
 use url::Url;
 
 use crate::globals::fetch::body::{Body, FetchBody};
+use crate::globals::fetch::form_data::FormData;
 use crate::globals::fetch::header::HeadersKind;
+use crate::globals::fetch::multipart::{boundary, FormValue};
+use crate::globals::fetch::progress::with_progress;
 use crate::globals::fetch::response::body::ResponseBody;
+use crate::globals::fetch::timing::ResponseTiming;
 use crate::globals::fetch::Headers;
-use crate::promise::future_to_promise;
+use crate::promise::{future_to_promise, run_blocking};
+use crate::resources::record_bytes_fetched;
 
 mod body;
 mod options;
@@ -28,12 +41,13 @@ mod options;
 pub struct Response {
 	reflector: Reflector,
 
-	pub(crate) headers: Box<Heap<*mut JSObject>>,
+	pub(crate) headers: BoxedHeap<*mut JSObject>,
 	pub(crate) body: Option<ResponseBody>,
 
 	pub(crate) kind: ResponseKind,
 	#[trace(no_trace)]
 	pub(crate) url: Option<Url>,
+	#[ion(readonly)]
 	pub(crate) redirected: bool,
 
 	#[trace(no_trace)]
@@ -41,6 +55,14 @@ pub struct Response {
 	pub(crate) status_text: Option<String>,
 
 	pub(crate) range_requested: bool,
+
+	#[trace(no_trace)]
+	pub(crate) url_list: Vec<Url>,
+	#[trace(no_trace)]
+	pub(crate) timing: Option<ResponseTiming>,
+	pub(crate) download_progress: BoxedHeap<*mut JSObject>,
+	#[trace(no_trace)]
+	pub(crate) error: Option<NetworkError>,
 }
 
 impl Response {
@@ -56,7 +78,7 @@ impl Response {
 		let response = Response {
 			reflector: Reflector::default(),
 
-			headers: Box::default(),
+			headers: BoxedHeap::new(ptr::null_mut()),
 			body: Some(ResponseBody::Hyper(body)),
 
 			kind: ResponseKind::default(),
@@ -67,6 +89,11 @@ impl Response {
 			status_text,
 
 			range_requested: false,
+
+			url_list: Vec::new(),
+			timing: None,
+			download_progress: BoxedHeap::new(ptr::null_mut()),
+			error: None,
 		};
 
 		(parts.headers, response)
@@ -76,7 +103,7 @@ impl Response {
 		Response {
 			reflector: Reflector::default(),
 
-			headers: Box::default(),
+			headers: BoxedHeap::new(ptr::null_mut()),
 			body: Some(ResponseBody::Hyper(Body::from(bytes))),
 
 			kind: ResponseKind::Basic,
@@ -87,8 +114,54 @@ impl Response {
 			status_text: Some(String::from("OK")),
 
 			range_requested: false,
+
+			url_list: Vec::new(),
+			timing: None,
+			download_progress: BoxedHeap::new(ptr::null_mut()),
+			error: None,
 		}
 	}
+
+	/// Moves the contents of the response into a new [Response], leaving its body used.
+	pub(crate) fn take(&mut self) -> Response {
+		Response {
+			reflector: Reflector::default(),
+
+			headers: BoxedHeap::new(self.headers.get()),
+			body: self.body.take(),
+
+			kind: self.kind,
+			url: self.url.clone(),
+			redirected: self.redirected,
+
+			status: self.status,
+			status_text: self.status_text.clone(),
+
+			range_requested: self.range_requested,
+
+			url_list: self.url_list.clone(),
+			timing: self.timing,
+			download_progress: BoxedHeap::new(self.download_progress.get()),
+			error: self.error.take(),
+		}
+	}
+
+	/// Reads the whole body into memory, replacing it with the bytes read so it can still be consumed.
+	pub(crate) async fn buffer(&mut self, cx: &Context) -> Result<Bytes> {
+		let bytes = match self.body.take() {
+			Some(body) => {
+				let incoming = body.is_incoming();
+				let bytes = body.read_to_bytes().await?;
+				if incoming {
+					record_bytes_fetched(cx, bytes.len() as u64);
+				}
+				bytes
+			}
+			None => Bytes::new(),
+		};
+		self.body = Some(ResponseBody::Hyper(Body::from(bytes.clone())));
+		Ok(bytes)
+	}
 }
 
 #[js_class]
@@ -100,7 +173,7 @@ impl Response {
 		let mut response = Response {
 			reflector: Reflector::default(),
 
-			headers: Box::default(),
+			headers: BoxedHeap::new(ptr::null_mut()),
 			body: Some(ResponseBody::Hyper(Body::Empty)),
 
 			kind: ResponseKind::default(),
@@ -111,9 +184,14 @@ impl Response {
 			status_text: init.status_text,
 
 			range_requested: false,
+
+			url_list: Vec::new(),
+			timing: None,
+			download_progress: BoxedHeap::new(ptr::null_mut()),
+			error: None,
 		};
 
-		let mut headers = init.headers.into_headers(HeaderMap::new(), HeadersKind::Response)?;
+		let headers = init.headers.into_headers(HeaderMap::new(), HeadersKind::Response)?;
 
 		if let Some(body) = body {
 			if init.status == StatusCode::NO_CONTENT
@@ -126,7 +204,7 @@ impl Response {
 				));
 			}
 
-			body.add_content_type_header(&mut headers.headers);
+			body.add_content_type_header(&mut headers.headers.borrow_mut());
 			response.body = Some(ResponseBody::Fetch(body));
 		}
 
@@ -145,11 +223,6 @@ impl Response {
 		self.url.as_ref().map(Url::to_string).unwrap_or_default()
 	}
 
-	#[ion(get)]
-	pub fn get_redirected(&self) -> bool {
-		self.redirected
-	}
-
 	#[ion(get)]
 	pub fn get_status(&self) -> u16 {
 		self.status.as_ref().map(StatusCode::as_u16).unwrap_or_default()
@@ -170,47 +243,150 @@ impl Response {
 		self.headers.get()
 	}
 
+	/// Returns the URLs that were fetched for the response, in order, including any redirects.
+	#[ion(get)]
+	pub fn get_url_list(&self) -> Vec<String> {
+		self.url_list.iter().map(Url::to_string).collect()
+	}
+
+	/// Returns the time taken by each stage of the fetch in milliseconds, or `null` if the response was not fetched
+	/// over the network.
+	#[ion(get)]
+	pub fn get_timing<'cx>(&self, cx: &'cx Context) -> Option<Object<'cx>> {
+		self.timing.map(|timing| timing.to_object(cx))
+	}
+
 	#[ion(get)]
 	pub fn get_body_used(&self) -> bool {
 		self.body.is_none()
 	}
 
-	async fn read_to_bytes(&mut self) -> Result<Vec<u8>> {
+	async fn read_to_bytes(&mut self, cx: &Context) -> ResultExc<Bytes> {
 		if self.body.is_none() {
-			return Err(Error::new("Response body has already been used.", None));
+			return Err(Error::new("Response body has already been used.", None).into());
+		}
+		let body = self.body.take().unwrap();
+		let incoming = body.is_incoming();
+
+		let callback = self.download_progress.get();
+		let bytes = if callback.is_null() {
+			body.read_to_bytes().await?
+		} else {
+			let headers = Object::from(unsafe { Local::from_heap(&self.headers) });
+			let total = Headers::get_private(cx, &headers)?
+				.headers
+				.borrow()
+				.get(CONTENT_LENGTH)
+				.and_then(|length| length.to_str().ok()?.parse().ok());
+
+			let (sender, receiver) = mpsc::unbounded();
+			with_progress(cx, callback, total, receiver, body.read_to_bytes_with_progress(sender)).await??
+		};
+
+		if incoming {
+			record_bytes_fetched(cx, bytes.len() as u64);
+		}
+		Ok(bytes)
+	}
+
+	/// Reads the body as form data, parsing `multipart/form-data` bodies as they are received.
+	async fn read_form_data(&mut self, cx: &Context) -> ResultExc<Vec<(String, FormValue)>> {
+		let headers = Object::from(unsafe { Local::from_heap(&self.headers) });
+		let content_type = Headers::get_private(cx, &headers)?
+			.headers
+			.borrow()
+			.get(CONTENT_TYPE)
+			.and_then(|kind| kind.to_str().ok().map(String::from))
+			.unwrap_or_default();
+		let essence = content_type.split(';').next().unwrap_or_default().trim();
+
+		if let Some(boundary) = boundary(&content_type) {
+			let Some(body) = self.body.take() else {
+				return Err(Error::new("Response body has already been used.", None).into());
+			};
+			let incoming = body.is_incoming();
+			let (entries, read) = body.read_multipart(&boundary).await?;
+			if incoming {
+				record_bytes_fetched(cx, read);
+			}
+			Ok(entries)
+		} else if essence.eq_ignore_ascii_case("application/x-www-form-urlencoded") {
+			let bytes = self.read_to_bytes(cx).await?;
+			let entries = form_urlencoded::parse(&bytes).into_owned();
+			Ok(entries.map(|(name, value)| (name, FormValue::Text(value))).collect())
+		} else {
+			Err(Error::new("Response does not have a form data content type", ErrorKind::Type).into())
 		}
-		self.body.take().unwrap().read_to_bytes().await
 	}
 
 	#[ion(name = "arrayBuffer")]
 	pub fn array_buffer<'cx>(&mut self, cx: &'cx Context) -> Option<Promise<'cx>> {
 		let this = TracedHeap::new(self.reflector().get());
-		let cx2 = unsafe { Context::new_unchecked(cx.as_ptr()) };
-		future_to_promise::<_, _, Error>(cx, async move {
-			let response = Object::from(this.to_local());
-			let response = Response::get_mut_private(&cx2, &response)?;
-			let bytes = response.read_to_bytes().await?;
-			Ok(ArrayBufferWrapper::from(bytes))
-		})
+		let token = cx.token();
+		future_to_promise::<_, _, Exception>(
+			cx,
+			token.clone().guard(async move {
+				let cx2 = token.context();
+				let response = Object::from(this.to_local());
+				let response = Response::get_mut_private(&cx2, &response)?;
+				let bytes = response.read_to_bytes(&cx2).await?;
+				Ok(ArrayBufferWrapper::from(bytes))
+			}),
+		)
 	}
 
 	pub fn text<'cx>(&mut self, cx: &'cx Context) -> Option<Promise<'cx>> {
 		let this = TracedHeap::new(self.reflector().get());
-		let cx2 = unsafe { Context::new_unchecked(cx.as_ptr()) };
-		future_to_promise::<_, _, Error>(cx, async move {
-			let response = Object::from(this.to_local());
-			let response = Response::get_mut_private(&cx2, &response)?;
-			let bytes = response.read_to_bytes().await?;
-			String::from_utf8(bytes).map_err(|e| Error::new(format!("Invalid UTF-8 sequence: {e}"), None))
-		})
+		let token = cx.token();
+		future_to_promise::<_, _, Exception>(
+			cx,
+			token.clone().guard(async move {
+				let cx2 = token.context();
+				let response = Object::from(this.to_local());
+				let response = Response::get_mut_private(&cx2, &response)?;
+				let bytes = response.read_to_bytes(&cx2).await?;
+				let text = run_blocking(move |_| String::from_utf8(Vec::from(bytes))).await;
+				Ok(text.map_err(|e| Error::new(format!("Invalid UTF-8 sequence: {e}"), None))?)
+			}),
+		)
+	}
+
+	#[ion(name = "formData")]
+	pub fn form_data<'cx>(&mut self, cx: &'cx Context) -> Option<Promise<'cx>> {
+		let this = TracedHeap::new(self.reflector().get());
+		let token = cx.token();
+		future_to_promise::<_, _, Exception>(
+			cx,
+			token.clone().guard(async move {
+				let cx2 = token.context();
+				let response = Object::from(this.to_local());
+				let response = Response::get_mut_private(&cx2, &response)?;
+				let entries = response.read_form_data(&cx2).await?;
+				Ok(FormData::from_form_values(&cx2, entries)?.handle().get())
+			}),
+		)
 	}
 }
 
+/// Reason for a network error, which determines the error a fetch is rejected with.
+#[derive(Debug)]
+pub(crate) enum NetworkError {
+	/// The response headers were not received within the timeout.
+	Timeout(Duration),
+	/// The request could not be sent, or its response could not be received.
+	Transport(Error),
+}
+
+/// Returns a network error with the reason it occurred.
+pub(crate) fn network_error_with(error: NetworkError) -> Response {
+	Response { error: Some(error), ..network_error() }
+}
+
 pub fn network_error() -> Response {
 	Response {
 		reflector: Reflector::default(),
 
-		headers: Box::default(),
+		headers: BoxedHeap::new(ptr::null_mut()),
 		body: None,
 
 		kind: ResponseKind::Error,
@@ -221,5 +397,10 @@ pub fn network_error() -> Response {
 		status_text: None,
 
 		range_requested: false,
+
+		url_list: Vec::new(),
+		timing: None,
+		download_progress: BoxedHeap::new(ptr::null_mut()),
+		error: None,
 	}
 }
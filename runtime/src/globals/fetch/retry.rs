@@ -0,0 +1,110 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::hash::{BuildHasher, RandomState};
+use std::iter::once;
+use std::time::{Duration, SystemTime};
+
+use headers::{Date, Header};
+use http::header::RETRY_AFTER;
+use http::{HeaderValue, Method, StatusCode};
+use ion::{ClassDefinition, Context, Local, Object};
+use mozjs::conversions::ConversionBehavior;
+
+use crate::globals::fetch::response::{Response, ResponseKind};
+use crate::globals::fetch::Headers;
+
+/// Maximum delay between attempts, regardless of the options.
+const MAX_DELAY: Duration = Duration::from_secs(300);
+
+/// Options for retrying idempotent requests which fail with a network error or a transient status.
+#[derive(Clone, Copy, Debug, FromValue)]
+pub struct RetryOptions {
+	/// Maximum number of attempts, including the first.
+	#[ion(convert = ConversionBehavior::EnforceRange, default = 3)]
+	pub(crate) attempts: u32,
+	/// Delay before the first retry in milliseconds, which doubles for each subsequent retry.
+	#[ion(default = 100.0)]
+	pub(crate) backoff: f64,
+	/// Maximum delay between attempts in milliseconds, which is at most 5 minutes.
+	///
+	/// Responses with a `Retry-After` header exceeding this are not retried.
+	#[ion(default = 10000.0)]
+	pub(crate) max_backoff: f64,
+}
+
+impl RetryOptions {
+	/// Returns the delay before the next attempt, or [None] if the response should not be retried.
+	pub(crate) fn delay(&self, cx: &Context, method: &Method, response: &Response, attempt: u32) -> Option<Duration> {
+		if attempt >= self.attempts || !is_idempotent(method) || !is_transient(response) {
+			return None;
+		}
+
+		let max_backoff = from_millis(self.max_backoff);
+		if let Some(retry_after) = retry_after(cx, response) {
+			return (retry_after <= max_backoff).then_some(retry_after);
+		}
+
+		let backoff = from_millis(self.backoff);
+		let delay = backoff.saturating_mul(2u32.saturating_pow(attempt - 1)).min(max_backoff);
+		Some(delay / 2 + delay.mul_f64(jitter() / 2.0))
+	}
+}
+
+/// Converts a delay in milliseconds into a [Duration], clamped to [MAX_DELAY].
+/// Negative and NaN delays are treated as 0.
+fn from_millis(millis: f64) -> Duration {
+	match Duration::try_from_secs_f64(millis / 1000.0) {
+		Ok(delay) => delay.min(MAX_DELAY),
+		Err(_) if millis > 0.0 => MAX_DELAY,
+		Err(_) => Duration::ZERO,
+	}
+}
+
+fn is_idempotent(method: &Method) -> bool {
+	matches!(
+		*method,
+		Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE | Method::TRACE
+	)
+}
+
+fn is_transient(response: &Response) -> bool {
+	response.kind == ResponseKind::Error
+		|| matches!(
+			response.status,
+			Some(
+				StatusCode::REQUEST_TIMEOUT
+					| StatusCode::TOO_MANY_REQUESTS
+					| StatusCode::INTERNAL_SERVER_ERROR
+					| StatusCode::BAD_GATEWAY
+					| StatusCode::SERVICE_UNAVAILABLE
+					| StatusCode::GATEWAY_TIMEOUT
+			)
+		)
+}
+
+/// Parses the `Retry-After` header, which is either a number of seconds or an HTTP date.
+fn retry_after(cx: &Context, response: &Response) -> Option<Duration> {
+	if response.headers.get().is_null() {
+		return None;
+	}
+	let headers = Object::from(unsafe { Local::from_heap(&response.headers) });
+	let headers = Headers::get_private(cx, &headers).ok()?;
+	let headers = headers.headers.borrow();
+	let value = headers.get(RETRY_AFTER)?;
+
+	if let Some(seconds) = value.to_str().ok().and_then(|value| value.trim().parse().ok()) {
+		return Some(Duration::from_secs(seconds));
+	}
+	let date = SystemTime::from(Date::decode(&mut once::<&HeaderValue>(value)).ok()?);
+	Some(date.duration_since(SystemTime::now()).unwrap_or_default())
+}
+
+/// Returns a random number between 0 and 1.
+fn jitter() -> f64 {
+	let random = RandomState::new().hash_one(SystemTime::now());
+	(random >> 11) as f64 / (1u64 << 53) as f64
+}
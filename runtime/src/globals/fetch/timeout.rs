@@ -0,0 +1,95 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use ion::function::{Enforce, Opt};
+use ion::{Context, Exception};
+use mozjs::jsapi::JSPropertySpec;
+
+use crate::globals::abort::dom_error;
+use crate::ContextExt;
+
+/// Default timeouts of the requests made by `fetch`, which bound them even if they are not given an
+/// [AbortSignal](crate::globals::abort::AbortSignal).
+///
+/// Scripts can change them in milliseconds through `fetch.defaults`, where `null` removes a timeout.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FetchTimeouts {
+	/// Maximum time taken to resolve a host and connect to it.
+	/// This only applies to the default [HyperClient](crate::globals::fetch::HyperClient) of the runtime.
+	pub connect: Option<Duration>,
+	/// Maximum time from sending a request until its response headers are received.
+	/// Requests which exceed it fail with a network error, and may be retried, before the fetch is rejected with a
+	/// `TimeoutError`.
+	pub response_headers: Option<Duration>,
+	/// Maximum time from the start of a fetch until its response headers are received, including redirects and retries.
+	/// Fetches which exceed it are rejected with a `TimeoutError`.
+	pub total: Option<Duration>,
+}
+
+/// Timeouts of a runtime, which are shared with the connector of its default client.
+pub(crate) type SharedTimeouts = Arc<Mutex<FetchTimeouts>>;
+
+pub(crate) fn fetch_timeouts(cx: &Context) -> FetchTimeouts {
+	*unsafe { &cx.get_private().fetch_timeouts }.lock().unwrap()
+}
+
+pub(crate) fn timeout_error(cx: &Context, timeout: Duration) -> Exception {
+	let message = format!("Fetch timed out after {}ms", timeout.as_millis());
+	Exception::Other(dom_error(cx, "TimeoutError", &message).get())
+}
+
+fn millis(timeout: Option<Duration>) -> Option<f64> {
+	timeout.map(|timeout| timeout.as_secs_f64() * 1000.0)
+}
+
+fn update<F>(cx: &Context, field: F, timeout: Option<Option<Enforce<u64>>>)
+where
+	F: FnOnce(&mut FetchTimeouts) -> &mut Option<Duration>,
+{
+	let mut timeouts = unsafe { &cx.get_private().fetch_timeouts }.lock().unwrap();
+	*field(&mut timeouts) = timeout.flatten().map(|Enforce(timeout)| Duration::from_millis(timeout));
+}
+
+#[js_fn]
+fn get_connect_timeout(cx: &Context) -> Option<f64> {
+	millis(fetch_timeouts(cx).connect)
+}
+
+#[js_fn]
+fn set_connect_timeout(cx: &Context, Opt(timeout): Opt<Option<Enforce<u64>>>) {
+	update(cx, |timeouts| &mut timeouts.connect, timeout);
+}
+
+#[js_fn]
+fn get_headers_timeout(cx: &Context) -> Option<f64> {
+	millis(fetch_timeouts(cx).response_headers)
+}
+
+#[js_fn]
+fn set_headers_timeout(cx: &Context, Opt(timeout): Opt<Option<Enforce<u64>>>) {
+	update(cx, |timeouts| &mut timeouts.response_headers, timeout);
+}
+
+#[js_fn]
+fn get_timeout(cx: &Context) -> Option<f64> {
+	millis(fetch_timeouts(cx).total)
+}
+
+#[js_fn]
+fn set_timeout(cx: &Context, Opt(timeout): Opt<Option<Enforce<u64>>>) {
+	update(cx, |timeouts| &mut timeouts.total, timeout);
+}
+
+/// Accessors of `fetch.defaults`.
+pub(crate) const DEFAULTS: &[JSPropertySpec] = &[
+	property_spec_getter_setter!(get_connect_timeout, set_connect_timeout, "connectTimeout"),
+	property_spec_getter_setter!(get_headers_timeout, set_headers_timeout, "headersTimeout"),
+	property_spec_getter_setter!(get_timeout, set_timeout, "timeout"),
+	JSPropertySpec::ZERO,
+];
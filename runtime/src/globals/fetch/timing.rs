@@ -0,0 +1,210 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::{Duration, Instant};
+use std::{io, task};
+
+use http::Uri;
+use hyper::rt::{Read, ReadBufCursor, Write};
+use hyper_rustls::{HttpsConnector, MaybeHttpsStream};
+use hyper_util::client::legacy::connect::{Connected, Connection, HttpConnector};
+use hyper_util::rt::TokioIo;
+use ion::{Context, Object};
+use tokio::net::TcpStream;
+use tokio::time;
+use tower_service::Service;
+
+use crate::globals::fetch::dns::{with_lookup_timing, Resolver};
+use crate::globals::fetch::timeout::SharedTimeouts;
+
+type BoxError = Box<dyn Error + Send + Sync>;
+type Connecting<T> = Pin<Box<dyn Future<Output = Result<T, BoxError>> + Send>>;
+
+/// Time taken to establish a connection. This is zero for connections reused from the pool.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ConnectionTiming {
+	pub dns: Duration,
+	pub connect: Duration,
+	pub tls: Duration,
+}
+
+/// Timing of a connection, which is added to the extensions of each response received over it, but only reported by
+/// the first.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SharedConnectionTiming(Arc<Mutex<Option<ConnectionTiming>>>);
+
+impl SharedConnectionTiming {
+	/// Removes the timing of the connection, so it is only reported once.
+	pub(crate) fn take(&self) -> Option<ConnectionTiming> {
+		self.0.lock().unwrap().take()
+	}
+
+	fn set_tls(&self, tls: Duration) {
+		if let Some(timing) = self.0.lock().unwrap().as_mut() {
+			timing.tls = tls.saturating_sub(timing.dns + timing.connect);
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ResponseTiming {
+	pub connection: ConnectionTiming,
+	/// Time from sending the request until the response headers were received.
+	pub first_byte: Duration,
+	/// Time from the start of the fetch until the response headers were received, including redirects.
+	pub total: Duration,
+}
+
+impl ResponseTiming {
+	pub(crate) fn to_object<'cx>(&self, cx: &'cx Context) -> Object<'cx> {
+		object!(cx, {
+			"dns": millis(self.connection.dns),
+			"connect": millis(self.connection.connect),
+			"tls": millis(self.connection.tls),
+			"firstByte": millis(self.first_byte),
+			"total": millis(self.total),
+		})
+	}
+}
+
+fn millis(duration: Duration) -> f64 {
+	duration.as_secs_f64() * 1000.0
+}
+
+/// Connects over TCP within the connect timeout, recording the time taken for resolution and connection.
+#[derive(Clone)]
+pub struct TcpConnector {
+	http: HttpConnector<Resolver>,
+	timeouts: SharedTimeouts,
+}
+
+impl TcpConnector {
+	pub(crate) fn new(http: HttpConnector<Resolver>, timeouts: SharedTimeouts) -> TcpConnector {
+		TcpConnector { http, timeouts }
+	}
+}
+
+impl Service<Uri> for TcpConnector {
+	type Response = TimedStream;
+	type Error = BoxError;
+	type Future = Connecting<TimedStream>;
+
+	fn poll_ready(&mut self, cx: &mut task::Context<'_>) -> Poll<Result<(), BoxError>> {
+		self.http.poll_ready(cx).map_err(BoxError::from)
+	}
+
+	fn call(&mut self, dst: Uri) -> Self::Future {
+		let connecting = self.http.call(dst);
+		let timeout = self.timeouts.lock().unwrap().connect;
+
+		Box::pin(async move {
+			let start = Instant::now();
+			let (stream, dns) = with_lookup_timing(async {
+				match timeout {
+					Some(timeout) => Ok::<_, BoxError>(time::timeout(timeout, connecting).await??),
+					None => connecting.await.map_err(BoxError::from),
+				}
+			})
+			.await;
+			let stream = stream?;
+			let elapsed = start.elapsed();
+
+			let dns = dns.unwrap_or_default();
+			let timing = ConnectionTiming {
+				dns,
+				connect: elapsed.saturating_sub(dns),
+				tls: Duration::ZERO,
+			};
+			Ok(TimedStream {
+				stream,
+				timing: SharedConnectionTiming(Arc::new(Mutex::new(Some(timing)))),
+			})
+		})
+	}
+}
+
+/// TCP stream which adds the timing of its connection to the extensions of responses received over it.
+pub struct TimedStream {
+	stream: TokioIo<TcpStream>,
+	timing: SharedConnectionTiming,
+}
+
+impl Connection for TimedStream {
+	fn connected(&self) -> Connected {
+		self.stream.connected().extra(self.timing.clone())
+	}
+}
+
+impl Read for TimedStream {
+	fn poll_read(self: Pin<&mut Self>, cx: &mut task::Context<'_>, buf: ReadBufCursor<'_>) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.get_mut().stream).poll_read(cx, buf)
+	}
+}
+
+impl Write for TimedStream {
+	fn poll_write(self: Pin<&mut Self>, cx: &mut task::Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+		Pin::new(&mut self.get_mut().stream).poll_write(cx, buf)
+	}
+
+	fn poll_flush(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.get_mut().stream).poll_flush(cx)
+	}
+
+	fn poll_shutdown(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.get_mut().stream).poll_shutdown(cx)
+	}
+
+	fn is_write_vectored(&self) -> bool {
+		self.stream.is_write_vectored()
+	}
+
+	fn poll_write_vectored(
+		self: Pin<&mut Self>, cx: &mut task::Context<'_>, bufs: &[io::IoSlice<'_>],
+	) -> Poll<io::Result<usize>> {
+		Pin::new(&mut self.get_mut().stream).poll_write_vectored(cx, bufs)
+	}
+}
+
+/// Connects over TCP and TLS, recording the time taken for the TLS handshake.
+#[derive(Clone)]
+pub struct TimedConnector {
+	https: HttpsConnector<TcpConnector>,
+}
+
+impl TimedConnector {
+	pub(crate) fn new(https: HttpsConnector<TcpConnector>) -> TimedConnector {
+		TimedConnector { https }
+	}
+}
+
+impl Service<Uri> for TimedConnector {
+	type Response = MaybeHttpsStream<TimedStream>;
+	type Error = BoxError;
+	type Future = Connecting<MaybeHttpsStream<TimedStream>>;
+
+	fn poll_ready(&mut self, cx: &mut task::Context<'_>) -> Poll<Result<(), BoxError>> {
+		self.https.poll_ready(cx)
+	}
+
+	fn call(&mut self, dst: Uri) -> Self::Future {
+		let connecting = self.https.call(dst);
+
+		Box::pin(async move {
+			let start = Instant::now();
+			let stream = connecting.await?;
+
+			if let MaybeHttpsStream::Https(tls) = &stream {
+				tls.inner().get_ref().0.inner().timing.set_tls(start.elapsed());
+			}
+			Ok(stream)
+		})
+	}
+}
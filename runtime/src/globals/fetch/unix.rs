@@ -0,0 +1,64 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#[cfg(unix)]
+use std::path::PathBuf;
+
+use http::Uri;
+use hyper::body::Incoming;
+#[cfg(unix)]
+use hyper::client::conn::http1;
+#[cfg(unix)]
+use hyper_util::rt::TokioIo;
+use ion::Error;
+#[cfg(unix)]
+use percent_encoding::percent_decode_str;
+#[cfg(unix)]
+use tokio::net::UnixStream;
+use url::{Position, Url};
+
+use crate::globals::fetch::body::Body;
+
+/// Scheme of URLs which send HTTP requests over a Unix domain socket.
+///
+/// The host is the percent-encoded path of the socket, such as `http+unix://%2Fvar%2Frun%2Fdocker.sock/info`.
+pub(crate) const UNIX_SCHEME: &str = "http+unix";
+
+#[cfg(unix)]
+fn socket_path(url: &Url) -> Option<PathBuf> {
+	let host = url.host_str()?;
+	let path = percent_decode_str(host).decode_utf8().ok()?;
+	Some(PathBuf::from(path.into_owned()))
+}
+
+/// Returns the path and query of the URL, which are sent as the target of the request.
+pub(crate) fn request_target(url: &Url) -> Option<Uri> {
+	let target = &url[Position::BeforePath..Position::AfterQuery];
+	if target.starts_with('/') {
+		target.parse().ok()
+	} else {
+		format!("/{target}").parse().ok()
+	}
+}
+
+/// Sends the request over a new connection to the socket.
+#[cfg(unix)]
+pub(crate) async fn send(url: &Url, request: hyper::Request<Body>) -> Result<hyper::Response<Incoming>, Error> {
+	let path = socket_path(url).ok_or_else(|| Error::new("Invalid Unix socket path", None))?;
+	let stream = UnixStream::connect(&path)
+		.await
+		.map_err(|error| Error::new(format!("Failed to connect to {}: {error}", path.display()), None))?;
+	let (mut sender, connection) = http1::handshake(TokioIo::new(stream))
+		.await
+		.map_err(|error| Error::new(error.to_string(), None))?;
+	tokio::spawn(connection);
+	sender.send_request(request).await.map_err(|error| Error::new(error.to_string(), None))
+}
+
+#[cfg(not(unix))]
+pub(crate) async fn send(_: &Url, _: hyper::Request<Body>) -> Result<hyper::Response<Incoming>, Error> {
+	Err(Error::new("Unix sockets are not supported on this platform", None))
+}
@@ -4,10 +4,17 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::cell::RefCell;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+use std::{env, fs};
 
 use bytes::{BufMut, Bytes, BytesMut};
 use encoding_rs::UTF_8;
+use futures::{stream, Stream};
 use ion::class::Reflector;
 use ion::conversions::FromValue;
 use ion::format::NEWLINE;
@@ -15,8 +22,15 @@ use ion::function::{Clamp, Opt};
 use ion::typedarray::{ArrayBuffer, ArrayBufferView, ArrayBufferWrapper, Uint8ArrayWrapper};
 use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Promise, Result, Value};
 use mozjs::jsapi::JSObject;
+#[cfg(feature = "tokio-promise")]
+use tokio::runtime::Handle;
+use tracing::warn;
+use uuid::Uuid;
 
-use crate::promise::future_to_promise;
+use crate::config::CONFIG;
+use crate::globals::streams::readable::ReadableStream;
+use crate::globals::url::ObjectUrlEntry;
+use crate::promise::{future_to_promise, run_blocking};
 
 #[derive(Debug)]
 pub enum BufferSource<'cx> {
@@ -155,43 +169,339 @@ fn validate_kind(kind: Option<String>) -> Option<String> {
 		})
 }
 
+const STREAM_CHUNK_SIZE: u64 = 64 * 1024;
+
+/// Deletes the temporary file once every [FileSnapshot] referring to it has been dropped.
+#[derive(Debug)]
+struct TemporaryFile(PathBuf);
+
+impl Drop for TemporaryFile {
+	fn drop(&mut self) {
+		let _ = fs::remove_file(&self.0);
+	}
+}
+
+/// Represents a snapshot of a range of a file on disk, which is read lazily.
+///
+/// Reads fail if the file has been modified since the snapshot was taken.
+#[derive(Clone, Debug)]
+pub struct FileSnapshot {
+	path: Arc<Path>,
+	offset: u64,
+	len: u64,
+	modified: Option<SystemTime>,
+	temporary: Option<Arc<TemporaryFile>>,
+}
+
+impl FileSnapshot {
+	/// Creates a snapshot of the entire file at the given path. This performs blocking I/O.
+	pub fn new(path: &Path) -> io::Result<FileSnapshot> {
+		let metadata = fs::metadata(path)?;
+		if !metadata.is_file() {
+			return Err(io::Error::new(io::ErrorKind::InvalidInput, "Path is not a file"));
+		}
+
+		Ok(FileSnapshot {
+			path: Arc::from(path),
+			offset: 0,
+			len: metadata.len(),
+			modified: metadata.modified().ok(),
+			temporary: None,
+		})
+	}
+
+	/// Writes the bytes to a new temporary file, which is deleted once the snapshot and its slices are dropped.
+	/// This performs blocking I/O.
+	pub fn temporary(bytes: &[u8]) -> io::Result<FileSnapshot> {
+		let mut writer = TemporaryFileWriter::new()?;
+		writer.write(bytes)?;
+		writer.finish()
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	pub fn modified(&self) -> Option<SystemTime> {
+		self.modified
+	}
+
+	/// Reads the range of the file. This performs blocking I/O.
+	pub fn read(&self) -> io::Result<Bytes> {
+		let mut file = fs::File::open(&self.path)?;
+		if self.modified.is_some() && file.metadata()?.modified().ok() != self.modified {
+			return Err(io::Error::other("File has been modified since it was read"));
+		}
+
+		let mut bytes = vec![0; usize::try_from(self.len).map_err(io::Error::other)?];
+		file.seek(SeekFrom::Start(self.offset))?;
+		file.read_exact(&mut bytes)?;
+		Ok(Bytes::from(bytes))
+	}
+
+	fn error(&self, error: io::Error) -> Error {
+		Error::new(format!("Could not read file {}: {error}", self.path.display()), None)
+	}
+}
+
+/// Writes contents which are received incrementally to a new temporary file, so that they do not need to be held in
+/// memory.
+pub(crate) struct TemporaryFileWriter {
+	file: fs::File,
+	temporary: Arc<TemporaryFile>,
+}
+
+impl TemporaryFileWriter {
+	pub(crate) fn new() -> io::Result<TemporaryFileWriter> {
+		let temporary = Arc::new(TemporaryFile(env::temp_dir().join(format!("spiderfire-blob-{}", Uuid::new_v4()))));
+		let file = fs::OpenOptions::new().write(true).create_new(true).open(&temporary.0)?;
+		Ok(TemporaryFileWriter { file, temporary })
+	}
+
+	pub(crate) fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+		self.file.write_all(bytes)
+	}
+
+	/// Creates a snapshot of the written contents, which deletes the file once it and its slices are dropped.
+	pub(crate) fn finish(self) -> io::Result<FileSnapshot> {
+		drop(self.file);
+		let mut snapshot = FileSnapshot::new(&self.temporary.0)?;
+		snapshot.temporary = Some(self.temporary);
+		Ok(snapshot)
+	}
+}
+
+/// Represents the contents of a [Blob], which are either held in memory or read lazily from disk.
+#[derive(Clone, Debug)]
+pub enum BlobData {
+	Memory(Bytes),
+	File(FileSnapshot),
+	/// Concatenation of the contents of other blobs, such as those read from disk, which are read lazily.
+	Parts(Arc<[BlobData]>),
+	/// Contents which are being written to a temporary file on the blocking thread pool, and are held in memory until
+	/// the write completes.
+	Spilling(Arc<RwLock<BlobData>>),
+}
+
+impl BlobData {
+	/// Stores the bytes in memory, or in a temporary file if they are larger than
+	/// [Config::blob_spill_threshold](crate::config::Config::blob_spill_threshold).
+	///
+	/// The temporary file is written on the blocking thread pool, and the bytes are kept in memory if it fails.
+	pub fn from_bytes(bytes: Bytes) -> BlobData {
+		let threshold = CONFIG.get().and_then(|config| config.blob_spill_threshold);
+		if threshold.is_some_and(|threshold| bytes.len() as u64 > threshold) {
+			return BlobData::spill(bytes);
+		}
+		BlobData::Memory(bytes)
+	}
+
+	#[cfg(feature = "tokio-promise")]
+	fn spill(bytes: Bytes) -> BlobData {
+		let Ok(handle) = Handle::try_current() else {
+			return BlobData::Memory(bytes);
+		};
+
+		let data = Arc::new(RwLock::new(BlobData::Memory(bytes.clone())));
+		let spilling = Arc::downgrade(&data);
+		handle.spawn_blocking(move || {
+			if spilling.strong_count() == 0 {
+				return;
+			}
+			match FileSnapshot::temporary(&bytes) {
+				Ok(snapshot) => {
+					if let Some(data) = spilling.upgrade() {
+						*data.write().unwrap() = BlobData::File(snapshot);
+					}
+				}
+				Err(error) => warn!("Could not spill blob to a temporary file: {error}"),
+			}
+		});
+		BlobData::Spilling(data)
+	}
+
+	/// Without the `tokio-promise` feature, there is no blocking thread pool, so the temporary file is written on the
+	/// current thread.
+	#[cfg(not(feature = "tokio-promise"))]
+	fn spill(bytes: Bytes) -> BlobData {
+		match FileSnapshot::temporary(&bytes) {
+			Ok(snapshot) => BlobData::File(snapshot),
+			Err(error) => {
+				warn!("Could not spill blob to a temporary file: {error}");
+				BlobData::Memory(bytes)
+			}
+		}
+	}
+
+	/// Returns the contents as they are currently stored, which differs from these contents while they are being
+	/// spilled to disk.
+	pub fn current(&self) -> BlobData {
+		match self {
+			BlobData::Spilling(data) => data.read().unwrap().clone(),
+			data => data.clone(),
+		}
+	}
+
+	pub fn len(&self) -> u64 {
+		match self {
+			BlobData::Memory(bytes) => bytes.len() as u64,
+			BlobData::File(snapshot) => snapshot.len,
+			BlobData::Parts(parts) => parts.iter().map(BlobData::len).sum(),
+			BlobData::Spilling(data) => data.read().unwrap().len(),
+		}
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns the contents between `start` and `end`, without reading them.
+	pub fn slice(&self, start: u64, end: u64) -> BlobData {
+		match self {
+			BlobData::Memory(bytes) => BlobData::Memory(bytes.slice(start as usize..end as usize)),
+			BlobData::File(snapshot) => BlobData::File(FileSnapshot {
+				path: Arc::clone(&snapshot.path),
+				offset: snapshot.offset + start,
+				len: end - start,
+				modified: snapshot.modified,
+				temporary: snapshot.temporary.clone(),
+			}),
+			BlobData::Parts(parts) => {
+				let mut sliced = Vec::new();
+				let mut offset = 0;
+				for part in parts.iter() {
+					let len = part.len();
+					if offset < end && offset + len > start {
+						sliced.push(part.slice(start.saturating_sub(offset), (end - offset).min(len)));
+					}
+					offset += len;
+				}
+				match <[BlobData; 1]>::try_from(sliced) {
+					Ok([part]) => part,
+					Err(sliced) => BlobData::Parts(Arc::from(sliced)),
+				}
+			}
+			BlobData::Spilling(data) => data.read().unwrap().slice(start, end),
+		}
+	}
+
+	/// Reads the contents, blocking the current thread if they are on disk.
+	pub fn read_sync(&self) -> Result<Bytes> {
+		match self {
+			BlobData::Memory(bytes) => Ok(bytes.clone()),
+			BlobData::File(snapshot) => snapshot.read().map_err(|error| snapshot.error(error)),
+			BlobData::Parts(parts) => {
+				let mut bytes = BytesMut::new();
+				for part in parts.iter() {
+					bytes.extend_from_slice(&part.read_sync()?);
+				}
+				Ok(bytes.freeze())
+			}
+			BlobData::Spilling(_) => self.current().read_sync(),
+		}
+	}
+
+	/// Reads the contents, reading from disk on the blocking thread pool.
+	pub async fn read(self) -> Result<Bytes> {
+		match self {
+			BlobData::Memory(bytes) => Ok(bytes),
+			BlobData::File(snapshot) => {
+				let path = Arc::clone(&snapshot.path);
+				run_blocking(move |_| snapshot.read()).await.map_err(|error| {
+					Error::new(format!("Could not read file {}: {error}", path.display()), None)
+				})
+			}
+			BlobData::Parts(parts) => {
+				let mut bytes = BytesMut::new();
+				for part in parts.iter() {
+					bytes.extend_from_slice(&Box::pin(part.clone().read()).await?);
+				}
+				Ok(bytes.freeze())
+			}
+			BlobData::Spilling(_) => Box::pin(self.current().read()).await,
+		}
+	}
+
+	/// Returns a stream of the contents, which are read in chunks if they are on disk.
+	pub fn stream(self) -> impl Stream<Item = Result<Bytes>> {
+		let len = self.len();
+		stream::unfold((self, 0), move |(data, offset)| async move {
+			if offset >= len {
+				return None;
+			}
+
+			let end = len.min(offset + STREAM_CHUNK_SIZE);
+			match data.slice(offset, end).read().await {
+				Ok(bytes) => Some((Ok(bytes), (data, end))),
+				Err(error) => Some((Err(error), (data, len))),
+			}
+		})
+	}
+}
+
+impl Default for BlobData {
+	fn default() -> BlobData {
+		BlobData::Memory(Bytes::new())
+	}
+}
+
 #[derive(Debug)]
 #[js_class]
 pub struct Blob {
 	pub(crate) reflector: Reflector,
 	#[trace(no_trace)]
-	pub(crate) bytes: Bytes,
+	pub(crate) data: BlobData,
 	pub(crate) kind: Option<String>,
+	#[trace(no_trace)]
+	pub(crate) object_urls: RefCell<Vec<Arc<ObjectUrlEntry>>>,
 }
 
 #[js_class]
 impl Blob {
 	#[ion(constructor)]
-	pub fn constructor(Opt(parts): Opt<Vec<BlobPart>>, Opt(options): Opt<BlobOptions>) -> Blob {
+	pub fn constructor(Opt(parts): Opt<Vec<BlobPart>>, Opt(options): Opt<BlobOptions>) -> Result<Blob> {
 		let options = options.unwrap_or_default();
 
-		let mut bytes = BytesMut::new();
+		let data = match parts.as_deref() {
+			Some([BlobPart::Blob(blob)]) => blob.data.clone(),
+			_ => {
+				// Blobs which are not held in memory are kept as parts, so that they are not read.
+				let mut segments = Vec::new();
+				let mut bytes = BytesMut::new();
+				for part in parts.iter().flatten() {
+					match part {
+						BlobPart::String(str) => options.endings.convert(&mut bytes, str),
+						BlobPart::BufferSource(source) => bytes.extend_from_slice(unsafe { source.as_slice() }),
+						BlobPart::Blob(blob) => match &blob.data {
+							BlobData::Memory(data) => bytes.extend_from_slice(data),
+							BlobData::File(_) | BlobData::Spilling(_) => {
+								segments.push(BlobData::Memory(bytes.split().freeze()));
+								segments.push(blob.data.clone());
+							}
+							BlobData::Parts(parts) => {
+								segments.push(BlobData::Memory(bytes.split().freeze()));
+								segments.extend(parts.iter().cloned());
+							}
+						},
+					}
+				}
 
-		if let Some(parts) = parts {
-			for part in parts {
-				match part {
-					BlobPart::String(str) => options.endings.convert(&mut bytes, &str),
-					BlobPart::BufferSource(source) => bytes.extend_from_slice(unsafe { source.as_slice() }),
-					BlobPart::Blob(blob) => bytes.extend_from_slice(&blob.bytes),
+				if segments.is_empty() {
+					BlobData::from_bytes(bytes.freeze())
+				} else {
+					segments.push(BlobData::Memory(bytes.freeze()));
+					segments.retain(|segment| !segment.is_empty());
+					BlobData::Parts(Arc::from(segments))
 				}
 			}
-		}
+		};
 
-		Blob {
-			reflector: Reflector::default(),
-			bytes: bytes.freeze(),
-			kind: validate_kind(options.kind),
-		}
+		Ok(Blob::from_data(data, validate_kind(options.kind)))
 	}
 
 	#[ion(get)]
 	pub fn get_size(&self) -> u64 {
-		self.bytes.len() as u64
+		self.data.len()
 	}
 
 	#[ion(get)]
@@ -202,44 +512,66 @@ impl Blob {
 	pub fn slice(
 		&self, cx: &Context, Opt(start): Opt<Clamp<i64>>, Opt(end): Opt<Clamp<i64>>, Opt(kind): Opt<String>,
 	) -> *mut JSObject {
-		let size = self.bytes.len() as i64;
+		let size = self.data.len() as i64;
 
 		let mut start = start.unwrap_or_default().0;
 		if start < 0 {
 			start = 0.max(size + start);
 		}
-		let start = start.min(size) as usize;
+		let start = start.min(size) as u64;
 
 		let mut end = end.unwrap_or(Clamp(size)).0;
 		if end < 0 {
 			end = 0.max(size + end);
 		}
-		let end = end.min(size) as usize;
-
-		let span = 0.max(end - start);
-		let bytes = self.bytes.slice(start..start + span);
+		let end = end.min(size) as u64;
 
-		let blob = Blob {
-			reflector: Reflector::default(),
-			bytes,
-			kind: validate_kind(kind),
-		};
+		let span = end.saturating_sub(start);
+		let blob = Blob::from_data(self.data.slice(start, start + span), validate_kind(kind));
 		Blob::new_object(cx, Box::new(blob))
 	}
 
 	pub fn text<'cx>(&self, cx: &'cx Context) -> Option<Promise<'cx>> {
-		let bytes = self.bytes.clone();
-		future_to_promise(cx, async move { Ok::<_, ()>(UTF_8.decode(&bytes).0.into_owned()) })
+		let data = self.data.clone();
+		future_to_promise(cx, async move {
+			let bytes = data.read().await?;
+			Ok::<_, Error>(run_blocking(move |_| UTF_8.decode(&bytes).0.into_owned()).await)
+		})
 	}
 
 	#[ion(name = "arrayBuffer")]
 	pub fn array_buffer<'cx>(&self, cx: &'cx Context) -> Option<Promise<'cx>> {
-		let bytes = self.bytes.clone();
-		future_to_promise(cx, async move { Ok::<_, ()>(ArrayBufferWrapper::from(bytes.to_vec())) })
+		let data = self.data.clone();
+		future_to_promise(cx, async move {
+			let bytes = data.read().await?;
+			Ok::<_, Error>(ArrayBufferWrapper::from(bytes))
+		})
+	}
+
+	pub fn stream<'cx>(&self, cx: &'cx Context) -> Object<'cx> {
+		ReadableStream::from_bytes_stream(cx, self.data.clone().stream())
 	}
 
 	pub fn bytes<'cx>(&self, cx: &'cx Context) -> Option<Promise<'cx>> {
-		let bytes = self.bytes.clone();
-		future_to_promise(cx, async move { Ok::<_, ()>(Uint8ArrayWrapper::from(bytes.to_vec())) })
+		let data = self.data.clone();
+		future_to_promise(cx, async move {
+			let bytes = data.read().await?;
+			Ok::<_, Error>(Uint8ArrayWrapper::from(bytes.to_vec()))
+		})
+	}
+}
+
+impl Blob {
+	pub fn from_data(data: BlobData, kind: Option<String>) -> Blob {
+		Blob {
+			reflector: Reflector::default(),
+			data,
+			kind,
+			object_urls: RefCell::default(),
+		}
+	}
+
+	pub fn data(&self) -> &BlobData {
+		&self.data
 	}
 }
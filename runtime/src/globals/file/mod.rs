@@ -4,10 +4,11 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-pub use blob::{Blob, BufferSource};
+pub use blob::{Blob, BlobData, BufferSource, FileSnapshot};
+pub(crate) use blob::TemporaryFileWriter;
 use chrono::{DateTime, TimeZone, Utc};
 use ion::function::{Opt, Wrap};
-use ion::{ClassDefinition, Context, Object};
+use ion::{ClassDefinition, Context, Object, Result};
 
 use crate::globals::file::blob::{BlobOptions, BlobPart};
 use crate::globals::file::reader::{FileReader, FileReaderSync};
@@ -33,15 +34,15 @@ pub struct File {
 #[js_class]
 impl File {
 	#[ion(constructor)]
-	pub fn constructor(parts: Vec<BlobPart>, name: String, Opt(options): Opt<FileOptions>) -> File {
+	pub fn constructor(parts: Vec<BlobPart>, name: String, Opt(options): Opt<FileOptions>) -> Result<File> {
 		let options = options.unwrap_or_default();
-		let blob = Blob::constructor(Opt(Some(parts)), Opt(Some(options.blob)));
+		let blob = Blob::constructor(Opt(Some(parts)), Opt(Some(options.blob)))?;
 		let modified = options
 			.modified
 			.and_then(|d| Utc.timestamp_millis_opt(d.0).single())
 			.unwrap_or_else(Utc::now);
 
-		File { blob, name, modified }
+		Ok(File { blob, name, modified })
 	}
 
 	#[ion(get)]
@@ -55,6 +56,28 @@ impl File {
 	}
 }
 
+impl File {
+	pub fn from_data(data: BlobData, kind: Option<String>, name: String) -> File {
+		File {
+			blob: Blob::from_data(data, kind),
+			name,
+			modified: Utc::now(),
+		}
+	}
+
+	/// Creates a [File] whose contents are read lazily from the snapshot.
+	pub fn from_snapshot(snapshot: FileSnapshot) -> File {
+		let name = snapshot.path().file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+		let modified = snapshot.modified().map_or_else(Utc::now, DateTime::from);
+
+		File {
+			blob: Blob::from_data(BlobData::File(snapshot), None),
+			name,
+			modified,
+		}
+	}
+}
+
 pub fn define(cx: &Context, object: &Object) -> bool {
 	Blob::init_class(cx, object).0
 		&& File::init_class(cx, object).0
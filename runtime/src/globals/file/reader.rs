@@ -5,23 +5,28 @@
  */
 
 use std::cell::UnsafeCell;
+use std::ptr;
 use std::str::FromStr;
 
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
+use bytes::Bytes;
 use encoding_rs::{Encoding, UTF_8};
 use ion::class::{NativeObject, Reflector};
 use ion::conversions::ToValue;
 use ion::function::Opt;
 use ion::string::byte::{ByteString, Latin1};
 use ion::typedarray::ArrayBufferWrapper;
-use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Result, TracedHeap};
+use ion::{
+	ClassDefinition, Context, Error, ErrorKind, Exception, Function, Object, Result, ResultExc, TracedHeap, Value,
+};
 use mime::Mime;
 use mozjs::jsapi::{Heap, JSObject};
 use mozjs::jsval::{JSVal, NullValue};
 
+use crate::event_loop::MicrotaskCheckpoint;
 use crate::globals::file::Blob;
-use crate::promise::future_to_promise;
+use crate::promise::{future_to_promise, run_blocking};
 
 fn encoding_from_string_mime(encoding: Option<&str>, mime: Option<&str>) -> &'static Encoding {
 	encoding
@@ -55,6 +60,82 @@ impl FileReaderState {
 	}
 }
 
+#[derive(Clone, Copy, Debug)]
+enum ReadKind {
+	ArrayBuffer,
+	BinaryString,
+	Text,
+	DataUrl,
+}
+
+impl ReadKind {
+	/// Decodes the bytes into the result of the read, which is done on the blocking thread pool, as decoding text and
+	/// encoding data URLs takes time proportional to the size of the blob.
+	fn decode(self, bytes: Bytes, encoding: Option<&str>, mime: Option<&str>) -> ReadOutput {
+		match self {
+			ReadKind::ArrayBuffer => ReadOutput::ArrayBuffer(bytes),
+			ReadKind::BinaryString => ReadOutput::BinaryString(bytes),
+			ReadKind::Text => {
+				let encoding = encoding_from_string_mime(encoding, mime);
+				ReadOutput::Text(encoding.decode_without_bom_handling(&bytes).0.into_owned())
+			}
+			ReadKind::DataUrl => ReadOutput::Text(data_url(&bytes, mime)),
+		}
+	}
+}
+
+enum ReadOutput {
+	ArrayBuffer(Bytes),
+	BinaryString(Bytes),
+	Text(String),
+}
+
+impl ReadOutput {
+	fn into_value<'cx>(self, cx: &'cx Context) -> Value<'cx> {
+		match self {
+			ReadOutput::ArrayBuffer(bytes) => ArrayBufferWrapper::from(bytes).as_value(cx),
+			ReadOutput::BinaryString(bytes) => {
+				unsafe { ByteString::<Latin1>::from_unchecked(Vec::from(bytes)) }.as_value(cx)
+			}
+			ReadOutput::Text(text) => text.as_value(cx),
+		}
+	}
+}
+
+fn data_url(bytes: &[u8], mime: Option<&str>) -> String {
+	let base64 = BASE64_STANDARD.encode(bytes);
+	match mime {
+		Some(mime) => format!("data:{mime};base64,{base64}"),
+		None => format!("data:base64,{base64}"),
+	}
+}
+
+/// Calls the event handler with a `ProgressEvent`-like object, until `EventTarget` is available.
+fn fire_progress_event(
+	cx: &Context, reader: &Object, handler: *mut JSObject, kind: &str, loaded: u64, total: u64,
+) -> ResultExc<()> {
+	if handler.is_null() {
+		return Ok(());
+	}
+	let Some(handler) = Function::from_object(cx, &cx.root(handler)) else {
+		return Ok(());
+	};
+
+	let event = Object::new(cx);
+	event.set_as(cx, "type", kind);
+	event.set_as(cx, "target", reader);
+	event.set_as(cx, "lengthComputable", &true);
+	event.set_as(cx, "loaded", &(loaded as f64));
+	event.set_as(cx, "total", &(total as f64));
+
+	let _checkpoint = MicrotaskCheckpoint::new(cx);
+	match handler.call(cx, reader, &[event.as_value(cx)]) {
+		Ok(_) => Ok(()),
+		Err(Some(report)) => Err(report.into_exception()),
+		Err(None) => Err(Error::none().into()),
+	}
+}
+
 #[derive(Debug)]
 #[js_class]
 pub struct FileReader {
@@ -62,6 +143,91 @@ pub struct FileReader {
 	state: FileReaderState,
 	result: Heap<JSVal>,
 	error: Heap<*mut JSObject>,
+	#[trace(no_trace)]
+	read_id: u32,
+	onloadstart: Heap<*mut JSObject>,
+	onprogress: Heap<*mut JSObject>,
+	onload: Heap<*mut JSObject>,
+	onabort: Heap<*mut JSObject>,
+	onerror: Heap<*mut JSObject>,
+	onloadend: Heap<*mut JSObject>,
+}
+
+impl FileReader {
+	fn read(&mut self, cx: &Context, blob: &Blob, kind: ReadKind, encoding: Option<String>) -> Result<()> {
+		self.state.validate()?;
+		self.result.set(NullValue());
+		self.error.set(ptr::null_mut());
+		self.read_id = self.read_id.wrapping_add(1);
+
+		let read_id = self.read_id;
+		let data = blob.data.clone();
+		let mime = blob.kind.clone();
+
+		let this = TracedHeap::new(self.reflector().get());
+		let token = cx.token();
+
+		future_to_promise::<_, _, Exception>(
+			cx,
+			token.clone().guard(async move {
+				let cx = &token.context();
+				let reader = Object::from(this.to_local());
+				let total = data.len();
+
+				let private = FileReader::get_private(cx, &reader)?;
+				if private.read_id != read_id {
+					return Ok(());
+				}
+				let handler = private.onloadstart.get();
+				fire_progress_event(cx, &reader, handler, "loadstart", 0, total)?;
+
+				let result = match data.read().await {
+					Ok(bytes) => {
+						Ok(run_blocking(move |_| kind.decode(bytes, encoding.as_deref(), mime.as_deref())).await)
+					}
+					Err(error) => Err(error),
+				};
+
+				let private = FileReader::get_mut_private(cx, &reader)?;
+				if private.read_id != read_id || private.state != FileReaderState::Loading {
+					return Ok(());
+				}
+
+				match result {
+					Ok(output) => {
+						let handler = private.onprogress.get();
+						fire_progress_event(cx, &reader, handler, "progress", total, total)?;
+
+						let value = output.into_value(cx);
+						let private = FileReader::get_mut_private(cx, &reader)?;
+						if private.read_id != read_id {
+							return Ok(());
+						}
+						private.state = FileReaderState::Done;
+						private.result.set(value.get());
+
+						let handler = private.onload.get();
+						fire_progress_event(cx, &reader, handler, "load", total, total)?;
+					}
+					Err(error) => {
+						private.state = FileReaderState::Done;
+						private.error.set(error.as_value(cx).to_object(cx).handle().get());
+
+						let handler = private.onerror.get();
+						fire_progress_event(cx, &reader, handler, "error", 0, total)?;
+					}
+				}
+
+				let private = FileReader::get_private(cx, &reader)?;
+				if private.read_id == read_id && private.state != FileReaderState::Loading {
+					let handler = private.onloadend.get();
+					fire_progress_event(cx, &reader, handler, "loadend", total, total)?;
+				}
+				Ok(())
+			}),
+		);
+		Ok(())
+	}
 }
 
 #[js_class]
@@ -92,86 +258,104 @@ impl FileReader {
 
 	#[ion(name = "readAsArrayBuffer")]
 	pub fn read_as_array_buffer(&mut self, cx: &Context, blob: &Blob) -> Result<()> {
-		self.state.validate()?;
-		let bytes = blob.bytes.clone();
-
-		let this = TracedHeap::new(self.reflector().get());
-		let cx2 = unsafe { Context::new_unchecked(cx.as_ptr()) };
-
-		future_to_promise::<_, _, Error>(cx, async move {
-			let reader = Object::from(this.to_local());
-			let reader = FileReader::get_private(&cx2, &reader)?;
-			let array_buffer = ArrayBufferWrapper::from(bytes.to_vec());
-			reader.result.set(array_buffer.as_value(&cx2).get());
-			Ok(())
-		});
-		Ok(())
+		self.read(cx, blob, ReadKind::ArrayBuffer, None)
 	}
 
 	#[ion(name = "readAsBinaryString")]
 	pub fn read_as_binary_string(&mut self, cx: &Context, blob: &Blob) -> Result<()> {
-		self.state.validate()?;
-		let bytes = blob.bytes.clone();
-
-		let this = TracedHeap::new(self.reflector().get());
-		let cx2 = unsafe { Context::new_unchecked(cx.as_ptr()) };
-
-		future_to_promise::<_, _, Error>(cx, async move {
-			let reader = Object::from(this.to_local());
-			let reader = FileReader::get_private(&cx2, &reader)?;
-			let byte_string = unsafe { ByteString::<Latin1>::from_unchecked(bytes.to_vec()) };
-			reader.result.set(byte_string.as_value(&cx2).get());
-			Ok(())
-		});
-		Ok(())
+		self.read(cx, blob, ReadKind::BinaryString, None)
 	}
 
 	#[ion(name = "readAsText")]
 	pub fn read_as_text(&mut self, cx: &Context, blob: &Blob, Opt(encoding): Opt<String>) -> Result<()> {
-		self.state.validate()?;
-		let bytes = blob.bytes.clone();
-		let mime = blob.kind.clone();
+		self.read(cx, blob, ReadKind::Text, encoding)
+	}
 
-		let this = TracedHeap::new(self.reflector().get());
-		let cx2 = unsafe { Context::new_unchecked(cx.as_ptr()) };
+	#[ion(name = "readAsDataURL")]
+	pub fn read_as_data_url(&mut self, cx: &Context, blob: &Blob) -> Result<()> {
+		self.read(cx, blob, ReadKind::DataUrl, None)
+	}
+
+	pub fn abort(&mut self, cx: &Context) -> ResultExc<()> {
+		self.result.set(NullValue());
+		if self.state != FileReaderState::Loading {
+			return Ok(());
+		}
 
-		future_to_promise::<_, _, Error>(cx, async move {
-			let encoding = encoding_from_string_mime(encoding.as_deref(), mime.as_deref());
+		self.state = FileReaderState::Done;
+		self.read_id = self.read_id.wrapping_add(1);
 
-			let reader = Object::from(this.to_local());
-			let reader = FileReader::get_private(&cx2, &reader)?;
-			let str = encoding.decode_without_bom_handling(&bytes).0;
-			reader.result.set(str.as_value(&cx2).get());
-			Ok(())
-		});
-		Ok(())
+		let reader = Object::from(cx.root(self.reflector().get()));
+		let (onabort, onloadend) = (self.onabort.get(), self.onloadend.get());
+		fire_progress_event(cx, &reader, onabort, "abort", 0, 0)?;
+		fire_progress_event(cx, &reader, onloadend, "loadend", 0, 0)
 	}
 
-	#[ion(name = "readAsDataURL")]
-	pub fn read_as_data_url(&mut self, cx: &Context, blob: &Blob) -> Result<()> {
-		self.state.validate()?;
-		let bytes = blob.bytes.clone();
-		let mime = blob.kind.clone();
+	#[ion(get)]
+	pub fn get_onloadstart(&self) -> *mut JSObject {
+		self.onloadstart.get()
+	}
 
-		let this = TracedHeap::new(self.reflector().get());
-		let cx2 = unsafe { Context::new_unchecked(cx.as_ptr()) };
-
-		future_to_promise::<_, _, Error>(cx, async move {
-			let reader = Object::from(this.to_local());
-			let reader = FileReader::get_private(&cx2, &reader)?;
-			let base64 = BASE64_STANDARD.encode(&bytes);
-			let data_url = match mime {
-				Some(mime) => format!("data:{mime};base64,{base64}"),
-				None => format!("data:base64,{base64}"),
-			};
-
-			reader.result.set(data_url.as_value(&cx2).get());
-			Ok(())
-		});
-		Ok(())
+	#[ion(set)]
+	pub fn set_onloadstart(&mut self, cx: &Context, handler: Option<Function>) {
+		self.onloadstart.set(handler_object(cx, handler));
+	}
+
+	#[ion(get)]
+	pub fn get_onprogress(&self) -> *mut JSObject {
+		self.onprogress.get()
+	}
+
+	#[ion(set)]
+	pub fn set_onprogress(&mut self, cx: &Context, handler: Option<Function>) {
+		self.onprogress.set(handler_object(cx, handler));
+	}
+
+	#[ion(get)]
+	pub fn get_onload(&self) -> *mut JSObject {
+		self.onload.get()
+	}
+
+	#[ion(set)]
+	pub fn set_onload(&mut self, cx: &Context, handler: Option<Function>) {
+		self.onload.set(handler_object(cx, handler));
+	}
+
+	#[ion(get)]
+	pub fn get_onabort(&self) -> *mut JSObject {
+		self.onabort.get()
+	}
+
+	#[ion(set)]
+	pub fn set_onabort(&mut self, cx: &Context, handler: Option<Function>) {
+		self.onabort.set(handler_object(cx, handler));
+	}
+
+	#[ion(get)]
+	pub fn get_onerror(&self) -> *mut JSObject {
+		self.onerror.get()
+	}
+
+	#[ion(set)]
+	pub fn set_onerror(&mut self, cx: &Context, handler: Option<Function>) {
+		self.onerror.set(handler_object(cx, handler));
+	}
+
+	#[ion(get)]
+	pub fn get_onloadend(&self) -> *mut JSObject {
+		self.onloadend.get()
+	}
+
+	#[ion(set)]
+	pub fn set_onloadend(&mut self, cx: &Context, handler: Option<Function>) {
+		self.onloadend.set(handler_object(cx, handler));
 	}
 }
 
+fn handler_object(cx: &Context, handler: Option<Function>) -> *mut JSObject {
+	handler.map_or_else(ptr::null_mut, |handler| handler.to_object(cx).handle().get())
+}
+
 impl Default for FileReader {
 	fn default() -> FileReader {
 		FileReader {
@@ -179,6 +363,13 @@ impl Default for FileReader {
 			state: FileReaderState::default(),
 			result: Heap { ptr: UnsafeCell::from(NullValue()) },
 			error: Heap::default(),
+			read_id: 0,
+			onloadstart: Heap::default(),
+			onprogress: Heap::default(),
+			onload: Heap::default(),
+			onabort: Heap::default(),
+			onerror: Heap::default(),
+			onloadend: Heap::default(),
 		}
 	}
 }
@@ -197,29 +388,24 @@ impl FileReaderSync {
 	}
 
 	#[ion(name = "readAsArrayBuffer")]
-	pub fn read_as_array_buffer(&mut self, blob: &Blob) -> ArrayBufferWrapper {
-		ArrayBufferWrapper::from(blob.bytes.to_vec())
+	pub fn read_as_array_buffer(&mut self, blob: &Blob) -> Result<ArrayBufferWrapper> {
+		Ok(ArrayBufferWrapper::from(blob.data.read_sync()?))
 	}
 
 	#[ion(name = "readAsBinaryString")]
-	pub fn read_as_binary_string(&mut self, blob: &Blob) -> ByteString {
-		unsafe { ByteString::<Latin1>::from_unchecked(blob.bytes.to_vec()) }
+	pub fn read_as_binary_string(&mut self, blob: &Blob) -> Result<ByteString> {
+		let bytes = blob.data.read_sync()?;
+		Ok(unsafe { ByteString::<Latin1>::from_unchecked(bytes.to_vec()) })
 	}
 
 	#[ion(name = "readAsText")]
-	pub fn read_as_text(&mut self, blob: &Blob, Opt(encoding): Opt<String>) -> String {
+	pub fn read_as_text(&mut self, blob: &Blob, Opt(encoding): Opt<String>) -> Result<String> {
 		let encoding = encoding_from_string_mime(encoding.as_deref(), blob.kind.as_deref());
-		encoding.decode_without_bom_handling(&blob.bytes).0.into_owned()
+		Ok(encoding.decode_without_bom_handling(&blob.data.read_sync()?).0.into_owned())
 	}
 
 	#[ion(name = "readAsDataURL")]
-	pub fn read_as_data_url(&mut self, blob: &Blob) -> String {
-		let mime = blob.kind.clone();
-
-		let base64 = BASE64_STANDARD.encode(&blob.bytes);
-		match mime {
-			Some(mime) => format!("data:{mime};base64,{base64}"),
-			None => format!("data:base64,{base64}"),
-		}
+	pub fn read_as_data_url(&mut self, blob: &Blob) -> Result<String> {
+		Ok(data_url(&blob.data.read_sync()?, blob.kind.as_deref()))
 	}
 }
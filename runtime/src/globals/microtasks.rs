@@ -4,13 +4,45 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::rc::Rc;
+
+use ion::conversions::ToValue;
 use ion::flags::PropertyFlags;
-use ion::{Context, Error, Function, Object, Result};
+use ion::function::Opt;
+use ion::{Context, Error, ErrorReport, Exception, Function, Object, Promise, Result, TracedHeap, Value};
 use mozjs::jsapi::JSFunctionSpec;
+use tracing::error;
 
+use crate::event_loop::hooks::{PromiseHook, PromiseHooks};
 use crate::event_loop::microtasks::Microtask;
+use crate::event_loop::{UncaughtErrorAction, UncaughtErrorHandler};
 use crate::ContextExt;
 
+#[derive(FromValue)]
+struct PromiseHooksInit<'cx> {
+	#[ion(default)]
+	init: Option<Function<'cx>>,
+	#[ion(default)]
+	resolve: Option<Function<'cx>>,
+	#[ion(default)]
+	before: Option<Function<'cx>>,
+	#[ion(default)]
+	after: Option<Function<'cx>>,
+}
+
+fn wrap_hook<'cx>(cx: &'cx Context, hook: Option<Function<'cx>>) -> Option<PromiseHook> {
+	hook.map(|hook| {
+		let hook = TracedHeap::new(hook.to_object(cx).get());
+		Box::new(move |cx: &Context, promise: &Promise| {
+			let hook = Function::from_object(cx, &hook.to_local()).unwrap();
+			if let Err(Some(report)) = hook.call(cx, &Object::global(cx), &[promise.as_value(cx)]) {
+				let event_loop = unsafe { &cx.get_private().event_loop };
+				event_loop.report_error(cx, report);
+			}
+		}) as PromiseHook
+	})
+}
+
 #[js_fn]
 fn queue_microtask(cx: &Context, callback: Function) -> Result<()> {
 	let event_loop = unsafe { &mut cx.get_private().event_loop };
@@ -22,7 +54,58 @@ fn queue_microtask(cx: &Context, callback: Function) -> Result<()> {
 	}
 }
 
+/// Sets callbacks which are called during the lifecycle of promises, or removes them if no hooks are passed.
+/// Errors thrown by a hook are reported as uncaught errors, without interrupting the promise job.
+#[js_fn]
+fn set_promise_hooks(cx: &Context, Opt(hooks): Opt<PromiseHooksInit>) {
+	let hooks = hooks.map(|hooks| PromiseHooks {
+		init: wrap_hook(cx, hooks.init),
+		resolve: wrap_hook(cx, hooks.resolve),
+		before: wrap_hook(cx, hooks.before),
+		after: wrap_hook(cx, hooks.after),
+	});
+	let event_loop = unsafe { &mut cx.get_private().event_loop };
+	event_loop.set_promise_hooks(hooks);
+}
+
+/// Sets a handler which is called with errors not caught by scripts, instead of stopping the event loop.
+/// If the handler throws, its error is logged and the event loop stops with the original error.
+#[js_fn]
+fn set_uncaught_exception_handler(cx: &Context, Opt(handler): Opt<Function>) {
+	let handler = handler.map(|handler| {
+		let handler = TracedHeap::new(handler.to_object(cx).get());
+		Rc::new(move |cx: &Context, report: &ErrorReport| {
+			let handler = Function::from_object(cx, &handler.to_local()).unwrap();
+			match handler.call(cx, &Object::global(cx), &[report.exception.as_value(cx)]) {
+				Ok(_) => UncaughtErrorAction::Continue,
+				Err(error) => {
+					if let Some(error) = error {
+						error!("Uncaught Exception Handler threw: {}", error.format(cx));
+					}
+					UncaughtErrorAction::Exit
+				}
+			}
+		}) as Rc<dyn UncaughtErrorHandler>
+	});
+	let event_loop = unsafe { &mut cx.get_private().event_loop };
+	event_loop.uncaught_exception_handler = handler;
+}
+
+/// Reports an error to the uncaught error handlers as if it was thrown by a task, without interrupting the caller.
+#[js_fn]
+fn report_error(cx: &Context, error: Value) -> Result<()> {
+	let exception = Exception::from_value(cx, &error)?;
+	let report = ErrorReport::from_exception_with_error_stack(cx, exception);
+	let event_loop = unsafe { &cx.get_private().event_loop };
+	event_loop.report_error(cx, report);
+	Ok(())
+}
+
 const FUNCTION: JSFunctionSpec = function_spec!(queue_microtask, c"queueMicrotask", 0);
+const SET_PROMISE_HOOKS: JSFunctionSpec = function_spec!(set_promise_hooks, c"setPromiseHooks", 0);
+const SET_UNCAUGHT_EXCEPTION_HANDLER: JSFunctionSpec =
+	function_spec!(set_uncaught_exception_handler, c"setUncaughtExceptionHandler", 1);
+const REPORT_ERROR: JSFunctionSpec = function_spec!(report_error, c"reportError", 1);
 
 pub fn define(cx: &Context, global: &Object) -> bool {
 	global.define_as(
@@ -30,5 +113,20 @@ pub fn define(cx: &Context, global: &Object) -> bool {
 		"queueMicrotask",
 		&Function::from_spec(cx, &FUNCTION),
 		PropertyFlags::CONSTANT_ENUMERATED,
+	) && global.define_as(
+		cx,
+		"setPromiseHooks",
+		&Function::from_spec(cx, &SET_PROMISE_HOOKS),
+		PropertyFlags::CONSTANT_ENUMERATED,
+	) && global.define_as(
+		cx,
+		"setUncaughtExceptionHandler",
+		&Function::from_spec(cx, &SET_UNCAUGHT_EXCEPTION_HANDLER),
+		PropertyFlags::CONSTANT_ENUMERATED,
+	) && global.define_as(
+		cx,
+		"reportError",
+		&Function::from_spec(cx, &REPORT_ERROR),
+		PropertyFlags::CONSTANT_ENUMERATED,
 	)
 }
@@ -5,33 +5,47 @@
  */
 
 use ion::{ClassDefinition, Context, Iterator, Object};
+use mozjs::jsapi::JSAutoRealm;
+
+use crate::config::{Globals, CONFIG};
+use crate::ContextExt;
 
 pub mod abort;
 pub mod base64;
 pub mod clone;
 pub mod console;
+pub mod debug;
+pub mod deterministic;
 pub mod encoding;
 #[cfg(feature = "fetch")]
 pub mod fetch;
 pub mod file;
 pub mod microtasks;
+pub mod performance;
 pub mod streams;
 pub mod timers;
 pub mod url;
 
-pub fn init_globals(cx: &Context, global: &Object) -> bool {
+/// Defines the standard globals enabled in `globals` on `global`, in its realm.
+///
+/// Classes are initialised separately for each realm, so this can be called for several globals in one context.
+pub fn init_globals(cx: &Context, global: &Object, globals: Globals) -> bool {
+	let _realm = JSAutoRealm::new(cx.as_ptr(), global.handle().get());
 	let result = base64::define(cx, global)
 		&& clone::define(cx, global)
-		&& console::define(cx, global)
+		&& (!globals.console || console::define(cx, global))
+		&& debug::define(cx, global)
 		&& encoding::define(cx, global)
 		&& file::define(cx, global)
+		&& (!globals.performance || performance::define(cx, global))
 		&& streams::define(cx, global)
 		&& url::define(cx, global)
-		&& Iterator::init_class(cx, global).0;
+		&& Iterator::init_class(cx, global).0
+		&& (!CONFIG.get().is_some_and(|config| config.deterministic) || deterministic::define(cx, global));
 
 	#[cfg(feature = "fetch")]
 	{
-		result && fetch::define(cx, global)
+		result && (!globals.fetch || fetch::define(cx, global))
 	}
 	#[cfg(not(feature = "fetch"))]
 	{
@@ -39,10 +53,18 @@ pub fn init_globals(cx: &Context, global: &Object) -> bool {
 	}
 }
 
-pub fn init_timers(cx: &Context, global: &Object) -> bool {
-	timers::define(cx, global) && abort::define(cx, global)
+pub fn init_timers(cx: &Context, global: &Object, globals: Globals) -> bool {
+	let _realm = JSAutoRealm::new(cx.as_ptr(), global.handle().get());
+	(!globals.timers || timers::define(cx, global)) && abort::define(cx, global)
 }
 
 pub fn init_microtasks(cx: &Context, global: &Object) -> bool {
+	let _realm = JSAutoRealm::new(cx.as_ptr(), global.handle().get());
 	microtasks::define(cx, global)
 }
+
+/// Returns the globals enabled for the runtime of the context, such as for standard modules to check which of them
+/// should be registered.
+pub fn enabled_globals(cx: &Context) -> Globals {
+	unsafe { cx.get_private().globals }
+}
@@ -0,0 +1,86 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::time::Duration;
+
+use ion::flags::PropertyFlags;
+use ion::function::Opt;
+use ion::{Context, Object};
+use mozjs::jsapi::JSFunctionSpec;
+
+use crate::ContextExt;
+
+/// Event loop utilization, with durations in milliseconds.
+#[derive(Clone, Copy, Debug, FromValue)]
+struct EventLoopUtilization {
+	idle: f64,
+	active: f64,
+}
+
+impl EventLoopUtilization {
+	fn to_object<'cx>(self, cx: &'cx Context, max_tick: f64) -> Object<'cx> {
+		let total = self.idle + self.active;
+		let utilization = if total > 0.0 { self.active / total } else { 0.0 };
+
+		let object = Object::new(cx);
+		object.set_as(cx, "idle", &self.idle);
+		object.set_as(cx, "active", &self.active);
+		object.set_as(cx, "utilization", &utilization);
+		object.set_as(cx, "maxTick", &max_tick);
+		object
+	}
+}
+
+fn millis(duration: Duration) -> f64 {
+	duration.as_secs_f64() * 1000.0
+}
+
+/// Returns the time the event loop has spent running tasks and waiting for them.
+///
+/// If a previous result is given, the difference from it is returned instead.
+/// If two previous results are given, the difference between them is returned.
+#[js_fn]
+fn event_loop_utilization<'cx>(
+	cx: &'cx Context, Opt(first): Opt<EventLoopUtilization>, Opt(second): Opt<EventLoopUtilization>,
+) -> Object<'cx> {
+	let metrics = unsafe { cx.get_private().event_loop.metrics() };
+	let max_tick = millis(metrics.max_tick);
+	let current = EventLoopUtilization {
+		idle: millis(metrics.idle),
+		active: millis(metrics.active),
+	};
+
+	let utilization = match (first, second) {
+		(Some(first), Some(second)) => EventLoopUtilization {
+			idle: first.idle - second.idle,
+			active: first.active - second.active,
+		},
+		(Some(first), None) => EventLoopUtilization {
+			idle: current.idle - first.idle,
+			active: current.active - first.active,
+		},
+		_ => current,
+	};
+	utilization.to_object(cx, max_tick)
+}
+
+/// Returns the time since the runtime was created, in milliseconds, from its [Clock](crate::clock::Clock).
+#[js_fn]
+fn now(cx: &Context) -> f64 {
+	millis(unsafe { cx.get_private().clock.elapsed() })
+}
+
+const METHODS: &[JSFunctionSpec] = &[
+	function_spec!(event_loop_utilization, c"eventLoopUtilization", 0),
+	function_spec!(now, 0),
+	JSFunctionSpec::ZERO,
+];
+
+pub fn define(cx: &Context, global: &Object) -> bool {
+	let performance = Object::new(cx);
+	(unsafe { performance.define_methods(cx, METHODS) })
+		&& global.define_as(cx, "performance", &performance, PropertyFlags::CONSTANT_ENUMERATED)
+}
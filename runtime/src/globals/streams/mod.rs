@@ -4,13 +4,38 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use ion::{ClassDefinition, Context, Object};
+use ion::{ClassDefinition, Context, Error, ErrorKind, Function, Object, Result};
 use readable::{
 	ByobReader, ByobRequest, ByteStreamController, CommonController, CommonReader, DefaultController, DefaultReader,
 	ReadableStream,
 };
+use strategy::{ByteLengthQueuingStrategy, CountQueuingStrategy};
+use writable::WritableStream;
 
 pub mod readable;
+pub mod strategy;
+pub mod writable;
+
+#[derive(Default, FromValue)]
+pub struct QueueingStrategy<'cx> {
+	high_water_mark: Option<f64>,
+	size: Option<Function<'cx>>,
+}
+
+impl QueueingStrategy<'_> {
+	pub(crate) fn high_water_mark(&self, default: f64) -> Result<f64> {
+		match self.high_water_mark {
+			Some(high_water_mark) if high_water_mark.is_nan() => {
+				Err(Error::new("highWaterMark cannot be NaN", ErrorKind::Range))
+			}
+			Some(high_water_mark) if high_water_mark < 0.0 => {
+				Err(Error::new("highWaterMark must be non-negative", ErrorKind::Range))
+			}
+			Some(high_water_mark) => Ok(high_water_mark),
+			None => Ok(default),
+		}
+	}
+}
 
 pub fn define<'cx>(cx: &'cx Context, global: &'cx Object) -> bool {
 	let dummy = Object::new(cx);
@@ -22,4 +47,9 @@ pub fn define<'cx>(cx: &'cx Context, global: &'cx Object) -> bool {
 		&& CommonReader::init_class(cx, &dummy).0
 		&& DefaultReader::init_class(cx, global).0
 		&& ByobReader::init_class(cx, global).0
+		&& WritableStream::init_class(cx, global).0
+		&& writable::DefaultController::init_class(cx, global).0
+		&& writable::DefaultWriter::init_class(cx, global).0
+		&& ByteLengthQueuingStrategy::init_class(cx, global).0
+		&& CountQueuingStrategy::init_class(cx, global).0
 }
@@ -11,21 +11,22 @@ use ion::class::{NativeObject, Reflector};
 use ion::conversions::{FromValue, ToValue};
 use ion::typedarray::{type_to_constructor, ArrayBuffer, ArrayBufferView, Uint8Array};
 use ion::{
-	ClassDefinition, Context, Error, ErrorKind, Exception, Function, Local, Object, Promise, Result, ResultExc,
-	TracedHeap, Value,
+	BoxedHeap, ClassDefinition, Context, Error, ErrorKind, ErrorReport, Exception, Function, Local, Object, Promise,
+	Result, ResultExc, TracedHeap, Value,
 };
 use mozjs::conversions::ConversionBehavior;
-use mozjs::jsapi::{Handle, Heap, JSContext, JSFunction, JSObject, Type};
+use mozjs::jsapi::{Handle, JSContext, JSFunction, JSObject, Type};
 use mozjs::jsval::{DoubleValue, Int32Value, JSVal, NullValue, UndefinedValue};
 
+use crate::event_loop::MicrotaskCheckpoint;
 use crate::globals::streams::readable::reader::{Reader, ReaderKind, Request};
-use crate::globals::streams::readable::{
-	ByobReader, QueueingStrategy, ReadableStream, State, StreamSource, UnderlyingSource,
-};
+use crate::globals::streams::readable::{ByobReader, ReadableStream, State, StreamSource, UnderlyingSource};
+use crate::globals::streams::QueueingStrategy;
+use crate::promise::add_reactions_with_checkpoint;
 
 #[derive(Traceable)]
 pub(crate) struct PullIntoDescriptor {
-	pub(crate) buffer: Box<Heap<*mut JSObject>>,
+	pub(crate) buffer: BoxedHeap<*mut JSObject>,
 	pub(crate) offset: usize,
 	pub(crate) length: usize,
 	pub(crate) filled: usize,
@@ -193,7 +194,7 @@ impl<'c> Controller<'c> {
 						};
 
 						controller.pending_descriptors.push_back(PullIntoDescriptor {
-							buffer: Heap::boxed(buffer.get()),
+							buffer: BoxedHeap::new(buffer.get()),
 							offset: 0,
 							length: controller.auto_allocate_chunk_size,
 							filled: 0,
@@ -233,7 +234,7 @@ impl<'c> Controller<'c> {
 pub struct CommonController {
 	reflector: Reflector,
 
-	pub(crate) stream: Box<Heap<*mut JSObject>>,
+	pub(crate) stream: BoxedHeap<*mut JSObject>,
 	pub(crate) source: StreamSource,
 
 	pub(crate) started: bool,
@@ -250,7 +251,7 @@ impl CommonController {
 		CommonController {
 			reflector: Reflector::default(),
 
-			stream: Heap::boxed(stream.handle().get()),
+			stream: BoxedHeap::new(stream.handle().get()),
 			source,
 
 			started: false,
@@ -277,7 +278,11 @@ impl CommonController {
 		ReadableStream::get_mut_private(cx, &stream)
 	}
 
-	pub(crate) fn start<C: ControllerInternals>(&mut self, cx: &Context, start: Option<&Function>) -> ResultExc<()> {
+	/// Calls the start algorithm.
+	/// Returns a [MicrotaskCheckpoint] which runs the reactions to its result if it has already settled.
+	pub(crate) fn start<'cx, C: ControllerInternals>(
+		&mut self, cx: &'cx Context, start: Option<&Function>,
+	) -> ResultExc<Option<MicrotaskCheckpoint<'cx>>> {
 		let controller = self.reflector().get();
 
 		let underlying_source = self.source.source_object();
@@ -285,14 +290,29 @@ impl CommonController {
 		let result = start
 			.map(|start| start.call(cx, &underlying_source, &[value]).map(|v| v.get()))
 			.unwrap_or_else(|| Ok(UndefinedValue()))
-			.map_err(|report| report.unwrap().exception)?;
+			.map_err(|report| report.map_or_else(|| Exception::Error(Error::none()), ErrorReport::into_exception))?;
+
+		Ok(self.started::<C>(cx, &Value::from(cx.root(result))))
+	}
+
+	/// Starts a controller which has no start algorithm, such as for a native source.
+	pub(crate) fn start_native<'cx, C: ControllerInternals>(
+		&mut self, cx: &'cx Context,
+	) -> Option<MicrotaskCheckpoint<'cx>> {
+		self.started::<C>(cx, &Value::undefined_handle())
+	}
 
-		let promise = Promise::resolved(cx, &Value::from(cx.root(result)));
+	fn started<'cx, C: ControllerInternals>(
+		&mut self, cx: &'cx Context, result: &Value,
+	) -> Option<MicrotaskCheckpoint<'cx>> {
+		let controller = self.reflector().get();
+		let promise = Promise::adopt(cx, result);
 
 		let controller1 = TracedHeap::new(controller);
 		let controller2 = TracedHeap::new(controller);
-		promise.add_reactions(
+		add_reactions_with_checkpoint(
 			cx,
+			&promise,
 			move |cx, _| {
 				let controller = C::from_traced_heap(cx, &controller1)?;
 				controller.common().started = true;
@@ -304,9 +324,7 @@ impl CommonController {
 				controller.error_internal(cx, error)?;
 				Ok(Value::undefined_handle())
 			},
-		);
-
-		Ok(())
+		)
 	}
 
 	pub(crate) fn can_close_or_enqueue(&self, stream: &ReadableStream) -> bool {
@@ -383,10 +401,16 @@ pub(crate) trait ControllerInternals: ClassDefinition {
 
 	fn common(&mut self) -> &mut CommonController;
 
-	fn start(&mut self, cx: &Context, start: Option<&Function>) -> ResultExc<()> {
+	fn start<'cx>(
+		&mut self, cx: &'cx Context, start: Option<&Function>,
+	) -> ResultExc<Option<MicrotaskCheckpoint<'cx>>> {
 		self.common().start::<Self>(cx, start)
 	}
 
+	fn start_native<'cx>(&mut self, cx: &'cx Context) -> Option<MicrotaskCheckpoint<'cx>> {
+		self.common().start_native::<Self>(cx)
+	}
+
 	fn pull_if_needed(&mut self, cx: &Context) -> ResultExc<()> {
 		self.common().pull_if_needed::<Self>(cx)
 	}
@@ -412,8 +436,8 @@ pub(crate) trait ControllerInternals: ClassDefinition {
 #[ion(name = "ReadableStreamDefaultController")]
 pub struct DefaultController {
 	pub(crate) common: CommonController,
-	pub(crate) size: Option<Box<Heap<*mut JSFunction>>>,
-	pub(crate) queue: VecDeque<(Box<Heap<JSVal>>, u64)>,
+	pub(crate) size: Option<BoxedHeap<*mut JSFunction>>,
+	pub(crate) queue: VecDeque<(BoxedHeap<JSVal>, u64)>,
 }
 
 #[js_class]
@@ -422,7 +446,7 @@ impl DefaultController {
 		stream: &Object, source_object: Option<&Object>, source: &UnderlyingSource, strategy: &QueueingStrategy,
 		high_water_mark: f64,
 	) -> DefaultController {
-		let size = strategy.size.as_ref().map(|s| Heap::boxed(s.get()));
+		let size = strategy.size.as_ref().map(|s| BoxedHeap::new(s.get()));
 
 		DefaultController {
 			common: CommonController::new_from_script(stream, source_object, source, high_water_mark),
@@ -480,7 +504,7 @@ impl DefaultController {
 					let size = u64::from_value(cx, &size, false, ConversionBehavior::EnforceRange);
 					match size {
 						Ok(size) => {
-							self.queue.push_back((Heap::boxed(chunk.get()), size));
+							self.queue.push_back((BoxedHeap::new(chunk.get()), size));
 							self.common.queue_size += size as usize;
 							self.pull_if_needed(cx)?;
 						}
@@ -526,9 +550,9 @@ impl ControllerInternals for DefaultController {
 pub struct ByteStreamController {
 	pub(crate) common: CommonController,
 	pub(crate) auto_allocate_chunk_size: usize,
-	pub(crate) byob_request: Option<Box<Heap<*mut JSObject>>>,
+	pub(crate) byob_request: Option<BoxedHeap<*mut JSObject>>,
 	pub(crate) pending_descriptors: VecDeque<PullIntoDescriptor>,
-	pub(crate) queue: VecDeque<(Box<Heap<*mut JSObject>>, usize, usize)>,
+	pub(crate) queue: VecDeque<(BoxedHeap<*mut JSObject>, usize, usize)>,
 }
 
 #[js_class]
@@ -557,7 +581,7 @@ impl ByteStreamController {
 		let max_copy = self.common.queue_size.min(descriptor.length - descriptor.filled);
 		let max_aligned = descriptor.filled + max_copy - (descriptor.filled + max_copy) % descriptor.element;
 
-		let ready = max_aligned > descriptor.min;
+		let ready = max_aligned >= descriptor.min;
 
 		let mut remaining = if ready {
 			max_aligned - descriptor.filled
@@ -596,7 +620,9 @@ impl ByteStreamController {
 		}
 
 		if !ready {
-			// TODO: Assert Queue Size 0, Assert Filled > 0, Assert Filled < Element Size
+			debug_assert_eq!(self.common.queue_size, 0);
+			debug_assert!(descriptor.filled > 0);
+			debug_assert!(descriptor.filled < descriptor.min);
 		}
 
 		Ok(ready)
@@ -628,7 +654,7 @@ impl ByteStreamController {
 			}
 		};
 
-		self.queue.push_back((Heap::boxed(buffer.get()), 0, length));
+		self.queue.push_back((BoxedHeap::new(buffer.get()), 0, length));
 		self.common.queue_size += length;
 		Ok(())
 	}
@@ -690,13 +716,14 @@ impl ByteStreamController {
 				let descriptor = self.pending_descriptors.front_mut().unwrap();
 				descriptor.filled += written;
 
-				let PullIntoDescriptor { filled, offset, length, min, .. } = *descriptor;
+				let PullIntoDescriptor { filled, offset, min, .. } = *descriptor;
 
 				match kind {
 					ReaderKind::None => {
 						if filled > 0 {
-							self.enqueue_cloned_chunk(cx, &buffer, offset, length)?;
+							self.enqueue_cloned_chunk(cx, &buffer, offset, filled)?;
 						}
+						self.pending_descriptors.pop_front();
 
 						if let Some(Reader::Byob(reader)) = stream.native_reader(cx)? {
 							self.process_descriptors(cx, reader, stream.state)?;
@@ -777,8 +804,8 @@ impl ByteStreamController {
 		if descriptor.offset + descriptor.filled != offset {
 			return Err(Error::new("View Offset must be the same as descriptor.", ErrorKind::Range).into());
 		}
-		if descriptor.length != view.len() {
-			return Err(Error::new("View Length must be the same as descriptor.", ErrorKind::Range).into());
+		if descriptor.buffer().len() != buffer.len() {
+			return Err(Error::new("View Buffer Length must be the same as descriptor.", ErrorKind::Range).into());
 		}
 		if descriptor.filled + view.len() > descriptor.length {
 			return Err(Error::new("View cannot overfill descriptor", ErrorKind::Range).into());
@@ -809,10 +836,10 @@ impl ByteStreamController {
 
 			let request = ByobRequest {
 				reflector: Reflector::default(),
-				controller: Some(Heap::boxed(self.reflector().get())),
-				view: Some(Heap::boxed(view.get())),
+				controller: Some(BoxedHeap::new(self.reflector().get())),
+				view: Some(BoxedHeap::new(view.get())),
 			};
-			self.byob_request = Some(Heap::boxed(ByobRequest::new_object(cx, Box::new(request))));
+			self.byob_request = Some(BoxedHeap::new(ByobRequest::new_object(cx, Box::new(request))));
 		}
 
 		if let Some(request) = &self.byob_request {
@@ -868,7 +895,7 @@ impl ByteStreamController {
 				descriptor.buffer.set(buffer.get());
 				if descriptor.kind == ReaderKind::None {
 					if descriptor.filled > 0 {
-						self.enqueue_cloned_chunk(cx, &buffer, descriptor.offset, descriptor.length)?;
+						self.enqueue_cloned_chunk(cx, &buffer, descriptor.offset, descriptor.filled)?;
 					}
 					shift = true;
 				}
@@ -911,18 +938,18 @@ impl ByteStreamController {
 					}
 
 					if !complete {
-						self.queue.push_back((Heap::boxed(buffer.get()), offset, length));
+						self.queue.push_back((BoxedHeap::new(buffer.get()), offset, length));
 						self.common.queue_size += length;
 					}
 				}
 				Some(Reader::Byob(reader)) => {
-					self.queue.push_back((Heap::boxed(buffer.get()), offset, length));
+					self.queue.push_back((BoxedHeap::new(buffer.get()), offset, length));
 					self.common.queue_size += length;
 
 					self.process_descriptors(cx, reader, stream.state)?;
 				}
 				None => {
-					self.queue.push_back((Heap::boxed(buffer.get()), offset, length));
+					self.queue.push_back((BoxedHeap::new(buffer.get()), offset, length));
 					self.common.queue_size += length;
 				}
 			}
@@ -954,8 +981,8 @@ impl ControllerInternals for ByteStreamController {
 #[ion(name = "ReadableStreamBYOBRequest")]
 pub struct ByobRequest {
 	reflector: Reflector,
-	pub(crate) controller: Option<Box<Heap<*mut JSObject>>>,
-	pub(crate) view: Option<Box<Heap<*mut JSObject>>>,
+	pub(crate) controller: Option<BoxedHeap<*mut JSObject>>,
+	pub(crate) view: Option<BoxedHeap<*mut JSObject>>,
 }
 
 #[js_class]
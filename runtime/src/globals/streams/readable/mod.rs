@@ -4,27 +4,42 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::cell::Cell;
 use std::collections::VecDeque;
 use std::rc::Rc;
 
+use bytes::{Bytes, BytesMut};
 pub use controller::{ByobRequest, ByteStreamController, CommonController, DefaultController};
 use controller::{Controller, ControllerInternals, ControllerKind};
+use futures::{stream, Stream};
 use ion::class::{NativeObject, Reflector};
 use ion::conversions::{ConversionBehavior, FromValue, ToValue};
 use ion::function::Opt;
-use ion::{ClassDefinition, Context, Error, ErrorKind, Function, Local, Object, Promise, Result, ResultExc, Value};
-use mozjs::jsapi::{Heap, JSObject};
+use ion::{
+	BoxedHeap, ClassDefinition, Context, Error, ErrorKind, Function, Local, Object, Promise, Result, ResultExc, Value,
+};
+use mozjs::jsapi::JSObject;
 use mozjs::jsval::JSVal;
+pub use pipe::PipeOptions;
 pub use reader::{ByobReader, CommonReader, DefaultReader};
 use reader::{Reader, ReaderKind};
-pub use source::StreamSource;
+pub use source::{BytesStream, StreamSource};
 use source::{forward_reader_error, TeeBytesState, TeeDefaultState};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::globals::streams::writable::WritableStream;
+use crate::globals::streams::QueueingStrategy;
 
 mod controller;
+mod pipe;
 mod reader;
 mod source;
 
+/// Maximum size of the chunks read from an [AsyncRead] by [ReadableStream::from_async_read].
+const ASYNC_READ_CHUNK_SIZE: usize = 64 * 1024;
+
 #[derive(Default, FromValue)]
+#[ion(validate = UnderlyingSource::validate)]
 pub struct UnderlyingSource<'cx> {
 	start: Option<Function<'cx>>,
 	pull: Option<Function<'cx>>,
@@ -36,24 +51,28 @@ pub struct UnderlyingSource<'cx> {
 }
 
 impl UnderlyingSource<'_> {
+	fn validate(_: &Context, source: &UnderlyingSource) -> Result<()> {
+		match source.ty.as_deref() {
+			None | Some("bytes") => Ok(()),
+			Some(_) => Err(Error::new(
+				"Type of Underlying Source must be 'bytes' or not exist.",
+				ErrorKind::Type,
+			)),
+		}
+	}
+
 	pub(crate) fn to_native(&self, object: Option<&Object>) -> StreamSource {
 		match object {
 			Some(object) => StreamSource::Script {
-				object: Heap::boxed(object.handle().get()),
-				pull: self.pull.as_ref().map(|pull| Heap::boxed(pull.get())),
-				cancel: self.cancel.as_ref().map(|cancel| Heap::boxed(cancel.get())),
+				object: BoxedHeap::new(object.handle().get()),
+				pull: self.pull.as_ref().map(|pull| BoxedHeap::new(pull.get())),
+				cancel: self.cancel.as_ref().map(|cancel| BoxedHeap::new(cancel.get())),
 			},
 			None => StreamSource::None,
 		}
 	}
 }
 
-#[derive(Default, FromValue)]
-pub struct QueueingStrategy<'cx> {
-	high_water_mark: Option<f64>,
-	size: Option<Function<'cx>>,
-}
-
 #[derive(Default, FromValue)]
 pub struct ReaderOptions {
 	mode: Option<String>,
@@ -71,14 +90,14 @@ pub struct ReadableStream {
 	reflector: Reflector,
 
 	pub(crate) controller_kind: ControllerKind,
-	pub(crate) controller: Box<Heap<*mut JSObject>>,
+	pub(crate) controller: BoxedHeap<*mut JSObject>,
 
 	pub(crate) reader_kind: ReaderKind,
-	pub(crate) reader: Option<Box<Heap<*mut JSObject>>>,
+	pub(crate) reader: Option<BoxedHeap<*mut JSObject>>,
 
 	pub(crate) state: State,
 	pub(crate) disturbed: bool,
-	pub(crate) error: Option<Box<Heap<JSVal>>>,
+	pub(crate) error: Option<BoxedHeap<JSVal>>,
 }
 
 #[js_class]
@@ -103,31 +122,20 @@ impl ReadableStream {
 						return Err(Error::new("Implementation preserved member 'size'", ErrorKind::Range).into());
 					}
 
-					if let Some(high_water_mark) = strategy.high_water_mark {
-						if high_water_mark.is_nan() {
-							return Err(Error::new("highWaterMark cannot be NaN", ErrorKind::Range).into());
-						} else if high_water_mark < 0.0 {
-							return Err(Error::new("highWaterMark must be non-negative", ErrorKind::Range).into());
-						}
-					}
-					let high_water_mark = strategy.high_water_mark.unwrap_or(0.0);
+					let high_water_mark = strategy.high_water_mark(0.0)?;
 
 					let controller =
 						ByteStreamController::initialise(this, underlying_source, source, high_water_mark)?;
-					let controller = Heap::boxed(ByteStreamController::new_object(cx, Box::new(controller)));
-					unsafe {
+					let controller = BoxedHeap::new(ByteStreamController::new_object(cx, Box::new(controller)));
+					let checkpoint = unsafe {
 						let controller = Object::from(Local::from_heap(&controller));
-						ByteStreamController::get_mut_private_unchecked(&controller)
-							.start(cx, source.start.as_ref())?;
+						ByteStreamController::get_mut_private_unchecked(&controller).start(cx, source.start.as_ref())?
+					};
+					if let Some(checkpoint) = checkpoint {
+						checkpoint.defer();
 					}
 
 					Ok(Some((ControllerKind::ByteStream, controller)))
-				} else if source.ty.is_some() {
-					Err(Error::new(
-						"Type of Underlying Source must be 'bytes' or not exist.",
-						ErrorKind::Type,
-					)
-					.into())
 				} else {
 					Ok(None)
 				}
@@ -147,10 +155,15 @@ impl ReadableStream {
 					&strategy,
 					high_water_mark,
 				);
-				let controller = Heap::boxed(DefaultController::new_object(cx, Box::new(controller)));
-				unsafe {
+				let controller = BoxedHeap::new(DefaultController::new_object(cx, Box::new(controller)));
+				// The stream is only initialised once the constructor returns,
+				// so the reactions must wait for the event loop.
+				let checkpoint = unsafe {
 					let controller = Object::from(Local::from_heap(&controller));
-					DefaultController::get_mut_private_unchecked(&controller).start(cx, source.start.as_ref())?;
+					DefaultController::get_mut_private_unchecked(&controller).start(cx, source.start.as_ref())?
+				};
+				if let Some(checkpoint) = checkpoint {
+					checkpoint.defer();
 				}
 
 				(ControllerKind::Default, controller)
@@ -169,23 +182,7 @@ impl ReadableStream {
 		if self.get_locked() {
 			Err(Error::new("ReadableStream is locked.", ErrorKind::Type).into())
 		} else {
-			self.disturbed = true;
-			match self.state {
-				State::Readable => {
-					self.close(cx)?;
-					self.native_controller(cx)?.cancel(cx, reason)
-				}
-				State::Closed => Ok(Promise::resolved(cx, &Value::undefined_handle())),
-				State::Errored => {
-					let mut value = Value::null(cx);
-					if let Some(error) = &self.error {
-						value.handle_mut().set(error.get());
-					}
-					let promise = Promise::new(cx);
-					promise.reject(cx, &value);
-					Ok(promise)
-				}
-			}
+			self.cancel_internal(cx, reason)
 		}
 	}
 
@@ -205,7 +202,7 @@ impl ReadableStream {
 				let object = Object::from(cx.root(ByobReader::new_object(cx, Box::new(reader))));
 
 				self.reader_kind = ReaderKind::Byob;
-				self.reader = Some(Heap::boxed(object.handle().get()));
+				self.reader = Some(BoxedHeap::new(object.handle().get()));
 
 				Ok(object)
 			} else {
@@ -216,7 +213,7 @@ impl ReadableStream {
 			let object = Object::from(cx.root(DefaultReader::new_object(cx, Box::new(reader))));
 
 			self.reader_kind = ReaderKind::Default;
-			self.reader = Some(Heap::boxed(object.handle().get()));
+			self.reader = Some(BoxedHeap::new(object.handle().get()));
 
 			Ok(object)
 		}
@@ -226,10 +223,24 @@ impl ReadableStream {
 		self.get_reader(cx, Opt(None))?;
 		Ok(self.tee_internal(cx, false))
 	}
+
+	#[ion(name = "pipeTo")]
+	pub fn pipe_to<'cx>(
+		&mut self, cx: &'cx Context, destination: Object, Opt(options): Opt<PipeOptions>,
+	) -> ResultExc<Promise<'cx>> {
+		let destination = WritableStream::get_mut_private(cx, &destination)?;
+		if self.get_locked() {
+			return Err(Error::new("ReadableStream is locked.", ErrorKind::Type).into());
+		}
+		if destination.get_locked() {
+			return Err(Error::new("WritableStream is locked.", ErrorKind::Type).into());
+		}
+		pipe::pipe_to(cx, self, destination, options.unwrap_or_default())
+	}
 }
 
 impl ReadableStream {
-	pub(crate) fn new(controller_kind: ControllerKind, controller: Box<Heap<*mut JSObject>>) -> ReadableStream {
+	pub(crate) fn new(controller_kind: ControllerKind, controller: BoxedHeap<*mut JSObject>) -> ReadableStream {
 		ReadableStream {
 			reflector: Reflector::default(),
 
@@ -245,6 +256,84 @@ impl ReadableStream {
 		}
 	}
 
+	/// Creates a readable byte stream which pulls its chunks from a native [Stream] on the future queue.
+	pub fn from_bytes_stream<'cx, S>(cx: &'cx Context, stream: S) -> Object<'cx>
+	where
+		S: Stream<Item = Result<Bytes>> + 'static,
+	{
+		let object = Object::from(cx.root(ReadableStream::new_raw_object(cx)));
+
+		let stream: BytesStream = Box::pin(stream);
+		let source = StreamSource::Stream(Some(Rc::new(Cell::new(Some(stream)))));
+		let controller = ByteStreamController {
+			common: CommonController::new(&object, source, 0.0),
+			auto_allocate_chunk_size: 0,
+			byob_request: None,
+			pending_descriptors: VecDeque::default(),
+			queue: VecDeque::default(),
+		};
+		let controller = BoxedHeap::new(ByteStreamController::new_object(cx, Box::new(controller)));
+
+		let _checkpoint = unsafe {
+			let controller = Object::from(Local::from_heap(&controller));
+			ByteStreamController::get_mut_private_unchecked(&controller).start_native(cx)
+		};
+
+		let stream = ReadableStream::new(ControllerKind::ByteStream, controller);
+		unsafe {
+			ReadableStream::set_private(object.handle().get(), Box::new(stream));
+		}
+		object
+	}
+
+	/// Creates a readable byte stream which reads from an [AsyncRead] on the future queue.
+	///
+	/// Chunks of up to 64 KiB are only read when the stream is pulled, so the data is never buffered in memory before
+	/// it is read from the stream. Reads from BYOB readers are filled from these chunks.
+	pub fn from_async_read<'cx, R>(cx: &'cx Context, reader: R) -> Object<'cx>
+	where
+		R: AsyncRead + Unpin + 'static,
+	{
+		let stream = stream::unfold(Some(reader), |reader| async move {
+			let mut reader = reader?;
+			let mut buffer = BytesMut::with_capacity(ASYNC_READ_CHUNK_SIZE);
+			match reader.read_buf(&mut buffer).await {
+				Ok(0) => None,
+				Ok(_) => Some((Ok(buffer.freeze()), Some(reader))),
+				Err(error) => Some((Err(Error::new(format!("Failed to read: {error}"), None)), None)),
+			}
+		});
+		ReadableStream::from_bytes_stream(cx, stream)
+	}
+
+	/// Creates a readable stream which reads its chunks from the reader of a transferred stream.
+	pub(crate) fn from_transferred<'cx>(cx: &'cx Context, reader: &Object) -> Object<'cx> {
+		let object = Object::from(cx.root(ReadableStream::new_raw_object(cx)));
+
+		let source = StreamSource::Transferred(BoxedHeap::new(reader.handle().get()));
+		let controller = DefaultController {
+			common: CommonController::new(&object, source, 0.0),
+			size: None,
+			queue: VecDeque::default(),
+		};
+		let controller = BoxedHeap::new(DefaultController::new_object(cx, Box::new(controller)));
+
+		let checkpoint = unsafe {
+			let controller = Object::from(Local::from_heap(&controller));
+			DefaultController::get_mut_private_unchecked(&controller).start_native(cx)
+		};
+		// Microtasks must not run while the structured clone is being read.
+		if let Some(checkpoint) = checkpoint {
+			checkpoint.defer();
+		}
+
+		let stream = ReadableStream::new(ControllerKind::Default, controller);
+		unsafe {
+			ReadableStream::set_private(object.handle().get(), Box::new(stream));
+		}
+		object
+	}
+
 	pub(crate) fn tee_internal<'cx>(&mut self, cx: &'cx Context, clone_branch_2: bool) -> [Object<'cx>; 2] {
 		match self.controller_kind {
 			ControllerKind::Default => {
@@ -256,12 +345,12 @@ impl ReadableStream {
 						size: None,
 						queue: VecDeque::default(),
 					};
-					let controller = Heap::boxed(DefaultController::new_object(cx, Box::new(controller)));
+					let controller = BoxedHeap::new(DefaultController::new_object(cx, Box::new(controller)));
 
-					unsafe {
+					let _checkpoint = unsafe {
 						let controller = Object::from(Local::from_heap(&controller));
-						DefaultController::get_mut_private_unchecked(&controller).start(cx, None).unwrap();
-					}
+						DefaultController::get_mut_private_unchecked(&controller).start_native(cx)
+					};
 
 					let stream = ReadableStream::new(ControllerKind::Default, controller);
 					unsafe {
@@ -290,12 +379,12 @@ impl ReadableStream {
 						pending_descriptors: VecDeque::default(),
 						queue: VecDeque::default(),
 					};
-					let controller = Heap::boxed(ByteStreamController::new_object(cx, Box::new(controller)));
+					let controller = BoxedHeap::new(ByteStreamController::new_object(cx, Box::new(controller)));
 
-					unsafe {
+					let _checkpoint = unsafe {
 						let controller = Object::from(Local::from_heap(&controller));
-						ByteStreamController::get_mut_private_unchecked(&controller).start(cx, None).unwrap();
-					}
+						ByteStreamController::get_mut_private_unchecked(&controller).start_native(cx)
+					};
 
 					let stream = ReadableStream::new(ControllerKind::ByteStream, controller);
 					unsafe {
@@ -319,6 +408,26 @@ impl ReadableStream {
 		}
 	}
 
+	pub(crate) fn cancel_internal<'cx>(&mut self, cx: &'cx Context, reason: Option<Value>) -> ResultExc<Promise<'cx>> {
+		self.disturbed = true;
+		match self.state {
+			State::Readable => {
+				self.close(cx)?;
+				self.native_controller(cx)?.cancel(cx, reason)
+			}
+			State::Closed => Ok(Promise::resolved(cx, &Value::undefined_handle())),
+			State::Errored => {
+				let mut value = Value::null(cx);
+				if let Some(error) = &self.error {
+					value.handle_mut().set(error.get());
+				}
+				let promise = Promise::new(cx);
+				promise.reject(cx, &value);
+				Ok(promise)
+			}
+		}
+	}
+
 	pub(crate) fn close(&mut self, cx: &Context) -> ResultExc<()> {
 		if self.state != State::Readable {
 			return Err(Error::new("Cannot Close Stream", None).into());
@@ -333,8 +442,7 @@ impl ReadableStream {
 		closed.resolve(cx, &Value::undefined_handle());
 		if self.reader_kind == ReaderKind::Default {
 			for request in &*requests {
-				let promise = request.promise();
-				(request.close)(cx, &promise, None)?;
+				unsafe { cx.scope(|cx| (request.close)(cx, &request.promise(), None)) }?;
 			}
 			requests.clear();
 		}
@@ -347,7 +455,7 @@ impl ReadableStream {
 			return Err(Error::new("Cannot Error Stream", None));
 		}
 		self.state = State::Errored;
-		self.error = Some(Heap::boxed(error.get()));
+		self.error = Some(BoxedHeap::new(error.get()));
 
 		let (requests, closed) = match self.native_reader(cx)? {
 			Some(reader) => reader.requests_closed(),
@@ -356,8 +464,7 @@ impl ReadableStream {
 
 		closed.reject(cx, error);
 		for request in &*requests {
-			let promise = request.promise();
-			(request.error)(cx, &promise, error);
+			unsafe { cx.scope(|cx| (request.error)(cx, &request.promise(), error)) };
 		}
 		requests.clear();
 
@@ -0,0 +1,357 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use futures::future::{select, Either};
+use ion::conversions::ToValue;
+use ion::function::Opt;
+use ion::{
+	BoxedHeap, ClassDefinition, Context, Error, ErrorKind, Local, Object, Promise, Result, ResultExc, TracedHeap, Value,
+};
+use mozjs::jsapi::JSObject;
+use mozjs::jsval::JSVal;
+
+use crate::globals::abort::{AbortSender, AbortSignal, Signal};
+use crate::globals::streams::readable::controller::{Controller, ControllerInternals};
+use crate::globals::streams::readable::reader::Request;
+use crate::globals::streams::readable::{BytesStream, DefaultReader, ReadableStream, State, StreamSource};
+use crate::globals::streams::writable::{DefaultWriter, SinkFuture, State as WritableState, WritableStream};
+use crate::promise::future_to_promise;
+use crate::ContextExt;
+
+#[derive(Default, FromValue)]
+pub struct PipeOptions {
+	#[ion(default)]
+	prevent_close: bool,
+	#[ion(default)]
+	prevent_abort: bool,
+	#[ion(default)]
+	prevent_cancel: bool,
+}
+
+/// State of a pipe from a readable stream to a writable stream, which holds the locks of both streams until it is
+/// finalised.
+struct Pipe {
+	reader: TracedHeap<*mut JSObject>,
+	writer: TracedHeap<*mut JSObject>,
+	promise: TracedHeap<*mut JSObject>,
+	options: PipeOptions,
+	/// Stops the native forwarding of chunks once the pipe shuts down.
+	cancel: AbortSender,
+
+	shutting_down: Cell<bool>,
+	pending_write: RefCell<Option<TracedHeap<*mut JSObject>>>,
+	error: RefCell<Option<TracedHeap<JSVal>>>,
+}
+
+/// Pipes the readable stream to the writable stream, and returns a promise which settles once the pipe has finished.
+///
+/// If the source is a native byte stream and the underlying sink of the destination supports
+/// [writing streams](crate::globals::streams::writable::NativeSink::write_stream), the chunks are forwarded natively,
+/// without being read into JS. Forwarding stops once the pipe shuts down or the destination is aborted.
+pub(crate) fn pipe_to<'cx>(
+	cx: &'cx Context, stream: &mut ReadableStream, destination: &mut WritableStream, options: PipeOptions,
+) -> ResultExc<Promise<'cx>> {
+	let (cancel, cancelled) = AbortSender::new();
+	let native = native_pipe(cx, stream, destination, cancelled)?;
+
+	let reader = stream.get_reader(cx, Opt(None))?;
+	let writer = destination.get_writer(cx)?;
+	stream.disturbed = true;
+
+	let promise = Promise::new(cx);
+	let pipe = Rc::new(Pipe {
+		reader: TracedHeap::new(reader.handle().get()),
+		writer: TracedHeap::new(writer.handle().get()),
+		promise: TracedHeap::new(promise.get()),
+		options,
+		cancel,
+
+		shutting_down: Cell::new(false),
+		pending_write: RefCell::new(None),
+		error: RefCell::new(None),
+	});
+
+	let (pipe1, pipe2) = (Rc::clone(&pipe), Rc::clone(&pipe));
+	DefaultReader::get_private(cx, &reader)?.common.closed().add_reactions(
+		cx,
+		move |cx, _| {
+			pipe1.source_closed(cx)?;
+			Ok(Value::undefined_handle())
+		},
+		move |cx, error| {
+			pipe2.source_errored(cx, error)?;
+			Ok(Value::undefined_handle())
+		},
+	);
+
+	let (pipe1, pipe2) = (Rc::clone(&pipe), Rc::clone(&pipe));
+	DefaultWriter::get_private(cx, &writer)?.closed().add_reactions(
+		cx,
+		move |cx, _| {
+			let error = Error::new("Destination WritableStream has been closed.", ErrorKind::Type);
+			pipe1.destination_errored(cx, &error.as_value(cx))?;
+			Ok(Value::undefined_handle())
+		},
+		move |cx, error| {
+			pipe2.destination_errored(cx, error)?;
+			Ok(Value::undefined_handle())
+		},
+	);
+
+	match native {
+		Some(future) => {
+			let written = future_to_promise(cx, future).unwrap();
+			let (pipe1, pipe2) = (Rc::clone(&pipe), Rc::clone(&pipe));
+			written.add_reactions(
+				cx,
+				move |cx, _| {
+					if let Some(stream) = pipe1.reader(cx)?.common.stream(cx)? {
+						if stream.state == State::Readable {
+							stream.native_controller(cx)?.into_byte_stream().unwrap().close(cx)?;
+						}
+					}
+					Ok(Value::undefined_handle())
+				},
+				move |cx, error| {
+					if let Some(stream) = pipe2.reader(cx)?.common.stream(cx)? {
+						stream.native_controller(cx)?.into_byte_stream().unwrap().error_internal(cx, error)?;
+					}
+					Ok(Value::undefined_handle())
+				},
+			);
+		}
+		None => pipe.step(cx)?,
+	}
+
+	Ok(promise)
+}
+
+/// Takes the native byte stream of the source and passes it to the native sink of the destination, if both of them
+/// are native, and have not been read from or written to yet.
+///
+/// The returned future fails once `cancelled` or the abort signal of the destination is aborted, as the chunks can no
+/// longer be read from the source.
+fn native_pipe(
+	cx: &Context, stream: &ReadableStream, destination: &WritableStream, cancelled: Signal,
+) -> Result<Option<SinkFuture>> {
+	if stream.disturbed
+		|| stream.state != State::Readable
+		|| destination.state != WritableState::Writable
+		|| destination.has_operation_marked_in_flight()
+		|| destination.close_queued_or_in_flight()
+		|| !destination.write_requests.is_empty()
+		|| unsafe { cx.get_private().event_loop.futures.is_none() }
+	{
+		return Ok(None);
+	}
+
+	let Controller::ByteStream(controller) = stream.native_controller(cx)? else {
+		return Ok(None);
+	};
+	if controller.common.pulling || !controller.queue.is_empty() || !controller.pending_descriptors.is_empty() {
+		return Ok(None);
+	}
+	let StreamSource::Stream(Some(slot)) = &controller.common.source else {
+		return Ok(None);
+	};
+	let Some(bytes): Option<BytesStream> = slot.take() else {
+		return Ok(None);
+	};
+
+	let controller = destination.native_controller(cx)?;
+	let signal = Object::from(cx.root(controller.get_signal()));
+	let aborted = AbortSignal::get_private(cx, &signal)?.signal.clone();
+	match controller.sink.write_stream(bytes) {
+		Ok(future) => Ok(Some(Box::pin(async move {
+			match select(future, select(aborted.poll(), cancelled.poll())).await {
+				Either::Left((result, _)) => result,
+				Either::Right(_) => Err(Error::new("Pipe was aborted.", ErrorKind::Type)),
+			}
+		}))),
+		Err(bytes) => {
+			slot.set(Some(bytes));
+			Ok(None)
+		}
+	}
+}
+
+impl Pipe {
+	fn reader<'p>(&'p self, cx: &Context) -> Result<&'p mut DefaultReader> {
+		DefaultReader::get_mut_private(cx, &Object::from(self.reader.to_local()))
+	}
+
+	fn writer<'p>(&'p self, cx: &Context) -> Result<&'p mut DefaultWriter> {
+		DefaultWriter::get_mut_private(cx, &Object::from(self.writer.to_local()))
+	}
+
+	/// Reads the next chunk once the destination is ready for it.
+	fn step(self: &Rc<Self>, cx: &Context) -> ResultExc<()> {
+		if self.shutting_down.get() {
+			return Ok(());
+		}
+
+		let pipe = Rc::clone(self);
+		self.writer(cx)?.ready().add_reactions(
+			cx,
+			move |cx, _| {
+				pipe.read(cx)?;
+				Ok(Value::undefined_handle())
+			},
+			|_, _| Ok(Value::undefined_handle()),
+		);
+		Ok(())
+	}
+
+	fn read(self: &Rc<Self>, cx: &Context) -> ResultExc<()> {
+		if self.shutting_down.get() {
+			return Ok(());
+		}
+
+		let pipe = Rc::clone(self);
+		let request = Request {
+			promise: BoxedHeap::new(Promise::new(cx).get()),
+			chunk: Box::new(move |cx, _, chunk| {
+				if let Err(exception) = pipe.write(cx, chunk) {
+					let _ = pipe.destination_errored(cx, &exception.as_value(cx));
+				}
+			}),
+			close: Box::new(|_, _, _| Ok(())),
+			error: Box::new(|_, _, _| {}),
+		};
+		self.reader(cx)?.read_internal(cx, request)?;
+		Ok(())
+	}
+
+	fn write(self: &Rc<Self>, cx: &Context, chunk: &Value) -> ResultExc<()> {
+		if self.shutting_down.get() {
+			return Ok(());
+		}
+
+		let chunk = Value::from(Local::from_handle(chunk.handle()));
+		let written = self.writer(cx)?.write(cx, Opt(Some(chunk)))?;
+		written.catch(cx, |_, _| Ok(Value::undefined_handle()));
+		*self.pending_write.borrow_mut() = Some(TracedHeap::new(written.get()));
+
+		self.step(cx)
+	}
+
+	fn source_closed(self: &Rc<Self>, cx: &Context) -> ResultExc<()> {
+		if self.options.prevent_close {
+			self.shutdown(cx, None)
+		} else {
+			self.shutdown_with_action(cx, None, |cx, pipe| pipe.writer(cx)?.close(cx))
+		}
+	}
+
+	fn source_errored(self: &Rc<Self>, cx: &Context, error: &Value) -> ResultExc<()> {
+		if self.options.prevent_abort {
+			self.shutdown(cx, Some(error))
+		} else {
+			let reason = TracedHeap::new(error.get());
+			self.shutdown_with_action(cx, Some(error), move |cx, pipe| {
+				let reason = Value::from(reason.to_local());
+				pipe.writer(cx)?.abort(cx, Opt(Some(reason)))
+			})
+		}
+	}
+
+	fn destination_errored(self: &Rc<Self>, cx: &Context, error: &Value) -> ResultExc<()> {
+		if self.options.prevent_cancel {
+			self.shutdown(cx, Some(error))
+		} else {
+			let reason = TracedHeap::new(error.get());
+			self.shutdown_with_action(cx, Some(error), move |cx, pipe| {
+				let reason = Value::from(reason.to_local());
+				pipe.reader(cx)?.cancel(cx, Opt(Some(reason)))
+			})
+		}
+	}
+
+	fn shutdown_with_action<A>(self: &Rc<Self>, cx: &Context, error: Option<&Value>, action: A) -> ResultExc<()>
+	where
+		A: for<'cx> FnOnce(&'cx Context, &Pipe) -> ResultExc<Promise<'cx>> + 'static,
+	{
+		if self.shutting_down.replace(true) {
+			return Ok(());
+		}
+		*self.error.borrow_mut() = error.map(|error| TracedHeap::new(error.get()));
+		self.cancel.abort(cx, error);
+
+		let pipe = Rc::clone(self);
+		self.after_pending_write(cx, move |cx| {
+			let (pipe1, pipe2) = (Rc::clone(&pipe), Rc::clone(&pipe));
+			action(cx, &pipe)?.add_reactions(
+				cx,
+				move |cx, _| {
+					pipe1.finalise(cx)?;
+					Ok(Value::undefined_handle())
+				},
+				move |cx, error| {
+					*pipe2.error.borrow_mut() = Some(TracedHeap::new(error.get()));
+					pipe2.finalise(cx)?;
+					Ok(Value::undefined_handle())
+				},
+			);
+			Ok(())
+		})
+	}
+
+	fn shutdown(self: &Rc<Self>, cx: &Context, error: Option<&Value>) -> ResultExc<()> {
+		if self.shutting_down.replace(true) {
+			return Ok(());
+		}
+		*self.error.borrow_mut() = error.map(|error| TracedHeap::new(error.get()));
+		self.cancel.abort(cx, error);
+
+		let pipe = Rc::clone(self);
+		self.after_pending_write(cx, move |cx| pipe.finalise(cx))
+	}
+
+	/// Calls the callback once the last chunk read from the source has been written to the destination.
+	fn after_pending_write<F>(&self, cx: &Context, callback: F) -> ResultExc<()>
+	where
+		F: for<'cx> FnOnce(&'cx Context) -> ResultExc<()> + 'static,
+	{
+		let pending = self.pending_write.borrow().as_ref().map(TracedHeap::get);
+		let Some(pending) = pending else {
+			return callback(cx);
+		};
+
+		let callback = Rc::new(Cell::new(Some(callback)));
+		let callback2 = Rc::clone(&callback);
+		Promise::from(cx.root(pending)).unwrap().add_reactions(
+			cx,
+			move |cx, _| {
+				if let Some(callback) = callback.take() {
+					callback(cx)?;
+				}
+				Ok(Value::undefined_handle())
+			},
+			move |cx, _| {
+				if let Some(callback) = callback2.take() {
+					callback(cx)?;
+				}
+				Ok(Value::undefined_handle())
+			},
+		);
+		Ok(())
+	}
+
+	fn finalise(&self, cx: &Context) -> ResultExc<()> {
+		self.writer(cx)?.release_lock(cx)?;
+		self.reader(cx)?.release_lock(cx)?;
+
+		let promise = Promise::from(self.promise.to_local()).unwrap();
+		match &*self.error.borrow() {
+			Some(error) => promise.reject(cx, &Value::from(error.to_local())),
+			None => promise.resolve(cx, &Value::undefined_handle()),
+		};
+		Ok(())
+	}
+}
@@ -10,9 +10,9 @@ use ion::class::Reflector;
 use ion::conversions::ToValue;
 use ion::function::Opt;
 use ion::typedarray::{type_to_constructor, type_to_element_size, ArrayBufferView};
-use ion::{ClassDefinition, Context, Error, ErrorKind, Local, Object, Promise, Result, ResultExc, Value};
+use ion::{BoxedHeap, ClassDefinition, Context, Error, ErrorKind, Local, Object, Promise, Result, ResultExc, Value};
 use mozjs::conversions::ConversionBehavior;
-use mozjs::jsapi::{Heap, JSObject};
+use mozjs::jsapi::JSObject;
 
 use crate::globals::streams::readable::controller::{ControllerInternals, ControllerKind, PullIntoDescriptor};
 use crate::globals::streams::readable::{ReadableStream, State};
@@ -22,7 +22,7 @@ pub type CloseClosure = dyn Fn(&Context, &Promise, Option<&Value>) -> ResultExc<
 
 #[derive(Traceable)]
 pub struct Request {
-	pub(crate) promise: Box<Heap<*mut JSObject>>,
+	pub(crate) promise: BoxedHeap<*mut JSObject>,
 	#[trace(no_trace)]
 	pub(crate) chunk: Box<ChunkErrorClosure>,
 	#[trace(no_trace)]
@@ -46,7 +46,7 @@ impl Request {
 		}
 
 		Request {
-			promise: Heap::boxed(promise),
+			promise: BoxedHeap::new(promise),
 			chunk: Box::new(|cx, promise, chunk| {
 				let result = ReadResult {
 					value: Some(Value::from(Local::from_handle(chunk.handle()))),
@@ -129,9 +129,9 @@ impl<'r> Reader<'r> {
 pub struct CommonReader {
 	reflector: Reflector,
 
-	stream: Option<Box<Heap<*mut JSObject>>>,
+	stream: Option<BoxedHeap<*mut JSObject>>,
 	pub(crate) requests: VecDeque<Request>,
-	pub(crate) closed: Box<Heap<*mut JSObject>>,
+	pub(crate) closed: BoxedHeap<*mut JSObject>,
 }
 
 #[js_class]
@@ -150,9 +150,9 @@ impl CommonReader {
 
 		CommonReader {
 			reflector: Reflector::default(),
-			stream: Some(Heap::boxed(stream_object.handle().get())),
+			stream: Some(BoxedHeap::new(stream_object.handle().get())),
 			requests: VecDeque::new(),
-			closed: Heap::boxed(closed.get()),
+			closed: BoxedHeap::new(closed.get()),
 		}
 	}
 
@@ -170,9 +170,9 @@ impl CommonReader {
 		Promise::from(unsafe { Local::from_heap(&self.closed) }).unwrap()
 	}
 
-	pub(crate) fn cancel<'cx>(&self, cx: &'cx Context, reason: Opt<Value>) -> ResultExc<Promise<'cx>> {
+	pub(crate) fn cancel<'cx>(&self, cx: &'cx Context, Opt(reason): Opt<Value>) -> ResultExc<Promise<'cx>> {
 		if let Some(stream) = self.stream(cx)? {
-			stream.cancel(cx, reason)
+			stream.cancel_internal(cx, reason)
 		} else {
 			let promise = Promise::new(cx);
 			promise.reject_with_error(cx, &Error::new("Reader has already been released.", ErrorKind::Type));
@@ -198,12 +198,12 @@ impl CommonReader {
 			stream.native_controller(cx)?.release();
 
 			while let Some(request) = self.requests.pop_front() {
-				let promise = request.promise();
-				(request.error)(
-					cx,
-					&promise,
-					&Error::new("Reader has been released.", ErrorKind::Type).as_value(cx),
-				);
+				unsafe {
+					cx.scope(|cx| {
+						let error = Error::new("Reader has been released.", ErrorKind::Type).as_value(cx);
+						(request.error)(cx, &request.promise(), &error);
+					})
+				};
 			}
 		} else {
 			return Err(Error::new("Reader has already been released.", ErrorKind::Type));
@@ -226,7 +226,7 @@ impl DefaultReader {
 		let reader = DefaultReader::new(cx, &stream_object)?;
 		let stream = ReadableStream::get_mut_private(cx, &stream_object)?;
 		stream.reader_kind = ReaderKind::Default;
-		stream.reader = Some(Heap::boxed(this.handle().get()));
+		stream.reader = Some(BoxedHeap::new(this.handle().get()));
 
 		Ok(reader)
 	}
@@ -306,7 +306,7 @@ impl ByobReader {
 		let reader = ByobReader::new(cx, &stream_object)?;
 		let stream = ReadableStream::get_mut_private(cx, &stream_object)?;
 		stream.reader_kind = ReaderKind::Byob;
-		stream.reader = Some(Heap::boxed(this.handle().get()));
+		stream.reader = Some(BoxedHeap::new(this.handle().get()));
 
 		Ok(reader)
 	}
@@ -355,9 +355,9 @@ impl ByobReader {
 		match buffer.transfer(cx) {
 			Ok(buffer) => {
 				let mut descriptor = PullIntoDescriptor {
-					buffer: Heap::boxed(buffer.get()),
+					buffer: BoxedHeap::new(buffer.get()),
 					offset,
-					length: length * element_size,
+					length,
 					filled: 0,
 					min: min * element_size,
 					element: element_size,
@@ -457,7 +457,8 @@ impl ByobReader {
 				return Ok(promise);
 			}
 
-			if options.min > view.len() as u64 {
+			let element_size = type_to_element_size(view.view_type());
+			if options.min > (view.len() / element_size) as u64 {
 				promise.reject(
 					cx,
 					&Error::new("min is greater than View Length", ErrorKind::Range).as_value(cx),
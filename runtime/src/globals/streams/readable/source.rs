@@ -5,40 +5,50 @@
  */
 
 use std::cell::Cell;
+use std::pin::Pin;
+use std::ptr;
 use std::rc::Rc;
 
 use bytes::{Buf, Bytes};
+use futures::{Stream, StreamExt};
 use ion::class::NativeObject;
 use ion::clone::StructuredCloneBuffer;
-use ion::conversions::{FromValue, ToValue};
+use ion::conversions::{FromValue, IntoValue, ToValue};
 use ion::function::Opt;
-use ion::typedarray::{ArrayBuffer, ArrayBufferView, Uint8Array};
+use ion::typedarray::{ArrayBuffer, ArrayBufferView, Uint8Array, Uint8ArrayWrapper};
 use ion::{
-	ClassDefinition, Context, Exception, Function, JSIterator, Local, Object, Promise, Result, ResultExc, TracedHeap,
-	Value,
+	BoxedHeap, ClassDefinition, Context, Error, Exception, Function, JSIterator, Local, Object, Promise, Result,
+	ResultExc, TracedHeap, Value,
 };
 use mozjs::gc::HandleObject;
-use mozjs::jsapi::{CloneDataPolicy, Heap, JSFunction, JSObject, StructuredCloneScope};
+use mozjs::jsapi::{CloneDataPolicy, JSFunction, JSObject, StructuredCloneScope};
 use mozjs::jsval::{JSVal, UndefinedValue};
 
 use crate::globals::clone::{StructuredCloneDataHolder, STRUCTURED_CLONE_CALLBACKS};
 use crate::globals::streams::readable::controller::ControllerInternals;
 use crate::globals::streams::readable::reader::{ReaderKind, Request};
-use crate::globals::streams::readable::{ByobRequest, ByteStreamController, ReadableStream, ReaderOptions};
+use crate::globals::streams::readable::{
+	ByobRequest, ByteStreamController, DefaultController, DefaultReader, ReadableStream, ReaderOptions,
+};
+use crate::promise::future_to_promise;
+
+pub type BytesStream = Pin<Box<dyn Stream<Item = Result<Bytes>>>>;
 
 #[derive(Traceable)]
 pub enum StreamSource {
 	None,
 	Script {
-		object: Box<Heap<*mut JSObject>>,
-		pull: Option<Box<Heap<*mut JSFunction>>>,
-		cancel: Option<Box<Heap<*mut JSFunction>>>,
+		object: BoxedHeap<*mut JSObject>,
+		pull: Option<BoxedHeap<*mut JSFunction>>,
+		cancel: Option<BoxedHeap<*mut JSFunction>>,
 	},
 	Bytes(#[trace(no_trace)] Option<Bytes>),
 	BytesBuf(#[trace(no_trace)] Option<Box<dyn Buf>>),
-	Iterator(#[trace(no_trace)] Box<dyn JSIterator>, Option<Box<Heap<JSVal>>>),
+	Iterator(#[trace(no_trace)] Box<dyn JSIterator>, Option<BoxedHeap<JSVal>>),
 	TeeDefault(Rc<TeeDefaultState>, bool),
 	TeeBytes(Rc<TeeBytesState>, bool),
+	Stream(#[trace(no_trace)] Option<Rc<Cell<Option<BytesStream>>>>),
+	Transferred(BoxedHeap<*mut JSObject>),
 }
 
 impl StreamSource {
@@ -56,10 +66,8 @@ impl StreamSource {
 				let controller = controller.as_value(cx);
 				let this = Object::from(unsafe { Local::from_heap(object) });
 
-				let result = pull.call(cx, &this, &[controller]).map_err(|report| report.unwrap().exception)?;
-				Ok(Some(
-					Promise::from_value(cx, &result, true, ()).unwrap_or_else(|_| Promise::new(cx)),
-				))
+				let result = pull.call(cx, &this, &[controller]).map_err(|report| report.unwrap().into_exception())?;
+				Ok(Some(Promise::adopt(cx, &result)))
 			}
 			StreamSource::Script { pull: None, .. } => Ok(Some(Promise::resolved(cx, &Value::undefined_handle()))),
 			StreamSource::Bytes(bytes) => Ok(bytes.take().map(|bytes| {
@@ -95,7 +103,7 @@ impl StreamSource {
 
 				let promise = Promise::new(cx);
 				let request = Request {
-					promise: Heap::boxed(promise.get()),
+					promise: BoxedHeap::new(promise.get()),
 					chunk: Box::new(move |cx, _, chunk| {
 						let promise = Promise::resolved(cx, &Value::undefined_handle());
 						let chunk = TracedHeap::new(chunk.get());
@@ -217,7 +225,7 @@ impl StreamSource {
 					let state3 = Rc::clone(state);
 
 					let request = Request {
-						promise: Heap::boxed(promise.get()),
+						promise: BoxedHeap::new(promise.get()),
 						chunk: Box::new(move |cx, _, chunk| {
 							let promise = Promise::resolved(cx, &Value::undefined_handle());
 							let chunk = TracedHeap::new(chunk.get());
@@ -322,7 +330,7 @@ impl StreamSource {
 					let second = *second;
 
 					let request = Request {
-						promise: Heap::boxed(promise.get()),
+						promise: BoxedHeap::new(promise.get()),
 						chunk: Box::new(move |cx, _, chunk| {
 							let promise = Promise::resolved(cx, &Value::undefined_handle());
 							let chunk = TracedHeap::new(chunk.get());
@@ -415,6 +423,96 @@ impl StreamSource {
 				promise.resolve(cx, &Value::undefined_handle());
 				Ok(Some(promise))
 			}
+			StreamSource::Stream(Some(slot)) => {
+				let Some(mut stream) = slot.take() else {
+					return Ok(None);
+				};
+				let slot = Rc::downgrade(slot);
+
+				let chunk = future_to_promise(cx, async move {
+					loop {
+						match stream.next().await {
+							Some(Ok(bytes)) if bytes.is_empty() => {}
+							Some(Ok(bytes)) => {
+								if let Some(slot) = slot.upgrade() {
+									slot.set(Some(stream));
+								}
+								return Ok(StreamChunk(Some(bytes)));
+							}
+							Some(Err(error)) => return Err(error),
+							None => return Ok(StreamChunk(None)),
+						}
+					}
+				});
+				let Some(chunk) = chunk else {
+					return Err(Error::new("Future Queue has not been initialised.", None).into());
+				};
+
+				let promise = Promise::new(cx);
+				let promise1 = TracedHeap::new(promise.get());
+				let promise2 = TracedHeap::new(promise.get());
+				let controller = TracedHeap::new(controller);
+
+				chunk.add_reactions(
+					cx,
+					move |cx, chunk| {
+						let controller = ByteStreamController::from_traced_heap(cx, &controller)?;
+						let stream = controller.common.stream(cx)?;
+
+						if controller.common.can_close_or_enqueue(stream) {
+							if chunk.handle().is_undefined() {
+								controller.close(cx)?;
+								if !controller.pending_descriptors.is_empty() {
+									controller.respond(cx, 0)?;
+								}
+							} else {
+								let chunk = ArrayBufferView::from_value(cx, chunk, true, ())?;
+								controller.enqueue(cx, chunk)?;
+							}
+						}
+
+						Promise::from(promise1.to_local()).unwrap().resolve(cx, &Value::undefined_handle());
+						Ok(Value::undefined_handle())
+					},
+					move |cx, error| {
+						Promise::from(promise2.to_local()).unwrap().reject(cx, error);
+						Ok(Value::undefined_handle())
+					},
+				);
+				Ok(Some(promise))
+			}
+			StreamSource::Transferred(reader) => {
+				let reader = Object::from(unsafe { Local::from_heap(reader) });
+				let reader = DefaultReader::get_mut_private(cx, &reader)?;
+
+				let controller1 = TracedHeap::new(controller);
+				let controller2 = TracedHeap::new(controller);
+
+				let promise = Promise::new(cx);
+				let request = Request {
+					promise: BoxedHeap::new(promise.get()),
+					chunk: Box::new(move |cx, promise, chunk| {
+						let result = DefaultController::from_traced_heap(cx, &controller1)
+							.map_err(Exception::from)
+							.and_then(|controller| controller.enqueue_internal(cx, chunk));
+						match result {
+							Ok(()) => promise.resolve(cx, &Value::undefined_handle()),
+							Err(exception) => promise.reject(cx, &exception.as_value(cx)),
+						};
+					}),
+					close: Box::new(move |cx, promise, _| {
+						DefaultController::from_traced_heap(cx, &controller2)?.close(cx)?;
+						promise.resolve(cx, &Value::undefined_handle());
+						Ok(())
+					}),
+					error: Box::new(|cx, promise, error| {
+						promise.reject(cx, error);
+					}),
+				};
+
+				reader.read_internal(cx, request)?;
+				Ok(Some(promise))
+			}
 			_ => Ok(None),
 		}
 	}
@@ -426,7 +524,7 @@ impl StreamSource {
 				let this = Object::from(unsafe { Local::from_heap(object) });
 				let reason = reason.unwrap_or_else(Value::undefined_handle);
 
-				let result = cancel.call(cx, &this, &[reason]).map_err(|report| report.unwrap().exception)?;
+				let result = cancel.call(cx, &this, &[reason]).map_err(|report| report.unwrap().into_exception())?;
 				if let Ok(result) = Promise::from_value(cx, &result, true, ()) {
 					result.then(cx, |_, _| Ok(Value::undefined_handle()));
 					promise.handle_mut().set(result.get());
@@ -451,6 +549,15 @@ impl StreamSource {
 
 				promise.handle_mut().set(state.common.cancel_promise.get());
 			}
+			StreamSource::Stream(stream) => {
+				*stream = None;
+				promise.resolve(cx, &Value::undefined_handle());
+			}
+			StreamSource::Transferred(reader) => {
+				let reader = Object::from(unsafe { Local::from_heap(reader) });
+				let result = DefaultReader::get_private(cx, &reader)?.cancel(cx, Opt(reason))?;
+				promise.handle_mut().set(result.get());
+			}
 			_ => {}
 		}
 
@@ -472,35 +579,52 @@ impl StreamSource {
 			StreamSource::Iterator(_, data) => {
 				*data = None;
 			}
+			StreamSource::Stream(stream) => {
+				*stream = None;
+			}
+			StreamSource::Transferred(_) => {
+				*self = StreamSource::None;
+			}
 			_ => {}
 		}
 	}
 }
 
+struct StreamChunk(Option<Bytes>);
+
+impl<'cx> IntoValue<'cx> for StreamChunk {
+	fn into_value(self: Box<Self>, cx: &'cx Context, value: &mut Value) {
+		match self.0 {
+			Some(bytes) => Box::new(Uint8ArrayWrapper::from(Vec::from(bytes))).into_value(cx, value),
+			None => value.handle_mut().set(UndefinedValue()),
+		}
+	}
+}
+
 #[derive(Traceable)]
 pub(crate) struct TeeCommonState {
-	stream: Box<Heap<*mut JSObject>>,
-	pub(crate) branch: [Box<Heap<*mut JSObject>>; 2],
+	stream: BoxedHeap<*mut JSObject>,
+	pub(crate) branch: [BoxedHeap<*mut JSObject>; 2],
 
 	reading: Cell<bool>,
 	cancelled: [Cell<bool>; 2],
 
-	reason: [Box<Heap<JSVal>>; 2],
-	cancel_promise: Box<Heap<*mut JSObject>>,
+	reason: [BoxedHeap<JSVal>; 2],
+	cancel_promise: BoxedHeap<*mut JSObject>,
 }
 
 impl TeeCommonState {
 	pub(crate) fn new(cx: &Context, stream: &ReadableStream) -> TeeCommonState {
 		let promise = Promise::new(cx);
 		TeeCommonState {
-			stream: Heap::boxed(stream.reflector.get()),
-			branch: [Box::default(), Box::default()],
+			stream: BoxedHeap::new(stream.reflector.get()),
+			branch: [BoxedHeap::new(ptr::null_mut()), BoxedHeap::new(ptr::null_mut())],
 
 			reading: Cell::new(false),
 			cancelled: [Cell::new(false), Cell::new(false)],
 
-			reason: [Heap::boxed(UndefinedValue()), Heap::boxed(UndefinedValue())],
-			cancel_promise: Heap::boxed(promise.get()),
+			reason: [BoxedHeap::new(UndefinedValue()), BoxedHeap::new(UndefinedValue())],
+			cancel_promise: BoxedHeap::new(promise.get()),
 		}
 	}
 
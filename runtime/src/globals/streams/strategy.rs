@@ -0,0 +1,77 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use ion::class::Reflector;
+use ion::{BoxedHeap, Context, Function, Object, Result, Value};
+use mozjs::jsapi::{JSFunction, JSFunctionSpec};
+
+#[derive(FromValue)]
+pub struct QueuingStrategyInit {
+	high_water_mark: f64,
+}
+
+#[js_class]
+pub struct ByteLengthQueuingStrategy {
+	reflector: Reflector,
+	#[ion(readonly)]
+	high_water_mark: f64,
+	size: BoxedHeap<*mut JSFunction>,
+}
+
+#[js_class]
+impl ByteLengthQueuingStrategy {
+	#[ion(constructor)]
+	pub fn constructor(cx: &Context, init: QueuingStrategyInit) -> ByteLengthQueuingStrategy {
+		ByteLengthQueuingStrategy {
+			reflector: Reflector::default(),
+			high_water_mark: init.high_water_mark,
+			size: BoxedHeap::new(Function::from_spec(cx, &BYTE_LENGTH_SIZE).get()),
+		}
+	}
+
+	#[ion(get)]
+	pub fn get_size(&self) -> *mut JSFunction {
+		self.size.get()
+	}
+}
+
+#[js_class]
+pub struct CountQueuingStrategy {
+	reflector: Reflector,
+	#[ion(readonly)]
+	high_water_mark: f64,
+	size: BoxedHeap<*mut JSFunction>,
+}
+
+#[js_class]
+impl CountQueuingStrategy {
+	#[ion(constructor)]
+	pub fn constructor(cx: &Context, init: QueuingStrategyInit) -> CountQueuingStrategy {
+		CountQueuingStrategy {
+			reflector: Reflector::default(),
+			high_water_mark: init.high_water_mark,
+			size: BoxedHeap::new(Function::from_spec(cx, &COUNT_SIZE).get()),
+		}
+	}
+
+	#[ion(get)]
+	pub fn get_size(&self) -> *mut JSFunction {
+		self.size.get()
+	}
+}
+
+#[js_fn]
+fn byte_length_size<'cx>(cx: &'cx Context, chunk: Object<'cx>) -> Result<Value<'cx>> {
+	Ok(chunk.get(cx, "byteLength")?.unwrap_or_else(|| Value::undefined(cx)))
+}
+
+#[js_fn]
+fn count_size() -> f64 {
+	1.0
+}
+
+const BYTE_LENGTH_SIZE: JSFunctionSpec = function_spec!(byte_length_size, c"size", 1);
+const COUNT_SIZE: JSFunctionSpec = function_spec!(count_size, c"size", 0);
@@ -0,0 +1,84 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::io;
+use std::rc::Rc;
+
+use futures::StreamExt;
+use ion::conversions::FromValue;
+use ion::{Context, Error, Result, Value};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+use crate::globals::file::BufferSource;
+use crate::globals::streams::readable::BytesStream;
+use crate::globals::streams::writable::sink::bytes_chunk;
+use crate::globals::streams::writable::{NativeSink, SinkFuture};
+
+/// Writes strings and buffer sources to an [AsyncWrite], such as a file, socket or the stdin of a child process.
+///
+/// Chunks are sized by their length in bytes, or code units for strings, so the `desiredSize` of the writer of the
+/// stream is the number of bytes which can be written before it signals backpressure. The writer is flushed and shut
+/// down when the stream is closed, and native byte streams piped to it are written without being converted to JS.
+pub struct AsyncWriteSink<W> {
+	writer: Rc<Mutex<W>>,
+	high_water_mark: usize,
+}
+
+impl<W: AsyncWrite + Unpin + 'static> AsyncWriteSink<W> {
+	pub fn new(writer: W, high_water_mark: usize) -> AsyncWriteSink<W> {
+		AsyncWriteSink {
+			writer: Rc::new(Mutex::new(writer)),
+			high_water_mark,
+		}
+	}
+}
+
+impl<W: AsyncWrite + Unpin + 'static> NativeSink for AsyncWriteSink<W> {
+	fn write(&mut self, cx: &Context, chunk: &Value) -> Result<SinkFuture> {
+		let bytes = bytes_chunk(cx, chunk, "AsyncWriteSink")?;
+		let writer = Rc::clone(&self.writer);
+		Ok(Box::pin(async move {
+			writer.lock().await.write_all(&bytes).await.map_err(write_error)
+		}))
+	}
+
+	fn close(&mut self, _: &Context) -> SinkFuture {
+		let writer = Rc::clone(&self.writer);
+		Box::pin(async move {
+			let mut writer = writer.lock().await;
+			writer.flush().await.map_err(write_error)?;
+			writer.shutdown().await.map_err(write_error)
+		})
+	}
+
+	fn write_stream(&mut self, mut stream: BytesStream) -> std::result::Result<SinkFuture, BytesStream> {
+		let writer = Rc::clone(&self.writer);
+		Ok(Box::pin(async move {
+			let mut writer = writer.lock().await;
+			while let Some(chunk) = stream.next().await {
+				writer.write_all(&chunk?).await.map_err(write_error)?;
+			}
+			writer.flush().await.map_err(write_error)
+		}))
+	}
+
+	fn high_water_mark(&self) -> f64 {
+		self.high_water_mark as f64
+	}
+
+	fn chunk_size(&self, cx: &Context, chunk: &Value) -> Result<f64> {
+		if chunk.handle().is_string() {
+			Ok(ion::String::from_value(cx, chunk, true, ())?.len() as f64)
+		} else {
+			Ok(BufferSource::from_value(cx, chunk, true, false).map_or(1.0, |source| source.len() as f64))
+		}
+	}
+}
+
+fn write_error(error: io::Error) -> Error {
+	Error::new(format!("Failed to write: {error}"), None)
+}
@@ -0,0 +1,317 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::VecDeque;
+use std::{ptr, slice};
+
+use ion::class::{NativeObject, Reflector};
+use ion::conversions::{FromValue, ToValue};
+use ion::function::Opt;
+use ion::{
+	BoxedHeap, ClassDefinition, Context, Error, ErrorKind, ErrorReport, Exception, Function, Local, Object, Promise,
+	Result, ResultExc, TracedHeap, Value,
+};
+use mozjs::jsapi::{JSFunction, JSObject};
+use mozjs::jsval::{JSVal, UndefinedValue};
+
+use crate::event_loop::MicrotaskCheckpoint;
+use crate::globals::abort::{AbortSender, AbortSignal};
+use crate::globals::streams::writable::{State, StreamSink, WritableStream};
+use crate::promise::add_reactions_with_checkpoint;
+
+#[js_class]
+#[ion(name = "WritableStreamDefaultController")]
+pub struct DefaultController {
+	reflector: Reflector,
+
+	pub(crate) stream: BoxedHeap<*mut JSObject>,
+	pub(crate) sink: StreamSink,
+	pub(crate) size: Option<BoxedHeap<*mut JSFunction>>,
+
+	pub(crate) started: bool,
+	high_water_mark: f64,
+	pub(crate) queue: VecDeque<(Option<BoxedHeap<JSVal>>, f64)>,
+	pub(crate) queue_size: f64,
+
+	signal: BoxedHeap<*mut JSObject>,
+	#[trace(no_trace)]
+	abort: AbortSender,
+}
+
+#[js_class]
+impl DefaultController {
+	pub(crate) fn initialise(
+		cx: &Context, stream: &Object, sink: StreamSink, size: Option<&Function>, high_water_mark: f64,
+	) -> DefaultController {
+		let (abort, receiver) = AbortSender::new();
+		let mut signal = AbortSignal::default();
+		signal.signal = receiver;
+
+		DefaultController {
+			reflector: Reflector::default(),
+
+			stream: BoxedHeap::new(stream.handle().get()),
+			sink,
+			size: size.map(|size| BoxedHeap::new(size.get())),
+
+			started: false,
+			high_water_mark,
+			queue: VecDeque::new(),
+			queue_size: 0.0,
+
+			signal: BoxedHeap::new(AbortSignal::new_object(cx, Box::new(signal))),
+			abort,
+		}
+	}
+
+	#[ion(get)]
+	pub fn get_signal(&self) -> *mut JSObject {
+		self.signal.get()
+	}
+
+	pub fn error(&mut self, cx: &Context, Opt(error): Opt<Value>) -> ResultExc<()> {
+		if self.stream(cx)?.state == State::Writable {
+			self.error_internal(cx, &error.unwrap_or_else(Value::undefined_handle))?;
+		}
+		Ok(())
+	}
+}
+
+impl DefaultController {
+	pub(crate) fn from_traced_heap<'h>(cx: &Context, heap: &'h TracedHeap<*mut JSObject>) -> Result<&'h mut Self> {
+		let controller = Object::from(heap.to_local());
+		DefaultController::get_mut_private(cx, &controller)
+	}
+
+	pub(crate) fn stream<'cx>(&self, cx: &'cx Context) -> Result<&'cx mut WritableStream> {
+		let stream = Object::from(unsafe { Local::from_heap(&*ptr::from_ref(&self.stream)) });
+		WritableStream::get_mut_private(cx, &stream)
+	}
+
+	/// Calls the start algorithm.
+	/// Returns a [MicrotaskCheckpoint] which runs the reactions to its result if it has already settled.
+	pub(crate) fn start<'cx>(
+		&mut self, cx: &'cx Context, start: Option<&Function>,
+	) -> ResultExc<Option<MicrotaskCheckpoint<'cx>>> {
+		let controller = self.reflector().get();
+
+		let underlying_sink = self.sink.sink_object();
+		let value = controller.as_value(cx);
+		let result = start
+			.map(|start| start.call(cx, &underlying_sink, &[value]).map(|v| v.get()))
+			.unwrap_or_else(|| Ok(UndefinedValue()))
+			.map_err(|report| report.map_or_else(|| Exception::Error(Error::none()), ErrorReport::into_exception))?;
+
+		Ok(self.started(cx, &Value::from(cx.root(result))))
+	}
+
+	/// Starts a controller which has no start algorithm, such as for a native sink.
+	pub(crate) fn start_native<'cx>(&mut self, cx: &'cx Context) -> Option<MicrotaskCheckpoint<'cx>> {
+		self.started(cx, &Value::undefined_handle())
+	}
+
+	fn started<'cx>(&mut self, cx: &'cx Context, result: &Value) -> Option<MicrotaskCheckpoint<'cx>> {
+		let controller = self.reflector().get();
+		let promise = Promise::adopt(cx, result);
+
+		let controller1 = TracedHeap::new(controller);
+		let controller2 = TracedHeap::new(controller);
+		add_reactions_with_checkpoint(
+			cx,
+			&promise,
+			move |cx, _| {
+				let controller = DefaultController::from_traced_heap(cx, &controller1)?;
+				controller.started = true;
+				controller.advance_queue_if_needed(cx)?;
+				Ok(Value::undefined_handle())
+			},
+			move |cx, error| {
+				let controller = DefaultController::from_traced_heap(cx, &controller2)?;
+				controller.started = true;
+				controller.stream(cx)?.deal_with_rejection(cx, error)?;
+				Ok(Value::undefined_handle())
+			},
+		)
+	}
+
+	pub(crate) fn chunk_size(&mut self, cx: &Context, chunk: &Value) -> ResultExc<f64> {
+		if let Some(result) = self.sink.chunk_size(cx, chunk) {
+			return match result {
+				Ok(size) => Ok(size),
+				Err(error) => {
+					self.error_if_needed(cx, &error.as_value(cx))?;
+					Ok(1.0)
+				}
+			};
+		}
+
+		let result = self
+			.size
+			.as_ref()
+			.map(|size| {
+				let size = Function::from(unsafe { Local::from_heap(size) });
+				size.call(cx, &Object::null(cx), slice::from_ref(chunk))
+			})
+			.unwrap_or_else(|| Ok(Value::i32(cx, 1)));
+
+		let error = match result {
+			Ok(size) => match f64::from_value(cx, &size, false, ()) {
+				Ok(size) => return Ok(size),
+				Err(error) => error.as_value(cx),
+			},
+			Err(Some(report)) => report.exception.as_value(cx),
+			Err(None) => unreachable!(),
+		};
+
+		self.error_if_needed(cx, &error)?;
+		Ok(1.0)
+	}
+
+	pub(crate) fn write(&mut self, cx: &Context, chunk: &Value, size: f64) -> ResultExc<()> {
+		if !size.is_finite() || size < 0.0 {
+			let error = Error::new("Chunk size must be a non-negative finite number.", ErrorKind::Range);
+			return self.error_if_needed(cx, &error.as_value(cx));
+		}
+
+		self.queue.push_back((Some(BoxedHeap::new(chunk.get())), size));
+		self.queue_size += size;
+
+		let stream = self.stream(cx)?;
+		if !stream.close_queued_or_in_flight() && stream.state == State::Writable {
+			stream.update_backpressure(cx, self.backpressure())?;
+		}
+
+		self.advance_queue_if_needed(cx)
+	}
+
+	pub(crate) fn close(&mut self, cx: &Context) -> ResultExc<()> {
+		self.queue.push_back((None, 0.0));
+		self.advance_queue_if_needed(cx)
+	}
+
+	pub(crate) fn advance_queue_if_needed(&mut self, cx: &Context) -> ResultExc<()> {
+		let stream = self.stream(cx)?;
+		if !self.started || stream.in_flight_write.is_some() {
+			return Ok(());
+		}
+
+		if stream.state == State::Erroring {
+			return stream.finish_erroring(cx);
+		}
+
+		match self.queue.front() {
+			Some((Some(chunk), _)) => {
+				let chunk = Value::from(cx.root(chunk.get()));
+				self.process_write(cx, &chunk)
+			}
+			Some((None, _)) => self.process_close(cx),
+			None => Ok(()),
+		}
+	}
+
+	fn process_write(&mut self, cx: &Context, chunk: &Value) -> ResultExc<()> {
+		let stream = self.stream(cx)?;
+		stream.in_flight_write = stream.write_requests.pop_front();
+
+		let controller = self.reflector().get();
+		let promise = self.sink.write(cx, chunk, controller);
+
+		let controller1 = TracedHeap::new(controller);
+		let controller2 = TracedHeap::new(controller);
+		promise.add_reactions(
+			cx,
+			move |cx, _| {
+				let controller = DefaultController::from_traced_heap(cx, &controller1)?;
+				let stream = controller.stream(cx)?;
+				stream.finish_in_flight_write(cx);
+
+				if let Some((_, size)) = controller.queue.pop_front() {
+					controller.queue_size = (controller.queue_size - size).max(0.0);
+				}
+
+				if !stream.close_queued_or_in_flight() && stream.state == State::Writable {
+					stream.update_backpressure(cx, controller.backpressure())?;
+				}
+
+				controller.advance_queue_if_needed(cx)?;
+				Ok(Value::undefined_handle())
+			},
+			move |cx, error| {
+				let controller = DefaultController::from_traced_heap(cx, &controller2)?;
+				let stream = controller.stream(cx)?;
+				if stream.state == State::Writable {
+					controller.sink.clear_algorithms();
+				}
+				stream.finish_in_flight_write_with_error(cx, error)?;
+				Ok(Value::undefined_handle())
+			},
+		);
+
+		Ok(())
+	}
+
+	fn process_close(&mut self, cx: &Context) -> ResultExc<()> {
+		let stream = self.stream(cx)?;
+		stream.in_flight_close = stream.close_request.take();
+
+		self.queue.pop_front();
+		self.queue_size = 0.0;
+
+		let promise = self.sink.close(cx);
+		self.sink.clear_algorithms();
+
+		let stream1 = TracedHeap::new(self.stream.get());
+		let stream2 = TracedHeap::new(self.stream.get());
+		promise.add_reactions(
+			cx,
+			move |cx, _| {
+				WritableStream::from_traced_heap(cx, &stream1)?.finish_in_flight_close(cx)?;
+				Ok(Value::undefined_handle())
+			},
+			move |cx, error| {
+				WritableStream::from_traced_heap(cx, &stream2)?.finish_in_flight_close_with_error(cx, error)?;
+				Ok(Value::undefined_handle())
+			},
+		);
+
+		Ok(())
+	}
+
+	pub(crate) fn abort_steps<'cx>(&mut self, cx: &'cx Context, reason: &Value) -> Promise<'cx> {
+		let promise = self.sink.abort(cx, reason);
+		self.sink.clear_algorithms();
+		promise
+	}
+
+	pub(crate) fn signal_abort(&self, cx: &Context, reason: &Value) {
+		self.abort.abort(cx, Some(reason));
+	}
+
+	pub(crate) fn error_if_needed(&mut self, cx: &Context, error: &Value) -> ResultExc<()> {
+		if self.stream(cx)?.state == State::Writable {
+			self.error_internal(cx, error)?;
+		}
+		Ok(())
+	}
+
+	pub(crate) fn error_internal(&mut self, cx: &Context, error: &Value) -> ResultExc<()> {
+		self.sink.clear_algorithms();
+		self.stream(cx)?.start_erroring(cx, error)
+	}
+
+	pub(crate) fn reset_queue(&mut self) {
+		self.queue.clear();
+		self.queue_size = 0.0;
+	}
+
+	pub(crate) fn backpressure(&self) -> bool {
+		self.desired_size() <= 0.0
+	}
+
+	pub(crate) fn desired_size(&self) -> f64 {
+		self.high_water_mark - self.queue_size
+	}
+}
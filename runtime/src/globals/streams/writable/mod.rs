@@ -0,0 +1,467 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::VecDeque;
+
+pub use async_write::AsyncWriteSink;
+pub use controller::DefaultController;
+use ion::class::{NativeObject, Reflector};
+use ion::conversions::{FromValue, ToValue};
+use ion::function::Opt;
+use ion::{
+	BoxedHeap, ClassDefinition, Context, Error, ErrorKind, Function, Local, Object, Promise, Result, ResultExc,
+	TracedHeap, Value,
+};
+use mozjs::jsapi::JSObject;
+use mozjs::jsval::{DoubleValue, Int32Value, JSVal, NullValue, UndefinedValue};
+pub use sink::{NativeSink, SinkFuture, StreamSink};
+pub use stdout::StdoutSink;
+use tokio::io::AsyncWrite;
+pub use writer::DefaultWriter;
+
+use crate::event_loop::MicrotaskCheckpoint;
+use crate::globals::streams::QueueingStrategy;
+
+mod async_write;
+mod controller;
+mod sink;
+mod stdout;
+mod writer;
+
+#[derive(Default, FromValue)]
+pub struct UnderlyingSink<'cx> {
+	start: Option<Function<'cx>>,
+	write: Option<Function<'cx>>,
+	close: Option<Function<'cx>>,
+	abort: Option<Function<'cx>>,
+	#[ion(name = "type")]
+	ty: Option<Value<'cx>>,
+}
+
+impl UnderlyingSink<'_> {
+	pub(crate) fn to_native(&self, object: Option<&Object>) -> StreamSink {
+		match object {
+			Some(object) => StreamSink::Script {
+				object: BoxedHeap::new(object.handle().get()),
+				write: self.write.as_ref().map(|write| BoxedHeap::new(write.get())),
+				close: self.close.as_ref().map(|close| BoxedHeap::new(close.get())),
+				abort: self.abort.as_ref().map(|abort| BoxedHeap::new(abort.get())),
+			},
+			None => StreamSink::None,
+		}
+	}
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Traceable)]
+pub enum State {
+	Writable,
+	Closed,
+	Erroring,
+	Errored,
+}
+
+#[derive(Traceable)]
+pub(crate) struct PendingAbort {
+	promise: BoxedHeap<*mut JSObject>,
+	reason: BoxedHeap<JSVal>,
+	was_already_erroring: bool,
+}
+
+#[js_class]
+pub struct WritableStream {
+	reflector: Reflector,
+
+	pub(crate) controller: BoxedHeap<*mut JSObject>,
+	pub(crate) writer: Option<BoxedHeap<*mut JSObject>>,
+
+	pub(crate) state: State,
+	pub(crate) error: Option<BoxedHeap<JSVal>>,
+	pub(crate) backpressure: bool,
+
+	pub(crate) write_requests: VecDeque<BoxedHeap<*mut JSObject>>,
+	pub(crate) in_flight_write: Option<BoxedHeap<*mut JSObject>>,
+	pub(crate) close_request: Option<BoxedHeap<*mut JSObject>>,
+	pub(crate) in_flight_close: Option<BoxedHeap<*mut JSObject>>,
+	pub(crate) pending_abort: Option<PendingAbort>,
+}
+
+#[js_class]
+impl WritableStream {
+	#[ion(constructor)]
+	pub fn constructor<'cx: 'o, 'o>(
+		cx: &'cx Context, #[ion(this)] this: &Object, Opt(underlying_sink): Opt<Object<'o>>,
+		Opt(strategy): Opt<QueueingStrategy>,
+	) -> ResultExc<WritableStream> {
+		let strategy = strategy.unwrap_or_default();
+		let sink = match &underlying_sink {
+			Some(underlying_sink) => UnderlyingSink::from_value(cx, &underlying_sink.as_value(cx), false, ())?,
+			None => UnderlyingSink::default(),
+		};
+
+		if sink.ty.is_some() {
+			return Err(Error::new("Type of Underlying Sink must not exist.", ErrorKind::Range).into());
+		}
+
+		let high_water_mark = strategy.high_water_mark(1.0)?;
+		let controller = DefaultController::initialise(
+			cx,
+			this,
+			sink.to_native(underlying_sink.as_ref()),
+			strategy.size.as_ref(),
+			high_water_mark,
+		);
+		let controller = BoxedHeap::new(DefaultController::new_object(cx, Box::new(controller)));
+		// The stream is only initialised once the constructor returns,
+		// so the reactions must wait for the event loop.
+		let checkpoint = unsafe {
+			let controller = Object::from(Local::from_heap(&controller));
+			DefaultController::get_mut_private_unchecked(&controller).start(cx, sink.start.as_ref())?
+		};
+		if let Some(checkpoint) = checkpoint {
+			checkpoint.defer();
+		}
+
+		Ok(WritableStream::new(controller, high_water_mark <= 0.0))
+	}
+
+	#[ion(get)]
+	pub fn get_locked(&self) -> bool {
+		self.writer.is_some()
+	}
+
+	pub fn abort<'cx>(&mut self, cx: &'cx Context, Opt(reason): Opt<Value>) -> ResultExc<Promise<'cx>> {
+		if self.get_locked() {
+			let promise = Promise::new(cx);
+			promise.reject_with_error(cx, &Error::new("WritableStream is locked.", ErrorKind::Type));
+			return Ok(promise);
+		}
+		self.abort_internal(cx, reason.unwrap_or_else(Value::undefined_handle))
+	}
+
+	pub fn close<'cx>(&mut self, cx: &'cx Context) -> ResultExc<Promise<'cx>> {
+		if self.get_locked() {
+			let promise = Promise::new(cx);
+			promise.reject_with_error(cx, &Error::new("WritableStream is locked.", ErrorKind::Type));
+			return Ok(promise);
+		}
+		if self.close_queued_or_in_flight() {
+			let promise = Promise::new(cx);
+			promise.reject_with_error(cx, &Error::new("WritableStream is already closing.", ErrorKind::Type));
+			return Ok(promise);
+		}
+		self.close_internal(cx)
+	}
+
+	#[ion(name = "getWriter")]
+	pub fn get_writer<'cx>(&mut self, cx: &'cx Context) -> Result<Object<'cx>> {
+		let writer = DefaultWriter::new(cx, &Object::from(Local::from_handle(self.reflector().handle())))?;
+		let object = Object::from(cx.root(DefaultWriter::new_object(cx, Box::new(writer))));
+		self.writer = Some(BoxedHeap::new(object.handle().get()));
+		Ok(object)
+	}
+}
+
+impl WritableStream {
+	pub(crate) fn new(controller: BoxedHeap<*mut JSObject>, backpressure: bool) -> WritableStream {
+		WritableStream {
+			reflector: Reflector::default(),
+
+			controller,
+			writer: None,
+
+			state: State::Writable,
+			error: None,
+			backpressure,
+
+			write_requests: VecDeque::new(),
+			in_flight_write: None,
+			close_request: None,
+			in_flight_close: None,
+			pending_abort: None,
+		}
+	}
+
+	/// Creates a writable stream which forwards its chunks to a [NativeSink].
+	pub fn from_native_sink<'cx, S: NativeSink + 'static>(cx: &'cx Context, sink: S) -> Object<'cx> {
+		let high_water_mark = sink.high_water_mark();
+		let (object, _checkpoint) = WritableStream::from_sink(cx, StreamSink::Native(Box::new(sink)), high_water_mark);
+		object
+	}
+
+	/// Creates a writable stream which writes strings and buffer sources to an [AsyncWrite](tokio::io::AsyncWrite),
+	/// signalling backpressure once `high_water_mark` bytes are queued.
+	pub fn from_async_write<'cx, W>(cx: &'cx Context, writer: W, high_water_mark: usize) -> Object<'cx>
+	where
+		W: AsyncWrite + Unpin + 'static,
+	{
+		WritableStream::from_native_sink(cx, AsyncWriteSink::new(writer, high_water_mark))
+	}
+
+	/// Creates a writable stream which forwards its chunks to the writer of a transferred stream.
+	pub(crate) fn from_transferred<'cx>(cx: &'cx Context, writer: &Object) -> Object<'cx> {
+		let sink = StreamSink::Transferred(BoxedHeap::new(writer.handle().get()));
+		let (object, checkpoint) = WritableStream::from_sink(cx, sink, 1.0);
+		// Microtasks must not run while the structured clone is being read.
+		if let Some(checkpoint) = checkpoint {
+			checkpoint.defer();
+		}
+		object
+	}
+
+	fn from_sink<'cx>(
+		cx: &'cx Context, sink: StreamSink, high_water_mark: f64,
+	) -> (Object<'cx>, Option<MicrotaskCheckpoint<'cx>>) {
+		let object = Object::from(cx.root(WritableStream::new_raw_object(cx)));
+
+		let controller = DefaultController::initialise(cx, &object, sink, None, high_water_mark);
+		let controller = BoxedHeap::new(DefaultController::new_object(cx, Box::new(controller)));
+
+		let checkpoint = unsafe {
+			let controller = Object::from(Local::from_heap(&controller));
+			DefaultController::get_mut_private_unchecked(&controller).start_native(cx)
+		};
+
+		let stream = WritableStream::new(controller, high_water_mark <= 0.0);
+		unsafe {
+			WritableStream::set_private(object.handle().get(), Box::new(stream));
+		}
+		(object, checkpoint)
+	}
+
+	pub(crate) fn abort_internal<'cx>(&mut self, cx: &'cx Context, mut reason: Value) -> ResultExc<Promise<'cx>> {
+		if self.state == State::Closed || self.state == State::Errored {
+			return Ok(Promise::resolved(cx, &Value::undefined_handle()));
+		}
+
+		self.native_controller(cx)?.signal_abort(cx, &reason);
+		if self.state == State::Closed || self.state == State::Errored {
+			return Ok(Promise::resolved(cx, &Value::undefined_handle()));
+		}
+
+		if let Some(pending_abort) = &self.pending_abort {
+			return Ok(Promise::from(cx.root(pending_abort.promise.get())).unwrap());
+		}
+
+		let was_already_erroring = self.state == State::Erroring;
+		if was_already_erroring {
+			reason = Value::undefined_handle();
+		}
+
+		let promise = Promise::new(cx);
+		self.pending_abort = Some(PendingAbort {
+			promise: BoxedHeap::new(promise.get()),
+			reason: BoxedHeap::new(reason.get()),
+			was_already_erroring,
+		});
+
+		if !was_already_erroring {
+			self.start_erroring(cx, &reason)?;
+		}
+		Ok(promise)
+	}
+
+	pub(crate) fn close_internal<'cx>(&mut self, cx: &'cx Context) -> ResultExc<Promise<'cx>> {
+		if self.state == State::Closed || self.state == State::Errored {
+			let promise = Promise::new(cx);
+			promise.reject_with_error(cx, &Error::new("WritableStream is closed or errored.", ErrorKind::Type));
+			return Ok(promise);
+		}
+
+		let promise = Promise::new(cx);
+		self.close_request = Some(BoxedHeap::new(promise.get()));
+
+		if self.backpressure && self.state == State::Writable {
+			if let Some(writer) = self.native_writer(cx)? {
+				writer.ready().resolve(cx, &Value::undefined_handle());
+			}
+		}
+
+		self.native_controller(cx)?.close(cx)?;
+		Ok(promise)
+	}
+
+	pub(crate) fn add_write_request<'cx>(&mut self, cx: &'cx Context) -> Promise<'cx> {
+		let promise = Promise::new(cx);
+		self.write_requests.push_back(BoxedHeap::new(promise.get()));
+		promise
+	}
+
+	pub(crate) fn deal_with_rejection(&mut self, cx: &Context, error: &Value) -> ResultExc<()> {
+		if self.state == State::Writable {
+			self.start_erroring(cx, error)
+		} else {
+			self.finish_erroring(cx)
+		}
+	}
+
+	pub(crate) fn start_erroring(&mut self, cx: &Context, reason: &Value) -> ResultExc<()> {
+		self.state = State::Erroring;
+		self.error = Some(BoxedHeap::new(reason.get()));
+
+		if let Some(writer) = self.native_writer(cx)? {
+			writer.ensure_ready_rejected(cx, reason);
+		}
+
+		if !self.has_operation_marked_in_flight() && self.native_controller(cx)?.started {
+			self.finish_erroring(cx)?;
+		}
+		Ok(())
+	}
+
+	pub(crate) fn finish_erroring(&mut self, cx: &Context) -> ResultExc<()> {
+		self.state = State::Errored;
+		self.native_controller(cx)?.reset_queue();
+
+		let error = self.stored_error(cx);
+		while let Some(request) = self.write_requests.pop_front() {
+			Promise::from(unsafe { Local::from_heap(&request) }).unwrap().reject(cx, &error);
+		}
+
+		let Some(pending_abort) = self.pending_abort.take() else {
+			return self.reject_close_and_closed_if_needed(cx);
+		};
+
+		let promise = Promise::from(unsafe { Local::from_heap(&pending_abort.promise) }).unwrap();
+		if pending_abort.was_already_erroring {
+			promise.reject(cx, &error);
+			return self.reject_close_and_closed_if_needed(cx);
+		}
+
+		let reason = Value::from(unsafe { Local::from_heap(&pending_abort.reason) });
+		let result = self.native_controller(cx)?.abort_steps(cx, &reason);
+
+		let stream1 = TracedHeap::new(self.reflector().get());
+		let stream2 = TracedHeap::new(self.reflector().get());
+		let promise1 = TracedHeap::new(promise.get());
+		let promise2 = TracedHeap::new(promise.get());
+
+		result.add_reactions(
+			cx,
+			move |cx, _| {
+				Promise::from(promise1.to_local()).unwrap().resolve(cx, &Value::undefined_handle());
+				WritableStream::from_traced_heap(cx, &stream1)?.reject_close_and_closed_if_needed(cx)?;
+				Ok(Value::undefined_handle())
+			},
+			move |cx, reason| {
+				Promise::from(promise2.to_local()).unwrap().reject(cx, reason);
+				WritableStream::from_traced_heap(cx, &stream2)?.reject_close_and_closed_if_needed(cx)?;
+				Ok(Value::undefined_handle())
+			},
+		);
+		Ok(())
+	}
+
+	pub(crate) fn finish_in_flight_write(&mut self, cx: &Context) {
+		if let Some(request) = self.in_flight_write.take() {
+			Promise::from(unsafe { Local::from_heap(&request) }).unwrap().resolve(cx, &Value::undefined_handle());
+		}
+	}
+
+	pub(crate) fn finish_in_flight_write_with_error(&mut self, cx: &Context, error: &Value) -> ResultExc<()> {
+		if let Some(request) = self.in_flight_write.take() {
+			Promise::from(unsafe { Local::from_heap(&request) }).unwrap().reject(cx, error);
+		}
+		self.deal_with_rejection(cx, error)
+	}
+
+	pub(crate) fn finish_in_flight_close(&mut self, cx: &Context) -> Result<()> {
+		if let Some(request) = self.in_flight_close.take() {
+			Promise::from(unsafe { Local::from_heap(&request) }).unwrap().resolve(cx, &Value::undefined_handle());
+		}
+
+		if self.state == State::Erroring {
+			self.error = None;
+			if let Some(pending_abort) = self.pending_abort.take() {
+				let promise = Promise::from(unsafe { Local::from_heap(&pending_abort.promise) }).unwrap();
+				promise.resolve(cx, &Value::undefined_handle());
+			}
+		}
+
+		self.state = State::Closed;
+		if let Some(writer) = self.native_writer(cx)? {
+			writer.closed().resolve(cx, &Value::undefined_handle());
+		}
+		Ok(())
+	}
+
+	pub(crate) fn finish_in_flight_close_with_error(&mut self, cx: &Context, error: &Value) -> ResultExc<()> {
+		if let Some(request) = self.in_flight_close.take() {
+			Promise::from(unsafe { Local::from_heap(&request) }).unwrap().reject(cx, error);
+		}
+
+		if let Some(pending_abort) = self.pending_abort.take() {
+			Promise::from(unsafe { Local::from_heap(&pending_abort.promise) }).unwrap().reject(cx, error);
+		}
+		self.deal_with_rejection(cx, error)
+	}
+
+	pub(crate) fn close_queued_or_in_flight(&self) -> bool {
+		self.close_request.is_some() || self.in_flight_close.is_some()
+	}
+
+	pub(crate) fn has_operation_marked_in_flight(&self) -> bool {
+		self.in_flight_write.is_some() || self.in_flight_close.is_some()
+	}
+
+	pub(crate) fn reject_close_and_closed_if_needed(&mut self, cx: &Context) -> ResultExc<()> {
+		let error = self.stored_error(cx);
+		if let Some(request) = self.close_request.take() {
+			Promise::from(unsafe { Local::from_heap(&request) }).unwrap().reject(cx, &error);
+		}
+
+		if let Some(writer) = self.native_writer(cx)? {
+			writer.ensure_closed_rejected(cx, &error);
+		}
+		Ok(())
+	}
+
+	pub(crate) fn update_backpressure(&mut self, cx: &Context, backpressure: bool) -> Result<()> {
+		if backpressure != self.backpressure {
+			if let Some(writer) = self.native_writer(cx)? {
+				if backpressure {
+					writer.ready.set(Promise::new(cx).get());
+				} else {
+					writer.ready().resolve(cx, &Value::undefined_handle());
+				}
+			}
+		}
+		self.backpressure = backpressure;
+		Ok(())
+	}
+
+	pub(crate) fn desired_size(&self, cx: &Context) -> Result<JSVal> {
+		match self.state {
+			State::Errored | State::Erroring => Ok(NullValue()),
+			State::Closed => Ok(Int32Value(0)),
+			State::Writable => Ok(DoubleValue(self.native_controller(cx)?.desired_size())),
+		}
+	}
+
+	pub(crate) fn stored_error<'cx>(&self, cx: &'cx Context) -> Value<'cx> {
+		let error = self.error.as_ref().map_or_else(UndefinedValue, |error| error.get());
+		Value::from(cx.root(error))
+	}
+
+	pub(crate) fn from_traced_heap<'h>(cx: &Context, heap: &'h TracedHeap<*mut JSObject>) -> Result<&'h mut Self> {
+		let stream = Object::from(heap.to_local());
+		WritableStream::get_mut_private(cx, &stream)
+	}
+
+	pub(crate) fn native_controller(&self, cx: &Context) -> Result<&mut DefaultController> {
+		let controller = Object::from(unsafe { Local::from_heap(&self.controller) });
+		DefaultController::get_mut_private(cx, &controller)
+	}
+
+	pub(crate) fn native_writer(&self, cx: &Context) -> Result<Option<&mut DefaultWriter>> {
+		self.writer
+			.as_ref()
+			.map(|writer| {
+				let writer = Object::from(unsafe { Local::from_heap(writer) });
+				DefaultWriter::get_mut_private(cx, &writer)
+			})
+			.transpose()
+	}
+}
@@ -0,0 +1,203 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::future::{ready, Future};
+use std::pin::Pin;
+use std::slice;
+
+use bytes::Bytes;
+use ion::conversions::{FromValue, ToValue};
+use ion::function::Opt;
+use ion::{
+	BoxedHeap, ClassDefinition, Context, Error, ErrorKind, Exception, Function, Local, Object, Promise, Result,
+	ResultExc, Value,
+};
+use mozjs::gc::HandleObject;
+use mozjs::jsapi::{Heap, JSFunction, JSObject};
+
+use crate::globals::file::BufferSource;
+use crate::globals::streams::readable::BytesStream;
+use crate::globals::streams::writable::DefaultWriter;
+use crate::promise::future_to_promise;
+
+pub type SinkFuture = Pin<Box<dyn Future<Output = Result<()>>>>;
+
+/// Represents a native underlying sink of a [WritableStream](super::WritableStream).
+///
+/// Writes are not started until the previous write has completed, so the returned futures are never run concurrently.
+/// The futures are run on the future queue, and must not borrow from the sink.
+///
+/// Chunks remain queued until they have been written, and the stream signals backpressure through the `desiredSize` and
+/// `ready` of its writer once the total size of the queued chunks reaches the high-water mark of the sink.
+pub trait NativeSink {
+	/// Converts the chunk and returns a future which writes it to the sink.
+	fn write(&mut self, cx: &Context, chunk: &Value) -> Result<SinkFuture>;
+
+	/// Returns the total size of queued chunks at which the stream signals backpressure.
+	fn high_water_mark(&self) -> f64 {
+		1.0
+	}
+
+	/// Returns the size of a chunk, which counts towards the high-water mark while it is queued.
+	fn chunk_size(&self, _: &Context, _: &Value) -> Result<f64> {
+		Ok(1.0)
+	}
+
+	/// Returns a future which flushes and closes the sink, after all writes have completed.
+	fn close(&mut self, _: &Context) -> SinkFuture {
+		Box::pin(ready(Ok(())))
+	}
+
+	/// Returns a future which releases the sink, after it has been aborted with the given reason.
+	fn abort(&mut self, _: &Context, _: &Value) -> SinkFuture {
+		Box::pin(ready(Ok(())))
+	}
+
+	/// Returns a future which writes every chunk of a native byte stream to the sink, without converting them to JS.
+	///
+	/// This is used to pipe native readable streams to the sink, and the stream is returned if it is not supported.
+	fn write_stream(&mut self, stream: BytesStream) -> std::result::Result<SinkFuture, BytesStream> {
+		Err(stream)
+	}
+}
+
+#[derive(Traceable)]
+pub enum StreamSink {
+	None,
+	Script {
+		object: BoxedHeap<*mut JSObject>,
+		write: Option<BoxedHeap<*mut JSFunction>>,
+		close: Option<BoxedHeap<*mut JSFunction>>,
+		abort: Option<BoxedHeap<*mut JSFunction>>,
+	},
+	Native(#[trace(no_trace)] Box<dyn NativeSink>),
+	Transferred(BoxedHeap<*mut JSObject>),
+}
+
+impl StreamSink {
+	pub fn sink_object(&self) -> Object {
+		match self {
+			StreamSink::Script { object, .. } => Object::from(unsafe { Local::from_heap(object) }),
+			_ => Object::from(Local::from_handle(HandleObject::null())),
+		}
+	}
+
+	pub fn write<'cx>(&mut self, cx: &'cx Context, chunk: &Value, controller: *mut JSObject) -> Promise<'cx> {
+		match self {
+			StreamSink::Script { object, write: Some(write), .. } => {
+				let write = Function::from(unsafe { Local::from_heap(write) });
+				let this = Object::from(unsafe { Local::from_heap(object) });
+				let args = [Value::from(Local::from_handle(chunk.handle())), controller.as_value(cx)];
+				call_algorithm(cx, &write, &this, &args)
+			}
+			StreamSink::Native(sink) => match sink.write(cx, chunk) {
+				Ok(future) => sink_to_promise(cx, future),
+				Err(error) => Promise::rejected(cx, &error.as_value(cx)),
+			},
+			StreamSink::Transferred(writer) => {
+				let chunk = Value::from(Local::from_handle(chunk.handle()));
+				with_writer(cx, writer, |writer| writer.write(cx, Opt(Some(chunk))))
+			}
+			_ => Promise::resolved(cx, &Value::undefined_handle()),
+		}
+	}
+
+	pub fn close<'cx>(&mut self, cx: &'cx Context) -> Promise<'cx> {
+		match self {
+			StreamSink::Script { object, close: Some(close), .. } => {
+				let close = Function::from(unsafe { Local::from_heap(close) });
+				let this = Object::from(unsafe { Local::from_heap(object) });
+				call_algorithm(cx, &close, &this, &[])
+			}
+			StreamSink::Native(sink) => sink_to_promise(cx, sink.close(cx)),
+			StreamSink::Transferred(writer) => with_writer(cx, writer, |writer| writer.close(cx)),
+			_ => Promise::resolved(cx, &Value::undefined_handle()),
+		}
+	}
+
+	pub fn abort<'cx>(&mut self, cx: &'cx Context, reason: &Value) -> Promise<'cx> {
+		match self {
+			StreamSink::Script { object, abort: Some(abort), .. } => {
+				let abort = Function::from(unsafe { Local::from_heap(abort) });
+				let this = Object::from(unsafe { Local::from_heap(object) });
+				call_algorithm(cx, &abort, &this, slice::from_ref(reason))
+			}
+			StreamSink::Native(sink) => sink_to_promise(cx, sink.abort(cx, reason)),
+			StreamSink::Transferred(writer) => {
+				let reason = Value::from(Local::from_handle(reason.handle()));
+				with_writer(cx, writer, |writer| writer.abort(cx, Opt(Some(reason))))
+			}
+			_ => Promise::resolved(cx, &Value::undefined_handle()),
+		}
+	}
+
+	/// Returns the size of a chunk according to a native sink, or [None] if the stream uses its queueing strategy.
+	pub(crate) fn chunk_size(&self, cx: &Context, chunk: &Value) -> Option<Result<f64>> {
+		match self {
+			StreamSink::Native(sink) => Some(sink.chunk_size(cx, chunk)),
+			_ => None,
+		}
+	}
+
+	pub(crate) fn write_stream(&mut self, stream: BytesStream) -> std::result::Result<SinkFuture, BytesStream> {
+		match self {
+			StreamSink::Native(sink) => sink.write_stream(stream),
+			_ => Err(stream),
+		}
+	}
+
+	pub fn clear_algorithms(&mut self) {
+		match self {
+			StreamSink::Script { write, close, abort, .. } => {
+				*write = None;
+				*close = None;
+				*abort = None;
+			}
+			StreamSink::Native(_) | StreamSink::Transferred(_) => {
+				*self = StreamSink::None;
+			}
+			StreamSink::None => {}
+		}
+	}
+}
+
+/// Converts a string or buffer source chunk to bytes, for sinks which write bytes to the named destination.
+pub(crate) fn bytes_chunk(cx: &Context, chunk: &Value, destination: &str) -> Result<Bytes> {
+	if chunk.handle().is_string() {
+		Ok(Bytes::from(String::from_value(cx, chunk, true, ())?))
+	} else if let Ok(source) = BufferSource::from_value(cx, chunk, true, false) {
+		Ok(source.to_bytes())
+	} else {
+		let message = format!("Expected String or BufferSource in {destination}");
+		Err(Error::new(message, ErrorKind::Type))
+	}
+}
+
+fn call_algorithm<'cx>(cx: &'cx Context, function: &Function, this: &Object, args: &[Value]) -> Promise<'cx> {
+	match function.call(cx, this, args) {
+		Ok(result) => Promise::adopt(cx, &result),
+		Err(Some(report)) => Promise::rejected(cx, &report.exception.as_value(cx)),
+		Err(None) => unreachable!(),
+	}
+}
+
+fn with_writer<'cx, F>(cx: &'cx Context, writer: &Heap<*mut JSObject>, f: F) -> Promise<'cx>
+where
+	F: FnOnce(&DefaultWriter) -> ResultExc<Promise<'cx>>,
+{
+	let writer = Object::from(unsafe { Local::from_heap(writer) });
+	match DefaultWriter::get_private(cx, &writer).map_err(Exception::from).and_then(f) {
+		Ok(promise) => promise,
+		Err(exception) => Promise::rejected(cx, &exception.as_value(cx)),
+	}
+}
+
+fn sink_to_promise(cx: &Context, future: SinkFuture) -> Promise {
+	future_to_promise(cx, future).unwrap_or_else(|| {
+		let error = Error::new("Future Queue has not been initialised.", None);
+		Promise::rejected(cx, &error.as_value(cx))
+	})
+}
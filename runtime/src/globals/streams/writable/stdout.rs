@@ -0,0 +1,72 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::io;
+use std::io::Write;
+
+use bytes::Bytes;
+use futures::StreamExt;
+use ion::{Context, Error, Result, Value};
+
+use crate::globals::streams::readable::BytesStream;
+use crate::globals::streams::writable::sink::bytes_chunk;
+use crate::globals::streams::writable::{NativeSink, SinkFuture};
+use crate::promise::run_blocking;
+
+/// Maximum number of ready chunks which are written to stdout at once when piping a native stream.
+const BATCH_SIZE: usize = 64;
+
+/// Writes strings and buffer sources to the stdout of the process.
+///
+/// Chunks are written through the same handle as the [StdioSink](crate::globals::console::StdioSink) of the `console`,
+/// so their output is not interleaved. Native byte streams piped to it are written in batches, without being converted
+/// to JS.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StdoutSink;
+
+impl NativeSink for StdoutSink {
+	fn write(&mut self, cx: &Context, chunk: &Value) -> Result<SinkFuture> {
+		let bytes = bytes_chunk(cx, chunk, "stdout")?;
+		Ok(Box::pin(async move { write_chunks(vec![bytes]).await }))
+	}
+
+	fn write_stream(&mut self, stream: BytesStream) -> std::result::Result<SinkFuture, BytesStream> {
+		Ok(Box::pin(async move {
+			let mut batches = stream.ready_chunks(BATCH_SIZE);
+			while let Some(batch) = batches.next().await {
+				let mut chunks = Vec::with_capacity(batch.len());
+				let mut error = None;
+				for chunk in batch {
+					match chunk {
+						Ok(chunk) => chunks.push(chunk),
+						Err(err) => {
+							error = Some(err);
+							break;
+						}
+					}
+				}
+
+				write_chunks(chunks).await?;
+				if let Some(error) = error {
+					return Err(error);
+				}
+			}
+			Ok(())
+		}))
+	}
+}
+
+async fn write_chunks(chunks: Vec<Bytes>) -> Result<()> {
+	run_blocking(move |_| {
+		let mut stdout = io::stdout().lock();
+		for chunk in &chunks {
+			stdout.write_all(chunk)?;
+		}
+		stdout.flush()
+	})
+	.await
+	.map_err(|error| Error::new(format!("Failed to write to stdout: {error}"), None))
+}
@@ -0,0 +1,201 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use ion::class::Reflector;
+use ion::conversions::ToValue;
+use ion::function::Opt;
+use ion::{BoxedHeap, ClassDefinition, Context, Error, ErrorKind, Local, Object, Promise, Result, ResultExc, Value};
+use mozjs::jsapi::{JSObject, PromiseState};
+use mozjs::jsval::JSVal;
+
+use crate::globals::streams::writable::{State, WritableStream};
+
+#[js_class]
+#[ion(name = "WritableStreamDefaultWriter")]
+pub struct DefaultWriter {
+	reflector: Reflector,
+
+	stream: Option<BoxedHeap<*mut JSObject>>,
+	pub(crate) ready: BoxedHeap<*mut JSObject>,
+	pub(crate) closed: BoxedHeap<*mut JSObject>,
+}
+
+#[js_class]
+impl DefaultWriter {
+	#[ion(constructor)]
+	pub fn constructor(cx: &Context, #[ion(this)] this: &Object, stream_object: Object) -> Result<DefaultWriter> {
+		let writer = DefaultWriter::new(cx, &stream_object)?;
+		let stream = WritableStream::get_mut_private(cx, &stream_object)?;
+		stream.writer = Some(BoxedHeap::new(this.handle().get()));
+
+		Ok(writer)
+	}
+
+	pub(crate) fn new(cx: &Context, stream_object: &Object) -> Result<DefaultWriter> {
+		let stream = WritableStream::get_private(cx, stream_object)?;
+		if stream.get_locked() {
+			return Err(Error::new(
+				"Cannot create DefaultWriter from locked stream.",
+				ErrorKind::Type,
+			));
+		}
+
+		let ready = Promise::new(cx);
+		let closed = Promise::new(cx);
+		match stream.state {
+			State::Writable => {
+				if stream.close_queued_or_in_flight() || !stream.backpressure {
+					ready.resolve(cx, &Value::undefined_handle());
+				}
+			}
+			State::Erroring => {
+				reject_handled(cx, &ready, &stream.stored_error(cx));
+			}
+			State::Closed => {
+				ready.resolve(cx, &Value::undefined_handle());
+				closed.resolve(cx, &Value::undefined_handle());
+			}
+			State::Errored => {
+				let error = stream.stored_error(cx);
+				reject_handled(cx, &ready, &error);
+				reject_handled(cx, &closed, &error);
+			}
+		}
+
+		Ok(DefaultWriter {
+			reflector: Reflector::default(),
+			stream: Some(BoxedHeap::new(stream_object.handle().get())),
+			ready: BoxedHeap::new(ready.get()),
+			closed: BoxedHeap::new(closed.get()),
+		})
+	}
+
+	#[ion(get)]
+	pub fn get_closed(&self) -> *mut JSObject {
+		self.closed.get()
+	}
+
+	#[ion(get)]
+	pub fn get_ready(&self) -> *mut JSObject {
+		self.ready.get()
+	}
+
+	#[ion(get)]
+	pub fn get_desired_size(&self, cx: &Context) -> Result<JSVal> {
+		match self.stream(cx)? {
+			Some(stream) => stream.desired_size(cx),
+			None => Err(Error::new("Writer has already been released.", ErrorKind::Type)),
+		}
+	}
+
+	pub fn abort<'cx>(&self, cx: &'cx Context, Opt(reason): Opt<Value>) -> ResultExc<Promise<'cx>> {
+		match self.stream(cx)? {
+			Some(stream) => stream.abort_internal(cx, reason.unwrap_or_else(Value::undefined_handle)),
+			None => Ok(released(cx)),
+		}
+	}
+
+	pub fn close<'cx>(&self, cx: &'cx Context) -> ResultExc<Promise<'cx>> {
+		match self.stream(cx)? {
+			Some(stream) if stream.close_queued_or_in_flight() => {
+				let promise = Promise::new(cx);
+				promise.reject_with_error(cx, &Error::new("WritableStream is already closing.", ErrorKind::Type));
+				Ok(promise)
+			}
+			Some(stream) => stream.close_internal(cx),
+			None => Ok(released(cx)),
+		}
+	}
+
+	pub fn write<'cx>(&self, cx: &'cx Context, Opt(chunk): Opt<Value>) -> ResultExc<Promise<'cx>> {
+		let Some(stream) = self.stream(cx)? else {
+			return Ok(released(cx));
+		};
+
+		let chunk = chunk.unwrap_or_else(Value::undefined_handle);
+		let size = stream.native_controller(cx)?.chunk_size(cx, &chunk)?;
+
+		match stream.state {
+			State::Errored | State::Erroring => Ok(Promise::rejected(cx, &stream.stored_error(cx))),
+			State::Closed => {
+				let promise = Promise::new(cx);
+				promise.reject_with_error(cx, &Error::new("WritableStream is closed.", ErrorKind::Type));
+				Ok(promise)
+			}
+			State::Writable if stream.close_queued_or_in_flight() => {
+				let promise = Promise::new(cx);
+				promise.reject_with_error(cx, &Error::new("WritableStream is closing.", ErrorKind::Type));
+				Ok(promise)
+			}
+			State::Writable => {
+				let promise = stream.add_write_request(cx);
+				stream.native_controller(cx)?.write(cx, &chunk, size)?;
+				Ok(promise)
+			}
+		}
+	}
+
+	#[ion(name = "releaseLock")]
+	pub fn release_lock(&mut self, cx: &Context) -> Result<()> {
+		let Some(stream) = self.stream(cx)? else {
+			return Ok(());
+		};
+
+		stream.writer = None;
+
+		let error = Error::new("Writer has been released.", ErrorKind::Type).as_value(cx);
+		self.ensure_ready_rejected(cx, &error);
+		self.ensure_closed_rejected(cx, &error);
+
+		self.stream = None;
+		Ok(())
+	}
+}
+
+impl DefaultWriter {
+	pub(crate) fn stream(&self, cx: &Context) -> Result<Option<&mut WritableStream>> {
+		self.stream
+			.as_ref()
+			.map::<Result<_>, _>(|stream| {
+				let stream = Object::from(unsafe { Local::from_heap(stream) });
+				WritableStream::get_mut_private(cx, &stream)
+			})
+			.transpose()
+	}
+
+	pub(crate) fn ready(&self) -> Promise {
+		Promise::from(unsafe { Local::from_heap(&self.ready) }).unwrap()
+	}
+
+	pub(crate) fn closed(&self) -> Promise {
+		Promise::from(unsafe { Local::from_heap(&self.closed) }).unwrap()
+	}
+
+	pub(crate) fn ensure_ready_rejected(&self, cx: &Context, error: &Value) {
+		if self.ready().state() != PromiseState::Pending {
+			self.ready.set(Promise::new(cx).get());
+		}
+		reject_handled(cx, &self.ready(), error);
+	}
+
+	pub(crate) fn ensure_closed_rejected(&self, cx: &Context, error: &Value) {
+		if self.closed().state() != PromiseState::Pending {
+			self.closed.set(Promise::new(cx).get());
+		}
+		reject_handled(cx, &self.closed(), error);
+	}
+}
+
+fn reject_handled(cx: &Context, promise: &Promise, error: &Value) {
+	promise.reject(cx, error);
+	promise.catch(cx, |_, _| Ok(Value::undefined_handle()));
+}
+
+fn released(cx: &Context) -> Promise {
+	let promise = Promise::new(cx);
+	promise.reject_with_error(cx, &Error::new("Writer has already been released.", ErrorKind::Type));
+	promise
+}
@@ -4,37 +4,54 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use chrono::Duration;
-use ion::function::{Clamp, Enforce, Opt, Rest};
-use ion::{Context, Error, Function, Object, Result};
+use std::time::Duration;
+
+use ion::conversions::FromValue;
+use ion::function::{CompileOptions, Enforce, Opt, Rest, Wrap};
+use ion::{Context, Error, ErrorReport, Function, Object, Result, ResultExc, Value};
 use mozjs::jsapi::JSFunctionSpec;
 use mozjs::jsval::JSVal;
 
-use crate::event_loop::macrotasks::{Macrotask, TimerMacrotask, UserMacrotask};
+use crate::event_loop::macrotasks::{
+	Macrotask, TimerMacrotask, UserMacrotask, MAXIMUM_NESTING_LEVEL, MINIMUM_DELAY_NESTED,
+};
+use crate::resources::record_timer_created;
 use crate::ContextExt;
 
-const MINIMUM_DELAY: i32 = 1;
-const MINIMUM_DELAY_NESTED: i32 = 4;
+const MINIMUM_DELAY: Duration = Duration::from_millis(1);
 
 fn set_timer(
-	cx: &Context, callback: Function, duration: Option<Clamp<i32>>, arguments: Box<[JSVal]>, repeat: bool,
+	cx: &Context, callback: Function, duration: Option<Wrap<i32>>, arguments: Box<[JSVal]>, repeat: bool,
 ) -> Result<u32> {
 	let event_loop = unsafe { &mut cx.get_private().event_loop };
 	if let Some(queue) = &mut event_loop.macrotasks {
-		let minimum = if queue.nesting > 5 {
+		let minimum = if queue.nesting > MAXIMUM_NESTING_LEVEL {
 			MINIMUM_DELAY_NESTED
 		} else {
 			MINIMUM_DELAY
 		};
 
-		let duration = duration.map(|t| t.0.max(minimum)).unwrap_or(minimum);
-		let timer = TimerMacrotask::new(callback, arguments, repeat, Duration::milliseconds(duration.into()));
-		Ok(queue.enqueue(Macrotask::Timer(timer), None))
+		// Timeouts are converted to a `long`, so delays above 2^31-1 ms wrap around, and NaN is treated as 0.
+		let duration = duration.map(|t| Duration::from_millis(u64::try_from(t.0).unwrap_or(0))).unwrap_or_default();
+		let timer = TimerMacrotask::new(callback, arguments, repeat, duration.max(minimum), queue.now());
+		let id = queue.enqueue(Macrotask::Timer(timer), None);
+		record_timer_created(cx);
+		Ok(id)
 	} else {
 		Err(Error::new("Macrotask Queue has not been initialised.", None))
 	}
 }
 
+/// Converts the handler of a timer, compiling it as the body of a function if it is a string.
+fn timer_handler<'cx>(cx: &'cx Context, handler: &Value) -> ResultExc<Function<'cx>> {
+	if handler.handle().is_string() {
+		let source = String::from_value(cx, handler, false, ())?;
+		Function::compile(cx, "", &[], &source, CompileOptions::default()).map_err(ErrorReport::into_exception)
+	} else {
+		Ok(Function::from_value(cx, handler, true, ())?)
+	}
+}
+
 fn clear_timer(cx: &Context, id: Option<Enforce<u32>>) -> Result<()> {
 	if let Some(id) = id {
 		let event_loop = unsafe { &mut cx.get_private().event_loop };
@@ -51,16 +68,18 @@ fn clear_timer(cx: &Context, id: Option<Enforce<u32>>) -> Result<()> {
 
 #[js_fn]
 fn set_timeout(
-	cx: &Context, callback: Function, Opt(duration): Opt<Clamp<i32>>, Rest(arguments): Rest<JSVal>,
-) -> Result<u32> {
-	set_timer(cx, callback, duration, arguments, false)
+	cx: &Context, handler: Value, Opt(duration): Opt<Wrap<i32>>, Rest(arguments): Rest<JSVal>,
+) -> ResultExc<u32> {
+	let callback = timer_handler(cx, &handler)?;
+	Ok(set_timer(cx, callback, duration, arguments, false)?)
 }
 
 #[js_fn]
 fn set_interval(
-	cx: &Context, callback: Function, Opt(duration): Opt<Clamp<i32>>, Rest(arguments): Rest<JSVal>,
-) -> Result<u32> {
-	set_timer(cx, callback, duration, arguments, true)
+	cx: &Context, handler: Value, Opt(duration): Opt<Wrap<i32>>, Rest(arguments): Rest<JSVal>,
+) -> ResultExc<u32> {
+	let callback = timer_handler(cx, &handler)?;
+	Ok(set_timer(cx, callback, duration, arguments, true)?)
 }
 
 #[js_fn]
@@ -77,7 +96,8 @@ fn clear_interval(cx: &Context, Opt(id): Opt<Enforce<u32>>) -> Result<()> {
 fn queue_macrotask(cx: &Context, callback: Function) -> Result<()> {
 	let event_loop = unsafe { &mut cx.get_private().event_loop };
 	if let Some(queue) = &mut event_loop.macrotasks {
-		queue.enqueue(Macrotask::User(UserMacrotask::new(callback)), None);
+		let macrotask = UserMacrotask::new(callback, queue.now());
+		queue.enqueue(Macrotask::User(macrotask), None);
 		Ok(())
 	} else {
 		Err(Error::new("Macrotask Queue has not been initialised.", None))
@@ -5,12 +5,16 @@
  */
 
 use std::cmp::Ordering;
+use std::sync::Arc;
 
-use ion::class::{NativeObject, Reflector};
+use ion::class::Reflector;
 use ion::function::Opt;
-use ion::{ClassDefinition, Context, Error, Local, Object, Result};
-use mozjs::jsapi::{Heap, JSObject};
+use ion::string::byte::{ByteString, Latin1};
+use ion::{BoxedHeap, ClassDefinition, Context, Error, Local, Object, Result};
+use mozjs::jsapi::JSObject;
 pub use search_params::URLSearchParams;
+pub(crate) use store::ObjectUrlEntry;
+pub use store::{BlobStore, BlobStoreMetrics};
 use url::Url;
 use uuid::Uuid;
 
@@ -18,6 +22,7 @@ use crate::globals::file::Blob;
 use crate::runtime::ContextExt;
 
 mod search_params;
+mod store;
 
 const BLOB_ORIGIN: &str = "spiderfire";
 
@@ -41,7 +46,7 @@ pub struct URL {
 	reflector: Reflector,
 	#[trace(no_trace)]
 	pub(crate) url: Url,
-	search_params: Box<Heap<*mut JSObject>>,
+	search_params: BoxedHeap<*mut JSObject>,
 }
 
 impl URL {
@@ -61,7 +66,7 @@ impl URL {
 			.parse(&input)
 			.map_err(|error| Error::new(error.to_string(), None))?;
 
-		let search_params = Heap::boxed(URLSearchParams::new_object(
+		let search_params = BoxedHeap::new(URLSearchParams::new_object(
 			cx,
 			URLSearchParams::from_url(&url, this.handle().get()),
 		));
@@ -84,7 +89,7 @@ impl URL {
 		let url = Url::options().base_url(base.as_ref()).parse(&input).ok()?;
 
 		let url_object = URL::new_raw_object(cx);
-		let search_params = Heap::boxed(URLSearchParams::new_object(
+		let search_params = BoxedHeap::new(URLSearchParams::new_object(
 			cx,
 			URLSearchParams::from_url(&url, url_object),
 		));
@@ -104,19 +109,28 @@ impl URL {
 	}
 
 	#[ion(name = "createObjectURL")]
-	pub fn create_object_url(cx: &Context, blob: &Blob) -> String {
-		let uuid = Uuid::new_v4();
+	pub fn create_object_url<'cx>(cx: &'cx Context, blob: &Blob) -> Result<ion::String<'cx>> {
+		let url = format!("blob:{BLOB_ORIGIN}/{}", Uuid::new_v4().hyphenated());
+		let entry = Arc::new(ObjectUrlEntry::new(blob.data.clone(), blob.kind.clone()));
 		unsafe {
-			cx.get_private().blob_store.insert(uuid, Heap::boxed(blob.reflector().get()));
+			cx.get_private().blob_store.insert(&Url::parse(&url)?, &entry);
 		}
-		format!("blob:{BLOB_ORIGIN}/{}", uuid.hyphenated())
+
+		// Revoked URLs are only referenced by their strings, so the blob does not accumulate them.
+		let mut object_urls = blob.object_urls.borrow_mut();
+		object_urls.retain(|entry| !entry.is_revoked());
+		object_urls.push(Arc::clone(&entry));
+
+		let url = unsafe { ByteString::<Latin1>::from_unchecked(url.into_bytes()) };
+		ion::String::from_latin1_with_finaliser(cx, url, move || drop(entry))
+			.map_err(|_| Error::new("Failed to create object URL", None))
 	}
 
 	#[ion(name = "revokeObjectURL")]
 	pub fn revoke_object_url(cx: &Context, url: String) {
-		if let Some(uuid) = Url::parse(&url).ok().and_then(|url| parse_uuid_from_url_path(&url)) {
+		if let Ok(url) = Url::parse(&url) {
 			unsafe {
-				cx.get_private().blob_store.remove(&uuid);
+				cx.get_private().blob_store.remove(&url);
 			}
 		}
 	}
@@ -268,10 +282,11 @@ impl URL {
 
 	#[ion(set)]
 	pub fn set_search(&mut self, cx: &Context, search: String) {
+		let search = search.strip_prefix('?').unwrap_or(&search);
 		if search.is_empty() {
 			self.url.set_query(None);
 		} else {
-			self.url.set_query(Some(&search));
+			self.url.set_query(Some(search));
 		}
 
 		self.search_params(cx).pairs = self.url.query_pairs().into_owned().collect();
@@ -284,7 +299,8 @@ impl URL {
 
 	#[ion(set)]
 	pub fn set_hash(&mut self, hash: String) {
-		self.url.set_fragment(Some(&*hash).filter(|hash| !hash.is_empty()));
+		let hash = hash.strip_prefix('#').unwrap_or(&hash);
+		self.url.set_fragment(Some(hash).filter(|hash| !hash.is_empty()));
 	}
 
 	#[ion(get)]
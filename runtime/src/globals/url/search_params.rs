@@ -43,7 +43,7 @@ impl<'cx> FromValue<'cx> for URLSearchParamsInit {
 					};
 					match key.to_owned_key(cx) {
 						Ok(OwnedKey::Int(i)) => Some(Ok((i.to_string(), value))),
-						Ok(OwnedKey::String(key)) => Some(Ok((key, value))),
+						Ok(OwnedKey::String(key)) => Some(Ok((key.into(), value))),
 						Err(e) => Some(Err(e)),
 						_ => None,
 					}
@@ -0,0 +1,88 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+
+use url::{Position, Url};
+
+use crate::globals::file::BlobData;
+
+/// Represents the contents of a [Blob](crate::globals::file::Blob) referenced by an object URL.
+///
+/// The entry is shared by the blob and the URL string, and is dropped once both have been garbage collected, or the
+/// blob has been garbage collected after the URL was revoked.
+#[derive(Debug)]
+pub(crate) struct ObjectUrlEntry {
+	pub(crate) data: BlobData,
+	pub(crate) kind: Option<String>,
+	revoked: AtomicBool,
+}
+
+impl ObjectUrlEntry {
+	pub(crate) fn new(data: BlobData, kind: Option<String>) -> ObjectUrlEntry {
+		ObjectUrlEntry {
+			data,
+			kind,
+			revoked: AtomicBool::new(false),
+		}
+	}
+
+	pub(crate) fn is_revoked(&self) -> bool {
+		self.revoked.load(Ordering::Relaxed)
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BlobStoreMetrics {
+	/// Number of object URLs which have not been revoked or evicted.
+	pub entries: usize,
+	/// Total size of the blobs referenced by the object URLs, in bytes.
+	pub bytes: u64,
+}
+
+/// Maps object URLs to the blobs they reference, without keeping the blobs alive.
+///
+/// Object URLs are compared without their fragments.
+#[derive(Debug, Default)]
+pub struct BlobStore {
+	entries: HashMap<String, Weak<ObjectUrlEntry>>,
+}
+
+impl BlobStore {
+	pub(crate) fn insert(&mut self, url: &Url, entry: &Arc<ObjectUrlEntry>) {
+		self.evict();
+		self.entries.insert(String::from(&url[..Position::AfterQuery]), Arc::downgrade(entry));
+	}
+
+	pub(crate) fn get(&mut self, url: &Url) -> Option<Arc<ObjectUrlEntry>> {
+		let key = &url[..Position::AfterQuery];
+		let entry = self.entries.get(key)?.upgrade();
+		if entry.is_none() {
+			self.entries.remove(key);
+		}
+		entry
+	}
+
+	/// Removes the object URL, so that its blob is no longer kept alive by the blob which created it.
+	pub(crate) fn remove(&mut self, url: &Url) {
+		if let Some(entry) = self.entries.remove(&url[..Position::AfterQuery]).and_then(|entry| entry.upgrade()) {
+			entry.revoked.store(true, Ordering::Relaxed);
+		}
+	}
+
+	/// Removes the entries whose blobs and URLs have been garbage collected.
+	pub fn evict(&mut self) {
+		self.entries.retain(|_, entry| entry.strong_count() > 0);
+	}
+
+	pub fn metrics(&mut self) -> BlobStoreMetrics {
+		self.evict();
+		let bytes = self.entries.values().filter_map(Weak::upgrade).map(|entry| entry.data.len()).sum();
+		BlobStoreMetrics { entries: self.entries.len(), bytes }
+	}
+}
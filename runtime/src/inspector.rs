@@ -0,0 +1,138 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use futures::channel::mpsc::UnboundedSender;
+use ion::conversions::{FromValue, ToValue};
+use ion::object::default_new_global;
+use ion::script::Script;
+use ion::{Context, Function, Object, TracedHeap};
+use mozjs::conversions::ConversionBehavior;
+use mozjs::jsapi::{JSAutoRealm, JSObject, JS_DefineDebuggerObject};
+
+use crate::config::LogLevel;
+use crate::ContextExt;
+
+/// Creates a `Debugger` which collects coverage of the scripts compiled in the debuggee after it is created, and
+/// returns a function which reports the highest execution count of each line of those scripts.
+///
+/// Scripts which have not run have no coverage, so their lines are reported from their possible breakpoints instead.
+const COVERAGE: &str = r#"(debuggee => {
+	const dbg = new Debugger(debuggee);
+	dbg.collectCoverageInfo = true;
+	const initial = new Set(dbg.findScripts());
+
+	return () => {
+		const scripts = new Map();
+		for (const script of dbg.findScripts()) {
+			if (initial.has(script) || !script.url) {
+				continue;
+			}
+			const offsets =
+				script.getOffsetsCoverage() ?? script.getPossibleBreakpoints().map(offset => ({ ...offset, count: 0 }));
+
+			let lines = scripts.get(script.url);
+			if (!lines) {
+				lines = new Map();
+				scripts.set(script.url, lines);
+			}
+			for (const { lineNumber, count } of offsets) {
+				lines.set(lineNumber, Math.max(lines.get(lineNumber) ?? 0, count));
+			}
+		}
+
+		return Array.from(scripts, ([url, lines]) => ({
+			url,
+			lines: Array.from(lines, ([line, count]) => ({ line, count })).sort((a, b) => a.line - b.line),
+		}));
+	};
+})"#;
+
+/// Diagnostic events emitted by the runtime, independent of where its output is written.
+#[derive(Clone, Debug)]
+pub enum InspectorEvent {
+	/// A message was written by the `console` global, without the prefixes added by the configuration.
+	Console { level: LogLevel, message: String },
+	/// An exception was thrown by a task of the event loop and not caught.
+	UncaughtError { message: String },
+	/// A promise was rejected without a handler.
+	UnhandledRejection { reason: String },
+	/// A module was compiled by the module loader, before it is evaluated.
+	ScriptParsed { path: String },
+}
+
+/// Receives [InspectorEvents](InspectorEvent) from a runtime, such as to surface diagnostics in a host application.
+pub trait InspectorSubscriber {
+	fn event(&self, event: &InspectorEvent);
+}
+
+/// Streams events to the receiver, which may be on another thread.
+/// Events are discarded once the receiver is dropped.
+impl InspectorSubscriber for UnboundedSender<InspectorEvent> {
+	fn event(&self, event: &InspectorEvent) {
+		let _ = self.unbounded_send(event.clone());
+	}
+}
+
+/// Sends the event to each subscriber of the runtime. The event is only created if there are subscribers.
+pub(crate) fn notify<F: FnOnce() -> InspectorEvent>(cx: &Context, event: F) {
+	let subscribers = unsafe { &cx.get_private().inspector_subscribers };
+	if subscribers.is_empty() {
+		return;
+	}
+
+	let event = event();
+	for subscriber in subscribers {
+		subscriber.event(&event);
+	}
+}
+
+/// The number of times each line of a script has run.
+#[derive(Clone, Debug, FromValue)]
+pub struct ScriptCoverage {
+	/// Path or URL of the script.
+	pub url: String,
+	/// Lines with code, in ascending order.
+	pub lines: Vec<LineCoverage>,
+}
+
+#[derive(Clone, Copy, Debug, FromValue)]
+pub struct LineCoverage {
+	/// Line number, starting from 1.
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	pub line: u32,
+	/// Number of times the line has run, which is 0 if it has not.
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	pub count: u64,
+}
+
+/// Collects the [coverage](ScriptCoverage) of scripts with a `Debugger` in a separate global, as it cannot be in the
+/// same compartment as the global it debugs.
+pub(crate) struct Coverage(TracedHeap<*mut JSObject>);
+
+impl Coverage {
+	/// Starts collecting coverage of scripts compiled in `debuggee` from now on.
+	pub(crate) fn start(cx: &Context, debuggee: &Object) -> Option<Coverage> {
+		let global = default_new_global(cx);
+		let _realm = JSAutoRealm::new(cx.as_ptr(), global.handle().get());
+		if !unsafe { JS_DefineDebuggerObject(cx.as_ptr(), global.handle().into()) } {
+			return None;
+		}
+
+		let start = Script::compile_and_evaluate(cx, Path::new("coverage.js"), COVERAGE).ok()?;
+		let start = Function::from_value(cx, &start, true, ()).ok()?;
+		let report = start.call(cx, &global, &[debuggee.as_value(cx)]).ok()?;
+		Some(Coverage(TracedHeap::new(report.to_object(cx).handle().get())))
+	}
+
+	pub(crate) fn report(&self, cx: &Context) -> Option<Vec<ScriptCoverage>> {
+		let _realm = JSAutoRealm::new(cx.as_ptr(), self.0.get());
+		let report = Function::from_object(cx, &self.0.to_local())?;
+		let scripts = report.call(cx, &Object::global(cx), &[]).ok()?;
+		Vec::from_value(cx, &scripts, true, ()).ok()
+	}
+}
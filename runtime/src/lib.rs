@@ -12,13 +12,18 @@ extern crate ion;
 pub use crate::runtime::*;
 
 pub mod cache;
+pub mod clock;
 pub mod config;
 pub mod event_loop;
 pub mod globals;
+pub mod inspector;
 pub mod module;
-#[cfg(feature = "tokio-promise")]
 pub mod promise;
+pub mod resources;
 mod runtime;
+#[cfg(feature = "otlp")]
+pub mod telemetry;
+mod timeout;
 pub mod typescript;
 
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -5,23 +5,161 @@
  */
 
 use std::collections::hash_map::{Entry, HashMap};
+use std::env::current_dir;
 use std::ffi::OsStr;
 use std::fs::read_to_string;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use data_url::DataUrl;
 use dunce::canonicalize;
 use ion::module::{Module, ModuleData, ModuleLoader, ModuleRequest};
 use ion::{Context, Error, Local, Object, Result, Value};
 use mozjs::jsapi::JSObject;
+use tracing::info_span;
 use url::Url;
 
 use crate::cache::locate_in_cache;
-use crate::cache::map::save_sourcemap;
+use crate::cache::map::SourceMapStore;
 use crate::config::Config;
+use crate::globals::file::BlobData;
+use crate::module::lock::Lockfile;
+use crate::module::package::{is_bare_specifier, resolve_package, DEFAULT_CONDITIONS};
+use crate::module::{compile_and_evaluate, resolve_path, NODE_PREFIX};
+use crate::resources::record_file_opened;
+use crate::ContextExt;
+
+/// Prefixes of `data:` URLs and blob object URLs, whose modules are loaded from the URL rather than the file system.
+const URL_PREFIXES: [&str; 2] = ["data:", "blob:"];
+
+/// Reads the source of a module from a `data:` URL or blob object URL.
+///
+/// JavaScript is returned as is, and JSON is wrapped in a module which parses it with `JSON.parse` as its default
+/// export, so that keys such as `__proto__` are defined as own properties.
+///
+/// Modules are resolved synchronously, so blobs which were spilled to disk are read like module files.
+fn read_url(cx: &Context, specifier: &str) -> Result<String> {
+	let (bytes, mime) = if specifier.starts_with("data:") {
+		let data_url = DataUrl::process(specifier).map_err(|_| Error::new("Invalid data: URL", None))?;
+		let (bytes, _) = data_url.decode_to_vec().map_err(|_| Error::new("Invalid data: URL", None))?;
+		let mime = data_url.mime_type();
+		(bytes, format!("{}/{}", mime.type_, mime.subtype))
+	} else {
+		let url = Url::parse(specifier)?;
+		let entry = unsafe { cx.get_private().blob_store.get(&url) };
+		let Some(entry) = entry else {
+			return Err(Error::new("Blob URL has been revoked", None));
+		};
+		let mime = entry.kind.as_deref().and_then(|kind| kind.split(';').next()).unwrap_or_default();
+		let bytes = match entry.data.current() {
+			BlobData::Memory(bytes) => bytes.to_vec(),
+			_ => {
+				record_file_opened(cx);
+				entry.data.read_sync()?.to_vec()
+			}
+		};
+		(bytes, mime.trim().to_ascii_lowercase())
+	};
+
+	let source = String::from_utf8(bytes).map_err(|_| Error::new("Module is not valid UTF-8", None))?;
+	match mime.as_str() {
+		"text/javascript" | "application/javascript" => Ok(source),
+		"application/json" => match serde_json::from_str::<serde_json::Value>(&source) {
+			Ok(_) => Ok(format!("export default JSON.parse({});", serde_json::to_string(&source)?)),
+			Err(error) => Err(Error::new(format!("Invalid JSON module: {error}"), None)),
+		},
+		_ => Err(Error::new(format!("Unsupported module type: {mime}"), None)),
+	}
+}
+
+/// The source of a module which a specifier resolves to, as determined by a [Loader] without loading the module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Resolution {
+	/// A `node:` specifier, which refers to a registered compatibility module or standard module.
+	Builtin,
+	/// A `data:` URL or blob object URL.
+	Url,
+	/// A path, which is either a file or the name of a registered module, such as a standard module.
+	Path(PathBuf),
+}
 
-#[derive(Default)]
 pub struct Loader {
 	registry: HashMap<String, *mut JSObject>,
+	conditions: Vec<String>,
+	lockfile: Option<Lockfile>,
+}
+
+impl Loader {
+	/// Adds conditions, such as `"worker"` or `"development"`, to consider when matching the `exports` of packages.
+	pub fn conditions<S: Into<String>>(mut self, conditions: impl IntoIterator<Item = S>) -> Loader {
+		for condition in conditions {
+			let condition = condition.into();
+			if !self.conditions.contains(&condition) {
+				self.conditions.push(condition);
+			}
+		}
+		self
+	}
+
+	/// Verifies modules loaded from `node_modules` against the packages recorded in the lockfile.
+	pub fn lockfile(self, lockfile: Lockfile) -> Loader {
+		Loader { lockfile: Some(lockfile), ..self }
+	}
+
+	/// Resolves a specifier imported by the module at `referrer`, including bare specifiers of packages, without
+	/// loading it.
+	pub fn resolve_specifier(&self, referrer: Option<&Path>, specifier: &str) -> Resolution {
+		if specifier.starts_with(NODE_PREFIX) {
+			Resolution::Builtin
+		} else if URL_PREFIXES.iter().any(|prefix| specifier.starts_with(prefix)) {
+			Resolution::Url
+		} else {
+			let path = self.resolve_package(referrer, specifier);
+			Resolution::Path(path.unwrap_or_else(|| resolve_path(referrer, specifier)))
+		}
+	}
+
+	/// Resolves `node:` specifiers to a registered compatibility module, or the standard module of the same name.
+	fn resolve_builtin(&self, specifier: &str) -> Option<&*mut JSObject> {
+		let name = specifier.strip_prefix(NODE_PREFIX)?;
+		self.registry.get(specifier).or_else(|| self.registry.get(name))
+	}
+
+	fn resolve_package(&self, referrer: Option<&Path>, specifier: &str) -> Option<PathBuf> {
+		if !is_bare_specifier(specifier) || self.registry.contains_key(specifier) {
+			return None;
+		}
+		let directory = match referrer.and_then(Path::parent) {
+			Some(directory) => directory.to_path_buf(),
+			None => current_dir().ok()?,
+		};
+		resolve_package(&directory, specifier, &self.conditions)
+	}
+
+	/// Loads a module from a `data:` URL or blob object URL.
+	/// As their contents cannot change, modules are cached by URL, and remain available after a blob URL is revoked.
+	fn resolve_url<'cx>(&mut self, cx: &'cx Context, specifier: &str) -> Result<Module<'cx>> {
+		if let Some(module) = self.registry.get(specifier) {
+			return Ok(Module(Object::from(unsafe { Local::from_marked(module) })));
+		}
+
+		let script = read_url(cx, specifier)?;
+		let _span = info_span!("module.load", url = specifier).entered();
+		SourceMapStore::with(|store| store.register_source(specifier, &script));
+		let module = compile_and_evaluate(cx, specifier, None, &script)?;
+		let request = ModuleRequest::new(cx, specifier);
+		self.register(cx, module.0.handle().get(), &request)?;
+		Ok(module)
+	}
+}
+
+impl Default for Loader {
+	fn default() -> Loader {
+		Loader {
+			registry: HashMap::new(),
+			conditions: DEFAULT_CONDITIONS.iter().map(|condition| String::from(*condition)).collect(),
+			lockfile: None,
+		}
+	}
 }
 
 impl ModuleLoader for Loader {
@@ -29,38 +167,45 @@ impl ModuleLoader for Loader {
 		let specifier = request.specifier(cx).to_owned(cx).unwrap();
 		let data = ModuleData::from_private(cx, private);
 
-		let path = if specifier.starts_with("./") || specifier.starts_with("../") {
-			Path::new(data.as_ref().and_then(|d| d.path.as_ref()).unwrap())
-				.parent()
-				.unwrap()
-				.join(&specifier)
-		} else {
-			Path::new(&specifier).to_path_buf()
+		let referrer = data.as_ref().and_then(|d| d.path.as_deref()).map(Path::new);
+		let path = match self.resolve_specifier(referrer, &specifier) {
+			Resolution::Builtin => {
+				return match self.resolve_builtin(&specifier) {
+					Some(module) => Ok(Module(Object::from(unsafe { Local::from_marked(module) }))),
+					None => Err(Error::new(format!("Unknown built-in module: {specifier}"), None)),
+				};
+			}
+			Resolution::Url => return self.resolve_url(cx, &specifier),
+			Resolution::Path(path) => path,
 		};
 
 		let specifier = String::from(path.to_str().unwrap());
 		if let Some(module) = self.registry.get(&specifier) {
 			Ok(Module(Object::from(unsafe { Local::from_marked(module) })))
 		} else if let Ok(script) = read_to_string(&path) {
-			let is_typescript = Config::global().typescript && path.extension() == Some(OsStr::new("ts"));
-			let (script, sourcemap) = is_typescript
-				.then(|| locate_in_cache(&path, &script))
-				.flatten()
-				.map(|(s, sm)| (s, Some(sm)))
-				.unwrap_or_else(|| (script, None));
-			if let Some(sourcemap) = sourcemap {
-				save_sourcemap(&path, sourcemap);
+			let _span = info_span!("module.load", path = specifier).entered();
+			record_file_opened(cx);
+
+			if let Some(lockfile) = &mut self.lockfile {
+				lockfile.verify(&path).map_err(|error| Error::new(error.to_string(), None))?;
 			}
 
-			let module = Module::compile_and_evaluate(cx, &specifier, Some(path.as_path()), &script);
+			let is_typescript = Config::global().typescript && path.extension() == Some(OsStr::new("ts"));
+			let script = match is_typescript.then(|| locate_in_cache(&path, &script)).flatten() {
+				Some((compiled, sourcemap)) => {
+					SourceMapStore::with(|store| store.insert(&path, sourcemap));
+					compiled
+				}
+				None => {
+					SourceMapStore::with(|store| store.register_source(&path, &script));
+					script
+				}
+			};
 
-			if let Ok((module, _)) = module {
-				let request = ModuleRequest::new(cx, path.to_str().unwrap());
-				self.register(cx, module.0.handle().get(), &request)?;
-				Ok(module)
-			} else {
-				Err(Error::new(format!("Unable to compile module: {specifier}"), None))
-			}
+			let module = compile_and_evaluate(cx, &specifier, Some(path.as_path()), &script)?;
+			let request = ModuleRequest::new(cx, path.to_str().unwrap());
+			self.register(cx, module.0.handle().get(), &request)?;
+			Ok(module)
 		} else {
 			Err(Error::new(format!("Unable to read module: {specifier}"), None))
 		}
@@ -88,6 +233,9 @@ impl ModuleLoader for Loader {
 				}
 			}
 		}
+		if !meta.set_as(cx, "conditions", &self.conditions) {
+			return Err(Error::none());
+		}
 		Ok(())
 	}
 }
@@ -0,0 +1,235 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::fs::{read, read_dir, read_to_string, write};
+use std::path::{Component, Path, PathBuf};
+use std::{fmt, io};
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use dunce::canonicalize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha3::{Digest, Sha3_512};
+
+pub const LOCKFILE: &str = "spiderfire.lock";
+const LOCKFILE_VERSION: u32 = 1;
+const NODE_MODULES: &str = "node_modules";
+
+/// Records the version and content hash of each package in `node_modules`, so that modules loaded from packages can
+/// be verified against the state of the packages when the lockfile was created.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Lockfile {
+	version: u32,
+	packages: BTreeMap<String, LockedPackage>,
+	#[serde(skip)]
+	root: PathBuf,
+	#[serde(skip)]
+	verified: HashSet<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LockedPackage {
+	pub name: String,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub version: Option<String>,
+	pub integrity: String,
+}
+
+impl Lockfile {
+	/// Creates a lockfile for the packages in the `node_modules` directory of `root`, including nested packages.
+	pub fn generate(root: &Path) -> Result<Lockfile, LockError> {
+		let root = canonicalize(root)?;
+		let mut packages = BTreeMap::new();
+		let node_modules = root.join(NODE_MODULES);
+		if node_modules.is_dir() {
+			lock_packages(&root, &node_modules, &mut packages)?;
+		}
+
+		Ok(Lockfile {
+			version: LOCKFILE_VERSION,
+			packages,
+			root,
+			verified: HashSet::new(),
+		})
+	}
+
+	/// Reads `spiderfire.lock` from `root`, if it exists.
+	pub fn read(root: &Path) -> Result<Option<Lockfile>, LockError> {
+		let path = root.join(LOCKFILE);
+		if !path.is_file() {
+			return Ok(None);
+		}
+
+		let mut lockfile: Lockfile = serde_json::from_str(&read_to_string(&path)?)?;
+		if lockfile.version != LOCKFILE_VERSION {
+			return Err(LockError::Version(lockfile.version));
+		}
+		lockfile.root = canonicalize(root)?;
+		Ok(Some(lockfile))
+	}
+
+	/// Writes the lockfile to `spiderfire.lock` in its root directory.
+	pub fn write(&self) -> Result<(), LockError> {
+		let mut json = serde_json::to_string_pretty(self)?;
+		json.push('\n');
+		write(self.root.join(LOCKFILE), json)?;
+		Ok(())
+	}
+
+	pub fn packages(&self) -> &BTreeMap<String, LockedPackage> {
+		&self.packages
+	}
+
+	/// Verifies the package containing the module at `path` against the lockfile.
+	/// Modules outside of `node_modules` are not verified, and each package is only hashed once.
+	pub fn verify(&mut self, path: &Path) -> Result<(), LockError> {
+		let Some(package) = package_root(&canonicalize(path)?) else {
+			return Ok(());
+		};
+		let key = package_key(&self.root, &package);
+		if self.verified.contains(&key) {
+			return Ok(());
+		}
+
+		let Some(locked) = self.packages.get(&key) else {
+			return Err(LockError::Missing(key));
+		};
+		if locked.integrity != integrity(&package)? {
+			return Err(LockError::Integrity(key));
+		}
+
+		self.verified.insert(key);
+		Ok(())
+	}
+}
+
+#[derive(Debug)]
+pub enum LockError {
+	Io(io::Error),
+	Json(serde_json::Error),
+	Version(u32),
+	Missing(String),
+	Integrity(String),
+}
+
+impl From<io::Error> for LockError {
+	fn from(err: io::Error) -> LockError {
+		LockError::Io(err)
+	}
+}
+
+impl From<serde_json::Error> for LockError {
+	fn from(err: serde_json::Error) -> LockError {
+		LockError::Json(err)
+	}
+}
+
+impl Display for LockError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		match self {
+			LockError::Io(err) => f.write_str(&err.to_string()),
+			LockError::Json(err) => write!(f, "Invalid {LOCKFILE}: {err}"),
+			LockError::Version(version) => write!(f, "Unsupported {LOCKFILE} version: {version}"),
+			LockError::Missing(package) => {
+				write!(f, "Package {package} is not in {LOCKFILE}, run `spiderfire cache --lock` to update it")
+			}
+			LockError::Integrity(package) => {
+				write!(f, "Package {package} does not match the integrity recorded in {LOCKFILE}")
+			}
+		}
+	}
+}
+
+/// Returns the directory of the innermost package in `node_modules` which contains `path`.
+fn package_root(path: &Path) -> Option<PathBuf> {
+	let components: Vec<_> = path.components().collect();
+	let index = components.iter().rposition(|component| component.as_os_str() == NODE_MODULES)?;
+
+	let name = components.get(index + 1)?;
+	let length = match name {
+		Component::Normal(name) if name.to_string_lossy().starts_with('@') => index + 3,
+		_ => index + 2,
+	};
+	(length < components.len()).then(|| components[..length].iter().collect())
+}
+
+/// Returns the path of a package relative to the root, with `/` as the separator.
+fn package_key(root: &Path, package: &Path) -> String {
+	let relative = package.strip_prefix(root).unwrap_or(package);
+	let components: Vec<_> = relative.components().map(|component| component.as_os_str().to_string_lossy()).collect();
+	components.join("/")
+}
+
+fn lock_packages(
+	root: &Path, node_modules: &Path, packages: &mut BTreeMap<String, LockedPackage>,
+) -> Result<(), LockError> {
+	for entry in read_dir(node_modules)? {
+		let path = entry?.path();
+		if !path.is_dir() {
+			continue;
+		}
+
+		let is_scope = path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('@'));
+		if is_scope {
+			lock_packages(root, &path, packages)?;
+			continue;
+		}
+
+		let manifest = path.join("package.json");
+		if !manifest.is_file() {
+			continue;
+		}
+		let manifest: Value = serde_json::from_str(&read_to_string(&manifest)?)?;
+		let key = package_key(root, &path);
+		let fallback = key.rsplit_once("node_modules/").map_or(key.as_str(), |(_, name)| name);
+		let package = LockedPackage {
+			name: String::from(manifest.get("name").and_then(Value::as_str).unwrap_or(fallback)),
+			version: manifest.get("version").and_then(Value::as_str).map(String::from),
+			integrity: integrity(&path)?,
+		};
+		packages.insert(key, package);
+
+		let nested = path.join(NODE_MODULES);
+		if nested.is_dir() {
+			lock_packages(root, &nested, packages)?;
+		}
+	}
+	Ok(())
+}
+
+/// Hashes the relative paths and contents of the files in a package, excluding nested packages.
+fn integrity(package: &Path) -> io::Result<String> {
+	let mut files = Vec::new();
+	collect_files(package, &mut files)?;
+	files.sort();
+
+	let mut hasher = Sha3_512::new();
+	for file in files {
+		let relative = package_key(package, &file);
+		hasher.update(relative.as_bytes());
+		hasher.update([0]);
+		hasher.update(read(&file)?);
+		hasher.update([0]);
+	}
+	Ok(format!("sha3-512-{}", BASE64_STANDARD.encode(hasher.finalize())))
+}
+
+fn collect_files(directory: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+	for entry in read_dir(directory)? {
+		let path = entry?.path();
+		if path.is_dir() {
+			if path.file_name().is_some_and(|name| name != NODE_MODULES) {
+				collect_files(&path, files)?;
+			}
+		} else {
+			files.push(path);
+		}
+	}
+	Ok(())
+}
@@ -0,0 +1,100 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::hash_map::{Entry, HashMap};
+use std::path::{Path, PathBuf};
+
+use ion::module::{Module, ModuleData, ModuleLoader, ModuleRequest};
+use ion::utils::normalise_path;
+use ion::{Context, Error, Local, Object, Result, Value};
+use mozjs::jsapi::JSObject;
+use tracing::info_span;
+
+use crate::cache::map::SourceMapStore;
+use crate::module::{compile_and_evaluate, resolve_path, NODE_PREFIX};
+
+/// Loads modules from sources held in memory, instead of the file system, such as for embedders or targets without
+/// one.
+///
+/// Modules are identified by their normalised path, which relative specifiers are resolved against. TypeScript is not
+/// compiled, and `import.meta` is empty.
+#[derive(Default)]
+pub struct MemoryLoader {
+	sources: HashMap<PathBuf, String>,
+	registry: HashMap<String, *mut JSObject>,
+}
+
+impl MemoryLoader {
+	pub fn new() -> MemoryLoader {
+		MemoryLoader::default()
+	}
+
+	/// Adds the source of a module, which can then be imported from `path`.
+	pub fn module<P: AsRef<Path>, S: Into<String>>(mut self, path: P, source: S) -> MemoryLoader {
+		self.insert(path, source);
+		self
+	}
+
+	/// Adds the source of a module, replacing any previous source of `path`.
+	///
+	/// Modules which have already been imported are not reloaded.
+	pub fn insert<P: AsRef<Path>, S: Into<String>>(&mut self, path: P, source: S) {
+		self.sources.insert(normalise_path(path), source.into());
+	}
+
+	fn get<'cx>(&self, specifier: &str) -> Option<Module<'cx>> {
+		let module = self.registry.get(specifier)?;
+		Some(Module(Object::from(unsafe { Local::from_marked(module) })))
+	}
+}
+
+impl ModuleLoader for MemoryLoader {
+	fn resolve<'cx>(&mut self, cx: &'cx Context, private: &Value, request: &ModuleRequest) -> Result<Module<'cx>> {
+		let specifier = request.specifier(cx).to_owned(cx)?;
+
+		if let Some(name) = specifier.strip_prefix(NODE_PREFIX) {
+			let module = self.get(&specifier).or_else(|| self.get(name));
+			return module.ok_or_else(|| Error::new(format!("Unknown built-in module: {specifier}"), None));
+		}
+		if let Some(module) = self.get(&specifier) {
+			return Ok(module);
+		}
+
+		let data = ModuleData::from_private(cx, private);
+		let referrer = data.as_ref().and_then(|d| d.path.as_deref()).map(Path::new);
+		let path = normalise_path(resolve_path(referrer, &specifier));
+
+		let specifier = String::from(path.to_str().unwrap());
+		if let Some(module) = self.get(&specifier) {
+			return Ok(module);
+		}
+		let Some(script) = self.sources.get(&path) else {
+			return Err(Error::new(format!("Unable to find module: {specifier}"), None));
+		};
+
+		let _span = info_span!("module.load", path = specifier).entered();
+		SourceMapStore::with(|store| store.register_source(&path, script));
+		let module = compile_and_evaluate(cx, &specifier, Some(path.as_path()), script)?;
+		let request = ModuleRequest::new(cx, &specifier);
+		self.register(cx, module.0.handle().get(), &request)?;
+		Ok(module)
+	}
+
+	fn register(&mut self, cx: &Context, module: *mut JSObject, request: &ModuleRequest) -> Result<()> {
+		let specifier = request.specifier(cx).to_owned(cx)?;
+		match self.registry.entry(specifier) {
+			Entry::Vacant(v) => {
+				v.insert(module);
+				Ok(())
+			}
+			Entry::Occupied(_) => Err(Error::new("Module already exists", None)),
+		}
+	}
+
+	fn metadata(&self, _: &Context, _: &Value, _: &Object) -> Result<()> {
+		Ok(())
+	}
+}
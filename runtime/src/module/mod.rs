@@ -4,8 +4,53 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::path::{Path, PathBuf};
+
+use ion::module::Module;
+use ion::{Context, Error, Result};
+
+use crate::inspector::{notify, InspectorEvent};
+
+#[cfg(feature = "fs")]
 pub use loader::*;
+#[cfg(feature = "fs")]
+pub use lock::*;
+pub use memory::*;
+#[cfg(feature = "fs")]
+pub use package::*;
 pub use standard::*;
 
+#[cfg(feature = "fs")]
 pub mod loader;
+#[cfg(feature = "fs")]
+pub mod lock;
+pub mod memory;
+#[cfg(feature = "fs")]
+pub mod package;
 pub mod standard;
+
+/// Prefix of specifiers for Node.js built-in modules.
+pub const NODE_PREFIX: &str = "node:";
+
+/// Resolves a module specifier against the path of the module which requested it.
+/// Relative specifiers are resolved against the directory of the referrer, while other specifiers are used as is.
+pub fn resolve_path(referrer: Option<&Path>, specifier: &str) -> PathBuf {
+	match referrer.and_then(Path::parent) {
+		Some(directory) if specifier.starts_with("./") || specifier.starts_with("../") => directory.join(specifier),
+		_ => PathBuf::from(specifier),
+	}
+}
+
+/// Compiles and evaluates a module for a module loader, notifying inspector subscribers once it is compiled, even if
+/// its evaluation throws.
+pub(crate) fn compile_and_evaluate<'cx>(
+	cx: &'cx Context, specifier: &str, path: Option<&Path>, script: &str,
+) -> Result<Module<'cx>> {
+	let error = || Error::new(format!("Unable to compile module: {specifier}"), None);
+	let module = Module::compile(cx, specifier, path, script).map_err(|_| error())?;
+	notify(cx, || InspectorEvent::ScriptParsed { path: String::from(specifier) });
+
+	module.link(cx).map_err(|_| error())?;
+	module.evaluate(cx).map_err(|_| error())?;
+	Ok(module)
+}
@@ -0,0 +1,119 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+/// Conditions which are always considered when matching the `exports` of a package.
+pub const DEFAULT_CONDITIONS: [&str; 3] = ["spiderfire", "import", "default"];
+
+/// Checks if a module specifier refers to a package, rather than a relative or absolute path.
+pub fn is_bare_specifier(specifier: &str) -> bool {
+	!(specifier.starts_with("./")
+		|| specifier.starts_with("../")
+		|| specifier.starts_with('/')
+		|| Path::new(specifier).is_absolute())
+}
+
+/// Resolves a bare specifier to a file within a package in a `node_modules` directory of `directory` or its
+/// ancestors.
+///
+/// If the package defines `exports` in its `package.json`, the specifier is matched against them, taking
+/// `conditions` into account. Otherwise, `main` or `index.js` is used for the package itself, and subpaths are
+/// resolved directly against the package directory.
+pub fn resolve_package(directory: &Path, specifier: &str, conditions: &[String]) -> Option<PathBuf> {
+	let (name, subpath) = split_specifier(specifier)?;
+
+	for directory in directory.ancestors() {
+		let package = directory.join("node_modules").join(name);
+		let manifest = package.join("package.json");
+		if !manifest.is_file() {
+			continue;
+		}
+
+		let manifest: Value = serde_json::from_str(&read_to_string(&manifest).ok()?).ok()?;
+		if let Some(exports) = manifest.get("exports") {
+			let target = resolve_exports(exports, &subpath, conditions)?;
+			return Some(package.join(target));
+		}
+
+		return if subpath == "." {
+			let main = manifest.get("main").and_then(Value::as_str).unwrap_or("index.js");
+			Some(package.join(main))
+		} else {
+			Some(package.join(subpath))
+		};
+	}
+
+	None
+}
+
+/// Splits a bare specifier into the name of the package and the subpath within it, such as `"."` or `"./utils"`.
+fn split_specifier(specifier: &str) -> Option<(&str, String)> {
+	let mut separators = specifier.match_indices('/').map(|(index, _)| index);
+	let end = if specifier.starts_with('@') {
+		separators.nth(1)
+	} else {
+		separators.next()
+	};
+
+	let (name, rest) = match end {
+		Some(end) => specifier.split_at(end),
+		None => (specifier, ""),
+	};
+	if name.is_empty() || name.starts_with('.') || (specifier.starts_with('@') && !name.contains('/')) {
+		return None;
+	}
+	Some((name, format!(".{rest}")))
+}
+
+/// Resolves a subpath of a package against its `exports`, returning the target relative to the package.
+fn resolve_exports(exports: &Value, subpath: &str, conditions: &[String]) -> Option<String> {
+	let exports = match exports {
+		Value::Object(map) if map.keys().all(|key| key.starts_with('.')) => map,
+		_ if subpath == "." => return resolve_target(exports, None, conditions),
+		_ => return None,
+	};
+
+	if let Some(target) = exports.get(subpath) {
+		return resolve_target(target, None, conditions);
+	}
+
+	let (target, replacement) = match_pattern(exports, subpath)?;
+	resolve_target(target, Some(replacement), conditions)
+}
+
+/// Finds the subpath pattern with the longest prefix which matches `subpath`, returning its target and the
+/// substitution for `*`.
+fn match_pattern<'v, 's>(exports: &'v Map<String, Value>, subpath: &'s str) -> Option<(&'v Value, &'s str)> {
+	exports
+		.iter()
+		.filter_map(|(key, target)| {
+			let (prefix, suffix) = key.split_once('*')?;
+			let replacement = subpath.strip_prefix(prefix)?.strip_suffix(suffix)?;
+			Some((prefix.len(), target, replacement))
+		})
+		.max_by_key(|(length, _, _)| *length)
+		.map(|(_, target, replacement)| (target, replacement))
+}
+
+/// Resolves a target of `exports`, choosing the first condition in the package's order which is active.
+fn resolve_target(target: &Value, replacement: Option<&str>, conditions: &[String]) -> Option<String> {
+	match target {
+		Value::String(target) if target.starts_with("./") => match replacement {
+			Some(replacement) => Some(target.replace('*', replacement)),
+			None => Some(target.clone()),
+		},
+		Value::Array(targets) => targets.iter().find_map(|target| resolve_target(target, replacement, conditions)),
+		Value::Object(map) => map
+			.iter()
+			.filter(|(condition, _)| *condition == "default" || conditions.contains(condition))
+			.find_map(|(_, target)| resolve_target(target, replacement, conditions)),
+		_ => None,
+	}
+}
@@ -49,20 +49,23 @@ pub fn init_module<'cx, M: NativeModule<'cx>>(cx: &'cx Context, global: &Object,
 
 	if let Some(module) = module.module(cx) {
 		if global.define_as(cx, internal, &module, PropertyFlags::CONSTANT) {
-			let loader = unsafe { &mut (*cx.get_inner_data().as_ptr()).module_loader };
-			return loader
-				.as_mut()
-				.is_some_and(|loader| {
-					let module = Module::compile(cx, M::NAME, None, M::SOURCE).unwrap();
-					let request = ModuleRequest::new(cx, M::NAME);
-					loader.register(cx, module.0.handle().get(), &request).is_ok()
-				})
-				.then_some(module);
+			return register_module(cx, M::NAME, M::SOURCE).then_some(module);
 		}
 	}
 	None
 }
 
+/// Compiles a module written in JavaScript and registers it with the module loader, so that it can be imported as
+/// `name`.
+pub fn register_module(cx: &Context, name: &str, source: &str) -> bool {
+	let loader = unsafe { &mut (*cx.get_inner_data().as_ptr()).module_loader };
+	loader.as_mut().is_some_and(|loader| {
+		let module = Module::compile(cx, name, None, source).unwrap();
+		let request = ModuleRequest::new(cx, name);
+		loader.register(cx, module.0.handle().get(), &request).is_ok()
+	})
+}
+
 pub fn init_global_module<'cx, M: NativeModule<'cx>>(
 	cx: &'cx Context, global: &Object, module: &M,
 ) -> Option<Object<'cx>> {
@@ -5,34 +5,185 @@
  */
 
 use std::future::Future;
+#[cfg(feature = "tokio-promise")]
+use std::panic::resume_unwind;
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
+use futures::future::{select, Either};
+use futures::FutureExt;
 use ion::conversions::{BoxedIntoValue, IntoValue};
-use ion::{Context, Promise};
-use tokio::task::spawn_local;
+use ion::{Context, Promise, ResultExc, Value};
+use mozjs::jsapi::PromiseState;
+#[cfg(feature = "tokio-promise")]
+use tokio::task::spawn_blocking;
 
+use crate::event_loop::future::FutureOutput;
+use crate::event_loop::hooks::PromiseHooks;
+use crate::event_loop::MicrotaskCheckpoint;
+use crate::globals::abort::Signal;
 use crate::ContextExt;
 
 /// Returns None if no future queue has been initialised.
+///
+/// The future is spawned with the [Spawner](crate::event_loop::Spawner) of the runtime, unless the runtime has no
+/// spawner, in which case it is polled in place by the event loop.
 pub fn future_to_promise<'cx, F, O, E>(cx: &'cx Context, future: F) -> Option<Promise<'cx>>
 where
 	F: Future<Output = Result<O, E>> + 'static,
 	O: for<'cx2> IntoValue<'cx2> + 'static,
 	E: for<'cx2> IntoValue<'cx2> + 'static,
+{
+	enqueue_future(cx, box_output(future))
+}
+
+/// Returns None if no future queue has been initialised.
+///
+/// Like [future_to_promise], except the promise is rejected with the reason of the signal once it is aborted, even if
+/// it was aborted beforehand. The future is then dropped without being polled again, which [cancels](Cancellation) any
+/// offloaded tasks it was awaiting.
+pub fn future_to_promise_with_signal<'cx, F, O, E>(cx: &'cx Context, signal: &Signal, future: F) -> Option<Promise<'cx>>
+where
+	F: Future<Output = Result<O, E>> + 'static,
+	O: for<'cx2> IntoValue<'cx2> + 'static,
+	E: for<'cx2> IntoValue<'cx2> + 'static,
+{
+	let aborted = signal.poll();
+	let future = box_output(future);
+	enqueue_future(cx, async move {
+		match select(pin!(aborted), pin!(future)).await {
+			Either::Left((reason, _)) => Err(Box::new(reason) as BoxedIntoValue),
+			Either::Right((output, _)) => output,
+		}
+	})
+}
+
+async fn box_output<F, O, E>(future: F) -> FutureOutput
+where
+	F: Future<Output = Result<O, E>>,
+	O: for<'cx2> IntoValue<'cx2> + 'static,
+	E: for<'cx2> IntoValue<'cx2> + 'static,
+{
+	match future.await {
+		Ok(o) => Ok(Box::new(o) as BoxedIntoValue),
+		Err(e) => Err(Box::new(e) as BoxedIntoValue),
+	}
+}
+
+fn enqueue_future<'cx, F>(cx: &'cx Context, future: F) -> Option<Promise<'cx>>
+where
+	F: Future<Output = FutureOutput> + 'static,
 {
 	let promise = Promise::new(cx);
-	let object = promise.handle().get();
-
-	let handle = spawn_local(async move {
-		let result: Result<BoxedIntoValue, BoxedIntoValue> = match future.await {
-			Ok(o) => Ok(Box::new(o)),
-			Err(e) => Err(Box::new(e)),
-		};
-		(result, object)
-	});
-
-	let event_loop = unsafe { &cx.get_private().event_loop };
-	event_loop.futures.as_ref().map(|futures| {
-		futures.enqueue(handle);
+
+	let private = unsafe { cx.get_private() };
+	let spawner = private.event_loop.spawner.as_ref();
+	let hooks = private.event_loop.promise_hooks.clone();
+	let promise = private.event_loop.futures.as_mut().map(|futures| {
+		match spawner {
+			Some(spawner) => futures.enqueue(spawner.spawn(future.boxed_local()), &promise),
+			None => futures.enqueue_local(future.boxed_local(), &promise),
+		}
 		promise
-	})
+	})?;
+
+	if let Some(hooks) = hooks {
+		PromiseHooks::call(&hooks.init, cx, &promise);
+	}
+	Some(promise)
+}
+
+/// Adds native reactions to a promise, and returns a [MicrotaskCheckpoint] if the promise has already settled.
+///
+/// Reactions to a settled promise are queued immediately, but would otherwise only run at the next checkpoint of the
+/// event loop, after native code may have observed the state they update. Dropping the returned guard runs them, so it
+/// should be held until no state used by the reactions is borrowed.
+///
+/// Returns [None] if the promise is pending, or the reactions could not be added.
+#[must_use]
+pub fn add_reactions_with_checkpoint<'cx, T, C>(
+	cx: &'cx Context, promise: &Promise, on_resolved: T, on_rejected: C,
+) -> Option<MicrotaskCheckpoint<'cx>>
+where
+	T: for<'cx2> FnOnce(&'cx2 Context, &Value<'cx2>) -> ResultExc<Value<'cx2>> + 'static,
+	C: for<'cx2> FnOnce(&'cx2 Context, &Value<'cx2>) -> ResultExc<Value<'cx2>> + 'static,
+{
+	let settled = promise.state() != PromiseState::Pending;
+	(promise.add_reactions(cx, on_resolved, on_rejected) && settled).then(|| MicrotaskCheckpoint::new(cx))
+}
+
+/// Represents the cancellation state of an offloaded task.
+///
+/// The task is cancelled when the future awaiting it is dropped, such as when the runtime is shut down.
+/// Long-running tasks should check for cancellation periodically and return early.
+#[derive(Clone, Debug, Default)]
+pub struct Cancellation(Arc<AtomicBool>);
+
+impl Cancellation {
+	pub fn is_cancelled(&self) -> bool {
+		self.0.load(Ordering::Acquire)
+	}
+
+	fn cancel(&self) {
+		self.0.store(true, Ordering::Release);
+	}
+}
+
+struct CancelOnDrop(Cancellation);
+
+impl Drop for CancelOnDrop {
+	fn drop(&mut self) {
+		self.0.cancel();
+	}
+}
+
+/// Runs a blocking or CPU-intensive task on the blocking thread pool of the runtime, and returns its output.
+///
+/// Panics within the task are propagated to the caller.
+#[cfg(feature = "tokio-promise")]
+pub async fn run_blocking<F, T>(task: F) -> T
+where
+	F: FnOnce(&Cancellation) -> T + Send + 'static,
+	T: Send + 'static,
+{
+	let cancellation = Cancellation::default();
+	let guard = CancelOnDrop(cancellation.clone());
+
+	let result = spawn_blocking(move || task(&cancellation)).await;
+	drop(guard);
+
+	match result {
+		Ok(output) => output,
+		Err(error) if error.is_panic() => resume_unwind(error.into_panic()),
+		Err(error) => panic!("Offloaded task failed: {error}"),
+	}
+}
+
+/// Runs a blocking or CPU-intensive task when the returned future is first polled, and returns its output.
+///
+/// Without the `tokio-promise` feature, there is no blocking thread pool, so the task blocks the JS thread.
+#[cfg(not(feature = "tokio-promise"))]
+pub async fn run_blocking<F, T>(task: F) -> T
+where
+	F: FnOnce(&Cancellation) -> T + Send + 'static,
+	T: Send + 'static,
+{
+	let cancellation = Cancellation::default();
+	let _guard = CancelOnDrop(cancellation.clone());
+	task(&cancellation)
+}
+
+/// Runs a blocking or CPU-intensive task on the blocking thread pool of the runtime,
+/// so that it does not block the JS thread.
+///
+/// Returns a promise which settles with the result of the task,
+/// or None if no future queue has been initialised.
+pub fn offload<'cx, F, O, E>(cx: &'cx Context, task: F) -> Option<Promise<'cx>>
+where
+	F: FnOnce(&Cancellation) -> Result<O, E> + Send + 'static,
+	O: for<'cx2> IntoValue<'cx2> + Send + 'static,
+	E: for<'cx2> IntoValue<'cx2> + Send + 'static,
+{
+	future_to_promise(cx, run_blocking(task))
 }
@@ -0,0 +1,85 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use ion::Context;
+use mozjs::jsapi::{JSGCParamKey, JS_GetGCParameter};
+
+use crate::ContextExt;
+
+/// Resources used by a runtime since it was created.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+	/// Bytes of response bodies received over the network by `fetch`.
+	pub bytes_fetched: u64,
+	/// Files opened by the module loader and the `fs` module, including attempts which failed.
+	pub files_opened: u64,
+	/// Timers created by `setTimeout` and `setInterval`.
+	pub timers_created: u64,
+	/// Native futures which have not completed yet.
+	pub futures_pending: usize,
+	/// `fetch` requests with `keepalive` set which have not completed yet.
+	pub keepalive_requests: usize,
+	/// Total size of the bodies of the keepalive requests which have not completed yet, in bytes.
+	pub keepalive_bytes: u64,
+	/// Current size of the garbage-collected heap, in bytes.
+	pub heap_bytes: u64,
+	/// Largest size of the garbage-collected heap observed, in bytes.
+	/// The heap is sampled whenever the event loop is polled and when usage is queried.
+	pub peak_heap_bytes: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct ResourceCounters {
+	bytes_fetched: u64,
+	files_opened: u64,
+	timers_created: u64,
+	peak_heap_bytes: u64,
+}
+
+impl ResourceCounters {
+	/// Samples the size of the heap, updating the peak. Returns the current size.
+	pub(crate) fn sample_heap(&mut self, cx: &Context) -> u64 {
+		let bytes = u64::from(unsafe { JS_GetGCParameter(cx.as_ptr(), JSGCParamKey::JSGC_BYTES) });
+		self.peak_heap_bytes = self.peak_heap_bytes.max(bytes);
+		bytes
+	}
+}
+
+/// Returns the resources used by the runtime of the context.
+pub fn resource_usage(cx: &Context) -> ResourceUsage {
+	let private = unsafe { cx.get_private() };
+	let heap_bytes = private.resources.sample_heap(cx);
+	let counters = &private.resources;
+	#[cfg(feature = "fetch")]
+	let keepalive = (private.fetch_keepalive.requests(), private.fetch_keepalive.bytes());
+	#[cfg(not(feature = "fetch"))]
+	let keepalive = (0, 0);
+
+	ResourceUsage {
+		bytes_fetched: counters.bytes_fetched,
+		files_opened: counters.files_opened,
+		timers_created: counters.timers_created,
+		futures_pending: private.event_loop.futures.as_ref().map_or(0, |futures| futures.len()),
+		keepalive_requests: keepalive.0,
+		keepalive_bytes: keepalive.1,
+		heap_bytes,
+		peak_heap_bytes: counters.peak_heap_bytes,
+	}
+}
+
+/// Records that a file was opened, for accounting by embedders.
+pub fn record_file_opened(cx: &Context) {
+	unsafe { cx.get_private().resources.files_opened += 1 };
+}
+
+#[cfg(feature = "fetch")]
+pub(crate) fn record_bytes_fetched(cx: &Context, bytes: u64) {
+	unsafe { cx.get_private().resources.bytes_fetched += bytes };
+}
+
+pub(crate) fn record_timer_created(cx: &Context) {
+	unsafe { cx.get_private().resources.timers_created += 1 };
+}
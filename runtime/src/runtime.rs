@@ -4,40 +4,86 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use std::collections::HashMap;
-use std::ptr;
+use std::fmt::{Debug, Formatter};
+use std::mem::ManuallyDrop;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU32, Ordering};
+#[cfg(feature = "fetch")]
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use std::{fmt, ptr};
 
 use ion::module::{init_module_loader, ModuleLoader};
 use ion::object::default_new_global;
-use ion::{Context, ContextInner, ErrorReport, Object};
+use ion::{live_boxed_heaps, live_roots, Context, ContextInner, ErrorReport, Function, Object};
 use mozjs::gc::Traceable;
 use mozjs::glue::CreateJobQueue;
 use mozjs::jsapi::{
-	ContextOptionsRef, Heap, JSAutoRealm, JSObject, JSTracer, SetJobQueue, SetPromiseRejectionTrackerCallback,
+	ContextOptionsRef, JSAutoRealm, JSGCParamKey, JSTracer, JS_AddInterruptCallback, JS_SetGCParameter,
+	JS_SetSecurityCallbacks, SetJobQueue, SetPromiseRejectionTrackerCallback,
 };
-use uuid::Uuid;
+use mozjs::rust::{JSEngine, Runtime as RustRuntime};
+use tracing::warn;
+#[cfg(feature = "fetch")]
+use url::{Origin, Url};
 
+use crate::cache::map::SECURITY_CALLBACKS;
+use crate::clock::Clock;
+use crate::config::{Config, Globals, CONFIG};
 use crate::event_loop::future::FutureQueue;
 use crate::event_loop::macrotasks::MacrotaskQueue;
+use crate::event_loop::messages::MessageQueue;
 use crate::event_loop::microtasks::{MicrotaskQueue, JOB_QUEUE_TRAPS};
-use crate::event_loop::{promise_rejection_tracker_callback, EventLoop};
+use crate::event_loop::{
+	promise_rejection_tracker_callback, EventLoop, EventLoopMetrics, EventLoopSender, MissedTickBehavior, Spawner,
+	UncaughtErrorAction, UncaughtErrorHandler, UnhandledRejectionHandler,
+};
+use crate::globals::console::ConsoleSink;
+#[cfg(feature = "fetch")]
+use crate::globals::fetch::{
+	add_fetch_interceptor, FetchClient, FetchInterceptor, FetchTimeouts, HttpCache, KeepaliveRequests, PreflightCache,
+	SharedTimeouts,
+};
+use crate::globals::url::{BlobStore, BlobStoreMetrics};
 use crate::globals::{init_globals, init_microtasks, init_timers};
-use crate::module::StandardModules;
+use crate::inspector::{Coverage, InspectorSubscriber, ScriptCoverage};
+use crate::module::{MemoryLoader, StandardModules};
+use crate::resources::{resource_usage, ResourceCounters, ResourceUsage};
+use crate::timeout::{interrupt_callback, Watchdog};
+
+static RUNTIME_ID: AtomicU32 = AtomicU32::new(1);
 
 #[derive(Default)]
 pub struct ContextPrivate {
+	pub(crate) id: u32,
 	pub(crate) event_loop: EventLoop,
-	pub(crate) blob_store: HashMap<Uuid, Box<Heap<*mut JSObject>>>,
+	pub(crate) blob_store: BlobStore,
+	pub(crate) console_sink: Option<Rc<dyn ConsoleSink>>,
+	pub(crate) inspector_subscribers: Vec<Rc<dyn InspectorSubscriber>>,
+	pub(crate) coverage: Option<Coverage>,
+	pub(crate) resources: ResourceCounters,
+	pub(crate) deadline: Option<Instant>,
+	pub(crate) clock: Clock,
+	pub(crate) random: u64,
+	pub(crate) globals: Globals,
+	#[cfg(feature = "fetch")]
+	pub(crate) fetch_interceptors: Vec<Rc<dyn FetchInterceptor>>,
+	#[cfg(feature = "fetch")]
+	pub(crate) fetch_cache: Option<HttpCache>,
+	#[cfg(feature = "fetch")]
+	pub(crate) fetch_client: Option<Rc<dyn FetchClient>>,
+	#[cfg(feature = "fetch")]
+	pub(crate) fetch_timeouts: SharedTimeouts,
+	#[cfg(feature = "fetch")]
+	pub(crate) fetch_keepalive: Rc<KeepaliveRequests>,
+	#[cfg(feature = "fetch")]
+	pub(crate) fetch_origin: Option<Origin>,
+	#[cfg(feature = "fetch")]
+	pub(crate) fetch_preflight: PreflightCache,
 }
 
 unsafe impl Traceable for ContextPrivate {
-	unsafe fn trace(&self, trc: *mut JSTracer) {
-		for blob in self.blob_store.values() {
-			unsafe {
-				blob.trace(trc);
-			}
-		}
-	}
+	unsafe fn trace(&self, _: *mut JSTracer) {}
 }
 
 pub trait ContextExt {
@@ -51,6 +97,59 @@ impl ContextExt for Context {
 	}
 }
 
+/// Owns the SpiderMonkey engine, and the JS runtime and [Context] which a [Runtime] is built on.
+///
+/// The engine can only be initialised once per process.
+pub struct Engine {
+	cx: ManuallyDrop<Context>,
+	runtime: ManuallyDrop<RustRuntime>,
+	#[expect(dead_code)]
+	engine: JSEngine,
+}
+
+impl Engine {
+	/// Initialises the engine, panicking if it has already been initialised.
+	pub fn new() -> Engine {
+		let engine = JSEngine::init().unwrap();
+		let runtime = RustRuntime::new(engine.handle());
+		let cx = Context::from_runtime(&runtime);
+		Engine {
+			cx: ManuallyDrop::new(cx),
+			runtime: ManuallyDrop::new(runtime),
+			engine,
+		}
+	}
+
+	/// Returns the context to build a [Runtime] with, using [RuntimeBuilder::build].
+	pub fn cx(&mut self) -> &mut Context {
+		&mut self.cx
+	}
+}
+
+impl Default for Engine {
+	fn default() -> Engine {
+		Engine::new()
+	}
+}
+
+impl Drop for Engine {
+	/// Destroys the JS runtime, which finalises the objects owning boxed heaps, before the engine is shut down.
+	fn drop(&mut self) {
+		unsafe {
+			ManuallyDrop::drop(&mut self.cx);
+			ManuallyDrop::drop(&mut self.runtime);
+		}
+
+		// Boxed heaps are only tracked in debug builds.
+		for heap in live_boxed_heaps() {
+			warn!(
+				"Leaked boxed heap of {}, created at:\n{}",
+				heap.type_name, heap.backtrace
+			);
+		}
+	}
+}
+
 pub struct Runtime<'cx> {
 	global: Object<'cx>,
 	cx: &'cx Context,
@@ -67,34 +166,196 @@ impl<'cx> Runtime<'cx> {
 		&self.global
 	}
 
+	/// Returns the ID of the runtime, which is unique within the process.
+	pub fn id(&self) -> u32 {
+		unsafe { self.cx.get_private().id }
+	}
+
+	/// Returns the number and total size of the blobs referenced by live object URLs.
+	pub fn blob_store_metrics(&self) -> BlobStoreMetrics {
+		unsafe { self.cx.get_private().blob_store.metrics() }
+	}
+
+	/// Returns the resources used by the runtime, such as bytes fetched and timers created.
+	pub fn resource_usage(&self) -> ResourceUsage {
+		resource_usage(self.cx)
+	}
+
+	/// Returns the time the event loop has spent running tasks and waiting for them.
+	pub fn event_loop_metrics(&self) -> EventLoopMetrics {
+		unsafe { self.cx.get_private().event_loop.metrics() }
+	}
+
+	/// Adds an interceptor which runs before `fetch` sends a request over the network.
+	#[cfg(feature = "fetch")]
+	pub fn add_fetch_interceptor<I: FetchInterceptor + 'static>(&self, interceptor: I) {
+		add_fetch_interceptor(self.cx, Rc::new(interceptor))
+	}
+
+	/// Returns the number of times each line of the scripts compiled since the runtime was built has run.
+	///
+	/// Returns [None] if coverage was not enabled with [RuntimeBuilder::coverage], or could not be collected.
+	pub fn coverage(&self) -> Option<Vec<ScriptCoverage>> {
+		unsafe { self.cx.get_private().coverage.as_ref() }.and_then(|coverage| coverage.report(self.cx))
+	}
+
+	/// Returns the [Clock] which timers, `Date` and `performance.now` are based on.
+	pub fn clock(&self) -> &Clock {
+		unsafe { &self.cx.get_private().clock }
+	}
+
+	/// Advances the virtual [Clock] of the runtime by `duration`, so that timers which have become due run in the next
+	/// tick of the event loop.
+	///
+	/// Returns false if the runtime is not in [deterministic mode](Config::deterministic), as the system clock cannot
+	/// be advanced.
+	pub fn advance_time(&self, duration: Duration) -> bool {
+		let private = unsafe { self.cx.get_private() };
+		let advanced = private.clock.advance(duration);
+		if advanced {
+			private.event_loop.wake();
+		}
+		advanced
+	}
+
+	/// Returns a handle which sends [Messages](crate::event_loop::Message) to `callback` from any thread.
+	///
+	/// The callback replaces that of previously created senders, and the event loop runs while any sender is alive.
+	pub fn event_loop_sender(&self, callback: &Function) -> EventLoopSender {
+		let event_loop = unsafe { &mut self.cx.get_private().event_loop };
+		event_loop.messages.get_or_insert_with(MessageQueue::default).sender(self.cx, callback)
+	}
+
 	pub fn global_mut(&mut self) -> &Object<'cx> {
 		&mut self.global
 	}
 
+	/// Passes an error which was not caught by a script, such as one thrown while evaluating the entry point, to the
+	/// uncaught error handlers.
+	pub fn handle_uncaught_error(&self, report: &ErrorReport) -> UncaughtErrorAction {
+		let event_loop = unsafe { &self.cx.get_private().event_loop };
+		event_loop.handle_uncaught_error(self.cx, report)
+	}
+
+	/// Runs `callback`, such as the evaluation of a script or module, and throws a catchable `TimeoutError` in any
+	/// script it runs once `timeout` has elapsed.
+	///
+	/// Timeouts can be nested, in which case the earliest deadline applies. Promise jobs and timers queued by the
+	/// callback are not bounded, as they run later in the event loop.
+	pub fn with_timeout<T, F: FnOnce() -> T>(&self, timeout: Duration, callback: F) -> T {
+		let deadline = Instant::now() + timeout;
+		let previous = unsafe { self.cx.get_private().deadline };
+		let deadline = previous.map_or(deadline, |previous| previous.min(deadline));
+		unsafe { self.cx.get_private().deadline = Some(deadline) };
+
+		let watchdog = Watchdog::start(self.cx, deadline.saturating_duration_since(Instant::now()));
+		let result = callback();
+		drop(watchdog);
+
+		unsafe { self.cx.get_private().deadline = previous };
+		result
+	}
+
 	pub async fn run_event_loop(&self) -> Result<(), Option<ErrorReport>> {
 		let event_loop = unsafe { &mut self.cx.get_private().event_loop };
 		event_loop.run_event_loop(self.cx).await
 	}
+
+	/// Waits up to `timeout` for `fetch` requests with `keepalive` set to complete, and then cancels all native futures
+	/// which have not completed, as when the runtime is dropped.
+	///
+	/// This lets requests such as analytics beacons finish after the event loop has stopped, such as due to an uncaught
+	/// error. Their promises are not settled, as no more JS is run. Returns whether all of them completed in time.
+	#[cfg(feature = "fetch")]
+	pub async fn shutdown(&self, timeout: Duration) -> bool {
+		let keepalive = unsafe { Rc::clone(&self.cx.get_private().fetch_keepalive) };
+		let event_loop = unsafe { &mut self.cx.get_private().event_loop };
+		let completed = tokio::time::timeout(timeout, event_loop.poll_native_until(keepalive.idle())).await.is_ok();
+
+		event_loop.shutdown(self.cx);
+		completed
+	}
 }
 
 impl Drop for Runtime<'_> {
 	fn drop(&mut self) {
+		let event_loop = unsafe { &mut self.cx.get_private().event_loop };
+		event_loop.shutdown(self.cx);
+
 		let inner_private = self.cx.get_inner_data().as_ptr();
 		unsafe {
 			let _ = Box::from_raw(inner_private);
 			ContextInner::remove_tracer(self.cx.as_ptr(), inner_private);
 		}
+
+		// Persistent roots are only tracked in debug builds.
+		for root in live_roots() {
+			warn!(
+				"Leaked persistent root of {}, created at:\n{}",
+				root.type_name, root.backtrace
+			);
+		}
 	}
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone)]
 pub struct RuntimeBuilder<ML: ModuleLoader + 'static = (), Std: StandardModules + 'static = ()> {
 	microtask_queue: bool,
 	macrotask_queue: bool,
+	missed_tick_behavior: MissedTickBehavior,
+	console_sink: Option<Rc<dyn ConsoleSink>>,
+	inspector_subscribers: Vec<Rc<dyn InspectorSubscriber>>,
+	coverage: bool,
+	uncaught_error_handler: Option<Rc<dyn UncaughtErrorHandler>>,
+	unhandled_rejection_handler: Option<Rc<dyn UnhandledRejectionHandler>>,
+	spawner: Option<Rc<dyn Spawner>>,
+	globals: Option<Globals>,
+	#[cfg(feature = "fetch")]
+	fetch_interceptors: Vec<Rc<dyn FetchInterceptor>>,
+	#[cfg(feature = "fetch")]
+	fetch_cache: Option<HttpCache>,
+	#[cfg(feature = "fetch")]
+	fetch_client: Option<Rc<dyn FetchClient>>,
+	#[cfg(feature = "fetch")]
+	fetch_timeouts: FetchTimeouts,
+	#[cfg(feature = "fetch")]
+	fetch_origin: Option<Origin>,
 	modules: Option<ML>,
 	standard_modules: Option<Std>,
 }
 
+/// Handlers, sinks and clients are trait objects, so they are only shown by whether they are set.
+impl<ML: ModuleLoader + 'static, Std: StandardModules + 'static> Debug for RuntimeBuilder<ML, Std> {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let mut debug = f.debug_struct("RuntimeBuilder");
+		debug
+			.field("microtask_queue", &self.microtask_queue)
+			.field("macrotask_queue", &self.macrotask_queue)
+			.field("missed_tick_behavior", &self.missed_tick_behavior)
+			.field("console_sink", &self.console_sink.is_some())
+			.field("inspector_subscribers", &self.inspector_subscribers.len())
+			.field("coverage", &self.coverage)
+			.field("uncaught_error_handler", &self.uncaught_error_handler.is_some())
+			.field(
+				"unhandled_rejection_handler",
+				&self.unhandled_rejection_handler.is_some(),
+			)
+			.field("spawner", &self.spawner.is_some())
+			.field("globals", &self.globals);
+		#[cfg(feature = "fetch")]
+		debug
+			.field("fetch_interceptors", &self.fetch_interceptors.len())
+			.field("fetch_cache", &self.fetch_cache.is_some())
+			.field("fetch_client", &self.fetch_client.is_some())
+			.field("fetch_timeouts", &self.fetch_timeouts)
+			.field("fetch_origin", &self.fetch_origin);
+		debug
+			.field("modules", &self.modules.is_some())
+			.field("standard_modules", &self.standard_modules.is_some())
+			.finish()
+	}
+}
+
 impl<ML: ModuleLoader + 'static, Std: StandardModules + 'static> RuntimeBuilder<ML, Std> {
 	pub fn new() -> RuntimeBuilder<ML, Std> {
 		RuntimeBuilder::default()
@@ -105,6 +366,116 @@ impl<ML: ModuleLoader + 'static, Std: StandardModules + 'static> RuntimeBuilder<
 		self
 	}
 
+	/// Sets how repeating timers are rescheduled when they fall behind schedule.
+	pub fn missed_tick_behavior(mut self, behavior: MissedTickBehavior) -> RuntimeBuilder<ML, Std> {
+		self.missed_tick_behavior = behavior;
+		self
+	}
+
+	/// Replaces the default [StdioSink](crate::globals::console::StdioSink) which receives the output of `console`.
+	pub fn console_sink<S: ConsoleSink + 'static>(mut self, sink: S) -> RuntimeBuilder<ML, Std> {
+		self.console_sink = Some(Rc::new(sink));
+		self
+	}
+
+	/// Adds a subscriber which receives console output, uncaught errors and loaded modules as
+	/// [InspectorEvents](crate::inspector::InspectorEvent).
+	pub fn inspector<S: InspectorSubscriber + 'static>(mut self, subscriber: S) -> RuntimeBuilder<ML, Std> {
+		self.inspector_subscribers.push(Rc::new(subscriber));
+		self
+	}
+
+	/// Collects the [coverage](Runtime::coverage) of scripts compiled once the runtime is built, which prevents some
+	/// optimisations of the engine.
+	pub fn coverage(mut self) -> RuntimeBuilder<ML, Std> {
+		self.coverage = true;
+		self
+	}
+
+	/// Sets a handler which receives errors not caught by scripts before the event loop stops, and may keep it running.
+	///
+	/// It is called before the handler set by `setUncaughtExceptionHandler`.
+	pub fn uncaught_error_handler<H: UncaughtErrorHandler + 'static>(mut self, handler: H) -> RuntimeBuilder<ML, Std> {
+		self.uncaught_error_handler = Some(Rc::new(handler));
+		self
+	}
+
+	/// Sets a handler which receives the reasons of unhandled promise rejections, instead of logging them as errors.
+	pub fn unhandled_rejection_handler<H: UnhandledRejectionHandler + 'static>(
+		mut self, handler: H,
+	) -> RuntimeBuilder<ML, Std> {
+		self.unhandled_rejection_handler = Some(Rc::new(handler));
+		self
+	}
+
+	/// Replaces the [Spawner] which the event loop runs native futures and timers on.
+	///
+	/// The [TokioSpawner](crate::event_loop::TokioSpawner) is used by default, when built with the `tokio-promise`
+	/// feature. Without a spawner, native futures are polled in place by the event loop, and timers are waited for on
+	/// a separate thread.
+	pub fn spawner<S: Spawner + 'static>(mut self, spawner: S) -> RuntimeBuilder<ML, Std> {
+		self.spawner = Some(Rc::new(spawner));
+		self
+	}
+
+	/// Sets the global [Config], which is shared by every runtime in the process.
+	///
+	/// Returns the configuration as an error if a different configuration has already been set.
+	pub fn config(self, config: Config) -> Result<RuntimeBuilder<ML, Std>, Config> {
+		if *CONFIG.get_or_init(|| config) == config {
+			Ok(self)
+		} else {
+			Err(config)
+		}
+	}
+
+	/// Sets the globals defined by the runtime, instead of those in the global [Config].
+	pub fn globals(mut self, globals: Globals) -> RuntimeBuilder<ML, Std> {
+		self.globals = Some(globals);
+		self
+	}
+
+	/// Adds an interceptor which runs before `fetch` sends a request over the network.
+	///
+	/// Interceptors run in the order they were added.
+	#[cfg(feature = "fetch")]
+	pub fn fetch_interceptor<I: FetchInterceptor + 'static>(mut self, interceptor: I) -> RuntimeBuilder<ML, Std> {
+		self.fetch_interceptors.push(Rc::new(interceptor));
+		self
+	}
+
+	/// Enables the [HttpCache] used by `fetch`, which does not cache responses by default.
+	#[cfg(feature = "fetch")]
+	pub fn fetch_cache(mut self, cache: HttpCache) -> RuntimeBuilder<ML, Std> {
+		self.fetch_cache = Some(cache);
+		self
+	}
+
+	/// Replaces the default [HyperClient](crate::globals::fetch::HyperClient) which `fetch` sends requests with.
+	#[cfg(feature = "fetch")]
+	pub fn fetch_client<C: FetchClient + 'static>(mut self, client: C) -> RuntimeBuilder<ML, Std> {
+		self.fetch_client = Some(Rc::new(client));
+		self
+	}
+
+	/// Sets the default [FetchTimeouts] of `fetch`, which scripts can change through `fetch.defaults`.
+	#[cfg(feature = "fetch")]
+	pub fn fetch_timeouts(mut self, timeouts: FetchTimeouts) -> RuntimeBuilder<ML, Std> {
+		self.fetch_timeouts = timeouts;
+		self
+	}
+
+	/// Sets the origin of the runtime, which `fetch` enforces CORS against.
+	///
+	/// Requests to other origins are then sent with an `Origin` header, preceded by a preflight if they use methods or
+	/// headers which are not CORS-safelisted, and fail unless the server allows the origin with
+	/// `Access-Control-Allow-Origin`. Without an origin, `same-origin` requests always fail and CORS is not enforced.
+	#[cfg(feature = "fetch")]
+	pub fn fetch_origin(mut self, url: &Url) -> RuntimeBuilder<ML, Std> {
+		self.fetch_origin = Some(url.origin());
+		self
+	}
+
 	pub fn microtask_queue(mut self) -> RuntimeBuilder<ML, Std> {
 		self.microtask_queue = true;
 		self
@@ -126,9 +497,30 @@ impl<ML: ModuleLoader + 'static, Std: StandardModules + 'static> RuntimeBuilder<
 
 		let global_obj = global.handle().get();
 		global.set_as(cx, "global", &global_obj);
-		init_globals(cx, &global);
+
+		let globals = self.globals.or_else(|| CONFIG.get().map(|config| config.globals)).unwrap_or_default();
+		init_globals(cx, &global, globals);
 
 		let mut private = Box::<ContextPrivate>::default();
+		private.globals = globals;
+		if let Some(config) = CONFIG.get().filter(|config| config.deterministic) {
+			private.clock = Clock::virtual_time();
+			private.random = config.random_seed;
+		}
+		private.id = RUNTIME_ID.fetch_add(1, Ordering::Relaxed);
+		private.console_sink = self.console_sink;
+		private.inspector_subscribers = self.inspector_subscribers;
+		private.event_loop.uncaught_error_handler = self.uncaught_error_handler;
+		private.event_loop.unhandled_rejection_handler = self.unhandled_rejection_handler;
+		private.event_loop.spawner = self.spawner;
+		#[cfg(feature = "fetch")]
+		{
+			private.fetch_interceptors = self.fetch_interceptors;
+			private.fetch_cache = self.fetch_cache;
+			private.fetch_client = self.fetch_client;
+			private.fetch_timeouts = Arc::new(Mutex::new(self.fetch_timeouts));
+			private.fetch_origin = self.fetch_origin;
+		}
 
 		if self.microtask_queue {
 			private.event_loop.microtasks = Some(MicrotaskQueue::default());
@@ -151,11 +543,17 @@ impl<ML: ModuleLoader + 'static, Std: StandardModules + 'static> RuntimeBuilder<
 			}
 		}
 		if self.macrotask_queue {
-			private.event_loop.macrotasks = Some(MacrotaskQueue::default());
-			init_timers(cx, &global);
+			private.event_loop.macrotasks = Some(MacrotaskQueue::new(self.missed_tick_behavior, private.clock.clone()));
+			init_timers(cx, &global, globals);
 		}
 
+		unsafe { JS_AddInterruptCallback(cx.as_ptr(), Some(interrupt_callback)) };
+		unsafe { JS_SetSecurityCallbacks(cx.as_ptr(), &SECURITY_CALLBACKS) };
+
 		let _options = unsafe { &mut *ContextOptionsRef(cx.as_ptr()) };
+		if let Some(max_bytes) = CONFIG.get().and_then(|config| config.gc_max_bytes) {
+			unsafe { JS_SetGCParameter(cx.as_ptr(), JSGCParamKey::JSGC_MAX_BYTES, max_bytes) };
+		}
 
 		cx.set_private(private);
 
@@ -172,15 +570,54 @@ impl<ML: ModuleLoader + 'static, Std: StandardModules + 'static> RuntimeBuilder<
 			}
 		}
 
+		if self.coverage {
+			unsafe { cx.get_private().coverage = Coverage::start(cx, &global) };
+		}
+
 		Runtime { global, cx, realm }
 	}
 }
 
+impl<Std: StandardModules + 'static> RuntimeBuilder<MemoryLoader, Std> {
+	/// Creates a builder for a runtime which does not access the network or file system, such as for embedding or for
+	/// targets without them.
+	///
+	/// Only the [minimal](Globals::minimal) globals are defined, and modules are only loaded from `loader`.
+	pub fn minimal(loader: MemoryLoader) -> RuntimeBuilder<MemoryLoader, Std> {
+		RuntimeBuilder::new()
+			.microtask_queue()
+			.macrotask_queue()
+			.globals(Globals::minimal())
+			.modules(loader)
+	}
+}
+
 impl<ML: ModuleLoader + 'static, Std: StandardModules + 'static> Default for RuntimeBuilder<ML, Std> {
 	fn default() -> RuntimeBuilder<ML, Std> {
 		RuntimeBuilder {
 			microtask_queue: false,
 			macrotask_queue: false,
+			missed_tick_behavior: MissedTickBehavior::default(),
+			console_sink: None,
+			inspector_subscribers: Vec::new(),
+			coverage: false,
+			uncaught_error_handler: None,
+			unhandled_rejection_handler: None,
+			#[cfg(feature = "tokio-promise")]
+			spawner: Some(Rc::new(crate::event_loop::TokioSpawner)),
+			#[cfg(not(feature = "tokio-promise"))]
+			spawner: None,
+			globals: None,
+			#[cfg(feature = "fetch")]
+			fetch_interceptors: Vec::new(),
+			#[cfg(feature = "fetch")]
+			fetch_cache: None,
+			#[cfg(feature = "fetch")]
+			fetch_client: None,
+			#[cfg(feature = "fetch")]
+			fetch_timeouts: FetchTimeouts::default(),
+			#[cfg(feature = "fetch")]
+			fetch_origin: None,
 			modules: None,
 			standard_modules: None,
 		}
@@ -0,0 +1,77 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::env::var_os;
+
+#[cfg(feature = "fetch")]
+use http::HeaderMap;
+use opentelemetry::trace::{TraceError, TracerProvider as _};
+use opentelemetry::{global, KeyValue};
+#[cfg(feature = "fetch")]
+use opentelemetry_http::HeaderInjector;
+use opentelemetry_otlp::SpanExporter;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::runtime::TokioCurrentThread;
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::Resource;
+#[cfg(feature = "fetch")]
+use tracing::Span;
+#[cfg(feature = "fetch")]
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry;
+use tracing_subscriber::util::SubscriberInitExt;
+
+const SERVICE_NAME: &str = "spiderfire";
+
+/// Exports the spans of the runtime to an OpenTelemetry collector over OTLP.
+///
+/// Pending spans are flushed when it is dropped.
+#[derive(Debug)]
+pub struct Telemetry {
+	provider: TracerProvider,
+}
+
+impl Telemetry {
+	/// Installs the global tracing subscriber and [W3C Trace Context](https://www.w3.org/TR/trace-context/) propagator.
+	///
+	/// The collector endpoint is read from `OTEL_EXPORTER_OTLP_ENDPOINT`, and defaults to `http://localhost:4317`.
+	pub fn init() -> Result<Telemetry, TraceError> {
+		let exporter = SpanExporter::builder().with_tonic().build()?;
+
+		let mut resource = Resource::default();
+		if var_os("OTEL_SERVICE_NAME").is_none() {
+			resource = resource.merge(&Resource::new([KeyValue::new("service.name", SERVICE_NAME)]));
+		}
+
+		let provider = TracerProvider::builder()
+			.with_batch_exporter(exporter, TokioCurrentThread)
+			.with_resource(resource)
+			.build();
+		let tracer = provider.tracer(SERVICE_NAME);
+
+		registry()
+			.with(tracing_opentelemetry::layer().with_tracer(tracer))
+			.try_init()
+			.map_err(|error| TraceError::Other(Box::new(error)))?;
+		global::set_text_map_propagator(TraceContextPropagator::new());
+
+		Ok(Telemetry { provider })
+	}
+}
+
+impl Drop for Telemetry {
+	fn drop(&mut self) {
+		let _ = self.provider.shutdown();
+	}
+}
+
+/// Adds the `traceparent` header of the current span to an outgoing request.
+#[cfg(feature = "fetch")]
+pub(crate) fn inject_context(headers: &mut HeaderMap) {
+	let context = Span::current().context();
+	global::get_text_map_propagator(|propagator| propagator.inject_context(&context, &mut HeaderInjector(headers)));
+}
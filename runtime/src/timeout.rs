@@ -0,0 +1,69 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::thread::{spawn, JoinHandle};
+use std::time::{Duration, Instant};
+
+use ion::conversions::ToValue;
+use ion::{Context, Error, Exception, ThrowException};
+use mozjs::jsapi::{JSContext, JS_RequestInterruptCallback};
+
+use crate::ContextExt;
+
+/// Throws a `TimeoutError` in scripts once the deadline of the current evaluation has passed.
+///
+/// The interrupt is requested again after throwing, so that scripts which catch the error are interrupted again
+/// until the evaluation returns.
+pub(crate) unsafe extern "C" fn interrupt_callback(cx: *mut JSContext) -> bool {
+	let cx = &unsafe { Context::new_unchecked(cx) };
+	match unsafe { cx.get_private().deadline } {
+		Some(deadline) if Instant::now() >= deadline => {}
+		_ => return true,
+	}
+
+	let error = Error::new("Evaluation timed out", None).as_value(cx);
+	error.to_object(cx).set_as(cx, "name", "TimeoutError");
+	Exception::Other(error.get()).throw(cx);
+
+	unsafe { JS_RequestInterruptCallback(cx.as_ptr()) };
+	false
+}
+
+struct ContextPtr(*mut JSContext);
+
+// SAFETY: The pointer is only used to request an interrupt, which is safe from any thread.
+unsafe impl Send for ContextPtr {}
+
+/// Requests an interrupt of the context from another thread once a timeout has elapsed, unless it is dropped first.
+pub(crate) struct Watchdog {
+	cancel: Option<Sender<()>>,
+	thread: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+	pub(crate) fn start(cx: &Context, timeout: Duration) -> Watchdog {
+		let (cancel, cancelled) = channel();
+		let cx = ContextPtr(cx.as_ptr());
+		let thread = spawn(move || {
+			let cx = cx;
+			if let Err(RecvTimeoutError::Timeout) = cancelled.recv_timeout(timeout) {
+				unsafe { JS_RequestInterruptCallback(cx.0) };
+			}
+		});
+
+		Watchdog { cancel: Some(cancel), thread: Some(thread) }
+	}
+}
+
+impl Drop for Watchdog {
+	fn drop(&mut self) {
+		drop(self.cancel.take());
+		if let Some(thread) = self.thread.take() {
+			let _ = thread.join();
+		}
+	}
+}
@@ -6,7 +6,7 @@
 
 use std::fmt;
 use std::fmt::{Display, Formatter};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::string::FromUtf8Error;
 
 use sourcemap::SourceMap;
@@ -19,16 +19,55 @@ use swc_core::ecma::ast::{EsVersion, Pass, Program};
 use swc_core::ecma::codegen::text_writer::JsWriter;
 use swc_core::ecma::codegen::{Config as CodegenConfig, Emitter};
 use swc_core::ecma::parser::lexer::Lexer;
-use swc_core::ecma::parser::{Capturing, Parser, Syntax, TsSyntax};
+use swc_core::ecma::parser::{Capturing, EsSyntax, Parser, Syntax, TsSyntax};
 use swc_core::ecma::transforms::base::fixer::fixer;
 use swc_core::ecma::transforms::base::hygiene::hygiene;
 use swc_core::ecma::transforms::base::resolver;
 use swc_core::ecma::transforms::typescript::strip;
 use swc_core::ecma::visit::VisitMut;
+use tracing::info_span;
 
 use crate::config::Config;
+use crate::VERSION;
 
-pub fn compile_typescript(filename: &str, source: &str) -> Result<(String, SourceMap), Error> {
+const PARSE_TARGET: EsVersion = EsVersion::Es2022;
+const EMIT_TARGET: EsVersion = EsVersion::Es2024;
+
+/// Options which the output of [compile_typescript] depends on.
+#[derive(Clone, Copy, Debug)]
+pub struct CompilerOptions {
+	/// Whether sources are compiled as scripts, rather than modules.
+	pub script: bool,
+}
+
+impl CompilerOptions {
+	/// Returns the options given by the global [Config].
+	pub fn global() -> CompilerOptions {
+		CompilerOptions { script: Config::global().script }
+	}
+
+	/// Describes the options, along with the version of spiderfire and the configuration of the parser and code
+	/// generator, including the TypeScript and JSX syntax. Cached output is only reused if it has the same description.
+	pub fn describe(&self) -> String {
+		let kind = if self.script { "script" } else { "module" };
+		let syntax = syntax();
+		let codegen = codegen_config();
+		format!("spiderfire {VERSION}, {kind}, {syntax:?}, {PARSE_TARGET:?}, {codegen:?}")
+	}
+}
+
+fn syntax() -> TsSyntax {
+	TsSyntax::default()
+}
+
+fn codegen_config() -> CodegenConfig {
+	CodegenConfig::default().with_target(EMIT_TARGET)
+}
+
+pub fn compile_typescript(
+	filename: &str, source: &str, options: CompilerOptions,
+) -> Result<(String, SourceMap), Error> {
+	let _span = info_span!("typescript.compile", file = filename).entered();
 	let name = Lrc::new(FileName::Real(PathBuf::from(filename)));
 
 	let source_map: Lrc<SwcSourceMap> = Lrc::default();
@@ -36,13 +75,14 @@ pub fn compile_typescript(filename: &str, source: &str) -> Result<(String, Sourc
 	let input = StringInput::from(&*file);
 
 	let comments = SingleThreadedComments::default();
-	let (handler, mut parser) = initialise_parser(Lrc::clone(&source_map), &comments, input);
+	let syntax = Syntax::Typescript(syntax());
+	let (handler, mut parser) = initialise_parser(Lrc::clone(&source_map), &comments, input, syntax);
 
 	let mut buffer = Vec::new();
 	let mut mappings = Vec::new();
-	let mut emitter = initialise_emitter(Lrc::clone(&source_map), &comments, &mut buffer, &mut mappings);
+	let mut emitter = initialise_emitter(Lrc::clone(&source_map), &comments, &mut buffer, Some(&mut mappings));
 
-	let mut program = if Config::global().script {
+	let mut program = if options.script {
 		Program::Script(parser.parse_script().map_err(|e| {
 			e.into_diagnostic(&handler).emit();
 			Error::Parse
@@ -59,6 +99,35 @@ pub fn compile_typescript(filename: &str, source: &str) -> Result<(String, Sourc
 	Ok((String::from_utf8(buffer)?, source_map))
 }
 
+/// Formats JavaScript or TypeScript source by reprinting it, keeping its comments.
+/// Files with TypeScript or JSX extensions are parsed with the corresponding syntax.
+pub fn format_source(filename: &Path, source: &str) -> Result<String, Error> {
+	let extension = filename.extension().and_then(|extension| extension.to_str()).unwrap_or_default();
+	let syntax = match extension {
+		"ts" | "mts" | "cts" => Syntax::Typescript(TsSyntax::default()),
+		"tsx" => Syntax::Typescript(TsSyntax { tsx: true, ..TsSyntax::default() }),
+		"jsx" => Syntax::Es(EsSyntax { jsx: true, ..EsSyntax::default() }),
+		_ => Syntax::Es(EsSyntax::default()),
+	};
+
+	let name = Lrc::new(FileName::Real(filename.to_path_buf()));
+	let source_map: Lrc<SwcSourceMap> = Lrc::default();
+	let file = source_map.new_source_file(name, String::from(source));
+	let input = StringInput::from(&*file);
+
+	let comments = SingleThreadedComments::default();
+	let (handler, mut parser) = initialise_parser(Lrc::clone(&source_map), &comments, input, syntax);
+	let program = parser.parse_program().map_err(|e| {
+		e.into_diagnostic(&handler).emit();
+		Error::Parse
+	})?;
+
+	let mut buffer = Vec::new();
+	let mut emitter = initialise_emitter(Lrc::clone(&source_map), &comments, &mut buffer, None);
+	emitter.emit_program(&program).map_err(|_| Error::Emission)?;
+	Ok(String::from_utf8(buffer)?)
+}
+
 pub fn handle_program(
 	program: &mut Program, emitter: &mut Emitter<JsWriter<&mut Vec<u8>>, SwcSourceMap>,
 ) -> Result<(), Error> {
@@ -77,15 +146,10 @@ pub fn handle_program(
 }
 
 fn initialise_parser<'a>(
-	source_map: Lrc<SwcSourceMap>, comments: &'a dyn Comments, input: StringInput<'a>,
+	source_map: Lrc<SwcSourceMap>, comments: &'a dyn Comments, input: StringInput<'a>, syntax: Syntax,
 ) -> (Handler, Parser<Capturing<Lexer<'a>>>) {
 	let handler = Handler::with_tty_emitter(ColorConfig::Auto, true, false, Some(source_map));
-	let lexer = Lexer::new(
-		Syntax::Typescript(TsSyntax::default()),
-		EsVersion::Es2022,
-		input,
-		Some(comments),
-	);
+	let lexer = Lexer::new(syntax, PARSE_TARGET, input, Some(comments));
 	let capturing = Capturing::new(lexer);
 	let mut parser = Parser::new_from(capturing);
 
@@ -98,13 +162,13 @@ fn initialise_parser<'a>(
 
 fn initialise_emitter<'a>(
 	source_map: Lrc<SwcSourceMap>, comments: &'a dyn Comments, buffer: &'a mut Vec<u8>,
-	mappings: &'a mut Vec<(BytePos, LineCol)>,
+	mappings: Option<&'a mut Vec<(BytePos, LineCol)>>,
 ) -> Emitter<'a, JsWriter<'a, &'a mut Vec<u8>>, SwcSourceMap> {
 	Emitter {
-		cfg: CodegenConfig::default().with_target(EsVersion::Es2024),
+		cfg: codegen_config(),
 		cm: Lrc::clone(&source_map),
 		comments: Some(comments),
-		wr: JsWriter::new(source_map, "\n", buffer, Some(mappings)),
+		wr: JsWriter::new(source_map, "\n", buffer, mappings),
 	}
 }
 
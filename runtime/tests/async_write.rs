@@ -0,0 +1,80 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::io;
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context as TaskContext, Poll};
+
+use ion::flags::PropertyFlags;
+use ion::script::Script;
+use ion::{Context, Object};
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::globals::streams::writable::WritableStream;
+use runtime::RuntimeBuilder;
+use tokio::io::AsyncWrite;
+use tokio::task::LocalSet;
+
+const FILE_NAME: &str = "async-write.js";
+const SCRIPT: &str = include_str!("scripts/async-write.js");
+
+thread_local! {
+	static WRITTEN: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+	static SHUTDOWN: Cell<bool> = const { Cell::new(false) };
+}
+
+#[tokio::test]
+async fn async_write() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().microtask_queue().macrotask_queue().build(cx);
+
+	let global = rt.global();
+	global.define_method(rt.cx(), "asyncWriter", async_writer_stream, 0, PropertyFlags::all());
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+		})
+		.await;
+
+	WRITTEN.with_borrow(|written| assert_eq!(written, b"\x01\x02\x03ab"));
+	assert!(SHUTDOWN.get());
+}
+
+struct Writer;
+
+impl AsyncWrite for Writer {
+	fn poll_write(self: Pin<&mut Self>, _: &mut TaskContext, buf: &[u8]) -> Poll<io::Result<usize>> {
+		WRITTEN.with_borrow_mut(|written| written.extend_from_slice(buf));
+		Poll::Ready(Ok(buf.len()))
+	}
+
+	fn poll_flush(self: Pin<&mut Self>, _: &mut TaskContext) -> Poll<io::Result<()>> {
+		Poll::Ready(Ok(()))
+	}
+
+	fn poll_shutdown(self: Pin<&mut Self>, _: &mut TaskContext) -> Poll<io::Result<()>> {
+		SHUTDOWN.set(true);
+		Poll::Ready(Ok(()))
+	}
+}
+
+#[ion::js_fn]
+fn async_writer_stream(cx: &Context) -> Object {
+	WritableStream::from_async_write(cx, Writer, 4)
+}
@@ -0,0 +1,55 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+use std::time::Duration;
+
+use ion::script::Script;
+use ion::{ClassDefinition, Context};
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::globals::file::{Blob, BlobData};
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+use tokio::time::sleep;
+
+const FILE_NAME: &str = "blob.js";
+const SCRIPT: &str = include_str!("scripts/blob.js");
+
+#[tokio::test]
+async fn blob_spill() {
+	let config = Config::default().log_level(LogLevel::Debug).script(true).blob_spill_threshold(Some(1024));
+	CONFIG.set(config).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().microtask_queue().macrotask_queue().build(cx);
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let blob = Script::compile_and_evaluate(rt.cx(), Path::new("spilled.js"), "blob").unwrap();
+			let blob = blob.to_object(rt.cx());
+			let blob = Blob::get_private(rt.cx(), &blob).unwrap();
+			for _ in 0..100 {
+				if let BlobData::File(snapshot) = blob.data().current() {
+					assert!(snapshot.path().exists());
+					return;
+				}
+				sleep(Duration::from_millis(50)).await;
+			}
+			panic!("Blob was not spilled to a temporary file");
+		})
+		.await;
+}
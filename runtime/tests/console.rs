@@ -4,17 +4,29 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::cell::RefCell;
 use std::path::Path;
+use std::rc::Rc;
 
 use ion::script::Script;
 use ion::Context;
 use mozjs::rust::{JSEngine, Runtime};
 use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::globals::console::ConsoleSink;
 use runtime::RuntimeBuilder;
 
 const FILE_NAME: &str = "console.js";
 const SCRIPT: &str = include_str!("scripts/console.js");
 
+#[derive(Clone, Default)]
+struct CaptureSink(Rc<RefCell<Vec<(LogLevel, String)>>>);
+
+impl ConsoleSink for CaptureSink {
+	fn write(&self, level: LogLevel, message: &str) {
+		self.0.borrow_mut().push((level, String::from(message)));
+	}
+}
+
 #[test]
 fn console() {
 	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
@@ -23,8 +35,21 @@ fn console() {
 	let rt = Runtime::new(engine.handle());
 
 	let cx = &mut Context::from_runtime(&rt);
-	let rt = RuntimeBuilder::<()>::new().build(cx);
+	let sink = CaptureSink::default();
+	let rt = RuntimeBuilder::<()>::new().console_sink(sink.clone()).build(cx);
 
 	let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
 	assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+	let output = sink.0.borrow();
+	assert!(output.iter().any(|(level, message)| *level == LogLevel::Info && message.starts_with("Log")));
+	assert!(output.iter().any(|(level, message)| *level == LogLevel::Warn && message.starts_with("Warn")));
+	assert!(output.iter().any(|(level, message)| *level == LogLevel::Error && message.starts_with("Error")));
+
+	// Every argument after the message of an assertion is logged.
+	let assertion = output.iter().find(|(_, message)| message.starts_with("Assertion Failed: Assertion:"));
+	assert!(assertion.is_some_and(|(_, message)| message.contains("true") && message.contains("Time -")));
+
+	let table = output.iter().find(|(_, message)| message.contains("1 more")).map(|(_, message)| message);
+	assert!(table.is_some_and(|table| table.contains("[Object]") && table.contains("xxx…")));
 }
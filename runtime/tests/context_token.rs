@@ -0,0 +1,28 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use futures::FutureExt;
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::{Engine, RuntimeBuilder};
+
+#[test]
+fn context_token() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let mut engine = Engine::new();
+	let rt = RuntimeBuilder::<()>::new().build(engine.cx());
+
+	let token = rt.cx().token();
+	assert!(token.is_alive());
+	assert!(token.upgrade().is_some_and(|cx| cx.as_ptr() == rt.cx().as_ptr()));
+	assert_eq!(token.guard(async { 1 }).now_or_never(), Some(1));
+
+	let guarded = token.guard(async { 2 });
+	drop(rt);
+	assert!(!token.is_alive());
+	assert!(token.upgrade().is_none());
+	assert_eq!(guarded.now_or_never(), None);
+}
@@ -0,0 +1,53 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use ion::script::Script;
+use ion::Context;
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+
+const FILE_NAME: &str = "deterministic.js";
+const SCRIPT: &str = include_str!("scripts/deterministic.js");
+
+#[tokio::test]
+async fn deterministic() {
+	CONFIG
+		.set(Config::default().log_level(LogLevel::Debug).deterministic(true).random_seed(42))
+		.unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().microtask_queue().macrotask_queue().build(cx);
+	assert!(rt.clock().is_virtual());
+
+	let start = Instant::now();
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			assert!(rt.advance_time(Duration::from_millis(5)));
+			assert_eq!(rt.clock().elapsed(), Duration::from_millis(5));
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+		})
+		.await;
+
+	assert_eq!(rt.clock().elapsed(), Duration::from_millis(2000));
+	assert!(
+		start.elapsed() < Duration::from_secs(1),
+		"Timers waited for the system clock"
+	);
+}
@@ -0,0 +1,67 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#![cfg(feature = "fetch")]
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use hyper_util::client::legacy::connect::dns::Name;
+use runtime::globals::fetch::Resolver;
+use tokio::net::UdpSocket;
+use tower_service::Service;
+
+#[tokio::test]
+async fn resolver_overrides() {
+	let addrs = [IpAddr::V6(Ipv6Addr::LOCALHOST), IpAddr::V4(Ipv4Addr::LOCALHOST)];
+	let mut resolver = Resolver::new().override_host("API.example.test", &addrs);
+
+	let name = Name::from_str("api.example.test").unwrap();
+	let resolved: Vec<_> = resolver.call(name).await.unwrap().collect();
+	let expected: Vec<_> = addrs.iter().map(|addr| SocketAddr::new(*addr, 0)).collect();
+	assert_eq!(resolved, expected);
+}
+
+/// Answers every query with the loopback address and a record TTL of `ttl` seconds, and counts the queries.
+async fn nameserver(ttl: u32) -> (SocketAddr, Arc<AtomicUsize>) {
+	let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+	let addr = socket.local_addr().unwrap();
+	let queries = Arc::new(AtomicUsize::new(0));
+	let counter = Arc::clone(&queries);
+	tokio::spawn(async move {
+		let mut buffer = [0; 512];
+		loop {
+			let (read, peer) = socket.recv_from(&mut buffer).await.unwrap();
+			counter.fetch_add(1, Ordering::SeqCst);
+
+			let mut response = buffer[..read].to_vec();
+			response[2..8].copy_from_slice(&[0x81, 0x80, 0x00, 0x01, 0x00, 0x01]);
+			response.extend_from_slice(&[0xC0, 0x0C, 0x00, 0x01, 0x00, 0x01]);
+			response.extend_from_slice(&ttl.to_be_bytes());
+			response.extend_from_slice(&[0x00, 0x04, 127, 0, 0, 1]);
+			socket.send_to(&response, peer).await.unwrap();
+		}
+	});
+	(addr, queries)
+}
+
+#[tokio::test]
+async fn resolver_record_ttl() {
+	for (ttl, expected) in [(0, 4), (60, 2)] {
+		let (addr, queries) = nameserver(ttl).await;
+		let mut resolver = Resolver::new().nameserver(addr).ttl(Duration::from_secs(60));
+
+		for _ in 0..2 {
+			let name = Name::from_str("localhost").unwrap();
+			assert!(resolver.call(name).await.unwrap().next().is_some());
+		}
+		// Addresses of records with a TTL of zero are resolved again, and queried again for their TTL.
+		assert_eq!(queries.load(Ordering::SeqCst), expected);
+	}
+}
@@ -0,0 +1,31 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use ion::conversions::FromValue;
+use ion::script::Script;
+use runtime::config::{Config, Globals, LogLevel, CONFIG};
+use runtime::{Engine, RuntimeBuilder};
+
+const SCRIPT: &str = "[typeof console, typeof setTimeout].join(\",\")";
+
+#[test]
+fn engine() {
+	let mut engine = Engine::new();
+	let config = Config::default().log_level(LogLevel::Warn).globals(Globals::none().console(true));
+	let rt = RuntimeBuilder::<()>::new().config(config).unwrap().build(engine.cx());
+
+	let global = CONFIG.get().expect("Configuration should be set by the builder");
+	assert_eq!(global.log_level, LogLevel::Warn);
+	assert!(RuntimeBuilder::<()>::new().config(config).is_ok());
+	assert!(RuntimeBuilder::<()>::new().config(config.log_level(LogLevel::Debug)).is_err());
+
+	let result = Script::compile_and_evaluate(rt.cx(), Path::new("engine.js"), SCRIPT);
+	let types = result.unwrap_or_else(|report| panic!("Error: {report:?}"));
+	let types = String::from_value(rt.cx(), &types, true, ()).unwrap();
+	assert_eq!(types, "object,undefined");
+}
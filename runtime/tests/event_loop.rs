@@ -0,0 +1,59 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use ion::flags::PropertyFlags;
+use ion::script::Script;
+use ion::{Context, Promise};
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::promise::future_to_promise;
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+
+const FILE_NAME: &str = "event-loop.js";
+const SCRIPT: &str = include_str!("scripts/event-loop.js");
+
+#[tokio::test]
+async fn ordering() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().microtask_queue().macrotask_queue().build(cx);
+
+	let global = rt.global();
+	global.define_method(rt.cx(), "resolveNative", resolve_native, 0, PropertyFlags::all());
+	global.define_method(rt.cx(), "panicNative", panic_native, 0, PropertyFlags::all());
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+		})
+		.await;
+}
+
+#[ion::js_fn]
+fn resolve_native(cx: &Context) -> Option<Promise> {
+	future_to_promise::<_, _, ()>(cx, async { Ok(()) })
+}
+
+#[ion::js_fn]
+fn panic_native(cx: &Context) -> Option<Promise> {
+	future_to_promise(cx, panicking())
+}
+
+async fn panicking() -> Result<(), ()> {
+	panic!("Native future panicked")
+}
@@ -0,0 +1,99 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#![cfg(all(unix, feature = "fetch"))]
+
+use std::env::temp_dir;
+use std::fs::remove_file;
+use std::path::Path;
+use std::process;
+
+use ion::script::Script;
+use ion::Context;
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::globals::fetch::HttpCache;
+use runtime::RuntimeBuilder;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixListener;
+use tokio::task::{spawn_local, LocalSet};
+
+const FILE_NAME: &str = "fetch-cache.js";
+const SCRIPT: &str = include_str!("scripts/fetch-cache.js");
+
+/// Serves a fresh response and a response which must be revalidated, returning the requests received.
+async fn serve(listener: UnixListener) -> Vec<String> {
+	let mut requests = Vec::new();
+	for index in 1..=4 {
+		let (mut stream, _) = listener.accept().await.unwrap();
+
+		let mut request = Vec::new();
+		let mut buffer = [0; 1024];
+		while !request.ends_with(b"\r\n\r\n") {
+			let read = stream.read(&mut buffer).await.unwrap();
+			assert_ne!(read, 0, "Connection closed before the request was received");
+			request.extend_from_slice(&buffer[..read]);
+		}
+
+		let request = String::from_utf8(request).unwrap().to_ascii_lowercase();
+		let path = request.split(' ').nth(1).unwrap().to_owned();
+		let revalidating = request.contains("if-none-match: \"v1\"");
+
+		let response = match path.as_str() {
+			"/etag" if revalidating => String::from("HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n"),
+			"/etag" => ok_response(&format!("etag {index}"), "Cache-Control: no-cache\r\nETag: \"v1\""),
+			_ => ok_response(&format!("fresh {index}"), "Cache-Control: max-age=60"),
+		};
+		stream.write_all(response.as_bytes()).await.unwrap();
+
+		requests.push(if revalidating { format!("{path} revalidate") } else { path });
+	}
+	requests
+}
+
+fn ok_response(body: &str, headers: &str) -> String {
+	format!(
+		"HTTP/1.1 200 OK\r\n{headers}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+		body.len()
+	)
+}
+
+#[tokio::test]
+async fn fetch_cache() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let path = temp_dir().join(format!("spiderfire-fetch-cache-{}.sock", process::id()));
+	let _ = remove_file(&path);
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new()
+		.microtask_queue()
+		.macrotask_queue()
+		.fetch_cache(HttpCache::new())
+		.build(cx);
+	rt.global().set_as(rt.cx(), "SOCKET_PATH", &path.to_string_lossy().into_owned());
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let server = spawn_local(serve(UnixListener::bind(&path).unwrap()));
+
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let requests = server.await.unwrap();
+			assert_eq!(requests, ["/fresh", "/etag", "/etag revalidate", "/fresh"]);
+		})
+		.await;
+
+	let _ = remove_file(&path);
+}
@@ -0,0 +1,69 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#![cfg(feature = "fetch")]
+
+use std::path::Path;
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use ion::script::Script;
+use ion::{Context, Error};
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::globals::fetch::{Body, ClientFuture, FetchClient};
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+
+const FILE_NAME: &str = "fetch-client.js";
+const SCRIPT: &str = include_str!("scripts/fetch-client.js");
+
+/// Transport which responds to every request with its method and URL, without using the network.
+/// Requests to `/refused` fail as if the connection was refused.
+struct EchoClient;
+
+impl FetchClient for EchoClient {
+	fn send(&self, request: hyper::Request<Body>) -> ClientFuture {
+		if request.uri().path() == "/refused" {
+			return Box::pin(async { Err(Error::new("Connection refused", None)) });
+		}
+		let echo = format!("{} {}", request.method(), request.uri().path());
+		Box::pin(async move {
+			let body = Full::new(Bytes::from(echo)).map_err(|never| match never {}).boxed();
+			let response = hyper::Response::builder().status(201).header("X-Client", "echo");
+			Ok(response.body(Body::Stream(body)).unwrap())
+		})
+	}
+}
+
+#[tokio::test]
+async fn fetch_client() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new()
+		.microtask_queue()
+		.macrotask_queue()
+		.fetch_client(EchoClient)
+		.build(cx);
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+		})
+		.await;
+
+	let usage = rt.resource_usage();
+	assert_eq!(usage.bytes_fetched, "POST /upload".len() as u64);
+}
@@ -0,0 +1,103 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#![cfg(feature = "fetch")]
+
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use hyper::Method;
+use ion::script::Script;
+use ion::Context;
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::globals::fetch::{Body, ClientFuture, FetchClient};
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+use url::Url;
+
+const FILE_NAME: &str = "fetch-cors.js";
+const SCRIPT: &str = include_str!("scripts/fetch-cors.js");
+
+const ORIGIN: &str = "https://app.example";
+
+/// Transport which allows `https://app.example` to access `/allowed` with `PUT` and `X-Custom`, counting the preflights
+/// it receives.
+///
+/// `/redirect` and `/back` redirect to another origin and back to `https://app.example`, after which only the `null`
+/// origin is allowed.
+struct CorsClient(Arc<AtomicUsize>);
+
+impl FetchClient for CorsClient {
+	fn send(&self, request: hyper::Request<Body>) -> ClientFuture {
+		let path = request.uri().path();
+		let mut response = hyper::Response::builder();
+		if path == "/allowed" || path == "/redirect" || path == "/back" {
+			response = response.header("Access-Control-Allow-Origin", ORIGIN);
+		} else if path == "/tainted" && request.headers().get("Origin").is_some_and(|origin| origin == "null") {
+			response = response.header("Access-Control-Allow-Origin", "null");
+		}
+
+		let location = match path {
+			"/redirect" => Some("https://other.example/tainted"),
+			"/back" => Some("https://app.example/same"),
+			_ => None,
+		};
+		if let Some(location) = location {
+			let response = response.status(302).header("Location", location);
+			return Box::pin(async move { Ok(response.body(Body::Empty).unwrap()) });
+		}
+
+		if request.method() == Method::OPTIONS {
+			self.0.fetch_add(1, Ordering::SeqCst);
+			assert_eq!(request.headers()["Origin"], ORIGIN);
+			response = response
+				.status(204)
+				.header("Access-Control-Allow-Methods", "PUT")
+				.header("Access-Control-Allow-Headers", "X-Custom")
+				.header("Access-Control-Max-Age", "60");
+		} else {
+			response = response
+				.status(200)
+				.header("Content-Type", "text/plain")
+				.header("X-Secret", "secret")
+				.header("X-Exposed", "exposed")
+				.header("Access-Control-Expose-Headers", "X-Exposed");
+		}
+		Box::pin(async move { Ok(response.body(Body::Empty).unwrap()) })
+	}
+}
+
+#[tokio::test]
+async fn fetch_cors() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let preflights = Arc::new(AtomicUsize::new(0));
+	let rt = RuntimeBuilder::<()>::new()
+		.microtask_queue()
+		.macrotask_queue()
+		.fetch_client(CorsClient(Arc::clone(&preflights)))
+		.fetch_origin(&Url::parse(ORIGIN).unwrap())
+		.build(cx);
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+		})
+		.await;
+
+	assert_eq!(preflights.load(Ordering::SeqCst), 2, "Preflight should be cached for PUT");
+}
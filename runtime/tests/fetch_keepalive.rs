@@ -0,0 +1,71 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#![cfg(feature = "fetch")]
+
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use ion::script::Script;
+use ion::Context;
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::globals::fetch::{Body, ClientFuture, FetchClient};
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+use tokio::time::sleep;
+
+const FILE_NAME: &str = "fetch-keepalive.js";
+const SCRIPT: &str = include_str!("scripts/fetch-keepalive.js");
+
+/// Transport which responds to every request after a delay, counting the requests it has sent.
+struct DelayedClient(Arc<AtomicUsize>);
+
+impl FetchClient for DelayedClient {
+	fn send(&self, _: hyper::Request<Body>) -> ClientFuture {
+		let sent = Arc::clone(&self.0);
+		Box::pin(async move {
+			sleep(Duration::from_millis(50)).await;
+			sent.fetch_add(1, Ordering::SeqCst);
+			Ok(hyper::Response::builder().status(204).body(Body::Empty).unwrap())
+		})
+	}
+}
+
+#[tokio::test]
+async fn fetch_keepalive() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let sent = Arc::new(AtomicUsize::new(0));
+	let rt = RuntimeBuilder::<()>::new()
+		.microtask_queue()
+		.macrotask_queue()
+		.fetch_client(DelayedClient(Arc::clone(&sent)))
+		.build(cx);
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let usage = rt.resource_usage();
+			assert_eq!(usage.keepalive_requests, 1, "Request over the body limit should not be in flight");
+			assert_eq!(usage.keepalive_bytes, 1024);
+
+			assert!(rt.shutdown(Duration::from_secs(5)).await);
+		})
+		.await;
+
+	assert_eq!(sent.load(Ordering::SeqCst), 1);
+	assert_eq!(rt.resource_usage().keepalive_requests, 0);
+}
@@ -0,0 +1,82 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#![cfg(all(unix, feature = "fetch"))]
+
+use std::env::temp_dir;
+use std::fs::remove_file;
+use std::path::Path;
+use std::process;
+
+use ion::script::Script;
+use ion::Context;
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::RuntimeBuilder;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixListener;
+use tokio::task::{spawn_local, LocalSet};
+
+const FILE_NAME: &str = "fetch-retry.js";
+const SCRIPT: &str = include_str!("scripts/fetch-retry.js");
+
+/// Responds with `503 Service Unavailable` until the third attempt.
+async fn serve(listener: UnixListener) {
+	for attempt in 1..=3 {
+		let (mut stream, _) = listener.accept().await.unwrap();
+
+		let mut request = Vec::new();
+		let mut buffer = [0; 1024];
+		while !request.ends_with(b"\r\n\r\n") {
+			let read = stream.read(&mut buffer).await.unwrap();
+			assert_ne!(read, 0, "Connection closed before the request was received");
+			request.extend_from_slice(&buffer[..read]);
+		}
+
+		let response = if attempt < 3 {
+			String::from("HTTP/1.1 503 Service Unavailable\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+		} else {
+			let body = format!("attempt {attempt}");
+			format!(
+				"HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+				body.len()
+			)
+		};
+		stream.write_all(response.as_bytes()).await.unwrap();
+	}
+}
+
+#[tokio::test]
+async fn fetch_retry() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let path = temp_dir().join(format!("spiderfire-fetch-retry-{}.sock", process::id()));
+	let _ = remove_file(&path);
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().microtask_queue().macrotask_queue().build(cx);
+	rt.global().set_as(rt.cx(), "SOCKET_PATH", &path.to_string_lossy().into_owned());
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let server = spawn_local(serve(UnixListener::bind(&path).unwrap()));
+
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			server.await.unwrap();
+		})
+		.await;
+
+	let _ = remove_file(&path);
+}
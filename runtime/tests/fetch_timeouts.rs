@@ -0,0 +1,66 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#![cfg(feature = "fetch")]
+
+use std::future::pending;
+use std::path::Path;
+use std::time::Duration;
+
+use ion::script::Script;
+use ion::Context;
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::globals::fetch::{Body, ClientFuture, FetchClient, FetchTimeouts};
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+
+const FILE_NAME: &str = "fetch-timeouts.js";
+const SCRIPT: &str = include_str!("scripts/fetch-timeouts.js");
+
+/// Transport which never responds to requests for `/stalled`, and responds to other requests immediately.
+struct StalledClient;
+
+impl FetchClient for StalledClient {
+	fn send(&self, request: hyper::Request<Body>) -> ClientFuture {
+		if request.uri().path() == "/stalled" {
+			Box::pin(pending())
+		} else {
+			Box::pin(async { Ok(hyper::Response::new(Body::Empty)) })
+		}
+	}
+}
+
+#[tokio::test]
+async fn fetch_timeouts() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let timeouts = FetchTimeouts {
+		response_headers: Some(Duration::from_millis(20)),
+		..FetchTimeouts::default()
+	};
+	let rt = RuntimeBuilder::<()>::new()
+		.microtask_queue()
+		.macrotask_queue()
+		.fetch_client(StalledClient)
+		.fetch_timeouts(timeouts)
+		.build(cx);
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+		})
+		.await;
+}
@@ -0,0 +1,85 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#![cfg(feature = "fetch")]
+
+use std::path::Path;
+
+use ion::conversions::FromValue;
+use ion::flags::PropertyFlags;
+use ion::script::Script;
+use ion::Context;
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::RuntimeBuilder;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::task::LocalSet;
+
+const FILE_NAME: &str = "fetch-timing.js";
+const SCRIPT: &str = r#"
+globalThis.connects = [];
+(async () => {
+	const responses = await Promise.all([fetch(url), fetch(url)]);
+	await Promise.all(responses.map(response => response.text()));
+	const reused = await fetch(url);
+	await reused.text();
+	responses.push(reused);
+	connects = responses.map(response => response.timing.connect > 0);
+})();
+"#;
+
+/// Serves empty responses over persistent connections.
+async fn serve(listener: TcpListener) {
+	loop {
+		let (stream, _) = listener.accept().await.unwrap();
+		tokio::spawn(async move {
+			let mut stream = BufReader::new(stream);
+			let mut line = String::new();
+			while stream.read_line(&mut line).await.is_ok_and(|read| read > 0) {
+				if line == "\r\n" {
+					let response = b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n";
+					if stream.get_mut().write_all(response).await.is_err() {
+						break;
+					}
+				}
+				line.clear();
+			}
+		});
+	}
+}
+
+#[tokio::test]
+async fn fetch_timing() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let url = format!("http://{}/", listener.local_addr().unwrap());
+	tokio::spawn(serve(listener));
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().microtask_queue().macrotask_queue().build(cx);
+	let cx = rt.cx();
+	rt.global().define_as(cx, "url", &url, PropertyFlags::all());
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(cx, Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			// Each new connection reports its own timing, and a reused connection does not report it again.
+			let connects = Script::compile_and_evaluate(cx, Path::new(FILE_NAME), "connects.join()").unwrap();
+			assert_eq!(String::from_value(cx, &connects, true, ()).unwrap(), "true,true,false");
+		})
+		.await;
+}
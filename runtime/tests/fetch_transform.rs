@@ -0,0 +1,70 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#![cfg(feature = "fetch")]
+
+use std::convert::Infallible;
+use std::path::Path;
+
+use bytes::Bytes;
+use futures::stream;
+use http_body_util::{BodyExt, StreamBody};
+use hyper::body::Frame;
+use ion::conversions::FromValue;
+use ion::script::Script;
+use ion::Context;
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::globals::fetch::{Body, ClientFuture, FetchClient};
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+
+const FILE_NAME: &str = "fetch-transform.js";
+const SCRIPT: &str = include_str!("scripts/fetch-transform.js");
+
+/// Transport which responds to every request with a body of several chunks, without using the network.
+struct ChunkClient;
+
+impl FetchClient for ChunkClient {
+	fn send(&self, _: hyper::Request<Body>) -> ClientFuture {
+		Box::pin(async {
+			let chunks = ["ab", "cd", "ef"].map(|chunk| Ok::<_, Infallible>(Frame::data(Bytes::from(chunk))));
+			let body = StreamBody::new(stream::iter(chunks)).map_err(|never| match never {}).boxed();
+			Ok(hyper::Response::new(Body::Stream(body)))
+		})
+	}
+}
+
+#[tokio::test]
+async fn fetch_transform() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new()
+		.microtask_queue()
+		.macrotask_queue()
+		.fetch_client(ChunkClient)
+		.build(cx);
+	let cx = rt.cx();
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(cx, Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			// Every chunk reaches the transform, rather than the pipe waiting for a pull which never happens.
+			let chunks = Script::compile_and_evaluate(cx, Path::new(FILE_NAME), "chunks.join()").unwrap();
+			assert_eq!(String::from_value(cx, &chunks, true, ()).unwrap(), "AB,CD,EF");
+		})
+		.await;
+}
@@ -0,0 +1,94 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#![cfg(all(unix, feature = "fetch"))]
+
+use std::env::temp_dir;
+use std::fs::remove_file;
+use std::path::Path;
+use std::process;
+
+use ion::script::Script;
+use ion::Context;
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::RuntimeBuilder;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixListener;
+use tokio::task::{spawn_local, LocalSet};
+
+const FILE_NAME: &str = "fetch-unix.js";
+const SCRIPT: &str = include_str!("scripts/fetch-unix.js");
+
+async fn serve(listener: UnixListener) {
+	let (mut stream, _) = listener.accept().await.unwrap();
+
+	let mut request = Vec::new();
+	let mut buffer = [0; 1024];
+	let end = loop {
+		if let Some(end) = request.windows(4).position(|window| window == b"\r\n\r\n") {
+			break end + 4;
+		}
+		let read = stream.read(&mut buffer).await.unwrap();
+		assert_ne!(read, 0, "Connection closed before the request was received");
+		request.extend_from_slice(&buffer[..read]);
+	};
+
+	let head = String::from_utf8(request[..end].to_vec()).unwrap();
+	let length: usize = head
+		.lines()
+		.find_map(|line| line.to_ascii_lowercase().strip_prefix("content-length: ")?.parse().ok())
+		.unwrap_or(0);
+	let mut body = request[end..].to_vec();
+	while body.len() < length {
+		let read = stream.read(&mut buffer).await.unwrap();
+		assert_ne!(read, 0, "Connection closed before the body was received");
+		body.extend_from_slice(&buffer[..read]);
+	}
+
+	let line = head.split(" HTTP/1.1").next().unwrap();
+	let content = format!("{line} {}", body.len());
+	let hints = "HTTP/1.1 103 Early Hints\r\nLink: </style.css>; rel=preload\r\n\r\n";
+	stream.write_all(hints.as_bytes()).await.unwrap();
+
+	let response = format!(
+		"HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{content}",
+		content.len()
+	);
+	stream.write_all(response.as_bytes()).await.unwrap();
+}
+
+#[tokio::test]
+async fn fetch_unix_socket() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let path = temp_dir().join(format!("spiderfire-fetch-{}.sock", process::id()));
+	let _ = remove_file(&path);
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().microtask_queue().macrotask_queue().build(cx);
+	rt.global().set_as(rt.cx(), "SOCKET_PATH", &path.to_string_lossy().into_owned());
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let server = spawn_local(serve(UnixListener::bind(&path).unwrap()));
+
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			server.await.unwrap();
+		})
+		.await;
+
+	let _ = remove_file(&path);
+}
@@ -0,0 +1,44 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use ion::conversions::FromValue;
+use ion::script::Script;
+use ion::Context;
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, Globals, LogLevel, CONFIG};
+use runtime::RuntimeBuilder;
+
+const SCRIPT: &str = r#"
+[
+	typeof console,
+	typeof fetch,
+	typeof performance,
+	typeof setTimeout,
+	typeof URL,
+].join(",")
+"#;
+
+#[test]
+fn disabled_globals() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new()
+		.microtask_queue()
+		.macrotask_queue()
+		.globals(Globals::none().console(true))
+		.build(cx);
+
+	let result = Script::compile_and_evaluate(rt.cx(), Path::new("globals.js"), SCRIPT);
+	let types = result.unwrap_or_else(|report| panic!("Error: {report:?}"));
+	let types = String::from_value(rt.cx(), &types, true, ()).unwrap();
+	assert_eq!(types, "object,undefined,undefined,undefined,function");
+}
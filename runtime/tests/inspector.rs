@@ -0,0 +1,84 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use futures::channel::mpsc::unbounded;
+use ion::module::Module;
+use ion::Context;
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::inspector::InspectorEvent;
+use runtime::module::Loader;
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+
+const FILE_NAME: &str = "inspector.js";
+const SCRIPT: &str = include_str!("scripts/inspector.js");
+const THROWING: &str = r#"import "./module-throw.js";"#;
+
+#[tokio::test]
+async fn inspector() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let (sender, mut receiver) = unbounded();
+	let rt = RuntimeBuilder::<_, ()>::new()
+		.microtask_queue()
+		.macrotask_queue()
+		.modules(Loader::default())
+		.inspector(sender)
+		.coverage()
+		.build(cx);
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let path = format!("./tests/scripts/{FILE_NAME}");
+			let result = Module::compile_and_evaluate(rt.cx(), FILE_NAME, Some(Path::new(&path)), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+			assert!(rt.run_event_loop().await.is_err());
+
+			let path = Path::new("./tests/scripts/inspector-throw.js");
+			let result = Module::compile_and_evaluate(rt.cx(), "inspector-throw.js", Some(path), THROWING);
+			assert!(result.is_err());
+		})
+		.await;
+
+	let coverage = rt.coverage().unwrap();
+	let script = coverage.iter().find(|script| script.url.ends_with(FILE_NAME)).unwrap();
+	assert!(script.lines.iter().any(|line| line.line == 3 && line.count == 1));
+	assert!(coverage.iter().any(|script| script.url.ends_with("module-export.js")));
+
+	let mut events = Vec::new();
+	while let Ok(Some(event)) = receiver.try_next() {
+		events.push(event);
+	}
+
+	assert!(events.iter().any(|event| matches!(
+		event,
+		InspectorEvent::ScriptParsed { path } if path.ends_with("module-export.js")
+	)));
+	assert!(events.iter().any(|event| matches!(
+		event,
+		InspectorEvent::ScriptParsed { path } if path.ends_with("module-throw.js")
+	)));
+	assert!(events.iter().any(|event| matches!(
+		event,
+		InspectorEvent::Console { level: LogLevel::Info, message } if message == "Inspected SpiderMonkey"
+	)));
+	assert!(events.iter().any(|event| matches!(
+		event,
+		InspectorEvent::UnhandledRejection { reason } if reason.contains("Rejected")
+	)));
+	assert!(events.iter().any(|event| matches!(
+		event,
+		InspectorEvent::UncaughtError { message } if message.contains("Uncaught")
+	)));
+}
@@ -0,0 +1,42 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::env::temp_dir;
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::process;
+
+use runtime::module::{LockError, Lockfile};
+
+#[test]
+fn lockfile() {
+	let root = temp_dir().join(format!("spiderfire-lockfile-{}", process::id()));
+	let package = root.join("node_modules/@spiderfire/package");
+	create_dir_all(&package).unwrap();
+	write(package.join("package.json"), r#"{"name": "@spiderfire/package", "version": "1.0.0"}"#).unwrap();
+	write(package.join("index.js"), "export default 1;").unwrap();
+	write(root.join("main.js"), "import value from \"@spiderfire/package\";").unwrap();
+
+	let lockfile = Lockfile::generate(&root).unwrap();
+	let locked = &lockfile.packages()["node_modules/@spiderfire/package"];
+	assert_eq!(locked.name, "@spiderfire/package");
+	assert_eq!(locked.version.as_deref(), Some("1.0.0"));
+	lockfile.write().unwrap();
+
+	let mut lockfile = Lockfile::read(&root).unwrap().unwrap();
+	assert!(lockfile.verify(&root.join("main.js")).is_ok());
+	assert!(lockfile.verify(&package.join("index.js")).is_ok());
+
+	write(package.join("index.js"), "export default 2;").unwrap();
+	let mut lockfile = Lockfile::read(&root).unwrap().unwrap();
+	assert!(matches!(lockfile.verify(&package.join("index.js")), Err(LockError::Integrity(_))));
+
+	let unlocked = root.join("node_modules/unlocked");
+	create_dir_all(&unlocked).unwrap();
+	write(unlocked.join("index.js"), "export default 3;").unwrap();
+	assert!(matches!(lockfile.verify(&unlocked.join("index.js")), Err(LockError::Missing(_))));
+
+	remove_dir_all(&root).unwrap();
+}
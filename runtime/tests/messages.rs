@@ -0,0 +1,58 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+use std::thread;
+
+use ion::script::Script;
+use ion::{Context, Function};
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::event_loop::Message;
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+
+const FILE_NAME: &str = "messages.js";
+const SCRIPT: &str = include_str!("scripts/messages.js");
+
+#[tokio::test]
+async fn event_loop_sender() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().microtask_queue().macrotask_queue().build(cx);
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let callback = Function::from_object(rt.cx(), &result.unwrap().to_object(rt.cx())).unwrap();
+			let sender = rt.event_loop_sender(&callback);
+
+			let value = Script::compile_and_evaluate(rt.cx(), Path::new("value.js"), "({answer: 42, list: [1, 2]})");
+			let message = Message::serialize(rt.cx(), &value.unwrap());
+			assert!(message.is_ok(), "Failed to serialize message");
+			let message = message.unwrap();
+
+			let handle = thread::spawn(move || {
+				assert!(sender.send(message).is_ok());
+				assert!(sender.send(Message::string(String::from("message"))).is_ok());
+			});
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+			handle.join().unwrap();
+
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new("check.js"), "check()");
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+		})
+		.await;
+}
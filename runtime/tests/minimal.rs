@@ -0,0 +1,102 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+#[cfg(feature = "tokio-promise")]
+use std::cell::Cell;
+use std::path::Path;
+#[cfg(feature = "tokio-promise")]
+use std::rc::Rc;
+#[cfg(feature = "tokio-promise")]
+use std::time::Instant;
+
+#[cfg(feature = "tokio-promise")]
+use futures::future::LocalBoxFuture;
+use ion::module::Module;
+use ion::Context;
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::{JSEngine, Runtime as RustRuntime};
+use runtime::config::{Config, LogLevel, CONFIG};
+#[cfg(feature = "tokio-promise")]
+use runtime::event_loop::{Spawner, TokioSpawner};
+use runtime::module::MemoryLoader;
+use runtime::{Runtime, RuntimeBuilder};
+use tokio::task::LocalSet;
+
+const GREET: &str = "export const greet = name => `Hello, ${name}!`;";
+
+const MAIN: &str = r#"
+import { greet } from "./lib/greet.js";
+
+if (typeof fetch !== "undefined") {
+	throw new Error("fetch is defined");
+}
+
+setTimeout(() => {
+	if (greet("minimal") !== "Hello, minimal!") {
+		throw new Error("Incorrect Greeting");
+	}
+}, 10);
+"#;
+
+/// Waits for timers with the [TokioSpawner], while counting them.
+#[cfg(feature = "tokio-promise")]
+struct CountingSpawner(Rc<Cell<u32>>);
+
+#[cfg(feature = "tokio-promise")]
+impl Spawner for CountingSpawner {
+	fn sleep_until(&self, deadline: Instant) -> LocalBoxFuture<'static, ()> {
+		self.0.set(self.0.get() + 1);
+		TokioSpawner.sleep_until(deadline)
+	}
+}
+
+#[cfg(feature = "tokio-promise")]
+#[tokio::test]
+async fn minimal() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = RustRuntime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let timers = Rc::new(Cell::new(0));
+	let loader = MemoryLoader::new().module("lib/greet.js", GREET);
+	let rt = RuntimeBuilder::<_, ()>::minimal(loader)
+		.spawner(CountingSpawner(Rc::clone(&timers)))
+		.build(cx);
+
+	run(&rt).await;
+	assert!(timers.get() > 0, "Timer was not registered with the spawner");
+}
+
+/// Without the `tokio-promise` feature, the runtime has no spawner, so timers are waited for on a separate thread.
+#[cfg(not(feature = "tokio-promise"))]
+#[tokio::test]
+async fn minimal_without_spawner() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = RustRuntime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let loader = MemoryLoader::new().module("lib/greet.js", GREET);
+	let rt = RuntimeBuilder::<_, ()>::minimal(loader).build(cx);
+
+	run(&rt).await;
+}
+
+async fn run(rt: &Runtime<'_>) {
+	let result = Module::compile_and_evaluate(rt.cx(), "main.js", Some(Path::new("main.js")), MAIN);
+	let (_, promise) = result.unwrap_or_else(|error| panic!("Error: {error:?}"));
+
+	let local = LocalSet::new();
+	local.run_until(rt.run_event_loop()).await.unwrap();
+
+	assert!(
+		promise.is_some_and(|promise| promise.state() == PromiseState::Fulfilled),
+		"Module evaluation failed"
+	);
+}
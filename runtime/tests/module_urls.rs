@@ -0,0 +1,56 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use ion::conversions::FromValue;
+use ion::module::Module;
+use ion::script::Script;
+use ion::Context;
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::module::Loader;
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "module-urls.js";
+const SCRIPT: &str = include_str!("scripts/module-urls.js");
+
+const BLOB: &str = r#"
+URL.createObjectURL(new Blob(["export const greet = name => `Hello, ${name}!`;"], { type: "text/javascript" }))
+"#;
+
+#[tokio::test]
+async fn module_urls() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<_, ()>::new().microtask_queue().modules(Loader::default()).build(cx);
+
+	let url = Script::compile_and_evaluate(rt.cx(), Path::new("blob.js"), BLOB).unwrap();
+	let url = String::from_value(rt.cx(), &url, true, ()).unwrap();
+	let script = SCRIPT.replace("BLOB_URL", &url);
+
+	let result = Module::compile_and_evaluate(rt.cx(), FILE_NAME, None, &script);
+	assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+	let (_, promise) = result.unwrap();
+	assert!(rt.run_event_loop().await.is_ok());
+	assert!(
+		promise.is_some_and(|promise| promise.state() == PromiseState::Fulfilled),
+		"Module evaluation failed"
+	);
+
+	let revoke = format!(r#"URL.revokeObjectURL("{url}")"#);
+	Script::compile_and_evaluate(rt.cx(), Path::new("revoke.js"), &revoke).unwrap();
+
+	let script = format!(r#"import {{ greet }} from "{url}"; greet("again");"#);
+	let result = Module::compile_and_evaluate(rt.cx(), "module-urls-cached.js", None, &script);
+	assert!(result.is_ok(), "Revoked blob URL was not cached: {:?}", result.unwrap_err());
+}
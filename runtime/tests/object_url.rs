@@ -0,0 +1,75 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use ion::conversions::FromValue;
+use ion::script::Script;
+use ion::Context;
+use mozjs::rust::{JSEngine, Runtime as RustRuntime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::{Runtime, RuntimeBuilder};
+#[cfg(feature = "fetch")]
+use tokio::task::LocalSet;
+
+const FILE_NAME: &str = "object-url.js";
+
+#[cfg(feature = "fetch")]
+const FETCH: &str = r#"
+globalThis.fetched = null;
+Promise.all([url, `${url}#fragment`, `${url}-suffix`].map(url => fetch(url).catch(() => null))).then(
+	responses => Promise.all(responses.map(response => response?.text() ?? null)),
+).then(texts => globalThis.fetched = texts.join());
+"#;
+
+#[tokio::test]
+async fn object_url() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = RustRuntime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().microtask_queue().macrotask_queue().build(cx);
+
+	evaluate(
+		&rt,
+		r#"globalThis.url = URL.createObjectURL(new Blob(["spiderfire"]));"#,
+	);
+	let metrics = rt.blob_store_metrics();
+	assert_eq!(metrics.entries, 1);
+	assert_eq!(metrics.bytes, 10);
+
+	#[cfg(feature = "fetch")]
+	{
+		run(&rt, FETCH).await;
+		assert_eq!(evaluate(&rt, "globalThis.fetched"), "spiderfire,spiderfire,");
+	}
+
+	evaluate(&rt, "URL.revokeObjectURL(`${globalThis.url}#fragment`);");
+	let metrics = rt.blob_store_metrics();
+	assert_eq!(metrics.entries, 0);
+	assert_eq!(metrics.bytes, 0);
+
+	#[cfg(feature = "fetch")]
+	{
+		run(&rt, FETCH).await;
+		assert_eq!(evaluate(&rt, "globalThis.fetched"), ",,");
+	}
+}
+
+fn evaluate(rt: &Runtime<'_>, source: &str) -> String {
+	let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), source);
+	let value = result.unwrap_or_else(|error| panic!("Error: {error:?}"));
+	String::from_value(rt.cx(), &value, false, ()).unwrap()
+}
+
+#[cfg(feature = "fetch")]
+async fn run(rt: &Runtime<'_>, source: &str) {
+	evaluate(rt, source);
+	let local = LocalSet::new();
+	local.run_until(rt.run_event_loop()).await.unwrap();
+}
@@ -0,0 +1,75 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+use std::sync::mpsc::channel;
+use std::thread::sleep;
+use std::time::Duration;
+
+use ion::conversions::FromValue;
+use ion::flags::PropertyFlags;
+use ion::script::Script;
+use ion::{Context, Error};
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::promise::{offload, run_blocking};
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+use tokio::time::timeout;
+
+const FILE_NAME: &str = "offload.js";
+const SCRIPT: &str = r#"
+globalThis.order = [];
+setTimeout(() => order.push("timeout"), 0);
+offloaded.then(value => order.push(value));
+"#;
+
+#[tokio::test]
+async fn offload_does_not_block() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().microtask_queue().macrotask_queue().build(cx);
+	let cx = rt.cx();
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let promise = offload(cx, |_| {
+				sleep(Duration::from_millis(200));
+				Ok::<_, Error>(42)
+			})
+			.unwrap();
+			rt.global().define_as(cx, "offloaded", &promise, PropertyFlags::all());
+
+			let result = Script::compile_and_evaluate(cx, Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			// The timer fires while the task is still running on the blocking thread pool.
+			let order = Script::compile_and_evaluate(cx, Path::new(FILE_NAME), "order.join()").unwrap();
+			assert_eq!(String::from_value(cx, &order, true, ()).unwrap(), "timeout,42");
+		})
+		.await;
+
+	let (sender, receiver) = channel();
+	let task = run_blocking(move |cancellation| {
+		while !cancellation.is_cancelled() {
+			sleep(Duration::from_millis(10));
+		}
+		sender.send(()).unwrap();
+	});
+	assert!(timeout(Duration::from_millis(50), task).await.is_err());
+	assert!(
+		receiver.recv_timeout(Duration::from_secs(5)).is_ok(),
+		"Task was not cancelled"
+	);
+}
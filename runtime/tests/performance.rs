@@ -0,0 +1,40 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+use std::time::Duration;
+
+use ion::script::Script;
+use ion::Context;
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "performance.js";
+const SCRIPT: &str = include_str!("scripts/performance.js");
+
+#[tokio::test]
+async fn event_loop_utilization() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().microtask_queue().macrotask_queue().build(cx);
+
+	let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+	assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+	let result = rt.run_event_loop().await;
+	assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+	let metrics = rt.event_loop_metrics();
+	assert!(metrics.active >= Duration::from_millis(20));
+	assert!(metrics.max_tick >= Duration::from_millis(20));
+	assert!(metrics.ticks > 0);
+	assert!(metrics.utilization() > 0.0 && metrics.utilization() < 1.0);
+}
@@ -0,0 +1,95 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::future::ready;
+use std::path::Path;
+
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use ion::flags::PropertyFlags;
+use ion::script::Script;
+use ion::{Context, Error, Object, Result, Value};
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::globals::streams::readable::{BytesStream, ReadableStream};
+use runtime::globals::streams::writable::{NativeSink, SinkFuture, WritableStream};
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+
+const FILE_NAME: &str = "pipe.js";
+const SCRIPT: &str = include_str!("scripts/pipe.js");
+
+thread_local! {
+	static WRITTEN: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+	static STREAMED: Cell<bool> = const { Cell::new(false) };
+	static CLOSED: Cell<bool> = const { Cell::new(false) };
+}
+
+#[tokio::test]
+async fn pipe_to() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().microtask_queue().macrotask_queue().build(cx);
+
+	let global = rt.global();
+	global.define_method(rt.cx(), "nativeStream", native_stream, 0, PropertyFlags::all());
+	global.define_method(rt.cx(), "nativeSink", native_sink_stream, 0, PropertyFlags::all());
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+		})
+		.await;
+
+	WRITTEN.with_borrow(|written| assert_eq!(written, &[1, 2, 3, 4, 5]));
+	assert!(STREAMED.get());
+	assert!(CLOSED.get());
+}
+
+struct Sink;
+
+impl NativeSink for Sink {
+	fn write(&mut self, _: &Context, _: &Value) -> Result<SinkFuture> {
+		Err(Error::new("Chunks should be written as a stream.", None))
+	}
+
+	fn close(&mut self, _: &Context) -> SinkFuture {
+		CLOSED.set(true);
+		Box::pin(ready(Ok(())))
+	}
+
+	fn write_stream(&mut self, mut stream: BytesStream) -> std::result::Result<SinkFuture, BytesStream> {
+		STREAMED.set(true);
+		Ok(Box::pin(async move {
+			while let Some(bytes) = stream.next().await {
+				let bytes = bytes?;
+				WRITTEN.with_borrow_mut(|written| written.extend_from_slice(&bytes));
+			}
+			Ok(())
+		}))
+	}
+}
+
+#[ion::js_fn]
+fn native_stream(cx: &Context) -> Object {
+	let chunks = [Ok(Bytes::from_static(&[1, 2])), Ok(Bytes::from_static(&[3, 4, 5]))];
+	ReadableStream::from_bytes_stream(cx, stream::iter(chunks))
+}
+
+#[ion::js_fn]
+fn native_sink_stream(cx: &Context) -> Object {
+	WritableStream::from_native_sink(cx, Sink)
+}
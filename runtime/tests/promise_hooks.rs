@@ -0,0 +1,79 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use ion::conversions::FromValue;
+use ion::script::Script;
+use ion::{Context, ErrorReport};
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::event_loop::UncaughtErrorAction;
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+
+const FILE_NAME: &str = "promise-hooks.js";
+const SCRIPT: &str = r#"
+globalThis.events = [];
+setPromiseHooks({
+	before: () => events.push("before"),
+	after: () => {
+		events.push("after");
+		throw new Error("Hook");
+	},
+});
+Promise.resolve().then(() => {
+	events.push("reaction");
+	setPromiseHooks();
+	Promise.resolve().then(() => events.push("unhooked"));
+});
+"#;
+
+#[tokio::test]
+async fn promise_hooks() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let errors = Rc::new(RefCell::new(Vec::new()));
+	let handled = Rc::clone(&errors);
+	let rt = RuntimeBuilder::<()>::new()
+		.microtask_queue()
+		.macrotask_queue()
+		.uncaught_error_handler(move |cx: &Context, report: &ErrorReport| {
+			handled.borrow_mut().push(report.format(cx));
+			UncaughtErrorAction::Continue
+		})
+		.build(cx);
+	let cx = rt.cx();
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(cx, Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			// Hooks set by scripts wrap each reaction job until they are removed.
+			let events = Script::compile_and_evaluate(cx, Path::new(FILE_NAME), "events.join()").unwrap();
+			assert_eq!(
+				String::from_value(cx, &events, true, ()).unwrap(),
+				"before,reaction,after,unhooked"
+			);
+		})
+		.await;
+
+	// An error thrown by a hook is passed to the uncaught error handlers.
+	let errors = errors.borrow();
+	assert_eq!(errors.len(), 1);
+	assert!(errors[0].contains("Hook"));
+}
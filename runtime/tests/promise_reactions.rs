@@ -0,0 +1,80 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::Cell;
+use std::path::Path;
+use std::rc::Rc;
+
+use bytes::Bytes;
+use futures::stream;
+use ion::flags::PropertyFlags;
+use ion::script::Script;
+use ion::{Context, Promise, Value};
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::globals::streams::readable::ReadableStream;
+use runtime::promise::add_reactions_with_checkpoint;
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+
+const FILE_NAME: &str = "promise-reactions.js";
+const SCRIPT: &str = r#"
+const reader = stream.getReader();
+reader.read().then(({ value }) => {
+	if (value.join(",") !== "1,2,3") {
+		throw new Error(`Incorrect Bytes: ${value.join(",")}`);
+	}
+});
+"#;
+
+#[tokio::test]
+async fn reactions_with_checkpoint() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().microtask_queue().macrotask_queue().build(cx);
+	let cx = rt.cx();
+
+	let pending = Promise::new(cx);
+	assert!(add_reactions_with_checkpoint(cx, &pending, |_, v| Ok(v.clone()), |_, v| Ok(v.clone())).is_none());
+
+	let resolved = Rc::new(Cell::new(false));
+	let promise = Promise::resolved(cx, &Value::i32(cx, 1));
+	let checkpoint = add_reactions_with_checkpoint(
+		cx,
+		&promise,
+		{
+			let resolved = Rc::clone(&resolved);
+			move |_, value| {
+				resolved.set(true);
+				Ok(value.clone())
+			}
+		},
+		|_, reason| Ok(reason.clone()),
+	);
+	assert!(checkpoint.is_some());
+	assert!(!resolved.get());
+	drop(checkpoint);
+	assert!(resolved.get());
+
+	let chunks = [Ok(Bytes::from_static(&[1, 2, 3]))];
+	let stream = ReadableStream::from_bytes_stream(cx, stream::iter(chunks));
+	rt.global().define_as(cx, "stream", &stream, PropertyFlags::all());
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+		})
+		.await;
+}
@@ -0,0 +1,56 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use ion::script::Script;
+use ion::{default_new_global, Context};
+use mozjs::jsapi::{GetCurrentRealmOrNull, JSAutoRealm};
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, Globals, LogLevel, CONFIG};
+use runtime::globals::init_globals;
+use runtime::RuntimeBuilder;
+
+const SCRIPT: &str = r#"
+const url = new URL("https://example.com/path?query");
+if (url.pathname !== "/path" || url.searchParams.get("query") !== "") {
+	throw new Error(`Invalid URL: ${url.href}`);
+}
+if (new TextEncoder().encode("realm").length !== 5) {
+	throw new Error("Invalid TextEncoder");
+}
+"#;
+
+#[test]
+fn multiple_realms() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().build(cx);
+
+	let result = Script::compile_and_evaluate(rt.cx(), Path::new("first.js"), SCRIPT);
+	assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+	let global = default_new_global(rt.cx());
+	assert!(init_globals(rt.cx(), &global, Globals::default()), "Failed to initialise globals in second realm");
+
+	{
+		let _realm = JSAutoRealm::new(rt.cx().as_ptr(), global.handle().get());
+		let result = Script::compile_and_evaluate(rt.cx(), Path::new("second.js"), SCRIPT);
+		assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+		unsafe {
+			let realm = GetCurrentRealmOrNull(rt.cx().as_ptr());
+			rt.cx().remove_class_infos(realm);
+		}
+	}
+
+	let result = Script::compile_and_evaluate(rt.cx(), Path::new("third.js"), SCRIPT);
+	assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+}
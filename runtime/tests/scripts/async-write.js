@@ -0,0 +1,33 @@
+const writer = asyncWriter().getWriter();
+if (writer.desiredSize !== 4) {
+	throw new Error(`Expected desiredSize of 4, found ${writer.desiredSize}`);
+}
+
+writer.write(new Uint8Array([1, 2, 3]));
+if (writer.desiredSize !== 1) {
+	throw new Error(`Expected desiredSize of 1, found ${writer.desiredSize}`);
+}
+
+writer.write("ab");
+if (writer.desiredSize !== -1) {
+	throw new Error(`Expected desiredSize of -1, found ${writer.desiredSize}`);
+}
+
+let ready = false;
+writer.ready.then(() => {
+	ready = true;
+});
+
+let closed = false;
+writer.close().then(() => {
+	closed = true;
+});
+
+setTimeout(() => {
+	if (!ready || !closed) {
+		throw new Error("Writer was not ready and closed after its chunks were written");
+	}
+	if (writer.desiredSize !== 0) {
+		throw new Error(`Expected desiredSize of 0 after closing, found ${writer.desiredSize}`);
+	}
+}, 50);
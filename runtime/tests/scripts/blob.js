@@ -0,0 +1,53 @@
+const text = "spiderfire".repeat(10000);
+const blob = new Blob([text]);
+const results = {};
+
+blob.text().then(value => {
+	results.text = value === text;
+});
+
+blob.slice(5, 15).text().then(value => {
+	results.slice = value;
+});
+
+const combined = new Blob(["<", blob, ">"]);
+
+Promise.all([combined.slice(0, 11).text(), combined.slice(-11).text()]).then(values => {
+	results.combined = values.join("");
+});
+
+(async () => {
+	const reader = blob.stream().getReader();
+	let length = 0;
+	let chunks = 0;
+	while (true) {
+		const { done, value } = await reader.read();
+		if (done) {
+			break;
+		}
+		length += value.byteLength;
+		chunks++;
+	}
+	results.stream = [length, chunks];
+})();
+
+setTimeout(() => {
+	if (blob.size !== text.length) {
+		throw new Error(`Incorrect Size: ${blob.size}`);
+	}
+	if (!results.text) {
+		throw new Error("Incorrect Text");
+	}
+	if (results.slice !== "firespider") {
+		throw new Error(`Incorrect Slice: ${results.slice}`);
+	}
+	if (combined.size !== text.length + 2) {
+		throw new Error(`Incorrect Combined Size: ${combined.size}`);
+	}
+	if (results.combined !== "<spiderfirespiderfire>") {
+		throw new Error(`Incorrect Combined Slices: ${results.combined}`);
+	}
+	if (results.stream?.[0] !== text.length || results.stream?.[1] !== 2) {
+		throw new Error(`Incorrect Stream: ${results.stream}`);
+	}
+}, 100);
@@ -0,0 +1,83 @@
+let next = 0;
+const stream = new ReadableStream({
+	type: "bytes",
+	pull(controller) {
+		controller.enqueue(new Uint8Array([next, next + 1]));
+		next += 2;
+		if (next >= 10) {
+			controller.close();
+		}
+	},
+});
+
+const reader = stream.getReader({ mode: "byob" });
+const results = [];
+
+reader.read(new Uint8Array(6), { min: 6 })
+	.then(({ value }) => {
+		results.push(value.join(","));
+		return reader.read(new Uint16Array(2), { min: 2 });
+	})
+	.then(({ value }) => {
+		results.push(value.byteLength);
+	});
+
+let closing = 0;
+const closingStream = new ReadableStream({
+	type: "bytes",
+	pull(controller) {
+		if (closing++ === 0) {
+			controller.enqueue(new Uint8Array([1, 2]));
+		} else {
+			controller.close();
+			controller.byobRequest.respond(0);
+		}
+	},
+});
+
+closingStream.getReader({ mode: "byob" })
+	.read(new Uint8Array(4), { min: 4 })
+	.then(({ done, value }) => {
+		results[2] = `${done}:${value.join(",")}`;
+	});
+
+let releasedController;
+const releasedStream = new ReadableStream({
+	type: "bytes",
+	start(controller) {
+		releasedController = controller;
+	},
+});
+
+const releasedReader = releasedStream.getReader({ mode: "byob" });
+releasedReader.closed.catch(() => {});
+releasedReader.read(new Uint8Array(4), { min: 4 }).catch(error => {
+	results[3] = error.name;
+});
+releasedController.enqueue(new Uint8Array([1, 2]));
+releasedReader.releaseLock();
+releasedController.enqueue(new Uint8Array([3]));
+
+releasedStream.getReader()
+	.read()
+	.then(({ value }) => {
+		results[4] = value.join(",");
+	});
+
+setTimeout(() => {
+	if (results[0] !== "0,1,2,3,4,5") {
+		throw new Error(`Incorrect Bytes: ${results[0]}`);
+	}
+	if (results[1] !== 4) {
+		throw new Error(`Incorrect Byte Length: ${results[1]}`);
+	}
+	if (results[2] !== "true:1,2") {
+		throw new Error(`Incorrect Partial Read: ${results[2]}`);
+	}
+	if (results[3] !== "TypeError") {
+		throw new Error(`Released Read was not rejected: ${results[3]}`);
+	}
+	if (results[4] !== "1,2") {
+		throw new Error(`Incorrect Bytes after Release: ${results[4]}`);
+	}
+}, 50);
@@ -50,3 +50,5 @@ for (let i = 0; i < 576; i++) {
 }
 console.timeEnd();
 console.timeEnd("Timer");
+
+console.table([{a: 1, b: {c: {d: 1}}, c: "x".repeat(100), d: 4}], undefined, {maxColumns: 3, maxWidth: 120});
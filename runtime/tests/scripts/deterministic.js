@@ -0,0 +1,27 @@
+const values = [Math.random(), Math.random()];
+if (values.join(",") !== "0.7415648787718233,0.1599103928769201") {
+	throw new Error(`Random Sequence Not Seeded: ${values}`);
+}
+
+if (Date.now() !== 0 || new Date().getTime() !== 0 || performance.now() !== 0) {
+	throw new Error(`Virtual Time Not At Epoch: ${Date.now()}`);
+}
+if (new Date(1000).getTime() !== 1000 || !(new Date() instanceof Date) || typeof Date() !== "string") {
+	throw new Error("Date Constructor Changed");
+}
+if (new Date(0).getTimezoneOffset() !== 0 || new Date(0).getHours() !== 0) {
+	throw new Error(`Time Zone Not UTC: ${new Date(0).toString()}`);
+}
+
+const order = [];
+setTimeout(() => order.push(`a:${Date.now()}`), 1000);
+setTimeout(() => order.push(`b:${Date.now()}`), 10);
+
+setTimeout(() => {
+	if (order.join(",") !== "b:10,a:1000") {
+		throw new Error(`Timers Not Run In Virtual Time: ${order}`);
+	}
+	if (performance.now() !== 2000) {
+		throw new Error(`Incorrect Virtual Time: ${performance.now()}`);
+	}
+}, 2000);
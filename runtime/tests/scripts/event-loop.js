@@ -0,0 +1,44 @@
+const order = [];
+
+setTimeout(() => {
+	order.push("timeout 1");
+	Promise.resolve().then(() => order.push("timeout 1 microtask"));
+}, 10);
+
+setTimeout(() => order.push("timeout 2"), 15);
+
+queueMicrotask(() => order.push("microtask"));
+Promise.resolve().then(() => order.push("promise"));
+
+resolveNative().then(() => {
+	order.push("native");
+	Promise.resolve().then(() => order.push("native microtask"));
+});
+
+let panicked = null;
+panicNative().catch(error => {
+	panicked = error.message;
+});
+
+order.push("script");
+
+setTimeout(() => {
+	const expected = [
+		"script",
+		"microtask",
+		"promise",
+		"native",
+		"native microtask",
+		"timeout 1",
+		"timeout 1 microtask",
+		"timeout 2",
+	];
+	if (!panicked?.includes("panicked")) {
+		throw new Error(`Panicking native future was not rejected: ${panicked}`);
+	}
+
+	const actual = order.join(", ");
+	if (actual !== expected.join(", ")) {
+		throw new Error(`Unexpected Order: ${actual}`);
+	}
+}, 50);
@@ -0,0 +1,42 @@
+const base = `http+unix://${encodeURIComponent(SOCKET_PATH)}`;
+
+async function fetchText(path, init) {
+	const response = await fetch(`${base}${path}`, init);
+	return `${response.status} ${await response.text()}`;
+}
+
+async function run() {
+	const results = [
+		await fetchText("/fresh"),
+		await fetchText("/fresh"),
+		await fetchText("/etag"),
+		await fetchText("/etag"),
+		await fetchText("/fresh", { cache: "no-store" }),
+		await fetchText("/missing", { cache: "only-if-cached" }).catch(() => "error"),
+	];
+	return results.join("|");
+}
+
+let result = null;
+run().then(
+	text => {
+		result = text;
+		setTimeout(() => {
+			if (text !== "200 fresh 1|200 fresh 1|200 etag 2|200 etag 2|200 fresh 4|error") {
+				throw new Error(`Incorrect Responses: ${text}`);
+			}
+		});
+	},
+	error => {
+		result = error;
+		setTimeout(() => {
+			throw error;
+		});
+	},
+);
+
+setTimeout(() => {
+	if (result === null) {
+		throw new Error("Cached requests did not complete");
+	}
+}, 1000);
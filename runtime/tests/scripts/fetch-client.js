@@ -0,0 +1,39 @@
+let results = null;
+fetch("http://example.invalid/upload", { method: "POST", body: "ignored" })
+	.then(response => {
+		if (response.status !== 201) {
+			throw new Error(`Incorrect Status: ${response.status}`);
+		}
+		if (response.headers.get("X-Client") !== "echo") {
+			throw new Error(`Incorrect Header: ${response.headers.get("X-Client")}`);
+		}
+		return response.text();
+	})
+	.then(
+		text => {
+			results = text;
+			setTimeout(() => {
+				if (text !== "POST /upload") {
+					throw new Error(`Incorrect Body: ${text}`);
+				}
+			});
+		},
+		error => {
+			results = error;
+			setTimeout(() => {
+				throw error;
+			});
+		},
+	);
+
+let refused = null;
+fetch("http://example.invalid/refused").catch(error => (refused = error));
+
+setTimeout(() => {
+	if (results === null) {
+		throw new Error("Request was not sent with the client");
+	}
+	if (!(refused instanceof TypeError) || !refused.message.includes("Connection refused")) {
+		throw new Error(`Transport Error was not reported: ${refused}`);
+	}
+}, 1000);
@@ -0,0 +1,57 @@
+let done = false;
+
+async function expectNetworkError(promise, message) {
+	try {
+		await promise;
+	} catch (error) {
+		if (!(error instanceof TypeError)) {
+			throw new Error(`Incorrect Error: ${error}`);
+		}
+		return;
+	}
+	throw new Error(message);
+}
+
+(async () => {
+	const response = await fetch("https://api.example/allowed", { method: "PUT", headers: { "X-Custom": "1" } });
+	if (response.type !== "cors") {
+		throw new Error(`Incorrect Type: ${response.type}`);
+	}
+	if (response.headers.get("X-Exposed") !== "exposed" || response.headers.get("Content-Type") !== "text/plain") {
+		throw new Error("Exposed headers were removed");
+	}
+	if (response.headers.get("X-Secret") !== null) {
+		throw new Error("Unexposed header was not removed");
+	}
+
+	await fetch("https://api.example/allowed", { method: "PUT", headers: { "X-Custom": "2" } });
+	await expectNetworkError(fetch("https://api.example/allowed", { method: "DELETE" }), "DELETE was not preflighted");
+	await expectNetworkError(fetch("https://api.example/denied"), "Response without Allow-Origin was read");
+
+	const tainted = await fetch("https://api.example/redirect");
+	if (tainted.type !== "cors" || !tainted.redirected) {
+		throw new Error(`Incorrect Redirected Response: ${tainted.type}`);
+	}
+	await expectNetworkError(fetch("https://api.example/back"), "Redirect back to the origin was not tainted");
+
+	const local = await fetch("https://app.example/local");
+	if (local.type !== "basic" || local.headers.get("X-Secret") !== "secret") {
+		throw new Error("Same-origin response was filtered");
+	}
+})().then(
+	() => {
+		done = true;
+	},
+	error => {
+		done = true;
+		setTimeout(() => {
+			throw error;
+		});
+	},
+);
+
+setTimeout(() => {
+	if (!done) {
+		throw new Error("Requests did not complete");
+	}
+}, 1000);
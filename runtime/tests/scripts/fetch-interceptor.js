@@ -0,0 +1,29 @@
+const greeting = fetch("http://example.invalid/greeting").then(response => response.text());
+const echo = fetch("http://example.invalid/echo", { headers: { "X-Echo": "echoed" } }).then(response => response.text());
+
+let results = null;
+Promise.all([greeting, echo]).then(
+	values => {
+		results = values;
+		setTimeout(() => {
+			if (values[0] !== "mocked") {
+				throw new Error(`Incorrect Greeting: ${values[0]}`);
+			}
+			if (values[1] !== "echoed") {
+				throw new Error(`Incorrect Echo: ${values[1]}`);
+			}
+		});
+	},
+	error => {
+		results = error;
+		setTimeout(() => {
+			throw error;
+		});
+	},
+);
+
+setTimeout(() => {
+	if (results === null) {
+		throw new Error("Intercepted requests did not complete");
+	}
+}, 1000);
@@ -0,0 +1,3 @@
+const large = "x".repeat(64 * 1024 + 1);
+fetch("http://example.invalid/large", { method: "POST", body: large, keepalive: true }).catch(() => {});
+fetch("http://example.invalid/beacon", { method: "POST", body: "x".repeat(1024), keepalive: true });
@@ -0,0 +1,27 @@
+const url = `http+unix://${encodeURIComponent(SOCKET_PATH)}/flaky`;
+
+let result = null;
+fetch(url, { retry: { attempts: 3, backoff: 10 } })
+	.then(response => response.text().then(text => [response.status, text]))
+	.then(
+		([status, text]) => {
+			result = text;
+			setTimeout(() => {
+				if (status !== 200 || text !== "attempt 3") {
+					throw new Error(`Incorrect Response: ${status} ${text}`);
+				}
+			});
+		},
+		error => {
+			result = error;
+			setTimeout(() => {
+				throw error;
+			});
+		},
+	);
+
+setTimeout(() => {
+	if (result === null) {
+		throw new Error("Retried request did not complete");
+	}
+}, 1000);
@@ -0,0 +1,49 @@
+const results = [];
+
+function check(condition, message) {
+	if (!condition) {
+		throw new Error(message);
+	}
+}
+
+async function run() {
+	const {defaults} = fetch;
+	check(defaults.headersTimeout === 20, `Incorrect Headers Timeout: ${defaults.headersTimeout}`);
+	check(defaults.connectTimeout === null && defaults.timeout === null, "Unexpected Timeouts");
+
+	const headers = await fetch("http://example.invalid/stalled").catch(error => error);
+	check(headers.name === "TimeoutError", `Headers Timeout did not cause a TimeoutError: ${headers}`);
+	results.push("headers");
+
+	defaults.headersTimeout = null;
+	defaults.timeout = 30;
+	const total = await fetch("http://example.invalid/stalled").catch(error => error);
+	check(total.name === "TimeoutError", `Total Timeout did not cause a TimeoutError: ${total}`);
+	results.push("total");
+
+	const response = await fetch("http://example.invalid/fast");
+	check(response.status === 200, `Incorrect Status: ${response.status}`);
+	results.push("fast");
+
+	let threw = false;
+	try {
+		defaults.timeout = -1;
+	} catch {
+		threw = true;
+	}
+	check(threw && defaults.timeout === 30, "Negative Timeout was not rejected");
+	results.push("invalid");
+}
+
+let error = null;
+run().catch(e => (error = e));
+
+setTimeout(() => {
+	if (error !== null) {
+		throw error;
+	}
+	const actual = results.join(", ");
+	if (actual !== "headers, total, fast, invalid") {
+		throw new Error(`Incomplete Results: ${actual}`);
+	}
+}, 500);
@@ -0,0 +1,29 @@
+globalThis.chunks = [];
+(async () => {
+	const response = await fetch("http://example.invalid/chunks");
+
+	// Transforms the response body, as a `TransformStream` would.
+	let controller;
+	const decoder = new TextDecoder();
+	const readable = new ReadableStream({
+		start(c) {
+			controller = c;
+		},
+	});
+	const writable = new WritableStream({
+		write(chunk) {
+			controller.enqueue(decoder.decode(chunk).toUpperCase());
+		},
+		close() {
+			controller.close();
+		},
+	});
+
+	// The body is piped as soon as the response is received, before the event loop has run any other tasks.
+	const piped = response.body.pipeTo(writable);
+	const reader = readable.getReader();
+	for (let result = await reader.read(); !result.done; result = await reader.read()) {
+		chunks.push(result.value);
+	}
+	await piped;
+})();
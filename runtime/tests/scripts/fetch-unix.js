@@ -0,0 +1,58 @@
+const url = `http+unix://${encodeURIComponent(SOCKET_PATH)}/info?verbose=1`;
+
+const body = "x".repeat(200000);
+
+const uploaded = [];
+const downloaded = [];
+const hints = [];
+const init = {
+	method: "POST",
+	body,
+	onUploadProgress: ({ loaded, total }) => uploaded.push([loaded, total]),
+	onDownloadProgress: ({ loaded }) => downloaded.push(loaded),
+	onInformational: ({ status, headers }) => hints.push(`${status} ${headers.get("Link")}`),
+};
+
+let result = null;
+fetch(url, init)
+	.then(response => response.text().then(text => [response, text]))
+	.then(
+		([response, text]) => {
+			result = text;
+			setTimeout(() => {
+				if (text !== `POST /info?verbose=1 ${body.length}`) {
+					throw new Error(`Incorrect Response: ${text}`);
+				}
+				const [loaded, total] = uploaded[uploaded.length - 1] ?? [];
+				if (uploaded.length < 2 || loaded !== body.length || total !== body.length) {
+					throw new Error(`Incorrect Upload Progress: ${uploaded.join("|")}`);
+				}
+				if (hints.length !== 1 || hints[0] !== "103 </style.css>; rel=preload") {
+					throw new Error(`Incorrect Informational Responses: ${hints.join("|")}`);
+				}
+				if (downloaded[downloaded.length - 1] !== text.length) {
+					throw new Error(`Incorrect Download Progress: ${downloaded.join("|")}`);
+				}
+				if (response.urlList.length !== 1 || response.urlList[0] !== url) {
+					throw new Error(`Incorrect URL List: ${response.urlList}`);
+				}
+
+				const timing = response.timing;
+				if (timing === null || timing.firstByte < 0 || timing.total < timing.firstByte) {
+					throw new Error(`Incorrect Timing: ${JSON.stringify(timing)}`);
+				}
+			});
+		},
+		error => {
+			result = error;
+			setTimeout(() => {
+				throw error;
+			});
+		},
+	);
+
+setTimeout(() => {
+	if (result === null) {
+		throw new Error("Request over Unix socket did not complete");
+	}
+}, 1000);
@@ -0,0 +1,34 @@
+const events = [];
+const reader = new FileReader();
+
+for (const type of ["loadstart", "progress", "load", "abort", "error", "loadend"]) {
+	reader[`on${type}`] = event => events.push(`${event.type}:${event.loaded}/${event.total}`);
+}
+
+reader.readAsText(new Blob(["spiderfire"]));
+if (reader.readyState !== FileReader.LOADING) {
+	throw new Error(`Incorrect Ready State: ${reader.readyState}`);
+}
+
+const aborted = new FileReader();
+let abortEvents = 0;
+aborted.onabort = () => abortEvents++;
+aborted.onloadend = () => abortEvents++;
+aborted.onload = () => {
+	throw new Error("Aborted Reader Loaded");
+};
+aborted.readAsArrayBuffer(new Blob(["spiderfire"]));
+aborted.abort();
+
+setTimeout(() => {
+	const expected = "loadstart:0/10,progress:10/10,load:10/10,loadend:10/10";
+	if (events.join(",") !== expected) {
+		throw new Error(`Incorrect Events: ${events.join(",")}`);
+	}
+	if (reader.readyState !== FileReader.DONE || reader.result !== "spiderfire") {
+		throw new Error(`Incorrect Result: ${reader.result}`);
+	}
+	if (abortEvents !== 2 || aborted.readyState !== FileReader.DONE || aborted.result !== null) {
+		throw new Error("Incorrect Abort");
+	}
+}, 50);
@@ -0,0 +1,62 @@
+const headers = new Headers([
+	["X-Second", "b"],
+	["Set-Cookie", "a=1; Expires=Wed, 21 Oct 2015 07:28:00 GMT"],
+	["X-First", "a"],
+	["Set-Cookie", "b=2"],
+	["X-Second", "c"],
+]);
+
+const cookies = headers.getSetCookie();
+const entries = [...headers].map(([name, value]) => `${name}=${value}`);
+const keys = [...headers.keys()];
+const forEach = [];
+headers.forEach(function (value, name, target) {
+	forEach.push(this.prefix + name + (target === headers));
+}, { prefix: ">" });
+const primitives = [];
+headers.forEach(function () {
+	"use strict";
+	primitives.push(this);
+}, 1);
+
+const live = new Headers({ "X-A": "1" });
+const seen = [];
+for (const [name] of live) {
+	seen.push(name);
+	if (name === "x-a") {
+		live.append("X-B", "2");
+	}
+}
+const copied = new Headers(headers);
+copied.delete("X-First");
+
+setTimeout(() => {
+	if (cookies.length !== 2 || cookies[0] !== "a=1; Expires=Wed, 21 Oct 2015 07:28:00 GMT") {
+		throw new Error(`Incorrect Cookies: ${cookies}`);
+	}
+
+	const expected = [
+		"set-cookie=a=1; Expires=Wed, 21 Oct 2015 07:28:00 GMT",
+		"set-cookie=b=2",
+		"x-first=a",
+		"x-second=b, c",
+	];
+	if (entries.join("|") !== expected.join("|")) {
+		throw new Error(`Incorrect Entries: ${entries.join("|")}`);
+	}
+	if (keys.join(",") !== "set-cookie,set-cookie,x-first,x-second") {
+		throw new Error(`Incorrect Keys: ${keys}`);
+	}
+	if (forEach.join(",") !== ">set-cookietrue,>set-cookietrue,>x-firsttrue,>x-secondtrue") {
+		throw new Error(`Incorrect forEach: ${forEach}`);
+	}
+	if (primitives.length !== 4 || primitives.some(value => value !== 1)) {
+		throw new Error(`Incorrect forEach with primitive thisArg: ${primitives}`);
+	}
+	if (seen.join(",") !== "x-a,x-b") {
+		throw new Error(`Iteration did not observe appended header: ${seen}`);
+	}
+	if (!headers.has("X-First") || copied.has("x-first") || copied.get("X-Second") !== "b, c") {
+		throw new Error("Copied Headers are not independent");
+	}
+}, 10);
@@ -0,0 +1,7 @@
+import {c} from "./module-export.js";
+
+console.log(`Inspected ${c}`);
+Promise.reject(new Error("Rejected"));
+setTimeout(() => {
+	throw new Error("Uncaught");
+}, 10);
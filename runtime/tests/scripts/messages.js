@@ -0,0 +1,16 @@
+const received = [];
+
+function receive(message) {
+	received.push(message);
+	Promise.resolve().then(() => received.push("microtask"));
+}
+
+function check() {
+	const actual = JSON.stringify(received);
+	const expected = JSON.stringify([{answer: 42, list: [1, 2]}, "microtask", "message", "microtask"]);
+	if (actual !== expected) {
+		throw new Error(`Unexpected Messages: ${actual}`);
+	}
+}
+
+receive;
@@ -0,0 +1,21 @@
+const order = [];
+const reader = new FileReader();
+
+for (const type of ["loadstart", "progress", "load", "loadend"]) {
+	reader[`on${type}`] = event => {
+		order.push(event.type);
+		Promise.resolve().then(() => order.push(`${event.type}:microtask`));
+		queueMicrotask(() => order.push(`${event.type}:queued`));
+	};
+}
+
+reader.readAsText(new Blob(["spiderfire"]));
+
+setTimeout(() => {
+	const expected = ["loadstart", "progress", "load", "loadend"]
+		.flatMap(type => [type, `${type}:microtask`, `${type}:queued`])
+		.join(",");
+	if (order.join(",") !== expected) {
+		throw new Error(`Incorrect Order: ${order.join(",")}`);
+	}
+}, 50);
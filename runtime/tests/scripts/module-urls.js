@@ -0,0 +1,17 @@
+import answer from "data:text/javascript,export default 6 * 7;";
+import config from 'data:application/json,{"name":"spiderfire","modules":["data","blob"]}';
+import { greet } from "BLOB_URL";
+import proto from 'data:application/json,{"__proto__":{"polluted":true}}';
+
+if (answer !== 42) {
+	throw new Error(`Incorrect JavaScript Export: ${answer}`);
+}
+if (config.name !== "spiderfire" || config.modules.length !== 2) {
+	throw new Error(`Incorrect JSON Export: ${JSON.stringify(config)}`);
+}
+if (greet("module") !== "Hello, module!") {
+	throw new Error(`Incorrect Blob Export: ${greet("module")}`);
+}
+if (!Object.hasOwn(proto, "__proto__") || Object.getPrototypeOf(proto) !== Object.prototype) {
+	throw new Error(`Incorrect JSON __proto__ Export: ${JSON.stringify(proto)}`);
+}
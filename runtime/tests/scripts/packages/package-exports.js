@@ -0,0 +1,18 @@
+import target from "conditional";
+import log from "conditional/features/log";
+import legacy from "@scope/legacy";
+
+if (target !== "spiderfire") {
+	throw new Error(`Incorrect Export: ${target}`);
+}
+if (log !== "development") {
+	throw new Error(`Incorrect Subpath Pattern Export: ${log}`);
+}
+if (legacy !== "legacy") {
+	throw new Error(`Incorrect Main: ${legacy}`);
+}
+
+const conditions = import.meta.conditions.join(",");
+if (conditions !== "spiderfire,import,default,development") {
+	throw new Error(`Incorrect Conditions: ${conditions}`);
+}
@@ -0,0 +1,24 @@
+const initial = performance.eventLoopUtilization();
+
+setTimeout(() => {
+	const start = Date.now();
+	while (Date.now() - start < 20) {}
+}, 0);
+
+setTimeout(() => {
+	const current = performance.eventLoopUtilization();
+	const delta = performance.eventLoopUtilization(initial);
+
+	if (current.active < 20 || current.maxTick < 20) {
+		throw new Error(`Busy Time Not Recorded: ${JSON.stringify(current)}`);
+	}
+	if (current.idle <= 0) {
+		throw new Error(`Idle Time Not Recorded: ${JSON.stringify(current)}`);
+	}
+	if (current.utilization <= 0 || current.utilization >= 1) {
+		throw new Error(`Invalid Utilization: ${current.utilization}`);
+	}
+	if (delta.active !== current.active - initial.active) {
+		throw new Error(`Invalid Delta: ${JSON.stringify(delta)}`);
+	}
+}, 50);
@@ -0,0 +1,68 @@
+const chunks = [];
+const source = new ReadableStream({
+	start(controller) {
+		controller.enqueue("a");
+		controller.enqueue("b");
+		controller.close();
+	},
+});
+const destination = new WritableStream({
+	write(chunk) {
+		chunks.push(chunk);
+	},
+});
+
+let piped = false;
+source.pipeTo(destination).then(() => {
+	piped = !source.locked && !destination.locked;
+});
+
+const error = new Error("Source Failed");
+let aborted, rejected;
+const erroring = new ReadableStream({
+	start(controller) {
+		controller.error(error);
+	},
+});
+const aborting = new WritableStream({
+	abort(reason) {
+		aborted = reason;
+	},
+});
+erroring.pipeTo(aborting).catch((reason) => {
+	rejected = reason;
+});
+
+let kept = false;
+const open = new WritableStream();
+new ReadableStream({
+	start(controller) {
+		controller.close();
+	},
+})
+	.pipeTo(open, { preventClose: true })
+	.then(() => {
+		const writer = open.getWriter();
+		return writer.close();
+	})
+	.then(() => {
+		kept = true;
+	});
+
+nativeStream().pipeTo(nativeSink());
+
+if (!(console.stdout instanceof WritableStream)) {
+	throw new Error("console.stdout is not a WritableStream");
+}
+
+setTimeout(() => {
+	if (chunks.join(",") !== "a,b" || !piped) {
+		throw new Error(`Incorrect Chunks: ${chunks.join(",")}`);
+	}
+	if (aborted !== error || rejected !== error) {
+		throw new Error("Source error was not propagated to the destination");
+	}
+	if (!kept) {
+		throw new Error("Destination was closed with preventClose");
+	}
+}, 50);
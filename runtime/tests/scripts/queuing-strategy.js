@@ -0,0 +1,20 @@
+globalThis.result = "";
+
+const bytes = new ByteLengthQueuingStrategy({ highWaterMark: 16 });
+const count = new CountQueuingStrategy({ highWaterMark: 2 });
+
+const sizes = [bytes.highWaterMark, bytes.size(new Uint8Array(5)), count.highWaterMark, count.size("chunk")];
+
+let controller;
+new ReadableStream(
+	{
+		start(c) {
+			controller = c;
+		},
+	},
+	bytes,
+);
+controller.enqueue(new Uint8Array(10));
+sizes.push(controller.desiredSize);
+
+result = sizes.join();
@@ -0,0 +1,10 @@
+const request = new Request("http://example.invalid/", { headers: { "X-A": "1" } });
+const copied = new Request(request);
+copied.headers.append("X-B", "2");
+
+if (copied.headers.get("X-A") !== "1") {
+	throw new Error("Headers were not copied from the input request");
+}
+if (request.headers.has("X-B")) {
+	throw new Error("Copied Request shares Headers with the input request");
+}
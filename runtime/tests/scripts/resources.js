@@ -0,0 +1,20 @@
+const initial = spiderfire.resourceUsage();
+if (initial.timersCreated !== 0) {
+	throw new Error(`Invalid Initial Timers: ${initial.timersCreated}`);
+}
+
+setTimeout(() => {}, 0);
+const interval = setInterval(() => clearInterval(interval), 1);
+
+setTimeout(() => {
+	const usage = spiderfire.resourceUsage();
+	if (usage.timersCreated !== 3) {
+		throw new Error(`Timers Not Recorded: ${JSON.stringify(usage)}`);
+	}
+	if (usage.heapBytes <= 0 || usage.peakHeapBytes < usage.heapBytes) {
+		throw new Error(`Invalid Heap Size: ${JSON.stringify(usage)}`);
+	}
+	if (Object.keys(globalThis).includes("spiderfire")) {
+		throw new Error("Debug Global Is Enumerable");
+	}
+}, 10);
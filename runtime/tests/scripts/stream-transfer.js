@@ -0,0 +1,44 @@
+globalThis.result = "";
+
+async function readAll(stream) {
+	const reader = stream.getReader();
+	const chunks = [];
+	for (let next = await reader.read(); !next.done; next = await reader.read()) {
+		chunks.push(next.value);
+	}
+	return chunks;
+}
+
+(async () => {
+	const readable = new ReadableStream({
+		start(controller) {
+			controller.enqueue("a");
+			controller.enqueue("b");
+			controller.close();
+		},
+	});
+	const transferredReadable = structuredClone(readable, { transfer: [readable] });
+	if (!readable.locked || transferredReadable === readable) {
+		throw new Error("Readable stream was not transferred");
+	}
+	const chunks = await readAll(transferredReadable);
+
+	const written = [];
+	const writable = new WritableStream({
+		write(chunk) {
+			written.push(chunk);
+		},
+	});
+	const transferredWritable = structuredClone(writable, { transfer: [writable] });
+	if (!writable.locked || transferredWritable === writable) {
+		throw new Error("Writable stream was not transferred");
+	}
+	const writer = transferredWritable.getWriter();
+	await writer.write("c");
+	await writer.write("d");
+	await writer.close();
+
+	result = [...chunks, ...written].join();
+})().catch(error => {
+	result = String(error);
+});
@@ -0,0 +1,36 @@
+const reader = nativeStream().getReader();
+const bytes = [];
+
+function read() {
+	return reader.read().then(({ done, value }) => {
+		if (!done) {
+			bytes.push(...value);
+			return read();
+		}
+	});
+}
+
+read();
+
+const byobReader = asyncReadStream().getReader({ mode: "byob" });
+const views = [];
+
+function readInto() {
+	return byobReader.read(new Uint8Array(2)).then(({ done, value }) => {
+		if (!done) {
+			views.push(value.join(""));
+			return readInto();
+		}
+	});
+}
+
+readInto();
+
+setTimeout(() => {
+	if (bytes.join(",") !== "1,2,3,4,5") {
+		throw new Error(`Incorrect Bytes: ${bytes.join(",")}`);
+	}
+	if (views.join(",") !== "67,8") {
+		throw new Error(`Incorrect Views: ${views.join(",")}`);
+	}
+}, 50);
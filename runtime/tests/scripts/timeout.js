@@ -0,0 +1,7 @@
+globalThis.caught = null;
+
+try {
+	for (;;) {}
+} catch (error) {
+	globalThis.caught = error;
+}
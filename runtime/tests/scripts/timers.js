@@ -0,0 +1,49 @@
+const order = [];
+
+setTimeout(() => order.push("overflow"), 2 ** 31);
+setTimeout(() => order.push("nan"), NaN);
+setTimeout(() => order.push("infinity"), Infinity);
+setTimeout(() => order.push("negative"), -10);
+setTimeout(() => order.push("wrapped"), 2 ** 32 + 5);
+
+for (let i = 0; i < 5; i++) {
+	setTimeout(() => order.push(`same ${i}`), 3);
+}
+
+const interval = setInterval(() => {
+	order.push("interval");
+	clearInterval(interval);
+}, NaN);
+
+setTimeout("order.push('string')", NaN);
+
+let syntaxError = false;
+try {
+	setTimeout("(", 0);
+} catch (error) {
+	syntaxError = error instanceof SyntaxError;
+}
+
+setTimeout(() => {
+	const expected = [
+		"overflow",
+		"nan",
+		"infinity",
+		"negative",
+		"interval",
+		"string",
+		"same 0",
+		"same 1",
+		"same 2",
+		"same 3",
+		"same 4",
+		"wrapped",
+	];
+	const actual = order.join(", ");
+	if (actual !== expected.join(", ")) {
+		throw new Error(`Unexpected Order: ${actual}`);
+	}
+	if (!syntaxError) {
+		throw new Error("String handler with invalid syntax did not throw a SyntaxError");
+	}
+}, 50);
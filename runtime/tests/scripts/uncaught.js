@@ -0,0 +1,27 @@
+const caught = [];
+setUncaughtExceptionHandler(error => {
+	caught.push(error.message);
+});
+
+reportError(new Error("Reported"));
+queueMicrotask(() => {
+	throw new Error("Microtask");
+});
+queueMicrotask(() => caught.push("Next"));
+
+setTimeout(() => {
+	throw new Error("First");
+}, 1);
+setTimeout(() => {
+	throw new Error("Second");
+}, 5);
+setTimeout(() => {
+	setUncaughtExceptionHandler(null);
+	if (caught.join() !== "Reported,Microtask,Next,First,Second") {
+		throw new Error(`Unexpected errors caught by handler: ${caught.join()}`);
+	}
+	throw new Error("Host");
+}, 10);
+setTimeout(() => {
+	throw new Error("Last");
+}, 20);
@@ -0,0 +1,5 @@
+Promise.reject(new Error("Unhandled"));
+Promise.reject(new Error("Handled")).catch(() => {});
+Promise.resolve().then(() => {
+	throw "Thrown";
+});
@@ -0,0 +1,5 @@
+const writer = nativeSink().getWriter();
+
+writer.write(new Uint8Array([1, 2]));
+writer.write(new Uint8Array([3, 4, 5]));
+writer.close();
@@ -0,0 +1,85 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::env::temp_dir;
+use std::fs::{create_dir_all, remove_dir_all, write};
+use std::path::Path;
+use std::process;
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use ion::script::Script;
+use ion::{Context, Error, Exception};
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::cache::map::SourceMapStore;
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "sourcemaps.js";
+
+/// Maps the third line of the script to the tenth line and fifth column of `original.ts`.
+const SOURCEMAP: &str = r#"{"version":3,"sources":["original.ts"],"names":[],"mappings":";;ASIA"}"#;
+
+#[tokio::test]
+async fn inline_sourcemap() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().build(cx);
+
+	let url = format!("data:application/json;base64,{}", BASE64_STANDARD.encode(SOURCEMAP));
+	let script = format!("\"use strict\";\n\nthrow new Error(\"mapped\");\n//# sourceMappingURL={url}\n");
+	assert!(SourceMapStore::with(|store| store.register_source(FILE_NAME, &script)));
+
+	let mut report = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), &script).unwrap_err();
+	SourceMapStore::with(|store| store.transform_error_report(&mut report));
+
+	let Exception::Error(Error { location: Some(location), .. }) = &report.exception else {
+		panic!("Expected Error with Location, found {:?}", report.exception);
+	};
+	assert_eq!((location.lineno, location.column), (10, 5));
+
+	let record = &report.stack.as_ref().unwrap().records[0];
+	assert_eq!((record.location.lineno, record.location.column), (10, 5));
+
+	let script = "throw new Error(\"unmapped\");";
+	assert!(!SourceMapStore::with(|store| store.register_source(FILE_NAME, script)));
+	assert!(SourceMapStore::with(|store| store.get(FILE_NAME).is_none()));
+
+	// Code evaluated with `eval` is registered under the filename and line of its caller.
+	let evaluated = format!("\"use strict\";\n\nthrow new Error(\"mapped\");\n//# sourceMappingURL={url}\n");
+	let script = format!("\n\neval({evaluated:?});");
+	let mut report = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), &script).unwrap_err();
+	let path = format!("{FILE_NAME} line 3 > eval");
+	assert!(SourceMapStore::with(|store| store.get(&path).is_some()));
+	SourceMapStore::with(|store| store.transform_error_report(&mut report));
+
+	let record = &report.stack.as_ref().unwrap().records[0];
+	assert_eq!((record.location.lineno, record.location.column), (10, 5));
+}
+
+#[test]
+fn sourcemap_directory() {
+	let root = temp_dir().join(format!("spiderfire-sourcemaps-{}", process::id()));
+	let directory = root.join("scripts");
+	create_dir_all(&directory).unwrap();
+	write(root.join("outside.js.map"), SOURCEMAP).unwrap();
+	write(directory.join("inside.js.map"), SOURCEMAP).unwrap();
+
+	// Source maps are only read from within the directory of the script.
+	let script = directory.join("script.js");
+	for (url, registered) in [("inside.js.map", true), ("../outside.js.map", false)] {
+		let source = format!("throw new Error();\n//# sourceMappingURL={url}\n");
+		write(&script, &source).unwrap();
+		let result = SourceMapStore::with(|store| store.register_source(&script, &source));
+		assert_eq!(result, registered, "{url}");
+	}
+
+	remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,62 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use bytes::Bytes;
+use futures::stream;
+use ion::flags::PropertyFlags;
+use ion::script::Script;
+use ion::{Context, Object};
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::globals::streams::readable::ReadableStream;
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+
+const FILE_NAME: &str = "streams.js";
+const SCRIPT: &str = include_str!("scripts/streams.js");
+
+#[tokio::test]
+async fn bytes_stream() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().microtask_queue().macrotask_queue().build(cx);
+
+	let global = rt.global();
+	global.define_method(rt.cx(), "nativeStream", native_stream, 0, PropertyFlags::all());
+	global.define_method(rt.cx(), "asyncReadStream", async_read_stream, 0, PropertyFlags::all());
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+		})
+		.await;
+}
+
+#[ion::js_fn]
+fn native_stream(cx: &Context) -> Object {
+	let chunks = [
+		Ok(Bytes::from_static(&[1, 2])),
+		Ok(Bytes::new()),
+		Ok(Bytes::from_static(&[3, 4, 5])),
+	];
+	ReadableStream::from_bytes_stream(cx, stream::iter(chunks))
+}
+
+#[ion::js_fn]
+fn async_read_stream(cx: &Context) -> Object {
+	ReadableStream::from_async_read(cx, &[6u8, 7, 8][..])
+}
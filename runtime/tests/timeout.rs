@@ -0,0 +1,43 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+use std::time::Duration;
+
+use ion::conversions::FromValue;
+use ion::script::Script;
+use ion::Context;
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::RuntimeBuilder;
+
+const FILE_NAME: &str = "timeout.js";
+const SCRIPT: &str = include_str!("scripts/timeout.js");
+
+const TIMEOUT: Duration = Duration::from_millis(100);
+
+#[test]
+fn evaluation_timeout() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().build(cx);
+	let cx = rt.cx();
+
+	let _ = rt.with_timeout(TIMEOUT, || Script::compile_and_evaluate(cx, Path::new(FILE_NAME), SCRIPT));
+	let name = Script::compile_and_evaluate(cx, Path::new("name.js"), "caught.name").unwrap();
+	assert_eq!(String::from_value(cx, &name, true, ()).unwrap(), "TimeoutError");
+
+	let result = rt.with_timeout(TIMEOUT, || Script::compile_and_evaluate(cx, Path::new("loop.js"), "for (;;) {}"));
+	let report = result.expect_err("Evaluation should time out");
+	assert!(report.format(cx).contains("Evaluation timed out"));
+
+	let result = Script::compile_and_evaluate(cx, Path::new("after.js"), "for (let i = 0; i < 1000; i++) {}");
+	assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+}
@@ -0,0 +1,60 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use ion::script::Script;
+use ion::Context;
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::typescript::{compile_typescript, CompilerOptions};
+use runtime::RuntimeBuilder;
+use tracing::span::{Attributes, Id};
+use tracing::subscriber::set_default;
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context as LayerContext, SubscriberExt};
+use tracing_subscriber::{registry, Layer};
+
+const FILE_NAME: &str = "tracing.js";
+const SCRIPT: &str = include_str!("scripts/tracing.js");
+
+#[derive(Clone, Default)]
+struct SpanNames(Arc<Mutex<Vec<&'static str>>>);
+
+impl<S: Subscriber> Layer<S> for SpanNames {
+	fn on_new_span(&self, attributes: &Attributes<'_>, _: &Id, _: LayerContext<'_, S>) {
+		self.0.lock().unwrap().push(attributes.metadata().name());
+	}
+}
+
+#[tokio::test]
+async fn spans() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let names = SpanNames::default();
+	let _guard = set_default(registry().with(names.clone()));
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().microtask_queue().macrotask_queue().build(cx);
+
+	let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+	assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+	let result = rt.run_event_loop().await;
+	assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+	let options = CompilerOptions::global();
+	assert!(compile_typescript("tracing.ts", "const answer: number = 42;", options).is_ok());
+
+	let names = names.0.lock().unwrap();
+	for name in ["macrotask", "microtasks", "typescript.compile"] {
+		assert!(names.contains(&name), "Missing span: {name}");
+	}
+}
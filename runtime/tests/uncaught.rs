@@ -0,0 +1,63 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use ion::script::Script;
+use ion::{Context, ErrorReport};
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::event_loop::UncaughtErrorAction;
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+
+const FILE_NAME: &str = "uncaught.js";
+const SCRIPT: &str = include_str!("scripts/uncaught.js");
+
+#[tokio::test]
+async fn uncaught_error_handlers() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let errors = Rc::new(RefCell::new(Vec::new()));
+	let handled = Rc::clone(&errors);
+	let rt = RuntimeBuilder::<()>::new()
+		.microtask_queue()
+		.macrotask_queue()
+		.uncaught_error_handler(move |cx: &Context, report: &ErrorReport| {
+			let message = report.format(cx);
+			let action = if message.contains("Host") {
+				UncaughtErrorAction::Continue
+			} else {
+				UncaughtErrorAction::Exit
+			};
+			handled.borrow_mut().push(message);
+			action
+		})
+		.build(cx);
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			let report = result.expect_err("Event loop should stop after the last error").unwrap();
+			assert!(report.format(rt.cx()).contains("Last"));
+		})
+		.await;
+
+	let errors = errors.borrow();
+	assert_eq!(errors.len(), 6);
+	assert!(errors[0].contains("Reported") && errors[1].contains("Microtask"));
+	assert!(errors[2].contains("First") && errors[3].contains("Second") && errors[4].contains("Host"));
+}
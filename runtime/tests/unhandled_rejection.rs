@@ -0,0 +1,51 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use ion::format::{format_value, Config as FormatConfig};
+use ion::script::Script;
+use ion::{Context, Value};
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+
+const FILE_NAME: &str = "unhandled-rejection.js";
+const SCRIPT: &str = include_str!("scripts/unhandled-rejection.js");
+
+#[tokio::test]
+async fn unhandled_rejection_handler() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let reasons = Rc::new(RefCell::new(Vec::new()));
+	let handled = Rc::clone(&reasons);
+	let rt = RuntimeBuilder::<()>::new()
+		.microtask_queue()
+		.unhandled_rejection_handler(move |cx: &Context, reason: &Value| {
+			handled.borrow_mut().push(format_value(cx, FormatConfig::default(), reason).to_string());
+		})
+		.build(cx);
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+			assert!(rt.run_event_loop().await.is_ok());
+		})
+		.await;
+
+	let reasons = reasons.borrow();
+	assert_eq!(reasons.len(), 2);
+	assert!(reasons[0].contains("Unhandled") && reasons[1].contains("Thrown"));
+}
@@ -0,0 +1,119 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fs::{read_dir, read_to_string};
+use std::path::{Path, PathBuf};
+
+use ion::conversions::FromValue;
+use ion::script::Script;
+use ion::{default_new_global, Context};
+use mozjs::jsapi::{GetCurrentRealmOrNull, JSAutoRealm};
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, Globals, LogLevel, CONFIG};
+use runtime::globals::{init_globals, init_microtasks, init_timers};
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+
+const HARNESS: &str = include_str!("wpt/testharness.js");
+
+const SUITES: &[&str] = &[
+	"dom",
+	"encoding",
+	#[cfg(feature = "fetch")]
+	"fetch",
+	"streams",
+	"url",
+];
+
+/// Runs the Web Platform Test-style files in `tests/wpt`, each in a new realm, and fails if any of their tests fail.
+#[tokio::test]
+async fn wpt() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().microtask_queue().macrotask_queue().build(cx);
+
+	let mut failures = Vec::new();
+	let mut passes = 0;
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			for file in fixtures() {
+				let name = file.strip_prefix(wpt_dir()).unwrap().to_string_lossy().replace('\\', "/");
+				for result in run_fixture(&rt, &file).await.lines() {
+					println!("[{name}] {result}");
+					if result.starts_with("PASS ") {
+						passes += 1;
+					} else {
+						failures.push(format!("[{name}] {result}"));
+					}
+				}
+			}
+		})
+		.await;
+
+	println!("{passes} passed, {} failed", failures.len());
+	assert!(failures.is_empty(), "Failed Tests:\n{}", failures.join("\n"));
+}
+
+fn wpt_dir() -> PathBuf {
+	Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/wpt")
+}
+
+fn fixtures() -> Vec<PathBuf> {
+	let mut fixtures = Vec::new();
+	for suite in SUITES {
+		let mut files: Vec<_> = read_dir(wpt_dir().join(suite))
+			.unwrap()
+			.map(|entry| entry.unwrap().path())
+			.filter(|path| path.extension().is_some_and(|extension| extension == "js"))
+			.collect();
+		files.sort();
+		fixtures.extend(files);
+	}
+	fixtures
+}
+
+/// Evaluates the harness and a test file in a new global, runs the event loop until its tests have settled, and
+/// returns the result of each test.
+async fn run_fixture(rt: &runtime::Runtime<'_>, path: &Path) -> String {
+	let cx = rt.cx();
+	let source = read_to_string(path).unwrap();
+
+	let global = default_new_global(cx);
+	let globals = Globals::default();
+	assert!(init_globals(cx, &global, globals), "Failed to initialise globals");
+	assert!(init_microtasks(cx, &global), "Failed to initialise microtasks");
+	assert!(init_timers(cx, &global, globals), "Failed to initialise timers");
+
+	{
+		let _realm = JSAutoRealm::new(cx.as_ptr(), global.handle().get());
+		for (file, source) in [(Path::new("testharness.js"), HARNESS), (path, source.as_str())] {
+			if let Err(report) = Script::compile_and_evaluate(cx, file, source) {
+				return format!("FAIL {}: {}", file.display(), report.format(cx));
+			}
+		}
+	}
+
+	if let Err(report) = rt.run_event_loop().await {
+		let report = report.map(|report| report.format(cx)).unwrap_or_default();
+		return format!("FAIL Uncaught Error: {report}");
+	}
+
+	let _realm = JSAutoRealm::new(cx.as_ptr(), global.handle().get());
+	let report = Script::compile_and_evaluate(cx, Path::new("report.js"), "__report()").unwrap();
+	let report = String::from_value(cx, &report, true, ()).unwrap();
+
+	unsafe {
+		let realm = GetCurrentRealmOrNull(cx.as_ptr());
+		cx.remove_class_infos(realm);
+	}
+	report
+}
@@ -0,0 +1,64 @@
+test(() => {
+	const controller = new AbortController();
+	controller.abort();
+	const { reason } = controller.signal;
+	assert_true(controller.signal.aborted);
+	assert_true(reason instanceof Error);
+	assert_equals(reason.name, "AbortError");
+}, "abort() without a reason aborts with an AbortError");
+
+test(() => {
+	const controller = new AbortController();
+	const reason = { custom: true };
+	controller.abort(reason);
+	controller.abort("ignored");
+	assert_equals(controller.signal.reason, reason);
+}, "abort() keeps the first reason");
+
+test(() => {
+	assert_equals(AbortSignal.abort().reason.name, "AbortError");
+	assert_equals(AbortSignal.abort(undefined).reason.name, "AbortError");
+	assert_equals(AbortSignal.abort(null).reason, null);
+}, "AbortSignal.abort() only replaces undefined reasons");
+
+test(() => {
+	const reason = new TypeError("reason");
+	const signal = AbortSignal.abort(reason);
+	let thrown;
+	try {
+		signal.throwIfAborted();
+	} catch (error) {
+		thrown = error;
+	}
+	assert_equals(thrown, reason);
+
+	new AbortController().signal.throwIfAborted();
+}, "throwIfAborted() throws the reason");
+
+promise_test(async () => {
+	const signal = AbortSignal.timeout(1);
+	await new Promise(resolve => setTimeout(resolve, 20));
+	assert_true(signal.aborted);
+	assert_equals(signal.reason.name, "TimeoutError");
+}, "AbortSignal.timeout() aborts with a TimeoutError");
+
+promise_test(async () => {
+	let signal;
+	const stream = new WritableStream({
+		start(controller) {
+			signal = controller.signal;
+		},
+	});
+	await stream.abort();
+	assert_true(signal.aborted);
+	assert_equals(signal.reason.name, "AbortError");
+
+	const reason = { custom: true };
+	const other = new WritableStream({
+		start(controller) {
+			signal = controller.signal;
+		},
+	});
+	await other.abort(reason);
+	assert_equals(signal.reason, reason);
+}, "WritableStream abort() signals its controller with the reason");
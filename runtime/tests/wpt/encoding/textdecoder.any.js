@@ -0,0 +1,34 @@
+test(() => {
+	const decoder = new TextDecoder();
+	assert_equals(decoder.decode(new Uint8Array([0x68, 0xc3, 0xa9, 0x6c, 0x6c, 0x6f])), "héllo");
+}, "decode() decodes UTF-8");
+
+test(() => {
+	const decoder = new TextDecoder();
+	assert_equals(decoder.decode(new Uint8Array([0xef, 0xbb, 0xbf, 0x61])), "a");
+
+	const ignoring = new TextDecoder("utf-8", { ignoreBOM: true });
+	assert_true(ignoring.ignoreBOM);
+	assert_equals(ignoring.decode(new Uint8Array([0xef, 0xbb, 0xbf, 0x61])), "\uFEFFa");
+}, "decode() strips the byte order mark unless ignoreBOM is set");
+
+test(() => {
+	assert_equals(new TextDecoder().decode(new Uint8Array([0x61, 0xff])), "a\uFFFD");
+}, "decode() replaces invalid sequences");
+
+test(() => {
+	const decoder = new TextDecoder("utf-8", { fatal: true });
+	assert_true(decoder.fatal);
+	assert_throws_js(TypeError, () => decoder.decode(new Uint8Array([0xff])));
+}, "decode() throws on invalid sequences when fatal");
+
+test(() => {
+	const decoder = new TextDecoder();
+	const first = decoder.decode(new Uint8Array([0xe2, 0x82]), { stream: true });
+	const second = decoder.decode(new Uint8Array([0xac]));
+	assert_equals(first + second, "€");
+}, "decode() with stream buffers incomplete sequences");
+
+test(() => {
+	assert_throws_js(RangeError, () => new TextDecoder("invalid-encoding"));
+}, "constructor throws for unknown labels");
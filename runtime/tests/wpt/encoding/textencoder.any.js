@@ -0,0 +1,18 @@
+test(() => {
+	const encoded = new TextEncoder().encode("héllo");
+	assert_true(encoded instanceof Uint8Array);
+	assert_array_equals(encoded, [0x68, 0xc3, 0xa9, 0x6c, 0x6c, 0x6f]);
+}, "encode() encodes strings as UTF-8");
+
+test(() => {
+	assert_equals(new TextEncoder().encode().length, 0);
+	assert_equals(new TextEncoder().encode("").length, 0);
+}, "encode() with no input returns an empty array");
+
+test(() => {
+	const destination = new Uint8Array(4);
+	const result = new TextEncoder().encodeInto("a€b", destination);
+	assert_equals(result.read, 2, "read");
+	assert_equals(result.written, 4, "written");
+	assert_array_equals(destination, [0x61, 0xe2, 0x82, 0xac]);
+}, "encodeInto() stops before characters which do not fit");
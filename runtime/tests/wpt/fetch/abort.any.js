@@ -0,0 +1,19 @@
+promise_test(async () => {
+	const reason = { custom: true };
+	const controller = new AbortController();
+	controller.abort(reason);
+
+	let rejection;
+	await fetch("https://example.com/", { signal: controller.signal }).catch(error => {
+		rejection = error;
+	});
+	assert_equals(rejection, reason);
+}, "fetch() rejects with the reason of an aborted signal");
+
+promise_test(async () => {
+	let rejection;
+	await fetch("https://example.com/", { signal: AbortSignal.abort() }).catch(error => {
+		rejection = error;
+	});
+	assert_equals(rejection.name, "AbortError");
+}, "fetch() rejects with an AbortError by default");
@@ -0,0 +1,75 @@
+const BOUNDARY = "----spiderfire-boundary";
+
+function multipart(parts) {
+	const body = parts.map(part => `--${BOUNDARY}\r\n${part}\r\n`).join("") + `--${BOUNDARY}--\r\n`;
+	return new Response(body, { headers: { "Content-Type": `multipart/form-data; boundary="${BOUNDARY}"` } });
+}
+
+test(() => {
+	const form = new FormData();
+	form.append("a", "1");
+	form.append("b", new Blob(["blob"], { type: "text/plain" }));
+	form.append("a", "2");
+	assert_true(form.has("a"));
+	assert_equals(form.get("a"), "1");
+	assert_array_equals(form.getAll("a"), ["1", "2"]);
+	assert_true(form.get("b") instanceof File);
+	assert_equals(form.get("b").name, "blob");
+	assert_equals(form.get("missing"), null);
+
+	form.set("a", "3");
+	assert_array_equals(form.getAll("a"), ["3"]);
+	assert_array_equals([...form].map(([name]) => name), ["a", "b"]);
+
+	form.delete("a");
+	assert_false(form.has("a"));
+}, "FormData entries can be appended, set and deleted");
+
+promise_test(async () => {
+	const response = multipart([
+		`Content-Disposition: form-data; name="field"\r\n\r\nvalue`,
+		`Content-Disposition: form-data; name="file"; filename="a%22b.txt"\r\nContent-Type: text/plain\r\n\r\nline 1\r\nline 2`,
+		`Content-Disposition: form-data; name="field"\r\n\r\n--${BOUNDARY}-ish`,
+	]);
+	const form = await response.formData();
+	assert_array_equals(form.getAll("field"), ["value", `--${BOUNDARY}-ish`]);
+
+	const file = form.get("file");
+	assert_true(file instanceof File);
+	assert_equals(file.name, 'a"b.txt');
+	assert_equals(file.type, "text/plain");
+	assert_equals(await file.text(), "line 1\r\nline 2");
+	assert_true(response.bodyUsed);
+}, "formData() parses multipart/form-data bodies");
+
+promise_test(async () => {
+	const response = new Response("a=1&b=two+words", {
+		headers: { "Content-Type": "application/x-www-form-urlencoded" },
+	});
+	const form = await response.formData();
+	assert_equals(form.get("a"), "1");
+	assert_equals(form.get("b"), "two words");
+}, "formData() parses application/x-www-form-urlencoded bodies");
+
+promise_test(async () => {
+	const truncated = new Response(`--${BOUNDARY}\r\nContent-Disposition: form-data; name="a"\r\n\r\nvalue`, {
+		headers: { "Content-Type": `multipart/form-data; boundary=${BOUNDARY}` },
+	});
+	await promise_rejects_js(null, TypeError, truncated.formData());
+	await promise_rejects_js(null, TypeError, new Response("text").formData());
+}, "formData() rejects truncated bodies and other content types");
+
+promise_test(async () => {
+	const body = `--${BOUNDARY}\r\nContent-Disposition: form-data; name="file"; filename="a.txt"\r\n\r\ncontents\r\n--${BOUNDARY}--\r\n`;
+	const request = new Request("https://example.com", {
+		method: "POST",
+		body,
+		headers: { "Content-Type": `multipart/form-data; boundary=${BOUNDARY}` },
+	});
+	const form = await request.formData();
+	assert_equals(await form.get("file").text(), "contents");
+	await promise_rejects_js(null, Error, request.formData());
+
+	const urlencoded = new Request("https://example.com", { method: "POST", body: new URLSearchParams("a=1") });
+	assert_equals((await urlencoded.formData()).get("a"), "1");
+}, "Request formData() parses multipart/form-data and application/x-www-form-urlencoded bodies");
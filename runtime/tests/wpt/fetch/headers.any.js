@@ -0,0 +1,37 @@
+test(() => {
+	const headers = new Headers({ "Content-Type": "text/plain" });
+	assert_equals(headers.get("content-type"), "text/plain");
+	assert_true(headers.has("CONTENT-TYPE"));
+	assert_equals(headers.get("missing"), null);
+}, "header names are case-insensitive");
+
+test(() => {
+	const headers = new Headers();
+	headers.append("Accept", "text/html");
+	headers.append("Accept", "application/json");
+	assert_equals(headers.get("accept"), "text/html, application/json");
+
+	headers.set("Accept", "*/*");
+	assert_equals(headers.get("accept"), "*/*");
+
+	headers.delete("Accept");
+	assert_false(headers.has("accept"));
+}, "append(), set() and delete()");
+
+test(() => {
+	const headers = new Headers([
+		["B", "2"],
+		["A", "1"],
+		["b", "3"],
+	]);
+	assert_array_equals([...headers.keys()], ["a", "b"]);
+	assert_array_equals([...headers.values()], ["1", "2, 3"]);
+}, "iteration is sorted and combines values");
+
+test(() => {
+	const headers = new Headers();
+	headers.append("Set-Cookie", "a=1");
+	headers.append("Set-Cookie", "b=2");
+	assert_array_equals(headers.getSetCookie(), ["a=1", "b=2"]);
+	assert_array_equals([...headers.keys()], ["set-cookie", "set-cookie"]);
+}, "Set-Cookie headers are not combined");
@@ -0,0 +1,39 @@
+test(() => {
+	const response = new Response();
+	assert_equals(response.status, 200);
+	assert_true(response.ok);
+	assert_equals(response.statusText, "");
+	assert_false(response.bodyUsed);
+}, "default Response");
+
+test(() => {
+	const response = new Response(null, { status: 404, statusText: "Not Found", headers: { "X-Test": "1" } });
+	assert_equals(response.status, 404);
+	assert_false(response.ok);
+	assert_equals(response.statusText, "Not Found");
+	assert_equals(response.headers.get("x-test"), "1");
+}, "Response with init");
+
+test(() => {
+	assert_throws_js(RangeError, () => new Response(null, { status: 99 }));
+	assert_throws_js(TypeError, () => new Response("body", { status: 204 }));
+}, "Response with an invalid status or a null body status with a body throws");
+
+promise_test(async () => {
+	const response = new Response("body text");
+	assert_equals(await response.text(), "body text");
+	assert_true(response.bodyUsed);
+	await promise_rejects_js(null, TypeError, response.text());
+}, "text() consumes the body once");
+
+promise_test(async () => {
+	const response = new Response(new Uint8Array([1, 2, 3]));
+	const buffer = await response.arrayBuffer();
+	assert_array_equals(new Uint8Array(buffer), [1, 2, 3]);
+}, "arrayBuffer() reads binary bodies");
+
+promise_test(async () => {
+	const response = await fetch("data:text/plain;charset=utf-8,hello%20world");
+	assert_equals(response.headers.get("content-type"), "text/plain;charset=utf-8");
+	assert_equals(await response.text(), "hello world");
+}, "fetch() of a data: URL keeps the parameters of its MIME type");
@@ -0,0 +1,89 @@
+promise_test(async () => {
+	const stream = new ReadableStream({
+		start(controller) {
+			controller.enqueue("a");
+			controller.enqueue("b");
+			controller.close();
+		},
+	});
+	const reader = stream.getReader();
+	assert_true(stream.locked);
+
+	let result = await reader.read();
+	assert_false(result.done);
+	assert_equals(result.value, "a");
+
+	result = await reader.read();
+	assert_equals(result.value, "b");
+
+	result = await reader.read();
+	assert_true(result.done);
+	assert_equals(result.value, undefined);
+}, "reads enqueued chunks in order");
+
+promise_test(async () => {
+	let pulls = 0;
+	const stream = new ReadableStream({
+		pull(controller) {
+			pulls++;
+			controller.enqueue(pulls);
+			if (pulls === 3) {
+				controller.close();
+			}
+		},
+	});
+
+	const chunks = [];
+	const reader = stream.getReader();
+	for (let result = await reader.read(); !result.done; result = await reader.read()) {
+		chunks.push(result.value);
+	}
+	assert_array_equals(chunks, [1, 2, 3]);
+}, "pull() is called as chunks are read");
+
+test(() => {
+	const stream = new ReadableStream();
+	stream.getReader();
+	assert_throws_js(TypeError, () => stream.getReader());
+}, "getReader() throws when the stream is locked");
+
+promise_test(async () => {
+	const stream = new ReadableStream();
+	const reader = stream.getReader();
+	reader.releaseLock();
+	assert_false(stream.locked);
+	stream.getReader();
+}, "releaseLock() unlocks the stream");
+
+promise_test(async () => {
+	const error = new TypeError("source error");
+	const stream = new ReadableStream({
+		start(controller) {
+			controller.error(error);
+		},
+	});
+	await promise_rejects_js(null, TypeError, stream.getReader().read());
+}, "read() rejects when the stream errors");
+
+promise_test(async () => {
+	let reason;
+	const stream = new ReadableStream({
+		cancel(cancelReason) {
+			reason = cancelReason;
+		},
+	});
+	await stream.cancel("done");
+	assert_equals(reason, "done");
+}, "cancel() calls the cancel algorithm of the source");
+
+promise_test(async () => {
+	const stream = new ReadableStream({
+		start(controller) {
+			controller.enqueue("chunk");
+			controller.close();
+		},
+	});
+	const [first, second] = stream.tee();
+	assert_equals((await first.getReader().read()).value, "chunk");
+	assert_equals((await second.getReader().read()).value, "chunk");
+}, "tee() duplicates the stream");
@@ -0,0 +1,63 @@
+promise_test(async () => {
+	const chunks = [];
+	const stream = new WritableStream({
+		write(chunk) {
+			chunks.push(chunk);
+		},
+	});
+	const writer = stream.getWriter();
+	assert_true(stream.locked);
+
+	await writer.write("a");
+	await writer.write("b");
+	await writer.close();
+	assert_array_equals(chunks, ["a", "b"]);
+}, "writes chunks in order");
+
+promise_test(async () => {
+	let closed = false;
+	const stream = new WritableStream({
+		close() {
+			closed = true;
+		},
+	});
+	const writer = stream.getWriter();
+	await writer.close();
+	await writer.closed;
+	assert_true(closed);
+}, "close() calls the close algorithm of the sink");
+
+promise_test(async () => {
+	let reason;
+	const stream = new WritableStream({
+		abort(abortReason) {
+			reason = abortReason;
+		},
+	});
+	await stream.getWriter().abort("stopped");
+	assert_equals(reason, "stopped");
+}, "abort() calls the abort algorithm of the sink");
+
+promise_test(async () => {
+	const stream = new WritableStream({
+		write() {
+			throw new TypeError("sink error");
+		},
+	});
+	const writer = stream.getWriter();
+	await promise_rejects_js(null, TypeError, writer.write("a"));
+	await promise_rejects_js(null, TypeError, writer.closed);
+}, "write() rejects when the sink throws");
+
+test(() => {
+	const stream = new WritableStream();
+	stream.getWriter();
+	assert_throws_js(TypeError, () => stream.getWriter());
+}, "getWriter() throws when the stream is locked");
+
+promise_test(async () => {
+	const stream = new WritableStream({}, { highWaterMark: 2 });
+	const writer = stream.getWriter();
+	assert_equals(writer.desiredSize, 2);
+	await writer.ready;
+}, "desiredSize reflects the high water mark");
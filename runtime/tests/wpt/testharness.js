@@ -0,0 +1,102 @@
+// Minimal subset of the Web Platform Tests harness, which records the result of each test for the runner.
+(function () {
+	const results = [];
+	const pending = new Set();
+	let queue = Promise.resolve();
+
+	class AssertionError extends Error {
+		constructor(message) {
+			super(message);
+			this.name = "AssertionError";
+		}
+	}
+
+	function format(value) {
+		if (typeof value === "string") {
+			return JSON.stringify(value);
+		}
+		if (Array.isArray(value)) {
+			return `[${value.map(format).join(", ")}]`;
+		}
+		return String(value);
+	}
+
+	function assert(condition, description, message) {
+		if (!condition) {
+			throw new AssertionError(description ? `${description}: ${message}` : message);
+		}
+	}
+
+	function record(name, error) {
+		if (error === undefined) {
+			results.push(`PASS ${name}`);
+		} else {
+			results.push(`FAIL ${name}: ${error?.name ?? "Error"}: ${error?.message ?? error}`);
+		}
+	}
+
+	globalThis.assert_true = function (actual, description) {
+		assert(actual === true, description, `expected true, got ${format(actual)}`);
+	};
+
+	globalThis.assert_false = function (actual, description) {
+		assert(actual === false, description, `expected false, got ${format(actual)}`);
+	};
+
+	globalThis.assert_equals = function (actual, expected, description) {
+		assert(Object.is(actual, expected), description, `expected ${format(expected)}, got ${format(actual)}`);
+	};
+
+	globalThis.assert_not_equals = function (actual, expected, description) {
+		assert(!Object.is(actual, expected), description, `got disallowed value ${format(actual)}`);
+	};
+
+	globalThis.assert_array_equals = function (actual, expected, description) {
+		const equal = actual.length === expected.length && Array.prototype.every.call(actual, (value, index) => {
+			return Object.is(value, expected[index]);
+		});
+		assert(equal, description, `expected ${format(Array.from(expected))}, got ${format(Array.from(actual))}`);
+	};
+
+	globalThis.assert_throws_js = function (constructor, func, description) {
+		try {
+			func();
+		} catch (error) {
+			assert(error instanceof constructor, description, `expected ${constructor.name}, got ${error?.name}`);
+			return;
+		}
+		assert(false, description, `expected ${constructor.name} to be thrown`);
+	};
+
+	globalThis.promise_rejects_js = function (_test, constructor, promise, description) {
+		return promise.then(
+			() => assert(false, description, `expected ${constructor.name} to be rejected`),
+			error => assert(error instanceof constructor, description, `expected ${constructor.name}, got ${error?.name}`),
+		);
+	};
+
+	globalThis.test = function (func, name) {
+		try {
+			func({ name });
+			record(name);
+		} catch (error) {
+			record(name, error);
+		}
+	};
+
+	// Asynchronous tests run one after another, in the order they were defined, as in the Web Platform Tests.
+	globalThis.promise_test = function (func, name) {
+		pending.add(name);
+		queue = queue
+			.then(() => func({ name }))
+			.then(() => record(name), error => record(name, error))
+			.finally(() => pending.delete(name));
+	};
+
+	Object.defineProperty(globalThis, "__report", {
+		value() {
+			const incomplete = Array.from(pending, name => `FAIL ${name}: Test did not complete`);
+			return [...results, ...incomplete].join("\n");
+		},
+	});
+})();
@@ -0,0 +1,55 @@
+test(() => {
+	const params = new URLSearchParams("?a=1&b=2&a=3");
+	assert_equals(params.get("a"), "1");
+	assert_array_equals(params.getAll("a"), ["1", "3"]);
+	assert_equals(params.get("missing"), null);
+	assert_equals(params.size, 3);
+}, "parses query strings");
+
+test(() => {
+	const params = new URLSearchParams({ b: "2", a: "1" });
+	assert_equals(params.toString(), "b=2&a=1");
+
+	const pairs = new URLSearchParams([["x", "1"], ["y", "2"]]);
+	assert_equals(pairs.toString(), "x=1&y=2");
+}, "constructs from records and sequences");
+
+test(() => {
+	const params = new URLSearchParams("a=1&b=2&a=3");
+	params.set("a", "4");
+	assert_equals(params.toString(), "a=4&b=2");
+
+	params.append("c", "5");
+	params.delete("b");
+	assert_equals(params.toString(), "a=4&c=5");
+	assert_true(params.has("c"));
+	assert_false(params.has("b"));
+}, "set(), append() and delete() modify the list");
+
+test(() => {
+	const params = new URLSearchParams("a=1&a=2&b=3");
+	assert_true(params.has("a", "2"));
+	assert_false(params.has("a", "3"));
+	params.delete("a", "1");
+	assert_equals(params.toString(), "a=2&b=3");
+}, "has() and delete() with values");
+
+test(() => {
+	const params = new URLSearchParams("c=3&a=1&b=2&a=0");
+	params.sort();
+	assert_equals(params.toString(), "a=1&a=0&b=2&c=3");
+}, "sort() is stable");
+
+test(() => {
+	const params = new URLSearchParams();
+	params.append("key", "a b&c=d");
+	assert_equals(params.toString(), "key=a+b%26c%3Dd");
+	assert_equals(new URLSearchParams("key=a+b%26c%3Dd").get("key"), "a b&c=d");
+}, "values are encoded as application/x-www-form-urlencoded");
+
+test(() => {
+	const entries = [...new URLSearchParams("a=1&b=2")];
+	assert_equals(entries.length, 2);
+	assert_array_equals(entries[0], ["a", "1"]);
+	assert_array_equals(entries[1], ["b", "2"]);
+}, "iterates over entries");
@@ -0,0 +1,79 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::future::ready;
+use std::path::Path;
+
+use ion::conversions::FromValue;
+use ion::flags::PropertyFlags;
+use ion::script::Script;
+use ion::typedarray::Uint8Array;
+use ion::{Context, Object, Result, Value};
+use mozjs::rust::{JSEngine, Runtime};
+use runtime::config::{Config, LogLevel, CONFIG};
+use runtime::globals::streams::writable::{NativeSink, SinkFuture, WritableStream};
+use runtime::RuntimeBuilder;
+use tokio::task::LocalSet;
+
+const FILE_NAME: &str = "writable.js";
+const SCRIPT: &str = include_str!("scripts/writable.js");
+
+thread_local! {
+	static WRITTEN: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+	static CLOSED: Cell<bool> = const { Cell::new(false) };
+}
+
+#[tokio::test]
+async fn native_sink() {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug).script(true)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = Runtime::new(engine.handle());
+
+	let cx = &mut Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<()>::new().microtask_queue().macrotask_queue().build(cx);
+
+	let global = rt.global();
+	global.define_method(rt.cx(), "nativeSink", native_sink_stream, 0, PropertyFlags::all());
+
+	let local = LocalSet::new();
+	local
+		.run_until(async {
+			let result = Script::compile_and_evaluate(rt.cx(), Path::new(FILE_NAME), SCRIPT);
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+
+			let result = rt.run_event_loop().await;
+			assert!(result.is_ok(), "Error: {:?}", result.unwrap_err());
+		})
+		.await;
+
+	WRITTEN.with_borrow(|written| assert_eq!(written, &[1, 2, 3, 4, 5]));
+	assert!(CLOSED.get());
+}
+
+struct Sink;
+
+impl NativeSink for Sink {
+	fn write(&mut self, cx: &Context, chunk: &Value) -> Result<SinkFuture> {
+		let chunk = Uint8Array::from_value(cx, chunk, true, ())?;
+		let bytes = unsafe { chunk.as_slice() }.to_vec();
+		Ok(Box::pin(async move {
+			WRITTEN.with_borrow_mut(|written| written.extend(bytes));
+			Ok(())
+		}))
+	}
+
+	fn close(&mut self, _: &Context) -> SinkFuture {
+		CLOSED.set(true);
+		Box::pin(ready(Ok(())))
+	}
+}
+
+#[ion::js_fn]
+fn native_sink_stream(cx: &Context) -> Object {
+	WritableStream::from_native_sink(cx, Sink)
+}